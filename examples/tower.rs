@@ -1,42 +1,18 @@
-use scylla::client::session::Session;
 use scylla::client::session_builder::SessionBuilder;
+use scylla::client::tower::SessionService;
 use scylla::value::Row;
 use std::env;
-use std::future::Future;
-use std::pin::Pin;
 use std::sync::Arc;
-use std::task::Context;
-use std::task::Poll;
 use tower::Service;
 
-struct SessionService {
-    session: Arc<Session>,
-}
-
-// A trivial service implementation for sending parameterless simple string requests to Scylla.
-impl Service<scylla::statement::unprepared::Statement> for SessionService {
-    type Response = scylla::response::query_result::QueryResult;
-    type Error = scylla::errors::ExecutionError;
-    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
-
-    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-        Poll::Ready(Ok(()))
-    }
-
-    fn call(&mut self, req: scylla::statement::unprepared::Statement) -> Self::Future {
-        let session = self.session.clone();
-        Box::pin(async move { session.query_unpaged(req, &[]).await })
-    }
-}
-
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let uri = env::var("SCYLLA_URI").unwrap_or_else(|_| "127.0.0.1:9042".to_string());
 
     println!("Connecting to {uri} ...");
-    let mut session: SessionService = SessionService {
-        session: Arc::new(SessionBuilder::new().known_node(uri).build().await?),
-    };
+    let mut session = SessionService::new(Arc::new(
+        SessionBuilder::new().known_node(uri).build().await?,
+    ));
 
     let rows_result = session
         .call("SELECT keyspace_name, table_name FROM system_schema.tables;".into())
@@ -6,9 +6,9 @@ use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
 
 use scylla_cql::frame::request::SerializableRequest;
 use scylla_cql::frame::request::query::PagingState;
-use scylla_cql::frame::response::result::{ColumnType, NativeType};
+use scylla_cql::frame::response::result::{ColumnSpec, ColumnType, NativeType, TableSpec};
 use scylla_cql::frame::{Compression, SerializedRequest, request::query};
-use scylla_cql::serialize::row::SerializedValues;
+use scylla_cql::serialize::row::{RowSerializationContext, SerializedValues};
 
 fn make_query(contents: &str, values: SerializedValues) -> query::Query<'_> {
     query::Query {
@@ -80,5 +80,35 @@ fn serialized_request_make_bench(c: &mut Criterion) {
     }
 }
 
-criterion_group!(benches, serialized_request_make_bench);
+fn serialized_values_bench(c: &mut Criterion) {
+    let table = TableSpec::borrowed("ks", "table_name");
+    let columns = [
+        ColumnSpec::borrowed("a", ColumnType::Native(NativeType::Int), table.clone()),
+        ColumnSpec::borrowed("b", ColumnType::Native(NativeType::Text), table.clone()),
+        ColumnSpec::borrowed("c", ColumnType::Native(NativeType::BigInt), table),
+    ];
+    let ctx = RowSerializationContext::from_specs(&columns);
+    let row = (1234_i32, "i am storing a string", 5678_i64);
+
+    let mut group = c.benchmark_group("SerializedValues");
+    group.bench_function("from_serializable", |b| {
+        b.iter(|| {
+            let values = SerializedValues::from_serializable(&ctx, &row).unwrap();
+            std::hint::black_box(values);
+        })
+    });
+    group.bench_function("reset_from_serializable", |b| {
+        let mut values = SerializedValues::with_capacity(columns.len(), 64);
+        b.iter(|| {
+            values.reset_from_serializable(&ctx, &row).unwrap();
+            std::hint::black_box(&values);
+        })
+    });
+}
+
+criterion_group!(
+    benches,
+    serialized_request_make_bench,
+    serialized_values_bench
+);
 criterion_main!(benches);
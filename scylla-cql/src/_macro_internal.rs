@@ -5,14 +5,15 @@ pub use crate::deserialize::row::{
     BuiltinDeserializationError as BuiltinRowDeserializationError,
     BuiltinDeserializationErrorKind as BuiltinRowDeserializationErrorKind,
     BuiltinTypeCheckErrorKind as DeserBuiltinRowTypeCheckErrorKind, ColumnIterator, DeserializeRow,
-    deser_error_replace_rust_name as row_deser_error_replace_rust_name,
+    RawColumn, deser_error_replace_rust_name as row_deser_error_replace_rust_name,
     mk_deser_err as mk_row_deser_err, mk_typck_err as mk_row_typck_err,
 };
 pub use crate::deserialize::value::{
     BuiltinDeserializationError as BuiltinTypeDeserializationError,
     BuiltinDeserializationErrorKind as BuiltinTypeDeserializationErrorKind,
     BuiltinTypeCheckErrorKind as DeserBuiltinTypeTypeCheckErrorKind, DeserializeValue,
-    UdtDeserializationErrorKind, UdtIterator, UdtTypeCheckErrorKind as DeserUdtTypeCheckErrorKind,
+    EnumDeserializationErrorKind, EnumTypeCheckErrorKind, UdtDeserializationErrorKind, UdtIterator,
+    UdtTypeCheckErrorKind as DeserUdtTypeCheckErrorKind,
     deser_error_replace_rust_name as value_deser_error_replace_rust_name,
     mk_deser_err as mk_value_deser_err, mk_typck_err as mk_value_typck_err,
 };
@@ -86,6 +87,51 @@ pub trait PartialSerializeRowByName {
     fn check_missing(self) -> Result<(), SerializationError>;
 }
 
+/// Represents a row that can be deserialized by matching columns to its fields by name
+///
+/// For now this trait is an implementation detail of `#[derive(DeserializeRow)]` when
+/// deserializing by name
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be flattened here",
+    label = "`{Self}` is not a struct that derives `DeserializeRow` with `match_by_name` flavor",
+    note = "There are two common reasons for that:
+- `{Self}` does not use `#[derive(DeserializeRow)]`
+- `{Self}` uses `#[scylla(flavor = \"enforce_order\")]`"
+)]
+pub trait DeserializeRowByName<'frame, 'metadata>: Sized {
+    /// Tracks which columns have already been type checked
+    type TypeCheckPartial: Default;
+
+    /// Keeps the values of columns which have already been deserialized
+    type Partial: Default;
+
+    /// Type checks a single column, if it belongs to this struct (or to its flattened fields).
+    ///
+    /// It returns whether the column was used.
+    fn type_check_column(
+        partial: &mut Self::TypeCheckPartial,
+        column_index: usize,
+        spec: &ColumnSpec,
+        specs: &[ColumnSpec],
+    ) -> Result<bool, TypeCheckError>;
+
+    /// Appends names of the required columns which were not type checked
+    fn append_missing_columns(partial: &Self::TypeCheckPartial, missing: &mut Vec<&'static str>);
+
+    /// Deserializes a single column, if it belongs to this struct (or to its flattened fields).
+    ///
+    /// It returns whether the column was used.
+    fn deserialize_column(
+        partial: &mut Self::Partial,
+        column: &RawColumn<'frame, 'metadata>,
+    ) -> Result<bool, DeserializationError>;
+
+    /// Creates the struct out of the deserialized columns
+    ///
+    /// Type check guarantees that all the required columns were deserialized.
+    fn finalize(partial: Self::Partial) -> Self;
+}
+
 /// Represents a set of values that can be sent along a CQL statement when serializing in order
 ///
 /// For now this trait is an implementation detail of `#[derive(SerializeRow)]` when
@@ -116,6 +162,90 @@ impl<T: SerializeRowInOrder + ?Sized> SerializeRowInOrder for &T {
 }
 
 pub mod ser {
+    use crate::frame::response::result::ColumnType;
+    use crate::serialize::value::SerializeValue;
+    use crate::serialize::writers::WrittenCellProof;
+    use crate::serialize::{CellWriter, SerializationError};
+
+    /// Serializes the referenced value with a custom function instead of its
+    /// `SerializeValue` implementation
+    ///
+    /// For now this struct is an implementation detail of `#[scylla(serialize_with = ...)]`
+    /// field attribute of `#[derive(SerializeRow)]`.
+    pub struct SerializeWith<'v, T: ?Sized, F>(pub &'v T, pub F);
+
+    impl<T: ?Sized, F> SerializeValue for SerializeWith<'_, T, F>
+    where
+        F: for<'b> Fn(
+            &T,
+            &ColumnType,
+            CellWriter<'b>,
+        ) -> Result<WrittenCellProof<'b>, SerializationError>,
+    {
+        #[inline]
+        fn serialize<'b>(
+            &self,
+            typ: &ColumnType,
+            writer: CellWriter<'b>,
+        ) -> Result<WrittenCellProof<'b>, SerializationError> {
+            (self.1)(self.0, typ, writer)
+        }
+    }
+
+    pub mod value {
+        use crate::frame::response::result::{ColumnType, NativeType};
+        use crate::serialize::value::{
+            BuiltinSerializationErrorKind, BuiltinTypeCheckErrorKind, mk_ser_err, mk_typck_err,
+        };
+        use crate::serialize::writers::WrittenCellProof;
+        use crate::serialize::{CellWriter, SerializationError};
+
+        /// Serializes a variant of a fieldless enum `T` as its name
+        ///
+        /// Implementation detail of `#[derive(SerializeValue)]` on enums
+        pub fn serialize_enum_as_text<'b, T: ?Sized>(
+            name: &str,
+            typ: &ColumnType,
+            writer: CellWriter<'b>,
+        ) -> Result<WrittenCellProof<'b>, SerializationError> {
+            match typ {
+                ColumnType::Native(NativeType::Ascii | NativeType::Text) => writer
+                    .set_value(name.as_bytes())
+                    .map_err(|_| mk_ser_err::<T>(typ, BuiltinSerializationErrorKind::SizeOverflow)),
+                _ => Err(mk_typck_err::<T>(
+                    typ,
+                    BuiltinTypeCheckErrorKind::MismatchedType {
+                        expected: &[
+                            ColumnType::Native(NativeType::Ascii),
+                            ColumnType::Native(NativeType::Text),
+                        ],
+                    },
+                )),
+            }
+        }
+
+        /// Serializes a variant of a fieldless enum `T` as its discriminant
+        ///
+        /// Implementation detail of `#[derive(SerializeValue)]` on enums
+        pub fn serialize_enum_as_int<'b, T: ?Sized>(
+            discriminant: i32,
+            typ: &ColumnType,
+            writer: CellWriter<'b>,
+        ) -> Result<WrittenCellProof<'b>, SerializationError> {
+            match typ {
+                ColumnType::Native(NativeType::Int) => Ok(writer
+                    .set_value(discriminant.to_be_bytes().as_slice())
+                    .unwrap()),
+                _ => Err(mk_typck_err::<T>(
+                    typ,
+                    BuiltinTypeCheckErrorKind::MismatchedType {
+                        expected: &[ColumnType::Native(NativeType::Int)],
+                    },
+                )),
+            }
+        }
+    }
+
     pub mod row {
         use super::super::{PartialSerializeRowByName, SerializeRowByName, SerializeRowInOrder};
         use crate::{
@@ -310,3 +440,183 @@ pub mod ser {
         }
     }
 }
+
+pub mod de {
+    pub mod value {
+        use crate::deserialize::value::{
+            BuiltinTypeCheckErrorKind, DeserializeValue, deser_error_replace_rust_name,
+            mk_typck_err,
+        };
+        use crate::deserialize::{DeserializationError, FrameSlice, TypeCheckError};
+        use crate::frame::response::result::{ColumnType, NativeType};
+        use std::cell::RefCell;
+
+        std::thread_local! {
+            /// Variants of newtype enums chosen by their `type_check`: (enum name, CQL type, variant index).
+            static CHOSEN_ENUM_VARIANTS: RefCell<Vec<(&'static str, ColumnType<'static>, usize)>> =
+                const { RefCell::new(Vec::new()) };
+        }
+
+        /// Type checks a fieldless enum `T` represented by names of its variants
+        ///
+        /// Implementation detail of `#[derive(DeserializeValue)]` on enums
+        pub fn type_check_enum_as_text<T>(typ: &ColumnType) -> Result<(), TypeCheckError> {
+            match typ {
+                ColumnType::Native(NativeType::Ascii | NativeType::Text) => Ok(()),
+                _ => Err(mk_typck_err::<T>(
+                    typ,
+                    BuiltinTypeCheckErrorKind::MismatchedType {
+                        expected: &[
+                            ColumnType::Native(NativeType::Ascii),
+                            ColumnType::Native(NativeType::Text),
+                        ],
+                    },
+                )),
+            }
+        }
+
+        /// Deserializes the name of a variant of a fieldless enum `T`
+        ///
+        /// Implementation detail of `#[derive(DeserializeValue)]` on enums
+        pub fn deserialize_enum_as_text<'frame, 'metadata, T>(
+            typ: &'metadata ColumnType<'metadata>,
+            v: Option<FrameSlice<'frame>>,
+        ) -> Result<&'frame str, DeserializationError> {
+            <&'frame str as DeserializeValue<'frame, 'metadata>>::deserialize(typ, v)
+                .map_err(deser_error_replace_rust_name::<T>)
+        }
+
+        /// Type checks a fieldless enum `T` represented by discriminants of its variants
+        ///
+        /// Implementation detail of `#[derive(DeserializeValue)]` on enums
+        pub fn type_check_enum_as_int<T>(typ: &ColumnType) -> Result<(), TypeCheckError> {
+            match typ {
+                ColumnType::Native(NativeType::Int) => Ok(()),
+                _ => Err(mk_typck_err::<T>(
+                    typ,
+                    BuiltinTypeCheckErrorKind::MismatchedType {
+                        expected: &[ColumnType::Native(NativeType::Int)],
+                    },
+                )),
+            }
+        }
+
+        /// Deserializes the discriminant of a variant of a fieldless enum `T`
+        ///
+        /// Implementation detail of `#[derive(DeserializeValue)]` on enums
+        pub fn deserialize_enum_as_int<'frame, 'metadata, T>(
+            typ: &'metadata ColumnType<'metadata>,
+            v: Option<FrameSlice<'frame>>,
+        ) -> Result<i32, DeserializationError> {
+            <i32 as DeserializeValue<'frame, 'metadata>>::deserialize(typ, v)
+                .map_err(deser_error_replace_rust_name::<T>)
+        }
+
+        /// Remembers the variant of a newtype enum `T` chosen by type checking it against `typ`
+        ///
+        /// Implementation detail of `#[derive(DeserializeValue)]` on enums
+        pub fn remember_enum_variant<T: ?Sized>(typ: &ColumnType, variant: usize) {
+            let name = std::any::type_name::<T>();
+            CHOSEN_ENUM_VARIANTS.with_borrow_mut(|chosen| {
+                if !chosen
+                    .iter()
+                    .any(|(chosen_name, chosen_typ, _)| *chosen_name == name && chosen_typ == typ)
+                {
+                    chosen.push((name, typ.clone().into_owned(), variant));
+                }
+            });
+        }
+
+        /// Returns the variant of a newtype enum `T` chosen by type checking it against `typ`.
+        ///
+        /// The choice is remembered per thread, so if the type check was done on another
+        /// thread, the variant is chosen again with `choose`.
+        ///
+        /// Implementation detail of `#[derive(DeserializeValue)]` on enums
+        pub fn chosen_enum_variant<T: ?Sized>(
+            typ: &ColumnType,
+            choose: impl FnOnce() -> Option<usize>,
+        ) -> Option<usize> {
+            let name = std::any::type_name::<T>();
+            let variant = CHOSEN_ENUM_VARIANTS.with_borrow(|chosen| {
+                chosen
+                    .iter()
+                    .find(|(chosen_name, chosen_typ, _)| *chosen_name == name && chosen_typ == typ)
+                    .map(|(_, _, variant)| *variant)
+            });
+            variant.or_else(|| {
+                let variant = choose()?;
+                remember_enum_variant::<T>(typ, variant);
+                Some(variant)
+            })
+        }
+    }
+
+    pub mod row {
+        use super::super::DeserializeRowByName;
+        use crate::deserialize::row::{
+            BuiltinTypeCheckErrorKind, ColumnIterator, deser_error_replace_rust_name, mk_typck_err,
+        };
+        use crate::deserialize::{DeserializationError, TypeCheckError};
+        use crate::frame::response::result::ColumnSpec;
+
+        /// Type checks all the columns of a row by name
+        ///
+        /// Implementation detail of `#[derive(DeserializeRow)]` when deserializing by name
+        pub fn type_check_by_name<'frame, 'metadata, T: DeserializeRowByName<'frame, 'metadata>>(
+            specs: &[ColumnSpec],
+        ) -> Result<(), TypeCheckError> {
+            let column_types_iter = || specs.iter().map(|spec| spec.typ().clone().into_owned());
+
+            let mut partial = T::TypeCheckPartial::default();
+            for (column_index, spec) in specs.iter().enumerate() {
+                if !T::type_check_column(&mut partial, column_index, spec, specs)? {
+                    return Err(mk_typck_err::<T>(
+                        column_types_iter(),
+                        BuiltinTypeCheckErrorKind::ColumnWithUnknownName {
+                            column_index,
+                            column_name: spec.name().to_owned(),
+                        },
+                    ));
+                }
+            }
+
+            let mut missing_columns = Vec::new();
+            T::append_missing_columns(&partial, &mut missing_columns);
+            if !missing_columns.is_empty() {
+                return Err(mk_typck_err::<T>(
+                    column_types_iter(),
+                    BuiltinTypeCheckErrorKind::ValuesMissingForColumns {
+                        column_names: missing_columns,
+                    },
+                ));
+            }
+
+            Ok(())
+        }
+
+        /// Deserializes all the columns of a row by name
+        ///
+        /// Implementation detail of `#[derive(DeserializeRow)]` when deserializing by name
+        pub fn deserialize_by_name<
+            'frame,
+            'metadata,
+            T: DeserializeRowByName<'frame, 'metadata>,
+        >(
+            row: ColumnIterator<'frame, 'metadata>,
+        ) -> Result<T, DeserializationError> {
+            let mut partial = T::Partial::default();
+            for column in row {
+                let column = column.map_err(deser_error_replace_rust_name::<T>)?;
+                if !T::deserialize_column(&mut partial, &column)? {
+                    unreachable!(
+                        "Typecheck should have prevented this scenario! Unknown column name: {}",
+                        column.spec.name()
+                    );
+                }
+            }
+
+            Ok(T::finalize(partial))
+        }
+    }
+}
@@ -567,3 +567,65 @@ fn _test_struct_deserialization_skip_rename_collision_with_field() {}
 /// }
 /// ```
 fn _test_struct_deserialization_rename_collision_with_another_rename() {}
+
+/// ```compile_fail
+///
+/// #[derive(scylla_macros::DeserializeRow)]
+/// #[scylla(crate = scylla_cql, flavor = "enforce_order")]
+/// struct TestRow {
+///     #[scylla(allow_missing)]
+///     a: i32,
+/// }
+/// ```
+fn _test_struct_deserialization_allow_missing_requires_match_by_name() {}
+
+/// ```compile_fail
+///
+/// #[derive(scylla_macros::DeserializeRow)]
+/// #[scylla(crate = scylla_cql)]
+/// struct Inner {
+///     b: i32,
+/// }
+///
+/// #[derive(scylla_macros::DeserializeRow)]
+/// #[scylla(crate = scylla_cql, flavor = "enforce_order")]
+/// struct TestRow {
+///     #[scylla(flatten)]
+///     a: Inner,
+/// }
+/// ```
+fn _test_struct_deserialization_flatten_requires_match_by_name() {}
+
+/// ```compile_fail
+///
+/// #[derive(scylla_macros::DeserializeRow)]
+/// #[scylla(crate = scylla_cql)]
+/// struct Inner {
+///     b: i32,
+/// }
+///
+/// #[derive(scylla_macros::DeserializeRow)]
+/// #[scylla(crate = scylla_cql)]
+/// struct TestRow {
+///     #[scylla(flatten, rename = "c")]
+///     a: Inner,
+/// }
+/// ```
+fn _test_struct_deserialization_flatten_conflicts_with_rename() {}
+
+/// ```compile_fail
+///
+/// #[derive(scylla_macros::DeserializeRow)]
+/// #[scylla(crate = scylla_cql, flavor = "enforce_order")]
+/// struct Inner {
+///     b: i32,
+/// }
+///
+/// #[derive(scylla_macros::DeserializeRow)]
+/// #[scylla(crate = scylla_cql)]
+/// struct TestRow {
+///     #[scylla(flatten)]
+///     a: Inner,
+/// }
+/// ```
+fn _test_struct_deserialization_flatten_requires_match_by_name_inner() {}
@@ -319,6 +319,210 @@ fn test_struct_deserialization_cross_rename_fields() {
     }
 }
 
+#[test]
+fn test_struct_deserialization_allow_missing() {
+    #[derive(DeserializeRow, PartialEq, Eq, Debug)]
+    #[scylla(crate = crate)]
+    struct MyRow<'a> {
+        a: &'a str,
+        #[scylla(allow_missing)]
+        b: Option<i32>,
+        #[scylla(allow_missing, rename = "x")]
+        c: i32,
+    }
+
+    // All columns present
+    let specs = &[
+        spec("x", ColumnType::Native(NativeType::Int)),
+        spec("a", ColumnType::Native(NativeType::Text)),
+        spec("b", ColumnType::Native(NativeType::Int)),
+    ];
+    let byts = serialize_cells([val_int(7), val_str("abc"), val_int(123)]);
+    let row = deserialize::<MyRow<'_>>(specs, &byts).unwrap();
+    assert_eq!(
+        row,
+        MyRow {
+            a: "abc",
+            b: Some(123),
+            c: 7,
+        }
+    );
+
+    // Columns for allow_missing fields absent - fields are defaulted
+    let specs = &[spec("a", ColumnType::Native(NativeType::Text))];
+    let byts = serialize_cells([val_str("abc")]);
+    let row = deserialize::<MyRow<'_>>(specs, &byts).unwrap();
+    assert_eq!(
+        row,
+        MyRow {
+            a: "abc",
+            b: None,
+            c: 0,
+        }
+    );
+
+    // Column for a required field absent
+    let specs = &[spec("b", ColumnType::Native(NativeType::Int))];
+    let err = MyRow::type_check(specs).unwrap_err();
+    let err = get_typck_err_inner(err.0.as_ref());
+    assert_matches!(
+        &err.kind,
+        BuiltinTypeCheckErrorKind::ValuesMissingForColumns { column_names }
+            if column_names == &["a"]
+    );
+}
+
+#[test]
+fn test_struct_deserialization_flatten() {
+    #[derive(DeserializeRow, PartialEq, Eq, Debug)]
+    #[scylla(crate = crate)]
+    struct Audit {
+        #[scylla(rename = "created_by")]
+        author: String,
+        #[scylla(allow_missing)]
+        revision: i32,
+    }
+
+    #[derive(DeserializeRow, PartialEq, Eq, Debug)]
+    #[scylla(crate = crate)]
+    struct Metadata {
+        #[scylla(flatten)]
+        audit: Audit,
+        tag: Option<String>,
+    }
+
+    #[derive(DeserializeRow, PartialEq, Eq, Debug)]
+    #[scylla(crate = crate)]
+    struct MyRow {
+        id: i32,
+        #[scylla(flatten)]
+        metadata: Metadata,
+    }
+
+    // Columns of flattened structs interleaved with the outer struct's ones
+    let specs = &[
+        spec("tag", ColumnType::Native(NativeType::Text)),
+        spec("id", ColumnType::Native(NativeType::Int)),
+        spec("revision", ColumnType::Native(NativeType::Int)),
+        spec("created_by", ColumnType::Native(NativeType::Text)),
+    ];
+    let byts = serialize_cells([val_str("t"), val_int(1), val_int(3), val_str("me")]);
+    let row = deserialize::<MyRow>(specs, &byts).unwrap();
+    assert_eq!(
+        row,
+        MyRow {
+            id: 1,
+            metadata: Metadata {
+                audit: Audit {
+                    author: "me".to_owned(),
+                    revision: 3,
+                },
+                tag: Some("t".to_owned()),
+            },
+        }
+    );
+
+    // allow_missing applies within flattened structs
+    let specs = &[
+        spec("created_by", ColumnType::Native(NativeType::Text)),
+        spec("tag", ColumnType::Native(NativeType::Text)),
+        spec("id", ColumnType::Native(NativeType::Int)),
+    ];
+    let byts = serialize_cells([val_str("me"), None, val_int(1)]);
+    let row = deserialize::<MyRow>(specs, &byts).unwrap();
+    assert_eq!(
+        row,
+        MyRow {
+            id: 1,
+            metadata: Metadata {
+                audit: Audit {
+                    author: "me".to_owned(),
+                    revision: 0,
+                },
+                tag: None,
+            },
+        }
+    );
+
+    // Missing columns are reported for the outer and all flattened structs
+    let specs = &[spec("revision", ColumnType::Native(NativeType::Int))];
+    let err = MyRow::type_check(specs).unwrap_err();
+    let err = get_typck_err_inner(err.0.as_ref());
+    assert_matches!(
+        &err.kind,
+        BuiltinTypeCheckErrorKind::ValuesMissingForColumns { column_names }
+            if column_names == &["id", "created_by", "tag"]
+    );
+
+    // Column unknown to all structs
+    let specs = &[
+        spec("id", ColumnType::Native(NativeType::Int)),
+        spec("created_by", ColumnType::Native(NativeType::Text)),
+        spec("tag", ColumnType::Native(NativeType::Text)),
+        spec("other", ColumnType::Native(NativeType::Text)),
+    ];
+    let err = MyRow::type_check(specs).unwrap_err();
+    let err = get_typck_err_inner(err.0.as_ref());
+    assert_matches!(
+        &err.kind,
+        BuiltinTypeCheckErrorKind::ColumnWithUnknownName {
+            column_index: 3,
+            column_name,
+        } if column_name == "other"
+    );
+
+    // Wrong type of a column of a flattened struct
+    let specs = &[
+        spec("id", ColumnType::Native(NativeType::Int)),
+        spec("created_by", ColumnType::Native(NativeType::Int)),
+        spec("tag", ColumnType::Native(NativeType::Text)),
+    ];
+    MyRow::type_check(specs).unwrap_err();
+}
+
+#[test]
+fn test_struct_serialization_and_deserialization_roundtrip() {
+    #[derive(scylla_macros::SerializeDeserializeRow, PartialEq, Eq, Debug)]
+    #[scylla(crate = crate)]
+    struct Audit {
+        #[scylla(rename = "created_by")]
+        author: String,
+        revision: i32,
+    }
+
+    #[derive(scylla_macros::SerializeDeserializeRow, PartialEq, Eq, Debug)]
+    #[scylla(crate = crate)]
+    struct MyRow {
+        id: i32,
+        #[scylla(rename = "title")]
+        name: Option<String>,
+        #[scylla(skip)]
+        cached: i64,
+        #[scylla(flatten)]
+        audit: Audit,
+    }
+
+    let specs = [
+        spec("revision", ColumnType::Native(NativeType::Int)),
+        spec("title", ColumnType::Native(NativeType::Text)),
+        spec("id", ColumnType::Native(NativeType::Int)),
+        spec("created_by", ColumnType::Native(NativeType::Text)),
+    ];
+    let row = MyRow {
+        id: 1,
+        name: Some("Ala ma kota".to_owned()),
+        cached: 0,
+        audit: Audit {
+            author: "me".to_owned(),
+            revision: 3,
+        },
+    };
+
+    let byts = Bytes::from(crate::serialize::row::tests::do_serialize(&row, &specs));
+    let deserialized = deserialize::<MyRow>(&specs, &byts).unwrap();
+    assert_eq!(row, deserialized);
+}
+
 fn val_int(i: i32) -> Option<Vec<u8>> {
     Some(i.to_be_bytes().to_vec())
 }
@@ -24,7 +24,7 @@ use crate::frame::types;
 use crate::value::CqlVarintBorrowed;
 use crate::value::{
     Counter, CqlDate, CqlDecimal, CqlDecimalBorrowed, CqlDuration, CqlTime, CqlTimestamp,
-    CqlTimeuuid, CqlValue, CqlVarint, deser_cql_value,
+    CqlTimeuuid, CqlUdt, CqlValue, CqlVarint, deser_cql_value,
 };
 
 // Re-export for backwards compatibility. These types were moved to crate::value module.
@@ -80,6 +80,36 @@ impl<'frame, 'metadata> DeserializeValue<'frame, 'metadata> for CqlValue {
     }
 }
 
+impl<'frame, 'metadata> DeserializeValue<'frame, 'metadata> for CqlUdt {
+    fn type_check(typ: &ColumnType) -> Result<(), TypeCheckError> {
+        // Any UDT is accepted, the fields are deserialized as `CqlValue`s
+        UdtIterator::type_check(typ).map_err(typck_error_replace_rust_name::<Self>)
+    }
+
+    fn deserialize(
+        typ: &'metadata ColumnType<'metadata>,
+        v: Option<FrameSlice<'frame>>,
+    ) -> Result<Self, DeserializationError> {
+        let iter =
+            UdtIterator::deserialize(typ, v).map_err(deser_error_replace_rust_name::<Self>)?;
+        let (keyspace, name) = (iter.keyspace.to_owned(), iter.type_name.to_owned());
+        let fields = iter
+            .map(|((field_name, field_type), res)| {
+                res.and_then(|v| {
+                    let value = Option::<CqlValue>::deserialize(field_type, v.flatten())?;
+                    Ok((field_name.clone().into_owned(), value))
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(CqlUdt {
+            keyspace,
+            name,
+            fields,
+        })
+    }
+}
+
 // Option represents nullability of CQL values:
 // None corresponds to null,
 // Some(val) to non-null values.
@@ -835,14 +865,20 @@ where
             return Ok(Self::empty(typ, elem_typ));
         };
 
-        let count = types::read_int_length(v.as_slice_mut()).map_err(|err| {
-            mk_deser_err::<Self>(
-                typ,
-                SetOrListDeserializationErrorKind::LengthDeserializationFailed(
-                    DeserializationError::new(err),
-                ),
-            )
-        })?;
+        let count = types::read_int_length(v.as_slice_mut())
+            .and_then(|count| {
+                // Each element is prefixed with its 4-byte length.
+                types::check_sequence_length(v.as_slice(), count, 4)?;
+                Ok(count)
+            })
+            .map_err(|err| {
+                mk_deser_err::<Self>(
+                    typ,
+                    SetOrListDeserializationErrorKind::LengthDeserializationFailed(
+                        DeserializationError::new(err),
+                    ),
+                )
+            })?;
 
         Ok(Self::new(typ, elem_typ, count, v))
     }
@@ -1246,14 +1282,20 @@ where
             return Ok(Self::empty(typ, k_typ, v_typ));
         };
 
-        let count = types::read_int_length(v.as_slice_mut()).map_err(|err| {
-            mk_deser_err::<Self>(
-                typ,
-                MapDeserializationErrorKind::LengthDeserializationFailed(
-                    DeserializationError::new(err),
-                ),
-            )
-        })?;
+        let count = types::read_int_length(v.as_slice_mut())
+            .and_then(|count| {
+                // Each entry consists of a key and a value, both prefixed with their 4-byte length.
+                types::check_sequence_length(v.as_slice(), count, 2 * 4)?;
+                Ok(count)
+            })
+            .map_err(|err| {
+                mk_deser_err::<Self>(
+                    typ,
+                    MapDeserializationErrorKind::LengthDeserializationFailed(
+                        DeserializationError::new(err),
+                    ),
+                )
+            })?;
 
         Ok(Self::new(typ, k_typ, v_typ, 2 * count, v))
     }
@@ -1859,6 +1901,9 @@ pub enum BuiltinTypeCheckErrorKind {
 
     /// A type check detected type not deserializable to a vector.
     NotDeserializableToVec,
+
+    /// A type check failure specific to a Rust enum.
+    EnumError(EnumTypeCheckErrorKind),
 }
 
 impl From<SetOrListTypeCheckErrorKind> for BuiltinTypeCheckErrorKind {
@@ -1896,6 +1941,13 @@ impl From<UdtTypeCheckErrorKind> for BuiltinTypeCheckErrorKind {
     }
 }
 
+impl From<EnumTypeCheckErrorKind> for BuiltinTypeCheckErrorKind {
+    #[inline]
+    fn from(value: EnumTypeCheckErrorKind) -> Self {
+        BuiltinTypeCheckErrorKind::EnumError(value)
+    }
+}
+
 impl Display for BuiltinTypeCheckErrorKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -1910,6 +1962,7 @@ impl Display for BuiltinTypeCheckErrorKind {
             BuiltinTypeCheckErrorKind::NotDeserializableToVec => {
                 f.write_str("the CQL type is not deserializable to a vector")
             }
+            BuiltinTypeCheckErrorKind::EnumError(err) => err.fmt(f),
         }
     }
 }
@@ -2131,6 +2184,31 @@ impl Display for UdtTypeCheckErrorKind {
     }
 }
 
+/// Describes why type checking of a Rust enum failed.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum EnumTypeCheckErrorKind {
+    /// None of the variants of the enum can be deserialized from the CQL type.
+    NoMatchingVariant {
+        /// Names of the variants, along with the reasons why their types failed to type check.
+        variant_errors: Vec<(&'static str, TypeCheckError)>,
+    },
+}
+
+impl Display for EnumTypeCheckErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EnumTypeCheckErrorKind::NoMatchingVariant { variant_errors } => {
+                f.write_str("none of the enum variants can be deserialized from the CQL type")?;
+                for (variant_name, err) in variant_errors {
+                    write!(f, "; variant {variant_name}: {err}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
 /// Deserialization of one of the built-in types failed.
 #[derive(Debug, Error, Clone)]
 #[error("Failed to deserialize Rust type {rust_name} from CQL type {cql_type:?}: {kind}")]
@@ -2216,6 +2294,9 @@ pub enum BuiltinDeserializationErrorKind {
     /// A deserialization failure specific to a CQL UDT.
     UdtError(UdtDeserializationErrorKind),
 
+    /// A deserialization failure specific to a Rust enum.
+    EnumError(EnumDeserializationErrorKind),
+
     /// Deserialization of this CQL type is not supported by the driver.
     Unsupported,
 }
@@ -2257,6 +2338,7 @@ impl Display for BuiltinDeserializationErrorKind {
             BuiltinDeserializationErrorKind::MapError(err) => err.fmt(f),
             BuiltinDeserializationErrorKind::TupleError(err) => err.fmt(f),
             BuiltinDeserializationErrorKind::UdtError(err) => err.fmt(f),
+            BuiltinDeserializationErrorKind::EnumError(err) => err.fmt(f),
             BuiltinDeserializationErrorKind::Unsupported => {
                 f.write_str("deserialization of this CQL type is not supported by the driver")
             }
@@ -2413,6 +2495,53 @@ impl From<UdtDeserializationErrorKind> for BuiltinDeserializationErrorKind {
     }
 }
 
+/// Describes why deserialization of a Rust enum failed.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum EnumDeserializationErrorKind {
+    /// The text value does not correspond to any of the enum variants.
+    UnknownVariantName {
+        /// The text value received from the DB.
+        name: String,
+    },
+
+    /// The integer value does not correspond to any of the enum variants.
+    UnknownDiscriminant {
+        /// The integer value received from the DB.
+        discriminant: i32,
+    },
+
+    /// None of the variants of the enum can be deserialized from the CQL type.
+    ///
+    /// This can only happen if the value is deserialized without being type checked first.
+    NoMatchingVariant,
+}
+
+impl Display for EnumDeserializationErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EnumDeserializationErrorKind::UnknownVariantName { name } => {
+                write!(f, "{name:?} does not name any of the enum variants")
+            }
+            EnumDeserializationErrorKind::UnknownDiscriminant { discriminant } => {
+                write!(
+                    f,
+                    "{discriminant} is not a discriminant of any of the enum variants"
+                )
+            }
+            EnumDeserializationErrorKind::NoMatchingVariant => {
+                f.write_str("none of the enum variants can be deserialized from the CQL type")
+            }
+        }
+    }
+}
+
+impl From<EnumDeserializationErrorKind> for BuiltinDeserializationErrorKind {
+    fn from(err: EnumDeserializationErrorKind) -> Self {
+        Self::EnumError(err)
+    }
+}
+
 #[cfg(test)]
 #[path = "value_tests.rs"]
 pub(crate) mod tests;
@@ -2485,3 +2614,68 @@ fn _test_udt_bad_attributes_name_skip_name_checks_limitations_on_allow_missing()
 /// }
 /// ```
 fn _test_udt_unordered_flavour_no_limitations_on_allow_missing() {}
+
+/// ```compile_fail
+///
+/// #[derive(scylla_macros::DeserializeValue)]
+/// #[scylla(crate = scylla_cql)]
+/// enum TestEnum {
+///     A,
+///     B(i32),
+/// }
+/// ```
+fn _test_enum_bad_mixed_variants() {}
+
+/// ```compile_fail
+///
+/// #[derive(scylla_macros::DeserializeValue)]
+/// #[scylla(crate = scylla_cql)]
+/// enum TestEnum {
+///     A { a: i32 },
+/// }
+/// ```
+fn _test_enum_bad_struct_variant() {}
+
+/// ```compile_fail
+///
+/// #[derive(scylla_macros::DeserializeValue)]
+/// #[scylla(crate = scylla_cql, repr = "text")]
+/// enum TestEnum {
+///     A(i32),
+///     B(String),
+/// }
+/// ```
+fn _test_enum_bad_attributes_repr_on_newtype_variants() {}
+
+/// ```compile_fail
+///
+/// #[derive(scylla_macros::DeserializeValue)]
+/// #[scylla(crate = scylla_cql, repr = "int")]
+/// enum TestEnum {
+///     #[scylla(rename = "a")]
+///     A,
+/// }
+/// ```
+fn _test_enum_bad_attributes_rename_with_int_repr() {}
+
+/// ```compile_fail
+///
+/// #[derive(scylla_macros::DeserializeValue)]
+/// #[scylla(crate = scylla_cql)]
+/// enum TestEnum {
+///     #[scylla(rename = "B")]
+///     A,
+///     B,
+/// }
+/// ```
+fn _test_enum_bad_attributes_rename_collision() {}
+
+/// ```
+/// #[derive(scylla_macros::DeserializeValue)]
+/// #[scylla(crate = scylla_cql, repr = "int")]
+/// enum TestEnum {
+///     A = 1,
+///     B = 5,
+/// }
+/// ```
+fn _test_enum_good_attributes_int_repr_with_explicit_discriminants() {}
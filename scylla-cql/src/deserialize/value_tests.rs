@@ -12,7 +12,7 @@ use std::sync::Arc;
 
 use crate::deserialize::value::{TupleDeserializationErrorKind, TupleTypeCheckErrorKind};
 use crate::deserialize::{DeserializationError, FrameSlice, TypeCheckError};
-use crate::frame::frame_errors::CustomTypeParseError;
+use crate::frame::frame_errors::{CustomTypeParseError, LowLevelDeserializationError};
 use crate::frame::response::custom_type_parser::CustomTypeParser;
 use crate::frame::response::result::NativeType::*;
 use crate::frame::response::result::{CollectionType, ColumnType, NativeType, UserDefinedType};
@@ -21,13 +21,14 @@ use crate::serialize::value::SerializeValue;
 use crate::utils::parse::ParseErrorCause;
 use crate::value::{
     Counter, CqlDate, CqlDecimal, CqlDecimalBorrowed, CqlDuration, CqlTime, CqlTimestamp,
-    CqlTimeuuid, CqlValue, CqlVarint, CqlVarintBorrowed,
+    CqlTimeuuid, CqlUdt, CqlValue, CqlVarint, CqlVarintBorrowed,
 };
 
 use super::{
     BuiltinDeserializationError, BuiltinDeserializationErrorKind, BuiltinTypeCheckError,
-    BuiltinTypeCheckErrorKind, DeserializeValue, ListlikeIterator, MapDeserializationErrorKind,
-    MapIterator, MapTypeCheckErrorKind, MaybeEmpty, SetOrListDeserializationErrorKind,
+    BuiltinTypeCheckErrorKind, DeserializeValue, EnumDeserializationErrorKind,
+    EnumTypeCheckErrorKind, ListlikeIterator, MapDeserializationErrorKind, MapIterator,
+    MapTypeCheckErrorKind, MaybeEmpty, SetOrListDeserializationErrorKind,
     SetOrListTypeCheckErrorKind, UdtDeserializationErrorKind, UdtTypeCheckErrorKind, mk_deser_err,
 };
 
@@ -1413,6 +1414,336 @@ fn test_udt_loose_ordering() {
     }
 }
 
+#[test]
+fn test_udt_struct_level_allow_missing() {
+    #[derive(scylla_macros::DeserializeValue, PartialEq, Eq, Debug)]
+    #[scylla(crate = "crate", allow_missing)]
+    struct Udt<'a> {
+        a: &'a str,
+        b: Option<i32>,
+        c: i64,
+    }
+
+    #[derive(scylla_macros::DeserializeValue, PartialEq, Eq, Debug)]
+    #[scylla(crate = "crate", flavor = "enforce_order", allow_missing)]
+    struct UdtOrdered<'a> {
+        a: &'a str,
+        b: Option<i32>,
+        c: i64,
+    }
+
+    // The database type doesn't have the field 'b' yet.
+    {
+        let udt_bytes = UdtSerializer::new()
+            .field("The quick brown fox".as_bytes())
+            .field(&2137_i64.to_be_bytes())
+            .finalize();
+        let typ = udt_def_with_fields([
+            ("a", ColumnType::Native(NativeType::Text)),
+            ("c", ColumnType::Native(NativeType::BigInt)),
+        ]);
+
+        let udt = deserialize::<Udt<'_>>(&typ, &udt_bytes).unwrap();
+        assert_eq!(
+            udt,
+            Udt {
+                a: "The quick brown fox",
+                b: None,
+                c: 2137,
+            }
+        );
+
+        let udt = deserialize::<UdtOrdered<'_>>(&typ, &udt_bytes).unwrap();
+        assert_eq!(
+            udt,
+            UdtOrdered {
+                a: "The quick brown fox",
+                b: None,
+                c: 2137,
+            }
+        );
+    }
+
+    // The database type has none of the fields.
+    {
+        let udt_bytes = UdtSerializer::new().field(&12_i8.to_be_bytes()).finalize();
+        let typ = udt_def_with_fields([("d", ColumnType::Native(NativeType::TinyInt))]);
+
+        let udt = deserialize::<Udt<'_>>(&typ, &udt_bytes).unwrap();
+        assert_eq!(
+            udt,
+            Udt {
+                a: "",
+                b: None,
+                c: 0,
+            }
+        );
+    }
+
+    // Types are still checked.
+    {
+        let typ = udt_def_with_fields([("a", ColumnType::Native(NativeType::Int))]);
+        Udt::type_check(&typ).unwrap_err();
+    }
+}
+
+#[test]
+fn test_enum_as_text() {
+    #[derive(scylla_macros::DeserializeValue, PartialEq, Eq, Debug)]
+    #[scylla(crate = "crate")]
+    enum Status {
+        Active,
+        #[scylla(rename = "on_hold")]
+        OnHold,
+    }
+
+    for typ in [ColumnType::Native(Text), ColumnType::Native(Ascii)] {
+        let bytes = make_bytes(b"Active");
+        assert_eq!(deserialize::<Status>(&typ, &bytes).unwrap(), Status::Active);
+
+        let bytes = make_bytes(b"on_hold");
+        assert_eq!(deserialize::<Status>(&typ, &bytes).unwrap(), Status::OnHold);
+    }
+
+    // The Rust name of a renamed variant is not accepted.
+    {
+        let bytes = make_bytes(b"OnHold");
+        let err = deserialize::<Status>(&ColumnType::Native(Text), &bytes).unwrap_err();
+        let err = get_deser_err(&err);
+        assert_eq!(err.rust_name, std::any::type_name::<Status>());
+        assert_matches!(
+            err.kind,
+            BuiltinDeserializationErrorKind::EnumError(
+                EnumDeserializationErrorKind::UnknownVariantName { ref name }
+            ) if name == "OnHold"
+        );
+    }
+
+    // Wrong CQL type
+    {
+        let err = Status::type_check(&ColumnType::Native(Int)).unwrap_err();
+        let err = get_typeck_err_inner(err.0.as_ref());
+        assert_eq!(err.rust_name, std::any::type_name::<Status>());
+        assert_matches!(
+            err.kind,
+            BuiltinTypeCheckErrorKind::MismatchedType {
+                expected: &[ColumnType::Native(Ascii), ColumnType::Native(Text)],
+            }
+        );
+    }
+}
+
+#[test]
+fn test_enum_as_int() {
+    #[derive(scylla_macros::DeserializeValue, PartialEq, Eq, Debug)]
+    #[scylla(crate = "crate", repr = "int")]
+    enum Priority {
+        Low = 1,
+        High = 10,
+    }
+
+    let typ = ColumnType::Native(Int);
+    let bytes = make_bytes(&10i32.to_be_bytes());
+    assert_eq!(
+        deserialize::<Priority>(&typ, &bytes).unwrap(),
+        Priority::High
+    );
+
+    // Unknown discriminant
+    {
+        let bytes = make_bytes(&2i32.to_be_bytes());
+        let err = deserialize::<Priority>(&typ, &bytes).unwrap_err();
+        let err = get_deser_err(&err);
+        assert_eq!(err.rust_name, std::any::type_name::<Priority>());
+        assert_matches!(
+            err.kind,
+            BuiltinDeserializationErrorKind::EnumError(
+                EnumDeserializationErrorKind::UnknownDiscriminant { discriminant: 2 }
+            )
+        );
+    }
+
+    // Wrong CQL type
+    {
+        let err = Priority::type_check(&ColumnType::Native(BigInt)).unwrap_err();
+        let err = get_typeck_err_inner(err.0.as_ref());
+        assert_eq!(err.rust_name, std::any::type_name::<Priority>());
+        assert_matches!(
+            err.kind,
+            BuiltinTypeCheckErrorKind::MismatchedType {
+                expected: &[ColumnType::Native(Int)],
+            }
+        );
+    }
+}
+
+#[test]
+fn test_enum_with_newtype_variants() {
+    #[derive(scylla_macros::DeserializeValue, PartialEq, Eq, Debug)]
+    #[scylla(crate = "crate")]
+    enum IntOrText<'a> {
+        Int(i32),
+        Text(&'a str),
+        Ascii(String),
+    }
+
+    let bytes = make_bytes(&42i32.to_be_bytes());
+    assert_eq!(
+        deserialize::<IntOrText<'_>>(&ColumnType::Native(Int), &bytes).unwrap(),
+        IntOrText::Int(42)
+    );
+
+    // The first matching variant is chosen.
+    let bytes = make_bytes(b"foo");
+    assert_eq!(
+        deserialize::<IntOrText<'_>>(&ColumnType::Native(Ascii), &bytes).unwrap(),
+        IntOrText::Text("foo")
+    );
+
+    // No variant matches the CQL type.
+    {
+        let err = IntOrText::type_check(&ColumnType::Native(Boolean)).unwrap_err();
+        let err = get_typeck_err_inner(err.0.as_ref());
+        assert_eq!(err.rust_name, std::any::type_name::<IntOrText<'_>>());
+        let BuiltinTypeCheckErrorKind::EnumError(EnumTypeCheckErrorKind::NoMatchingVariant {
+            variant_errors,
+        }) = &err.kind
+        else {
+            panic!("unexpected error kind: {:?}", err.kind)
+        };
+        let variant_names = variant_errors
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>();
+        assert_eq!(variant_names, ["Int", "Text", "Ascii"]);
+        let (_, err) = &variant_errors[0];
+        let err = get_typeck_err_inner(err.0.as_ref());
+        assert_eq!(err.rust_name, std::any::type_name::<i32>());
+    }
+
+    // Deserializing without type checking first reports an error instead of panicking.
+    {
+        let typ = ColumnType::Native(Boolean);
+        let bytes = make_bytes(&[1]);
+        let value = FrameSlice::new(&bytes).read_cql_bytes().unwrap();
+        let err = IntOrText::deserialize(&typ, value).unwrap_err();
+        let err = get_deser_err(&err);
+        assert_eq!(err.rust_name, std::any::type_name::<IntOrText<'_>>());
+        assert_matches!(
+            err.kind,
+            BuiltinDeserializationErrorKind::EnumError(
+                EnumDeserializationErrorKind::NoMatchingVariant
+            )
+        );
+    }
+}
+
+#[test]
+fn test_enum_with_newtype_variants_chooses_variant_once() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static TYPE_CHECKS: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(PartialEq, Eq, Debug)]
+    struct CountedText(String);
+
+    impl<'frame, 'metadata> DeserializeValue<'frame, 'metadata> for CountedText {
+        fn type_check(typ: &ColumnType) -> Result<(), TypeCheckError> {
+            TYPE_CHECKS.fetch_add(1, Ordering::Relaxed);
+            String::type_check(typ)
+        }
+
+        fn deserialize(
+            typ: &'metadata ColumnType<'metadata>,
+            v: Option<FrameSlice<'frame>>,
+        ) -> Result<Self, DeserializationError> {
+            String::deserialize(typ, v).map(Self)
+        }
+    }
+
+    #[derive(scylla_macros::DeserializeValue, PartialEq, Eq, Debug)]
+    #[scylla(crate = "crate")]
+    enum IntOrText {
+        Int(i32),
+        Text(CountedText),
+    }
+
+    let typ = ColumnType::Native(Text);
+    let bytes = make_bytes(b"foo");
+    let deserialize_text = || {
+        let value = FrameSlice::new(&bytes).read_cql_bytes().unwrap();
+        assert_eq!(
+            IntOrText::deserialize(&typ, value).unwrap(),
+            IntOrText::Text(CountedText("foo".to_owned()))
+        );
+    };
+
+    IntOrText::type_check(&typ).unwrap();
+    assert_eq!(TYPE_CHECKS.load(Ordering::Relaxed), 1);
+
+    // The variant chosen by the type check is reused.
+    for _ in 0..3 {
+        deserialize_text();
+    }
+    assert_eq!(TYPE_CHECKS.load(Ordering::Relaxed), 1);
+
+    // On another thread, the variant is chosen once more.
+    std::thread::scope(|s| {
+        s.spawn(|| {
+            for _ in 0..3 {
+                deserialize_text();
+            }
+        });
+    });
+    assert_eq!(TYPE_CHECKS.load(Ordering::Relaxed), 2);
+}
+
+#[test]
+fn test_enum_with_udt_variants() {
+    #[derive(scylla_macros::DeserializeValue, PartialEq, Debug)]
+    #[scylla(crate = "crate")]
+    struct Circle {
+        radius: f64,
+    }
+
+    #[derive(scylla_macros::DeserializeValue, PartialEq, Debug)]
+    #[scylla(crate = "crate")]
+    struct Rectangle {
+        width: f64,
+        height: f64,
+    }
+
+    #[derive(scylla_macros::DeserializeValue, PartialEq, Debug)]
+    #[scylla(crate = "crate")]
+    enum Shape {
+        Circle(Circle),
+        Rectangle(Rectangle),
+    }
+
+    let typ = udt_def_with_fields([
+        ("width", ColumnType::Native(Double)),
+        ("height", ColumnType::Native(Double)),
+    ]);
+    let udt_bytes = UdtSerializer::new()
+        .field(&2.0f64.to_be_bytes())
+        .field(&3.0f64.to_be_bytes())
+        .finalize();
+    assert_eq!(
+        deserialize::<Shape>(&typ, &udt_bytes).unwrap(),
+        Shape::Rectangle(Rectangle {
+            width: 2.0,
+            height: 3.0,
+        })
+    );
+
+    let typ = udt_def_with_fields([("radius", ColumnType::Native(Double))]);
+    let udt_bytes = UdtSerializer::new().field(&1.5f64.to_be_bytes()).finalize();
+    assert_eq!(
+        deserialize::<Shape>(&typ, &udt_bytes).unwrap(),
+        Shape::Circle(Circle { radius: 1.5 })
+    );
+}
+
 #[test]
 fn test_udt_strict_ordering() {
     #[derive(scylla_macros::DeserializeValue, PartialEq, Eq, Debug)]
@@ -1956,7 +2287,6 @@ fn test_maybe_empty_errors() {
 fn test_secrecy_08_errors() {
     use secrecy_08::Secret;
 
-    use crate::frame::frame_errors::LowLevelDeserializationError;
     // Type check correctly renames Rust type
     assert_type_check_error!(
         &Bytes::new(),
@@ -1980,8 +2310,8 @@ fn test_secrecy_08_errors() {
             frozen: false,
             typ: CollectionType::List(Box::new(ColumnType::Native(NativeType::Text)))
         },
-        BuiltinDeserializationErrorKind::RawCqlBytesReadError(
-            LowLevelDeserializationError::IoError(_)
+        BuiltinDeserializationErrorKind::SetOrListError(
+            SetOrListDeserializationErrorKind::LengthDeserializationFailed(_)
         )
     );
 }
@@ -1991,7 +2321,6 @@ fn test_secrecy_08_errors() {
 fn test_secrecy_10_errors() {
     use secrecy_10::SecretBox;
 
-    use crate::frame::frame_errors::LowLevelDeserializationError;
     // Type check correctly renames Rust type
     assert_type_check_error!(
         &Bytes::new(),
@@ -2015,8 +2344,8 @@ fn test_secrecy_10_errors() {
             frozen: false,
             typ: CollectionType::List(Box::new(ColumnType::Native(NativeType::Text)))
         },
-        BuiltinDeserializationErrorKind::RawCqlBytesReadError(
-            LowLevelDeserializationError::IoError(_)
+        BuiltinDeserializationErrorKind::SetOrListError(
+            SetOrListDeserializationErrorKind::LengthDeserializationFailed(_)
         )
     );
 }
@@ -2402,6 +2731,57 @@ fn test_map_errors() {
     }
 }
 
+#[test]
+fn test_collection_count_exceeding_buffer() {
+    // The element count claims far more elements than the remaining bytes can hold.
+    let mut collection_contents = BytesMut::new();
+    collection_contents.put_i32(i32::MAX);
+    collection_contents.put_i32(4);
+    collection_contents.put_i32(42);
+    let collection = make_bytes(&collection_contents);
+
+    fn assert_exceeds_buffer(err: &DeserializationError) {
+        assert_matches!(
+            err.downcast_ref::<LowLevelDeserializationError>(),
+            Some(LowLevelDeserializationError::SequenceLengthExceedsBuffer {
+                length,
+                remaining: 8,
+            }) if *length == i32::MAX as usize
+        );
+    }
+
+    let list_typ = ColumnType::Collection {
+        frozen: false,
+        typ: CollectionType::List(Box::new(ColumnType::Native(NativeType::Int))),
+    };
+    let err = deserialize::<ListlikeIterator<i32>>(&list_typ, &collection).unwrap_err();
+    let err = get_deser_err(&err);
+    let BuiltinDeserializationErrorKind::SetOrListError(
+        SetOrListDeserializationErrorKind::LengthDeserializationFailed(err),
+    ) = &err.kind
+    else {
+        panic!("unexpected error kind: {}", err.kind)
+    };
+    assert_exceeds_buffer(err);
+
+    let map_typ = ColumnType::Collection {
+        frozen: false,
+        typ: CollectionType::Map(
+            Box::new(ColumnType::Native(NativeType::Int)),
+            Box::new(ColumnType::Native(NativeType::Int)),
+        ),
+    };
+    let err = deserialize::<MapIterator<i32, i32>>(&map_typ, &collection).unwrap_err();
+    let err = get_deser_err(&err);
+    let BuiltinDeserializationErrorKind::MapError(
+        MapDeserializationErrorKind::LengthDeserializationFailed(err),
+    ) = &err.kind
+    else {
+        panic!("unexpected error kind: {}", err.kind)
+    };
+    assert_exceeds_buffer(err);
+}
+
 #[test]
 fn test_tuple_errors() {
     // Not a tuple
@@ -3451,6 +3831,45 @@ fn test_udt_from_cql() {
     assert_eq!(Some(CqlValue::Boolean(true)), decoded[1].1);
 }
 
+#[test]
+fn test_cql_udt() {
+    let typ = udt_def_with_fields([
+        ("fst", ColumnType::Native(Int)),
+        ("snd", ColumnType::Native(Text)),
+        ("trd", ColumnType::Native(Boolean)),
+    ]);
+
+    // The last field is missing from the serialized form.
+    let udt_bytes = UdtSerializer::new()
+        .field(&10_i32.to_be_bytes())
+        .null_field()
+        .finalize();
+    let udt = deserialize::<CqlUdt>(&typ, &udt_bytes).unwrap();
+    assert_eq!(
+        udt,
+        CqlUdt::new("ks", "udt")
+            .with_field("fst", Some(CqlValue::Int(10)))
+            .with_field("snd", None)
+            .with_field("trd", None)
+    );
+    assert_eq!(udt.field("fst"), Some(&CqlValue::Int(10)));
+    assert_eq!(udt.field("snd"), None);
+    assert_eq!(udt.field("nonexistent"), None);
+
+    // Deserializing as CqlValue gives the same result.
+    let cql = deserialize::<CqlValue>(&typ, &udt_bytes).unwrap();
+    assert_eq!(CqlUdt::try_from(cql), Ok(udt));
+
+    // Not a UDT
+    let err = CqlUdt::type_check(&ColumnType::Native(Int)).unwrap_err();
+    let err = get_typeck_err_inner(err.0.as_ref());
+    assert_eq!(err.rust_name, std::any::type_name::<CqlUdt>());
+    assert_matches!(
+        err.kind,
+        BuiltinTypeCheckErrorKind::UdtError(UdtTypeCheckErrorKind::NotUdt)
+    );
+}
+
 #[test]
 fn test_deserialize_date() {
     // Date is correctly parsed from a 4 byte array
@@ -55,6 +55,15 @@ pub enum FrameBodyExtensionsParseError {
     /// Failed to decompress frame body (lz4).
     #[error("Error decompressing lz4 data {0}")]
     Lz4DecompressError(Arc<dyn Error + Sync + Send>),
+
+    /// Decompressed frame body would exceed the configured limit.
+    #[error("Decompressed frame body length {length} exceeds the limit of {max_length} bytes")]
+    DecompressedBodyTooLong {
+        /// Declared length of the decompressed body.
+        length: usize,
+        /// The configured limit.
+        max_length: usize,
+    },
 }
 
 /// An error that occurred during frame header deserialization.
@@ -91,6 +100,15 @@ pub enum FrameHeaderParseError {
     /// Connection was closed before whole frame was read.
     #[error("Connection was closed before body was read: missing {0} out of {1}")]
     ConnectionClosed(usize, usize),
+
+    /// Received a frame whose body exceeds the configured limit.
+    #[error("Frame body length {length} exceeds the limit of {max_length} bytes")]
+    BodyTooLong {
+        /// Body length declared in the frame header.
+        length: usize,
+        /// The configured limit.
+        max_length: usize,
+    },
 }
 
 /// An error that occurred during CQL request serialization.
@@ -128,6 +146,10 @@ pub enum CqlRequestSerializationError {
     /// Request body compression failed.
     #[error("Snap compression error: {0}")]
     SnapCompressError(Arc<dyn Error + Sync + Send>),
+
+    /// Failed to serialize the custom payload of a request.
+    #[error("Failed to serialize custom payload: {0}")]
+    CustomPayloadSerialization(std::num::TryFromIntError),
 }
 
 /// An error type returned when deserialization of CQL
@@ -522,6 +544,7 @@ pub enum CqlTypeParseError {
 /// - out of range integer conversion
 /// - conversion errors - e.g. slice-to-array or primitive-to-enum
 /// - not enough bytes in the buffer to deserialize a value
+/// - sequence length prefix larger than the rest of the buffer can hold
 #[non_exhaustive]
 #[derive(Error, Debug, Clone)]
 pub enum LowLevelDeserializationError {
@@ -541,6 +564,8 @@ pub enum LowLevelDeserializationError {
     InvalidInetLength(u8),
     #[error("UTF8 deserialization failed: {0}")]
     UTF8DeserializationError(#[from] std::str::Utf8Error),
+    #[error("Sequence length {length} exceeds what the remaining {remaining} bytes can hold")]
+    SequenceLengthExceedsBuffer { length: usize, remaining: usize },
 }
 
 impl From<std::io::Error> for LowLevelDeserializationError {
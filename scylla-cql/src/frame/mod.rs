@@ -15,7 +15,7 @@ pub mod response;
 pub mod server_event_type;
 pub mod types;
 
-use bytes::{Buf, BufMut, Bytes};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
 use frame_errors::{
     CqlRequestSerializationError, FrameBodyExtensionsParseError, FrameHeaderParseError,
 };
@@ -114,6 +114,8 @@ impl Display for Compression {
 /// The stream number is set by the `set_stream` method before sending.
 pub struct SerializedRequest {
     data: Vec<u8>,
+    // Set only if the body was compressed.
+    uncompressed_body_length: Option<usize>,
 }
 
 impl SerializedRequest {
@@ -127,16 +129,53 @@ impl SerializedRequest {
         req: &R,
         compression: Option<Compression>,
         tracing: bool,
+    ) -> Result<SerializedRequest, CqlRequestSerializationError> {
+        Self::make_in_buffer(req, compression, 0, tracing, &[], Vec::new())
+    }
+
+    /// Creates a new serialized request frame from a request object,
+    /// reusing the allocation of `buffer`.
+    ///
+    /// Bodies shorter than `compression_threshold` bytes are not compressed,
+    /// even if `compression` is set, because compressing small bodies costs CPU
+    /// while saving little or nothing.
+    ///
+    /// If `custom_payload` is not empty, its entries are sent in the custom payload
+    /// of the frame, preceding the request body.
+    ///
+    /// The previous contents of `buffer` are discarded. The buffer can be
+    /// reclaimed with [SerializedRequest::into_data] once the frame is sent,
+    /// which lets callers avoid allocating a new buffer for every request.
+    pub fn make_in_buffer<R: SerializableRequest>(
+        req: &R,
+        compression: Option<Compression>,
+        compression_threshold: usize,
+        tracing: bool,
+        custom_payload: &[(&str, &[u8])],
+        buffer: Vec<u8>,
     ) -> Result<SerializedRequest, CqlRequestSerializationError> {
         let mut flags = 0;
-        let mut data = vec![0; HEADER_SIZE];
+        let mut data = buffer;
+        data.clear();
+        data.resize(HEADER_SIZE, 0);
+
+        if !custom_payload.is_empty() {
+            flags |= flag::CUSTOM_PAYLOAD;
+            write_custom_payload(custom_payload, &mut data)
+                .map_err(CqlRequestSerializationError::CustomPayloadSerialization)?;
+        }
+
+        req.serialize(&mut data)?;
 
+        let body_length = data.len() - HEADER_SIZE;
+        let mut uncompressed_body_length = None;
         if let Some(compression) = compression {
-            flags |= flag::COMPRESSION;
-            let body = req.to_bytes()?;
-            compress_append(&body, compression, &mut data)?;
-        } else {
-            req.serialize(&mut data)?;
+            if body_length >= compression_threshold {
+                flags |= flag::COMPRESSION;
+                let body = data.split_off(HEADER_SIZE);
+                compress_append(&body, compression, &mut data)?;
+                uncompressed_body_length = Some(body_length);
+            }
         }
 
         if tracing {
@@ -151,7 +190,10 @@ impl SerializedRequest {
         let req_size = (data.len() - HEADER_SIZE) as u32;
         data[5..9].copy_from_slice(&req_size.to_be_bytes());
 
-        Ok(Self { data })
+        Ok(Self {
+            data,
+            uncompressed_body_length,
+        })
     }
 
     /// Sets the stream number for this request frame.
@@ -165,6 +207,33 @@ impl SerializedRequest {
     pub fn get_data(&self) -> &[u8] {
         &self.data[..]
     }
+
+    /// Returns the length of the frame body, as sent over the wire.
+    pub fn body_length(&self) -> usize {
+        self.data.len() - HEADER_SIZE
+    }
+
+    /// If the frame body was compressed, returns its length before compression.
+    pub fn uncompressed_body_length(&self) -> Option<usize> {
+        self.uncompressed_body_length
+    }
+
+    /// Consumes the request frame, returning the buffer holding its data.
+    pub fn into_data(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+fn write_custom_payload(
+    custom_payload: &[(&str, &[u8])],
+    buf: &mut Vec<u8>,
+) -> Result<(), std::num::TryFromIntError> {
+    types::write_short(u16::try_from(custom_payload.len())?, buf);
+    for (key, value) in custom_payload {
+        types::write_string(key, buf)?;
+        types::write_bytes(value, buf)?;
+    }
+    Ok(())
 }
 
 /// Parts of the frame header which are not determined by the request/response type.
@@ -193,11 +262,115 @@ impl Default for FrameParams {
     }
 }
 
+/// Limits on sizes of received response frames.
+///
+/// Lengths of frame bodies come from the peer, so without limits a misbehaving
+/// server or proxy could make the driver allocate unbounded amounts of memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResponseFrameLimits {
+    max_body_length: usize,
+    max_decompressed_body_length: usize,
+}
+
+impl ResponseFrameLimits {
+    /// Default limit of both the body length and the decompressed body length: 256 MiB,
+    /// which is the maximum frame size allowed by the CQL protocol.
+    pub const DEFAULT_MAX_BODY_LENGTH: usize = 256 * 1024 * 1024;
+
+    /// Creates limits with default values.
+    pub const fn new() -> Self {
+        Self {
+            max_body_length: Self::DEFAULT_MAX_BODY_LENGTH,
+            max_decompressed_body_length: Self::DEFAULT_MAX_BODY_LENGTH,
+        }
+    }
+
+    /// Sets the maximum length of a frame body, as declared in the frame header.
+    pub const fn with_max_body_length(mut self, max_body_length: usize) -> Self {
+        self.max_body_length = max_body_length;
+        self
+    }
+
+    /// Sets the maximum length of a compressed frame body after decompression.
+    pub const fn with_max_decompressed_body_length(
+        mut self,
+        max_decompressed_body_length: usize,
+    ) -> Self {
+        self.max_decompressed_body_length = max_decompressed_body_length;
+        self
+    }
+
+    /// The maximum length of a frame body, as declared in the frame header.
+    pub const fn max_body_length(&self) -> usize {
+        self.max_body_length
+    }
+
+    /// The maximum length of a compressed frame body after decompression.
+    pub const fn max_decompressed_body_length(&self) -> usize {
+        self.max_decompressed_body_length
+    }
+}
+
+impl Default for ResponseFrameLimits {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Reads a response frame from the provided reader (usually, a socket).
 /// Then parses and validates the frame header and extracts the body.
+///
+/// Bodies longer than [ResponseFrameLimits::DEFAULT_MAX_BODY_LENGTH] are rejected;
+/// use [read_response_frame_with_limits] to configure that.
 pub async fn read_response_frame(
     reader: &mut (impl AsyncRead + Unpin),
 ) -> Result<(FrameParams, ResponseOpcode, Bytes), FrameHeaderParseError> {
+    read_response_frame_with_limits(reader, &ResponseFrameLimits::default()).await
+}
+
+/// Reads a response frame from the provided reader (usually, a socket).
+/// Then parses and validates the frame header and extracts the body,
+/// rejecting bodies longer than allowed by `limits`.
+pub async fn read_response_frame_with_limits(
+    reader: &mut (impl AsyncRead + Unpin),
+    limits: &ResponseFrameLimits,
+) -> Result<(FrameParams, ResponseOpcode, Bytes), FrameHeaderParseError> {
+    let (frame_params, opcode, length) = read_response_frame_header(reader, limits).await?;
+
+    let mut raw_body = Vec::with_capacity(length).limit(length);
+    read_response_frame_body(reader, &mut raw_body, length).await?;
+
+    Ok((frame_params, opcode, raw_body.into_inner().into()))
+}
+
+/// Reads a response frame from the provided reader (usually, a socket),
+/// like [read_response_frame_with_limits], but lets the caller provide
+/// the buffer in which the body is assembled.
+///
+/// `buffer_for_body` is called with the length of the body, and returns
+/// the buffer to use. The body is split off the front of that buffer,
+/// so its remaining capacity can be used for subsequent frames. Once all
+/// bodies split off an allocation are dropped, [BytesMut::reserve] can reuse
+/// the allocation. Previous contents of the buffer are discarded.
+pub async fn read_response_frame_into<'buf>(
+    reader: &mut (impl AsyncRead + Unpin),
+    limits: &ResponseFrameLimits,
+    buffer_for_body: impl FnOnce(usize) -> &'buf mut BytesMut,
+) -> Result<(FrameParams, ResponseOpcode, Bytes), FrameHeaderParseError> {
+    let (frame_params, opcode, length) = read_response_frame_header(reader, limits).await?;
+
+    let buffer = buffer_for_body(length);
+    buffer.clear();
+    buffer.reserve(length);
+    read_response_frame_body(reader, &mut (&mut *buffer).limit(length), length).await?;
+
+    Ok((frame_params, opcode, buffer.split_to(length).freeze()))
+}
+
+async fn read_response_frame_header(
+    reader: &mut (impl AsyncRead + Unpin),
+    limits: &ResponseFrameLimits,
+) -> Result<(FrameParams, ResponseOpcode, usize), FrameHeaderParseError> {
     let mut raw_header = [0u8; HEADER_SIZE];
     reader
         .read_exact(&mut raw_header[..])
@@ -226,12 +399,24 @@ pub async fn read_response_frame(
 
     let opcode = ResponseOpcode::try_from(buf.get_u8())?;
 
-    // TODO: Guard from frames that are too large
     let length = buf.get_u32() as usize;
+    if length > limits.max_body_length {
+        return Err(FrameHeaderParseError::BodyTooLong {
+            length,
+            max_length: limits.max_body_length,
+        });
+    }
 
-    let mut raw_body = Vec::with_capacity(length).limit(length);
+    Ok((frame_params, opcode, length))
+}
+
+async fn read_response_frame_body(
+    reader: &mut (impl AsyncRead + Unpin),
+    raw_body: &mut (impl BufMut + Unpin),
+    length: usize,
+) -> Result<(), FrameHeaderParseError> {
     while raw_body.has_remaining_mut() {
-        let n = reader.read_buf(&mut raw_body).await.map_err(|err| {
+        let n = reader.read_buf(raw_body).await.map_err(|err| {
             FrameHeaderParseError::BodyChunkIoError(raw_body.remaining_mut(), err)
         })?;
         if n == 0 {
@@ -243,7 +428,7 @@ pub async fn read_response_frame(
         }
     }
 
-    Ok((frame_params, opcode, raw_body.into_inner().into()))
+    Ok(())
 }
 
 /// Represents the already parsed response body extensions,
@@ -270,13 +455,30 @@ pub struct ResponseBodyWithExtensions {
 /// Decompresses the response body if compression is enabled,
 /// and parses any extensions like trace ID, warnings, and custom payload.
 pub fn parse_response_body_extensions(
+    flags: u8,
+    compression: Option<Compression>,
+    body: Bytes,
+) -> Result<ResponseBodyWithExtensions, FrameBodyExtensionsParseError> {
+    parse_response_body_extensions_with_limits(
+        flags,
+        compression,
+        body,
+        &ResponseFrameLimits::default(),
+    )
+}
+
+/// Like [parse_response_body_extensions], but rejects bodies which
+/// decompress to more bytes than allowed by `limits`.
+pub fn parse_response_body_extensions_with_limits(
     flags: u8,
     compression: Option<Compression>,
     mut body: Bytes,
+    limits: &ResponseFrameLimits,
 ) -> Result<ResponseBodyWithExtensions, FrameBodyExtensionsParseError> {
     if flags & flag::COMPRESSION != 0 {
         if let Some(compression) = compression {
-            body = decompress(&body, compression)?.into();
+            body = decompress_with_limit(&body, compression, limits.max_decompressed_body_length)?
+                .into();
         } else {
             return Err(FrameBodyExtensionsParseError::NoCompressionNegotiated);
         }
@@ -355,16 +557,45 @@ pub fn compress_append(
 /// Deompresses the response body using the specified compression algorithm
 /// and returns the decompressed data as an owned buffer.
 pub fn decompress(
+    comp_body: &[u8],
+    compression: Compression,
+) -> Result<Vec<u8>, FrameBodyExtensionsParseError> {
+    decompress_with_limit(
+        comp_body,
+        compression,
+        ResponseFrameLimits::DEFAULT_MAX_BODY_LENGTH,
+    )
+}
+
+/// Like [decompress], but fails without allocating if the declared length
+/// of the decompressed data exceeds `max_length`.
+pub fn decompress_with_limit(
     mut comp_body: &[u8],
     compression: Compression,
+    max_length: usize,
 ) -> Result<Vec<u8>, FrameBodyExtensionsParseError> {
+    let uncomp_len = match compression {
+        Compression::Lz4 => comp_body.try_get_u32().map_err(|_| {
+            FrameBodyExtensionsParseError::Lz4DecompressError(Arc::new(
+                frame_errors::LowLevelDeserializationError::TooFewBytesReceived {
+                    expected: std::mem::size_of::<u32>(),
+                    received: comp_body.len(),
+                },
+            ))
+        })? as usize,
+        Compression::Snappy => snap::raw::decompress_len(comp_body)
+            .map_err(|err| FrameBodyExtensionsParseError::SnapDecompressError(Arc::new(err)))?,
+    };
+    if uncomp_len > max_length {
+        return Err(FrameBodyExtensionsParseError::DecompressedBodyTooLong {
+            length: uncomp_len,
+            max_length,
+        });
+    }
+
     match compression {
-        Compression::Lz4 => {
-            let uncomp_len = comp_body.get_u32() as usize;
-            let uncomp_body = lz4_flex::decompress(comp_body, uncomp_len)
-                .map_err(|err| FrameBodyExtensionsParseError::Lz4DecompressError(Arc::new(err)))?;
-            Ok(uncomp_body)
-        }
+        Compression::Lz4 => lz4_flex::decompress(comp_body, uncomp_len)
+            .map_err(|err| FrameBodyExtensionsParseError::Lz4DecompressError(Arc::new(err))),
         Compression::Snappy => snap::raw::Decoder::new()
             .decompress_vec(comp_body)
             .map_err(|err| FrameBodyExtensionsParseError::SnapDecompressError(Arc::new(err))),
@@ -406,4 +637,176 @@ mod test {
         assert_eq!(32, comp_body.len());
         assert_eq!(uncomp_body.as_bytes(), result);
     }
+
+    #[test]
+    fn test_decompress_with_limit() {
+        let uncomp_body = "Hello, World!".repeat(100);
+        for compression in [Compression::Lz4, Compression::Snappy] {
+            let mut comp_body = Vec::new();
+            compress_append(uncomp_body.as_bytes(), compression, &mut comp_body).unwrap();
+
+            let result = decompress_with_limit(&comp_body, compression, uncomp_body.len()).unwrap();
+            assert_eq!(uncomp_body.as_bytes(), result);
+
+            assert_matches::assert_matches!(
+                decompress_with_limit(&comp_body, compression, uncomp_body.len() - 1),
+                Err(FrameBodyExtensionsParseError::DecompressedBodyTooLong {
+                    length: 1300,
+                    max_length: 1299,
+                })
+            );
+        }
+
+        // Too short to contain the lz4 length prefix.
+        assert_matches::assert_matches!(
+            decompress(&[0, 0], Compression::Lz4),
+            Err(FrameBodyExtensionsParseError::Lz4DecompressError(_))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_response_frame_with_limits() {
+        // Header of a READY response with a declared body length of 1 GiB.
+        let frame = [0x84, 0, 0, 0, 0x02, 0x40, 0, 0, 0];
+        let limits = ResponseFrameLimits::new().with_max_body_length(1024);
+        assert_matches::assert_matches!(
+            read_response_frame_with_limits(&mut &frame[..], &limits).await,
+            Err(FrameHeaderParseError::BodyTooLong {
+                length: 0x4000_0000,
+                max_length: 1024,
+            })
+        );
+
+        let frame = [0x84, 0, 0, 0, 0x02, 0, 0, 0, 1, 42];
+        let (_, opcode, body) = read_response_frame_with_limits(&mut &frame[..], &limits)
+            .await
+            .unwrap();
+        assert_eq!(opcode, ResponseOpcode::Ready);
+        assert_eq!(&body[..], [42]);
+    }
+
+    #[tokio::test]
+    async fn test_read_response_frame_into() {
+        let limits = ResponseFrameLimits::new();
+        let mut buffer = BytesMut::with_capacity(64);
+        let frames = [
+            0x84, 0, 0, 1, 0x02, 0, 0, 0, 2, 1, 2, 0x84, 0, 0, 2, 0x02, 0, 0, 0, 3, 3, 4, 5,
+        ];
+        let mut reader = &frames[..];
+
+        let buffer_ref = &mut buffer;
+        let (params, _, first) = read_response_frame_into(&mut reader, &limits, move |length| {
+            assert_eq!(length, 2);
+            buffer_ref
+        })
+        .await
+        .unwrap();
+        assert_eq!(params.stream, 1);
+        assert_eq!(&first[..], [1, 2]);
+
+        let buffer_ref = &mut buffer;
+        let (params, _, second) =
+            read_response_frame_into(&mut reader, &limits, move |_| buffer_ref)
+                .await
+                .unwrap();
+        assert_eq!(params.stream, 2);
+        assert_eq!(&second[..], [3, 4, 5]);
+
+        // Both bodies were assembled in the same allocation.
+        assert_eq!(first.as_ptr().wrapping_add(2), second.as_ptr());
+    }
+
+    #[test]
+    fn test_serialized_request_reuses_buffer() {
+        let buffer = Vec::with_capacity(1024);
+        let buffer_ptr = buffer.as_ptr();
+        let request = request::Startup {
+            options: HashMap::new(),
+        };
+
+        let fresh = SerializedRequest::make(&request, None, false).unwrap();
+        let reused =
+            SerializedRequest::make_in_buffer(&request, None, 0, false, &[], buffer).unwrap();
+        assert_eq!(fresh.get_data(), reused.get_data());
+
+        let data = reused.into_data();
+        assert_eq!(data.as_ptr(), buffer_ptr);
+
+        // Stale contents of the buffer are discarded.
+        let reused =
+            SerializedRequest::make_in_buffer(&request, None, 0, false, &[], data).unwrap();
+        assert_eq!(fresh.get_data(), reused.get_data());
+    }
+
+    #[test]
+    fn test_serialized_request_custom_payload() {
+        let request = request::Startup {
+            options: HashMap::new(),
+        };
+        let plain = SerializedRequest::make(&request, None, false).unwrap();
+
+        let with_payload = SerializedRequest::make_in_buffer(
+            &request,
+            None,
+            0,
+            false,
+            &[("traceparent", b"00-01-02-01")],
+            Vec::new(),
+        )
+        .unwrap();
+        let data = with_payload.get_data();
+        assert_eq!(data[1], flag::CUSTOM_PAYLOAD);
+
+        // The payload precedes the request body.
+        let mut body = &data[HEADER_SIZE..];
+        let payload = types::read_bytes_map(&mut body).unwrap();
+        assert_eq!(payload.len(), 1);
+        assert_eq!(&payload["traceparent"][..], b"00-01-02-01");
+        assert_eq!(body, &plain.get_data()[HEADER_SIZE..]);
+        // The length in the header covers the payload.
+        assert_eq!(
+            &data[5..9],
+            &((data.len() - HEADER_SIZE) as u32).to_be_bytes()
+        );
+    }
+
+    #[test]
+    fn test_serialized_request_compression_threshold() {
+        let request = request::Startup {
+            options: [("CQL_VERSION".into(), "4.0.0".repeat(100).into())]
+                .into_iter()
+                .collect(),
+        };
+        let plain = SerializedRequest::make(&request, None, false).unwrap();
+        let body_length = plain.body_length();
+        assert_eq!(plain.uncompressed_body_length(), None);
+
+        for compression in [Compression::Lz4, Compression::Snappy] {
+            // Body shorter than the threshold is sent uncompressed.
+            let below = SerializedRequest::make_in_buffer(
+                &request,
+                Some(compression),
+                body_length + 1,
+                false,
+                &[],
+                Vec::new(),
+            )
+            .unwrap();
+            assert_eq!(below.get_data(), plain.get_data());
+            assert_eq!(below.uncompressed_body_length(), None);
+
+            let above = SerializedRequest::make_in_buffer(
+                &request,
+                Some(compression),
+                body_length,
+                false,
+                &[],
+                Vec::new(),
+            )
+            .unwrap();
+            assert_eq!(above.get_data()[1] & flag::COMPRESSION, flag::COMPRESSION);
+            assert_eq!(above.uncompressed_body_length(), Some(body_length));
+            assert!(above.body_length() < body_length);
+        }
+    }
 }
@@ -94,6 +94,19 @@ pub enum SchemaChangeEvent {
     },
 }
 
+impl SchemaChangeEvent {
+    /// Name of the keyspace affected by the change.
+    pub fn keyspace_name(&self) -> &str {
+        match self {
+            SchemaChangeEvent::KeyspaceChange { keyspace_name, .. }
+            | SchemaChangeEvent::TableChange { keyspace_name, .. }
+            | SchemaChangeEvent::TypeChange { keyspace_name, .. }
+            | SchemaChangeEvent::FunctionChange { keyspace_name, .. }
+            | SchemaChangeEvent::AggregateChange { keyspace_name, .. } => keyspace_name,
+        }
+    }
+}
+
 /// Type of change that was made to the schema.
 #[derive(Debug)]
 pub enum SchemaChangeType {
@@ -180,6 +193,8 @@ impl SchemaChangeEvent {
                 let number_of_arguments = types::read_short(buf).map_err(|err| {
                     SchemaChangeEventParseError::ArgumentCountParseError(err.into())
                 })?;
+                types::check_sequence_length(buf, number_of_arguments.into(), 2)
+                    .map_err(SchemaChangeEventParseError::ArgumentCountParseError)?;
 
                 let mut argument_vector = Vec::with_capacity(number_of_arguments as usize);
 
@@ -205,6 +220,8 @@ impl SchemaChangeEvent {
                 let number_of_arguments = types::read_short(buf).map_err(|err| {
                     SchemaChangeEventParseError::ArgumentCountParseError(err.into())
                 })?;
+                types::check_sequence_length(buf, number_of_arguments.into(), 2)
+                    .map_err(SchemaChangeEventParseError::ArgumentCountParseError)?;
 
                 let mut argument_vector = Vec::with_capacity(number_of_arguments as usize);
 
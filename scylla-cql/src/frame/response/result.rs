@@ -969,6 +969,9 @@ fn deser_type_generic<'frame, 'result, StrT: Into<Cow<'result, str>>>(
             let fields_size: usize = types::read_short(buf)
                 .map_err(|err| CqlTypeParseError::UdtFieldsCountParseError(err.into()))?
                 .into();
+            // Each field consists of at least a name length and a type id.
+            types::check_sequence_length(buf, fields_size, 2 + 2)
+                .map_err(CqlTypeParseError::UdtFieldsCountParseError)?;
 
             let mut field_types: Vec<(Cow<'result, str>, ColumnType)> =
                 Vec::with_capacity(fields_size);
@@ -994,6 +997,8 @@ fn deser_type_generic<'frame, 'result, StrT: Into<Cow<'result, str>>>(
             let len: usize = types::read_short(buf)
                 .map_err(|err| CqlTypeParseError::TupleLengthParseError(err.into()))?
                 .into();
+            types::check_sequence_length(buf, len, 2)
+                .map_err(CqlTypeParseError::TupleLengthParseError)?;
             let mut types = Vec::with_capacity(len);
             for _ in 0..len {
                 types.push(deser_type_generic(buf, read_string, read_custom_type)?);
@@ -1043,6 +1048,9 @@ fn mk_col_spec_parse_error(
     }
 }
 
+/// Minimal serialized size of a column spec: the length of its name and its type id.
+const MIN_COL_SPEC_SIZE: usize = 2 + 2;
+
 fn deser_col_specs_generic<'frame, 'result>(
     buf: &mut &'frame [u8],
     global_table_spec: Option<TableSpec<'frame>>,
@@ -1148,6 +1156,8 @@ fn deser_result_metadata(
             .then(|| deser_table_spec(buf))
             .transpose()?;
 
+        types::check_sequence_length(buf, col_count, MIN_COL_SPEC_SIZE)
+            .map_err(ResultMetadataParseError::ColumnCountParseError)?;
         deser_col_specs_owned(buf, global_table_spec, col_count)?
     };
 
@@ -1237,6 +1247,8 @@ impl RawMetadataAndRawRows {
                     .then(|| deser_table_spec(buf))
                     .transpose()?;
 
+                types::check_sequence_length(buf, col_count, MIN_COL_SPEC_SIZE)
+                    .map_err(ResultMetadataParseError::ColumnCountParseError)?;
                 let col_specs = deser_col_specs_borrowed(buf, global_table_spec, col_count)?;
 
                 ResultMetadata {
@@ -1325,13 +1337,15 @@ fn deser_prepared_metadata(
 
     let pk_count: usize =
         types::read_int_length(buf).map_err(PreparedMetadataParseError::PkCountParseError)?;
+    // Each partition key index is a [short].
+    types::check_sequence_length(buf, pk_count, 2)
+        .map_err(PreparedMetadataParseError::PkCountParseError)?;
 
     let mut pk_indexes = Vec::with_capacity(pk_count);
     for i in 0..pk_count {
         pk_indexes.push(PartitionKeyIndex {
             index: types::read_short(buf)
-                .map_err(|err| PreparedMetadataParseError::PkIndexParseError(err.into()))?
-                as u16,
+                .map_err(|err| PreparedMetadataParseError::PkIndexParseError(err.into()))?,
             sequence: i as u16,
         });
     }
@@ -1341,6 +1355,8 @@ fn deser_prepared_metadata(
         .then(|| deser_table_spec(buf))
         .transpose()?;
 
+    types::check_sequence_length(buf, col_count, MIN_COL_SPEC_SIZE)
+        .map_err(PreparedMetadataParseError::ColumnCountParseError)?;
     let col_specs = deser_col_specs_owned(buf, global_table_spec, col_count)?;
 
     Ok(PreparedMetadata {
@@ -2,6 +2,8 @@
 
 use super::TryFromPrimitiveError;
 use super::frame_errors::LowLevelDeserializationError;
+#[cfg(test)]
+use assert_matches::assert_matches;
 use byteorder::{BigEndian, ReadBytesExt};
 use bytes::BufMut;
 use bytes::Bytes;
@@ -117,6 +119,15 @@ impl Consistency {
     pub fn is_serial(&self) -> bool {
         matches!(self, Consistency::Serial | Consistency::LocalSerial)
     }
+
+    /// Checks if the consistency is confined to the local datacenter
+    /// (i.e. it is one of `LOCAL_ONE`, `LOCAL_QUORUM` or `LOCAL_SERIAL`).
+    pub fn is_dc_local(&self) -> bool {
+        matches!(
+            self,
+            Consistency::LocalOne | Consistency::LocalQuorum | Consistency::LocalSerial
+        )
+    }
 }
 
 /// Error returned when a serial consistency what expected, yet got another kind of consistency.
@@ -258,6 +269,25 @@ pub(crate) fn read_short_length(buf: &mut &[u8]) -> Result<usize, std::io::Error
     Ok(v)
 }
 
+/// Checks that a sequence of `len` elements, each occupying at least
+/// `min_element_size` bytes, fits in the remaining part of `buf`.
+///
+/// Length prefixes are sent by the peer, so they have to be validated
+/// before being used to preallocate memory.
+pub(crate) fn check_sequence_length(
+    buf: &[u8],
+    len: usize,
+    min_element_size: usize,
+) -> Result<(), LowLevelDeserializationError> {
+    if len.saturating_mul(min_element_size) > buf.len() {
+        return Err(LowLevelDeserializationError::SequenceLengthExceedsBuffer {
+            length: len,
+            remaining: buf.len(),
+        });
+    }
+    Ok(())
+}
+
 pub(crate) fn write_short_length(
     v: usize,
     buf: &mut impl BufMut,
@@ -347,6 +377,8 @@ pub fn read_bytes_map(
     buf: &mut &[u8],
 ) -> Result<HashMap<String, Bytes>, LowLevelDeserializationError> {
     let len = read_short_length(buf)?;
+    // Each entry consists of at least a [string] length and a [bytes] length.
+    check_sequence_length(buf, len, 2 + 4)?;
     let mut v = HashMap::with_capacity(len);
     for _ in 0..len {
         let key = read_string(buf)?.to_owned();
@@ -436,6 +468,7 @@ pub fn read_string_map(
     buf: &mut &[u8],
 ) -> Result<HashMap<String, String>, LowLevelDeserializationError> {
     let len = read_short_length(buf)?;
+    check_sequence_length(buf, len, 2 + 2)?;
     let mut v = HashMap::with_capacity(len);
     for _ in 0..len {
         let key = read_string(buf)?.to_owned();
@@ -471,6 +504,7 @@ fn type_string_map() {
 
 pub fn read_string_list(buf: &mut &[u8]) -> Result<Vec<String>, LowLevelDeserializationError> {
     let len = read_short_length(buf)?;
+    check_sequence_length(buf, len, 2)?;
     let mut v = Vec::with_capacity(len);
     for _ in 0..len {
         v.push(read_string(buf)?.to_owned());
@@ -501,12 +535,23 @@ fn type_string_list() {
     let mut buf = Vec::new();
     write_string_list(&val, &mut buf).unwrap();
     assert_eq!(read_string_list(&mut &buf[..]).unwrap(), val);
+
+    // A length prefix which the rest of the buffer cannot hold is rejected.
+    let buf = [0xFF, 0xFF, 0x00, 0x00];
+    assert_matches!(
+        read_string_list(&mut &buf[..]),
+        Err(LowLevelDeserializationError::SequenceLengthExceedsBuffer {
+            length: 0xFFFF,
+            remaining: 2,
+        })
+    );
 }
 
 pub fn read_string_multimap(
     buf: &mut &[u8],
 ) -> Result<HashMap<String, Vec<String>>, LowLevelDeserializationError> {
     let len = read_short_length(buf)?;
+    check_sequence_length(buf, len, 2 + 2)?;
     let mut v = HashMap::with_capacity(len);
     for _ in 0..len {
         let key = read_string(buf)?.to_owned();
@@ -9,6 +9,7 @@ pub mod frame;
 
 pub use scylla_macros::DeserializeRow;
 pub use scylla_macros::DeserializeValue;
+pub use scylla_macros::SerializeDeserializeRow;
 pub use scylla_macros::SerializeRow;
 pub use scylla_macros::SerializeValue;
 
@@ -11,11 +11,14 @@ use std::{collections::HashMap, sync::Arc};
 use bytes::BufMut;
 use thiserror::Error;
 
+use crate::deserialize::value::DeserializeValue;
+use crate::deserialize::{DeserializationError, FrameSlice};
 use crate::frame::request::RequestDeserializationError;
 use crate::frame::response::result::ColumnType;
 use crate::frame::response::result::PreparedMetadata;
 use crate::frame::types;
 use crate::frame::{response::result::ColumnSpec, types::RawValue};
+use crate::value::CqlValue;
 
 use super::value::SerializeValue;
 use super::{CellWriter, RowWriter, SerializationError};
@@ -403,6 +406,15 @@ pub enum BuiltinTypeCheckErrorKind {
         /// Name of the column for which the DB requested a value.
         db_column_name: String,
     },
+
+    /// The value provided for some column is not a valid value of the column's type.
+    InvalidValueForColumn {
+        /// Name of the column.
+        name: String,
+
+        /// The reason why the value is not valid.
+        err: DeserializationError,
+    },
 }
 
 impl Display for BuiltinTypeCheckErrorKind {
@@ -436,6 +448,12 @@ impl Display for BuiltinTypeCheckErrorKind {
                 f,
                 "expected column with name {db_column_name} at given position, but the Rust field name is {rust_column_name}"
             ),
+            BuiltinTypeCheckErrorKind::InvalidValueForColumn { name, err } => {
+                write!(
+                    f,
+                    "value provided for column {name} is not a valid value of its type: {err}"
+                )
+            }
         }
     }
 }
@@ -494,6 +512,20 @@ impl SerializedValues {
         }
     }
 
+    /// Constructs a new, empty `SerializedValues` with preallocated space
+    /// for `n_values` values whose contents take `n_bytes` bytes in total.
+    ///
+    /// `n_bytes` should not include the 4-byte length prefix of each value;
+    /// space for those is reserved based on `n_values`.
+    pub fn with_capacity(n_values: usize, n_bytes: usize) -> Self {
+        SerializedValues {
+            serialized_values: Vec::with_capacity(
+                n_values.saturating_mul(4).saturating_add(n_bytes),
+            ),
+            element_count: 0,
+        }
+    }
+
     /// A const empty instance, useful for taking references
     pub const EMPTY: &'static SerializedValues = &SerializedValues::new();
 
@@ -510,25 +542,61 @@ impl SerializedValues {
     where
         F: FnOnce(&mut RowWriter) -> Result<R, SerializationError>,
     {
-        let mut data = Vec::new();
-        let mut writer = RowWriter::new(&mut data);
-        let ret = f(&mut writer)?;
-        let element_count = match writer.value_count().try_into() {
-            Ok(n) => n,
-            Err(_) => {
-                return Err(SerializationError(Arc::new(mk_ser_err::<Self>(
+        let mut values = SerializedValues::new();
+        let ret = values.reset_from_closure(f)?;
+        Ok((values, ret))
+    }
+
+    /// Replaces the contents of this `SerializedValues` with the given [`SerializeRow`] object.
+    ///
+    /// Unlike [`SerializedValues::from_serializable`], this reuses the already allocated
+    /// buffer, which avoids an allocation per request when the same object is used
+    /// to bind values repeatedly (e.g. in a loop).
+    ///
+    /// If serialization fails, the object is left empty.
+    pub fn reset_from_serializable<T: SerializeRow + ?Sized>(
+        &mut self,
+        ctx: &RowSerializationContext,
+        row: &T,
+    ) -> Result<(), SerializationError> {
+        self.reset_from_closure(|writer| row.serialize(ctx, writer))
+    }
+
+    /// Replaces the contents of this `SerializedValues` with values written by given closure.
+    ///
+    /// This is the buffer-reusing counterpart of [`SerializedValues::from_closure`].
+    /// If the closure fails, the object is left empty.
+    pub fn reset_from_closure<F, R>(&mut self, f: F) -> Result<R, SerializationError>
+    where
+        F: FnOnce(&mut RowWriter) -> Result<R, SerializationError>,
+    {
+        self.clear();
+        let mut writer = RowWriter::new(&mut self.serialized_values);
+        let ret = f(&mut writer);
+        let element_count = writer.value_count().try_into();
+        match (ret, element_count) {
+            (Ok(ret), Ok(element_count)) => {
+                self.element_count = element_count;
+                Ok(ret)
+            }
+            (Err(err), _) => {
+                self.clear();
+                Err(err)
+            }
+            (Ok(_), Err(_)) => {
+                self.clear();
+                Err(SerializationError(Arc::new(mk_ser_err::<Self>(
                     BuiltinSerializationErrorKind::TooManyValues,
-                ))));
+                ))))
             }
-        };
-
-        Ok((
-            SerializedValues {
-                serialized_values: data,
-                element_count,
-            },
-            ret,
-        ))
+        }
+    }
+
+    /// Removes all values, keeping the allocated buffer for reuse.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.serialized_values.clear();
+        self.element_count = 0;
     }
 
     /// Returns `true` if the row contains no elements.
@@ -557,6 +625,37 @@ impl SerializedValues {
         self.serialized_values.len()
     }
 
+    /// Checks that the values fit the columns described by `ctx`, i.e. that there
+    /// is one value per column and that each value is a valid value of the column's type.
+    ///
+    /// This is meant for values which were serialized separately from the statement
+    /// they are bound to. Null and unset values are accepted for every column.
+    pub fn type_check(&self, ctx: &RowSerializationContext<'_>) -> Result<(), SerializationError> {
+        let columns = ctx.columns();
+        if self.element_count() as usize != columns.len() {
+            return Err(mk_typck_err::<Self>(
+                BuiltinTypeCheckErrorKind::WrongColumnCount {
+                    rust_cols: self.element_count() as usize,
+                    cql_cols: columns.len(),
+                },
+            ));
+        }
+
+        for (spec, value) in columns.iter().zip(self.iter()) {
+            if let RawValue::Value(bytes) = value {
+                CqlValue::deserialize(spec.typ(), Some(FrameSlice::new_borrowed(bytes))).map_err(
+                    |err| {
+                        mk_typck_err::<Self>(BuiltinTypeCheckErrorKind::InvalidValueForColumn {
+                            name: spec.name().to_owned(),
+                            err,
+                        })
+                    },
+                )?;
+            }
+        }
+        Ok(())
+    }
+
     pub(crate) fn write_to_request(&self, buf: &mut impl BufMut) {
         buf.put_u16(self.element_count);
         buf.put(self.serialized_values.as_slice())
@@ -678,6 +777,31 @@ mod doctests {
     /// }
     /// ```
     fn _test_struct_deserialization_rename_collision_with_another_rename() {}
+
+    /// ```compile_fail
+    ///
+    /// #[derive(scylla_macros::SerializeRow)]
+    /// #[scylla(crate = scylla_cql)]
+    /// struct Inner {
+    ///     b: i32,
+    /// }
+    ///
+    /// fn serialize_inner<'b>(
+    ///     _: &Inner,
+    ///     _: &scylla_cql::frame::response::result::ColumnType,
+    ///     _: scylla_cql::serialize::CellWriter<'b>,
+    /// ) -> Result<scylla_cql::serialize::writers::WrittenCellProof<'b>, scylla_cql::serialize::SerializationError> {
+    ///     unimplemented!()
+    /// }
+    ///
+    /// #[derive(scylla_macros::SerializeRow)]
+    /// #[scylla(crate = scylla_cql)]
+    /// struct TestRow {
+    ///     #[scylla(flatten, serialize_with = serialize_inner)]
+    ///     a: Inner,
+    /// }
+    /// ```
+    fn _test_struct_serialization_serialize_with_conflicts_with_flatten() {}
 }
 
 #[cfg(test)]
@@ -569,6 +569,93 @@ fn test_serialized_values_max_capacity() {
     )
 }
 
+#[test]
+fn test_serialized_values_reuse() {
+    let columns = [
+        col("a", ColumnType::Native(NativeType::Int)),
+        col("b", ColumnType::Native(NativeType::Text)),
+    ];
+    let ctx = RowSerializationContext::from_specs(&columns);
+
+    let mut values = SerializedValues::with_capacity(2, 16);
+    let capacity = values.serialized_values.capacity();
+    assert!(capacity >= 2 * 4 + 16);
+
+    for i in 0..10 {
+        values.reset_from_serializable(&ctx, &(i, "abc")).unwrap();
+        assert_eq!(values.element_count(), 2);
+        assert_eq!(
+            values.iter().collect::<Vec<_>>(),
+            [
+                RawValue::Value(&i32::to_be_bytes(i)),
+                RawValue::Value(b"abc")
+            ]
+        );
+        // The buffer is reused, no reallocation happens.
+        assert_eq!(values.serialized_values.capacity(), capacity);
+    }
+
+    // A failed serialization leaves the values empty.
+    values
+        .reset_from_serializable(&ctx, &("not an int", "abc"))
+        .unwrap_err();
+    assert!(values.is_empty());
+    assert_eq!(values.buffer_size(), 0);
+
+    values
+        .add_value(&1234i32, &ColumnType::Native(NativeType::Int))
+        .unwrap();
+    values.clear();
+    assert!(values.is_empty());
+    assert_eq!(values.buffer_size(), 0);
+    assert_eq!(values.serialized_values.capacity(), capacity);
+}
+
+#[test]
+fn test_serialized_values_type_check() {
+    let columns = [
+        col("a", ColumnType::Native(NativeType::Int)),
+        col("b", ColumnType::Native(NativeType::Text)),
+    ];
+    let ctx = RowSerializationContext::from_specs(&columns);
+
+    let values = SerializedValues::from_serializable(&ctx, &(1, "abc")).unwrap();
+    values.type_check(&ctx).unwrap();
+
+    // Null and unset values are accepted for every column.
+    let values =
+        SerializedValues::from_serializable(&ctx, &(None::<i32>, MaybeUnset::<&str>::Unset))
+            .unwrap();
+    values.type_check(&ctx).unwrap();
+
+    // Values serialized for another statement.
+    let other_columns = [col("a", ColumnType::Native(NativeType::Int))];
+    let other_ctx = RowSerializationContext::from_specs(&other_columns);
+    let values = SerializedValues::from_serializable(&other_ctx, &(1,)).unwrap();
+    let err = values.type_check(&ctx).unwrap_err();
+    let err = get_typeck_err(&err);
+    assert_matches!(
+        err.kind,
+        BuiltinTypeCheckErrorKind::WrongColumnCount {
+            rust_cols: 1,
+            cql_cols: 2,
+        }
+    );
+
+    let other_columns = [
+        col("a", ColumnType::Native(NativeType::BigInt)),
+        col("b", ColumnType::Native(NativeType::Text)),
+    ];
+    let other_ctx = RowSerializationContext::from_specs(&other_columns);
+    let values = SerializedValues::from_serializable(&other_ctx, &(1i64, "abc")).unwrap();
+    let err = values.type_check(&ctx).unwrap_err();
+    let err = get_typeck_err(&err);
+    assert_matches!(
+        &err.kind,
+        BuiltinTypeCheckErrorKind::InvalidValueForColumn { name, .. } if name == "a"
+    );
+}
+
 #[derive(SerializeRow, Debug)]
 #[scylla(crate = crate)]
 struct TestRowWithColumnRename {
@@ -712,6 +799,79 @@ fn test_row_serialization_with_skipped_field() {
     assert_eq!(reference, row);
 }
 
+fn serialize_duration_as_millis<'b>(
+    duration: &std::time::Duration,
+    typ: &ColumnType,
+    writer: CellWriter<'b>,
+) -> Result<WrittenCellProof<'b>, SerializationError> {
+    (duration.as_millis() as i64).serialize(typ, writer)
+}
+
+#[test]
+fn test_row_serialization_with_serialize_with() {
+    #[derive(SerializeRow, Debug)]
+    #[scylla(crate = crate)]
+    struct TestRowWithSerializeWith {
+        a: String,
+        #[scylla(rename = "timeout_ms", serialize_with = serialize_duration_as_millis)]
+        timeout: std::time::Duration,
+    }
+
+    #[derive(SerializeRow, Debug)]
+    #[scylla(crate = crate, flavor = "enforce_order")]
+    struct TestRowWithSerializeWithAndEnforceOrder {
+        a: String,
+        #[scylla(rename = "timeout_ms", serialize_with = serialize_duration_as_millis)]
+        timeout: std::time::Duration,
+    }
+
+    let spec = [
+        col("timeout_ms", ColumnType::Native(NativeType::BigInt)),
+        col("a", ColumnType::Native(NativeType::Text)),
+    ];
+    let reference = do_serialize((1500i64, "Ala ma kota"), &spec);
+    let row = do_serialize(
+        TestRowWithSerializeWith {
+            a: "Ala ma kota".to_owned(),
+            timeout: std::time::Duration::from_millis(1500),
+        },
+        &spec,
+    );
+    assert_eq!(reference, row);
+
+    let spec = [
+        col("a", ColumnType::Native(NativeType::Text)),
+        col("timeout_ms", ColumnType::Native(NativeType::BigInt)),
+    ];
+    let reference = do_serialize(("Ala ma kota", 1500i64), &spec);
+    let row = do_serialize(
+        TestRowWithSerializeWithAndEnforceOrder {
+            a: "Ala ma kota".to_owned(),
+            timeout: std::time::Duration::from_millis(1500),
+        },
+        &spec,
+    );
+    assert_eq!(reference, row);
+
+    // Errors of the custom function are reported like the ones of `SerializeValue` impls
+    let spec = [
+        col("timeout_ms", ColumnType::Native(NativeType::Text)),
+        col("a", ColumnType::Native(NativeType::Text)),
+    ];
+    let err = do_serialize_err(
+        TestRowWithSerializeWith {
+            a: "Ala ma kota".to_owned(),
+            timeout: std::time::Duration::from_millis(1500),
+        },
+        &spec,
+    );
+    let err = err.0.downcast_ref::<BuiltinSerializationError>().unwrap();
+    assert_matches!(
+        &err.kind,
+        BuiltinSerializationErrorKind::ColumnSerializationFailed { name, .. } if name == "timeout_ms"
+    );
+}
+
 #[test]
 fn test_row_serialization_with_boxed_tuple() {
     let spec = [
@@ -18,7 +18,8 @@ use crate::frame::response::result::{CollectionType, ColumnType, NativeType};
 use crate::frame::types::{unsigned_vint_encode, vint_encode};
 use crate::value::{
     Counter, CqlDate, CqlDecimal, CqlDecimalBorrowed, CqlDuration, CqlTime, CqlTimestamp,
-    CqlTimeuuid, CqlValue, CqlVarint, CqlVarintBorrowed, Emptiable, MaybeEmpty, MaybeUnset, Unset,
+    CqlTimeuuid, CqlUdt, CqlValue, CqlVarint, CqlVarintBorrowed, Emptiable, MaybeEmpty, MaybeUnset,
+    Unset,
 };
 
 #[cfg(feature = "chrono-04")]
@@ -610,6 +611,17 @@ impl SerializeValue for CqlValue {
     }
 }
 
+impl SerializeValue for CqlUdt {
+    fn serialize<'b>(
+        &self,
+        typ: &ColumnType,
+        writer: CellWriter<'b>,
+    ) -> Result<WrittenCellProof<'b>, SerializationError> {
+        serialize_udt(typ, &self.keyspace, &self.name, &self.fields, writer)
+            .map_err(fix_rust_name_in_err::<Self>)
+    }
+}
+
 fn serialize_cql_value<'b>(
     value: &CqlValue,
     typ: &ColumnType,
@@ -1119,7 +1131,7 @@ pub struct BuiltinTypeCheckError {
     pub kind: BuiltinTypeCheckErrorKind,
 }
 
-fn mk_typck_err<T: ?Sized>(
+pub(crate) fn mk_typck_err<T: ?Sized>(
     got: &ColumnType,
     kind: impl Into<BuiltinTypeCheckErrorKind>,
 ) -> SerializationError {
@@ -1669,6 +1681,71 @@ mod doctests {
     /// }
     /// ```
     fn _test_udt_default_when_null_is_accepted() {}
+
+    /// ```compile_fail
+    ///
+    /// #[derive(scylla_macros::SerializeValue)]
+    /// #[scylla(crate = scylla_cql)]
+    /// enum TestEnum {
+    ///     A,
+    ///     B(i32),
+    /// }
+    /// ```
+    fn _test_enum_bad_mixed_variants() {}
+
+    /// ```compile_fail
+    ///
+    /// #[derive(scylla_macros::SerializeValue)]
+    /// #[scylla(crate = scylla_cql)]
+    /// enum TestEnum {
+    ///     A { a: i32 },
+    /// }
+    /// ```
+    fn _test_enum_bad_struct_variant() {}
+
+    /// ```compile_fail
+    ///
+    /// #[derive(scylla_macros::SerializeValue)]
+    /// #[scylla(crate = scylla_cql, repr = "text")]
+    /// enum TestEnum {
+    ///     A(i32),
+    ///     B(String),
+    /// }
+    /// ```
+    fn _test_enum_bad_attributes_repr_on_newtype_variants() {}
+
+    /// ```compile_fail
+    ///
+    /// #[derive(scylla_macros::SerializeValue)]
+    /// #[scylla(crate = scylla_cql, repr = "int")]
+    /// enum TestEnum {
+    ///     #[scylla(rename = "a")]
+    ///     A,
+    /// }
+    /// ```
+    fn _test_enum_bad_attributes_rename_with_int_repr() {}
+
+    /// ```compile_fail
+    ///
+    /// #[derive(scylla_macros::SerializeValue)]
+    /// #[scylla(crate = scylla_cql)]
+    /// enum TestEnum {
+    ///     #[scylla(rename = "B")]
+    ///     A,
+    ///     B,
+    /// }
+    /// ```
+    fn _test_enum_bad_attributes_rename_collision() {}
+
+    /// ```
+    /// #[derive(scylla_macros::SerializeValue)]
+    /// #[scylla(crate = scylla_cql, repr = "int")]
+    /// enum TestEnum {
+    ///     A = 1,
+    ///     B = 5,
+    /// }
+    /// ```
+    fn _test_enum_good_attributes_int_repr_with_explicit_discriminants() {}
 }
 
 #[cfg(test)]
@@ -9,7 +9,7 @@ use crate::serialize::value::{
 use crate::serialize::writers::WrittenCellProof;
 use crate::serialize::{CellWriter, SerializationError};
 use crate::value::{
-    Counter, CqlDate, CqlDuration, CqlTime, CqlTimestamp, CqlTimeuuid, CqlValue, CqlVarint,
+    Counter, CqlDate, CqlDuration, CqlTime, CqlTimestamp, CqlTimeuuid, CqlUdt, CqlValue, CqlVarint,
     Emptiable, MaybeEmpty, MaybeUnset, Unset,
 };
 
@@ -731,6 +731,69 @@ fn test_cql_value_udt_errors() {
     );
 }
 
+#[test]
+fn test_cql_udt_serialization() {
+    let typ = ColumnType::UserDefinedType {
+        frozen: false,
+        definition: Arc::new(UserDefinedType {
+            name: "udt".into(),
+            keyspace: "ks".into(),
+            field_types: vec![
+                ("a".into(), ColumnType::Native(NativeType::Int)),
+                ("b".into(), ColumnType::Native(NativeType::Text)),
+                ("c".into(), ColumnType::Native(NativeType::Int)),
+            ],
+        }),
+    };
+
+    // Fields missing from the value are sent as nulls, regardless of their order.
+    let udt = CqlUdt::new("ks", "udt")
+        .with_field("c", Some(CqlValue::Int(789)))
+        .with_field("a", Some(CqlValue::Int(123)));
+    let reference = CqlValue::UserDefinedType {
+        keyspace: "ks".to_string(),
+        name: "udt".to_string(),
+        fields: vec![
+            ("a".to_string(), Some(CqlValue::Int(123))),
+            ("b".to_string(), None),
+            ("c".to_string(), Some(CqlValue::Int(789))),
+        ],
+    };
+    assert_eq!(do_serialize(&udt, &typ), do_serialize(&reference, &typ));
+
+    // Conversions from and to CqlValue
+    let udt = CqlUdt::try_from(reference.clone()).unwrap();
+    assert_eq!(udt.field("a"), Some(&CqlValue::Int(123)));
+    assert_eq!(CqlValue::from(udt), reference);
+    assert_eq!(
+        CqlUdt::try_from(CqlValue::Int(123)),
+        Err(CqlValue::Int(123))
+    );
+
+    // Field not present in the CQL type
+    let udt = CqlUdt::new("ks", "udt").with_field("d", Some(CqlValue::Int(123)));
+    let err = do_serialize_err(udt, &typ);
+    let typeck_err = get_typeck_err(&err);
+    assert_eq!(typeck_err.rust_name, std::any::type_name::<CqlUdt>());
+    assert_matches!(
+        &typeck_err.kind,
+        BuiltinTypeCheckErrorKind::UdtError(UdtTypeCheckErrorKind::NoSuchFieldInUdt { field_name })
+            if field_name == "d"
+    );
+
+    // Not a UDT
+    let err = do_serialize_err(
+        CqlUdt::new("ks", "udt"),
+        &ColumnType::Native(NativeType::Int),
+    );
+    let typeck_err = get_typeck_err(&err);
+    assert_eq!(typeck_err.rust_name, std::any::type_name::<CqlUdt>());
+    assert_matches!(
+        typeck_err.kind,
+        BuiltinTypeCheckErrorKind::UdtError(UdtTypeCheckErrorKind::NotUdt)
+    );
+}
+
 // Do not remove. It's not used in tests but we keep it here to check that
 // we properly ignore warnings about unused variables, unnecessary `mut`s
 // etc. that usually pop up when generating code for empty structs.
@@ -1417,6 +1480,58 @@ fn test_udt_serialization_with_skipped_name_checks() {
     assert_eq!(reference, udt);
 }
 
+#[test]
+fn test_udt_serialization_with_struct_level_allow_missing() {
+    #[derive(SerializeValue, Debug)]
+    #[scylla(crate = crate, allow_missing)]
+    struct TestUdtWithAllowMissing {
+        a: String,
+        b: i32,
+    }
+
+    #[derive(SerializeValue, Debug)]
+    #[scylla(crate = crate, flavor = "enforce_order", allow_missing)]
+    struct TestUdtWithAllowMissingAndEnforceOrder {
+        a: String,
+        b: i32,
+    }
+
+    // The database type doesn't have the field 'b' yet.
+    let typ = ColumnType::UserDefinedType {
+        frozen: false,
+        definition: Arc::new(UserDefinedType {
+            name: "typ".into(),
+            keyspace: "ks".into(),
+            field_types: vec![("a".into(), ColumnType::Native(NativeType::Text))],
+        }),
+    };
+
+    let mut reference = Vec::new();
+    // Total length of the struct is 15
+    reference.extend_from_slice(&15i32.to_be_bytes());
+    // Field 'a'
+    reference.extend_from_slice(&("Ala ma kota".len() as i32).to_be_bytes());
+    reference.extend_from_slice("Ala ma kota".as_bytes());
+
+    let udt = do_serialize(
+        TestUdtWithAllowMissing {
+            a: "Ala ma kota".to_owned(),
+            b: 42,
+        },
+        &typ,
+    );
+    assert_eq!(reference, udt);
+
+    let udt = do_serialize(
+        TestUdtWithAllowMissingAndEnforceOrder {
+            a: "Ala ma kota".to_owned(),
+            b: 42,
+        },
+        &typ,
+    );
+    assert_eq!(reference, udt);
+}
+
 #[derive(SerializeValue, Debug, PartialEq, Eq, Default)]
 #[scylla(crate = crate, forbid_excess_udt_fields)]
 struct TestStrictUdtWithFieldSorting {
@@ -1588,6 +1703,73 @@ fn test_row_serialization_with_skipped_field() {
     assert_eq!(reference, row);
 }
 
+fn serialize_duration_as_millis<'b>(
+    duration: &std::time::Duration,
+    typ: &ColumnType,
+    writer: CellWriter<'b>,
+) -> Result<WrittenCellProof<'b>, SerializationError> {
+    (duration.as_millis() as i64).serialize(typ, writer)
+}
+
+#[test]
+fn test_udt_serialization_with_serialize_with() {
+    #[derive(SerializeValue, Debug)]
+    #[scylla(crate = crate)]
+    struct TestUdtWithSerializeWith {
+        a: String,
+        #[scylla(rename = "timeout_ms", serialize_with = serialize_duration_as_millis)]
+        timeout: std::time::Duration,
+    }
+
+    #[derive(SerializeValue, Debug)]
+    #[scylla(crate = crate, flavor = "enforce_order")]
+    struct TestUdtWithSerializeWithAndEnforceOrder {
+        a: String,
+        #[scylla(rename = "timeout_ms", serialize_with = serialize_duration_as_millis)]
+        timeout: std::time::Duration,
+    }
+
+    let typ = ColumnType::UserDefinedType {
+        frozen: false,
+        definition: Arc::new(UserDefinedType {
+            name: "typ".into(),
+            keyspace: "ks".into(),
+            field_types: vec![
+                ("a".into(), ColumnType::Native(NativeType::Text)),
+                ("timeout_ms".into(), ColumnType::Native(NativeType::BigInt)),
+            ],
+        }),
+    };
+
+    let mut reference = Vec::new();
+    // Total length of the struct is 27
+    reference.extend_from_slice(&27i32.to_be_bytes());
+    // Field 'a'
+    reference.extend_from_slice(&("Ala ma kota".len() as i32).to_be_bytes());
+    reference.extend_from_slice("Ala ma kota".as_bytes());
+    // Field 'timeout_ms'
+    reference.extend_from_slice(&8i32.to_be_bytes());
+    reference.extend_from_slice(&1500i64.to_be_bytes());
+
+    let udt = do_serialize(
+        TestUdtWithSerializeWith {
+            a: "Ala ma kota".to_owned(),
+            timeout: std::time::Duration::from_millis(1500),
+        },
+        &typ,
+    );
+    assert_eq!(reference, udt);
+
+    let udt = do_serialize(
+        TestUdtWithSerializeWithAndEnforceOrder {
+            a: "Ala ma kota".to_owned(),
+            timeout: std::time::Duration::from_millis(1500),
+        },
+        &typ,
+    );
+    assert_eq!(reference, udt);
+}
+
 #[test]
 fn test_udt_with_non_rust_ident() {
     #[derive(SerializeValue, Debug)]
@@ -1619,6 +1801,95 @@ fn test_udt_with_non_rust_ident() {
     assert_eq!(reference, udt);
 }
 
+#[test]
+fn test_enum_serialization_as_text() {
+    #[derive(SerializeValue, Debug)]
+    #[scylla(crate = crate)]
+    enum Status {
+        Active,
+        #[scylla(rename = "on_hold")]
+        OnHold,
+    }
+
+    let text = ColumnType::Native(NativeType::Text);
+    let ascii = ColumnType::Native(NativeType::Ascii);
+    let mut reference = Vec::new();
+    reference.extend_from_slice(&6i32.to_be_bytes());
+    reference.extend_from_slice(b"Active");
+    assert_eq!(do_serialize(Status::Active, &text), reference);
+    assert_eq!(do_serialize(Status::Active, &ascii), reference);
+
+    let mut reference = Vec::new();
+    reference.extend_from_slice(&7i32.to_be_bytes());
+    reference.extend_from_slice(b"on_hold");
+    assert_eq!(do_serialize(Status::OnHold, &text), reference);
+
+    let err = do_serialize_err(Status::Active, &ColumnType::Native(NativeType::Int));
+    let err = get_typeck_err(&err);
+    assert_eq!(err.rust_name, std::any::type_name::<Status>());
+    assert_matches!(
+        err.kind,
+        BuiltinTypeCheckErrorKind::MismatchedType {
+            expected: &[
+                ColumnType::Native(NativeType::Ascii),
+                ColumnType::Native(NativeType::Text)
+            ],
+        }
+    );
+}
+
+#[test]
+fn test_enum_serialization_as_int() {
+    #[derive(SerializeValue, Debug)]
+    #[scylla(crate = crate, repr = "int")]
+    enum Priority {
+        Low = 1,
+        High = 10,
+    }
+
+    let typ = ColumnType::Native(NativeType::Int);
+    let mut reference = Vec::new();
+    reference.extend_from_slice(&4i32.to_be_bytes());
+    reference.extend_from_slice(&10i32.to_be_bytes());
+    assert_eq!(do_serialize(Priority::High, &typ), reference);
+
+    let err = do_serialize_err(Priority::Low, &ColumnType::Native(NativeType::Text));
+    let err = get_typeck_err(&err);
+    assert_eq!(err.rust_name, std::any::type_name::<Priority>());
+    assert_matches!(
+        err.kind,
+        BuiltinTypeCheckErrorKind::MismatchedType {
+            expected: &[ColumnType::Native(NativeType::Int)],
+        }
+    );
+}
+
+#[test]
+fn test_enum_serialization_with_newtype_variants() {
+    #[derive(SerializeValue, Debug)]
+    #[scylla(crate = crate)]
+    enum IntOrText {
+        Int(i32),
+        Text(String),
+    }
+
+    let int = ColumnType::Native(NativeType::Int);
+    let text = ColumnType::Native(NativeType::Text);
+    assert_eq!(
+        do_serialize(IntOrText::Int(42), &int),
+        do_serialize(42i32, &int)
+    );
+    assert_eq!(
+        do_serialize(IntOrText::Text("foo".to_owned()), &text),
+        do_serialize("foo", &text)
+    );
+
+    // The inner value is type checked against the CQL type.
+    let err = do_serialize_err(IntOrText::Int(42), &text);
+    let err = get_typeck_err(&err);
+    assert_eq!(err.rust_name, std::any::type_name::<i32>());
+}
+
 // Tests migrated from old frame/value_tests.rs file
 
 fn compute_hash<T: Hash>(x: &T) -> u64 {
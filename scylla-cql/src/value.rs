@@ -11,7 +11,7 @@ use crate::deserialize::DeserializationError;
 use crate::deserialize::FrameSlice;
 use crate::deserialize::value::DeserializeValue;
 use crate::deserialize::value::{
-    BuiltinDeserializationErrorKind, MapIterator, UdtIterator, VectorIterator, mk_deser_err,
+    BuiltinDeserializationErrorKind, MapIterator, VectorIterator, mk_deser_err,
 };
 use crate::frame::response::result::{CollectionType, ColumnType};
 use crate::frame::types;
@@ -1359,6 +1359,94 @@ impl std::fmt::Display for CqlValue {
     }
 }
 
+/// A user-defined type (UDT) value whose structure is only known at runtime.
+///
+/// Holds the values of the UDT fields keyed by their names, together with
+/// the keyspace and the name of the UDT. It can be both serialized and deserialized,
+/// so it is useful when the UDT cannot be described by a Rust struct
+/// deriving `SerializeValue` / `DeserializeValue`, e.g. in generic tooling.
+///
+/// When serializing, fields of the UDT definition which are not present here
+/// are sent as nulls, and fields which are not present in the UDT definition
+/// cause an error.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CqlUdt {
+    /// Keyspace the type belongs to.
+    pub keyspace: String,
+    /// Name of the user-defined type.
+    pub name: String,
+    /// Fields of the user-defined type - (name, value) pairs.
+    pub fields: Vec<(String, Option<CqlValue>)>,
+}
+
+impl CqlUdt {
+    /// Creates a UDT value of the given type with no fields set.
+    pub fn new(keyspace: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            keyspace: keyspace.into(),
+            name: name.into(),
+            fields: Vec::new(),
+        }
+    }
+
+    /// Returns the value of the field with the given name,
+    /// or `None` if the field is absent or null.
+    pub fn field(&self, field_name: &str) -> Option<&CqlValue> {
+        self.fields
+            .iter()
+            .find(|(name, _)| name == field_name)
+            .and_then(|(_, value)| value.as_ref())
+    }
+
+    /// Sets the value of the field with the given name, replacing the previous value
+    /// if the field was already present. `None` represents null.
+    pub fn set_field(&mut self, field_name: impl Into<String>, value: Option<CqlValue>) {
+        let field_name = field_name.into();
+        match self.fields.iter_mut().find(|(name, _)| *name == field_name) {
+            Some((_, old_value)) => *old_value = value,
+            None => self.fields.push((field_name, value)),
+        }
+    }
+
+    /// Sets the value of the field with the given name and returns the UDT value.
+    /// Convenient for building the UDT value in a single expression.
+    pub fn with_field(mut self, field_name: impl Into<String>, value: Option<CqlValue>) -> Self {
+        self.set_field(field_name, value);
+        self
+    }
+}
+
+impl From<CqlUdt> for CqlValue {
+    fn from(udt: CqlUdt) -> Self {
+        CqlValue::UserDefinedType {
+            keyspace: udt.keyspace,
+            name: udt.name,
+            fields: udt.fields,
+        }
+    }
+}
+
+impl TryFrom<CqlValue> for CqlUdt {
+    type Error = CqlValue;
+
+    /// Converts a `CqlValue` of the UDT type to `CqlUdt`.
+    /// Returns the original value if it is of another type.
+    fn try_from(value: CqlValue) -> Result<Self, Self::Error> {
+        match value {
+            CqlValue::UserDefinedType {
+                keyspace,
+                name,
+                fields,
+            } => Ok(CqlUdt {
+                keyspace,
+                name,
+                fields,
+            }),
+            other => Err(other),
+        }
+    }
+}
+
 /// Deserializes any CQL value from a byte slice according to the provided CQL type.
 pub fn deser_cql_value(
     typ: &ColumnType,
@@ -1489,25 +1577,7 @@ pub fn deser_cql_value(
             let v: Vec<CqlValue> = iter.collect::<StdResult<_, _>>()?;
             CqlValue::Vector(v)
         }
-        UserDefinedType {
-            definition: udt, ..
-        } => {
-            let iter = UdtIterator::deserialize(typ, v)?;
-            let fields: Vec<(String, Option<CqlValue>)> = iter
-                .map(|((col_name, col_type), res)| {
-                    res.and_then(|v| {
-                        let val = Option::<CqlValue>::deserialize(col_type, v.flatten())?;
-                        Ok((col_name.clone().into_owned(), val))
-                    })
-                })
-                .collect::<StdResult<_, _>>()?;
-
-            CqlValue::UserDefinedType {
-                keyspace: udt.keyspace.clone().into_owned(),
-                name: udt.name.clone().into_owned(),
-                fields,
-            }
-        }
+        UserDefinedType { .. } => CqlUdt::deserialize(typ, v)?.into(),
         Tuple(type_names) => {
             let t = type_names
                 .iter()
@@ -0,0 +1,251 @@
+use std::collections::HashMap;
+
+use darling::FromAttributes;
+use syn::ext::IdentExt;
+use syn::parse_quote;
+
+use crate::EnumRepr;
+use crate::parser::EnumShape;
+
+use super::DeserializeCommonStructAttrs;
+
+#[derive(FromAttributes)]
+#[darling(attributes(scylla))]
+struct EnumAttrs {
+    #[darling(rename = "crate")]
+    crate_path: Option<syn::Path>,
+
+    // How variants of a fieldless enum are represented in the database.
+    // Not allowed for enums whose variants have fields.
+    repr: Option<EnumRepr>,
+}
+
+impl DeserializeCommonStructAttrs for EnumAttrs {
+    fn crate_path(&self) -> Option<&syn::Path> {
+        self.crate_path.as_ref()
+    }
+}
+
+#[derive(FromAttributes)]
+#[darling(attributes(scylla))]
+struct VariantAttrs {
+    // If set, then the variant of a fieldless enum is represented by this
+    // name instead of the Rust variant name. Only allowed with the `text` repr.
+    rename: Option<String>,
+}
+
+struct Variant<'a> {
+    variant: &'a syn::Variant,
+    attrs: VariantAttrs,
+}
+
+impl Variant<'_> {
+    // The name representing this variant in the database
+    fn name(&self) -> String {
+        match &self.attrs.rename {
+            Some(name) => name.clone(),
+            None => self.variant.ident.unraw().to_string(),
+        }
+    }
+}
+
+pub(super) fn deserialize_value_enum_derive(
+    input: &syn::DeriveInput,
+    data: &syn::DataEnum,
+) -> Result<syn::ItemImpl, syn::Error> {
+    let shape = crate::parser::parse_enum_shape(data, "DeserializeValue")?;
+    let attrs = EnumAttrs::from_attributes(&input.attrs)?;
+    let variants = data
+        .variants
+        .iter()
+        .map(|variant| {
+            VariantAttrs::from_attributes(&variant.attrs).map(|attrs| Variant { variant, attrs })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    validate(&input.ident, &shape, &attrs, &variants)?;
+
+    let macro_internal = attrs.macro_internal_path();
+    let (frame_lifetime, metadata_lifetime) =
+        super::generate_pair_of_unique_lifetimes_for_impl(&input.generics);
+    let variant_idents = variants
+        .iter()
+        .map(|v| &v.variant.ident)
+        .collect::<Vec<_>>();
+
+    let (type_check, deserialize): (syn::Block, syn::Block) = match (
+        shape,
+        attrs.repr.unwrap_or_default(),
+    ) {
+        (EnumShape::Fieldless, EnumRepr::Text) => {
+            let variant_names = variants.iter().map(Variant::name);
+            (
+                parse_quote! {{
+                    #macro_internal::de::value::type_check_enum_as_text::<Self>(typ)
+                }},
+                parse_quote! {{
+                    let name = #macro_internal::de::value::deserialize_enum_as_text::<Self>(typ, v)?;
+                    match name {
+                        #(#variant_names => ::std::result::Result::Ok(Self::#variant_idents),)*
+                        _ => ::std::result::Result::Err(#macro_internal::mk_value_deser_err::<Self>(
+                            typ,
+                            #macro_internal::EnumDeserializationErrorKind::UnknownVariantName {
+                                name: <_ as ::std::borrow::ToOwned>::to_owned(name),
+                            },
+                        )),
+                    }
+                }},
+            )
+        }
+        (EnumShape::Fieldless, EnumRepr::Int) => (
+            parse_quote! {{
+                #macro_internal::de::value::type_check_enum_as_int::<Self>(typ)
+            }},
+            parse_quote! {{
+                let discriminant = #macro_internal::de::value::deserialize_enum_as_int::<Self>(typ, v)?;
+                #(if discriminant == Self::#variant_idents as i32 {
+                    return ::std::result::Result::Ok(Self::#variant_idents);
+                })*
+                ::std::result::Result::Err(#macro_internal::mk_value_deser_err::<Self>(
+                    typ,
+                    #macro_internal::EnumDeserializationErrorKind::UnknownDiscriminant {
+                        discriminant,
+                    },
+                ))
+            }},
+        ),
+        (EnumShape::Newtype, _) => {
+            // The first variant whose type type checks against the CQL type is chosen.
+            let variant_types = variants
+                .iter()
+                .map(|v| &v.variant.fields.iter().next().unwrap().ty)
+                .collect::<Vec<_>>();
+            let variant_names = variants.iter().map(Variant::name);
+            let deserialize_value_trait: syn::Path = parse_quote! {
+                #macro_internal::DeserializeValue<#frame_lifetime, #metadata_lifetime>
+            };
+            let variant_indices = (0..variants.len()).collect::<Vec<_>>();
+            (
+                parse_quote! {{
+                    let mut variant_errors = ::std::vec::Vec::new();
+                    #(match <#variant_types as #deserialize_value_trait>::type_check(typ) {
+                        ::std::result::Result::Ok(()) => {
+                            #macro_internal::de::value::remember_enum_variant::<Self>(typ, #variant_indices);
+                            return ::std::result::Result::Ok(());
+                        }
+                        ::std::result::Result::Err(err) => variant_errors.push((#variant_names, err)),
+                    })*
+                    ::std::result::Result::Err(#macro_internal::mk_value_typck_err::<Self>(
+                        typ,
+                        #macro_internal::EnumTypeCheckErrorKind::NoMatchingVariant {
+                            variant_errors,
+                        },
+                    ))
+                }},
+                // The variant is the one chosen by `type_check`. It is chosen again only
+                // if `type_check` was called on another thread.
+                parse_quote! {{
+                    let variant = #macro_internal::de::value::chosen_enum_variant::<Self>(typ, || {
+                        #(if <#variant_types as #deserialize_value_trait>::type_check(typ).is_ok() {
+                            return ::std::option::Option::Some(#variant_indices);
+                        })*
+                        ::std::option::Option::None
+                    });
+                    match variant {
+                        #(::std::option::Option::Some(#variant_indices) => {
+                            <#variant_types as #deserialize_value_trait>::deserialize(typ, v)
+                                .map(Self::#variant_idents)
+                        })*
+                        _ => ::std::result::Result::Err(#macro_internal::mk_value_deser_err::<Self>(
+                            typ,
+                            #macro_internal::EnumDeserializationErrorKind::NoMatchingVariant,
+                        )),
+                    }
+                }},
+            )
+        }
+    };
+
+    let enum_name = &input.ident;
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let impl_generics = &input.generics.params;
+    let predicates = super::generate_lifetime_constraints_for_impl(
+        &input.generics,
+        parse_quote!(#macro_internal::DeserializeValue),
+        &frame_lifetime,
+    );
+
+    Ok(parse_quote! {
+        #[automatically_derived]
+        impl<#frame_lifetime, #metadata_lifetime, #impl_generics>
+            #macro_internal::DeserializeValue<#frame_lifetime, #metadata_lifetime> for #enum_name #ty_generics
+        where #(#predicates),*
+        {
+            fn type_check(
+                typ: &#macro_internal::ColumnType,
+            ) -> ::std::result::Result<(), #macro_internal::TypeCheckError> #type_check
+
+            fn deserialize(
+                typ: &#metadata_lifetime #macro_internal::ColumnType<#metadata_lifetime>,
+                v: ::std::option::Option<#macro_internal::FrameSlice<#frame_lifetime>>,
+            ) -> ::std::result::Result<Self, #macro_internal::DeserializationError> #deserialize
+        }
+    })
+}
+
+fn validate(
+    enum_ident: &syn::Ident,
+    shape: &EnumShape,
+    attrs: &EnumAttrs,
+    variants: &[Variant],
+) -> Result<(), darling::Error> {
+    let mut errors = darling::Error::accumulator();
+
+    match shape {
+        EnumShape::Newtype => {
+            if attrs.repr.is_some() {
+                errors.push(
+                    darling::Error::custom(
+                        "the `repr` attribute is only allowed on enums whose variants have no fields",
+                    )
+                    .with_span(enum_ident),
+                );
+            }
+            for variant in variants.iter().filter(|v| v.attrs.rename.is_some()) {
+                errors.push(
+                    darling::Error::custom(
+                        "the `rename` annotations are only allowed on enums whose variants have no fields",
+                    )
+                    .with_span(&variant.variant.ident),
+                );
+            }
+        }
+        EnumShape::Fieldless if attrs.repr == Some(EnumRepr::Int) => {
+            for variant in variants.iter().filter(|v| v.attrs.rename.is_some()) {
+                errors.push(
+                    darling::Error::custom(
+                        "the `rename` annotations don't make sense with `repr = \"int\"` attribute",
+                    )
+                    .with_span(&variant.variant.ident),
+                );
+            }
+        }
+        EnumShape::Fieldless => {
+            // Detect name collisions caused by `rename`.
+            let mut used_names = HashMap::<String, &Variant>::new();
+            for variant in variants {
+                let name = variant.name();
+                if let Some(other_variant) = used_names.get(&name) {
+                    let other_variant_ident = &other_variant.variant.ident;
+                    let msg = format!(
+                        "the name `{name}` used by this enum variant is already used by variant `{other_variant_ident}`"
+                    );
+                    errors.push(darling::Error::custom(msg).with_span(&variant.variant.ident));
+                } else {
+                    used_names.insert(name, variant);
+                }
+            }
+        }
+    }
+
+    errors.finish()
+}
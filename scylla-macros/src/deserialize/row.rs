@@ -51,13 +51,29 @@ struct Field {
     #[darling(default)]
     default_when_null: bool,
 
+    // If true, then - if this column is missing from the row - it will be
+    // initialized to Default::default().
+    #[darling(default)]
+    allow_missing: bool,
+
+    // If true, then the field is deserialized from the columns of its own fields,
+    // as if they were fields of the parent struct. The field's type must derive
+    // DeserializeRow with the default (match_by_name) flavor.
+    #[darling(default)]
+    flatten: bool,
+
+    // Used for serialization only. Ignored in deserialization.
+    #[darling(default)]
+    #[darling(rename = "serialize_with")]
+    _serialize_with: Option<syn::Path>,
+
     ident: Option<syn::Ident>,
     ty: syn::Type,
 }
 
 impl DeserializeCommonFieldAttrs for Field {
     fn needs_default(&self) -> bool {
-        self.skip || self.default_when_null
+        self.skip || self.default_when_null || self.allow_missing
     }
 
     fn deserialize_target(&self) -> &syn::Type {
@@ -68,7 +84,7 @@ impl DeserializeCommonFieldAttrs for Field {
 // derive(DeserializeRow) for the new DeserializeRow trait
 pub(crate) fn deserialize_row_derive(
     tokens_input: proc_macro::TokenStream,
-) -> Result<syn::ItemImpl, syn::Error> {
+) -> Result<proc_macro2::TokenStream, syn::Error> {
     let input = syn::parse(tokens_input)?;
 
     let implemented_trait: syn::Path = parse_quote! { DeserializeRow };
@@ -88,13 +104,47 @@ pub(crate) fn deserialize_row_derive(
         s.generate_type_check_method().into(),
         s.generate_deserialize_method().into(),
     ];
-
-    Ok(s.generate_impl(implemented_trait, items))
+    let deserialize_row_impl = s.generate_impl(implemented_trait, items);
+
+    // Structs deserialized by name can be flattened into other structs.
+    let deserialize_row_by_name_impl = (s.attrs.flavor == Flavor::MatchByName).then(|| {
+        let items = ByNameGenerator(&s).generate();
+        s.generate_impl(parse_quote! { DeserializeRowByName }, items)
+    });
+
+    Ok(quote::quote! {
+        #deserialize_row_impl
+        #deserialize_row_by_name_impl
+    })
 }
 
 fn validate_attrs(attrs: &StructAttrs, fields: &[Field]) -> Result<(), darling::Error> {
     let mut errors = darling::Error::accumulator();
 
+    for field in fields {
+        if field.flatten && field.rename.is_some() {
+            let err = darling::Error::custom(
+                "`rename` and `flatten` annotations do not make sense together",
+            )
+            .with_span(&field.ident);
+            errors.push(err);
+        }
+        if field.flatten && (field.default_when_null || field.allow_missing) {
+            let err = darling::Error::custom(
+                "`default_when_null` and `allow_missing` annotations do not make sense with `flatten`",
+            )
+            .with_span(&field.ident);
+            errors.push(err);
+        }
+        if attrs.flavor == Flavor::EnforceOrder && (field.flatten || field.allow_missing) {
+            let err = darling::Error::custom(
+                "`flatten` and `allow_missing` annotations are only supported by the `match_by_name` flavor",
+            )
+            .with_span(&field.ident);
+            errors.push(err);
+        }
+    }
+
     if attrs.skip_name_checks {
         // Skipping name checks is only available in enforce_order mode
         if attrs.flavor != Flavor::EnforceOrder {
@@ -116,7 +166,7 @@ fn validate_attrs(attrs: &StructAttrs, fields: &[Field]) -> Result<(), darling::
     } else {
         // Detect name collisions caused by `rename`.
         let mut used_names = HashMap::<String, &Field>::new();
-        for field in fields {
+        for field in fields.iter().filter(|f| !f.flatten) {
             let column_name = field.column_name();
             if let Some(other_field) = used_names.get(&column_name) {
                 let other_field_ident = other_field.ident.as_ref().unwrap();
@@ -137,7 +187,7 @@ fn validate_attrs(attrs: &StructAttrs, fields: &[Field]) -> Result<(), darling::
 impl Field {
     // Returns whether this field is mandatory for deserialization.
     fn is_required(&self) -> bool {
-        !self.skip
+        !self.skip && !self.allow_missing
     }
 
     // The name of the column corresponding to this Rust struct field
@@ -159,14 +209,14 @@ type StructDesc = super::StructDescForDeserialize<StructAttrs, Field>;
 impl StructDesc {
     fn generate_type_check_method(&self) -> syn::ImplItemFn {
         match self.attrs.flavor {
-            Flavor::MatchByName => TypeCheckUnorderedGenerator(self).generate(),
+            Flavor::MatchByName => ByNameGenerator(self).generate_type_check(),
             Flavor::EnforceOrder => TypeCheckAssumeOrderGenerator(self).generate(),
         }
     }
 
     fn generate_deserialize_method(&self) -> syn::ImplItemFn {
         match self.attrs.flavor {
-            Flavor::MatchByName => DeserializeUnorderedGenerator(self).generate(),
+            Flavor::MatchByName => ByNameGenerator(self).generate_deserialize(),
             Flavor::EnforceOrder => DeserializeAssumeOrderGenerator(self).generate(),
         }
     }
@@ -367,303 +417,346 @@ impl DeserializeAssumeOrderGenerator<'_> {
     }
 }
 
-struct TypeCheckUnorderedGenerator<'sd>(&'sd StructDesc);
-
-impl TypeCheckUnorderedGenerator<'_> {
-    // An identifier for a bool variable that represents whether given
-    // field was already visited during type check
-    fn visited_flag_variable(field: &Field) -> syn::Ident {
-        quote::format_ident!("visited_{}", field.ident.as_ref().unwrap().unraw())
+// Generates the implementation of `DeserializeRow` in terms of `DeserializeRowByName`,
+// and the implementation of `DeserializeRowByName` itself.
+//
+// The partial state of the type check and of the deserialization is kept in nested tuples
+// (a cons list), with one element per non-skipped field: `(f1, (f2, (f3, ())))`.
+// For regular fields, the elements are a "visited" flag and an `Option` with the value,
+// respectively. For flattened fields, they are the partial states of the field's type.
+struct ByNameGenerator<'sd>(&'sd StructDesc);
+
+impl ByNameGenerator<'_> {
+    fn by_name_trait(&self) -> syn::Path {
+        let macro_internal = self.0.struct_attrs().macro_internal_path();
+        let (frame_lifetime, metadata_lifetime) = self.0.constraint_lifetimes();
+        parse_quote!(#macro_internal::DeserializeRowByName<#frame_lifetime, #metadata_lifetime>)
     }
 
-    // Generates a declaration of a "visited" flag for the purpose of type check.
-    // We generate it even if the flag is not required in order to protect
-    // from fields appearing more than once
-    fn generate_visited_flag_decl(field: &Field) -> Option<syn::Stmt> {
-        (!field.skip).then(|| {
-            let visited_flag = Self::visited_flag_variable(field);
-            parse_quote! {
-                let mut #visited_flag = false;
-            }
-        })
+    fn nonskipped_fields(&self) -> impl Iterator<Item = &Field> {
+        self.0.fields().iter().filter(|f| !f.skip)
     }
 
-    // Generates code that, given variable `typ`, type-checks given field
-    fn generate_type_check(&self, field: &Field) -> Option<syn::Block> {
-        (!field.skip).then(|| {
-            let macro_internal = self.0.struct_attrs().macro_internal_path();
-            let (frame_lifetime, metadata_lifetime) = self.0.constraint_lifetimes();
-            let visited_flag = Self::visited_flag_variable(field);
-            let typ = field.deserialize_target();
-            let cql_name_literal = field.cql_name_literal();
-            let decrement_if_required: Option::<syn::Stmt> = field.is_required().then(|| parse_quote! {
-                remaining_required_fields -= 1;
-            });
-
-            let typ_expr: syn::Type = if field.default_when_null {
-                parse_quote! {
-                    ::std::option::Option<#typ>
-                }
-            } else {
-                parse_quote! {
-                    #typ
-                }
-            };
+    // The partial state of the field at given position in the cons list.
+    fn partial_element(position: usize) -> syn::Expr {
+        let mut expr: syn::Expr = parse_quote!(partial);
+        for _ in 0..position {
+            expr = parse_quote!(#expr.1);
+        }
+        parse_quote!(#expr.0)
+    }
 
-            parse_quote! {
-                {
-                    if !#visited_flag {
-                        <#typ_expr as #macro_internal::DeserializeValue<#frame_lifetime, #metadata_lifetime>>::type_check(spec.typ())
-                            .map_err(|err| {
-                                #macro_internal::mk_row_typck_err::<Self>(
-                                    column_types_iter(),
-                                    #macro_internal::DeserBuiltinRowTypeCheckErrorKind::ColumnTypeCheckFailed {
-                                        column_index,
-                                        column_name: <_ as ::std::borrow::ToOwned>::to_owned(#cql_name_literal),
-                                        err,
-                                    }
-                                )
-                            })?;
-                        #visited_flag = true;
-                        #decrement_if_required
-                    } else {
-                        return ::std::result::Result::Err(
-                            #macro_internal::mk_row_typck_err::<Self>(
-                                column_types_iter(),
-                                #macro_internal::DeserBuiltinRowTypeCheckErrorKind::DuplicatedColumn {
-                                    column_index,
-                                    column_name: #cql_name_literal,
-                                }
-                            )
-                        )
-                    }
-                }
-            }
-        })
+    fn partial_type(&self, element_type: impl Fn(&Field) -> syn::Type) -> syn::Type {
+        self.nonskipped_fields()
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+            .fold(parse_quote!(()), |rest, field| {
+                let element = element_type(field);
+                parse_quote!((#element, #rest))
+            })
     }
 
-    // Generates code that appends the flag name if it is missing.
-    // The generated code is used to construct a nice error message.
-    fn generate_append_name(field: &Field) -> Option<syn::Block> {
-        field.is_required().then(|| {
-            let visited_flag = Self::visited_flag_variable(field);
-            let cql_name_literal = field.cql_name_literal();
-            parse_quote! {
-                {
-                    if !#visited_flag {
-                        missing_fields.push(#cql_name_literal);
-                    }
-                }
-            }
-        })
+    // The type which the column of a regular field is deserialized as.
+    fn column_type(field: &Field) -> syn::Type {
+        let typ = field.deserialize_target();
+        if field.default_when_null {
+            parse_quote!(::std::option::Option<#typ>)
+        } else {
+            parse_quote!(#typ)
+        }
     }
 
-    fn generate(&self) -> syn::ImplItemFn {
+    fn generate_type_check(&self) -> syn::ImplItemFn {
         let macro_internal = self.0.struct_attrs().macro_internal_path();
 
-        let fields = self.0.fields();
-        let visited_field_declarations = fields.iter().flat_map(Self::generate_visited_flag_decl);
-        let type_check_blocks = fields.iter().flat_map(|f| self.generate_type_check(f));
-        let append_name_blocks = fields.iter().flat_map(Self::generate_append_name);
-        let nonskipped_field_names = fields
-            .iter()
-            .filter(|f| !f.skip)
-            .map(|f| f.cql_name_literal());
-        let field_count_lit = fields.iter().filter(|f| f.is_required()).count();
-
         parse_quote! {
             fn type_check(
                 specs: &[#macro_internal::ColumnSpec],
             ) -> ::std::result::Result<(), #macro_internal::TypeCheckError> {
-                // Counts down how many required fields are remaining
-                let mut remaining_required_fields: ::std::primitive::usize = #field_count_lit;
-
-                // For each required field, generate a "visited" boolean flag
-                #(#visited_field_declarations)*
-
-                let column_types_iter = || ::std::iter::Iterator::map(specs.iter(), |spec| ::std::clone::Clone::clone(spec.typ()).into_owned());
-
-                for (column_index, spec) in ::std::iter::Iterator::enumerate(specs.iter()) {
-                    // Pattern match on the name and verify that the type is correct.
-                    match spec.name() {
-                        #(#nonskipped_field_names => #type_check_blocks,)*
-                        _unknown => {
-                            return ::std::result::Result::Err(
-                                #macro_internal::mk_row_typck_err::<Self>(
-                                    column_types_iter(),
-                                    #macro_internal::DeserBuiltinRowTypeCheckErrorKind::ColumnWithUnknownName {
-                                        column_index,
-                                        column_name: <_ as ::std::borrow::ToOwned>::to_owned(spec.name())
-                                    }
-                                )
-                            )
-                        }
-                    }
-                }
+                #macro_internal::de::row::type_check_by_name::<Self>(specs)
+            }
+        }
+    }
 
-                if remaining_required_fields > 0 {
-                    // If there are some missing required fields, generate an error
-                    // which contains missing field names
-                    let mut missing_fields = ::std::vec::Vec::<&'static str>::with_capacity(remaining_required_fields);
-                    #(#append_name_blocks)*
-                    return ::std::result::Result::Err(
-                        #macro_internal::mk_row_typck_err::<Self>(
-                            column_types_iter(),
-                            #macro_internal::DeserBuiltinRowTypeCheckErrorKind::ValuesMissingForColumns {
-                                column_names: missing_fields
-                            }
-                        )
-                    )
-                }
+    fn generate_deserialize(&self) -> syn::ImplItemFn {
+        let macro_internal = self.0.struct_attrs().macro_internal_path();
+        let (frame_lifetime, metadata_lifetime) = self.0.constraint_lifetimes();
 
-                ::std::result::Result::Ok(())
+        parse_quote! {
+            fn deserialize(
+                row: #macro_internal::ColumnIterator<#frame_lifetime, #metadata_lifetime>,
+            ) -> ::std::result::Result<Self, #macro_internal::DeserializationError> {
+                #macro_internal::de::row::deserialize_by_name::<Self>(row)
             }
         }
     }
-}
-
-struct DeserializeUnorderedGenerator<'sd>(&'sd StructDesc);
 
-impl DeserializeUnorderedGenerator<'_> {
-    // An identifier for a variable that is meant to store the parsed variable
-    // before being ultimately moved to the struct on deserialize
-    fn deserialize_field_variable(field: &Field) -> syn::Ident {
-        quote::format_ident!("f_{}", field.ident.as_ref().unwrap().unraw())
+    fn generate(&self) -> Vec<syn::ImplItem> {
+        vec![
+            self.generate_type_check_partial().into(),
+            self.generate_partial().into(),
+            self.generate_type_check_column().into(),
+            self.generate_append_missing_columns().into(),
+            self.generate_deserialize_column().into(),
+            self.generate_finalize().into(),
+        ]
     }
 
-    // Generates an expression which produces a value ready to be put into a field
-    // of the target structure
-    fn generate_finalize_field(&self, field: &Field) -> syn::Expr {
-        if field.skip {
-            // Skipped fields are initialized with Default::default()
-            return parse_quote! {
-                ::std::default::Default::default()
-            };
+    fn generate_type_check_partial(&self) -> syn::ImplItemType {
+        let by_name_trait = self.by_name_trait();
+        let partial_type = self.partial_type(|field| {
+            if field.flatten {
+                let typ = &field.ty;
+                parse_quote!(<#typ as #by_name_trait>::TypeCheckPartial)
+            } else {
+                parse_quote!(bool)
+            }
+        });
+        parse_quote! {
+            type TypeCheckPartial = #partial_type;
         }
+    }
 
-        let deserialize_field = Self::deserialize_field_variable(field);
-        let cql_name_literal = field.cql_name_literal();
+    fn generate_partial(&self) -> syn::ImplItemType {
+        let by_name_trait = self.by_name_trait();
+        let partial_type = self.partial_type(|field| {
+            let typ = &field.ty;
+            if field.flatten {
+                parse_quote!(<#typ as #by_name_trait>::Partial)
+            } else {
+                parse_quote!(::std::option::Option<#typ>)
+            }
+        });
         parse_quote! {
-            #deserialize_field.unwrap_or_else(|| ::std::panic!(
-                "column {} missing in DB row - type check should have prevented this!",
-                #cql_name_literal
-            ))
+            type Partial = #partial_type;
         }
     }
 
-    // Generated code that performs deserialization when the raw field
-    // is being processed
-    fn generate_deserialization(&self, column_index: usize, field: &Field) -> syn::Expr {
-        assert!(!field.skip);
+    fn generate_type_check_column(&self) -> syn::ImplItemFn {
         let macro_internal = self.0.struct_attrs().macro_internal_path();
         let (frame_lifetime, metadata_lifetime) = self.0.constraint_lifetimes();
-        let deserialize_field = Self::deserialize_field_variable(field);
-        let deserializer = field.deserialize_target();
-
-        let deserialize_expr: syn::Expr = if field.default_when_null {
-            parse_quote! {
-                <::std::option::Option<#deserializer> as #macro_internal::DeserializeValue<#frame_lifetime, #metadata_lifetime>>::deserialize(col.spec.typ(), col.slice)
-                    .map_err(|err| {
-                        #macro_internal::mk_row_deser_err::<Self>(
-                            #macro_internal::BuiltinRowDeserializationErrorKind::ColumnDeserializationFailed {
-                                column_index: #column_index,
-                                column_name: <_ as ::std::borrow::ToOwned>::to_owned(col.spec.name()),
-                                err,
-                            }
-                        )
-                    })?
-                    .unwrap_or_default()
-            }
-        } else {
-            parse_quote! {
-                <#deserializer as #macro_internal::DeserializeValue<#frame_lifetime, #metadata_lifetime>>::deserialize(col.spec.typ(), col.slice)
-                    .map_err(|err| {
-                        #macro_internal::mk_row_deser_err::<Self>(
-                            #macro_internal::BuiltinRowDeserializationErrorKind::ColumnDeserializationFailed {
-                                column_index: #column_index,
-                                column_name: <_ as ::std::borrow::ToOwned>::to_owned(col.spec.name()),
-                                err,
-                            }
-                        )
-                    })?
+        let by_name_trait = self.by_name_trait();
+
+        let mut column_names = Vec::new();
+        let mut column_blocks = Vec::<syn::Block>::new();
+        let mut flattened_checks = Vec::<syn::Stmt>::new();
+        for (position, field) in self.nonskipped_fields().enumerate() {
+            let visited_flag = Self::partial_element(position);
+            if field.flatten {
+                let typ = &field.ty;
+                flattened_checks.push(parse_quote! {
+                    if <#typ as #by_name_trait>::type_check_column(&mut #visited_flag, column_index, spec, specs)? {
+                        return ::std::result::Result::Ok(true);
+                    }
+                });
+                continue;
             }
-        };
+
+            let cql_name_literal = field.cql_name_literal();
+            let column_type = Self::column_type(field);
+            column_names.push(cql_name_literal.clone());
+            column_blocks.push(parse_quote! {
+                {
+                    if #visited_flag {
+                        return ::std::result::Result::Err(
+                            #macro_internal::mk_row_typck_err::<Self>(
+                                column_types_iter(),
+                                #macro_internal::DeserBuiltinRowTypeCheckErrorKind::DuplicatedColumn {
+                                    column_index,
+                                    column_name: #cql_name_literal,
+                                }
+                            )
+                        );
+                    }
+                    <#column_type as #macro_internal::DeserializeValue<#frame_lifetime, #metadata_lifetime>>::type_check(spec.typ())
+                        .map_err(|err| {
+                            #macro_internal::mk_row_typck_err::<Self>(
+                                column_types_iter(),
+                                #macro_internal::DeserBuiltinRowTypeCheckErrorKind::ColumnTypeCheckFailed {
+                                    column_index,
+                                    column_name: <_ as ::std::borrow::ToOwned>::to_owned(#cql_name_literal),
+                                    err,
+                                }
+                            )
+                        })?;
+                    #visited_flag = true;
+                    ::std::result::Result::Ok(true)
+                }
+            });
+        }
 
         parse_quote! {
-            {
-                assert!(
-                    #deserialize_field.is_none(),
-                    "duplicated column {} - type check should have prevented this!",
-                    stringify!(#deserialize_field)
-                );
+            #[allow(unused_variables)]
+            fn type_check_column(
+                partial: &mut Self::TypeCheckPartial,
+                column_index: usize,
+                spec: &#macro_internal::ColumnSpec,
+                specs: &[#macro_internal::ColumnSpec],
+            ) -> ::std::result::Result<bool, #macro_internal::TypeCheckError> {
+                let column_types_iter = || ::std::iter::Iterator::map(specs.iter(), |spec| ::std::clone::Clone::clone(spec.typ()).into_owned());
 
-                #deserialize_field = ::std::option::Option::Some(
-                    #deserialize_expr
-                );
+                // Pattern match on the name and verify that the type is correct.
+                match spec.name() {
+                    #(#column_names => #column_blocks,)*
+                    // If not a column of a regular field, then check if any flattened field
+                    // has a column for it.
+                    _ => {
+                        #(#flattened_checks)*
+                        ::std::result::Result::Ok(false)
+                    }
+                }
             }
         }
     }
 
-    // Generate a declaration of a variable that temporarily keeps
-    // the deserialized value
-    fn generate_deserialize_field_decl(field: &Field) -> Option<syn::Stmt> {
-        (!field.skip).then(|| {
-            let deserialize_field = Self::deserialize_field_variable(field);
-            parse_quote! {
-                let mut #deserialize_field = ::std::option::Option::None;
+    fn generate_append_missing_columns(&self) -> syn::ImplItemFn {
+        let by_name_trait = self.by_name_trait();
+
+        let appends = self.nonskipped_fields().enumerate().filter_map(
+            |(position, field)| -> Option<syn::Stmt> {
+                let visited_flag = Self::partial_element(position);
+                if field.flatten {
+                    let typ = &field.ty;
+                    Some(parse_quote! {
+                        <#typ as #by_name_trait>::append_missing_columns(&#visited_flag, missing);
+                    })
+                } else {
+                    let cql_name_literal = field.cql_name_literal();
+                    field.is_required().then(|| {
+                        parse_quote! {
+                            if !#visited_flag {
+                                missing.push(#cql_name_literal);
+                            }
+                        }
+                    })
+                }
+            },
+        );
+
+        parse_quote! {
+            #[allow(unused_variables)]
+            fn append_missing_columns(
+                partial: &Self::TypeCheckPartial,
+                missing: &mut ::std::vec::Vec<&'static str>,
+            ) {
+                #(#appends)*
             }
-        })
+        }
     }
 
-    fn generate(&self) -> syn::ImplItemFn {
+    fn generate_deserialize_column(&self) -> syn::ImplItemFn {
         let macro_internal = self.0.struct_attrs().macro_internal_path();
         let (frame_lifetime, metadata_lifetime) = self.0.constraint_lifetimes();
-        let fields = self.0.fields();
-
-        let deserialize_field_decls = fields
-            .iter()
-            .flat_map(Self::generate_deserialize_field_decl);
-        let deserialize_blocks = fields
-            .iter()
-            .filter(|f| !f.skip)
-            .enumerate()
-            .map(|(col_idx, f)| self.generate_deserialization(col_idx, f));
-        let field_idents = fields.iter().map(|f| f.ident.as_ref().unwrap());
-        let nonskipped_field_names = fields
-            .iter()
-            .filter(|&f| !f.skip)
-            .map(|f| f.cql_name_literal());
+        let by_name_trait = self.by_name_trait();
+
+        let mut column_names = Vec::new();
+        let mut column_blocks = Vec::<syn::Block>::new();
+        let mut flattened_deserializations = Vec::<syn::Stmt>::new();
+        for (position, field) in self.nonskipped_fields().enumerate() {
+            let value = Self::partial_element(position);
+            if field.flatten {
+                let typ = &field.ty;
+                flattened_deserializations.push(parse_quote! {
+                    if <#typ as #by_name_trait>::deserialize_column(&mut #value, column)? {
+                        return ::std::result::Result::Ok(true);
+                    }
+                });
+                continue;
+            }
 
-        let field_finalizers = fields.iter().map(|f| self.generate_finalize_field(f));
+            let cql_name_literal = field.cql_name_literal();
+            let column_type = Self::column_type(field);
+            let unwrap_default: Option<proc_macro2::TokenStream> = field
+                .default_when_null
+                .then(|| quote::quote!(.unwrap_or_default()));
+            column_names.push(cql_name_literal.clone());
+            column_blocks.push(parse_quote! {
+                {
+                    assert!(
+                        #value.is_none(),
+                        "duplicated column {} - type check should have prevented this!",
+                        #cql_name_literal
+                    );
 
-        // TODO: Allow collecting unrecognized fields into some special field
+                    #value = ::std::option::Option::Some(
+                        <#column_type as #macro_internal::DeserializeValue<#frame_lifetime, #metadata_lifetime>>::deserialize(column.spec.typ(), column.slice)
+                            .map_err(|err| {
+                                #macro_internal::mk_row_deser_err::<Self>(
+                                    #macro_internal::BuiltinRowDeserializationErrorKind::ColumnDeserializationFailed {
+                                        column_index: column.index,
+                                        column_name: <_ as ::std::borrow::ToOwned>::to_owned(column.spec.name()),
+                                        err,
+                                    }
+                                )
+                            })?
+                            #unwrap_default
+                    );
+                    ::std::result::Result::Ok(true)
+                }
+            });
+        }
 
         parse_quote! {
-            fn deserialize(
-                row: #macro_internal::ColumnIterator<#frame_lifetime, #metadata_lifetime>,
-            ) -> ::std::result::Result<Self, #macro_internal::DeserializationError> {
+            #[allow(unused_variables)]
+            fn deserialize_column(
+                partial: &mut Self::Partial,
+                column: &#macro_internal::RawColumn<#frame_lifetime, #metadata_lifetime>,
+            ) -> ::std::result::Result<bool, #macro_internal::DeserializationError> {
+                // Pattern match on the field name and deserialize.
+                match column.spec.name() {
+                    #(#column_names => #column_blocks,)*
+                    // If not a column of a regular field, then check if any flattened field
+                    // has a column for it.
+                    _ => {
+                        #(#flattened_deserializations)*
+                        ::std::result::Result::Ok(false)
+                    }
+                }
+            }
+        }
+    }
+
+    fn generate_finalize(&self) -> syn::ImplItemFn {
+        let by_name_trait = self.by_name_trait();
 
-                // Generate fields that will serve as temporary storage
-                // for the fields' values. Those are of type Option<FieldType>.
-                #(#deserialize_field_decls)*
+        let mut position = 0;
+        let field_idents = self.0.fields().iter().map(|f| f.ident.as_ref().unwrap());
+        let field_finalizers = self
+            .0
+            .fields()
+            .iter()
+            .map(|field| -> syn::Expr {
+                if field.skip {
+                    // Skipped fields are initialized with Default::default()
+                    return parse_quote!(::std::default::Default::default());
+                }
 
-                for col in row {
-                    let col = col.map_err(#macro_internal::row_deser_error_replace_rust_name::<Self>)?;
-                    // Pattern match on the field name and deserialize.
-                    match col.spec.name() {
-                        #(#nonskipped_field_names => #deserialize_blocks,)*
-                        unknown => ::std::unreachable!("Typecheck should have prevented this scenario! Unknown column name: {}", unknown),
+                let value = Self::partial_element(position);
+                position += 1;
+                if field.flatten {
+                    let typ = &field.ty;
+                    parse_quote!(<#typ as #by_name_trait>::finalize(#value))
+                } else if field.allow_missing {
+                    parse_quote!(#value.unwrap_or_default())
+                } else {
+                    let cql_name_literal = field.cql_name_literal();
+                    parse_quote! {
+                        #value.unwrap_or_else(|| ::std::panic!(
+                            "column {} missing in DB row - type check should have prevented this!",
+                            #cql_name_literal
+                        ))
                     }
                 }
+            })
+            .collect::<Vec<_>>();
 
-                // Create the final struct. The finalizer expressions convert
-                // the temporary storage fields to the final field values.
-                // For example, if a field is missing but marked as
-                // `default_when_null` it will create a default value, otherwise
-                // it will report an error.
-                ::std::result::Result::Ok(Self {
+        parse_quote! {
+            #[allow(unused_variables)]
+            fn finalize(partial: Self::Partial) -> Self {
+                Self {
                     #(#field_idents: #field_finalizers,)*
-                })
+                }
             }
         }
     }
@@ -31,6 +31,12 @@ struct StructAttrs {
     // they will be ignored. With true, an error will be raised.
     #[darling(default)]
     forbid_excess_udt_fields: bool,
+
+    // If true, then all fields behave as if they had the `allow_missing`
+    // attribute, i.e. fields missing from the UDT are initialized
+    // to Default::default().
+    #[darling(default)]
+    allow_missing: bool,
 }
 
 impl DeserializeCommonStructAttrs for StructAttrs {
@@ -64,6 +70,11 @@ struct Field {
     #[darling(default)]
     rename: Option<String>,
 
+    // Used for serialization only. Ignored in deserialization.
+    #[darling(default)]
+    #[darling(rename = "serialize_with")]
+    _serialize_with: Option<syn::Path>,
+
     ident: Option<syn::Ident>,
     ty: syn::Type,
 }
@@ -82,7 +93,10 @@ impl DeserializeCommonFieldAttrs for Field {
 pub(crate) fn deserialize_value_derive(
     tokens_input: TokenStream,
 ) -> Result<syn::ItemImpl, syn::Error> {
-    let input = syn::parse(tokens_input)?;
+    let input: syn::DeriveInput = syn::parse(tokens_input)?;
+    if let syn::Data::Enum(data) = &input.data {
+        return super::enums::deserialize_value_enum_derive(&input, data);
+    }
 
     let implemented_trait: syn::Path = parse_quote!(DeserializeValue);
     let implemented_trait_name = implemented_trait
@@ -93,7 +107,13 @@ pub(crate) fn deserialize_value_derive(
         .unraw()
         .to_string();
     let constraining_trait = implemented_trait.clone();
-    let s = StructDesc::new(&input, &implemented_trait_name, constraining_trait)?;
+    let mut s = StructDesc::new(&input, &implemented_trait_name, constraining_trait)?;
+
+    if s.attrs.allow_missing {
+        for field in s.fields.iter_mut() {
+            field.default_when_missing = true;
+        }
+    }
 
     validate_attrs(&s.attrs, s.fields())?;
 
@@ -43,12 +43,31 @@ impl FromMeta for Flavor {
     }
 }
 
+// Representation of fieldless enums in {De,S}erializeValue macros.
+#[derive(Copy, Clone, PartialEq, Eq, Default)]
+enum EnumRepr {
+    #[default]
+    Text,
+    Int,
+}
+
+impl FromMeta for EnumRepr {
+    fn from_string(value: &str) -> darling::Result<Self> {
+        match value {
+            "text" => Ok(Self::Text),
+            "int" => Ok(Self::Int),
+            _ => Err(darling::Error::unknown_value(value)),
+        }
+    }
+}
+
 mod serialize;
 
 /// Derive macro for the [`SerializeValue`](./serialize/value/trait.SerializeValue.html) trait
 /// which serializes given Rust structure as a User Defined Type (UDT).
 ///
-/// At the moment, only structs with named fields are supported.
+/// Structs with named fields and enums are supported. Enums are not serialized
+/// as UDTs - see the [Enums](#enums) section below.
 ///
 /// Serialization will fail if there are some fields in the Rust struct that don't match
 /// to any of the UDT fields.
@@ -143,6 +162,12 @@ mod serialize;
 /// Forces Rust struct to have all the fields present in UDT, otherwise
 /// serialization fails.
 ///
+/// `#[scylla(allow_missing)]`
+///
+/// Applies the `allow_missing` field attribute to all fields of the struct.
+/// Useful when the Rust struct is updated before the UDT is altered in the
+/// database.
+///
 /// # Field attributes
 ///
 /// `#[scylla(rename = "name_in_the_udt")]`
@@ -154,6 +179,57 @@ mod serialize;
 ///
 /// Don't use the field during serialization.
 ///
+/// `#[scylla(allow_missing)]`
+///
+/// If the UDT definition does not contain this field, it is not serialized
+/// instead of causing an error.
+///
+/// `#[scylla(serialize_with = path::to::function)]`
+///
+/// Serializes the field with the given function instead of its
+/// [`SerializeValue`](./serialize/value/trait.SerializeValue.html) implementation.
+/// The function must have the same signature as `SerializeValue::serialize`,
+/// except that it takes a reference to the field instead of `&self`.
+/// This allows to send fields of types which don't implement `SerializeValue`,
+/// or to send a value computed from the field.
+///
+/// # Enums
+///
+/// An enum whose variants have no fields is serialized as the name of
+/// the variant, which requires the column to be of the `text` or `ascii` type.
+/// The `#[scylla(rename = "name")]` variant attribute changes the name used
+/// for the variant.
+///
+/// With the `#[scylla(repr = "int")]` enum attribute, the variants are
+/// serialized as their discriminants instead, which requires the column to be
+/// of the `int` type. `rename` is not allowed then.
+///
+/// An enum whose variants all have exactly one unnamed field is serialized
+/// as the value of that field. This allows a column to hold a value of one of
+/// several types, e.g. one of several UDTs.
+///
+/// ```rust
+/// # use scylla::SerializeValue;
+/// #[derive(SerializeValue)]
+/// enum Status {
+///     Active,
+///     #[scylla(rename = "on_hold")]
+///     OnHold,
+/// }
+///
+/// #[derive(SerializeValue)]
+/// #[scylla(repr = "int")]
+/// enum Priority {
+///     Low = 1,
+///     High = 10,
+/// }
+///
+/// #[derive(SerializeValue)]
+/// enum IntOrText {
+///     Int(i32),
+///     Text(String),
+/// }
+/// ```
 #[proc_macro_derive(SerializeValue, attributes(scylla))]
 pub fn serialize_value_derive(tokens_input: TokenStream) -> TokenStream {
     match serialize::value::derive_serialize_value(tokens_input) {
@@ -266,6 +342,40 @@ pub fn serialize_value_derive(tokens_input: TokenStream) -> TokenStream {
 ///
 /// Note that the name of this field is ignored and hence the `rename` attribute does not make sense
 /// here and will cause a compilation error.
+///
+/// `#[scylla(serialize_with = path::to::function)]`
+///
+/// Serializes the field with the given function instead of its
+/// [`SerializeValue`](./serialize/value/trait.SerializeValue.html) implementation.
+/// The function must have the same signature as `SerializeValue::serialize`,
+/// except that it takes a reference to the field instead of `&self`.
+/// This allows to bind fields of types which don't implement `SerializeValue`,
+/// or to bind a value computed from the field. It cannot be combined with `flatten`.
+///
+/// ```rust
+/// # use scylla::SerializeRow;
+/// # use std::time::Duration;
+/// use scylla::frame::response::result::ColumnType;
+/// use scylla::serialize::SerializationError;
+/// use scylla::serialize::value::SerializeValue;
+/// use scylla::serialize::writers::{CellWriter, WrittenCellProof};
+///
+/// fn serialize_as_millis<'b>(
+///     duration: &Duration,
+///     typ: &ColumnType,
+///     writer: CellWriter<'b>,
+/// ) -> Result<WrittenCellProof<'b>, SerializationError> {
+///     (duration.as_millis() as i64).serialize(typ, writer)
+/// }
+///
+/// #[derive(SerializeRow)]
+/// # #[scylla(crate = "scylla_cql")]
+/// struct MyRow {
+///     id: i32,
+///     #[scylla(rename = "timeout_ms", serialize_with = serialize_as_millis)]
+///     timeout: Duration,
+/// }
+/// ```
 #[proc_macro_derive(SerializeRow, attributes(scylla))]
 pub fn serialize_row_derive(tokens_input: TokenStream) -> TokenStream {
     match serialize::row::derive_serialize_row(tokens_input) {
@@ -395,6 +505,42 @@ mod deserialize;
 /// By default, the generated implementation will try to match the Rust field
 /// to a column with the same name. This attribute allows to match to a column
 /// with provided name.
+///
+/// `#[scylla(allow_missing)]`
+///
+/// This attribute only works with `flavor = "match_by_name"`.
+///
+/// If the row does not contain a column for this field, the field will be
+/// initialized with `Default::default()`.
+///
+/// `#[scylla(flatten)]`
+///
+/// This attribute only works with `flavor = "match_by_name"`.
+///
+/// The field's type must also derive `DeserializeRow` with the `"match_by_name"`
+/// flavor. Its fields are matched against the columns of the row as if they
+/// were declared directly in the outer struct, which allows to share groups
+/// of columns between row types. The attribute cannot be combined with
+/// `rename`, `allow_missing` or `default_when_null`.
+///
+/// ```rust
+/// # use scylla::DeserializeRow;
+/// #[derive(DeserializeRow)]
+/// # #[scylla(crate = "scylla_cql")]
+/// struct Audit {
+///     created_by: String,
+///     #[scylla(allow_missing)]
+///     revision: i32,
+/// }
+///
+/// #[derive(DeserializeRow)]
+/// # #[scylla(crate = "scylla_cql")]
+/// struct MyRow {
+///     id: i32,
+///     #[scylla(flatten)]
+///     audit: Audit,
+/// }
+/// ```
 #[proc_macro_derive(DeserializeRow, attributes(scylla))]
 pub fn deserialize_row_derive(tokens_input: TokenStream) -> TokenStream {
     match deserialize::row::deserialize_row_derive(tokens_input) {
@@ -403,11 +549,62 @@ pub fn deserialize_row_derive(tokens_input: TokenStream) -> TokenStream {
     }
 }
 
+/// Derive macro which generates both the [`SerializeRow`](./serialize/row/trait.SerializeRow.html)
+/// and the [`DeserializeRow`](./deserialize/row/trait.DeserializeRow.html) implementations
+/// for a struct, out of a single set of attributes.
+///
+/// It is equivalent to `#[derive(SerializeRow, DeserializeRow)]`, but makes it explicit
+/// that the struct is meant to be used in both directions: for example, columns bound
+/// in an `INSERT` are the same columns which a `SELECT` returns, as both impls
+/// interpret the `rename`, `skip` and `flatten` attributes and the struct attributes
+/// (`crate`, `flavor`, `skip_name_checks`) the same way.
+///
+/// Attributes specific to one direction are ignored by the other one:
+/// - `default_when_null` and `allow_missing` only affect deserialization,
+/// - `serialize_with` only affects serialization.
+///
+/// Fields with the `flatten` attribute must have types which also derive `SerializeDeserializeRow`
+/// (or both `SerializeRow` and `DeserializeRow`).
+///
+/// See the documentation of both derive macros for the full description of the attributes.
+///
+/// # Example
+///
+/// ```rust
+/// # use scylla::SerializeDeserializeRow;
+/// #[derive(SerializeDeserializeRow)]
+/// # #[scylla(crate = "scylla_cql")]
+/// struct User {
+///     id: i32,
+///     #[scylla(rename = "user_name")]
+///     name: String,
+///     #[scylla(skip)]
+///     cached_score: Option<f64>,
+/// }
+/// ```
+#[proc_macro_derive(SerializeDeserializeRow, attributes(scylla))]
+pub fn serialize_deserialize_row_derive(tokens_input: TokenStream) -> TokenStream {
+    let serialize_row_impl = match serialize::row::derive_serialize_row(tokens_input.clone()) {
+        Ok(t) => t,
+        Err(e) => return e.into_compile_error().into(),
+    };
+    let deserialize_row_impl = match deserialize::row::deserialize_row_derive(tokens_input) {
+        Ok(tokens) => tokens,
+        Err(err) => return err.into_compile_error().into(),
+    };
+    quote::quote! {
+        #serialize_row_impl
+        #deserialize_row_impl
+    }
+    .into()
+}
+
 /// Derive macro for the [`DeserializeValue`](./deserialize/value/trait.DeserializeValue.html)
 /// trait that generates an implementation which deserializes a User Defined Type
 /// with the same layout as the Rust struct.
 ///
-/// At the moment, only structs with named fields are supported.
+/// Structs with named fields and enums are supported. Enums are not deserialized
+/// from UDTs - see the [Enums](#enums) section below.
 ///
 /// This macro properly supports structs with lifetimes, meaning that you can
 /// deserialize UDTs with fields that borrow memory from the serialized response.
@@ -501,6 +698,12 @@ pub fn deserialize_row_derive(tokens_input: TokenStream) -> TokenStream {
 /// If more strictness is desired, this flag makes sure that no excess fields
 /// are present and forces error in case there are some.
 ///
+/// `#[scylla(allow_missing)]`
+///
+/// Applies the `allow_missing` field attribute to all fields of the struct.
+/// Useful when the Rust struct is updated before the UDT is altered in the
+/// database.
+///
 /// ## Field attributes
 ///
 /// `#[scylla(skip)]`
@@ -523,6 +726,49 @@ pub fn deserialize_row_derive(tokens_input: TokenStream) -> TokenStream {
 /// By default, the generated implementation will try to match the Rust field
 /// to a UDT field with the same name. This attribute instead allows to match
 /// to a UDT field with provided name.
+///
+/// # Enums
+///
+/// An enum whose variants have no fields is deserialized as the name of
+/// the variant, which requires the column to be of the `text` or `ascii` type.
+/// The `#[scylla(rename = "name")]` variant attribute changes the name used
+/// for the variant.
+///
+/// With the `#[scylla(repr = "int")]` enum attribute, the variants are
+/// deserialized as their discriminants instead, which requires the column to be
+/// of the `int` type. `rename` is not allowed then.
+///
+/// An enum whose variants all have exactly one unnamed field is deserialized
+/// as the value of that field. This allows a column to hold a value of one of
+/// several types, e.g. one of several UDTs. The first variant
+/// whose field type matches the type of the column is chosen. The choice is made
+/// when the type is checked and reused for every deserialized value.
+///
+/// ```rust
+/// # use scylla::DeserializeValue;
+/// #[derive(DeserializeValue)]
+/// # #[scylla(crate = "scylla_cql")]
+/// enum Status {
+///     Active,
+///     #[scylla(rename = "on_hold")]
+///     OnHold,
+/// }
+///
+/// #[derive(DeserializeValue)]
+/// # #[scylla(crate = "scylla_cql")]
+/// #[scylla(repr = "int")]
+/// enum Priority {
+///     Low = 1,
+///     High = 10,
+/// }
+///
+/// #[derive(DeserializeValue)]
+/// # #[scylla(crate = "scylla_cql")]
+/// enum IntOrText {
+///     Int(i32),
+///     Text(String),
+/// }
+/// ```
 #[proc_macro_derive(DeserializeValue, attributes(scylla))]
 pub fn deserialize_value_derive(tokens_input: TokenStream) -> TokenStream {
     match deserialize::value::deserialize_value_derive(tokens_input) {
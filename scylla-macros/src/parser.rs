@@ -1,4 +1,4 @@
-use syn::{Data, DeriveInput, Fields, FieldsNamed};
+use syn::{Data, DataEnum, DeriveInput, Fields, FieldsNamed};
 
 /// Parses a struct DeriveInput and returns named fields of this struct.
 pub(crate) fn parse_named_fields<'a>(
@@ -17,3 +17,49 @@ pub(crate) fn parse_named_fields<'a>(
         Data::Union(u) => Err(syn::Error::new_spanned(u.union_token, create_err_msg())),
     }
 }
+
+/// The shapes of enums supported by {De,S}erializeValue macros.
+pub(crate) enum EnumShape {
+    /// All variants have no fields.
+    Fieldless,
+    /// All variants have a single unnamed field.
+    Newtype,
+}
+
+/// Checks that all variants of the enum have the same, supported shape.
+pub(crate) fn parse_enum_shape(
+    data: &DataEnum,
+    current_derive: &str,
+) -> Result<EnumShape, syn::Error> {
+    let create_err_msg = || {
+        format!(
+            "derive({current_derive}) works only for enums whose variants either all have no fields, \
+            or all have a single unnamed field"
+        )
+    };
+
+    let is_newtype = |fields: &Fields| matches!(fields, Fields::Unnamed(f) if f.unnamed.len() == 1);
+    let Some(first) = data.variants.first() else {
+        return Err(syn::Error::new_spanned(
+            data.enum_token,
+            format!("derive({current_derive}) works only for enums with at least one variant"),
+        ));
+    };
+    let shape = if is_newtype(&first.fields) {
+        EnumShape::Newtype
+    } else {
+        EnumShape::Fieldless
+    };
+
+    for variant in data.variants.iter() {
+        let matches_shape = match shape {
+            EnumShape::Fieldless => matches!(variant.fields, Fields::Unit),
+            EnumShape::Newtype => is_newtype(&variant.fields),
+        };
+        if !matches_shape {
+            return Err(syn::Error::new_spanned(&variant.ident, create_err_msg()));
+        }
+    }
+
+    Ok(shape)
+}
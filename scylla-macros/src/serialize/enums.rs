@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+
+use darling::FromAttributes;
+use syn::ext::IdentExt;
+use syn::parse_quote;
+
+use crate::EnumRepr;
+use crate::parser::EnumShape;
+
+#[derive(FromAttributes)]
+#[darling(attributes(scylla))]
+struct Attributes {
+    #[darling(rename = "crate")]
+    crate_path: Option<syn::Path>,
+
+    // How variants of a fieldless enum are represented in the database.
+    // Not allowed for enums whose variants have fields.
+    repr: Option<EnumRepr>,
+}
+
+impl Attributes {
+    fn crate_path(&self) -> syn::Path {
+        self.crate_path
+            .as_ref()
+            .map(|p| parse_quote!(#p::_macro_internal))
+            .unwrap_or_else(|| parse_quote!(::scylla::_macro_internal))
+    }
+}
+
+#[derive(FromAttributes)]
+#[darling(attributes(scylla))]
+struct VariantAttributes {
+    // If set, then the variant of a fieldless enum is represented by this
+    // name instead of the Rust variant name. Only allowed with the `text` repr.
+    rename: Option<String>,
+}
+
+struct Variant<'a> {
+    variant: &'a syn::Variant,
+    attrs: VariantAttributes,
+}
+
+impl Variant<'_> {
+    // The name representing this variant in the database
+    fn name(&self) -> String {
+        match &self.attrs.rename {
+            Some(name) => name.clone(),
+            None => self.variant.ident.unraw().to_string(),
+        }
+    }
+}
+
+pub(super) fn derive_serialize_value_enum(
+    input: &syn::DeriveInput,
+    data: &syn::DataEnum,
+) -> Result<syn::ItemImpl, syn::Error> {
+    let shape = crate::parser::parse_enum_shape(data, "SerializeValue")?;
+    let attributes = Attributes::from_attributes(&input.attrs)?;
+    let variants = data
+        .variants
+        .iter()
+        .map(|variant| {
+            VariantAttributes::from_attributes(&variant.attrs)
+                .map(|attrs| Variant { variant, attrs })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    validate(&input.ident, &shape, &attributes, &variants)?;
+
+    let enum_name = &input.ident;
+    let crate_path = attributes.crate_path();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let variant_idents = variants
+        .iter()
+        .map(|v| &v.variant.ident)
+        .collect::<Vec<_>>();
+
+    let serialize_block: syn::Block = match (shape, attributes.repr.unwrap_or_default()) {
+        (EnumShape::Fieldless, EnumRepr::Text) => {
+            let variant_names = variants.iter().map(Variant::name);
+            parse_quote! {{
+                let name = match self {
+                    #(Self::#variant_idents => #variant_names,)*
+                };
+                #crate_path::ser::value::serialize_enum_as_text::<Self>(name, typ, writer)
+            }}
+        }
+        (EnumShape::Fieldless, EnumRepr::Int) => parse_quote! {{
+            let discriminant = match self {
+                #(Self::#variant_idents => Self::#variant_idents as i32,)*
+            };
+            #crate_path::ser::value::serialize_enum_as_int::<Self>(discriminant, typ, writer)
+        }},
+        (EnumShape::Newtype, _) => {
+            let variant_types = variants
+                .iter()
+                .map(|v| &v.variant.fields.iter().next().unwrap().ty);
+            parse_quote! {{
+                match self {
+                    #(Self::#variant_idents(inner) => {
+                        <#variant_types as #crate_path::SerializeValue>::serialize(inner, typ, writer)
+                    })*
+                }
+            }}
+        }
+    };
+
+    Ok(parse_quote! {
+        #[automatically_derived]
+        impl #impl_generics #crate_path::SerializeValue for #enum_name #ty_generics #where_clause {
+            fn serialize<'b>(
+                &self,
+                typ: &#crate_path::ColumnType,
+                writer: #crate_path::CellWriter<'b>,
+            ) -> ::std::result::Result<#crate_path::WrittenCellProof<'b>, #crate_path::SerializationError> {
+                #serialize_block
+            }
+        }
+    })
+}
+
+fn validate(
+    enum_ident: &syn::Ident,
+    shape: &EnumShape,
+    attributes: &Attributes,
+    variants: &[Variant],
+) -> Result<(), syn::Error> {
+    let mut errors = darling::Error::accumulator();
+
+    match shape {
+        EnumShape::Newtype => {
+            if attributes.repr.is_some() {
+                errors.push(
+                    darling::Error::custom(
+                        "the `repr` attribute is only allowed on enums whose variants have no fields",
+                    )
+                    .with_span(enum_ident),
+                );
+            }
+            for variant in variants.iter().filter(|v| v.attrs.rename.is_some()) {
+                errors.push(
+                    darling::Error::custom(
+                        "the `rename` annotations are only allowed on enums whose variants have no fields",
+                    )
+                    .with_span(&variant.variant.ident),
+                );
+            }
+        }
+        EnumShape::Fieldless if attributes.repr == Some(EnumRepr::Int) => {
+            for variant in variants.iter().filter(|v| v.attrs.rename.is_some()) {
+                errors.push(
+                    darling::Error::custom(
+                        "the `rename` annotations don't make sense with `repr = \"int\"` attribute",
+                    )
+                    .with_span(&variant.variant.ident),
+                );
+            }
+        }
+        EnumShape::Fieldless => {
+            // Detect name collisions caused by `rename`.
+            let mut used_names = HashMap::<String, &Variant>::new();
+            for variant in variants {
+                let name = variant.name();
+                if let Some(other_variant) = used_names.get(&name) {
+                    let other_variant_ident = &other_variant.variant.ident;
+                    let msg = format!(
+                        "the name `{name}` used by this enum variant is already used by variant `{other_variant_ident}`"
+                    );
+                    errors.push(darling::Error::custom(msg).with_span(&variant.variant.ident));
+                } else {
+                    used_names.insert(name, variant);
+                }
+            }
+        }
+    }
+
+    errors.finish()?;
+    Ok(())
+}
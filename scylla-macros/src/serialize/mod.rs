@@ -1,2 +1,3 @@
+mod enums;
 pub(crate) mod row;
 pub(crate) mod value;
@@ -46,6 +46,17 @@ impl Field {
             None => self.ident.to_string(),
         }
     }
+
+    // Returns an expression which serializes `value` (a reference to the field)
+    // as a column, honoring the `serialize_with` attribute.
+    fn serializable_value(&self, crate_path: &syn::Path, value: syn::Expr) -> syn::Expr {
+        match &self.attrs.serialize_with {
+            Some(serialize_with) => {
+                parse_quote!(&#crate_path::ser::SerializeWith(#value, #serialize_with))
+            }
+            None => value,
+        }
+    }
 }
 
 #[derive(FromAttributes)]
@@ -66,10 +77,20 @@ struct FieldAttributes {
     #[darling(default)]
     skip: bool,
 
+    // If set, then the field is serialized by calling the function under
+    // this path instead of using its `SerializeValue` implementation.
+    // The function must have the same signature as `SerializeValue::serialize`.
+    serialize_with: Option<syn::Path>,
+
     // Used for deserialization only. Ignored in serialization.
     #[darling(default)]
     #[darling(rename = "default_when_null")]
     _default_when_null: bool,
+
+    // Used for deserialization only. Ignored in serialization.
+    #[darling(default)]
+    #[darling(rename = "allow_missing")]
+    _allow_missing: bool,
 }
 
 struct Context {
@@ -168,6 +189,19 @@ impl Context {
             });
         errors.extend(rename_flatten_errors);
 
+        // Flattened fields are serialized as rows, so a custom function can't serialize them
+        let serialize_with_flatten_errors = self
+            .fields
+            .iter()
+            .filter(|f| f.attrs.flatten && f.attrs.serialize_with.is_some())
+            .map(|f| {
+                darling::Error::custom(
+                    "`serialize_with` and `flatten` annotations do not make sense together",
+                )
+                .with_span(&f.ident)
+            });
+        errors.extend(serialize_with_flatten_errors);
+
         // Check for name collisions
         let mut used_names = HashMap::<String, &Field>::new();
         for field in self.fields.iter() {
@@ -271,6 +305,13 @@ impl Generator for ColumnSortingGenerator<'_> {
             .map(|ident| format_ident!("__visited_flag_{}", ident))
             .collect();
         let nonflattened_types: Vec<_> = nonflattened.iter().map(|f| &f.typ).collect();
+        let nonflattened_values: Vec<_> = nonflattened
+            .iter()
+            .map(|f| {
+                let ident = &f.ident;
+                f.serializable_value(&crate_path, parse_quote!(self.#ident))
+            })
+            .collect();
 
         let partial_struct: syn::ItemStruct = parse_quote! {
             pub struct #partial_struct_name #partial_generics {
@@ -295,7 +336,7 @@ impl Generator for ColumnSortingGenerator<'_> {
                     // first check if the spec name matches a non-flattened column
                     #(#nonflattened_columns => {
                         #crate_path::ser::row::serialize_column::<#struct_name #ty_generics>(
-                            &self.#nonflattened_fields, spec, writer,
+                            #nonflattened_values, spec, writer,
                         )?;
                         if !self.#nonflattened_visited_flag_names {
                             self.#nonflattened_visited_flag_names = true;
@@ -442,8 +483,9 @@ impl Generator for ColumnOrderedGenerator<'_> {
             } else {
                 let column = f.column_name();
                 let enforce_name = !self.ctx.attributes.skip_name_checks;
+                let value = f.serializable_value(&crate_path, parse_quote!(&self.#field));
                 syn::parse_quote! {
-                    #crate_path::ser::row::NextColumnSerializer::serialize::<Self, #enforce_name>(columns, #column, &self.#field, writer)?;
+                    #crate_path::ser::row::NextColumnSerializer::serialize::<Self, #enforce_name>(columns, #column, #value, writer)?;
                 }
             }
         });
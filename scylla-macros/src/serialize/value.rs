@@ -30,6 +30,11 @@ struct Attributes {
     // the DB will interpret them as NULLs anyway.
     #[darling(default)]
     forbid_excess_udt_fields: bool,
+
+    // If true, then all fields behave as if they had the `allow_missing`
+    // attribute, i.e. fields missing from the UDT are not serialized.
+    #[darling(default)]
+    allow_missing: bool,
 }
 
 impl Attributes {
@@ -60,6 +65,26 @@ impl Field {
     fn is_required(&self) -> bool {
         !self.attrs.skip && !self.attrs.ignore_missing
     }
+
+    // Returns an expression which serializes this field of `self`,
+    // honoring the `serialize_with` attribute.
+    fn generate_serialize_call(
+        &self,
+        crate_path: &syn::Path,
+        typ: syn::Expr,
+        writer: syn::Expr,
+    ) -> syn::Expr {
+        let ident = &self.ident;
+        match &self.attrs.serialize_with {
+            Some(serialize_with) => parse_quote!(#serialize_with(&self.#ident, #typ, #writer)),
+            None => {
+                let field_type = &self.typ;
+                parse_quote! {
+                    <#field_type as #crate_path::SerializeValue>::serialize(&self.#ident, #typ, #writer)
+                }
+            }
+        }
+    }
 }
 
 #[derive(FromAttributes)]
@@ -80,6 +105,11 @@ struct FieldAttributes {
     #[darling(rename = "allow_missing")]
     ignore_missing: bool,
 
+    // If set, then the field is serialized by calling the function under
+    // this path instead of using its `SerializeValue` implementation.
+    // The function must have the same signature as `SerializeValue::serialize`.
+    serialize_with: Option<syn::Path>,
+
     // Used for deserialization only. Ignored in serialization.
     #[darling(default)]
     #[darling(rename = "default_when_null")]
@@ -95,6 +125,9 @@ pub(crate) fn derive_serialize_value(
     tokens_input: TokenStream,
 ) -> Result<syn::ItemImpl, syn::Error> {
     let input: syn::DeriveInput = syn::parse(tokens_input)?;
+    if let syn::Data::Enum(data) = &input.data {
+        return super::enums::derive_serialize_value_enum(&input, data);
+    }
     let struct_name = input.ident.clone();
     let named_fields = crate::parser::parse_named_fields(&input, "SerializeValue")?;
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
@@ -107,10 +140,13 @@ pub(crate) fn derive_serialize_value(
         .named
         .iter()
         .map(|f| {
-            FieldAttributes::from_attributes(&f.attrs).map(|attrs| Field {
-                ident: f.ident.clone().unwrap(),
-                typ: f.ty.clone(),
-                attrs,
+            FieldAttributes::from_attributes(&f.attrs).map(|mut attrs| {
+                attrs.ignore_missing |= attributes.allow_missing;
+                Field {
+                    ident: f.ident.clone().unwrap(),
+                    typ: f.ty.clone(),
+                    attrs,
+                }
             })
         })
         // Filter the fields now instead of at the places that use them later
@@ -282,7 +318,18 @@ impl Generator for FieldSortingGenerator<'_> {
         let rust_field_ignore_missing_flags =
             self.ctx.fields.iter().map(|f| f.attrs.ignore_missing);
         let udt_field_names = rust_field_names.clone(); // For now, it's the same
-        let field_types = self.ctx.fields.iter().map(|f| &f.typ).collect::<Vec<_>>();
+        let field_serialize_calls = self
+            .ctx
+            .fields
+            .iter()
+            .map(|f| {
+                f.generate_serialize_call(
+                    &crate_path,
+                    parse_quote!(field_type),
+                    parse_quote!(sub_builder),
+                )
+            })
+            .collect::<Vec<_>>();
 
         let missing_rust_field_expression: syn::Expr =
             if self.ctx.attributes.forbid_excess_udt_fields {
@@ -385,7 +432,7 @@ impl Generator for FieldSortingGenerator<'_> {
                         #udt_field_names => {
                             #serialize_missing_nulls_statement
                             let sub_builder = #crate_path::CellValueBuilder::make_sub_writer(&mut builder);
-                            match <#field_types as #crate_path::SerializeValue>::serialize(&self.#rust_field_idents, field_type, sub_builder) {
+                            match #field_serialize_calls {
                                 ::std::result::Result::Ok(_proof) => {}
                                 ::std::result::Result::Err(err) => {
                                     return ::std::result::Result::Err(mk_ser_err(
@@ -479,10 +526,13 @@ impl Generator for FieldOrderedGenerator<'_> {
 
         // Serialize each field
         for field in self.ctx.fields.iter() {
-            let rust_field_ident = &field.ident;
             let rust_field_name = field.field_name();
             let field_can_be_ignored = field.attrs.ignore_missing;
-            let typ = &field.typ;
+            let serialize_call = field.generate_serialize_call(
+                &crate_path,
+                parse_quote!(typ),
+                parse_quote!(sub_builder),
+            );
             let name_check_expression: syn::Expr = if !self.ctx.attributes.skip_name_checks {
                 parse_quote! { field_name == #rust_field_name }
             } else {
@@ -496,7 +546,7 @@ impl Generator for FieldOrderedGenerator<'_> {
                             ::std::iter::Iterator::next(&mut field_iter);
 
                             let sub_builder = #crate_path::CellValueBuilder::make_sub_writer(&mut builder);
-                            match <#typ as #crate_path::SerializeValue>::serialize(&self.#rust_field_ident, typ, sub_builder) {
+                            match #serialize_call {
                                 ::std::result::Result::Ok(_proof) => {},
                                 ::std::result::Result::Err(err) => {
                                     return ::std::result::Result::Err(mk_ser_err(
@@ -1,7 +1,20 @@
 //! Traits and implementations for custom authentication against a server.
+//!
+//! Authentication is a SASL-like exchange, performed on each new connection which the server
+//! asks to authenticate:
+//! 1. [`AuthenticatorProvider`] starts an [`AuthenticatorSession`] and gives the initial response,
+//! 2. each challenge of the server is answered by [`AuthenticatorSession::evaluate_challenge`],
+//!    for as many rounds as the authentication protocol needs,
+//! 3. the exchange is finished by [`AuthenticatorSession::success`].
+//!
+//! This makes it possible to plug in e.g. Kerberos (GSSAPI) or proprietary authenticators.
+
+use std::fmt;
+use std::net::SocketAddr;
+use std::sync::Arc;
 
 use async_trait::async_trait;
-use bytes::{BufMut, BytesMut};
+use zeroize::Zeroizing;
 
 pub use crate::frame::Authenticator;
 
@@ -39,6 +52,20 @@ pub trait AuthenticatorProvider: Sync + Send {
         &self,
         authenticator_name: &str,
     ) -> Result<(Option<Vec<u8>>, Box<dyn AuthenticatorSession>), AuthError>;
+
+    /// Like [`start_authentication_session`](Self::start_authentication_session), but also given
+    /// the address of the node which the connection is opened to. Protocols such as Kerberos need it
+    /// e.g. to choose the service principal of the node.
+    ///
+    /// This is the method which the driver calls. By default, it ignores the address and calls
+    /// [`start_authentication_session`](Self::start_authentication_session).
+    async fn start_authentication_session_for_node(
+        &self,
+        authenticator_name: &str,
+        _node_address: SocketAddr,
+    ) -> Result<(Option<Vec<u8>>, Box<dyn AuthenticatorSession>), AuthError> {
+        self.start_authentication_session(authenticator_name).await
+    }
 }
 
 struct PlainTextAuthenticatorSession;
@@ -57,16 +84,83 @@ impl AuthenticatorSession for PlainTextAuthenticatorSession {
     }
 }
 
+/// Username and password used by [`PlainTextAuthenticator`].
+///
+/// The password is never included in `Debug` output
+/// and is wiped from memory when the credentials are dropped.
+#[derive(Clone)]
+pub struct Credentials {
+    username: String,
+    password: Zeroizing<String>,
+}
+
+impl Credentials {
+    /// Creates new [`Credentials`] with provided username and password.
+    pub fn new(username: impl Into<String>, password: impl Into<String>) -> Self {
+        Credentials {
+            username: username.into(),
+            password: Zeroizing::new(password.into()),
+        }
+    }
+
+    /// Returns the username.
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
+    /// Returns the password.
+    pub fn password(&self) -> &str {
+        &self.password
+    }
+}
+
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Credentials")
+            .field("username", &self.username)
+            .field("password", &"<redacted>")
+            .finish()
+    }
+}
+
+/// Source of [`Credentials`] for [`PlainTextAuthenticator`].
+///
+/// It is asked for credentials each time a connection authenticates, so short-lived credentials,
+/// e.g. issued by Vault or a cloud IAM service, can be rotated without recreating the session.
+/// Established connections are not affected by the rotation. Caching the credentials,
+/// if needed, is up to the implementation.
+///
+/// [`Credentials`] themselves implement this trait, always returning the same credentials.
+#[async_trait]
+pub trait CredentialsProvider: Send + Sync {
+    /// Returns the credentials to authenticate a new connection with.
+    async fn credentials(&self) -> Result<Credentials, AuthError>;
+}
+
+#[async_trait]
+impl CredentialsProvider for Credentials {
+    async fn credentials(&self) -> Result<Credentials, AuthError> {
+        Ok(self.clone())
+    }
+}
+
 /// Default authenticator provider that requires username and password if authentication is required.
 pub struct PlainTextAuthenticator {
-    username: String,
-    password: String,
+    credentials_provider: Arc<dyn CredentialsProvider>,
 }
 
 impl PlainTextAuthenticator {
     /// Creates new [`PlainTextAuthenticator`] instance with provided username and password.
     pub fn new(username: String, password: String) -> Self {
-        PlainTextAuthenticator { username, password }
+        Self::with_credentials_provider(Arc::new(Credentials::new(username, password)))
+    }
+
+    /// Creates new [`PlainTextAuthenticator`] instance which asks the provider
+    /// for credentials on each authentication.
+    pub fn with_credentials_provider(credentials_provider: Arc<dyn CredentialsProvider>) -> Self {
+        PlainTextAuthenticator {
+            credentials_provider,
+        }
     }
 }
 
@@ -76,18 +170,77 @@ impl AuthenticatorProvider for PlainTextAuthenticator {
         &self,
         _authenticator_name: &str,
     ) -> Result<(Option<Vec<u8>>, Box<dyn AuthenticatorSession>), AuthError> {
-        let mut response = BytesMut::new();
-        let username_as_bytes = self.username.as_bytes();
-        let password_as_bytes = self.password.as_bytes();
-
-        response.put_u8(0);
-        response.put_slice(username_as_bytes);
-        response.put_u8(0);
-        response.put_slice(password_as_bytes);
-
-        Ok((
-            Some(response.to_vec()),
-            Box::new(PlainTextAuthenticatorSession),
-        ))
+        let credentials = self.credentials_provider.credentials().await?;
+        let username = credentials.username.as_bytes();
+        let password = credentials.password.as_bytes();
+
+        let mut response = Vec::with_capacity(username.len() + password.len() + 2);
+        response.push(0);
+        response.extend_from_slice(username);
+        response.push(0);
+        response.extend_from_slice(password);
+
+        Ok((Some(response), Box::new(PlainTextAuthenticatorSession)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use async_trait::async_trait;
+
+    use super::PlainTextAuthenticator;
+    use super::{AuthError, AuthenticatorProvider, Credentials, CredentialsProvider};
+
+    struct RotatingCredentials {
+        generation: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl CredentialsProvider for RotatingCredentials {
+        async fn credentials(&self) -> Result<Credentials, AuthError> {
+            let generation = self.generation.fetch_add(1, Ordering::Relaxed);
+            Ok(Credentials::new("user", format!("password{generation}")))
+        }
+    }
+
+    #[tokio::test]
+    async fn credentials_are_fetched_on_each_authentication() {
+        let authenticator =
+            PlainTextAuthenticator::with_credentials_provider(Arc::new(RotatingCredentials {
+                generation: AtomicUsize::new(0),
+            }));
+
+        for expected in [&b"\0user\0password0"[..], &b"\0user\0password1"[..]] {
+            let (response, _) = authenticator
+                .start_authentication_session("PasswordAuthenticator")
+                .await
+                .unwrap();
+            assert_eq!(response.as_deref(), Some(expected));
+        }
+    }
+
+    #[tokio::test]
+    async fn initial_response_contains_username_and_password() {
+        let authenticator = PlainTextAuthenticator::new("cassandra".into(), "pa55word".into());
+        let (response, _) = authenticator
+            .start_authentication_session("PasswordAuthenticator")
+            .await
+            .unwrap();
+        assert_eq!(response.as_deref(), Some(&b"\0cassandra\0pa55word"[..]));
+    }
+
+    #[test]
+    fn debug_does_not_reveal_password() {
+        let credentials = Credentials::new("user", "secret");
+        assert_eq!(credentials.password(), "secret");
+
+        let debug = format!("{credentials:?}");
+        assert_eq!(
+            debug,
+            r#"Credentials { username: "user", password: "<redacted>" }"#
+        );
     }
 }
@@ -0,0 +1,554 @@
+//! Change Data Capture (CDC) log tables.
+//!
+//! When CDC is enabled on a table, ScyllaDB records every change to the table
+//! in a CDC log table, named `<table>_scylla_cdc_log` (see [log_table_name]).
+//! The log table is partitioned by *streams*: each change is written to the stream
+//! that covers the token of the changed partition at the time of the write.
+//! The set of streams changes whenever the cluster topology does; each such set is
+//! a *CDC generation*, [CdcGeneration].
+//!
+//! [CdcGeneration::fetch_all] reads the generations published by the cluster,
+//! [CdcGeneration::stream_for_partition_key] computes the stream which the changes
+//! of a base table partition are written to, and [CdcLogReader] reads the changes
+//! of a single stream in a time window.
+//!
+//! # Example
+//! ```rust
+//! # use scylla::client::session::Session;
+//! # use std::error::Error;
+//! # async fn check_only_compiles(session: &Session) -> Result<(), Box<dyn Error>> {
+//! use futures::TryStreamExt;
+//! use scylla::cdc::{CdcGeneration, CdcLogReader};
+//! use scylla::value::CqlTimestamp;
+//!
+//! let generations = CdcGeneration::fetch_all(session).await?;
+//! let generation = generations.last().expect("CDC is not enabled in the cluster");
+//!
+//! let cluster_state = session.get_cluster_state();
+//! let stream = generation
+//!     .stream_for_partition_key(&cluster_state, "ks", "t", &(1,))?
+//!     .expect("the generation has no streams");
+//!
+//! let reader = CdcLogReader::prepare(session, "ks", "t").await?;
+//! let (from, to) = (generation.timestamp(), CqlTimestamp(1_700_000_000_000));
+//! let mut rows_stream = reader
+//!     .read(session, &stream, from, to)
+//!     .await?
+//!     .rows_stream::<(i32, Option<i32>)>()?;
+//!
+//! while let Some((pk, v)) = rows_stream.try_next().await? {
+//!     println!("pk, v: {}, {:?}", pk, v);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+
+use futures::TryStreamExt as _;
+use scylla_cql::deserialize::TypeCheckError;
+use scylla_cql::serialize::row::SerializeRow;
+use scylla_cql::value::CqlTimestamp;
+use thiserror::Error;
+
+use crate::client::pager::{NextRowError, QueryPager};
+use crate::client::session::Session;
+use crate::cluster::ClusterState;
+use crate::errors::{ClusterStateTokenError, PagerExecutionError, PrepareError};
+use crate::routing::{ShardCount, Sharder, Token};
+use crate::statement::Consistency;
+use crate::statement::prepared::PreparedStatement;
+use crate::statement::unprepared::Statement;
+
+/// Suffix appended to the name of a table to get the name of its CDC log table.
+pub const LOG_TABLE_SUFFIX: &str = "_scylla_cdc_log";
+
+/// Name of the log table column holding the stream id (a `blob`).
+pub const STREAM_ID_COLUMN: &str = "cdc$stream_id";
+
+/// Name of the log table column holding the time of the change (a `timeuuid`).
+pub const TIME_COLUMN: &str = "cdc$time";
+
+/// Name of the log table column holding the position of the change within its batch (an `int`).
+pub const BATCH_SEQ_NO_COLUMN: &str = "cdc$batch_seq_no";
+
+/// Name of the log table column holding the kind of the change (a `tinyint`), see [CdcOperation].
+pub const OPERATION_COLUMN: &str = "cdc$operation";
+
+/// Name of the log table column holding the TTL of the change (a `bigint`).
+pub const TTL_COLUMN: &str = "cdc$ttl";
+
+/// Name of the log table column marking the last change of a batch (a `boolean`).
+pub const END_OF_BATCH_COLUMN: &str = "cdc$end_of_batch";
+
+// ScyllaDB uses the default value for the generations it creates.
+const DEFAULT_SHARDING_IGNORE_MSB: u8 = 12;
+
+/// Returns the name of the CDC log table of the given base table.
+pub fn log_table_name(base_table: &str) -> String {
+    format!("{base_table}{LOG_TABLE_SUFFIX}")
+}
+
+/// Returns the name of the base table of the given CDC log table,
+/// or `None` if the name is not a name of a CDC log table.
+pub fn base_table_name(log_table: &str) -> Option<&str> {
+    log_table
+        .strip_suffix(LOG_TABLE_SUFFIX)
+        .filter(|base_table| !base_table.is_empty())
+}
+
+/// The kind of change recorded in a CDC log row, stored in the [OPERATION_COLUMN].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum CdcOperation {
+    /// The state of the row before the change.
+    PreImage,
+    /// An `UPDATE` of a row.
+    RowUpdate,
+    /// An `INSERT` of a row.
+    RowInsert,
+    /// A deletion of a row.
+    RowDelete,
+    /// A deletion of a whole partition.
+    PartitionDelete,
+    /// The start of a deleted range of rows, inclusive.
+    RowRangeDeleteInclusiveLeftBound,
+    /// The start of a deleted range of rows, exclusive.
+    RowRangeDeleteExclusiveLeftBound,
+    /// The end of a deleted range of rows, inclusive.
+    RowRangeDeleteInclusiveRightBound,
+    /// The end of a deleted range of rows, exclusive.
+    RowRangeDeleteExclusiveRightBound,
+    /// The state of the row after the change.
+    PostImage,
+}
+
+impl TryFrom<i8> for CdcOperation {
+    type Error = i8;
+
+    fn try_from(value: i8) -> Result<Self, Self::Error> {
+        Ok(match value {
+            0 => Self::PreImage,
+            1 => Self::RowUpdate,
+            2 => Self::RowInsert,
+            3 => Self::RowDelete,
+            4 => Self::PartitionDelete,
+            5 => Self::RowRangeDeleteInclusiveLeftBound,
+            6 => Self::RowRangeDeleteExclusiveLeftBound,
+            7 => Self::RowRangeDeleteInclusiveRightBound,
+            8 => Self::RowRangeDeleteExclusiveRightBound,
+            9 => Self::PostImage,
+            _ => return Err(value),
+        })
+    }
+}
+
+/// Identifier of a CDC stream, the partition key of a CDC log table.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StreamId([u8; 16]);
+
+impl StreamId {
+    /// Creates a stream id from its binary representation.
+    pub fn new(bytes: [u8; 16]) -> Self {
+        Self(bytes)
+    }
+
+    /// Binary representation of the stream id, as stored in the [STREAM_ID_COLUMN].
+    pub fn as_bytes(&self) -> &[u8; 16] {
+        &self.0
+    }
+
+    /// The token of the stream, i.e. the token of the log table partition.
+    pub fn token(&self) -> Token {
+        let (token, _) = self.0.split_first_chunk::<8>().unwrap();
+        Token::new(i64::from_be_bytes(*token))
+    }
+}
+
+impl TryFrom<&[u8]> for StreamId {
+    type Error = CdcError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        bytes
+            .try_into()
+            .map(Self)
+            .map_err(|_| CdcError::InvalidStreamId(bytes.len()))
+    }
+}
+
+impl fmt::Debug for StreamId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "StreamId({self})")
+    }
+}
+
+impl fmt::Display for StreamId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x")?;
+        self.0.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+    }
+}
+
+/// The streams of a single token range of a [CdcGeneration].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CdcStreamRange {
+    end: Token,
+    streams: Vec<StreamId>,
+}
+
+impl CdcStreamRange {
+    /// Creates a token range ending at `end` (inclusive), with the given streams,
+    /// one per shard of the range's owner.
+    pub fn new(end: Token, streams: Vec<StreamId>) -> Self {
+        Self { end, streams }
+    }
+
+    /// The end of the token range, inclusive. The range starts right after
+    /// the end of the preceding range of the generation.
+    pub fn end(&self) -> Token {
+        self.end
+    }
+
+    /// The streams of the token range.
+    pub fn streams(&self) -> &[StreamId] {
+        &self.streams
+    }
+}
+
+/// A CDC generation: the set of streams in use since the given time.
+///
+/// The token ring is divided into ranges, each with one stream per shard
+/// of the node owning the range. A change to a partition is written to the stream
+/// of the partition's token range which belongs to the same shard as the partition.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CdcGeneration {
+    timestamp: CqlTimestamp,
+    expired: Option<CqlTimestamp>,
+    ranges: Vec<CdcStreamRange>,
+}
+
+impl CdcGeneration {
+    /// Creates a generation starting at `timestamp` from its token ranges.
+    /// The ranges are sorted by their ends.
+    pub fn new(timestamp: CqlTimestamp, mut ranges: Vec<CdcStreamRange>) -> Self {
+        ranges.sort_unstable_by_key(|range| range.end);
+        Self {
+            timestamp,
+            expired: None,
+            ranges,
+        }
+    }
+
+    /// Fetches all generations published by the cluster, in chronological order.
+    ///
+    /// Generations are read from the `system_distributed` keyspace.
+    pub async fn fetch_all(session: &Session) -> Result<Vec<CdcGeneration>, CdcError> {
+        let mut timestamps_statement = Statement::new(
+            "SELECT time, expired FROM system_distributed.cdc_generation_timestamps \
+            WHERE key = 'timestamps'",
+        );
+        timestamps_statement.set_consistency(Consistency::Quorum);
+        let mut timestamps: Vec<_> = session
+            .query_iter(timestamps_statement, ())
+            .await?
+            .rows_stream::<(CqlTimestamp, Option<CqlTimestamp>)>()?
+            .try_collect()
+            .await?;
+        timestamps.sort_unstable_by_key(|(timestamp, _)| timestamp.0);
+
+        let mut streams_statement = session
+            .prepare(
+                "SELECT range_end, streams FROM system_distributed.cdc_streams_descriptions_v2 \
+                WHERE time = ?",
+            )
+            .await?;
+        streams_statement.set_consistency(Consistency::Quorum);
+
+        let mut generations = Vec::with_capacity(timestamps.len());
+        for (timestamp, expired) in timestamps {
+            let rows: Vec<_> = session
+                .execute_iter(streams_statement.clone(), (timestamp,))
+                .await?
+                .rows_stream::<(i64, Vec<Vec<u8>>)>()?
+                .try_collect()
+                .await?;
+            let ranges = rows
+                .into_iter()
+                .map(|(end, streams)| {
+                    let streams = streams
+                        .iter()
+                        .map(|stream| StreamId::try_from(stream.as_slice()))
+                        .collect::<Result<_, _>>()?;
+                    Ok(CdcStreamRange::new(Token::new(end), streams))
+                })
+                .collect::<Result<_, CdcError>>()?;
+            let mut generation = CdcGeneration::new(timestamp, ranges);
+            generation.expired = expired;
+            generations.push(generation);
+        }
+        Ok(generations)
+    }
+
+    /// The time since which the generation is in use.
+    pub fn timestamp(&self) -> CqlTimestamp {
+        self.timestamp
+    }
+
+    /// The time at which the generation expired, if it did.
+    pub fn expired(&self) -> Option<CqlTimestamp> {
+        self.expired
+    }
+
+    /// The token ranges of the generation, sorted by their ends.
+    pub fn ranges(&self) -> &[CdcStreamRange] {
+        &self.ranges
+    }
+
+    /// All streams of the generation.
+    pub fn streams(&self) -> impl Iterator<Item = &StreamId> {
+        self.ranges.iter().flat_map(|range| range.streams.iter())
+    }
+
+    /// Returns the stream which the changes of a partition with the given token are written to.
+    ///
+    /// `sharding_ignore_msb` is the sharding parameter of the node owning the token,
+    /// see [Sharder::msb_ignore].
+    /// Returns `None` only if the generation has no streams.
+    pub fn stream_for_token(&self, token: Token, sharding_ignore_msb: u8) -> Option<StreamId> {
+        // The range with the smallest end not less than the token; the ring wraps around
+        // to the first range.
+        let idx = self.ranges.partition_point(|range| range.end < token);
+        let range = self.ranges.get(idx).or_else(|| self.ranges.first())?;
+        let shard_count = ShardCount::new(u16::try_from(range.streams.len()).ok()?)?;
+        let sharder = Sharder::new(shard_count, sharding_ignore_msb);
+        let shard = sharder.shard_of(token);
+        // Streams are stored as a set, so they are not ordered by shard: find the stream
+        // whose token belongs to the same shard.
+        range
+            .streams
+            .iter()
+            .find(|stream| sharder.shard_of(stream.token()) == shard)
+            .or_else(|| range.streams.first())
+            .copied()
+    }
+
+    /// Returns the stream which the changes of the given partition of a base table are written to.
+    ///
+    /// The token of the partition is computed with [ClusterState::compute_token].
+    /// Returns `None` only if the generation has no streams.
+    pub fn stream_for_partition_key(
+        &self,
+        cluster_state: &ClusterState,
+        keyspace: &str,
+        table: &str,
+        partition_key: &dyn SerializeRow,
+    ) -> Result<Option<StreamId>, ClusterStateTokenError> {
+        let token = cluster_state.compute_token(keyspace, table, partition_key)?;
+        let sharding_ignore_msb = cluster_state
+            .get_nodes_info()
+            .iter()
+            .find_map(|node| node.sharder())
+            .map_or(DEFAULT_SHARDING_IGNORE_MSB, |sharder| sharder.msb_ignore);
+        Ok(self.stream_for_token(token, sharding_ignore_msb))
+    }
+}
+
+/// Reads the changes recorded in a CDC log table, one stream at a time.
+///
+/// The statement is routed to the replicas of the stream, like any other
+/// prepared statement.
+#[derive(Clone, Debug)]
+pub struct CdcLogReader {
+    statement: PreparedStatement,
+}
+
+impl CdcLogReader {
+    /// Prepares a reader of the CDC log table of the given base table,
+    /// selecting all columns of the log table.
+    pub async fn prepare(
+        session: &Session,
+        keyspace: &str,
+        base_table: &str,
+    ) -> Result<Self, PrepareError> {
+        Self::prepare_with_selector(session, keyspace, base_table, "*").await
+    }
+
+    /// Prepares a reader of the CDC log table of the given base table,
+    /// selecting the given columns or expressions, e.g. `"\"cdc$time\", pk, v"`.
+    pub async fn prepare_with_selector(
+        session: &Session,
+        keyspace: &str,
+        base_table: &str,
+        selector: &str,
+    ) -> Result<Self, PrepareError> {
+        let statement = session
+            .prepare(log_query(selector, keyspace, base_table))
+            .await?;
+        Ok(Self { statement })
+    }
+
+    /// The prepared statement used by the reader.
+    ///
+    /// Its bind markers are the stream id and the bounds of the time window.
+    pub fn statement(&self) -> &PreparedStatement {
+        &self.statement
+    }
+
+    /// Reads the changes of the stream recorded from `from` (inclusive) to `to` (exclusive),
+    /// in the order they were made.
+    pub async fn read(
+        &self,
+        session: &Session,
+        stream: &StreamId,
+        from: CqlTimestamp,
+        to: CqlTimestamp,
+    ) -> Result<QueryPager, PagerExecutionError> {
+        session
+            .execute_iter(
+                self.statement.clone(),
+                (stream.as_bytes().as_slice(), from, to),
+            )
+            .await
+    }
+}
+
+fn log_query(selector: &str, keyspace: &str, base_table: &str) -> String {
+    format!(
+        "SELECT {} FROM {}.{} WHERE {} = ? AND {} >= minTimeuuid(?) AND {} < minTimeuuid(?)",
+        selector,
+        quote_identifier(keyspace),
+        quote_identifier(&log_table_name(base_table)),
+        quote_identifier(STREAM_ID_COLUMN),
+        quote_identifier(TIME_COLUMN),
+        quote_identifier(TIME_COLUMN),
+    )
+}
+
+fn quote_identifier(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+/// An error returned by the functions of the [cdc](self) module.
+#[derive(Error, Debug, Clone)]
+#[non_exhaustive]
+pub enum CdcError {
+    /// Failed to prepare a statement.
+    #[error("Failed to prepare a statement: {0}")]
+    PrepareError(#[from] PrepareError),
+
+    /// Failed to execute a statement.
+    #[error("Failed to execute a statement: {0}")]
+    PagerExecutionError(#[from] PagerExecutionError),
+
+    /// Failed to type check the selected columns.
+    #[error("Failed to type check the selected columns: {0}")]
+    TypeCheckError(#[from] TypeCheckError),
+
+    /// Failed to fetch or deserialize a row.
+    #[error("Failed to fetch a row: {0}")]
+    NextRowError(#[from] NextRowError),
+
+    /// A stream id does not have 16 bytes.
+    #[error("Invalid stream id: expected 16 bytes, got {0}")]
+    InvalidStreamId(usize),
+}
+
+#[cfg(test)]
+mod tests {
+    use scylla_cql::value::CqlTimestamp;
+
+    use super::{
+        CdcGeneration, CdcOperation, CdcStreamRange, StreamId, base_table_name, log_query,
+        log_table_name,
+    };
+    use crate::routing::{ShardCount, Sharder, Token};
+
+    fn stream_id(token: i64, suffix: u8) -> StreamId {
+        let mut bytes = [0; 16];
+        bytes[..8].copy_from_slice(&token.to_be_bytes());
+        bytes[15] = suffix;
+        StreamId::new(bytes)
+    }
+
+    #[test]
+    fn test_names() {
+        assert_eq!(log_table_name("t"), "t_scylla_cdc_log");
+        assert_eq!(base_table_name("t_scylla_cdc_log"), Some("t"));
+        assert_eq!(base_table_name("_scylla_cdc_log"), None);
+        assert_eq!(base_table_name("t"), None);
+        assert_eq!(
+            log_query("*", "ks", "t"),
+            "SELECT * FROM \"ks\".\"t_scylla_cdc_log\" WHERE \"cdc$stream_id\" = ? \
+            AND \"cdc$time\" >= minTimeuuid(?) AND \"cdc$time\" < minTimeuuid(?)"
+        );
+        assert_eq!(CdcOperation::try_from(2), Ok(CdcOperation::RowInsert));
+        assert_eq!(CdcOperation::try_from(10), Err(10));
+    }
+
+    #[test]
+    fn test_stream_id() {
+        let id = stream_id(-2, 7);
+        assert_eq!(id.token(), Token::new(-2));
+        assert_eq!(id.to_string(), "0xfffffffffffffffe0000000000000007");
+        assert_eq!(StreamId::try_from(id.as_bytes().as_slice()).unwrap(), id);
+        assert!(StreamId::try_from([0; 15].as_slice()).is_err());
+    }
+
+    #[test]
+    fn test_stream_for_token() {
+        let msb = 12;
+        let sharder = Sharder::new(ShardCount::new(2).unwrap(), msb);
+        // Finds tokens in (start, end] belonging to the given shard.
+        let token_on_shard = |start: i64, end: i64, shard| {
+            let step = ((end as i128 - start as i128) / 1000) as i64;
+            (1..=1000)
+                .map(|i| Token::new(start + i * step))
+                .find(|token| sharder.shard_of(*token) == shard)
+                .unwrap()
+        };
+        // Ranges (MIN, -B], (-B, B] and (B, MAX], wrapping around.
+        const B: i64 = 1 << 55;
+        let bounds = [(i64::MIN, -B), (-B, B), (B, i64::MAX)];
+        let ranges = bounds
+            .iter()
+            .enumerate()
+            .map(|(i, &(start, end))| {
+                // Stored out of shard order, like in a set ordered by bytes.
+                let mut streams = (0..2)
+                    .map(|shard| stream_id(token_on_shard(start, end, shard).value(), i as u8))
+                    .collect::<Vec<_>>();
+                streams.reverse();
+                CdcStreamRange::new(Token::new(end), streams)
+            })
+            .rev()
+            .collect();
+        let generation = CdcGeneration::new(CqlTimestamp(0), ranges);
+        assert_eq!(generation.ranges()[0].end(), Token::new(-B));
+        assert_eq!(generation.streams().count(), 6);
+
+        for (i, &(start, end)) in bounds.iter().enumerate() {
+            for shard in 0..2 {
+                let token = token_on_shard(start, end, shard);
+                let stream = generation.stream_for_token(token, msb).unwrap();
+                assert_eq!(stream.as_bytes()[15], i as u8);
+                assert_eq!(sharder.shard_of(stream.token()), shard);
+            }
+        }
+        // The end of a range belongs to it.
+        let stream = generation.stream_for_token(Token::new(B), msb).unwrap();
+        assert_eq!(stream.as_bytes()[15], 1);
+        // The ring wraps around.
+        let generation = CdcGeneration::new(
+            CqlTimestamp(0),
+            vec![CdcStreamRange::new(Token::new(0), vec![stream_id(-5, 0)])],
+        );
+        assert_eq!(
+            generation.stream_for_token(Token::new(10), msb),
+            Some(stream_id(-5, 0))
+        );
+        assert_eq!(
+            CdcGeneration::new(CqlTimestamp(0), vec![]).stream_for_token(Token::new(0), msb),
+            None
+        );
+    }
+}
@@ -172,7 +172,7 @@ use crate::policies::retry::RetryPolicy;
 use crate::policies::speculative_execution::SpeculativeExecutionPolicy;
 
 pub(crate) mod defaults {
-    use super::ExecutionProfileInner;
+    use super::{ExecutionProfileInner, RequestPriority};
     use crate::policies::load_balancing::{self, LoadBalancingPolicy};
     use crate::policies::retry::{DefaultRetryPolicy, RetryPolicy};
     use crate::policies::speculative_execution::SpeculativeExecutionPolicy;
@@ -198,6 +198,15 @@ pub(crate) mod defaults {
     pub(crate) fn speculative_execution_policy() -> Option<Arc<dyn SpeculativeExecutionPolicy>> {
         None
     }
+    pub(crate) fn compression_enabled() -> bool {
+        true
+    }
+    pub(crate) fn priority() -> RequestPriority {
+        RequestPriority::Normal
+    }
+    pub(crate) fn execute_as() -> Option<Arc<str>> {
+        None
+    }
 
     impl Default for ExecutionProfileInner {
         fn default() -> Self {
@@ -208,11 +217,34 @@ pub(crate) mod defaults {
                 load_balancing_policy: load_balancing_policy(),
                 retry_policy: retry_policy(),
                 speculative_execution_policy: speculative_execution_policy(),
+                compression_enabled: compression_enabled(),
+                priority: priority(),
+                execute_as: execute_as(),
             }
         }
     }
 }
 
+/// Priority of requests, relative to other requests sent over the same connection.
+///
+/// Requests waiting to be sent over a connection are queued separately for each
+/// priority. When requests of several priorities are waiting, they are sent
+/// in a weighted round-robin fashion: for each low priority request, up to two
+/// normal and four high priority requests are sent. This way, e.g. a big scan
+/// done with low priority cannot starve latency-sensitive requests sharing its
+/// connections, while still making progress.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum RequestPriority {
+    /// Requests sent before the others.
+    High,
+    /// The default priority.
+    #[default]
+    Normal,
+    /// Requests which give way to the others, e.g. big scans or background jobs.
+    Low,
+}
+
 /// `ExecutionProfileBuilder` is used to create new `ExecutionProfile`s
 /// # Example
 ///
@@ -237,6 +269,9 @@ pub struct ExecutionProfileBuilder {
     load_balancing_policy: Option<Arc<dyn LoadBalancingPolicy>>,
     retry_policy: Option<Arc<dyn RetryPolicy>>,
     speculative_execution_policy: Option<Option<Arc<dyn SpeculativeExecutionPolicy>>>,
+    compression_enabled: Option<bool>,
+    priority: Option<RequestPriority>,
+    execute_as: Option<Option<Arc<str>>>,
 }
 
 impl ExecutionProfileBuilder {
@@ -351,6 +386,74 @@ impl ExecutionProfileBuilder {
         self
     }
 
+    /// Specifies whether requests executed with this profile may be compressed.
+    /// If set to false, requests are sent uncompressed even if compression
+    /// was negotiated with the cluster (see [SessionBuilder::compression](crate::client::session_builder::SessionBuilder::compression)).
+    /// This is useful e.g. for latency-sensitive workloads with small requests,
+    /// for which compression costs more CPU time than it saves on the network.
+    /// The default is true.
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::execution_profile::ExecutionProfile;
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let profile: ExecutionProfile = ExecutionProfile::builder()
+    ///     .compression_enabled(false)
+    ///     .build();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn compression_enabled(mut self, compression_enabled: bool) -> Self {
+        self.compression_enabled = Some(compression_enabled);
+        self
+    }
+
+    /// Sets the priority of requests executed with this profile, relative to other
+    /// requests sent over the same connection.
+    /// The default is [RequestPriority::Normal].
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::execution_profile::{ExecutionProfile, RequestPriority};
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// // A profile for big scans, which should not slow down the rest of the workload.
+    /// let profile: ExecutionProfile = ExecutionProfile::builder()
+    ///     .priority(RequestPriority::Low)
+    ///     .build();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn priority(mut self, priority: RequestPriority) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// Makes requests executed with this profile be executed on behalf of another role
+    /// (proxy execution), which lets e.g. a multi-tenant service share one session
+    /// between its tenants while each request is authorized as its tenant.
+    ///
+    /// The authenticated user needs a permission to execute as the given role. The role
+    /// is sent in the `ProxyExecute` entry of the custom payload of each request.
+    /// Note that servers which do not support proxy execution, such as ScyllaDB,
+    /// ignore it, and execute the requests as the authenticated user.
+    ///
+    /// The default is `None`, which means no proxy execution.
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::execution_profile::ExecutionProfile;
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let profile: ExecutionProfile = ExecutionProfile::builder()
+    ///     .execute_as(Some("tenant_a".to_owned()))
+    ///     .build();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn execute_as(mut self, role: Option<String>) -> Self {
+        self.execute_as = Some(role.map(Into::into));
+        self
+    }
+
     /// Builds the ExecutionProfile after setting all the options.
     ///
     /// # Example
@@ -381,6 +484,11 @@ impl ExecutionProfileBuilder {
             speculative_execution_policy: self
                 .speculative_execution_policy
                 .unwrap_or_else(defaults::speculative_execution_policy),
+            compression_enabled: self
+                .compression_enabled
+                .unwrap_or_else(defaults::compression_enabled),
+            priority: self.priority.unwrap_or_else(defaults::priority),
+            execute_as: self.execute_as.unwrap_or_else(defaults::execute_as),
         }))
     }
 }
@@ -410,6 +518,10 @@ pub(crate) struct ExecutionProfileInner {
     pub(crate) load_balancing_policy: Arc<dyn LoadBalancingPolicy>,
     pub(crate) retry_policy: Arc<dyn RetryPolicy>,
     pub(crate) speculative_execution_policy: Option<Arc<dyn SpeculativeExecutionPolicy>>,
+
+    pub(crate) compression_enabled: bool,
+    pub(crate) priority: RequestPriority,
+    pub(crate) execute_as: Option<Arc<str>>,
 }
 
 impl ExecutionProfileInner {
@@ -422,6 +534,9 @@ impl ExecutionProfileInner {
             load_balancing_policy: Some(self.load_balancing_policy.clone()),
             retry_policy: Some(self.retry_policy.clone()),
             speculative_execution_policy: Some(self.speculative_execution_policy.clone()),
+            compression_enabled: Some(self.compression_enabled),
+            priority: Some(self.priority),
+            execute_as: Some(self.execute_as.clone()),
         }
     }
 }
@@ -440,6 +555,9 @@ impl ExecutionProfile {
             load_balancing_policy: None,
             retry_policy: None,
             speculative_execution_policy: None,
+            compression_enabled: None,
+            priority: None,
+            execute_as: None,
         }
     }
 
@@ -490,6 +608,21 @@ impl ExecutionProfile {
     pub fn get_speculative_execution_policy(&self) -> Option<&Arc<dyn SpeculativeExecutionPolicy>> {
         self.0.speculative_execution_policy.as_ref()
     }
+
+    /// Gets whether requests executed with this profile may be compressed.
+    pub fn get_compression_enabled(&self) -> bool {
+        self.0.compression_enabled
+    }
+
+    /// Gets the priority of requests executed with this profile.
+    pub fn get_priority(&self) -> RequestPriority {
+        self.0.priority
+    }
+
+    /// Gets the role on behalf of which requests executed with this profile are executed.
+    pub fn get_execute_as(&self) -> Option<&str> {
+        self.0.execute_as.as_deref()
+    }
 }
 
 /// A handle that points to an ExecutionProfile.
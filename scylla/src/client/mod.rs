@@ -14,6 +14,8 @@
 //!   options relevant when executing a request against the DB.
 //! - [QueryPager](pager::QueryPager) and [TypedRowStream](pager::TypedRowStream) - entities that provide
 //!   automated transparent paging of a query.
+//! - `SessionService` (with the `tower-service-03` feature) - an adapter exposing a [Session](session::Session)
+//!   as a `tower::Service`.
 
 pub mod execution_profile;
 
@@ -28,6 +30,12 @@ pub mod session;
 
 pub mod session_builder;
 
+#[cfg(feature = "tower-service-03")]
+pub mod tower;
+
 pub use scylla_cql::frame::Compression;
 
-pub use crate::network::{PoolSize, WriteCoalescingDelay};
+pub use crate::network::{
+    DynamicPoolSize, KeepaliveRequest, PoolSize, PoolWarmup, Proxy, ShardAwarePortUsage,
+    WriteCoalescingDelay,
+};
@@ -23,24 +23,35 @@ use scylla_cql::frame::types::SerialConsistency;
 use scylla_cql::serialize::row::SerializedValues;
 use std::result::Result;
 use thiserror::Error;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
 
 use crate::client::execution_profile::ExecutionProfileInner;
 use crate::cluster::{ClusterState, NodeRef};
 use crate::deserialize::DeserializeOwnedRow;
+#[cfg(feature = "metrics")]
+use crate::errors::DbError;
 use crate::errors::{RequestAttemptError, RequestError};
 use crate::frame::response::result;
-use crate::network::Connection;
+use crate::network::{Connection, RequestSettings};
 use crate::observability::driver_tracing::RequestSpan;
 use crate::observability::history::{self, HistoryListener};
+use crate::observability::instrumentation::{TraceParent, TracingInstrumentation};
+use crate::observability::log_targets;
 #[cfg(feature = "metrics")]
 use crate::observability::metrics::Metrics;
+use crate::observability::request_observer::{
+    AttemptEnd, ObservedRequestId, RequestEnd, RequestObserver, RequestStart,
+};
+use crate::observability::slow_query_log::{LoggedStatement, SlowQueryLogger, SlowQueryTracker};
 use crate::policies::load_balancing::{self, LoadBalancingPolicy, RoutingInfo};
 use crate::policies::retry::{RequestInfo, RetryDecision, RetrySession};
 use crate::response::query_result::ColumnSpecs;
 use crate::response::{NonErrorQueryResponse, QueryResponse};
+use crate::routing::Shard;
+use crate::statement::StatementTags;
 use crate::statement::prepared::{PartitionKeyError, PreparedStatement};
 use crate::statement::unprepared::Statement;
+use crate::utils::runtime;
 use tracing::{Instrument, trace, trace_span, warn};
 use uuid::Uuid;
 
@@ -71,6 +82,10 @@ pub(crate) struct PreparedPagerConfig {
     pub(crate) cluster_state: Arc<ClusterState>,
     #[cfg(feature = "metrics")]
     pub(crate) metrics: Arc<Metrics>,
+    pub(crate) request_observer: Option<Arc<dyn RequestObserver>>,
+    pub(crate) tracing_instrumentation: Option<TracingInstrumentation>,
+    pub(crate) trace_parent: Option<TraceParent>,
+    pub(crate) slow_query_logger: Option<SlowQueryLogger>,
 }
 
 // A separate module is used here so that the parent module cannot construct
@@ -163,7 +178,7 @@ mod timeouter {
 
         /// Returns the instant at which the timeout will elapse.
         ///
-        /// This can be used with `tokio::time::timeout_at`.
+        /// This can be used with `runtime::timeout_at`.
         pub(super) fn deadline(&self) -> Instant {
             self.timeout_instant
         }
@@ -200,9 +215,17 @@ struct PagerWorker<'a, QueryFunc, SpanCreatorFunc> {
     paging_state: PagingState,
 
     history_listener: Option<Arc<dyn HistoryListener>>,
+    tags: StatementTags,
     current_request_id: Option<history::RequestId>,
     current_attempt_id: Option<history::AttemptId>,
 
+    request_observer: Option<Arc<dyn RequestObserver>>,
+    current_observed_request: Option<(ObservedRequestId, std::time::Instant)>,
+
+    statement_text: &'a str,
+    slow_query_logger: Option<SlowQueryLogger>,
+    current_slow_query: Option<SlowQueryTracker>,
+
     parent_span: tracing::Span,
     span_creator: SpanCreatorFunc,
 }
@@ -217,8 +240,11 @@ where
     async fn work(mut self, cluster_state: Arc<ClusterState>) -> PageSendAttemptedProof {
         let load_balancer = Arc::clone(&self.load_balancing_policy);
         let statement_info = self.routing_info.clone();
-        let query_plan =
-            load_balancing::Plan::new(load_balancer.as_ref(), &statement_info, &cluster_state);
+        let query_plan = load_balancing::SaturationAwarePlan::new(
+            load_balancing::Plan::new(load_balancer.as_ref(), &statement_info, &cluster_state),
+            #[cfg(feature = "metrics")]
+            Arc::clone(&self.metrics),
+        );
 
         let mut last_error: RequestError = RequestError::EmptyPlan;
         let mut current_consistency: Consistency = self.query_consistency;
@@ -238,6 +264,7 @@ where
                 Ok(connection) => connection,
                 Err(e) => {
                     trace!(
+                        target: log_targets::REQUEST,
                         parent: &span,
                         error = %e,
                         "Choosing connection failed"
@@ -249,7 +276,7 @@ where
             };
 
             'same_node_retries: loop {
-                trace!(parent: &span, "Execution started");
+                trace!(target: log_targets::REQUEST, parent: &span, "Execution started");
 
                 let coordinator =
                     Coordinator::new(node, node.sharder().is_some().then_some(shard), &connection);
@@ -265,7 +292,7 @@ where
 
                 let request_error: RequestAttemptError = match queries_result {
                     Ok(Ok(proof)) => {
-                        trace!(parent: &span, "Request succeeded");
+                        trace!(target: log_targets::REQUEST, parent: &span, "Request succeeded");
                         // query_pages returned Ok, so we are guaranteed
                         // that it attempted to send at least one page
                         // through self.sender and we can safely return now.
@@ -273,16 +300,21 @@ where
                     }
                     Ok(Err(error)) => {
                         trace!(
+                            target: log_targets::REQUEST,
                             parent: &span,
                             error = %error,
                             "Request failed"
                         );
+                        if let RequestAttemptError::UnableToAllocStreamId = error {
+                            node.record_stream_ids_exhausted(shard);
+                        }
                         error
                     }
                     Err(RequestTimeoutError(timeout)) => {
                         let request_error = RequestError::RequestTimeout(timeout);
                         self.log_request_error(&request_error);
                         trace!(
+                            target: log_targets::REQUEST,
                             parent: &span,
                             error = %request_error,
                             "Request timed out"
@@ -295,6 +327,14 @@ where
                     }
                 };
 
+                #[cfg(feature = "metrics")]
+                if let RequestAttemptError::DbError(DbError::RateLimitReached { .. }, _) =
+                    &request_error
+                {
+                    self.metrics
+                        .inc_rate_limited_requests(self.routing_info.table);
+                }
+
                 // Use retry policy to decide what to do next
                 let query_info = RequestInfo {
                     error: &request_error,
@@ -304,12 +344,17 @@ where
 
                 let retry_decision = self.retry_session.decide_should_retry(query_info);
                 trace!(
+                    target: log_targets::REQUEST,
                     parent: &span,
                     retry_decision = ?retry_decision
                 );
 
                 self.log_attempt_error(&request_error, &retry_decision);
 
+                #[cfg(feature = "metrics")]
+                self.metrics
+                    .log_retry_decision(retry_decision.kind(current_consistency), &request_error);
+
                 last_error = request_error.into();
 
                 match retry_decision {
@@ -319,15 +364,32 @@ where
                         current_consistency = cl.unwrap_or(current_consistency);
                         continue 'same_node_retries;
                     }
+                    RetryDecision::RetrySameTargetAfter(cl, delay) => {
+                        #[cfg(feature = "metrics")]
+                        self.metrics.inc_retries_num();
+                        current_consistency = cl.unwrap_or(current_consistency);
+                        runtime::sleep(delay).await;
+                        continue 'same_node_retries;
+                    }
                     RetryDecision::RetryNextTarget(cl) => {
                         #[cfg(feature = "metrics")]
                         self.metrics.inc_retries_num();
                         current_consistency = cl.unwrap_or(current_consistency);
                         continue 'nodes_in_plan;
                     }
+                    RetryDecision::RetryNextTargetAfter(cl, delay) => {
+                        #[cfg(feature = "metrics")]
+                        self.metrics.inc_retries_num();
+                        current_consistency = cl.unwrap_or(current_consistency);
+                        runtime::sleep(delay).await;
+                        continue 'nodes_in_plan;
+                    }
                     RetryDecision::DontRetry => break 'nodes_in_plan,
                     RetryDecision::IgnoreWriteError => {
-                        warn!("Ignoring error during fetching pages; stopping fetching.");
+                        warn!(
+                            target: log_targets::REQUEST,
+                            "Ignoring error during fetching pages; stopping fetching.",
+                        );
                         // If we are here then, most likely, we didn't send
                         // anything through the self.sender channel.
                         // Although we are in an awkward situation (_iter
@@ -366,7 +428,9 @@ where
     ) -> Result<Result<PageSendAttemptedProof, RequestAttemptError>, RequestTimeoutError> {
         loop {
             let request_span = (self.span_creator)();
-            match self
+            request_span.record_tags(&self.tags);
+            request_span.record_routing_info(self.routing_info.table, consistency);
+            let page_result = self
                 .query_one_page(
                     connection,
                     consistency,
@@ -374,9 +438,14 @@ where
                     coordinator.clone(),
                     &request_span,
                 )
-                .instrument(request_span.span().clone())
-                .await
-            {
+                .instrument(request_span.entered_span().clone())
+                .await;
+            request_span.record_outcome::<dyn std::fmt::Display>(match &page_result {
+                Ok(Ok(_)) => Ok(()),
+                Ok(Err(request_attempt_error)) => Err(request_attempt_error),
+                Err(request_timeout_error) => Err(request_timeout_error),
+            });
+            match page_result {
                 Ok(Ok(ControlFlow::Break(proof))) => {
                     // Successfully queried the last remaining page.
                     return Ok(Ok(proof));
@@ -414,32 +483,45 @@ where
 
         let connect_address = connection.get_connect_address();
         trace!(
+            target: log_targets::REQUEST,
             connection = %connect_address,
             "Sending"
         );
-        self.log_attempt_start(connect_address);
+        self.log_attempt_start(node, connect_address);
+        let attempt_span = request_span.new_attempt_span(
+            connect_address,
+            node.host_id,
+            connection
+                .get_shard_info()
+                .as_ref()
+                .map(|info| Shard::from(info.shard)),
+            consistency,
+        );
+        let in_flight = node.start_request();
 
         let runner = async {
             (self.page_query)(connection.clone(), consistency, self.paging_state.clone())
                 .await
                 .and_then(QueryResponse::into_non_error_query_response)
-        };
+        }
+        .instrument(attempt_span.span().clone());
         let query_response = match self.timeouter {
-            Some(ref timeouter) => {
-                match tokio::time::timeout_at(timeouter.deadline(), runner).await {
-                    Ok(res) => res,
-                    Err(_) /* tokio::time::error::Elapsed */ => {
-                        #[cfg(feature = "metrics")]
-                        self.metrics.inc_request_timeouts();
-                        return Err(RequestTimeoutError(timeouter.timeout_duration()));
-                    }
+            Some(ref timeouter) => match runtime::timeout_at(timeouter.deadline(), runner).await {
+                Ok(res) => res,
+                Err(runtime::Elapsed) => {
+                    #[cfg(feature = "metrics")]
+                    self.metrics.inc_request_timeouts();
+                    let error = RequestTimeoutError(timeouter.timeout_duration());
+                    attempt_span.record_outcome(Err(&error));
+                    return Err(error);
                 }
-            }
+            },
 
             None => runner.await,
         };
 
         let elapsed = query_start.elapsed();
+        drop(in_flight);
 
         request_span.record_shard_id(connection);
 
@@ -453,13 +535,20 @@ where
                 ..
             }) => {
                 #[cfg(feature = "metrics")]
-                let _ = self.metrics.log_query_latency(elapsed.as_millis() as u64);
+                {
+                    let latency = elapsed.as_millis() as u64;
+                    let _ = self.metrics.log_query_latency(latency);
+                    let _ = self.metrics.log_node_latency(node.host_id, latency);
+                }
+                self.observe_attempt_end(&coordinator, elapsed, Ok(()));
+                attempt_span.record_outcome::<RequestAttemptError>(Ok(()));
                 self.log_attempt_success();
                 self.log_request_success();
                 self.load_balancing_policy
                     .on_request_success(&self.routing_info, elapsed, node);
 
                 request_span.record_raw_rows_fields(&rows);
+                request_span.record_tracing_id(tracing_id);
 
                 let received_page = ReceivedPage {
                     rows,
@@ -493,6 +582,9 @@ where
             Err(err) => {
                 #[cfg(feature = "metrics")]
                 self.metrics.inc_failed_paged_queries();
+                self.observe_attempt_end(&coordinator, elapsed, Err(&err));
+                attempt_span.record_outcome(Err(&err));
+                node.record_request_error();
                 self.load_balancing_policy.on_request_failure(
                     &self.routing_info,
                     elapsed,
@@ -509,6 +601,12 @@ where
                 // We have most probably sent a modification statement (e.g. INSERT or UPDATE),
                 // so let's return an empty iterator as suggested in #631.
 
+                self.observe_attempt_end(&coordinator, elapsed, Ok(()));
+                attempt_span.record_outcome::<RequestAttemptError>(Ok(()));
+                self.log_attempt_success();
+                self.log_request_success();
+                request_span.record_tracing_id(tracing_id);
+
                 // We must attempt to send something because the iterator expects it.
                 let (proof, _) = self
                     .sender
@@ -521,6 +619,9 @@ where
                 self.metrics.inc_failed_paged_queries();
                 let err =
                     RequestAttemptError::UnexpectedResponse(response.response.to_response_kind());
+                self.observe_attempt_end(&coordinator, elapsed, Err(&err));
+                attempt_span.record_outcome(Err(&err));
+                node.record_request_error();
                 self.load_balancing_policy.on_request_failure(
                     &self.routing_info,
                     elapsed,
@@ -533,15 +634,36 @@ where
     }
 
     fn log_request_start(&mut self) {
+        self.current_slow_query = self
+            .slow_query_logger
+            .as_ref()
+            .map(SlowQueryLogger::start_request);
+        if let Some(observer) = &self.request_observer {
+            let id = ObservedRequestId::next();
+            observer.on_request_start(&RequestStart {
+                id,
+                table: self.routing_info.table,
+                tags: &self.tags,
+            });
+            self.current_observed_request = Some((id, std::time::Instant::now()));
+        }
+
         let history_listener: &dyn HistoryListener = match &self.history_listener {
             Some(hl) => &**hl,
             None => return,
         };
 
-        self.current_request_id = Some(history_listener.log_request_start());
+        let request_id = history_listener.log_request_start();
+        if !self.tags.is_empty() {
+            history_listener.log_request_tags(request_id, &self.tags);
+        }
+        history_listener.log_request_statement(request_id, self.statement_text);
+        self.current_request_id = Some(request_id);
     }
 
     fn log_request_success(&mut self) {
+        self.observe_request_end(Ok(()));
+
         let history_listener: &dyn HistoryListener = match &self.history_listener {
             Some(hl) => &**hl,
             None => return,
@@ -556,6 +678,8 @@ where
     }
 
     fn log_request_error(&mut self, error: &RequestError) {
+        self.observe_request_end(Err(error));
+
         let history_listener: &dyn HistoryListener = match &self.history_listener {
             Some(hl) => &**hl,
             None => return,
@@ -569,7 +693,11 @@ where
         history_listener.log_request_error(request_id, error);
     }
 
-    fn log_attempt_start(&mut self, node_addr: SocketAddr) {
+    fn log_attempt_start(&mut self, node: NodeRef<'_>, node_addr: SocketAddr) {
+        if let Some(tracker) = &self.current_slow_query {
+            tracker.record_attempt(node);
+        }
+
         let history_listener: &dyn HistoryListener = match &self.history_listener {
             Some(hl) => &**hl,
             None => return,
@@ -584,6 +712,53 @@ where
             Some(history_listener.log_attempt_start(request_id, None, node_addr));
     }
 
+    fn observe_request_end(&mut self, result: Result<(), &RequestError>) {
+        if let (Some(logger), Some(tracker)) =
+            (&self.slow_query_logger, self.current_slow_query.take())
+        {
+            logger.finish_request(
+                &tracker,
+                LoggedStatement::Text(self.statement_text),
+                self.routing_info.token,
+                self.routing_info.table,
+                &self.tags,
+                result,
+            );
+        }
+
+        let (Some(observer), Some((id, start))) =
+            (&self.request_observer, self.current_observed_request.take())
+        else {
+            return;
+        };
+        observer.on_request_end(&RequestEnd {
+            id,
+            latency: start.elapsed(),
+            tags: &self.tags,
+            result,
+        });
+    }
+
+    fn observe_attempt_end(
+        &self,
+        coordinator: &Coordinator,
+        latency: Duration,
+        result: Result<(), &RequestAttemptError>,
+    ) {
+        let (Some(observer), Some((request_id, _))) =
+            (&self.request_observer, self.current_observed_request)
+        else {
+            return;
+        };
+        observer.on_attempt_end(&AttemptEnd {
+            request_id,
+            node: coordinator.node(),
+            shard: coordinator.shard(),
+            latency,
+            result,
+        });
+    }
+
     fn log_attempt_success(&mut self) {
         let history_listener: &dyn HistoryListener = match &self.history_listener {
             Some(hl) => &**hl,
@@ -662,14 +837,12 @@ where
                     .and_then(QueryResponse::into_non_error_query_response)
             };
             let response_res = match self.timeout {
-                Some(timeout) => {
-                    match tokio::time::timeout(timeout, runner).await {
-                        Ok(res) => res,
-                        Err(_) /* tokio::time::error::Elapsed */ => {
-                            return Err(RequestTimeoutError(timeout));
-                        }
+                Some(timeout) => match runtime::timeout(timeout, runner).await {
+                    Ok(res) => res,
+                    Err(runtime::Elapsed) => {
+                        return Err(RequestTimeoutError(timeout));
                     }
-                }
+                },
 
                 None => runner.await,
             };
@@ -726,6 +899,8 @@ where
     }
 }
 
+type RowTypeCheck = fn(&[result::ColumnSpec<'_>]) -> Result<(), TypeCheckError>;
+
 /// An intermediate object that allows to construct a stream over a query
 /// that is asynchronously paged in the background.
 ///
@@ -733,12 +908,41 @@ where
 /// needs to be cast into a typed stream. This is done by use of `rows_stream()` method.
 /// As the method is generic over the target type, the turbofish syntax
 /// can come in handy there, e.g. `query_pager.rows_stream::<(i32, String, Uuid)>()`.
+/// Rows of types borrowing from the received pages, e.g. `(i32, &str)`, can be
+/// deserialized one by one using the `next_row()` method instead.
 #[derive(Debug)]
 pub struct QueryPager {
     current_page: RawRowLendingIterator,
+    // Type check of the row type which the current page was type-checked against by `next_row`.
+    // Rows may borrow from the page, so their types are not `'static` and have no `TypeId`.
+    // Instead, the type check function itself identifies the check which was performed.
+    checked_row_type: Option<RowTypeCheck>,
     page_receiver: mpsc::Receiver<Result<ReceivedPage, NextPageError>>,
     tracing_ids: Vec<Uuid>,
     request_coordinators: Vec<Coordinator>,
+    progress: PagerProgress,
+    progress_sender: watch::Sender<PagerProgress>,
+}
+
+/// Progress of a paged query, as seen by the consumer of a [QueryPager].
+///
+/// Can be used to display progress of long scans, or to enforce custom limits on them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct PagerProgress {
+    /// Number of rows returned from the pager so far.
+    pub rows_yielded: u64,
+    /// Number of pages received so far, including empty pages.
+    pub pages_fetched: u64,
+    /// Number of bytes of result metadata and rows received so far.
+    pub bytes_received: u64,
+}
+
+impl PagerProgress {
+    fn record_page(&mut self, page: &DeserializedMetadataAndRawRows) {
+        self.pages_fetched += 1;
+        self.bytes_received += page.metadata_and_rows_bytes_size() as u64;
+    }
 }
 
 // QueryPager is not an iterator or a stream! However, it implements
@@ -765,6 +969,8 @@ impl QueryPager {
             None => return None,
         };
 
+        self.progress.rows_yielded += 1;
+
         // We are guaranteed here to have a non-empty page, so unwrap
         Some(
             self.current_page
@@ -806,9 +1012,23 @@ impl QueryPager {
     ) -> Poll<Option<Result<(), NextRowError>>> {
         let mut s = self.as_mut();
 
-        let received_page = ready_some_ok!(Pin::new(&mut s.page_receiver).poll_recv(cx));
+        let received_page = match Pin::new(&mut s.page_receiver).poll_recv(cx) {
+            Poll::Ready(Some(Ok(page))) => page,
+            Poll::Ready(Some(Err(err))) => {
+                s.publish_progress();
+                return Poll::Ready(Some(Err(err.into())));
+            }
+            Poll::Ready(None) => {
+                s.publish_progress();
+                return Poll::Ready(None);
+            }
+            Poll::Pending => return Poll::Pending,
+        };
 
+        s.progress.record_page(&received_page.rows);
+        s.publish_progress();
         s.current_page = RawRowLendingIterator::new(received_page.rows);
+        s.checked_row_type = None;
 
         if let Some(tracing_id) = received_page.tracing_id {
             s.tracing_ids.push(tracing_id);
@@ -820,6 +1040,49 @@ impl QueryPager {
         Poll::Ready(Some(Ok(())))
     }
 
+    /// Returns the next row, deserialized to the given type, or `None` if there
+    /// are no more rows.
+    ///
+    /// Unlike [QueryPager::rows_stream], this allows deserializing types which borrow
+    /// from the received page, such as `&str` or `&[u8]`, which saves an allocation
+    /// per column. The returned row borrows from the pager, so it has to be dropped
+    /// before the next row is fetched.
+    ///
+    /// Each page is type-checked against the row type before its first row is deserialized,
+    /// and again whenever a different row type is requested.
+    ///
+    /// This is cancel-safe.
+    pub async fn next_row<'pager, RowT: DeserializeRow<'pager, 'pager>>(
+        &'pager mut self,
+    ) -> Option<Result<RowT, NextRowError>> {
+        if let Err(err) = std::future::poll_fn(|cx| Pin::new(&mut *self).poll_fill_page(cx)).await?
+        {
+            return Some(Err(err));
+        }
+
+        // Equal function addresses mean the same code, so the check would give the same result.
+        // Distinct addresses of a single type's check (e.g. from different codegen units)
+        // only cause the check to be repeated.
+        let type_check: RowTypeCheck = RowT::type_check;
+        if !self
+            .checked_row_type
+            .is_some_and(|checked| std::ptr::fn_addr_eq(checked, type_check))
+        {
+            if let Err(err) = type_check(self.column_specs().as_slice()) {
+                return Some(Err(NextRowError::NextPageError(
+                    NextPageError::TypeCheckError(err),
+                )));
+            }
+            self.checked_row_type = Some(type_check);
+        }
+
+        self.next().await.map(|res| {
+            res.and_then(|(column_iterator, _fresh_page)| {
+                RowT::deserialize(column_iterator).map_err(NextRowError::RowDeserializationError)
+            })
+        })
+    }
+
     /// Type-checks the iterator against given type.
     ///
     /// This is automatically called upon transforming [QueryPager] into [TypedRowStream].
@@ -848,11 +1111,17 @@ If you are using this API, you are probably doing something wrong."
         TypedRowStream::<RowT>::new(self)
     }
 
+    #[allow(clippy::too_many_arguments)] // Not always triggered, because of the metrics, so
+    // I can't use `expect`.
     pub(crate) async fn new_for_query(
         statement: Statement,
         execution_profile: Arc<ExecutionProfileInner>,
         cluster_state: Arc<ClusterState>,
         #[cfg(feature = "metrics")] metrics: Arc<Metrics>,
+        request_observer: Option<Arc<dyn RequestObserver>>,
+        tracing_instrumentation: Option<TracingInstrumentation>,
+        trace_parent: Option<TraceParent>,
+        slow_query_logger: Option<SlowQueryLogger>,
     ) -> Result<Self, NextPageError> {
         let (sender, receiver) = mpsc::channel::<Result<ReceivedPage, NextPageError>>(1);
 
@@ -864,6 +1133,8 @@ If you are using this API, you are probably doing something wrong."
             .config
             .serial_consistency
             .unwrap_or(execution_profile.serial_consistency);
+        let settings =
+            RequestSettings::from_profile(&execution_profile).with_trace_parent(trace_parent);
 
         let timeouter = statement
             .get_request_timeout()
@@ -893,6 +1164,7 @@ If you are using this API, you are probably doing something wrong."
         let parent_span = tracing::Span::current();
         let worker_task = async move {
             let statement_ref = &statement;
+            let settings_ref = &settings;
 
             let page_query = |connection: Arc<Connection>,
                               consistency: Consistency,
@@ -903,6 +1175,7 @@ If you are using this API, you are probably doing something wrong."
                             statement_ref,
                             consistency,
                             serial_consistency,
+                            settings_ref.clone(),
                             Some(page_size),
                             paging_state,
                         )
@@ -911,9 +1184,14 @@ If you are using this API, you are probably doing something wrong."
             };
 
             let query_ref = &statement;
+            let requests_parent_span = parent_span.clone();
 
             let span_creator = move || {
-                let span = RequestSpan::new_query(&query_ref.contents);
+                let span = RequestSpan::new_query(&query_ref.contents).with_instrumentation(
+                    tracing_instrumentation.as_ref(),
+                    Some(&query_ref.contents),
+                    &requests_parent_span,
+                );
                 span.record_request_size(0);
                 span
             };
@@ -931,8 +1209,14 @@ If you are using this API, you are probably doing something wrong."
                 metrics,
                 paging_state: PagingState::start(),
                 history_listener: statement.config.history_listener.clone(),
+                tags: statement.config.tags.clone(),
                 current_request_id: None,
                 current_attempt_id: None,
+                request_observer,
+                current_observed_request: None,
+                statement_text: &statement.contents,
+                slow_query_logger,
+                current_slow_query: None,
                 parent_span,
                 span_creator,
             };
@@ -958,6 +1242,8 @@ If you are using this API, you are probably doing something wrong."
             .config
             .serial_consistency
             .unwrap_or(config.execution_profile.serial_consistency);
+        let settings = RequestSettings::from_profile(&config.execution_profile)
+            .with_trace_parent(config.trace_parent);
 
         let timeouter = config
             .prepared
@@ -985,6 +1271,7 @@ If you are using this API, you are probably doing something wrong."
         let worker_task = async move {
             let prepared_ref = &config.prepared;
             let values_ref = &config.values;
+            let settings_ref = &settings;
 
             let (partition_key, token) = match prepared_ref
                 .extract_partition_key_and_calculate_token(
@@ -1000,6 +1287,9 @@ If you are using this API, you are probably doing something wrong."
                 }
             };
 
+            let token = token
+                .or_else(|| prepared_ref.in_list_routing_token(values_ref, &config.cluster_state));
+
             let table_spec = config.prepared.get_table_spec();
             let statement_info = RoutingInfo {
                 consistency,
@@ -1018,6 +1308,7 @@ If you are using this API, you are probably doing something wrong."
                         values_ref,
                         consistency,
                         serial_consistency,
+                        settings_ref.clone(),
                         Some(page_size),
                         paging_state,
                     )
@@ -1041,11 +1332,18 @@ If you are using this API, you are probably doing something wrong."
                     None
                 };
 
+            let tracing_instrumentation = config.tracing_instrumentation;
+            let requests_parent_span = parent_span.clone();
             let span_creator = move || {
                 let span = RequestSpan::new_prepared(
                     partition_key.as_ref().map(|pk| pk.iter()),
                     token,
                     serialized_values_size,
+                )
+                .with_instrumentation(
+                    tracing_instrumentation.as_ref(),
+                    Some(prepared_ref.get_statement()),
+                    &requests_parent_span,
                 );
                 if let Some(replicas) = replicas.as_ref() {
                     span.record_replicas(replicas.iter().map(|(node, shard)| (node, *shard)));
@@ -1066,8 +1364,14 @@ If you are using this API, you are probably doing something wrong."
                 metrics: config.metrics,
                 paging_state: PagingState::start(),
                 history_listener: config.prepared.config.history_listener.clone(),
+                tags: config.prepared.config.tags.clone(),
                 current_request_id: None,
                 current_attempt_id: None,
+                request_observer: config.request_observer,
+                current_observed_request: None,
+                statement_text: config.prepared.get_statement(),
+                slow_query_logger: config.slow_query_logger,
+                current_slow_query: None,
                 parent_span,
                 span_creator,
             };
@@ -1104,6 +1408,7 @@ If you are using this API, you are probably doing something wrong."
                         &values,
                         consistency,
                         serial_consistency,
+                        RequestSettings::INTERNAL,
                         Some(page_size),
                         paging_state,
                     )
@@ -1120,12 +1425,12 @@ If you are using this API, you are probably doing something wrong."
         worker_task: impl Future<Output = PageSendAttemptedProof> + Send + 'static,
         mut receiver: mpsc::Receiver<Result<ReceivedPage, NextPageError>>,
     ) -> Result<Self, NextPageError> {
-        let worker_handle = tokio::task::spawn(worker_task);
+        let worker_handle = runtime::spawn_with_handle(worker_task);
 
         let Some(page_received_res) = receiver.recv().await else {
             // - The future returned by worker.work sends at least one item
             //   to the channel (the PageSendAttemptedProof helps enforce this);
-            // - That future is polled in a tokio::task which isn't going to be
+            // - That future is polled in a spawned task which isn't going to be
             //   cancelled, **unless** the runtime is being shut down.
             // - Another way for the worker task to terminate without sending
             //   anything could be panic.
@@ -1134,40 +1439,37 @@ If you are using this API, you are probably doing something wrong."
             // 2. The worker task panicked.
             //
             // Both cases are handled below, and in both cases we do not return
-            // from this function, but rather either propagate the panic,
-            // or hang indefinitely to avoid returning from here during runtime shutdown.
-            let worker_result = worker_handle.await;
-            match worker_result {
-                Ok(_send_attempted_proof) => {
+            // from this function, but rather either propagate the panic
+            // (which `join` does), or hang indefinitely to avoid returning from here
+            // during runtime shutdown.
+            match worker_handle.join().await {
+                Some(_send_attempted_proof) => {
                     unreachable!(
                         "Worker task completed without sending any page, despite having returned proof of having sent some"
                     )
                 }
-                Err(join_error) => {
-                    let is_cancelled = join_error.is_cancelled();
-                    if let Ok(panic_payload) = join_error.try_into_panic() {
-                        // Worker task panicked. Propagate the panic.
-                        std::panic::resume_unwind(panic_payload);
-                    } else {
-                        // This is not a panic, so it must be runtime shutdown.
-                        assert!(
-                            is_cancelled,
-                            "PagerWorker task join error is neither a panic nor cancellation, which should be impossible"
-                        );
-                        // Let's await a never-ending future to avoid returning from here.
-                        // But before, let's emit a message to indicate that we're in such a situation.
-                        tracing::info!(
-                            "Runtime is being shut down while QueryPager is being constructed; hanging the future indefinitely"
-                        );
-                        return futures::future::pending().await;
-                    }
+                None => {
+                    // The task was cancelled, so the runtime is being shut down.
+                    // Let's await a never-ending future to avoid returning from here.
+                    // But before, let's emit a message to indicate that we're in such a situation.
+                    tracing::info!(
+                        target: log_targets::REQUEST,
+                        "Runtime is being shut down while QueryPager is being constructed; hanging the future indefinitely"
+                    );
+                    return futures::future::pending().await;
                 }
             }
         };
         let page_received = page_received_res?;
 
+        let mut progress = PagerProgress::default();
+        progress.record_page(&page_received.rows);
+
         Ok(Self {
+            progress,
+            progress_sender: watch::Sender::new(progress),
             current_page: RawRowLendingIterator::new(page_received.rows),
+            checked_row_type: None,
             page_receiver: receiver,
             tracing_ids: if let Some(tracing_id) = page_received.tracing_id {
                 vec![tracing_id]
@@ -1196,6 +1498,25 @@ If you are using this API, you are probably doing something wrong."
         ColumnSpecs::new(self.current_page.metadata().col_specs())
     }
 
+    /// Returns the current progress of the paged query.
+    #[inline]
+    pub fn progress(&self) -> PagerProgress {
+        self.progress
+    }
+
+    /// Subscribes to progress of the paged query.
+    ///
+    /// The watched value is updated each time a page is received and when
+    /// the query ends, so that it can be observed e.g. from another task.
+    /// In between, [Self::progress] returns the exact value.
+    pub fn progress_watch(&self) -> watch::Receiver<PagerProgress> {
+        self.progress_sender.subscribe()
+    }
+
+    fn publish_progress(&self) {
+        self.progress_sender.send_replace(self.progress);
+    }
+
     fn is_current_page_exhausted(&self) -> bool {
         self.current_page.rows_remaining() == 0
     }
@@ -1258,6 +1579,18 @@ impl<RowT> TypedRowStream<RowT> {
     pub fn column_specs(&self) -> ColumnSpecs<'_, '_> {
         self.raw_row_lending_stream.column_specs()
     }
+
+    /// Returns the current progress of the paged query.
+    #[inline]
+    pub fn progress(&self) -> PagerProgress {
+        self.raw_row_lending_stream.progress()
+    }
+
+    /// Subscribes to progress of the paged query. See [QueryPager::progress_watch].
+    #[inline]
+    pub fn progress_watch(&self) -> watch::Receiver<PagerProgress> {
+        self.raw_row_lending_stream.progress_watch()
+    }
 }
 
 /// Stream implementation for TypedRowStream.
@@ -3,58 +3,89 @@
 
 use super::execution_profile::{ExecutionProfile, ExecutionProfileHandle, ExecutionProfileInner};
 use super::pager::{PreparedPagerConfig, QueryPager};
-use super::{Compression, PoolSize, SelfIdentity, WriteCoalescingDelay};
+use super::{
+    Compression, DynamicPoolSize, KeepaliveRequest, PoolSize, PoolWarmup, SelfIdentity,
+    ShardAwarePortUsage, WriteCoalescingDelay,
+};
 use crate::authentication::AuthenticatorProvider;
 use crate::cluster::node::{KnownNode, NodeRef};
-use crate::cluster::{Cluster, ClusterNeatDebug, ClusterState};
+use crate::cluster::{Cluster, ClusterNeatDebug, ClusterState, TopologyEventStream};
+#[cfg(feature = "metrics")]
+use crate::errors::DbError;
 use crate::errors::{
     BadQuery, BrokenConnectionError, ExecutionError, MetadataError, NewSessionError,
     PagerExecutionError, PrepareError, RequestAttemptError, RequestError, SchemaAgreementError,
-    TracingError, UseKeyspaceError,
+    StableTopologyError, TracingError, UseKeyspaceError,
 };
+use crate::frame::ResponseFrameLimits;
 use crate::frame::response::result;
 use crate::network::tls::TlsProvider;
-use crate::network::{Connection, ConnectionConfig, PoolConfig, VerifiedKeyspaceName};
+use crate::network::{
+    Connection, ConnectionConfig, PoolConfig, Proxy, ProxyRules, RequestSettings, SocketOptions,
+    VerifiedKeyspaceName,
+};
+#[cfg(feature = "metrics")]
+use crate::observability::diagnostics::MetricsDiagnostics;
+use crate::observability::diagnostics::{
+    ControlConnectionDiagnostics, DiagnosticsReport, ExecutionProfileDiagnostics, NodeDiagnostics,
+};
 use crate::observability::driver_tracing::RequestSpan;
-use crate::observability::history::{self, HistoryListener};
+use crate::observability::history::{self, HistoryListener, RecentHistoryCollector};
+use crate::observability::instrumentation::{
+    TraceContextProvider, TraceParent, TracingInstrumentation,
+};
+use crate::observability::log_targets;
 #[cfg(feature = "metrics")]
-use crate::observability::metrics::Metrics;
-use crate::observability::tracing::TracingInfo;
-use crate::policies::address_translator::AddressTranslator;
+use crate::observability::metrics::{LatencyHistogramConfig, Metrics};
+use crate::observability::request_observer::{
+    AttemptEnd, ObservedRequestId, RequestEnd, RequestObserver, RequestStart,
+};
+use crate::observability::slow_query_log::{LoggedStatement, SlowQueryLogger, SlowQueryTracker};
+use crate::observability::tracing::{TracingInfo, TracingReport};
+use crate::policies::address_translator::{AddressTranslator, PeerAddressPreference};
 use crate::policies::host_filter::HostFilter;
+use crate::policies::keyspace_filter::KeyspaceFilter;
 use crate::policies::load_balancing::{self, RoutingInfo};
-use crate::policies::reconnect::ExponentialReconnectPolicy;
-#[cfg(all(scylla_unstable, feature = "unstable-reconnect-policy"))]
-use crate::policies::reconnect::ReconnectPolicy;
-use crate::policies::retry::{RequestInfo, RetryDecision, RetrySession};
+use crate::policies::reconnect::{ExponentialReconnectPolicy, ReconnectPolicy};
+use crate::policies::resolver::{DefaultResolver, Resolver};
+use crate::policies::retry::{RequestInfo, RetryDecision, RetrySession, SchemaChangeRetryPolicy};
+use crate::policies::schema_agreement::{
+    SchemaAgreementCheck, SchemaAgreementPolicy, SchemaAgreementProgress,
+};
 use crate::policies::speculative_execution;
+use crate::policies::tablet_listener::TabletListener;
 use crate::policies::timestamp_generator::TimestampGenerator;
 use crate::response::query_result::{MaybeFirstRowError, QueryResult, RowsError};
 use crate::response::{
     Coordinator, NonErrorQueryResponse, PagingState, PagingStateResponse, QueryResponse,
 };
-use crate::routing::partitioner::PartitionerName;
+#[cfg(feature = "paging-state-codec")]
+use crate::response::{PagingStateCodec, PagingStateKey};
+use crate::routing::partitioner::{CustomPartitioners, PartitionerName};
 use crate::routing::{Shard, ShardAwarePortRange};
 use crate::statement::batch::batch_values;
 use crate::statement::batch::{Batch, BatchStatement};
 use crate::statement::prepared::{PartitionKeyError, PreparedStatement};
 use crate::statement::unprepared::Statement;
 use crate::statement::{Consistency, PageSize, StatementConfig};
+use crate::system_views::SystemViews;
+use crate::utils::runtime;
 use arc_swap::ArcSwapOption;
 use futures::future::join_all;
 use futures::future::try_join_all;
+use futures::stream::{BoxStream, Stream, StreamExt};
 use itertools::Itertools;
 use scylla_cql::frame::response::NonErrorResponseWithDeserializedMetadata;
 use scylla_cql::serialize::batch::BatchValues;
 use scylla_cql::serialize::row::{SerializeRow, SerializedValues};
 use std::borrow::Borrow;
+use std::collections::HashMap;
 use std::future::Future;
 use std::net::{IpAddr, SocketAddr};
-use std::num::NonZeroU32;
+use std::num::{NonZeroU32, NonZeroUsize};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 use tokio::sync::OnceCell;
-use tokio::time::timeout;
 use tracing::{Instrument, debug, error, trace, trace_span};
 use uuid::Uuid;
 
@@ -90,17 +121,24 @@ impl Default for InternalStatements {
 pub struct Session {
     cluster: Cluster,
     default_execution_profile_handle: ExecutionProfileHandle,
-    schema_agreement_interval: Duration,
+    schema_agreement_policy: SchemaAgreementPolicy,
     #[cfg(feature = "metrics")]
     metrics: Arc<Metrics>,
-    schema_agreement_timeout: Duration,
     schema_agreement_automatic_waiting: bool,
     refresh_metadata_on_auto_schema_agreement: bool,
     keyspace_name: Arc<ArcSwapOption<String>>,
     tracing_info_fetch_attempts: NonZeroU32,
     tracing_info_fetch_interval: Duration,
+    tracing_info_fetch_max_interval: Option<Duration>,
     tracing_info_fetch_consistency: Consistency,
+    #[cfg(feature = "paging-state-codec")]
+    paging_state_codec: PagingStateCodec,
     internal_statements: InternalStatements,
+    request_observer: Option<Arc<dyn RequestObserver>>,
+    tracing_instrumentation: Option<TracingInstrumentation>,
+    trace_context_provider: Option<Arc<dyn TraceContextProvider>>,
+    slow_query_logger: Option<SlowQueryLogger>,
+    session_history: Option<Arc<RecentHistoryCollector>>,
 }
 
 /// This implementation deliberately omits some details from Cluster in order
@@ -113,16 +151,12 @@ impl std::fmt::Debug for Session {
                 "default_execution_profile_handle",
                 &self.default_execution_profile_handle,
             )
-            .field("schema_agreement_interval", &self.schema_agreement_interval);
+            .field("schema_agreement_policy", &self.schema_agreement_policy);
 
         #[cfg(feature = "metrics")]
         d.field("metrics", &self.metrics);
 
         d.field(
-            "auto_await_schema_agreement_timeout",
-            &self.schema_agreement_timeout,
-        )
-        .field(
             "schema_agreement_automatic_waiting",
             &self.schema_agreement_automatic_waiting,
         )
@@ -139,11 +173,24 @@ impl std::fmt::Debug for Session {
             "tracing_info_fetch_interval",
             &self.tracing_info_fetch_interval,
         )
+        .field(
+            "tracing_info_fetch_max_interval",
+            &self.tracing_info_fetch_max_interval,
+        )
         .field(
             "tracing_info_fetch_consistency",
             &self.tracing_info_fetch_consistency,
-        )
-        .finish()
+        );
+
+        #[cfg(feature = "paging-state-codec")]
+        d.field("paging_state_codec", &self.paging_state_codec);
+
+        d.field("request_observer", &self.request_observer)
+            .field("tracing_instrumentation", &self.tracing_instrumentation)
+            .field("trace_context_provider", &self.trace_context_provider)
+            .field("slow_query_logger", &self.slow_query_logger)
+            .field("session_history", &self.session_history)
+            .finish()
     }
 }
 
@@ -174,6 +221,8 @@ impl From<Arc<rustls::ClientConfig>> for TlsContext {
     }
 }
 
+pub use crate::network::tls::{NodeTlsProvider, NodeTlsSettings, ReloadableTlsContext, TlsTarget};
+
 /// Configuration options for [`Session`].
 /// Can be created manually, but usually it's easier to use
 /// [SessionBuilder](super::session_builder::SessionBuilder)
@@ -192,6 +241,16 @@ pub struct SessionConfig {
     /// - `in6addr_any` for IPv6 ([`Ipv6Addr::UNSPECIFIED`][std::net::Ipv6Addr::UNSPECIFIED])
     pub local_ip_address: Option<IpAddr>,
 
+    /// A proxy through which connections to all nodes are tunneled,
+    /// unless overridden for a datacenter in [SessionConfig::datacenter_proxies].
+    ///
+    /// By default set to None, which means that nodes are connected to directly.
+    pub proxy: Option<Proxy>,
+
+    /// Proxies used for connections to nodes in given datacenters, overriding
+    /// [SessionConfig::proxy]. `None` means that nodes in the datacenter are connected to directly.
+    pub datacenter_proxies: HashMap<String, Option<Proxy>>,
+
     /// Specifies the local port range used for shard-aware connections.
     ///
     /// By default set to [`ShardAwarePortRange::EPHEMERAL_PORT_RANGE`].
@@ -201,6 +260,14 @@ pub struct SessionConfig {
     /// If it's not supported by database server Session will fall back to no compression.
     pub compression: Option<Compression>,
 
+    /// Request bodies shorter than this number of bytes are sent uncompressed,
+    /// even if compression is enabled. Compressing small bodies costs CPU time
+    /// while saving little or nothing on the network.
+    ///
+    /// This option has no effect if [`SessionConfig::compression`] is `None`.
+    /// The default is 0, i.e. all requests are compressed.
+    pub compression_threshold: usize,
+
     /// Whether to set the nodelay TCP flag.
     pub tcp_nodelay: bool,
 
@@ -209,6 +276,23 @@ pub struct SessionConfig {
     /// If `None`, no TCP keepalive messages are sent.
     pub tcp_keepalive_interval: Option<Duration>,
 
+    /// Size of the receive buffer of TCP sockets (`SO_RCVBUF`).
+    /// If `None`, the system default is used.
+    pub tcp_recv_buffer_size: Option<usize>,
+
+    /// Size of the send buffer of TCP sockets (`SO_SNDBUF`).
+    /// If `None`, the system default is used.
+    pub tcp_send_buffer_size: Option<usize>,
+
+    /// Type of service (`IP_TOS`, or `IPV6_TCLASS` for IPv6) set on TCP sockets,
+    /// e.g. to mark the traffic with a DSCP value. If `None`, the system default is used.
+    pub ip_tos: Option<u8>,
+
+    /// Network interface all driver's TCP sockets are bound to (`SO_BINDTODEVICE`).
+    /// Supported only on Linux, Android and Fuchsia. If `None`, sockets are not bound
+    /// to any interface.
+    pub bind_interface: Option<String>,
+
     /// Handle to the default execution profile, which is used
     /// for all statements that do not specify an execution profile.
     pub default_execution_profile_handle: ExecutionProfileHandle,
@@ -227,6 +311,16 @@ pub struct SessionConfig {
     /// TLS context used configure TLS connections to DB nodes.
     pub tls_context: Option<TlsContext>,
 
+    /// TLS context which can be replaced at runtime, e.g. to rotate certificates.
+    /// Each new connection uses the context which is current when it is opened.
+    /// If set, it takes precedence over [SessionConfig::tls_context].
+    pub reloadable_tls_context: Option<ReloadableTlsContext>,
+
+    /// Chooses TLS settings separately for each node, e.g. per datacenter.
+    /// If set, it takes precedence over [SessionConfig::tls_context]
+    /// and [SessionConfig::reloadable_tls_context].
+    pub node_tls_provider: Option<Arc<dyn NodeTlsProvider>>,
+
     /// Custom authenticator provider to create an authenticator instance
     /// upon session creation.
     pub authenticator: Option<Arc<dyn AuthenticatorProvider>>,
@@ -241,28 +335,123 @@ pub struct SessionConfig {
     /// The default is `PerShard(1)`, which is the recommended setting for ScyllaDB clusters.
     pub connection_pool_size: PoolSize,
 
+    /// If set, the number of connections to each shard follows the load, within the given bounds,
+    /// and `connection_pool_size` is ignored.
+    /// The default is `None`, i.e. the pool size is fixed.
+    pub dynamic_pool_size: Option<DynamicPoolSize>,
+
     /// If true, prevents the driver from connecting to the shard-aware port, even if the node supports it.
     /// Generally, this options is best left as default (false).
+    /// When true, it takes precedence over `shard_aware_port_usage`.
     pub disallow_shard_aware_port: bool,
 
+    /// Controls whether and how the driver connects to the shard-aware port.
+    /// The default is [ShardAwarePortUsage::Preferred].
+    pub shard_aware_port_usage: ShardAwarePortUsage,
+
     /// Policy that determines how long the connection pool waits between attempts
     /// to fill connections to given host.
-    #[cfg(all(scylla_unstable, feature = "unstable-reconnect-policy"))]
+    /// The default is [ExponentialReconnectPolicy].
     pub reconnect_policy: Arc<dyn ReconnectPolicy>,
 
+    /// If true, the session only opens the control connection when it is created.
+    /// Connections to a node are opened when a request is sent to it for the first time.
+    /// The default is false, i.e. connections to all nodes are opened eagerly.
+    pub connect_lazily: bool,
+
+    /// If set, connections to a node are closed once they all have been idle, i.e. without
+    /// any requests, for this long. They are opened again when a request is sent to the node.
+    /// The default is `None`, i.e. idle connections are kept open.
+    pub connection_idle_timeout: Option<Duration>,
+
+    /// If set, connections older than this are replaced with new ones, e.g. to pick up renewed
+    /// certificates or a changed shard layout. A replacement is opened first, and the old
+    /// connection is closed once its requests in flight complete.
+    /// The default is `None`, i.e. connections are kept open regardless of their age.
+    pub connection_max_age: Option<Duration>,
+
+    /// If set, creating the session waits until connection pools are full,
+    /// for at most the timeout of the warmup. See [PoolWarmup].
+    /// The default is `None`, i.e. the session is created once each pool
+    /// has at least one connection.
+    pub pool_warmup: Option<PoolWarmup>,
+
+    /// If set, a node with that many requests in flight is considered saturated:
+    /// it is moved to the end of query plans, so that requests are sent to the next
+    /// nodes in the plans instead, and it is only tried if they all fail.
+    /// This sheds load away from slow nodes, before their queues grow.
+    /// The default is `None`, i.e. the number of requests in flight does not affect routing.
+    pub max_in_flight_requests_per_node: Option<NonZeroUsize>,
+
+    /// If true, lifecycle events of connections - opening them, failures to open them,
+    /// and closing them, also due to missed keepalives or idleness - are logged at `info`
+    /// level under the [CONNECTION](crate::observability::log_targets::CONNECTION) target.
+    /// Frame-level events are not affected.
+    /// The default is false, i.e. they are logged at `debug` level.
+    pub verbose_connection_logging: bool,
+
     ///  Timestamp generator used for generating timestamps on the client-side
     ///  If None, server-side timestamps are used.
     pub timestamp_generator: Option<Arc<dyn TimestampGenerator>>,
 
+    /// Observer notified about all requests executed by the session and their attempts.
+    /// The default is `None`.
+    pub request_observer: Option<Arc<dyn RequestObserver>>,
+
+    /// If set, the session creates `INFO` level spans for requests and their attempts,
+    /// suitable for exporting to distributed tracing systems.
+    /// The default is `None`.
+    pub tracing_instrumentation: Option<TracingInstrumentation>,
+
+    /// If set, the trace context it provides is sent to the cluster
+    /// in the custom payload of requests.
+    /// The default is `None`.
+    pub trace_context_provider: Option<Arc<dyn TraceContextProvider>>,
+
+    /// If set, requests whose latency exceeds the logger's threshold
+    /// are reported to its sink.
+    /// The default is `None`.
+    pub slow_query_logger: Option<SlowQueryLogger>,
+
+    /// If set, the history of all requests executed by the session is collected in it,
+    /// except for requests of statements with their own history listener.
+    /// The default is `None`.
+    pub session_history: Option<Arc<RecentHistoryCollector>>,
+
+    /// Configuration of the histograms in which latencies of requests are collected:
+    /// their precision, an optional sliding time window and additional percentiles
+    /// computed in snapshots.
+    #[cfg(feature = "metrics")]
+    pub latency_histogram: LatencyHistogramConfig,
+
     /// If empty, fetch all keyspaces
     pub keyspaces_to_fetch: Vec<String>,
 
+    /// The keyspace filter decides whether schema metadata of a keyspace is fetched.
+    /// It is applied on top of `keyspaces_to_fetch`. If `None`, all keyspaces are accepted.
+    pub keyspace_filter: Option<Arc<dyn KeyspaceFilter>>,
+
     /// If true, full schema is fetched with every metadata refresh.
     pub fetch_schema_metadata: bool,
 
     /// Custom timeout for requests that query metadata.
     pub metadata_request_serverside_timeout: Option<Duration>,
 
+    /// Timeout of the initial cluster metadata fetch, performed when the session is created.
+    /// If `None`, the fetch is bounded only by the timeouts of the requests it consists of.
+    pub initial_metadata_fetch_timeout: Option<Duration>,
+
+    /// If true, the session is created even if the initial cluster metadata fetch fails
+    /// or times out. The session then starts with metadata consisting only of the known nodes
+    /// and dummy tokens, and retries fetching metadata periodically.
+    /// [`Session::is_metadata_degraded`] tells whether the session still runs on such metadata.
+    pub allow_degraded_startup: bool,
+
+    /// Number of standby control connections, kept open to nodes other than the one
+    /// the control connection is opened to. When the control connection breaks, a standby
+    /// connection replaces it immediately, so that topology and schema changes keep being observed.
+    pub control_connection_standbys: usize,
+
     /// Interval of sending keepalive requests.
     /// If `None`, keepalives are never sent, so `Self::keepalive_timeout` has no effect.
     pub keepalive_interval: Option<Duration>,
@@ -271,6 +460,14 @@ pub struct SessionConfig {
     /// If `None`, connections are never closed due to lack of response to a keepalive message.
     pub keepalive_timeout: Option<Duration>,
 
+    /// The request sent as a keepalive. The default is [KeepaliveRequest::Options].
+    pub keepalive_request: KeepaliveRequest,
+
+    /// If true, a connection is closed, and then opened again by the pool, when a keepalive
+    /// request fails or times out. Otherwise, such failures are only logged and counted
+    /// in metrics. The default is true.
+    pub close_on_keepalive_failure: bool,
+
     /// How often the driver should ask if schema is in agreement.
     pub schema_agreement_interval: Duration,
 
@@ -279,6 +476,11 @@ pub struct SessionConfig {
     /// automatic waiting after a schema-altering statement is sent.
     pub schema_agreement_timeout: Duration,
 
+    /// Policy used for awaiting schema agreement, both manually and automatically.
+    /// If set, `Self::schema_agreement_interval` and `Self::schema_agreement_timeout`
+    /// are ignored in favour of the policy's settings.
+    pub schema_agreement_policy: Option<SchemaAgreementPolicy>,
+
     /// Controls whether schema agreement is automatically awaited
     /// after sending a schema-altering statement.
     pub schema_agreement_automatic_waiting: bool,
@@ -291,21 +493,49 @@ pub struct SessionConfig {
     /// If `None`, the driver will wait for hostname resolution indefinitely.
     pub hostname_resolution_timeout: Option<Duration>,
 
+    /// Known nodes given by hostnames are resolved again before reconnecting to them,
+    /// if they were resolved longer ago than this. This lets the driver follow
+    /// hostnames whose addresses change, e.g. of load balancers or Kubernetes services.
+    /// If `None`, hostnames are resolved again only after all known nodes become unreachable.
+    pub hostname_resolution_ttl: Option<Duration>,
+
+    /// The resolver used to resolve hostnames and SRV records of known nodes.
+    /// If `None`, [DefaultResolver] is used.
+    pub resolver: Option<Arc<dyn Resolver>>,
+
     /// The address translator is used to translate addresses received from ScyllaDB nodes
     /// (either with cluster metadata or with an event) to addresses that can be used to
     /// actually connect to those nodes. This may be needed e.g. when there is NAT
     /// between the nodes and the driver.
     pub address_translator: Option<Arc<dyn AddressTranslator>>,
 
+    /// Chooses which of the addresses advertised by discovered nodes the driver connects to,
+    /// before the address is translated by [SessionConfig::address_translator].
+    /// By default, the `broadcast_rpc_address` of each node is used.
+    pub peer_address_preference: PeerAddressPreference,
+
     /// The host filter decides whether any connections should be opened
     /// to the node or not. The driver will also avoid filtered out nodes when
     /// re-establishing the control connection.
     pub host_filter: Option<Arc<dyn HostFilter>>,
 
+    /// The internal requests host filter further restricts the nodes which the driver
+    /// sends its internal requests to, e.g. schema agreement checks. Nodes rejected by
+    /// [`host_filter`](SessionConfig::host_filter) never receive any requests,
+    /// regardless of this filter.
+    pub internal_requests_host_filter: Option<Arc<dyn HostFilter>>,
+
+    /// User-provided partitioners, used to compute tokens (and thus route requests)
+    /// for tables employing partitioners which the driver does not support natively.
+    pub custom_partitioners: CustomPartitioners,
+
     #[cfg(all(scylla_unstable, feature = "unstable-host-listener"))]
     /// Optional listener for host events (ADD, REMOVE, UP, DOWN).
     pub host_listener: Option<Arc<dyn crate::policies::host_listener::HostListener>>,
 
+    /// Optional listener for updates of tablet maps, e.g. caused by tablet migrations.
+    pub tablet_listener: Option<Arc<dyn TabletListener>>,
+
     /// If true, the driver will inject a delay controlled by [`SessionConfig::write_coalescing_delay`]
     /// before flushing data to the socket.
     /// This gives the driver an opportunity to collect more write requests
@@ -325,6 +555,31 @@ pub struct SessionConfig {
     /// This option is [`WriteCoalescingDelay::SmallNondeterministic`] by default.
     pub write_coalescing_delay: WriteCoalescingDelay,
 
+    /// Limits the number of bytes that a connection collects before flushing them
+    /// to the socket. Once the limit is reached, the data is flushed without
+    /// waiting for more requests, even if write coalescing is enabled.
+    ///
+    /// Lowering this value bounds the latency added to requests at the end
+    /// of a large batch of writes, at the cost of throughput.
+    ///
+    /// This option is `None` (no limit) by default.
+    pub write_coalescing_max_bytes: Option<NonZeroUsize>,
+
+    /// The maximum length of a response frame body, as declared in the frame header.
+    /// A connection which receives a longer frame is closed, without allocating
+    /// memory for the body.
+    ///
+    /// This protects the driver from a misbehaving server or proxy.
+    /// The default is 256 MiB.
+    pub max_response_frame_body_length: usize,
+
+    /// The maximum length of a compressed response frame body after decompression.
+    /// Responses which would decompress to more bytes fail without allocating
+    /// memory for the decompressed body.
+    ///
+    /// The default is 256 MiB.
+    pub max_decompressed_response_body_length: usize,
+
     /// Number of attempts to fetch [`TracingInfo`]
     /// in [`Session::get_tracing_info`]. Tracing info
     /// might not be available immediately on queried node - that's why
@@ -337,6 +592,12 @@ pub struct SessionConfig {
     /// the driver performs a few attempts with sleeps in between.
     pub tracing_info_fetch_interval: Duration,
 
+    /// If set, the delay between attempts to fetch [`TracingInfo`]
+    /// in [`Session::get_tracing_info`] doubles after each attempt,
+    /// starting with `tracing_info_fetch_interval`, up to this value.
+    /// The default is `None`, i.e. the delay is constant.
+    pub tracing_info_fetch_max_interval: Option<Duration>,
+
     /// Consistency level of fetching [`TracingInfo`]
     /// in [`Session::get_tracing_info`].
     pub tracing_info_fetch_consistency: Consistency,
@@ -347,6 +608,13 @@ pub struct SessionConfig {
     /// or they expect the topology to change frequently.
     pub cluster_metadata_refresh_interval: Duration,
 
+    /// Key used to sign paging states exported with [`Session::paging_state_codec`].
+    ///
+    /// If set, only paging states signed with this key are accepted on import.
+    /// The default is `None` - exported paging states are only bound to their statement.
+    #[cfg(feature = "paging-state-codec")]
+    pub paging_state_key: Option<PagingStateKey>,
+
     /// Driver and application self-identifying information,
     /// to be sent to server in STARTUP message.
     pub identity: SelfIdentity<'static>,
@@ -367,42 +635,84 @@ impl SessionConfig {
         SessionConfig {
             known_nodes: Vec::new(),
             local_ip_address: None,
+            proxy: None,
+            datacenter_proxies: HashMap::new(),
             shard_aware_local_port_range: ShardAwarePortRange::EPHEMERAL_PORT_RANGE,
             compression: None,
+            compression_threshold: 0,
             tcp_nodelay: true,
             tcp_keepalive_interval: None,
+            tcp_recv_buffer_size: None,
+            tcp_send_buffer_size: None,
+            ip_tos: None,
+            bind_interface: None,
             schema_agreement_interval: Duration::from_millis(200),
             default_execution_profile_handle: ExecutionProfile::new_from_inner(Default::default())
                 .into_handle(),
             used_keyspace: None,
             keyspace_case_sensitive: false,
             tls_context: None,
+            reloadable_tls_context: None,
+            node_tls_provider: None,
             authenticator: None,
             connect_timeout: Duration::from_secs(5),
             hostname_resolution_timeout: Some(Duration::from_secs(5)),
+            hostname_resolution_ttl: Some(Duration::from_secs(30)),
+            resolver: None,
             connection_pool_size: Default::default(),
+            dynamic_pool_size: None,
             disallow_shard_aware_port: false,
-            #[cfg(all(scylla_unstable, feature = "unstable-reconnect-policy"))]
+            shard_aware_port_usage: ShardAwarePortUsage::default(),
             reconnect_policy: Arc::new(ExponentialReconnectPolicy::new()),
+            connect_lazily: false,
+            connection_idle_timeout: None,
+            connection_max_age: None,
+            pool_warmup: None,
+            max_in_flight_requests_per_node: None,
+            verbose_connection_logging: false,
             timestamp_generator: None,
+            request_observer: None,
+            tracing_instrumentation: None,
+            trace_context_provider: None,
+            slow_query_logger: None,
+            session_history: None,
+            #[cfg(feature = "metrics")]
+            latency_histogram: LatencyHistogramConfig::default(),
             keyspaces_to_fetch: Vec::new(),
+            keyspace_filter: None,
             fetch_schema_metadata: true,
             metadata_request_serverside_timeout: Some(Duration::from_secs(2)),
+            initial_metadata_fetch_timeout: None,
+            allow_degraded_startup: false,
+            control_connection_standbys: 0,
             keepalive_interval: Some(Duration::from_secs(30)),
             keepalive_timeout: Some(Duration::from_secs(30)),
+            keepalive_request: KeepaliveRequest::Options,
+            close_on_keepalive_failure: true,
             schema_agreement_timeout: Duration::from_secs(60),
+            schema_agreement_policy: None,
             schema_agreement_automatic_waiting: true,
             address_translator: None,
+            peer_address_preference: PeerAddressPreference::default(),
             host_filter: None,
+            internal_requests_host_filter: None,
+            custom_partitioners: CustomPartitioners::new(),
             #[cfg(all(scylla_unstable, feature = "unstable-host-listener"))]
             host_listener: None,
+            tablet_listener: None,
             refresh_metadata_on_auto_schema_agreement: true,
             enable_write_coalescing: true,
             write_coalescing_delay: WriteCoalescingDelay::SmallNondeterministic,
+            write_coalescing_max_bytes: None,
+            max_response_frame_body_length: ResponseFrameLimits::DEFAULT_MAX_BODY_LENGTH,
+            max_decompressed_response_body_length: ResponseFrameLimits::DEFAULT_MAX_BODY_LENGTH,
             tracing_info_fetch_attempts: NonZeroU32::new(10).unwrap(),
             tracing_info_fetch_interval: Duration::from_millis(3),
+            tracing_info_fetch_max_interval: None,
             tracing_info_fetch_consistency: Consistency::One,
             cluster_metadata_refresh_interval: Duration::from_secs(60),
+            #[cfg(feature = "paging-state-codec")]
+            paging_state_key: None,
             identity: SelfIdentity::default(),
         }
     }
@@ -433,6 +743,20 @@ impl SessionConfig {
         self.known_nodes.push(KnownNode::Address(node_addr));
     }
 
+    /// Adds known database servers given by SRV records of the name,
+    /// e.g. `_cql._tcp.scylla.example.com`.
+    /// The records are looked up by the [resolver](Self::resolver).
+    /// # Example
+    /// ```
+    /// # use scylla::client::session::SessionConfig;
+    /// let mut config = SessionConfig::new();
+    /// config.add_known_srv("_cql._tcp.scylla.example.com");
+    /// ```
+    pub fn add_known_srv(&mut self, name: impl AsRef<str>) {
+        self.known_nodes
+            .push(KnownNode::Srv(name.as_ref().to_string()));
+    }
+
     /// Adds a list of known database server with hostnames.
     /// If the port is not explicitly specified, 9042 is used as default
     /// # Example
@@ -476,6 +800,17 @@ impl Default for SessionConfig {
     }
 }
 
+/// Order in which [`Session::execute_stream`] yields results.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ResultOrdering {
+    /// Results are yielded in the order of the value sets they were produced from.
+    #[default]
+    Ordered,
+    /// Results are yielded as soon as their executions complete.
+    Unordered,
+}
+
 pub(crate) enum RunRequestResult<ResT> {
     IgnoredWriteError,
     Completed(ResT),
@@ -548,6 +883,44 @@ impl Session {
         self.do_query_unpaged(&statement.into(), values).await
     }
 
+    /// Executes a schema-altering statement, awaits schema agreement and refreshes
+    /// metadata of the affected keyspace.
+    ///
+    /// Once this method returns, the schema change is visible on the nodes taking part
+    /// in schema agreement and in [cluster state](Self::get_cluster_state). This is done
+    /// regardless of [`SessionBuilder::auto_await_schema_agreement`](crate::client::session_builder::SessionBuilder::auto_await_schema_agreement);
+    /// schema agreement is awaited according to the session's schema agreement policy.
+    ///
+    /// Unless the statement has its own retry policy, it is executed with
+    /// [SchemaChangeRetryPolicy],
+    /// which retries schema changes that conflicted with concurrent ones.
+    ///
+    /// Returns the agreed schema version.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use scylla::client::session::Session;
+    /// # use std::error::Error;
+    /// # async fn check_only_compiles(session: &Session) -> Result<(), Box<dyn Error>> {
+    /// session
+    ///     .execute_schema_change("CREATE TABLE IF NOT EXISTS ks.tab (a int PRIMARY KEY, b text)")
+    ///     .await?;
+    ///
+    /// let table = session
+    ///     .get_cluster_state()
+    ///     .get_keyspace("ks")
+    ///     .and_then(|ks| ks.tables.get("tab").cloned());
+    /// assert!(table.is_some());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn execute_schema_change(
+        &self,
+        statement: impl Into<Statement>,
+    ) -> Result<Uuid, ExecutionError> {
+        self.do_execute_schema_change(statement.into()).await
+    }
+
     /// Queries a single page from the database, optionally continuing from a saved point.
     ///
     /// It is discouraged to use this method with non-empty values argument ([`SerializeRow::is_empty()`]
@@ -702,18 +1075,43 @@ impl Session {
         values: impl SerializeRow,
     ) -> Result<QueryResult, ExecutionError> {
         let serialized_values = prepared.serialize_values(&values)?;
-        let (result, paging_state) = self
-            .execute(prepared, &serialized_values, None, PagingState::start())
-            .await?;
-        if !paging_state.finished() {
-            error!(
-                "Unpaged prepared query returned a non-empty paging state! This is a driver-side or server-side bug."
-            );
-            return Err(ExecutionError::LastAttemptError(
-                RequestAttemptError::NonfinishedPagingState,
-            ));
-        }
-        Ok(result)
+        self.do_execute_unpaged(prepared, &serialized_values).await
+    }
+
+    /// Does the same as [Session::execute_unpaged], but takes values which were
+    /// serialized beforehand with [PreparedStatement::serialize_values_into].
+    ///
+    /// This allows reusing the buffer of the values between requests, e.g. in tight loops.
+    /// The values are checked against the bind markers of the statement: if their number
+    /// or types don't match, the request fails with [BadQuery::SerializationError](crate::errors::BadQuery::SerializationError)
+    /// without being sent.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use scylla::client::session::Session;
+    /// # use std::error::Error;
+    /// # async fn check_only_compiles(session: &Session) -> Result<(), Box<dyn Error>> {
+    /// use scylla::serialize::row::SerializedValues;
+    ///
+    /// let prepared = session
+    ///     .prepare("INSERT INTO ks.tab (a) VALUES(?)")
+    ///     .await?;
+    ///
+    /// let mut values = SerializedValues::new();
+    /// for a in 0..100_i32 {
+    ///     prepared.serialize_values_into(&(a,), &mut values)?;
+    ///     session.execute_unpaged_serialized(&prepared, &values).await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn execute_unpaged_serialized(
+        &self,
+        prepared: &PreparedStatement,
+        values: &SerializedValues,
+    ) -> Result<QueryResult, ExecutionError> {
+        prepared.type_check_serialized_values(values)?;
+        self.do_execute_unpaged(prepared, values).await
     }
 
     /// Executes a prepared statement, restricting results to single page.
@@ -835,6 +1233,63 @@ impl Session {
             .await
     }
 
+    /// Execute a prepared statement once for each set of values yielded by a stream.
+    ///
+    /// Up to `concurrency` executions are in flight at a time; the value stream
+    /// is polled only as fast as executions complete. Each execution behaves like
+    /// [`Session::execute_unpaged`]. The returned stream yields, for each value set,
+    /// its index in `values` together with the result of the execution.
+    /// With [`ResultOrdering::Ordered`], results are yielded in the order of `values`;
+    /// with [`ResultOrdering::Unordered`], they are yielded as soon as they are ready,
+    /// which avoids waiting for slow executions that precede fast ones.
+    ///
+    /// A failed execution does not stop the stream.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use scylla::client::session::Session;
+    /// # use std::error::Error;
+    /// # async fn check_only_compiles(session: &Session) -> Result<(), Box<dyn Error>> {
+    /// use std::num::NonZeroUsize;
+    /// use futures::StreamExt;
+    /// use scylla::client::session::ResultOrdering;
+    ///
+    /// let prepared = session.prepare("INSERT INTO ks.tab (a, b) VALUES(?, ?)").await?;
+    /// let values = futures::stream::iter((0..1000).map(|i: i32| (i, i.to_string())));
+    ///
+    /// let mut results = session.execute_stream(
+    ///     &prepared,
+    ///     values,
+    ///     NonZeroUsize::new(64).unwrap(),
+    ///     ResultOrdering::Unordered,
+    /// );
+    /// while let Some((idx, result)) = results.next().await {
+    ///     if let Err(err) = result {
+    ///         eprintln!("Insertion of value set {idx} failed: {err}");
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn execute_stream<'a, V>(
+        &'a self,
+        prepared: &'a PreparedStatement,
+        values: impl Stream<Item = V> + Send + 'a,
+        concurrency: NonZeroUsize,
+        ordering: ResultOrdering,
+    ) -> BoxStream<'a, (usize, Result<QueryResult, ExecutionError>)>
+    where
+        V: SerializeRow + Send + 'a,
+    {
+        let executions = values.enumerate().map(move |(idx, values)| async move {
+            (idx, self.execute_unpaged(prepared, values).await)
+        });
+        match ordering {
+            ResultOrdering::Ordered => executions.buffered(concurrency.get()).boxed(),
+            ResultOrdering::Unordered => executions.buffer_unordered(concurrency.get()).boxed(),
+        }
+    }
+
     /// Execute a batch statement\
     /// Batch contains many `unprepared` or `prepared` statements which are executed at once\
     /// Batch doesn't return any rows.
@@ -930,7 +1385,11 @@ impl Session {
             is_confirmed_lwt: false,
         };
 
-        let span = RequestSpan::new_batch();
+        let span = RequestSpan::new_batch().with_instrumentation(
+            self.tracing_instrumentation.as_ref(),
+            None,
+            &tracing::Span::current(),
+        );
 
         let (run_request_result, coordinator): (
             RunRequestResult<NonErrorQueryResponse>,
@@ -938,11 +1397,13 @@ impl Session {
         ) = self
             .run_request(
                 statement_info,
+                LoggedStatement::Batch(batch),
                 &batch.config,
                 execution_profile,
                 |connection: Arc<Connection>,
                  consistency: Consistency,
-                 execution_profile: &ExecutionProfileInner| {
+                 execution_profile: &ExecutionProfileInner,
+                 settings: RequestSettings| {
                     let serial_consistency = batch
                         .config
                         .serial_consistency
@@ -954,6 +1415,7 @@ impl Session {
                                 values_ref,
                                 consistency,
                                 serial_consistency,
+                                settings,
                             )
                             .await
                             .and_then(QueryResponse::into_non_error_query_response)
@@ -961,7 +1423,7 @@ impl Session {
                 },
                 &span,
             )
-            .instrument(span.span().clone())
+            .instrument(span.entered_span().clone())
             .await?;
 
         let result = match run_request_result {
@@ -1008,7 +1470,13 @@ impl Session {
 
         let (tablet_sender, tablet_receiver) = tokio::sync::mpsc::channel(TABLET_CHANNEL_SIZE);
 
-        let tls_provider = if let Some(tls_context) = config.tls_context {
+        let tls_provider = if let Some(node_tls_provider) = config.node_tls_provider {
+            Some(TlsProvider::new_with_node_provider(node_tls_provider))
+        } else if let Some(reloadable_tls_context) = config.reloadable_tls_context {
+            Some(TlsProvider::new_with_reloadable_context(
+                reloadable_tls_context,
+            ))
+        } else if let Some(tls_context) = config.tls_context {
             // To silence warnings when TlsContext is an empty enum (tls features are disabled).
             // In such case, TlsProvider is uninhabited.
             #[cfg_attr(
@@ -1027,14 +1495,30 @@ impl Session {
             None
         };
 
+        #[cfg(feature = "metrics")]
+        let metrics = Arc::new(Metrics::with_latency_histogram_config(
+            &config.latency_histogram,
+        ));
+
         let connection_config = ConnectionConfig {
             local_ip_address: config.local_ip_address,
             shard_aware_local_port_range: config.shard_aware_local_port_range,
             compression: config.compression,
+            compression_threshold: config.compression_threshold,
             tcp_nodelay: config.tcp_nodelay,
             tcp_keepalive_interval: config.tcp_keepalive_interval,
+            socket_options: SocketOptions {
+                recv_buffer_size: config.tcp_recv_buffer_size,
+                send_buffer_size: config.tcp_send_buffer_size,
+                ip_tos: config.ip_tos,
+                bind_interface: config.bind_interface,
+            },
             timestamp_generator: config.timestamp_generator,
             tls_provider,
+            proxy_rules: ProxyRules {
+                default: config.proxy,
+                per_datacenter: Arc::new(config.datacenter_proxies),
+            },
             authenticator: config.authenticator,
             connect_timeout: config.connect_timeout,
             event_sender: None,
@@ -1043,8 +1527,16 @@ impl Session {
             write_coalescing_delay: config
                 .enable_write_coalescing
                 .then_some(config.write_coalescing_delay),
+            write_coalescing_max_bytes: config.write_coalescing_max_bytes,
+            response_frame_limits: ResponseFrameLimits::new()
+                .with_max_body_length(config.max_response_frame_body_length)
+                .with_max_decompressed_body_length(config.max_decompressed_response_body_length),
             keepalive_interval: config.keepalive_interval,
             keepalive_timeout: config.keepalive_timeout,
+            keepalive_request: config.keepalive_request,
+            close_on_keepalive_failure: config.close_on_keepalive_failure,
+            #[cfg(feature = "metrics")]
+            metrics: Some(Arc::clone(&metrics)),
             tablet_sender: Some(tablet_sender),
             identity: config.identity,
         };
@@ -1052,16 +1544,20 @@ impl Session {
         let pool_config = PoolConfig {
             connection_config,
             pool_size: config.connection_pool_size,
-            can_use_shard_aware_port: !config.disallow_shard_aware_port,
-            #[cfg(all(scylla_unstable, feature = "unstable-reconnect-policy"))]
+            dynamic_pool_size: config.dynamic_pool_size,
+            shard_aware_port_usage: if config.disallow_shard_aware_port {
+                ShardAwarePortUsage::Disabled
+            } else {
+                config.shard_aware_port_usage
+            },
             reconnect_policy: config.reconnect_policy,
-            #[cfg(not(all(scylla_unstable, feature = "unstable-reconnect-policy")))]
-            reconnect_policy: Arc::new(ExponentialReconnectPolicy::new()),
+            connect_lazily: config.connect_lazily,
+            connection_idle_timeout: config.connection_idle_timeout,
+            connection_max_age: config.connection_max_age,
+            max_in_flight_requests_per_node: config.max_in_flight_requests_per_node,
+            verbose_connection_logging: config.verbose_connection_logging,
         };
 
-        #[cfg(feature = "metrics")]
-        let metrics = Arc::new(Metrics::new());
-
         let host_listener = {
             #[cfg(all(scylla_unstable, feature = "unstable-host-listener"))]
             {
@@ -1077,11 +1573,23 @@ impl Session {
             known_nodes,
             pool_config,
             config.keyspaces_to_fetch,
+            config.keyspace_filter,
             config.fetch_schema_metadata,
             config.metadata_request_serverside_timeout,
+            config.initial_metadata_fetch_timeout,
+            config.allow_degraded_startup,
+            config.control_connection_standbys,
             config.hostname_resolution_timeout,
+            config.hostname_resolution_ttl,
+            config
+                .resolver
+                .unwrap_or_else(|| Arc::new(DefaultResolver::new())),
+            config.peer_address_preference,
             config.host_filter,
+            config.internal_requests_host_filter,
+            config.custom_partitioners,
             host_listener,
+            config.tablet_listener,
             config.cluster_metadata_refresh_interval,
             tablet_receiver,
             #[cfg(feature = "metrics")]
@@ -1094,18 +1602,30 @@ impl Session {
         let session = Self {
             cluster,
             default_execution_profile_handle,
-            schema_agreement_interval: config.schema_agreement_interval,
+            schema_agreement_policy: config.schema_agreement_policy.unwrap_or_else(|| {
+                SchemaAgreementPolicy::builder()
+                    .interval(config.schema_agreement_interval)
+                    .timeout(config.schema_agreement_timeout)
+                    .build()
+            }),
             #[cfg(feature = "metrics")]
             metrics,
-            schema_agreement_timeout: config.schema_agreement_timeout,
             schema_agreement_automatic_waiting: config.schema_agreement_automatic_waiting,
             refresh_metadata_on_auto_schema_agreement: config
                 .refresh_metadata_on_auto_schema_agreement,
             keyspace_name: Arc::new(ArcSwapOption::default()), // will be set by use_keyspace
             tracing_info_fetch_attempts: config.tracing_info_fetch_attempts,
             tracing_info_fetch_interval: config.tracing_info_fetch_interval,
+            tracing_info_fetch_max_interval: config.tracing_info_fetch_max_interval,
             tracing_info_fetch_consistency: config.tracing_info_fetch_consistency,
+            #[cfg(feature = "paging-state-codec")]
+            paging_state_codec: PagingStateCodec::new(config.paging_state_key),
             internal_statements: InternalStatements::default(),
+            request_observer: config.request_observer,
+            tracing_instrumentation: config.tracing_instrumentation,
+            trace_context_provider: config.trace_context_provider,
+            slow_query_logger: config.slow_query_logger,
+            session_history: config.session_history,
         };
 
         if let Some(keyspace_name) = config.used_keyspace {
@@ -1114,6 +1634,17 @@ impl Session {
                 .await?;
         }
 
+        if let Some(warmup) = config.pool_warmup {
+            let (full_pools, warmed_up_pools) =
+                session.get_cluster_state().warm_up_pools(&warmup).await;
+            if (full_pools as f64) < warmup.required_fraction() * warmed_up_pools as f64 {
+                return Err(NewSessionError::PoolWarmupFailed {
+                    full_pools,
+                    warmed_up_pools,
+                });
+            }
+        }
+
         Ok(session)
     }
 
@@ -1162,6 +1693,64 @@ impl Session {
             .await
     }
 
+    async fn do_execute_schema_change(
+        &self,
+        mut statement: Statement,
+    ) -> Result<Uuid, ExecutionError> {
+        if statement.get_retry_policy().is_none() {
+            statement.set_retry_policy(Some(Arc::new(SchemaChangeRetryPolicy::new())));
+        }
+
+        let span = RequestSpan::new_query(&statement.contents).with_instrumentation(
+            self.tracing_instrumentation.as_ref(),
+            Some(&statement.contents),
+            &tracing::Span::current(),
+        );
+        // Schema agreement is awaited below regardless of the session's settings,
+        // so it must not be awaited automatically, too.
+        statement.config.skip_auto_schema_agreement = true;
+        let (response, coordinator) = self
+            .query_response(&statement, (), None, PagingState::start(), &span)
+            .await?;
+
+        // The coordinator can only be required to agree if it receives internal requests.
+        let coordinator_id = coordinator.node().host_id;
+        let required_node = self
+            .get_cluster_state()
+            .accepts_internal_requests(coordinator_id)
+            .then_some(coordinator_id);
+        let schema_version = self
+            .await_schema_agreement_with_required_node(&self.schema_agreement_policy, required_node)
+            .await?;
+
+        if let Some(schema_change) = response.as_schema_change() {
+            self.refresh_metadata_keyspace(schema_change.event.keyspace_name())
+                .await?;
+        }
+
+        Ok(schema_version)
+    }
+
+    async fn do_execute_unpaged(
+        &self,
+        prepared: &PreparedStatement,
+        serialized_values: &SerializedValues,
+    ) -> Result<QueryResult, ExecutionError> {
+        let (result, paging_state) = self
+            .execute(prepared, serialized_values, None, PagingState::start())
+            .await?;
+        if !paging_state.finished() {
+            error!(
+                target: log_targets::REQUEST,
+                "Unpaged prepared query returned a non-empty paging state! This is a driver-side or server-side bug."
+            );
+            return Err(ExecutionError::LastAttemptError(
+                RequestAttemptError::NonfinishedPagingState,
+            ));
+        }
+        Ok(result)
+    }
+
     async fn do_query_unpaged(
         &self,
         statement: &Statement,
@@ -1172,6 +1761,7 @@ impl Session {
             .await?;
         if !paging_state_response.finished() {
             error!(
+                target: log_targets::REQUEST,
                 "Unpaged unprepared query returned a non-empty paging state! This is a driver-side or server-side bug."
             );
             return Err(ExecutionError::LastAttemptError(
@@ -1214,6 +1804,32 @@ impl Session {
         page_size: Option<PageSize>,
         paging_state: PagingState,
     ) -> Result<(QueryResult, PagingStateResponse), ExecutionError> {
+        let span = RequestSpan::new_query(&statement.contents).with_instrumentation(
+            self.tracing_instrumentation.as_ref(),
+            Some(&statement.contents),
+            &tracing::Span::current(),
+        );
+        let (response, coordinator) = self
+            .query_response(statement, values, page_size, paging_state, &span)
+            .await?;
+
+        let (result, paging_state_response) =
+            response.into_query_result_and_paging_state(coordinator)?;
+        span.record_result_fields(&result);
+
+        Ok((result, paging_state_response))
+    }
+
+    /// Sends a request to the database, returning the raw response
+    /// together with the coordinator which served it.
+    async fn query_response(
+        &self,
+        statement: &Statement,
+        values: impl SerializeRow,
+        page_size: Option<PageSize>,
+        paging_state: PagingState,
+        span: &RequestSpan,
+    ) -> Result<(NonErrorQueryResponse, Coordinator), ExecutionError> {
         let execution_profile = statement
             .get_execution_profile_handle()
             .unwrap_or_else(|| self.get_default_execution_profile_handle())
@@ -1231,19 +1847,20 @@ impl Session {
             ..Default::default()
         };
 
-        let span = RequestSpan::new_query(&statement.contents);
-        let span_ref = &span;
+        let span_ref = span;
         let (run_request_result, coordinator): (
             RunRequestResult<NonErrorQueryResponse>,
             Coordinator,
         ) = self
             .run_request(
                 statement_info,
+                LoggedStatement::Text(&statement.contents),
                 &statement.config,
                 execution_profile,
                 |connection: Arc<Connection>,
                  consistency: Consistency,
-                 execution_profile: &ExecutionProfileInner| {
+                 execution_profile: &ExecutionProfileInner,
+                 settings: RequestSettings| {
                     let serial_consistency = statement
                         .config
                         .serial_consistency
@@ -1259,6 +1876,7 @@ impl Session {
                                     statement,
                                     consistency,
                                     serial_consistency,
+                                    settings,
                                     page_size,
                                     paging_state_ref.clone(),
                                 )
@@ -1274,6 +1892,7 @@ impl Session {
                                     &serialized,
                                     consistency,
                                     serial_consistency,
+                                    settings,
                                     page_size,
                                     paging_state_ref.clone(),
                                 )
@@ -1282,9 +1901,9 @@ impl Session {
                         }
                     }
                 },
-                &span,
+                span,
             )
-            .instrument(span.span().clone())
+            .instrument(span.entered_span().clone())
             .await?;
 
         let response = match run_request_result {
@@ -1298,11 +1917,7 @@ impl Session {
             RunRequestResult::Completed(response) => response,
         };
 
-        let (result, paging_state_response) =
-            response.into_query_result_and_paging_state(coordinator)?;
-        span.record_result_fields(&result);
-
-        Ok((result, paging_state_response))
+        Ok((response, coordinator))
     }
 
     async fn handle_set_keyspace_response(
@@ -1311,6 +1926,7 @@ impl Session {
     ) -> Result<(), UseKeyspaceError> {
         if let Some(set_keyspace) = response.as_set_keyspace() {
             debug!(
+                target: log_targets::REQUEST,
                 "Detected USE KEYSPACE query, setting session's keyspace to {}",
                 set_keyspace.keyspace_name
             );
@@ -1328,8 +1944,16 @@ impl Session {
     ) -> Result<(), ExecutionError> {
         if self.schema_agreement_automatic_waiting {
             if response.as_schema_change().is_some() {
-                self.await_schema_agreement_with_required_node(Some(coordinator_id))
-                    .await?;
+                // The coordinator can only be required to agree if it receives internal requests.
+                let required_node = self
+                    .get_cluster_state()
+                    .accepts_internal_requests(coordinator_id)
+                    .then_some(coordinator_id);
+                self.await_schema_agreement_with_required_node(
+                    &self.schema_agreement_policy,
+                    required_node,
+                )
+                .await?;
             }
 
             if self.refresh_metadata_on_auto_schema_agreement
@@ -1362,8 +1986,9 @@ impl Session {
     /// `Session::query_iter` specialization for empty values.
     async fn do_query_iter_without_values(
         &self,
-        statement: Statement,
+        mut statement: Statement,
     ) -> Result<QueryPager, PagerExecutionError> {
+        self.apply_session_history(&mut statement.config);
         let execution_profile = statement
             .get_execution_profile_handle()
             .unwrap_or_else(|| self.get_default_execution_profile_handle())
@@ -1375,6 +2000,10 @@ impl Session {
             self.cluster.get_state(),
             #[cfg(feature = "metrics")]
             Arc::clone(&self.metrics),
+            self.request_observer.clone(),
+            self.tracing_instrumentation.clone(),
+            self.current_trace_parent(),
+            self.slow_query_logger.clone(),
         )
         .await
         .map_err(PagerExecutionError::NextPageError)
@@ -1452,6 +2081,7 @@ impl Session {
         statement: &Statement,
     ) -> Result<PreparedStatement, PrepareError> {
         let cluster_state = self.get_cluster_state();
+        cluster_state.connect_lazily_to_any_node().await;
 
         // Start by attempting preparation on a single (random) connection to every node.
         {
@@ -1516,6 +2146,7 @@ impl Session {
         for another_raw_prepared in raw_prepared_statements_results_iter.flatten() {
             if prepared.get_id() != another_raw_prepared.get_id() {
                 tracing::error!(
+                    target: log_targets::REQUEST,
                     "Got differing ids upon statement preparation: statement \"{}\", id1: {:?}, id2: {:?}",
                     prepared.get_statement(),
                     prepared.get_id(),
@@ -1534,7 +2165,9 @@ impl Session {
         // Let's return the PreparedStatement.
         prepared.set_partitioner_name(
             Self::extract_partitioner_name(&prepared, cluster_state)
-                .and_then(PartitionerName::from_str)
+                .and_then(|name| {
+                    PartitionerName::from_str(name, &cluster_state.custom_partitioners)
+                })
                 .unwrap_or_default(),
         );
 
@@ -1581,6 +2214,9 @@ impl Session {
             )
             .map_err(PartitionKeyError::into_execution_error)?
             .unzip();
+        let token = token.or_else(|| {
+            prepared.in_list_routing_token(serialized_values, &self.get_cluster_state())
+        });
 
         let execution_profile = prepared
             .get_execution_profile_handle()
@@ -1607,6 +2243,11 @@ impl Session {
             partition_key.as_ref().map(|pk| pk.iter()),
             token,
             serialized_values.buffer_size(),
+        )
+        .with_instrumentation(
+            self.tracing_instrumentation.as_ref(),
+            Some(prepared.get_statement()),
+            &tracing::Span::current(),
         );
 
         if !span.span().is_disabled() {
@@ -1623,11 +2264,13 @@ impl Session {
         ) = self
             .run_request(
                 statement_info,
+                LoggedStatement::Text(prepared.get_statement()),
                 &prepared.config,
                 execution_profile,
                 |connection: Arc<Connection>,
                  consistency: Consistency,
-                 execution_profile: &ExecutionProfileInner| {
+                 execution_profile: &ExecutionProfileInner,
+                 settings: RequestSettings| {
                     let serial_consistency = prepared
                         .config
                         .serial_consistency
@@ -1639,6 +2282,7 @@ impl Session {
                                 serialized_values,
                                 consistency,
                                 serial_consistency,
+                                settings,
                                 page_size,
                                 paging_state_ref.clone(),
                             )
@@ -1648,7 +2292,7 @@ impl Session {
                 },
                 &span,
             )
-            .instrument(span.span().clone())
+            .instrument(span.entered_span().clone())
             .await?;
 
         let response = match run_request_result {
@@ -1684,9 +2328,10 @@ impl Session {
     ///   if special compile flag is passed.
     async fn execute_iter_nongeneric(
         &self,
-        prepared: PreparedStatement,
+        mut prepared: PreparedStatement,
         values: SerializedValues,
     ) -> Result<QueryPager, PagerExecutionError> {
+        self.apply_session_history(&mut prepared.config);
         let execution_profile = prepared
             .get_execution_profile_handle()
             .unwrap_or_else(|| self.get_default_execution_profile_handle())
@@ -1699,6 +2344,10 @@ impl Session {
             cluster_state: self.cluster.get_state(),
             #[cfg(feature = "metrics")]
             metrics: Arc::clone(&self.metrics),
+            request_observer: self.request_observer.clone(),
+            tracing_instrumentation: self.tracing_instrumentation.clone(),
+            trace_parent: self.current_trace_parent(),
+            slow_query_logger: self.slow_query_logger.clone(),
         })
         .await
         .map_err(PagerExecutionError::NextPageError)
@@ -1752,6 +2401,47 @@ impl Session {
         Ok(prepared_batch)
     }
 
+    /// Prepares many statements concurrently.
+    ///
+    /// Up to `concurrency` statements are prepared at a time. Unlike [`Session::prepare_batch`],
+    /// a failure to prepare one statement does not fail the whole call: the returned vector
+    /// holds the result of preparing each statement, in the order of `statements`.
+    /// This is useful for preparing a large set of statements at application startup.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use scylla::client::session::Session;
+    /// # use std::error::Error;
+    /// # async fn check_only_compiles(session: &Session) -> Result<(), Box<dyn Error>> {
+    /// use std::num::NonZeroUsize;
+    ///
+    /// let statements = [
+    ///     "INSERT INTO ks.tab (a) VALUES(?)",
+    ///     "SELECT a FROM ks.tab WHERE a = ?",
+    /// ];
+    /// let results = session
+    ///     .prepare_all(statements, NonZeroUsize::new(16).unwrap())
+    ///     .await;
+    /// for (statement, result) in statements.iter().zip(results) {
+    ///     if let Err(err) = result {
+    ///         eprintln!("Failed to prepare {statement}: {err}");
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn prepare_all(
+        &self,
+        statements: impl IntoIterator<Item = impl Into<Statement>>,
+        concurrency: NonZeroUsize,
+    ) -> Vec<Result<PreparedStatement, PrepareError>> {
+        futures::stream::iter(statements.into_iter().map(Into::into))
+            .map(|statement: Statement| async move { self.prepare_nongeneric(&statement).await })
+            .buffered(concurrency.get())
+            .collect()
+            .await
+    }
+
     /// Sends `USE <keyspace_name>` request on all connections\
     /// This allows to write `SELECT * FROM table` instead of `SELECT * FROM keyspace.table`\
     ///
@@ -1817,6 +2507,22 @@ impl Session {
         self.cluster.refresh_metadata().await
     }
 
+    /// Manually trigger a refresh of a single keyspace's schema metadata\
+    /// Unlike [`Session::refresh_metadata`], topology and other keyspaces are not fetched,
+    /// which makes it much cheaper on clusters with many keyspaces and tables.
+    ///
+    /// If the keyspace no longer exists, it is removed from the cluster state.
+    /// The driver refreshes the affected keyspace automatically upon schema change events,
+    /// so this is normally not needed.
+    pub async fn refresh_metadata_keyspace(
+        &self,
+        keyspace: impl Into<String>,
+    ) -> Result<(), MetadataError> {
+        self.cluster
+            .refresh_keyspace_metadata(keyspace.into())
+            .await
+    }
+
     /// Access metrics collected by the driver\
     /// Driver collects various metrics like number of queries or query latencies.
     /// They can be read using this method
@@ -1825,6 +2531,22 @@ impl Session {
         Arc::clone(&self.metrics)
     }
 
+    /// Returns true if the session runs in degraded mode: it was started with
+    /// [`SessionConfig::allow_degraded_startup`], the initial metadata fetch failed,
+    /// and no metadata refresh has succeeded since.
+    ///
+    /// In degraded mode, [cluster state](Self::get_cluster_state) consists only
+    /// of the known nodes with dummy tokens, and contains no schema.
+    pub fn is_metadata_degraded(&self) -> bool {
+        self.cluster.is_metadata_degraded()
+    }
+
+    /// Access the history of recent requests collected by the session,
+    /// if it was enabled with [SessionBuilder::session_history](crate::client::session_builder::SessionBuilder::session_history).
+    pub fn get_session_history(&self) -> Option<&Arc<RecentHistoryCollector>> {
+        self.session_history.as_ref()
+    }
+
     /// Access cluster state visible by the driver.
     ///
     /// Driver collects various information about network topology or schema.
@@ -1833,25 +2555,224 @@ impl Session {
         self.cluster.get_state()
     }
 
+    /// Subscribe to changes in the cluster topology.
+    ///
+    /// The returned stream yields a [TopologyEvent](crate::cluster::TopologyEvent) whenever
+    /// a node is added, removed, goes up or down, or changes its address, datacenter or rack.
+    /// Events are derived from server EVENTs as well as from metadata refreshes.
+    /// Only changes that happen after this call are reported; use [Session::get_cluster_state]
+    /// to obtain the current topology.
+    ///
+    /// ```rust
+    /// # extern crate scylla;
+    /// # use scylla::client::session::Session;
+    /// # async fn example(session: &Session) {
+    /// use futures::StreamExt;
+    /// use scylla::cluster::TopologyEvent;
+    ///
+    /// let mut events = session.topology_events();
+    /// while let Some(event) = events.next().await {
+    ///     if let TopologyEvent::NodeDown { address, .. } = event {
+    ///         println!("Node {address} is down");
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn topology_events(&self) -> TopologyEventStream {
+        self.cluster.subscribe_topology_events()
+    }
+
+    /// Awaits until no change of the cluster topology or metadata has been observed
+    /// for `settle_duration`.
+    ///
+    /// Every [TopologyEvent](crate::cluster::TopologyEvent) observed by the driver, e.g. a node
+    /// being added or going up, or a metadata refresh changing the cluster state, restarts
+    /// the settle period. This is useful after scaling the cluster, before starting traffic.
+    /// Changes are observed through server events and periodic metadata refreshes, so
+    /// the settle period should be long enough for the cluster to report pending changes.
+    ///
+    /// If topology keeps changing for `timeout`, `StableTopologyError::Timeout` is returned.
+    ///
+    /// ```rust
+    /// # extern crate scylla;
+    /// # use scylla::client::session::Session;
+    /// # use std::error::Error;
+    /// # async fn example(session: &Session) -> Result<(), Box<dyn Error>> {
+    /// use std::time::Duration;
+    ///
+    /// session
+    ///     .await_stable_topology(Duration::from_secs(10), Duration::from_secs(300))
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn await_stable_topology(
+        &self,
+        settle_duration: Duration,
+        timeout: Duration,
+    ) -> Result<(), StableTopologyError> {
+        let mut events = self.topology_events();
+        let settle = async {
+            // The stream ends only when the session is dropped, which cannot happen here.
+            while let Ok(Some(event)) = runtime::timeout(settle_duration, events.next()).await {
+                debug!(target: log_targets::TOPOLOGY, "Topology is not stable yet: {:?}", event);
+            }
+        };
+        runtime::timeout(timeout, settle)
+            .await
+            .map_err(|_| StableTopologyError::Timeout(timeout))
+    }
+
+    /// Produces a snapshot of the driver's state, intended to be attached to bug reports
+    /// and support requests.
+    ///
+    /// The report covers connection pools and recent connection events of all nodes,
+    /// the control connection, the age of cluster metadata, the default execution profile
+    /// and, with the `metrics` feature, a summary of driver metrics.
+    /// With the `serde` feature, the report can be serialized, e.g. to JSON.
+    ///
+    /// ```rust
+    /// # extern crate scylla;
+    /// # use scylla::client::session::Session;
+    /// # fn example(session: &Session) {
+    /// let report = session.diagnostics_report();
+    /// for node in &report.nodes {
+    ///     println!("{}: {:?}", node.address, node.connections_per_shard);
+    /// }
+    /// # }
+    /// ```
+    pub fn diagnostics_report(&self) -> DiagnosticsReport {
+        let (control_connection, metadata_age) = self.cluster.metadata_status();
+        DiagnosticsReport {
+            generated_at: SystemTime::now(),
+            driver_version: env!("CARGO_PKG_VERSION"),
+            keyspace: self.get_keyspace().map(|ks| ks.as_str().to_owned()),
+            control_connection,
+            metadata_age,
+            metadata_degraded: self.cluster.is_metadata_degraded(),
+            nodes: self
+                .get_cluster_state()
+                .get_nodes_info()
+                .iter()
+                .map(|node| NodeDiagnostics::new(node))
+                .collect(),
+            default_profile: ExecutionProfileDiagnostics::new(
+                &self.get_default_execution_profile_handle().to_profile(),
+            ),
+            #[cfg(feature = "metrics")]
+            metrics: MetricsDiagnostics::new(&self.metrics),
+        }
+    }
+
+    /// Returns the state of the control connection, which is the connection used
+    /// to fetch cluster metadata and receive server events.
+    ///
+    /// Changes of the control connection are also reported as
+    /// [TopologyEvent::ControlConnectionLost](crate::cluster::TopologyEvent::ControlConnectionLost) and
+    /// [TopologyEvent::ControlConnectionMoved](crate::cluster::TopologyEvent::ControlConnectionMoved).
+    ///
+    /// ```rust
+    /// # extern crate scylla;
+    /// # use scylla::client::session::Session;
+    /// # fn example(session: &Session) {
+    /// let status = session.control_connection_status();
+    /// if !status.working {
+    ///     println!("Control connection to {} is broken: {:?}", status.address, status.last_error);
+    /// }
+    /// # }
+    /// ```
+    pub fn control_connection_status(&self) -> ControlConnectionDiagnostics {
+        self.cluster.metadata_status().0
+    }
+
+    /// Gives typed access to virtual tables of the `system` keyspace,
+    /// e.g. the clients connected to a node or its runtime statistics.
+    ///
+    /// See [system_views](crate::system_views) for more information.
+    ///
+    /// ```rust
+    /// # extern crate scylla;
+    /// # use scylla::client::session::Session;
+    /// # use std::error::Error;
+    /// # async fn example(session: &Session) -> Result<(), Box<dyn Error>> {
+    /// for client in session.system().clients().await? {
+    ///     println!("{}:{} {:?}", client.address, client.port, client.driver_name);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn system(&self) -> SystemViews<'_> {
+        SystemViews::new(self)
+    }
+
+    /// Access the codec used to export and validate paging states
+    /// that leave the application, e.g. as cursors in HTTP responses.
+    ///
+    /// The codec signs exported paging states if
+    /// [`SessionConfig::paging_state_key`] is set.
+    #[cfg(feature = "paging-state-codec")]
+    pub fn paging_state_codec(&self) -> &PagingStateCodec {
+        &self.paging_state_codec
+    }
+
     /// Get [`TracingInfo`] of a traced query performed earlier
     ///
     /// See [the book](https://rust-driver.docs.scylladb.com/stable/tracing/tracing.html)
     /// for more information about query tracing
     pub async fn get_tracing_info(&self, tracing_id: &Uuid) -> Result<TracingInfo, TracingError> {
+        let delays = crate::observability::tracing::tracing_info_fetch_delays(
+            self.tracing_info_fetch_interval,
+            self.tracing_info_fetch_max_interval,
+        );
         // tracing_info_fetch_attempts is NonZeroU32 so at least one attempt will be made
-        for _ in 0..self.tracing_info_fetch_attempts.get() {
+        for delay in delays.take(self.tracing_info_fetch_attempts.get() as usize) {
             let current_try: Option<TracingInfo> =
                 self.try_getting_tracing_info(tracing_id).await?;
 
             match current_try {
                 Some(tracing_info) => return Ok(tracing_info),
-                None => tokio::time::sleep(self.tracing_info_fetch_interval).await,
+                None => runtime::sleep(delay).await,
             };
         }
 
         Err(TracingError::EmptyResults)
     }
 
+    /// Get a [`TracingReport`] of a traced request performed earlier:
+    /// its [`TracingInfo`], analysed to compute durations of events
+    /// and the activity of each node involved.
+    ///
+    /// Tracing info is fetched as in [`Session::get_tracing_info`].
+    ///
+    /// # Example
+    /// ```rust
+    /// # use scylla::client::session::Session;
+    /// # use std::error::Error;
+    /// # async fn check_only_compiles(session: &Session) -> Result<(), Box<dyn Error>> {
+    /// use scylla::statement::unprepared::Statement;
+    ///
+    /// let mut statement = Statement::new("SELECT a FROM ks.tab");
+    /// statement.set_tracing(true);
+    /// let result = session.query_unpaged(statement, &[]).await?;
+    ///
+    /// if let Some(tracing_id) = result.tracing_id() {
+    ///     let report = session.get_tracing_report(&tracing_id).await?;
+    ///     println!("Took {:?}", report.total_duration());
+    ///     for event in report.slowest_events(3) {
+    ///         println!("{:?} took {:?}", event.activity, event.duration);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_tracing_report(
+        &self,
+        tracing_id: &Uuid,
+    ) -> Result<TracingReport, TracingError> {
+        let info = self.get_tracing_info(tracing_id).await?;
+        Ok(TracingReport::new(*tracing_id, info))
+    }
+
     /// Gets the name of the keyspace that is currently set, or `None` if no
     /// keyspace was set.
     ///
@@ -1935,29 +2856,67 @@ impl Session {
     async fn run_request<'a, QueryFut>(
         &'a self,
         statement_info: RoutingInfo<'a>,
+        statement: LoggedStatement<'a>,
         statement_config: &'a StatementConfig,
         execution_profile: Arc<ExecutionProfileInner>,
-        run_request_once: impl Fn(Arc<Connection>, Consistency, &ExecutionProfileInner) -> QueryFut,
+        run_request_once: impl Fn(
+            Arc<Connection>,
+            Consistency,
+            &ExecutionProfileInner,
+            RequestSettings,
+        ) -> QueryFut,
         request_span: &'a RequestSpan,
     ) -> Result<(RunRequestResult<NonErrorQueryResponse>, Coordinator), ExecutionError>
     where
         QueryFut: Future<Output = Result<NonErrorQueryResponse, RequestAttemptError>>,
     {
+        let history_listener: Option<&'a dyn HistoryListener> =
+            statement_config.history_listener.as_deref().or_else(|| {
+                self.session_history
+                    .as_deref()
+                    .map(|history| history as &dyn HistoryListener)
+            });
         let history_listener_and_id: Option<(&'a dyn HistoryListener, history::RequestId)> =
-            statement_config
-                .history_listener
-                .as_ref()
-                .map(|hl| (&**hl, hl.log_request_start()));
+            history_listener.map(|hl| {
+                let request_id = hl.log_request_start();
+                if !statement_config.tags.is_empty() {
+                    hl.log_request_tags(request_id, &statement_config.tags);
+                }
+                hl.log_request_statement(request_id, &statement.text());
+                (hl, request_id)
+            });
+        request_span.record_tags(&statement_config.tags);
+        request_span.record_routing_info(statement_info.table, statement_info.consistency);
+
+        let observed_request = self.request_observer.as_deref().map(|observer| {
+            let id = ObservedRequestId::next();
+            observer.on_request_start(&RequestStart {
+                id,
+                table: statement_info.table,
+                tags: &statement_config.tags,
+            });
+            (observer, id, std::time::Instant::now())
+        });
+        let slow_query = self
+            .slow_query_logger
+            .as_ref()
+            .map(|logger| (logger, logger.start_request()));
 
         let load_balancer = statement_config
             .load_balancing_policy
             .as_deref()
             .unwrap_or(execution_profile.load_balancing_policy.as_ref());
 
+        let request_settings = RequestSettings::from_profile(&execution_profile)
+            .with_trace_parent(self.current_trace_parent());
+
         let runner = async {
             let cluster_state = self.cluster.get_state();
-            let request_plan =
-                load_balancing::Plan::new(load_balancer, &statement_info, &cluster_state);
+            let mut request_plan = load_balancing::SaturationAwarePlan::new(
+                load_balancing::Plan::new(load_balancer, &statement_info, &cluster_state),
+                #[cfg(feature = "metrics")]
+                Arc::clone(&self.metrics),
+            );
 
             // If a speculative execution policy is used to run request, request_plan has to be shared
             // between different async functions. This struct helps to wrap request_plan in mutex so it
@@ -1989,8 +2948,12 @@ impl Session {
 
             match speculative_policy {
                 Some(speculative) if statement_config.is_idempotent => {
+                    // The first target is computed upfront, so that the policy
+                    // can take it into account.
+                    let first = request_plan.next();
+                    let first_target = first.map(|(node, _)| Arc::clone(node));
                     let shared_request_plan = SharedPlan {
-                        iter: std::sync::Mutex::new(request_plan),
+                        iter: std::sync::Mutex::new(first.into_iter().chain(request_plan)),
                     };
 
                     let request_runner_generator = |is_speculative: bool| {
@@ -2014,9 +2977,11 @@ impl Session {
 
                         if is_speculative {
                             request_span.inc_speculative_executions();
+                            #[cfg(feature = "metrics")]
+                            self.metrics.inc_speculative_executions();
                         }
 
-                        self.run_request_speculative_fiber(
+                        let fiber = self.run_request_speculative_fiber(
                             &shared_request_plan,
                             &run_request_once,
                             &execution_profile,
@@ -2028,13 +2993,20 @@ impl Session {
                                 load_balancing_policy: load_balancer,
                                 query_info: &statement_info,
                                 request_span,
+                                request_observer: observed_request
+                                    .map(|(observer, id, _)| (observer, id)),
+                                slow_query_tracker: slow_query.as_ref().map(|(_, tracker)| tracker),
+                                request_settings: &request_settings,
                             },
-                        )
+                        );
+                        log_fiber_cancellation(history_data, fiber)
                     };
 
                     let context = speculative_execution::Context {
                         #[cfg(feature = "metrics")]
                         metrics: Arc::clone(&self.metrics),
+                        cluster_state: Arc::clone(&cluster_state),
+                        first_target,
                     };
 
                     speculative_execution::execute(
@@ -2053,7 +3025,7 @@ impl Session {
                                 request_id: *request_id,
                                 speculative_id: None,
                             });
-                    self.run_request_speculative_fiber(
+                    let fiber = self.run_request_speculative_fiber(
                         request_plan,
                         &run_request_once,
                         &execution_profile,
@@ -2065,10 +3037,15 @@ impl Session {
                             load_balancing_policy: load_balancer,
                             query_info: &statement_info,
                             request_span,
+                            request_observer: observed_request
+                                .map(|(observer, id, _)| (observer, id)),
+                            slow_query_tracker: slow_query.as_ref().map(|(_, tracker)| tracker),
+                            request_settings: &request_settings,
                         },
-                    )
-                    .await
-                    .unwrap_or(Err(RequestError::EmptyPlan))
+                    );
+                    log_fiber_cancellation(history_data, fiber)
+                        .await
+                        .unwrap_or(Err(RequestError::EmptyPlan))
                 }
             }
         };
@@ -2077,20 +3054,23 @@ impl Session {
             .request_timeout
             .or(execution_profile.request_timeout);
         let result = match effective_timeout {
-            Some(timeout) => tokio::time::timeout(timeout, runner).await.unwrap_or_else(
-                |_: tokio::time::error::Elapsed| {
-                    #[cfg(feature = "metrics")]
-                    self.metrics.inc_request_timeouts();
-
-                    let timeout_error = RequestError::RequestTimeout(timeout);
-                    trace!(
-                        parent: request_span.span(),
-                        error = %timeout_error,
-                        "Request timed out"
-                    );
-                    Err(timeout_error)
-                },
-            ),
+            Some(timeout) => {
+                runtime::timeout(timeout, runner)
+                    .await
+                    .unwrap_or_else(|_: runtime::Elapsed| {
+                        #[cfg(feature = "metrics")]
+                        self.metrics.inc_request_timeouts();
+
+                        let timeout_error = RequestError::RequestTimeout(timeout);
+                        trace!(
+                            target: log_targets::REQUEST,
+                            parent: request_span.span(),
+                            error = %timeout_error,
+                            "Request timed out"
+                        );
+                        Err(timeout_error)
+                    })
+            }
             None => runner.await,
         };
 
@@ -2101,16 +3081,60 @@ impl Session {
             }
         }
 
+        if let Some((observer, id, start)) = observed_request {
+            observer.on_request_end(&RequestEnd {
+                id,
+                latency: start.elapsed(),
+                tags: &statement_config.tags,
+                result: result.as_ref().map(|_| ()),
+            });
+        }
+        if let Some((logger, tracker)) = &slow_query {
+            logger.finish_request(
+                tracker,
+                statement,
+                statement_info.token,
+                statement_info.table,
+                &statement_config.tags,
+                result.as_ref().map(|_| ()),
+            );
+        }
+        request_span.record_outcome(result.as_ref().map(|_| ()));
+        if let Ok((RunRequestResult::Completed(response), _)) = &result {
+            request_span.record_tracing_id(response.tracing_id);
+        }
+
         // Automatically handle meaningful responses.
         if let Ok((RunRequestResult::Completed(ref response), ref coordinator)) = result {
             self.handle_set_keyspace_response(response).await?;
-            self.handle_auto_await_schema_agreement(response, coordinator.node().host_id)
-                .await?;
+            if !statement_config.skip_auto_schema_agreement {
+                self.handle_auto_await_schema_agreement(response, coordinator.node().host_id)
+                    .await?;
+            }
         }
 
         result.map_err(RequestError::into_execution_error)
     }
 
+    /// Makes pagers log their requests to the session history,
+    /// unless the statement has its own history listener.
+    fn apply_session_history(&self, config: &mut StatementConfig) {
+        if config.history_listener.is_none() {
+            config.history_listener = self
+                .session_history
+                .clone()
+                .map(|history| history as Arc<dyn HistoryListener>);
+        }
+    }
+
+    /// Returns the trace context to be propagated to the cluster
+    /// by the request which is currently started, if any.
+    fn current_trace_parent(&self) -> Option<TraceParent> {
+        self.trace_context_provider
+            .as_ref()
+            .and_then(|provider| provider.current_trace_parent())
+    }
+
     /// Executes the closure `run_request_once`, provided the load balancing plan and some information
     /// about the request, including retry session.
     /// If request fails, retry session is used to perform retries.
@@ -2119,7 +3143,12 @@ impl Session {
     async fn run_request_speculative_fiber<'a, QueryFut>(
         &'a self,
         request_plan: impl Iterator<Item = (NodeRef<'a>, Shard)>,
-        run_request_once: impl Fn(Arc<Connection>, Consistency, &ExecutionProfileInner) -> QueryFut,
+        run_request_once: impl Fn(
+            Arc<Connection>,
+            Consistency,
+            &ExecutionProfileInner,
+            RequestSettings,
+        ) -> QueryFut,
         execution_profile: &ExecutionProfileInner,
         mut context: ExecuteRequestContext<'a>,
     ) -> Option<Result<(RunRequestResult<NonErrorQueryResponse>, Coordinator), RequestError>>
@@ -2134,11 +3163,12 @@ impl Session {
         'nodes_in_plan: for (node, shard) in request_plan {
             let span = trace_span!("Executing request", node = %node.address, shard = %shard);
             'same_node_retries: loop {
-                trace!(parent: &span, "Execution started");
+                trace!(target: log_targets::REQUEST, parent: &span, "Execution started");
                 let connection = match node.connection_for_shard(shard).await {
                     Ok(connection) => connection,
                     Err(e) => {
                         trace!(
+                            target: log_targets::REQUEST,
                             parent: &span,
                             error = %e,
                             "Choosing connection failed"
@@ -2156,6 +3186,7 @@ impl Session {
 
                 let connect_address = connection.get_connect_address();
                 trace!(
+                    target: log_targets::REQUEST,
                     parent: &span,
                     connection = %connect_address,
                     "Sending"
@@ -2164,19 +3195,42 @@ impl Session {
                     Coordinator::new(node, node.sharder().is_some().then_some(shard), &connection);
 
                 let attempt_id: Option<history::AttemptId> =
-                    context.log_attempt_start(connect_address);
+                    context.log_attempt_start(node, connect_address);
+                let attempt_span = context.request_span.new_attempt_span(
+                    connect_address,
+                    node.host_id,
+                    connection
+                        .get_shard_info()
+                        .as_ref()
+                        .map(|info| Shard::from(info.shard)),
+                    current_consistency,
+                );
+                let in_flight = node.start_request();
                 let request_result: Result<NonErrorQueryResponse, RequestAttemptError> =
-                    run_request_once(connection, current_consistency, execution_profile)
-                        .instrument(span.clone())
-                        .await;
+                    run_request_once(
+                        connection,
+                        current_consistency,
+                        execution_profile,
+                        context.request_settings.clone(),
+                    )
+                    .instrument(span.clone())
+                    .instrument(attempt_span.span().clone())
+                    .await;
+                drop(in_flight);
 
                 let elapsed = request_start.elapsed();
                 let request_error: RequestAttemptError = match request_result {
                     Ok(response) => {
-                        trace!(parent: &span, "Request succeeded");
+                        trace!(target: log_targets::REQUEST, parent: &span, "Request succeeded");
                         #[cfg(feature = "metrics")]
-                        let _ = self.metrics.log_query_latency(elapsed.as_millis() as u64);
+                        {
+                            let latency = elapsed.as_millis() as u64;
+                            let _ = self.metrics.log_query_latency(latency);
+                            let _ = self.metrics.log_node_latency(node.host_id, latency);
+                        }
                         context.log_attempt_success(&attempt_id);
+                        context.observe_attempt_end(node, shard, elapsed, Ok(()));
+                        attempt_span.record_outcome::<RequestAttemptError>(Ok(()));
                         context.load_balancing_policy.on_request_success(
                             context.query_info,
                             elapsed,
@@ -2186,12 +3240,19 @@ impl Session {
                     }
                     Err(e) => {
                         trace!(
+                            target: log_targets::REQUEST,
                             parent: &span,
                             last_error = %e,
                             "Request failed"
                         );
+                        if let RequestAttemptError::UnableToAllocStreamId = e {
+                            node.record_stream_ids_exhausted(shard);
+                        }
+                        node.record_request_error();
                         #[cfg(feature = "metrics")]
                         self.metrics.inc_failed_nonpaged_queries();
+                        context.observe_attempt_end(node, shard, elapsed, Err(&e));
+                        attempt_span.record_outcome(Err(&e));
                         context.load_balancing_policy.on_request_failure(
                             context.query_info,
                             elapsed,
@@ -2202,6 +3263,14 @@ impl Session {
                     }
                 };
 
+                #[cfg(feature = "metrics")]
+                if let RequestAttemptError::DbError(DbError::RateLimitReached { .. }, _) =
+                    &request_error
+                {
+                    self.metrics
+                        .inc_rate_limited_requests(context.query_info.table);
+                }
+
                 // Use retry policy to decide what to do next
                 let request_info = RequestInfo {
                     error: &request_error,
@@ -2213,11 +3282,17 @@ impl Session {
 
                 let retry_decision = context.retry_session.decide_should_retry(request_info);
                 trace!(
+                    target: log_targets::REQUEST,
                     parent: &span,
                     retry_decision = ?retry_decision
                 );
 
                 context.log_attempt_error(&attempt_id, &request_error, &retry_decision);
+                attempt_span.record_retry_decision(&retry_decision);
+
+                #[cfg(feature = "metrics")]
+                self.metrics
+                    .log_retry_decision(retry_decision.kind(current_consistency), &request_error);
 
                 last_error = Some(request_error.into());
 
@@ -2228,12 +3303,26 @@ impl Session {
                         current_consistency = new_cl.unwrap_or(current_consistency);
                         continue 'same_node_retries;
                     }
+                    RetryDecision::RetrySameTargetAfter(new_cl, delay) => {
+                        #[cfg(feature = "metrics")]
+                        self.metrics.inc_retries_num();
+                        current_consistency = new_cl.unwrap_or(current_consistency);
+                        runtime::sleep(delay).await;
+                        continue 'same_node_retries;
+                    }
                     RetryDecision::RetryNextTarget(new_cl) => {
                         #[cfg(feature = "metrics")]
                         self.metrics.inc_retries_num();
                         current_consistency = new_cl.unwrap_or(current_consistency);
                         continue 'nodes_in_plan;
                     }
+                    RetryDecision::RetryNextTargetAfter(new_cl, delay) => {
+                        #[cfg(feature = "metrics")]
+                        self.metrics.inc_retries_num();
+                        current_consistency = new_cl.unwrap_or(current_consistency);
+                        runtime::sleep(delay).await;
+                        continue 'nodes_in_plan;
+                    }
                     RetryDecision::DontRetry => break 'nodes_in_plan,
 
                     RetryDecision::IgnoreWriteError => {
@@ -2248,77 +3337,138 @@ impl Session {
 
     /// Awaits schema agreement among all reachable nodes.
     ///
-    /// Issues an agreement check each `Session::schema_agreement_interval`.
-    /// If agreement is not reached in `Session::schema_agreement_timeout`,
+    /// Agreement is awaited according to the session's schema agreement policy
+    /// (see [SessionBuilder::schema_agreement_policy](crate::client::session_builder::SessionBuilder::schema_agreement_policy)).
+    /// By default, an agreement check is issued each `SessionConfig::schema_agreement_interval`.
+    /// If agreement is not reached in `SessionConfig::schema_agreement_timeout`,
     /// `SchemaAgreementError::Timeout` is returned.
     pub async fn await_schema_agreement(&self) -> Result<Uuid, SchemaAgreementError> {
-        self.await_schema_agreement_with_required_node(None).await
+        self.await_schema_agreement_with_required_node(&self.schema_agreement_policy, None)
+            .await
     }
 
-    /// Awaits schema agreement among all reachable nodes.
+    /// Awaits schema agreement among reachable nodes, according to the given policy
+    /// instead of the session's one.
     ///
-    /// Issues an agreement check each `Session::schema_agreement_interval`.
-    /// If agreement is not reached in `Session::schema_agreement_timeout`,
+    /// Issues an agreement check each [SchemaAgreementPolicy::interval], and reports
+    /// each check that did not reach agreement to the policy's listener.
+    /// If agreement is not reached in [SchemaAgreementPolicy::timeout],
     /// `SchemaAgreementError::Timeout` is returned.
     ///
+    /// # Example
+    /// ```rust
+    /// # use scylla::client::session::Session;
+    /// # use std::error::Error;
+    /// # async fn check_only_compiles(session: &Session) -> Result<(), Box<dyn Error>> {
+    /// use std::time::Duration;
+    /// use scylla::policies::schema_agreement::SchemaAgreementPolicy;
+    ///
+    /// let policy = SchemaAgreementPolicy::builder()
+    ///     .timeout(Duration::from_secs(300))
+    ///     .required_fraction(0.9)
+    ///     .build();
+    /// let version = session.await_schema_agreement_with_policy(&policy).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn await_schema_agreement_with_policy(
+        &self,
+        policy: &SchemaAgreementPolicy,
+    ) -> Result<Uuid, SchemaAgreementError> {
+        self.await_schema_agreement_with_required_node(policy, None)
+            .await
+    }
+
+    /// Awaits schema agreement among reachable nodes, according to `policy`.
+    ///
     /// If `required_node` is Some, only returns Ok if this node successfully
     /// returned its schema version during the agreement process.
     async fn await_schema_agreement_with_required_node(
         &self,
+        policy: &SchemaAgreementPolicy,
         required_node: Option<Uuid>,
     ) -> Result<Uuid, SchemaAgreementError> {
+        let start = std::time::Instant::now();
         // None: no finished attempt recorded
         // Some(Ok(())): Last attempt successful, without agreement
         // Some(Err(_)): Last attempt failed
         let mut last_agreement_failure: Option<Result<(), SchemaAgreementError>> = None;
-        timeout(self.schema_agreement_timeout, async {
+        runtime::timeout(policy.timeout, async {
             loop {
                 let result = self
-                    .check_schema_agreement_with_required_node(required_node)
+                    .check_schema_agreement_with_required_node(policy, required_node)
                     .await;
                 match result {
-                    Ok(Some(agreed_version)) => return agreed_version,
-                    Ok(None) => last_agreement_failure = Some(Ok(())),
+                    Ok((SchemaAgreementCheck::Agreed(agreed_version), _)) => return agreed_version,
+                    Ok((
+                        SchemaAgreementCheck::Disagreed {
+                            prevailing_version,
+                            disagreeing_nodes,
+                        },
+                        schema_versions,
+                    )) => {
+                        if let Some(listener) = policy.listener.as_deref() {
+                            listener.on_disagreement(&SchemaAgreementProgress {
+                                elapsed: start.elapsed(),
+                                schema_versions,
+                                prevailing_version,
+                                disagreeing_nodes,
+                            });
+                        }
+                        last_agreement_failure = Some(Ok(()))
+                    }
                     Err(err) => last_agreement_failure = Some(Err(err)),
                 }
-                tokio::time::sleep(self.schema_agreement_interval).await;
+                runtime::sleep(policy.interval).await;
             }
         })
         .await
         .map_err(|_| {
             match last_agreement_failure {
                 // There were no finished attempts - the only error we can return is Timeout.
-                None => SchemaAgreementError::Timeout(self.schema_agreement_timeout),
+                None => SchemaAgreementError::Timeout(policy.timeout),
                 // If the last finished attempt resulted in an error, this error will be more informative than Timeout.
                 Some(Err(err)) => err,
                 // This is the canonical case for timeout - last attempt finished successfully, but without agreement.
-                Some(Ok(())) => SchemaAgreementError::Timeout(self.schema_agreement_timeout),
+                Some(Ok(())) => SchemaAgreementError::Timeout(policy.timeout),
             }
         })
     }
 
-    /// Checks if all reachable nodes have the same schema version.
+    /// Checks if reachable nodes agree on the schema version, according to
+    /// the session's schema agreement policy. By default, all reachable nodes
+    /// must have the same schema version.
     ///
     /// If so, returns that agreed upon version.
     pub async fn check_schema_agreement(&self) -> Result<Option<Uuid>, SchemaAgreementError> {
-        self.check_schema_agreement_with_required_node(None).await
+        let (check, _) = self
+            .check_schema_agreement_with_required_node(&self.schema_agreement_policy, None)
+            .await?;
+        Ok(match check {
+            SchemaAgreementCheck::Agreed(version) => Some(version),
+            SchemaAgreementCheck::Disagreed { .. } => None,
+        })
     }
 
-    /// Checks if all reachable nodes have the same schema version.
-    /// If so, returns that agreed upon version.
+    /// Checks if reachable nodes agree on the schema version, according to `policy`.
+    /// Returns the outcome together with schema versions of nodes taken into account.
     ///
     /// If `required_node` is Some, only returns Ok if this node successfully
     /// returned its schema version.
+    #[allow(clippy::result_large_err)]
     async fn check_schema_agreement_with_required_node(
         &self,
+        policy: &SchemaAgreementPolicy,
         required_node: Option<Uuid>,
-    ) -> Result<Option<Uuid>, SchemaAgreementError> {
+    ) -> Result<(SchemaAgreementCheck, Vec<(Uuid, Uuid)>), SchemaAgreementError> {
         // Get lazily prepared statement for schema version query
         let schema_version_stmt = self.get_schema_version_statement().await?;
 
         let cluster_state = self.get_cluster_state();
+        cluster_state.connect_lazily_to_any_node().await;
         // The iterator is guaranteed to be nonempty.
-        let per_node_connections = cluster_state.iter_working_connections_per_node()?;
+        let per_node_connections =
+            cluster_state.iter_working_connections_per_node_for_internal_requests()?;
 
         // Therefore, this iterator is guaranteed to be nonempty, too.
         let handles = per_node_connections.map(|(host_id, pool)| async move {
@@ -2351,35 +3501,34 @@ impl Session {
 
         // Now we no longer need all the errors. We can return if there is
         // irrecoverable one, and collect the Ok values otherwise.
-        let versions_results: Vec<_> = versions_results
+        let versions_results: Vec<(Uuid, SchemaNodeResult)> = versions_results
             .into_iter()
-            .map(|(_, result)| result)
+            .map(|(host_id, result)| result.map(|result| (host_id, result)))
             .try_collect()?;
 
         // unwrap is safe because iterator is still not empty.
-        let local_version = match versions_results
+        if let (_, SchemaNodeResult::BrokenConnection(err)) = versions_results
             .iter()
-            .find_or_first(|r| matches!(r, SchemaNodeResult::Success(_)))
+            .find_or_first(|(_, r)| matches!(r, SchemaNodeResult::Success(_)))
             .unwrap()
         {
-            SchemaNodeResult::Success(v) => *v,
-            SchemaNodeResult::BrokenConnection(err) => {
-                // There are only broken connection errors. Nothing better to do
-                // than to return an error.
-                return Err(SchemaAgreementError::RequestError(
-                    RequestAttemptError::BrokenConnectionError(err.clone()),
-                ));
-            }
-        };
+            // There are only broken connection errors. Nothing better to do
+            // than to return an error.
+            return Err(SchemaAgreementError::RequestError(
+                RequestAttemptError::BrokenConnectionError(err.clone()),
+            ));
+        }
 
-        let in_agreement = versions_results
+        let schema_versions: Vec<(Uuid, Uuid)> = versions_results
             .into_iter()
-            .filter_map(|v_r| match v_r {
-                SchemaNodeResult::Success(v) => Some(v),
+            .filter_map(|(host_id, v_r)| match v_r {
+                SchemaNodeResult::Success(v) => Some((host_id, v)),
                 SchemaNodeResult::BrokenConnection(_) => None,
             })
-            .all(|v| v == local_version);
-        Ok(in_agreement.then_some(local_version))
+            .filter(|(host_id, _)| !policy.ignored_nodes.contains(host_id))
+            .collect();
+        let check = policy.check(&schema_versions, required_node);
+        Ok((check, schema_versions))
     }
 
     /// Iterate over connections to the node.
@@ -2460,16 +3609,68 @@ struct ExecuteRequestContext<'a> {
     load_balancing_policy: &'a dyn load_balancing::LoadBalancingPolicy,
     query_info: &'a load_balancing::RoutingInfo<'a>,
     request_span: &'a RequestSpan,
+    request_observer: Option<(&'a dyn RequestObserver, ObservedRequestId)>,
+    slow_query_tracker: Option<&'a SlowQueryTracker>,
+    request_settings: &'a RequestSettings,
 }
 
+#[derive(Clone, Copy)]
 struct HistoryData<'a> {
     listener: &'a dyn HistoryListener,
     request_id: history::RequestId,
     speculative_id: Option<history::SpeculativeId>,
 }
 
+/// Runs a fiber of a request, logging to the history listener if the fiber is dropped
+/// before finishing - because another fiber completed the request first, or the request timed out.
+async fn log_fiber_cancellation<F: Future>(
+    history_data: Option<HistoryData<'_>>,
+    fiber: F,
+) -> F::Output {
+    struct CancellationGuard<'a>(Option<HistoryData<'a>>);
+
+    impl Drop for CancellationGuard<'_> {
+        fn drop(&mut self) {
+            if let Some(hd) = self.0.take() {
+                hd.listener
+                    .log_fiber_cancelled(hd.request_id, hd.speculative_id);
+            }
+        }
+    }
+
+    let mut guard = CancellationGuard(history_data);
+    let output = fiber.await;
+    guard.0 = None;
+    output
+}
+
 impl ExecuteRequestContext<'_> {
-    fn log_attempt_start(&self, node_addr: SocketAddr) -> Option<history::AttemptId> {
+    fn observe_attempt_end(
+        &self,
+        node: NodeRef,
+        shard: Shard,
+        latency: Duration,
+        result: Result<(), &RequestAttemptError>,
+    ) {
+        if let Some((observer, request_id)) = self.request_observer {
+            observer.on_attempt_end(&AttemptEnd {
+                request_id,
+                node,
+                shard: node.sharder().is_some().then_some(shard),
+                latency,
+                result,
+            });
+        }
+    }
+
+    fn log_attempt_start(
+        &self,
+        node: NodeRef,
+        node_addr: SocketAddr,
+    ) -> Option<history::AttemptId> {
+        if let Some(tracker) = self.slow_query_tracker {
+            tracker.record_attempt(node);
+        }
         self.history_data.as_ref().map(|hd| {
             hd.listener
                 .log_attempt_start(hd.request_id, hd.speculative_id, node_addr)
@@ -2,19 +2,37 @@
 
 use super::execution_profile::ExecutionProfileHandle;
 use super::session::{Session, SessionConfig};
-use super::{Compression, PoolSize, SelfIdentity, WriteCoalescingDelay};
-use crate::authentication::{AuthenticatorProvider, PlainTextAuthenticator};
-use crate::client::session::TlsContext;
+use super::{
+    Compression, DynamicPoolSize, KeepaliveRequest, PoolSize, PoolWarmup, Proxy, SelfIdentity,
+    ShardAwarePortUsage, WriteCoalescingDelay,
+};
+use crate::authentication::{AuthenticatorProvider, CredentialsProvider, PlainTextAuthenticator};
+use crate::client::session::{NodeTlsProvider, ReloadableTlsContext, TlsContext};
 use crate::errors::NewSessionError;
-use crate::policies::address_translator::AddressTranslator;
+use crate::observability::history::RecentHistoryCollector;
+use crate::observability::instrumentation::{TraceContextProvider, TracingInstrumentation};
+use crate::observability::log_targets;
+#[cfg(feature = "metrics")]
+use crate::observability::metrics::LatencyHistogramConfig;
+use crate::observability::request_observer::RequestObserver;
+use crate::observability::slow_query_log::SlowQueryLogger;
+use crate::policies::address_translator::{AddressTranslator, PeerAddressPreference};
 use crate::policies::host_filter::HostFilter;
+use crate::policies::keyspace_filter::KeyspaceFilter;
+use crate::policies::reconnect::ReconnectPolicy;
+use crate::policies::resolver::Resolver;
+use crate::policies::schema_agreement::SchemaAgreementPolicy;
+use crate::policies::tablet_listener::TabletListener;
 use crate::policies::timestamp_generator::TimestampGenerator;
+#[cfg(feature = "paging-state-codec")]
+use crate::response::PagingStateKey;
 use crate::routing::ShardAwarePortRange;
+use crate::routing::partitioner::Partitioner;
 use crate::statement::Consistency;
 use std::borrow::Borrow;
 use std::marker::PhantomData;
 use std::net::{IpAddr, SocketAddr};
-use std::num::NonZeroU32;
+use std::num::{NonZeroU32, NonZeroUsize};
 use std::sync::Arc;
 use std::time::Duration;
 use tracing::warn;
@@ -155,6 +173,30 @@ impl GenericSessionBuilder<DefaultMode> {
         self
     }
 
+    /// Add known nodes given by SRV records of the name, e.g. `_cql._tcp.scylla.example.com`.
+    ///
+    /// The records are looked up by the [resolver](Self::resolver). [DefaultResolver] supports
+    /// SRV records only with the `dns-srv` feature enabled.
+    ///
+    /// [DefaultResolver]: crate::policies::resolver::DefaultResolver
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_srv("_cql._tcp.scylla.example.com")
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn known_srv(mut self, name: impl AsRef<str>) -> Self {
+        self.config.add_known_srv(name);
+        self
+    }
+
     /// Add a list of known nodes with IP addresses
     /// # Example
     /// ```
@@ -206,6 +248,47 @@ impl GenericSessionBuilder<DefaultMode> {
         self
     }
 
+    /// Set a provider of username and password for plain text authentication.
+    /// Unlike with [`user`](Self::user), the credentials are fetched from the provider
+    /// each time a connection authenticates, so they can be rotated without recreating the session.
+    ///
+    /// # Example
+    /// ```
+    /// # use std::sync::Arc;
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// use async_trait::async_trait;
+    /// use scylla::authentication::{AuthError, Credentials, CredentialsProvider};
+    ///
+    /// struct VaultCredentials;
+    ///
+    /// #[async_trait]
+    /// impl CredentialsProvider for VaultCredentials {
+    ///     async fn credentials(&self) -> Result<Credentials, AuthError> {
+    ///         // Fetch short-lived credentials, e.g. from Vault.
+    ///         Ok(Credentials::new("user", "short-lived password"))
+    ///     }
+    /// }
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("127.0.0.1:9042")
+    ///     .credentials_provider(Arc::new(VaultCredentials))
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn credentials_provider(
+        mut self,
+        credentials_provider: Arc<dyn CredentialsProvider>,
+    ) -> Self {
+        self.config.authenticator = Some(Arc::new(
+            PlainTextAuthenticator::with_credentials_provider(credentials_provider),
+        ));
+        self
+    }
+
     /// Set custom authenticator provider to create an authenticator instance during a session creation.
     ///
     /// # Example
@@ -321,6 +404,34 @@ impl GenericSessionBuilder<DefaultMode> {
         self
     }
 
+    /// Chooses which of the addresses advertised by discovered nodes the driver connects to:
+    /// their `broadcast_rpc_address` (the default), their listen address, a choice made
+    /// per datacenter, or one made by a custom callback.
+    ///
+    /// The chosen address is then translated by the [AddressTranslator], if one is set.
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// # use scylla::policies::address_translator::{PeerAddressKind, PeerAddressPreference};
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("127.0.0.1:9042")
+    ///     .peer_address_preference(
+    ///         PeerAddressPreference::new(PeerAddressKind::BroadcastRpc)
+    ///             .with_datacenter("dc1", PeerAddressKind::Listen),
+    ///     )
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn peer_address_preference(mut self, preference: PeerAddressPreference) -> Self {
+        self.config.peer_address_preference = preference;
+        self
+    }
+
     /// TLS feature
     ///
     /// Provide SessionBuilder with TlsContext that will be
@@ -368,6 +479,117 @@ impl GenericSessionBuilder<DefaultMode> {
         }
         self
     }
+
+    /// Provide SessionBuilder with a [ReloadableTlsContext], which will be used to create
+    /// TLS connections to the database. Its context can be replaced at runtime, e.g. to rotate
+    /// short-lived certificates, and each new connection uses the context which is current
+    /// when the connection is opened. Takes precedence over [tls_context](Self::tls_context).
+    ///
+    /// Default is None.
+    ///
+    #[cfg_attr(
+        feature = "openssl-010",
+        doc = r#"
+# Example
+
+```
+    # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    use std::time::Duration;
+    use scylla::client::session::{ReloadableTlsContext, Session, TlsContext};
+    use scylla::client::session_builder::SessionBuilder;
+    use openssl::error::ErrorStack;
+    use openssl::ssl::{SslContextBuilder, SslVerifyMode, SslMethod, SslFiletype};
+
+    fn load_context() -> Result<TlsContext, ErrorStack> {
+        let mut context_builder = SslContextBuilder::new(SslMethod::tls())?;
+        context_builder.set_certificate_file("./examples/certs/scylla.crt", SslFiletype::PEM)?;
+        context_builder.set_verify(SslVerifyMode::NONE);
+        Ok(context_builder.build().into())
+    }
+
+    let tls_context = ReloadableTlsContext::new(load_context()?);
+    // Pick up renewed certificates without restarting the application.
+    tls_context.reload_on_file_change(
+        ["./examples/certs/scylla.crt"],
+        Duration::from_secs(60),
+        load_context,
+    );
+
+    let session: Session = SessionBuilder::new()
+        .known_node("127.0.0.1:9042")
+        .reloadable_tls_context(Some(tls_context))
+        .build()
+        .await?;
+    # Ok(())
+    # }
+```
+"#
+    )]
+    pub fn reloadable_tls_context(mut self, tls_context: Option<ReloadableTlsContext>) -> Self {
+        self.config.reloadable_tls_context = tls_context;
+        self
+    }
+
+    /// Provide SessionBuilder with a [NodeTlsProvider], which chooses TLS settings
+    /// separately for each node: the TLS context (client certificate, trusted CAs,
+    /// verification mode) and the server name sent in SNI, or no TLS at all.
+    /// Useful e.g. in clusters whose datacenters use different certificates,
+    /// or which are being migrated to TLS.
+    /// Takes precedence over [tls_context](Self::tls_context) and
+    /// [reloadable_tls_context](Self::reloadable_tls_context).
+    ///
+    /// Default is None.
+    ///
+    #[cfg_attr(
+        feature = "openssl-010",
+        doc = r#"
+# Example
+
+```
+    # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    use std::sync::Arc;
+    use scylla::client::session::{
+        NodeTlsProvider, NodeTlsSettings, Session, TlsContext, TlsTarget,
+    };
+    use scylla::client::session_builder::SessionBuilder;
+    use openssl::ssl::{SslContext, SslMethod};
+
+    #[derive(Debug)]
+    struct PerDatacenterTls {
+        dc1: SslContext,
+        dc2: SslContext,
+    }
+
+    impl NodeTlsProvider for PerDatacenterTls {
+        fn tls_settings(&self, node: &TlsTarget<'_>) -> Option<NodeTlsSettings> {
+            match node.datacenter() {
+                Some("dc2") => Some(
+                    NodeTlsSettings::new(self.dc2.clone()).with_server_name("dc2.example.com"),
+                ),
+                // Contact points, whose datacenter is unknown, are connected to as nodes of dc1.
+                _ => Some(NodeTlsSettings::new(self.dc1.clone())),
+            }
+        }
+    }
+
+    let provider = PerDatacenterTls {
+        dc1: SslContext::builder(SslMethod::tls())?.build(),
+        dc2: SslContext::builder(SslMethod::tls())?.build(),
+    };
+    let session: Session = SessionBuilder::new()
+        .known_node("127.0.0.1:9042")
+        .node_tls_provider(Some(Arc::new(provider)))
+        .build()
+        .await?;
+    # Ok(())
+    # }
+```
+"#
+    )]
+    pub fn node_tls_provider(mut self, provider: Option<Arc<dyn NodeTlsProvider>>) -> Self {
+        self.config.node_tls_provider = provider;
+        self
+    }
 }
 
 // This block contains configuration options that make sense both for any `Session` type.
@@ -398,6 +620,57 @@ impl<K: SessionBuilderKind> GenericSessionBuilder<K> {
         self
     }
 
+    /// Tunnels connections to all nodes through the given SOCKS5 or HTTP CONNECT proxy,
+    /// e.g. when the cluster is reachable only through a bastion host.
+    ///
+    /// Proxied connections do not use the shard-aware port.
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::Proxy;
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("10.0.0.1:9042")
+    ///     .proxy(Proxy::socks5("192.168.0.1:1080".parse()?).with_credentials("user", "password"))
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.config.proxy = Some(proxy);
+        self
+    }
+
+    /// Overrides the proxy used for connections to nodes in the given datacenter.
+    /// With `None`, nodes in the datacenter are connected to directly.
+    ///
+    /// Contact points are always connected to with the proxy set by [Self::proxy],
+    /// because their datacenter is not known before connecting.
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::Proxy;
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("10.0.0.1:9042")
+    ///     .datacenter_proxy("remote_dc", Some(Proxy::http_connect("192.168.0.1:3128".parse()?)))
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn datacenter_proxy(mut self, datacenter: impl Into<String>, proxy: Option<Proxy>) -> Self {
+        self.config
+            .datacenter_proxies
+            .insert(datacenter.into(), proxy);
+        self
+    }
+
     /// Specifies the local port range used for shard-aware connections.
     ///
     /// A possible use case is when you want to have multiple [`Session`] objects and do not want
@@ -450,6 +723,36 @@ impl<K: SessionBuilderKind> GenericSessionBuilder<K> {
         self
     }
 
+    /// Set the minimum length of a request body to be compressed.
+    /// Shorter bodies are sent uncompressed, because compressing them costs
+    /// CPU time while saving little or nothing on the network.
+    /// The default is 0, i.e. all requests are compressed.
+    ///
+    /// This option has no effect if compression is not enabled with [`SessionBuilder::compression()`].
+    /// Compression can also be disabled for requests executed with a particular
+    /// execution profile, using
+    /// [`ExecutionProfileBuilder::compression_enabled()`](crate::client::execution_profile::ExecutionProfileBuilder::compression_enabled).
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// # use scylla::client::Compression;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("127.0.0.1:9042")
+    ///     .compression(Some(Compression::Lz4))
+    ///     .compression_threshold(512)
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn compression_threshold(mut self, threshold: usize) -> Self {
+        self.config.compression_threshold = threshold;
+        self
+    }
+
     /// Set the delay for schema agreement check. How often driver should ask if schema is in agreement
     /// The default is 200 milliseconds.
     ///
@@ -545,6 +848,7 @@ impl<K: SessionBuilderKind> GenericSessionBuilder<K> {
     pub fn tcp_keepalive_interval(mut self, interval: Duration) -> Self {
         if interval <= Duration::from_secs(1) {
             warn!(
+                target: log_targets::CONNECTION,
                 "Setting the TCP keepalive interval to low values ({:?}) is not recommended as it can have a negative impact on performance. Consider setting it above 1 second.",
                 interval
             );
@@ -554,6 +858,101 @@ impl<K: SessionBuilderKind> GenericSessionBuilder<K> {
         self
     }
 
+    /// Sets the size of the receive buffer of TCP sockets (`SO_RCVBUF`).
+    /// The default is `None`, which means that the system default is used.
+    ///
+    /// Larger buffers may improve throughput of connections with high bandwidth and latency.
+    /// Note that the operating system may adjust the size, e.g. Linux doubles it.
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("127.0.0.1:9042")
+    ///     .tcp_recv_buffer_size(Some(4 * 1024 * 1024))
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn tcp_recv_buffer_size(mut self, size: Option<usize>) -> Self {
+        self.config.tcp_recv_buffer_size = size;
+        self
+    }
+
+    /// Sets the size of the send buffer of TCP sockets (`SO_SNDBUF`).
+    /// The default is `None`, which means that the system default is used.
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("127.0.0.1:9042")
+    ///     .tcp_send_buffer_size(Some(4 * 1024 * 1024))
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn tcp_send_buffer_size(mut self, size: Option<usize>) -> Self {
+        self.config.tcp_send_buffer_size = size;
+        self
+    }
+
+    /// Sets the type of service of TCP sockets (`IP_TOS`, or `IPV6_TCLASS` for IPv6).
+    /// The default is `None`, which means that the system default is used.
+    ///
+    /// The upper 6 bits are the DSCP value, so e.g. DSCP `AF41` (34) is set with `34 << 2`.
+    /// On platforms which don't support it, the option is ignored with a warning.
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("127.0.0.1:9042")
+    ///     .ip_tos(Some(34 << 2))
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn ip_tos(mut self, tos: Option<u8>) -> Self {
+        self.config.ip_tos = tos;
+        self
+    }
+
+    /// Binds all TCP sockets to the given network interface (`SO_BINDTODEVICE`),
+    /// e.g. on multi-homed hosts. The default is `None`, which means that sockets
+    /// are not bound to any interface.
+    ///
+    /// Supported only on Linux, Android and Fuchsia; on other platforms connecting fails.
+    /// Binding may require elevated privileges. To bind sockets to a local address instead,
+    /// use [Self::local_ip_address].
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("127.0.0.1:9042")
+    ///     .bind_interface(Some("eth1"))
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn bind_interface(mut self, interface: Option<impl Into<String>>) -> Self {
+        self.config.bind_interface = interface.map(Into::into);
+        self
+    }
+
     /// Set keyspace to be used on all connections.\
     /// Each connection will send `"USE <keyspace_name>"` before sending any requests.\
     /// This can be later changed with [`crate::client::session::Session::use_keyspace`]
@@ -623,6 +1022,7 @@ impl<K: SessionBuilderKind> GenericSessionBuilder<K> {
 
     /// Sets the per-node connection pool size.
     /// The default is one connection per shard, which is the recommended setting for Scylla.
+    /// To make the number of connections follow the load, see [SessionBuilder::dynamic_pool_size].
     ///
     /// # Example
     /// ```
@@ -647,6 +1047,37 @@ impl<K: SessionBuilderKind> GenericSessionBuilder<K> {
         self
     }
 
+    /// Makes the number of connections to each shard follow the load, within the given bounds.
+    /// When set, the size configured with [SessionBuilder::pool_size] is ignored.
+    /// Cassandra nodes are treated as if they had only one shard.
+    ///
+    /// See [DynamicPoolSize] for how the number of connections is chosen.
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// use std::num::NonZeroUsize;
+    /// use scylla::client::DynamicPoolSize;
+    ///
+    /// // This session will keep between 1 and 4 connections to each shard, depending on the load.
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("127.0.0.1:9042")
+    ///     .dynamic_pool_size(DynamicPoolSize::new(
+    ///         NonZeroUsize::new(1).unwrap(),
+    ///         NonZeroUsize::new(4).unwrap(),
+    ///     )?)
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn dynamic_pool_size(mut self, size: DynamicPoolSize) -> Self {
+        self.config.dynamic_pool_size = Some(size);
+        self
+    }
+
     /// If true, prevents the driver from connecting to the shard-aware port, even if the node supports it.
     ///
     /// _This is a Scylla-specific option_. It has no effect on Cassandra clusters.
@@ -687,9 +1118,240 @@ impl<K: SessionBuilderKind> GenericSessionBuilder<K> {
         self
     }
 
-    /// Set the timestamp generator that will generate timestamps on the client-side.
+    /// Controls whether and how the driver connects to the shard-aware port
+    /// (19042, or 19142 with TLS).
     ///
-    /// # Example
+    /// The default, [ShardAwarePortUsage::Preferred], retries each failed shard-aware
+    /// connection attempt on the regular port. In environments where the shard-aware
+    /// port is known to be unreachable or source ports are rewritten (e.g. NAT),
+    /// [ShardAwarePortUsage::PreferredWithFallback] stops trying the shard-aware port
+    /// of a node after a number of consecutive failures, so reconnections do not
+    /// pay for a doomed attempt each time.
+    ///
+    /// With the `metrics` feature enabled, the effectiveness of the shard-aware port
+    /// can be observed through [Metrics::get_shard_aware_connections_on_target_shard](crate::observability::metrics::Metrics::get_shard_aware_connections_on_target_shard)
+    /// and [Metrics::get_shard_aware_connections_off_target_shard](crate::observability::metrics::Metrics::get_shard_aware_connections_off_target_shard).
+    ///
+    /// Setting [disallow_shard_aware_port](Self::disallow_shard_aware_port) to true
+    /// takes precedence over this option.
+    ///
+    /// # Example
+    /// ```
+    /// # use std::num::NonZeroUsize;
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// use scylla::client::ShardAwarePortUsage;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("127.0.0.1:9042")
+    ///     .shard_aware_port_usage(ShardAwarePortUsage::PreferredWithFallback {
+    ///         max_failures: NonZeroUsize::new(3).unwrap(),
+    ///     })
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn shard_aware_port_usage(mut self, usage: ShardAwarePortUsage) -> Self {
+        self.config.shard_aware_port_usage = usage;
+        self
+    }
+
+    /// Sets the policy deciding how long the connection pool of a node waits
+    /// before reconnecting, after connections to the node failed to open or broke.
+    /// The default is [ExponentialReconnectPolicy](crate::policies::reconnect::ExponentialReconnectPolicy).
+    ///
+    /// With the `metrics` feature, reconnections are counted by
+    /// [Metrics::get_reconnect_attempts](crate::observability::metrics::Metrics::get_reconnect_attempts)
+    /// and [Metrics::get_reconnect_successes](crate::observability::metrics::Metrics::get_reconnect_successes).
+    ///
+    /// # Example
+    /// ```
+    /// # use std::sync::Arc;
+    /// # use std::time::Duration;
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// # use scylla::policies::reconnect::ExponentialReconnectPolicy;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("127.0.0.1:9042")
+    ///     .reconnect_policy(Arc::new(
+    ///         ExponentialReconnectPolicy::new()
+    ///             .with_backoff_limits(Duration::from_millis(100), Duration::from_secs(30))
+    ///             .with_jitter_range(0.5..=1.5),
+    ///     ))
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn reconnect_policy(mut self, policy: Arc<dyn ReconnectPolicy>) -> Self {
+        self.config.reconnect_policy = policy;
+        self
+    }
+
+    /// If true, [build()](GenericSessionBuilder::build) only opens the control connection,
+    /// and connections to a node are opened when a request is sent to it for the first time.
+    /// Useful for tools which send a few requests to a small subset of a large cluster.
+    ///
+    /// The first request sent to a node waits until connections to it are opened.
+    /// Nodes which have not been used yet are reported by [Node::is_idle](crate::cluster::Node::is_idle).
+    /// The default is false, i.e. connections to all nodes are opened eagerly.
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("127.0.0.1:9042")
+    ///     .connect_lazily(true)
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn connect_lazily(mut self, enabled: bool) -> Self {
+        self.config.connect_lazily = enabled;
+        self
+    }
+
+    /// Sets the time after which connections to a node are closed, if none of them
+    /// was used meanwhile. They are opened again when a request is sent to the node.
+    /// Useful e.g. for clients which only send requests in occasional bursts.
+    /// The default is `None`, i.e. idle connections are kept open.
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// # use std::time::Duration;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("127.0.0.1:9042")
+    ///     .connection_idle_timeout(Some(Duration::from_secs(600)))
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn connection_idle_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.config.connection_idle_timeout = timeout;
+        self
+    }
+
+    /// Sets the maximum age of connections. Older connections are replaced with new ones,
+    /// e.g. to pick up renewed certificates or a changed shard layout.
+    /// A replacement is opened first, and the old connection is closed
+    /// once its requests in flight complete.
+    /// The default is `None`, i.e. connections are kept open regardless of their age.
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// # use std::time::Duration;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("127.0.0.1:9042")
+    ///     .connection_max_age(Some(Duration::from_secs(24 * 60 * 60)))
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn connection_max_age(mut self, max_age: Option<Duration>) -> Self {
+        self.config.connection_max_age = max_age;
+        self
+    }
+
+    /// Makes [build()](GenericSessionBuilder::build) wait until connection pools are full,
+    /// so that the first requests don't pay the latency of establishing connections.
+    /// See [PoolWarmup] for which pools are waited for and when building the session fails.
+    /// The default is `None`, i.e. the session is built once each pool has at least one connection.
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// # use scylla::client::PoolWarmup;
+    /// # use std::time::Duration;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("127.0.0.1:9042")
+    ///     .pool_warmup(Some(
+    ///         PoolWarmup::new(Duration::from_secs(10))
+    ///             .with_local_datacenter("dc1")
+    ///             .with_required_fraction(0.5),
+    ///     ))
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn pool_warmup(mut self, warmup: Option<PoolWarmup>) -> Self {
+        self.config.pool_warmup = warmup;
+        self
+    }
+
+    /// Sets the limit of concurrent requests in flight to a single node.
+    /// A node which reached the limit is moved to the end of query plans,
+    /// so that requests are routed to the next nodes in the plans instead.
+    /// It is still tried if all the other nodes in a plan fail, so the limit
+    /// never causes a request to fail by itself.
+    ///
+    /// How often nodes are skipped this way is exposed by
+    /// `Metrics::get_saturated_node_skips` (with the `metrics` feature enabled).
+    ///
+    /// The default is `None`, i.e. the number of requests in flight does not affect routing.
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// # use std::num::NonZeroUsize;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("127.0.0.1:9042")
+    ///     .max_in_flight_requests_per_node(NonZeroUsize::new(1024))
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn max_in_flight_requests_per_node(mut self, max: Option<NonZeroUsize>) -> Self {
+        self.config.max_in_flight_requests_per_node = max;
+        self
+    }
+
+    /// Enables verbose logging of connection lifecycle events: opening connections,
+    /// failures to open them, and closing them, also due to missed keepalives or idleness.
+    /// Such events are then logged at `info` level instead of `debug`, under the
+    /// [CONNECTION](crate::observability::log_targets::CONNECTION) target, so they can be
+    /// enabled without the much noisier frame-level and per-request events.
+    /// The default is false.
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("127.0.0.1:9042")
+    ///     .verbose_connection_logging(true)
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn verbose_connection_logging(mut self, enabled: bool) -> Self {
+        self.config.verbose_connection_logging = enabled;
+        self
+    }
+
+    /// Set the timestamp generator that will generate timestamps on the client-side.
+    ///
+    /// # Example
     /// ```
     /// # use scylla::client::session::Session;
     /// # use scylla::client::session_builder::SessionBuilder;
@@ -709,6 +1371,183 @@ impl<K: SessionBuilderKind> GenericSessionBuilder<K> {
         self
     }
 
+    /// Set the observer notified about all requests executed by the session:
+    /// when they start, when each of their attempts completes and when they complete.
+    /// See [request_observer](crate::observability::request_observer) for details.
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// # use scylla::observability::request_observer::{RequestEnd, RequestObserver};
+    /// # use std::sync::Arc;
+    /// #[derive(Debug)]
+    /// struct LatencyLogger;
+    ///
+    /// impl RequestObserver for LatencyLogger {
+    ///     fn on_request_end(&self, request: &RequestEnd<'_>) {
+    ///         println!("Request {:?} took {:?}", request.id, request.latency);
+    ///     }
+    /// }
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("127.0.0.1:9042")
+    ///     .request_observer(Arc::new(LatencyLogger))
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn request_observer(mut self, request_observer: Arc<dyn RequestObserver>) -> Self {
+        self.config.request_observer = Some(request_observer);
+        self
+    }
+
+    /// Make the session create `INFO` level spans for executed requests and their attempts,
+    /// annotated according to OpenTelemetry conventions, so that they can be exported
+    /// with the `tracing-opentelemetry` layer.
+    /// See [instrumentation](crate::observability::instrumentation) for details.
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// # use scylla::observability::instrumentation::TracingInstrumentation;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("127.0.0.1:9042")
+    ///     .tracing_instrumentation(TracingInstrumentation::new().with_statement_text(true))
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn tracing_instrumentation(mut self, instrumentation: TracingInstrumentation) -> Self {
+        self.config.tracing_instrumentation = Some(instrumentation);
+        self
+    }
+
+    /// Set the provider of the trace context of the application, which is propagated
+    /// to the cluster in the custom payload of requests, in the W3C `traceparent` format.
+    /// See [instrumentation](crate::observability::instrumentation#propagating-the-trace-context-to-the-cluster)
+    /// for details.
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// # use scylla::observability::instrumentation::{TraceContextProvider, TraceParent};
+    /// # use std::sync::Arc;
+    /// #[derive(Debug)]
+    /// struct FixedTrace(TraceParent);
+    ///
+    /// impl TraceContextProvider for FixedTrace {
+    ///     fn current_trace_parent(&self) -> Option<TraceParent> {
+    ///         Some(self.0)
+    ///     }
+    /// }
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let trace_parent = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01".parse()?;
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("127.0.0.1:9042")
+    ///     .trace_context_provider(Arc::new(FixedTrace(trace_parent)))
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn trace_context_provider(mut self, provider: Arc<dyn TraceContextProvider>) -> Self {
+        self.config.trace_context_provider = Some(provider);
+        self
+    }
+
+    /// Set the logger to which requests taking too long to complete are reported.
+    /// See [slow_query_log](crate::observability::slow_query_log) for details.
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// # use scylla::observability::slow_query_log::SlowQueryLogger;
+    /// # use std::time::Duration;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("127.0.0.1:9042")
+    ///     .slow_query_logger(SlowQueryLogger::new(Duration::from_millis(500)))
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn slow_query_logger(mut self, logger: SlowQueryLogger) -> Self {
+        self.config.slow_query_logger = Some(logger);
+        self
+    }
+
+    /// Set the collector of the history of recent requests executed by the session.
+    /// Requests of statements with their own history listener are not collected in it.
+    /// See [history](crate::observability::history) for details.
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// # use scylla::observability::history::RecentHistoryCollector;
+    /// # use std::sync::Arc;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let history = Arc::new(RecentHistoryCollector::new(1000));
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("127.0.0.1:9042")
+    ///     .session_history(Arc::clone(&history))
+    ///     .build()
+    ///     .await?;
+    ///
+    /// if let Err(err) = session.query_unpaged("SELECT * FROM ks.t", &[]).await {
+    ///     eprintln!("{err}\n{}", history.clone_structured_history());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn session_history(mut self, history: Arc<RecentHistoryCollector>) -> Self {
+        self.config.session_history = Some(history);
+        self
+    }
+
+    /// Configure the histograms in which the session's metrics collect latencies of requests.
+    /// See [LatencyHistogramConfig] for details.
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// # use scylla::observability::metrics::LatencyHistogramConfig;
+    /// # use std::time::Duration;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("127.0.0.1:9042")
+    ///     .latency_histogram(
+    ///         LatencyHistogramConfig::new()
+    ///             .with_precision(7, 16)?
+    ///             .with_sliding_window(Duration::from_secs(15 * 60), Duration::from_secs(60))
+    ///             .with_percentiles([99.99]),
+    ///     )
+    ///     .build()
+    ///     .await?;
+    ///
+    /// let last_five_minutes = session
+    ///     .get_metrics()
+    ///     .get_window_snapshot(Duration::from_secs(5 * 60));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "metrics")]
+    pub fn latency_histogram(mut self, config: LatencyHistogramConfig) -> Self {
+        self.config.latency_histogram = config;
+        self
+    }
+
     /// Set the keyspaces to be fetched, to retrieve their strategy, and schema metadata if enabled
     /// No keyspaces, the default value, means all the keyspaces will be fetched.
     ///
@@ -733,6 +1572,39 @@ impl<K: SessionBuilderKind> GenericSessionBuilder<K> {
         self
     }
 
+    /// Sets the keyspace filter. The keyspace filter decides whether schema
+    /// metadata of a keyspace should be fetched or not. Metadata of rejected
+    /// keyspaces is skipped, which saves memory and shortens metadata refreshes
+    /// on clusters with many keyspaces.
+    ///
+    /// Unlike [keyspaces_to_fetch](Self::keyspaces_to_fetch), which is applied
+    /// by the server, the filter is evaluated by the driver, so it can express
+    /// denylists and arbitrary predicates. If both are set, only keyspaces
+    /// satisfying both are fetched.
+    ///
+    /// See the [keyspace filter](crate::policies::keyspace_filter) module for a list
+    /// of pre-defined filters. Any `Fn(&str) -> bool` closure can be used as well.
+    ///
+    /// # Example
+    /// ```
+    /// # use std::sync::Arc;
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// // Skip schema metadata of per-tenant keyspaces
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("127.0.0.1:9042")
+    ///     .keyspace_filter(Arc::new(|ks: &str| !ks.starts_with("tenant_")))
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn keyspace_filter(mut self, filter: Arc<dyn KeyspaceFilter>) -> Self {
+        self.config.keyspace_filter = Some(filter);
+        self
+    }
+
     /// Set the fetch schema metadata flag.
     /// The default is true.
     ///
@@ -779,6 +1651,88 @@ impl<K: SessionBuilderKind> GenericSessionBuilder<K> {
         self
     }
 
+    /// Set the timeout of the initial cluster metadata fetch, performed when the session is built.
+    /// The default is `None`, which means the fetch is bounded only by the timeouts
+    /// of the requests it consists of.
+    ///
+    /// Unless [degraded startup](Self::allow_degraded_startup) is allowed,
+    /// building the session fails if the timeout elapses.
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// # use std::time::Duration;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("127.0.0.1:9042")
+    ///     .initial_metadata_fetch_timeout(Some(Duration::from_secs(10)))
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn initial_metadata_fetch_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.config.initial_metadata_fetch_timeout = timeout;
+        self
+    }
+
+    /// Allow building the session even if the initial cluster metadata fetch fails or times out.
+    /// The default is `false`.
+    ///
+    /// In such case, the session starts in degraded mode: with metadata consisting only
+    /// of the known nodes and dummy tokens, and without schema metadata. Requests are sent
+    /// to the known nodes, though not token-aware. The driver retries fetching metadata
+    /// every second until it succeeds, which is reported by [Session::is_metadata_degraded].
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// # use std::time::Duration;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("127.0.0.1:9042")
+    ///     .initial_metadata_fetch_timeout(Some(Duration::from_secs(10)))
+    ///     .allow_degraded_startup(true)
+    ///     .build()
+    ///     .await?;
+    /// if session.is_metadata_degraded() {
+    ///     println!("Cluster metadata is not available yet");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn allow_degraded_startup(mut self, allow: bool) -> Self {
+        self.config.allow_degraded_startup = allow;
+        self
+    }
+
+    /// Set the number of standby control connections. The default is 0.
+    ///
+    /// The control connection is used to fetch cluster metadata and receive server events.
+    /// Standby control connections are kept open to other nodes; when the control connection
+    /// breaks, one of them replaces it immediately, without waiting for a new connection
+    /// to be established. Broken standby connections are replaced after the next metadata refresh.
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("127.0.0.1:9042")
+    ///     .control_connection_standbys(1)
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn control_connection_standbys(mut self, standbys: usize) -> Self {
+        self.config.control_connection_standbys = standbys;
+        self
+    }
+
     /// Set the keepalive interval.
     /// The default is `Some(Duration::from_secs(30))`, which corresponds
     /// to keepalive CQL messages being sent every 30 seconds.
@@ -801,6 +1755,7 @@ impl<K: SessionBuilderKind> GenericSessionBuilder<K> {
     pub fn keepalive_interval(mut self, interval: Duration) -> Self {
         if interval <= Duration::from_secs(1) {
             warn!(
+                target: log_targets::CONNECTION,
                 "Setting the keepalive interval to low values ({:?}) is not recommended as it can have a negative impact on performance. Consider setting it above 1 second.",
                 interval
             );
@@ -832,6 +1787,7 @@ impl<K: SessionBuilderKind> GenericSessionBuilder<K> {
     pub fn keepalive_timeout(mut self, timeout: Duration) -> Self {
         if timeout <= Duration::from_secs(1) {
             warn!(
+                target: log_targets::CONNECTION,
                 "Setting the keepalive timeout to low values ({:?}) is not recommended as it may aggressively close connections. Consider setting it above 5 seconds.",
                 timeout
             );
@@ -841,6 +1797,59 @@ impl<K: SessionBuilderKind> GenericSessionBuilder<K> {
         self
     }
 
+    /// Sets the request sent as a keepalive.
+    /// The default is [KeepaliveRequest::Options]. A lightweight query checks
+    /// that nodes are able to serve reads, not only that they respond.
+    ///
+    /// With the `metrics` feature, keepalives are counted by
+    /// [Metrics::get_keepalive_requests](crate::observability::metrics::Metrics::get_keepalive_requests)
+    /// and [Metrics::get_keepalive_failures](crate::observability::metrics::Metrics::get_keepalive_failures).
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// use scylla::client::KeepaliveRequest;
+    ///
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("127.0.0.1:9042")
+    ///     .keepalive_request(KeepaliveRequest::Query(
+    ///         "SELECT now() FROM system.local".to_owned(),
+    ///     ))
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn keepalive_request(mut self, request: KeepaliveRequest) -> Self {
+        self.config.keepalive_request = request;
+        self
+    }
+
+    /// Sets whether a connection is closed, and then opened again by the pool,
+    /// when a keepalive request fails or times out.
+    /// If false, such failures are only logged and counted in metrics.
+    /// The default is true.
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("127.0.0.1:9042")
+    ///     .close_on_keepalive_failure(false)
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn close_on_keepalive_failure(mut self, enabled: bool) -> Self {
+        self.config.close_on_keepalive_failure = enabled;
+        self
+    }
+
     /// Sets the timeout for waiting for schema agreement.
     /// By default, the timeout is 60 seconds.
     ///
@@ -862,6 +1871,36 @@ impl<K: SessionBuilderKind> GenericSessionBuilder<K> {
         self
     }
 
+    /// Sets the policy for awaiting schema agreement, both manually and automatically
+    /// after a schema-altering statement is sent. The policy's interval and timeout
+    /// take precedence over those set with [Self::schema_agreement_interval]
+    /// and [Self::schema_agreement_timeout].
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// use std::time::Duration;
+    /// use scylla::policies::schema_agreement::SchemaAgreementPolicy;
+    ///
+    /// let policy = SchemaAgreementPolicy::builder()
+    ///     .timeout(Duration::from_secs(300))
+    ///     .required_fraction(0.9)
+    ///     .build();
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("127.0.0.1:9042")
+    ///     .schema_agreement_policy(policy)
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn schema_agreement_policy(mut self, policy: SchemaAgreementPolicy) -> Self {
+        self.config.schema_agreement_policy = Some(policy);
+        self
+    }
+
     /// Controls automatic waiting for schema agreement after a schema-altering
     /// statement is sent. By default, it is enabled.
     ///
@@ -906,6 +1945,74 @@ impl<K: SessionBuilderKind> GenericSessionBuilder<K> {
         self
     }
 
+    /// Changes the time after which hostnames of known nodes are resolved again
+    /// before reconnecting to them. The default is 30 seconds.
+    ///
+    /// Addresses of hostnames may change, e.g. when they point to cloud load balancers
+    /// or Kubernetes services. Using `None` makes the driver resolve hostnames again only
+    /// after all known nodes become unreachable.
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// # use std::time::Duration;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("scylla.default.svc.cluster.local:9042")
+    ///     .hostname_resolution_ttl(Some(Duration::from_secs(10)))
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn hostname_resolution_ttl(mut self, ttl: Option<Duration>) -> Self {
+        self.config.hostname_resolution_ttl = ttl;
+        self
+    }
+
+    /// Set the resolver used to resolve hostnames and SRV records of known nodes.
+    /// The default is [DefaultResolver], which uses the resolver of the operating system.
+    ///
+    /// [DefaultResolver]: crate::policies::resolver::DefaultResolver
+    ///
+    /// # Example
+    /// ```
+    /// # use std::net::SocketAddr;
+    /// # use std::sync::Arc;
+    /// # use async_trait::async_trait;
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// # use scylla::errors::ResolutionError;
+    /// # use scylla::policies::resolver::Resolver;
+    /// #[derive(Debug)]
+    /// struct StaticResolver(Vec<SocketAddr>);
+    ///
+    /// #[async_trait]
+    /// impl Resolver for StaticResolver {
+    ///     async fn resolve_host(
+    ///         &self,
+    ///         _host: &str,
+    ///         _default_port: u16,
+    ///     ) -> Result<Vec<SocketAddr>, ResolutionError> {
+    ///         Ok(self.0.clone())
+    ///     }
+    /// }
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("scylla.local")
+    ///     .resolver(Arc::new(StaticResolver(vec!["127.0.0.1:9042".parse()?])))
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn resolver(mut self, resolver: Arc<dyn Resolver>) -> Self {
+        self.config.resolver = Some(resolver);
+        self
+    }
+
     /// Sets the host filter. The host filter decides whether any connections
     /// should be opened to the node or not. The driver will also avoid
     /// those nodes when re-establishing the control connection.
@@ -926,38 +2033,157 @@ impl<K: SessionBuilderKind> GenericSessionBuilder<K> {
     /// # use scylla::policies::host_filter::DcHostFilter;
     ///
     /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
-    /// // The session will only connect to nodes from "my-local-dc"
+    /// // The session will only connect to nodes from "my-local-dc"
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("127.0.0.1:9042")
+    ///     .host_filter(Arc::new(DcHostFilter::new("my-local-dc".to_string())))
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn host_filter(mut self, filter: Arc<dyn HostFilter>) -> Self {
+        self.config.host_filter = Some(filter);
+        self
+    }
+
+    /// Sets the internal requests host filter. It further restricts the nodes
+    /// which the driver sends its internal requests to, e.g. schema agreement checks.
+    /// Nodes rejected by the [host filter](SessionBuilder::host_filter) never receive
+    /// any requests, regardless of this filter.
+    ///
+    /// By default, internal requests are sent to all nodes accepted by the host filter.
+    ///
+    /// # Example
+    /// ```
+    /// # use std::sync::Arc;
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// # use scylla::policies::host_filter::TokenOwnerHostFilter;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// // Schema agreement will not be checked on coordinator-only nodes.
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("127.0.0.1:9042")
+    ///     .internal_requests_host_filter(Arc::new(TokenOwnerHostFilter))
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn internal_requests_host_filter(mut self, filter: Arc<dyn HostFilter>) -> Self {
+        self.config.internal_requests_host_filter = Some(filter);
+        self
+    }
+
+    /// Registers a custom [Partitioner] for tables employing the partitioner `class_name`.
+    /// [ClusterState::compute_token](crate::cluster::ClusterState::compute_token) and
+    /// token-aware routing of prepared statements then use it to compute tokens.
+    ///
+    /// See [CustomPartitioners](crate::routing::partitioner::CustomPartitioners)
+    /// for how `class_name` is matched against partitioners of tables.
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// use scylla::routing::Token;
+    /// use scylla::routing::partitioner::{Partitioner, PartitionerHasher};
+    ///
+    /// struct XorPartitioner;
+    /// struct XorHasher(i64);
+    ///
+    /// impl Partitioner for XorPartitioner {
+    ///     type Hasher = XorHasher;
+    ///
+    ///     fn build_hasher(&self) -> XorHasher {
+    ///         XorHasher(0)
+    ///     }
+    /// }
+    ///
+    /// impl PartitionerHasher for XorHasher {
+    ///     fn write(&mut self, pk_part: &[u8]) {
+    ///         for byte in pk_part {
+    ///             self.0 = self.0.rotate_left(8) ^ i64::from(*byte);
+    ///         }
+    ///     }
+    ///
+    ///     fn finish(&self) -> Token {
+    ///         Token::new(self.0)
+    ///     }
+    /// }
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("127.0.0.1:9042")
+    ///     .custom_partitioner("com.example.XorPartitioner", XorPartitioner)
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn custom_partitioner<P>(mut self, class_name: impl Into<Arc<str>>, partitioner: P) -> Self
+    where
+        P: Partitioner + Send + Sync + 'static,
+        P::Hasher: Send + 'static,
+    {
+        self.config
+            .custom_partitioners
+            .register(class_name, partitioner);
+        self
+    }
+
+    /// Set the refresh metadata on schema agreement flag.
+    /// The default is true.
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
     /// let session: Session = SessionBuilder::new()
     ///     .known_node("127.0.0.1:9042")
-    ///     .host_filter(Arc::new(DcHostFilter::new("my-local-dc".to_string())))
+    ///     .refresh_metadata_on_auto_schema_agreement(true)
     ///     .build()
     ///     .await?;
     /// # Ok(())
     /// # }
     /// ```
-    pub fn host_filter(mut self, filter: Arc<dyn HostFilter>) -> Self {
-        self.config.host_filter = Some(filter);
+    pub fn refresh_metadata_on_auto_schema_agreement(mut self, refresh_metadata: bool) -> Self {
+        self.config.refresh_metadata_on_auto_schema_agreement = refresh_metadata;
         self
     }
 
-    /// Set the refresh metadata on schema agreement flag.
-    /// The default is true.
+    /// Set a listener notified when the driver updates its tablet maps,
+    /// e.g. after learning about a tablet migration.
+    /// The default is no listener.
     ///
     /// # Example
     /// ```
     /// # use scylla::client::session::Session;
     /// # use scylla::client::session_builder::SessionBuilder;
+    /// # use scylla::policies::tablet_listener::{TabletListener, TabletsUpdateEvent};
+    /// # use std::sync::Arc;
+    /// struct PrintingTabletListener;
+    ///
+    /// impl TabletListener for PrintingTabletListener {
+    ///     fn on_tablets_updated(&self, event: &TabletsUpdateEvent) {
+    ///         for table in event.changed_tables() {
+    ///             println!("Tablets of {}.{} changed", table.ks_name(), table.table_name());
+    ///         }
+    ///     }
+    /// }
+    ///
     /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
     /// let session: Session = SessionBuilder::new()
     ///     .known_node("127.0.0.1:9042")
-    ///     .refresh_metadata_on_auto_schema_agreement(true)
+    ///     .tablet_listener(Arc::new(PrintingTabletListener))
     ///     .build()
     ///     .await?;
     /// # Ok(())
     /// # }
     /// ```
-    pub fn refresh_metadata_on_auto_schema_agreement(mut self, refresh_metadata: bool) -> Self {
-        self.config.refresh_metadata_on_auto_schema_agreement = refresh_metadata;
+    pub fn tablet_listener(mut self, listener: Arc<dyn TabletListener>) -> Self {
+        self.config.tablet_listener = Some(listener);
         self
     }
 
@@ -1021,6 +2247,36 @@ impl<K: SessionBuilderKind> GenericSessionBuilder<K> {
         self
     }
 
+    /// Make the delay between attempts to fetch [TracingInfo](crate::observability::tracing::TracingInfo)
+    /// in [`Session::get_tracing_info`](crate::client::session::Session::get_tracing_info)
+    /// double after each attempt, starting with the
+    /// [fetch interval](Self::tracing_info_fetch_interval), up to the given value.
+    /// By default, the delay is constant.
+    ///
+    /// Backing off lets the driver wait long enough for tracing info to appear
+    /// in the tracing tables, without delaying the common case when it appears quickly.
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// # use std::num::NonZeroU32;
+    /// # use std::time::Duration;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("127.0.0.1:9042")
+    ///     .tracing_info_fetch_attempts(NonZeroU32::new(10).unwrap())
+    ///     .tracing_info_fetch_max_interval(Duration::from_millis(500))
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn tracing_info_fetch_max_interval(mut self, max_interval: Duration) -> Self {
+        self.config.tracing_info_fetch_max_interval = Some(max_interval);
+        self
+    }
+
     /// Set the consistency level of fetching [TracingInfo](crate::observability::tracing::TracingInfo)
     /// in [`Session::get_tracing_info`](crate::client::session::Session::get_tracing_info).
     /// The default is [`Consistency::One`].
@@ -1101,6 +2357,85 @@ impl<K: SessionBuilderKind> GenericSessionBuilder<K> {
         self
     }
 
+    /// Limits the number of bytes that a connection collects before flushing them
+    /// to the socket. Once the limit is reached, the data is flushed without
+    /// waiting for more requests, even if write coalescing is enabled.
+    ///
+    /// Lowering this value bounds the latency added to requests at the end
+    /// of a large batch of writes, at the cost of throughput.
+    ///
+    /// This option is `None` (no limit) by default.
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// # use std::num::NonZeroUsize;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("127.0.0.1:9042")
+    ///     .write_coalescing_max_bytes(NonZeroUsize::new(64 * 1024))
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn write_coalescing_max_bytes(mut self, max_bytes: Option<NonZeroUsize>) -> Self {
+        self.config.write_coalescing_max_bytes = max_bytes;
+        self
+    }
+
+    /// Sets the maximum length of a response frame body, as declared in the frame header.
+    /// A connection which receives a longer frame is closed, without allocating
+    /// memory for the body.
+    ///
+    /// This protects the driver from a misbehaving server or proxy.
+    /// The default is 256 MiB.
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("127.0.0.1:9042")
+    ///     .max_response_frame_body_length(64 * 1024 * 1024)
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn max_response_frame_body_length(mut self, max_length: usize) -> Self {
+        self.config.max_response_frame_body_length = max_length;
+        self
+    }
+
+    /// Sets the maximum length of a compressed response frame body after decompression.
+    /// Responses which would decompress to more bytes fail without allocating
+    /// memory for the decompressed body.
+    ///
+    /// The default is 256 MiB.
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// # use scylla::client::Compression;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("127.0.0.1:9042")
+    ///     .compression(Some(Compression::Lz4))
+    ///     .max_decompressed_response_body_length(64 * 1024 * 1024)
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn max_decompressed_response_body_length(mut self, max_length: usize) -> Self {
+        self.config.max_decompressed_response_body_length = max_length;
+        self
+    }
+
     /// Set the interval at which the driver refreshes the cluster metadata which contains information
     /// about the cluster topology as well as the cluster schema.
     ///
@@ -1126,6 +2461,34 @@ impl<K: SessionBuilderKind> GenericSessionBuilder<K> {
         self
     }
 
+    /// Set the key used to sign paging states exported through
+    /// [`Session::paging_state_codec`](crate::client::session::Session::paging_state_codec).
+    ///
+    /// With a key set, paging states handed out to untrusted parties (e.g. as HTTP cursors)
+    /// are authenticated with HMAC-SHA256, and tampered ones are rejected on import
+    /// instead of being forwarded to the server.
+    /// The default is no key.
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// # use scylla::response::PagingStateKey;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("127.0.0.1:9042")
+    ///     .paging_state_key(PagingStateKey::new(*b"a secret of at least 32 bytes...."))
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "paging-state-codec")]
+    pub fn paging_state_key(mut self, key: PagingStateKey) -> Self {
+        self.config.paging_state_key = Some(key);
+        self
+    }
+
     /// Set the custom identity of the driver/application/instance,
     /// to be sent as options in STARTUP message.
     ///
@@ -1172,12 +2535,23 @@ mod tests {
     use scylla_cql::Consistency;
     use scylla_cql::frame::types::SerialConsistency;
 
-    use super::super::Compression;
+    use super::super::{Compression, KeepaliveRequest, PoolWarmup, WriteCoalescingDelay};
     use super::SessionBuilder;
     use crate::client::execution_profile::{ExecutionProfile, defaults};
     use crate::cluster::node::KnownNode;
+    use crate::observability::history::RecentHistoryCollector;
+    use crate::observability::instrumentation::{
+        TraceContextProvider, TraceParent, TracingInstrumentation,
+    };
+    #[cfg(feature = "metrics")]
+    use crate::observability::metrics::LatencyHistogramConfig;
+    use crate::observability::request_observer::RequestObserver;
+    use crate::observability::slow_query_log::SlowQueryLogger;
+    use crate::policies::schema_agreement::SchemaAgreementPolicy;
     use crate::test_utils::setup_tracing;
     use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+    use std::num::{NonZeroU64, NonZeroUsize};
+    use std::sync::Arc;
     use std::time::Duration;
 
     #[test]
@@ -1203,6 +2577,22 @@ mod tests {
         assert_eq!(builder.config.compression, None);
     }
 
+    #[test]
+    fn add_known_srv() {
+        setup_tracing();
+        let builder = SessionBuilder::new()
+            .known_node("test_hostname")
+            .known_srv("_cql._tcp.example.com");
+
+        assert_eq!(
+            builder.config.known_nodes,
+            vec![
+                KnownNode::Hostname("test_hostname".into()),
+                KnownNode::Srv("_cql._tcp.example.com".into())
+            ]
+        );
+    }
+
     #[test]
     fn add_known_node_addr() {
         setup_tracing();
@@ -1265,6 +2655,16 @@ mod tests {
         assert_eq!(builder.config.compression, None);
     }
 
+    #[test]
+    fn compression_threshold() {
+        setup_tracing();
+        let mut builder = SessionBuilder::new();
+        assert_eq!(builder.config.compression_threshold, 0);
+
+        builder = builder.compression_threshold(1024);
+        assert_eq!(builder.config.compression_threshold, 1024);
+    }
+
     #[test]
     fn tcp_nodelay() {
         setup_tracing();
@@ -1278,6 +2678,238 @@ mod tests {
         assert!(builder.config.tcp_nodelay);
     }
 
+    #[test]
+    fn socket_options() {
+        setup_tracing();
+        let mut builder = SessionBuilder::new();
+        assert_eq!(builder.config.tcp_recv_buffer_size, None);
+        assert_eq!(builder.config.tcp_send_buffer_size, None);
+        assert_eq!(builder.config.ip_tos, None);
+        assert_eq!(builder.config.bind_interface, None);
+
+        builder = builder
+            .tcp_recv_buffer_size(Some(1 << 20))
+            .tcp_send_buffer_size(Some(1 << 21))
+            .ip_tos(Some(0xb8))
+            .bind_interface(Some("eth1"));
+        assert_eq!(builder.config.tcp_recv_buffer_size, Some(1 << 20));
+        assert_eq!(builder.config.tcp_send_buffer_size, Some(1 << 21));
+        assert_eq!(builder.config.ip_tos, Some(0xb8));
+        assert_eq!(builder.config.bind_interface.as_deref(), Some("eth1"));
+    }
+
+    #[test]
+    fn pool_warmup() {
+        setup_tracing();
+        let mut builder = SessionBuilder::new();
+        assert_eq!(builder.config.pool_warmup, None);
+
+        let warmup = PoolWarmup::new(Duration::from_secs(5))
+            .with_local_datacenter("dc1")
+            .with_required_fraction(1.0);
+        builder = builder.pool_warmup(Some(warmup.clone()));
+        assert_eq!(builder.config.pool_warmup, Some(warmup));
+
+        builder = builder.pool_warmup(None);
+        assert_eq!(builder.config.pool_warmup, None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn pool_warmup_rejects_invalid_fraction() {
+        PoolWarmup::new(Duration::from_secs(5)).with_required_fraction(1.5);
+    }
+
+    #[test]
+    fn connect_lazily() {
+        setup_tracing();
+        let mut builder = SessionBuilder::new();
+        assert!(!builder.config.connect_lazily);
+
+        builder = builder.connect_lazily(true);
+        assert!(builder.config.connect_lazily);
+
+        builder = builder.connect_lazily(false);
+        assert!(!builder.config.connect_lazily);
+    }
+
+    #[test]
+    fn connection_lifetime() {
+        setup_tracing();
+        let mut builder = SessionBuilder::new();
+        assert_eq!(builder.config.connection_idle_timeout, None);
+        assert_eq!(builder.config.connection_max_age, None);
+
+        builder = builder
+            .connection_idle_timeout(Some(Duration::from_secs(600)))
+            .connection_max_age(Some(Duration::from_secs(3600)));
+        assert_eq!(
+            builder.config.connection_idle_timeout,
+            Some(Duration::from_secs(600))
+        );
+        assert_eq!(
+            builder.config.connection_max_age,
+            Some(Duration::from_secs(3600))
+        );
+    }
+
+    #[test]
+    fn max_in_flight_requests_per_node() {
+        setup_tracing();
+        let mut builder = SessionBuilder::new();
+        assert_eq!(builder.config.max_in_flight_requests_per_node, None);
+
+        builder = builder.max_in_flight_requests_per_node(NonZeroUsize::new(128));
+        assert_eq!(
+            builder.config.max_in_flight_requests_per_node,
+            NonZeroUsize::new(128)
+        );
+    }
+
+    #[test]
+    fn verbose_connection_logging() {
+        setup_tracing();
+        let mut builder = SessionBuilder::new();
+        assert!(!builder.config.verbose_connection_logging);
+
+        builder = builder.verbose_connection_logging(true);
+        assert!(builder.config.verbose_connection_logging);
+    }
+
+    #[test]
+    fn request_observer() {
+        #[derive(Debug)]
+        struct NoopObserver;
+        impl RequestObserver for NoopObserver {}
+
+        setup_tracing();
+        let mut builder = SessionBuilder::new();
+        assert!(builder.config.request_observer.is_none());
+
+        let observer: Arc<dyn RequestObserver> = Arc::new(NoopObserver);
+        builder = builder.request_observer(Arc::clone(&observer));
+        assert!(Arc::ptr_eq(
+            builder.config.request_observer.as_ref().unwrap(),
+            &observer
+        ));
+    }
+
+    #[test]
+    fn tracing_instrumentation() {
+        setup_tracing();
+        let mut builder = SessionBuilder::new();
+        assert_eq!(builder.config.tracing_instrumentation, None);
+
+        let instrumentation = TracingInstrumentation::new().with_attempt_spans(false);
+        builder = builder.tracing_instrumentation(instrumentation.clone());
+        assert_eq!(
+            builder.config.tracing_instrumentation,
+            Some(instrumentation)
+        );
+    }
+
+    #[test]
+    fn trace_context_provider() {
+        #[derive(Debug)]
+        struct NoTrace;
+        impl TraceContextProvider for NoTrace {
+            fn current_trace_parent(&self) -> Option<TraceParent> {
+                None
+            }
+        }
+
+        setup_tracing();
+        let mut builder = SessionBuilder::new();
+        assert!(builder.config.trace_context_provider.is_none());
+
+        let provider: Arc<dyn TraceContextProvider> = Arc::new(NoTrace);
+        builder = builder.trace_context_provider(Arc::clone(&provider));
+        assert!(Arc::ptr_eq(
+            builder.config.trace_context_provider.as_ref().unwrap(),
+            &provider
+        ));
+    }
+
+    #[test]
+    fn slow_query_logger() {
+        setup_tracing();
+        let mut builder = SessionBuilder::new();
+        assert!(builder.config.slow_query_logger.is_none());
+
+        builder = builder.slow_query_logger(
+            SlowQueryLogger::new(Duration::from_millis(100)).with_sampling_rate(0.5),
+        );
+        let logger = builder.config.slow_query_logger.as_ref().unwrap();
+        assert_eq!(logger.threshold(), Duration::from_millis(100));
+        assert_eq!(logger.sampling_rate(), 0.5);
+    }
+
+    #[test]
+    fn session_history() {
+        setup_tracing();
+        let mut builder = SessionBuilder::new();
+        assert!(builder.config.session_history.is_none());
+
+        let history = Arc::new(RecentHistoryCollector::new(10));
+        builder = builder.session_history(Arc::clone(&history));
+        assert!(Arc::ptr_eq(
+            builder.config.session_history.as_ref().unwrap(),
+            &history
+        ));
+    }
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn latency_histogram() {
+        setup_tracing();
+        let config = LatencyHistogramConfig::new().with_percentiles([99.99]);
+        let builder = SessionBuilder::new().latency_histogram(config);
+        assert_eq!(
+            format!("{:?}", builder.config.latency_histogram),
+            format!(
+                "{:?}",
+                LatencyHistogramConfig::new().with_percentiles([99.99])
+            )
+        );
+    }
+
+    #[test]
+    fn keepalive_request() {
+        setup_tracing();
+        let mut builder = SessionBuilder::new();
+        assert_eq!(builder.config.keepalive_request, KeepaliveRequest::Options);
+        assert!(builder.config.close_on_keepalive_failure);
+
+        let query = KeepaliveRequest::Query("SELECT now() FROM system.local".to_owned());
+        builder = builder
+            .keepalive_request(query.clone())
+            .close_on_keepalive_failure(false);
+        assert_eq!(builder.config.keepalive_request, query);
+        assert!(!builder.config.close_on_keepalive_failure);
+    }
+
+    #[test]
+    fn write_coalescing() {
+        setup_tracing();
+        let mut builder = SessionBuilder::new();
+        assert!(builder.config.enable_write_coalescing);
+        assert_eq!(builder.config.write_coalescing_max_bytes, None);
+
+        let max_bytes = NonZeroUsize::new(4096);
+        builder = builder
+            .write_coalescing(false)
+            .write_coalescing_delay(WriteCoalescingDelay::Milliseconds(
+                NonZeroU64::new(2).unwrap(),
+            ))
+            .write_coalescing_max_bytes(max_bytes);
+        assert!(!builder.config.enable_write_coalescing);
+        assert!(matches!(
+            builder.config.write_coalescing_delay,
+            WriteCoalescingDelay::Milliseconds(ms) if ms.get() == 2
+        ));
+        assert_eq!(builder.config.write_coalescing_max_bytes, max_bytes);
+    }
+
     #[test]
     fn use_keyspace() {
         setup_tracing();
@@ -1323,6 +2955,63 @@ mod tests {
         assert!(builder.config.fetch_schema_metadata);
     }
 
+    #[test]
+    fn control_connection_standbys() {
+        setup_tracing();
+        let mut builder = SessionBuilder::new();
+        assert_eq!(builder.config.control_connection_standbys, 0);
+
+        builder = builder.control_connection_standbys(2);
+        assert_eq!(builder.config.control_connection_standbys, 2);
+    }
+
+    #[test]
+    fn hostname_resolution_ttl() {
+        setup_tracing();
+        let mut builder = SessionBuilder::new();
+        assert_eq!(
+            builder.config.hostname_resolution_ttl,
+            Some(Duration::from_secs(30))
+        );
+
+        builder = builder.hostname_resolution_ttl(None);
+        assert_eq!(builder.config.hostname_resolution_ttl, None);
+    }
+
+    #[test]
+    fn degraded_startup() {
+        setup_tracing();
+        let mut builder = SessionBuilder::new();
+        assert!(!builder.config.allow_degraded_startup);
+        assert_eq!(builder.config.initial_metadata_fetch_timeout, None);
+
+        builder = builder
+            .allow_degraded_startup(true)
+            .initial_metadata_fetch_timeout(Some(Duration::from_secs(3)));
+        assert!(builder.config.allow_degraded_startup);
+        assert_eq!(
+            builder.config.initial_metadata_fetch_timeout,
+            Some(Duration::from_secs(3))
+        );
+    }
+
+    #[test]
+    fn schema_agreement_policy() {
+        setup_tracing();
+        let mut builder = SessionBuilder::new();
+        assert!(builder.config.schema_agreement_policy.is_none());
+
+        builder = builder.schema_agreement_policy(
+            SchemaAgreementPolicy::builder()
+                .timeout(Duration::from_secs(300))
+                .required_fraction(0.5)
+                .build(),
+        );
+        let policy = builder.config.schema_agreement_policy.unwrap();
+        assert_eq!(policy.timeout(), Duration::from_secs(300));
+        assert_eq!(policy.required_fraction(), 0.5);
+    }
+
     // LatencyAwarePolicy, which is used in the test, requires presence of Tokio runtime.
     #[tokio::test]
     async fn execution_profile() {
@@ -1394,6 +3083,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn tracing_info_fetch_max_interval() {
+        setup_tracing();
+        let mut builder = SessionBuilder::new();
+        assert_eq!(builder.config.tracing_info_fetch_max_interval, None);
+
+        builder = builder.tracing_info_fetch_max_interval(Duration::from_millis(500));
+        assert_eq!(
+            builder.config.tracing_info_fetch_max_interval,
+            Some(Duration::from_millis(500))
+        );
+    }
+
     #[test]
     fn all_features() {
         setup_tracing();
@@ -0,0 +1,142 @@
+//! Integration with the [tower](https://docs.rs/tower) ecosystem.
+//!
+//! [SessionService] exposes a [Session] as a `tower::Service`, so that existing tower
+//! middleware (e.g. rate limiting, load shedding, timeouts or instrumentation of request
+//! latencies) can wrap requests executed by the driver.
+
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures::future::BoxFuture;
+use tower_service_03::Service;
+
+use crate::client::session::Session;
+use crate::errors::ExecutionError;
+use crate::response::query_result::QueryResult;
+use crate::serialize::row::SerializeRow;
+use crate::statement::prepared::PreparedStatement;
+use crate::statement::unprepared::Statement;
+
+/// A request executed by [SessionService]: a statement together with its bound values.
+#[derive(Clone)]
+#[non_exhaustive]
+pub enum SessionRequest<V> {
+    /// An unprepared statement, executed with [Session::query_unpaged].
+    Unprepared {
+        /// The statement to execute.
+        statement: Statement,
+        /// Values bound to the statement.
+        values: V,
+    },
+    /// A prepared statement, executed with [Session::execute_unpaged].
+    Prepared {
+        /// The statement to execute.
+        statement: PreparedStatement,
+        /// Values bound to the statement.
+        values: V,
+    },
+}
+
+impl<V> SessionRequest<V> {
+    /// Creates a request executing an unprepared statement.
+    pub fn unprepared(statement: impl Into<Statement>, values: V) -> Self {
+        Self::Unprepared {
+            statement: statement.into(),
+            values,
+        }
+    }
+
+    /// Creates a request executing a prepared statement.
+    pub fn prepared(statement: PreparedStatement, values: V) -> Self {
+        Self::Prepared { statement, values }
+    }
+}
+
+impl From<Statement> for SessionRequest<()> {
+    fn from(statement: Statement) -> Self {
+        Self::unprepared(statement, ())
+    }
+}
+
+impl From<&str> for SessionRequest<()> {
+    fn from(statement: &str) -> Self {
+        Self::unprepared(statement, ())
+    }
+}
+
+impl From<PreparedStatement> for SessionRequest<()> {
+    fn from(statement: PreparedStatement) -> Self {
+        Self::prepared(statement, ())
+    }
+}
+
+/// A `tower::Service` executing [SessionRequest]s on a [Session].
+///
+/// Each request is executed unpaged, so the service is meant for statements which
+/// return little data, e.g. writes and single-partition reads.
+/// The service is always ready; backpressure can be added by tower middleware.
+///
+/// # Example
+/// ```rust
+/// # extern crate scylla;
+/// # use scylla::client::session::Session;
+/// # use std::error::Error;
+/// # use std::sync::Arc;
+/// # async fn check_only_compiles(session: Arc<Session>) -> Result<(), Box<dyn Error>> {
+/// use scylla::client::tower::{SessionRequest, SessionService};
+/// use tower_service_03::Service;
+///
+/// let mut service = SessionService::new(session);
+/// let prepared = service
+///     .session()
+///     .prepare("INSERT INTO ks.tab (a, b) VALUES (?, ?)")
+///     .await?;
+/// service
+///     .call(SessionRequest::prepared(prepared, (1_i32, "one")))
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SessionService {
+    session: Arc<Session>,
+}
+
+impl SessionService {
+    /// Creates a service executing requests on the given session.
+    pub fn new(session: Arc<Session>) -> Self {
+        Self { session }
+    }
+
+    /// The session on which requests are executed.
+    pub fn session(&self) -> &Arc<Session> {
+        &self.session
+    }
+}
+
+impl<V> Service<SessionRequest<V>> for SessionService
+where
+    V: SerializeRow + Send + Sync + 'static,
+{
+    type Response = QueryResult;
+    type Error = ExecutionError;
+    type Future = BoxFuture<'static, Result<QueryResult, ExecutionError>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: SessionRequest<V>) -> Self::Future {
+        let session = Arc::clone(&self.session);
+        Box::pin(async move {
+            match request {
+                SessionRequest::Unprepared { statement, values } => {
+                    session.query_unpaged(statement, values).await
+                }
+                SessionRequest::Prepared { statement, values } => {
+                    session.execute_unpaged(&statement, values).await
+                }
+            }
+        })
+    }
+}
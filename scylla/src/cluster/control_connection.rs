@@ -11,6 +11,7 @@ use dashmap::DashMap;
 use crate::client::pager::QueryPager;
 use crate::errors::{NextPageError, NextRowError, RequestAttemptError, RequestError};
 use crate::network::Connection;
+use crate::policies::keyspace_filter::KeyspaceFilter;
 use crate::serialize::row::SerializeRow;
 use crate::statement::Statement;
 use crate::statement::prepared::PreparedStatement;
@@ -24,6 +25,8 @@ pub(super) struct ControlConnection {
     conn: Arc<Connection>,
     /// The custom server-side timeout set for requests executed on the control connection.
     overridden_serverside_timeout: Option<Duration>,
+    /// Decides which keyspaces' schema metadata is fetched.
+    keyspace_filter: Option<Arc<dyn KeyspaceFilter>>,
     cache: Arc<ControlConnectionCache>,
 }
 
@@ -32,6 +35,7 @@ impl ControlConnection {
         Self {
             conn,
             overridden_serverside_timeout: None,
+            keyspace_filter: None,
             cache,
         }
     }
@@ -44,6 +48,24 @@ impl ControlConnection {
         }
     }
 
+    /// Sets the filter deciding which keyspaces' schema metadata is fetched.
+    pub(super) fn with_keyspace_filter(
+        self,
+        keyspace_filter: Option<Arc<dyn KeyspaceFilter>>,
+    ) -> Self {
+        Self {
+            keyspace_filter,
+            ..self
+        }
+    }
+
+    /// Returns true iff schema metadata of the keyspace should be fetched.
+    pub(super) fn accepts_keyspace(&self, keyspace: &str) -> bool {
+        self.keyspace_filter
+            .as_ref()
+            .is_none_or(|filter| filter.accept(keyspace))
+    }
+
     pub(super) fn get_connect_address(&self) -> SocketAddr {
         self.conn.get_connect_address()
     }
@@ -260,6 +282,7 @@ mod tests {
             let (conn, _error_receiver) = open_connection(
                 &UntranslatedEndpoint::ContactPoint(ResolvedContactPoint {
                     address: proxy_addr,
+                    resolved_from: None,
                 }),
                 None,
                 &Default::default(),
@@ -0,0 +1,425 @@
+use std::collections::{BTreeSet, HashMap};
+use std::hash::Hash;
+
+use uuid::Uuid;
+
+use crate::routing::{Token, TokenRange};
+
+use super::ClusterState;
+use super::metadata::{FunctionSignature, Keyspace, Strategy};
+
+/// Differences between two consecutive [ClusterState]s.
+///
+/// Computed after each metadata refresh which changed anything, and delivered to
+/// subscribers of [Session::topology_events](crate::client::session::Session::topology_events)
+/// as [TopologyEvent::ClusterStateChanged](super::TopologyEvent::ClusterStateChanged).
+/// This allows invalidating exactly the affected parts of state derived from cluster metadata.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ClusterStateDiff {
+    /// IDs of nodes which joined the cluster, sorted.
+    pub nodes_added: Vec<Uuid>,
+    /// IDs of nodes which left the cluster, sorted.
+    pub nodes_removed: Vec<Uuid>,
+    /// Token ranges whose primary replica changed, sorted by their ends.
+    ///
+    /// Replicas of vnode-based keyspaces are derived from primary replicas,
+    /// so only data of these ranges may have moved. Tablets are not covered.
+    pub moved_token_ranges: Vec<TokenRange>,
+    /// Created, altered and dropped schema objects.
+    ///
+    /// When a keyspace is created or dropped, all its objects are reported
+    /// as created or dropped, too.
+    pub schema_changes: Vec<SchemaObjectChange>,
+}
+
+/// A change of a single schema object, part of [ClusterStateDiff].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SchemaObjectChange {
+    /// The changed object.
+    pub object: SchemaObject,
+    /// What happened to the object.
+    pub kind: SchemaChangeKind,
+}
+
+/// A schema object, identified by its keyspace and name.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum SchemaObject {
+    /// A keyspace. Altered if its replication strategy changed.
+    Keyspace {
+        /// Name of the keyspace.
+        keyspace: String,
+    },
+    /// A table.
+    Table {
+        /// Keyspace of the table.
+        keyspace: String,
+        /// Name of the table.
+        table: String,
+    },
+    /// A materialized view.
+    View {
+        /// Keyspace of the view.
+        keyspace: String,
+        /// Name of the view.
+        view: String,
+    },
+    /// A user-defined type.
+    UserDefinedType {
+        /// Keyspace of the type.
+        keyspace: String,
+        /// Name of the type.
+        name: String,
+    },
+    /// A user-defined function.
+    Function {
+        /// Keyspace of the function.
+        keyspace: String,
+        /// Name and argument types of the function.
+        signature: FunctionSignature,
+    },
+    /// A user-defined aggregate.
+    Aggregate {
+        /// Keyspace of the aggregate.
+        keyspace: String,
+        /// Name and argument types of the aggregate.
+        signature: FunctionSignature,
+    },
+}
+
+/// Kind of a [SchemaObjectChange].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SchemaChangeKind {
+    /// The object was created.
+    Created,
+    /// The object was altered.
+    Altered,
+    /// The object was dropped.
+    Dropped,
+}
+
+impl ClusterStateDiff {
+    /// Computes the differences between `old` and `new` cluster states.
+    pub fn between(old: &ClusterState, new: &ClusterState) -> Self {
+        let mut nodes_added = new
+            .known_peers
+            .keys()
+            .filter(|host_id| !old.known_peers.contains_key(host_id))
+            .copied()
+            .collect::<Vec<_>>();
+        nodes_added.sort_unstable();
+        let mut nodes_removed = old
+            .known_peers
+            .keys()
+            .filter(|host_id| !new.known_peers.contains_key(host_id))
+            .copied()
+            .collect::<Vec<_>>();
+        nodes_removed.sort_unstable();
+
+        let mut schema_changes = Vec::new();
+        diff_maps(&old.keyspaces, &new.keyspaces, |keyspace, old, new| {
+            diff_keyspaces(keyspace, old, new, &mut schema_changes)
+        });
+        schema_changes.sort_by(|a, b| a.object.cmp(&b.object));
+
+        Self {
+            nodes_added,
+            nodes_removed,
+            moved_token_ranges: moved_token_ranges(&ring_owners(old), &ring_owners(new)),
+            schema_changes,
+        }
+    }
+
+    /// Returns `true` if the states did not differ in any of the compared aspects.
+    pub fn is_empty(&self) -> bool {
+        self.nodes_added.is_empty()
+            && self.nodes_removed.is_empty()
+            && self.moved_token_ranges.is_empty()
+            && self.schema_changes.is_empty()
+    }
+}
+
+/// Calls `on_change` with entries present in only one of the maps,
+/// or present in both but with different values.
+fn diff_maps<K: Eq + Hash, V: PartialEq>(
+    old: &HashMap<K, V>,
+    new: &HashMap<K, V>,
+    mut on_change: impl FnMut(&K, Option<&V>, Option<&V>),
+) {
+    for (key, old_value) in old {
+        match new.get(key) {
+            Some(new_value) if new_value == old_value => (),
+            new_value => on_change(key, Some(old_value), new_value),
+        }
+    }
+    for (key, new_value) in new {
+        if !old.contains_key(key) {
+            on_change(key, None, Some(new_value));
+        }
+    }
+}
+
+fn change_kind<V>(old: Option<&V>, new: Option<&V>) -> SchemaChangeKind {
+    match (old, new) {
+        (None, _) => SchemaChangeKind::Created,
+        (_, None) => SchemaChangeKind::Dropped,
+        (Some(_), Some(_)) => SchemaChangeKind::Altered,
+    }
+}
+
+fn diff_keyspaces(
+    keyspace: &str,
+    old: Option<&Keyspace>,
+    new: Option<&Keyspace>,
+    changes: &mut Vec<SchemaObjectChange>,
+) {
+    if old.map(|ks| &ks.strategy) != new.map(|ks| &ks.strategy) {
+        changes.push(SchemaObjectChange {
+            object: SchemaObject::Keyspace {
+                keyspace: keyspace.to_owned(),
+            },
+            kind: change_kind(old, new),
+        });
+    }
+
+    // Objects of a created or dropped keyspace are compared against an empty one.
+    let empty = Keyspace {
+        strategy: Strategy::LocalStrategy,
+        tables: HashMap::new(),
+        views: HashMap::new(),
+        user_defined_types: HashMap::new(),
+        functions: HashMap::new(),
+        aggregates: HashMap::new(),
+    };
+    let (old, new) = (old.unwrap_or(&empty), new.unwrap_or(&empty));
+    let keyspace = || keyspace.to_owned();
+
+    diff_objects(&old.tables, &new.tables, changes, |table| {
+        SchemaObject::Table {
+            keyspace: keyspace(),
+            table: table.clone(),
+        }
+    });
+    diff_objects(&old.views, &new.views, changes, |view| SchemaObject::View {
+        keyspace: keyspace(),
+        view: view.clone(),
+    });
+    diff_objects(
+        &old.user_defined_types,
+        &new.user_defined_types,
+        changes,
+        |name| SchemaObject::UserDefinedType {
+            keyspace: keyspace(),
+            name: name.clone(),
+        },
+    );
+    diff_objects(&old.functions, &new.functions, changes, |signature| {
+        SchemaObject::Function {
+            keyspace: keyspace(),
+            signature: signature.clone(),
+        }
+    });
+    diff_objects(&old.aggregates, &new.aggregates, changes, |signature| {
+        SchemaObject::Aggregate {
+            keyspace: keyspace(),
+            signature: signature.clone(),
+        }
+    });
+}
+
+fn diff_objects<K: Eq + Hash, V: PartialEq>(
+    old: &HashMap<K, V>,
+    new: &HashMap<K, V>,
+    changes: &mut Vec<SchemaObjectChange>,
+    object: impl Fn(&K) -> SchemaObject,
+) {
+    diff_maps(old, new, |key, old, new| {
+        changes.push(SchemaObjectChange {
+            object: object(key),
+            kind: change_kind(old, new),
+        })
+    });
+}
+
+/// Returns tokens of the ring along with host IDs of their owners.
+fn ring_owners(state: &ClusterState) -> Vec<(Token, Uuid)> {
+    state
+        .locator
+        .ring()
+        .iter()
+        .map(|(token, node)| (*token, node.host_id))
+        .collect()
+}
+
+/// Returns the owner of the range ending at `token`, i.e. the owner
+/// of the first token of the ring not smaller than `token`, wrapping around.
+fn owner_of(ring: &[(Token, Uuid)], token: Token) -> Option<Uuid> {
+    let index = ring.partition_point(|(t, _)| *t < token);
+    ring.get(index).or(ring.first()).map(|(_, owner)| *owner)
+}
+
+/// Computes ranges whose owner differs between the rings, given both rings sorted by tokens.
+///
+/// Adjacent moved ranges are merged.
+fn moved_token_ranges(old: &[(Token, Uuid)], new: &[(Token, Uuid)]) -> Vec<TokenRange> {
+    if old == new {
+        return Vec::new();
+    }
+    let points = old
+        .iter()
+        .chain(new)
+        .map(|(token, _)| *token)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+    let Some(&last) = points.last() else {
+        return Vec::new();
+    };
+
+    let mut moved: Vec<TokenRange> = Vec::new();
+    // The first range wraps around the ring, starting at the last point.
+    let mut start = last;
+    for &end in &points {
+        if owner_of(old, end) != owner_of(new, end) {
+            match moved.last_mut() {
+                Some(range) if range.end() == start => *range = TokenRange::new(range.start(), end),
+                _ => moved.push(TokenRange::new(start, end)),
+            }
+        }
+        start = end;
+    }
+    moved
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use uuid::Uuid;
+
+    use super::{
+        ClusterStateDiff, SchemaChangeKind, SchemaObject, SchemaObjectChange, moved_token_ranges,
+    };
+    use crate::cluster::metadata::Strategy;
+    use crate::routing::locator::test::{
+        KEYSPACE_NTS_RF_2, KEYSPACE_SS_RF_2, mock_metadata_for_token_aware_tests,
+    };
+    use crate::routing::{Token, TokenRange};
+    use crate::test_utils::{
+        MockClusterStateBuilder, mock_cluster_state_for_token_aware_tests, setup_tracing,
+    };
+
+    #[tokio::test]
+    async fn test_cluster_state_diff() {
+        setup_tracing();
+        let old = mock_cluster_state_for_token_aware_tests().await;
+        assert!(ClusterStateDiff::between(&old, &old).is_empty());
+
+        let mut new_metadata = mock_metadata_for_token_aware_tests();
+        // Host IDs of the mock are random, so make them match the old state.
+        for peer in &mut new_metadata.peers {
+            peer.host_id = old
+                .known_peers
+                .values()
+                .find(|node| node.address == peer.address)
+                .unwrap()
+                .host_id;
+        }
+        let removed = new_metadata.peers.pop().unwrap();
+        new_metadata.keyspaces.remove(KEYSPACE_SS_RF_2);
+        let nts_keyspace = new_metadata
+            .keyspaces
+            .get_mut(KEYSPACE_NTS_RF_2)
+            .unwrap()
+            .as_mut()
+            .unwrap();
+        nts_keyspace.strategy = Strategy::SimpleStrategy {
+            replication_factor: 1,
+        };
+        nts_keyspace
+            .tables
+            .insert("t".to_owned(), new_metadata_table());
+        let new = MockClusterStateBuilder::new(new_metadata).build().await;
+
+        let diff = ClusterStateDiff::between(&old, &new);
+        assert!(diff.nodes_added.is_empty());
+        assert_eq!(diff.nodes_removed, [removed.host_id]);
+        // Every token of the removed node moved to its successor.
+        assert_eq!(diff.moved_token_ranges.len(), removed.tokens.len());
+        assert_eq!(
+            diff.schema_changes,
+            [
+                SchemaObjectChange {
+                    object: SchemaObject::Keyspace {
+                        keyspace: KEYSPACE_NTS_RF_2.to_owned(),
+                    },
+                    kind: SchemaChangeKind::Altered,
+                },
+                SchemaObjectChange {
+                    object: SchemaObject::Keyspace {
+                        keyspace: KEYSPACE_SS_RF_2.to_owned(),
+                    },
+                    kind: SchemaChangeKind::Dropped,
+                },
+                SchemaObjectChange {
+                    object: SchemaObject::Table {
+                        keyspace: KEYSPACE_NTS_RF_2.to_owned(),
+                        table: "t".to_owned(),
+                    },
+                    kind: SchemaChangeKind::Created,
+                },
+            ]
+        );
+    }
+
+    fn new_metadata_table() -> crate::cluster::metadata::Table {
+        crate::cluster::metadata::Table {
+            columns: HashMap::new(),
+            partition_key: vec![],
+            clustering_key: vec![],
+            partitioner: None,
+            options: Default::default(),
+            extensions: Default::default(),
+            indexes: HashMap::new(),
+            pk_column_specs: vec![],
+        }
+    }
+
+    #[test]
+    fn test_moved_token_ranges() {
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let t = Token::new;
+        let old = [(t(0), a), (t(100), b)];
+
+        assert!(moved_token_ranges(&old, &old).is_empty());
+
+        // `c` takes over (0, 50] from `b`.
+        let new = [(t(0), a), (t(50), c), (t(100), b)];
+        assert_eq!(
+            moved_token_ranges(&old, &new),
+            [TokenRange::new(t(0), t(50))]
+        );
+
+        // `a` leaves, so `b` takes over the wrapping range (100, 0].
+        let new = [(t(100), b)];
+        assert_eq!(
+            moved_token_ranges(&old, &new),
+            [TokenRange::new(t(100), t(0))]
+        );
+
+        // Adjacent moved ranges are merged.
+        let new = [(t(0), c), (t(50), c), (t(100), b)];
+        assert_eq!(
+            moved_token_ranges(&old, &new),
+            [TokenRange::new(t(100), t(50))]
+        );
+        let new = [(t(0), a), (t(50), c), (t(100), c)];
+        assert_eq!(
+            moved_token_ranges(&old, &new),
+            [TokenRange::new(t(0), t(100))]
+        );
+    }
+}
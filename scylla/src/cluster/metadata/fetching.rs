@@ -23,6 +23,7 @@ use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Instant;
 
+use crate::observability::log_targets;
 use futures::{FutureExt, Stream, StreamExt, TryStreamExt, future, stream};
 use rand::Rng;
 use scylla_cql::frame::response::result::{ColumnSpec, TableSpec};
@@ -35,16 +36,23 @@ use crate::client::pager::QueryPager;
 use crate::cluster::NodeAddr;
 use crate::cluster::control_connection::ControlConnection;
 use crate::cluster::metadata::{
-    CollectionType, Column, ColumnKind, ColumnType, Keyspace, MaterializedView, Metadata,
-    MissingUserDefinedType, NativeType, Peer, SingleKeyspaceMetadataError, Strategy, Table,
-    UserDefinedType,
+    CachingOptions, CdcOptions, CollectionType, Column, ColumnKind, ColumnType, CompactionOptions,
+    CompactionStrategy, CompressionOptions, FunctionSignature, Index, IndexKind, Keyspace,
+    MaterializedView, Metadata, MissingUserDefinedType, NativeType, Peer, PerPartitionRateLimit,
+    SingleKeyspaceMetadataError, Strategy, Table, TableExtensions, TableOptions, TabletOptions,
+    UserDefinedAggregate, UserDefinedFunction, UserDefinedType,
 };
-use crate::deserialize::DeserializeOwnedRow;
+use crate::deserialize::row::{
+    BuiltinTypeCheckError, BuiltinTypeCheckErrorKind, ColumnIterator, DeserializeRow,
+};
+use crate::deserialize::value::DeserializeValue;
+use crate::deserialize::{DeserializationError, DeserializeOwnedRow, TypeCheckError};
 use crate::errors::{
-    DbError, KeyspaceStrategyError, KeyspacesMetadataError, MetadataError, MetadataFetchError,
-    MetadataFetchErrorKind, NextPageError, NextRowError, PeersMetadataError, RequestAttemptError,
-    RequestError, TablesMetadataError, UdtMetadataError,
+    DbError, FunctionsMetadataError, KeyspaceStrategyError, KeyspacesMetadataError, MetadataError,
+    MetadataFetchError, MetadataFetchErrorKind, NextPageError, NextRowError, PeersMetadataError,
+    RequestAttemptError, RequestError, TablesMetadataError, UdtMetadataError,
 };
+use crate::policies::address_translator::{PeerAddressPreference, PeerAddresses};
 use crate::routing::Token;
 
 type PerKeyspace<T> = HashMap<String, T>;
@@ -157,10 +165,11 @@ impl ControlConnection {
     pub(crate) async fn query_metadata(
         &self,
         connect_port: u16,
+        address_preference: &PeerAddressPreference,
         keyspace_to_fetch: &[String],
         fetch_schema: bool,
     ) -> Result<Metadata, MetadataError> {
-        let peers_query = self.query_peers(connect_port);
+        let peers_query = self.query_peers(connect_port, address_preference);
         let keyspaces_query = self.query_keyspaces(keyspace_to_fetch, fetch_schema);
 
         let (peers, keyspaces) = tokio::try_join!(peers_query, keyspaces_query)?;
@@ -177,6 +186,44 @@ impl ControlConnection {
 
         Ok(Metadata { peers, keyspaces })
     }
+
+    /// Fetches schema metadata of a single keyspace, skipping topology metadata.
+    ///
+    /// Returns `None` if the keyspace does not exist.
+    pub(crate) async fn query_keyspace_metadata(
+        &self,
+        keyspace: &str,
+        fetch_schema: bool,
+    ) -> Result<Option<Result<Keyspace, SingleKeyspaceMetadataError>>, MetadataError> {
+        let keyspaces_to_fetch = [keyspace.to_owned()];
+        let mut keyspaces = self
+            .query_keyspaces(&keyspaces_to_fetch, fetch_schema)
+            .await?;
+        Ok(keyspaces.remove(keyspace))
+    }
+}
+
+/// A row of a `system_schema` table, which is partitioned by keyspace name.
+trait KeyspaceNamedRow {
+    fn keyspace_name(&self) -> &str;
+}
+
+impl<T> KeyspaceNamedRow for (String, T) {
+    fn keyspace_name(&self) -> &str {
+        &self.0
+    }
+}
+
+impl<T, U> KeyspaceNamedRow for (String, T, U) {
+    fn keyspace_name(&self) -> &str {
+        &self.0
+    }
+}
+
+impl<T, U, V, W, X> KeyspaceNamedRow for (String, T, U, V, W, X) {
+    fn keyspace_name(&self) -> &str {
+        &self.0
+    }
 }
 
 #[derive(DeserializeRow)]
@@ -185,10 +232,14 @@ struct NodeInfoRow {
     host_id: Option<Uuid>,
     #[scylla(rename = "rpc_address")]
     untranslated_ip_addr: IpAddr,
+    // `peer` in system.peers, `broadcast_address` in system.local.
+    #[scylla(rename = "peer")]
+    listen_address: Option<IpAddr>,
     #[scylla(rename = "data_center")]
     datacenter: Option<String>,
     rack: Option<String>,
     tokens: Option<Vec<String>>,
+    release_version: Option<String>,
 }
 
 #[derive(Clone, Copy)]
@@ -207,10 +258,14 @@ impl NodeInfoSource {
 }
 
 impl ControlConnection {
-    async fn query_peers(&self, connect_port: u16) -> Result<Vec<Peer>, MetadataError> {
+    async fn query_peers(
+        &self,
+        connect_port: u16,
+        address_preference: &PeerAddressPreference,
+    ) -> Result<Vec<Peer>, MetadataError> {
         let peers_query_stream = self
             .query_iter(
-                "select host_id, rpc_address, data_center, rack, tokens from system.peers",
+                "select host_id, rpc_address, peer, data_center, rack, tokens, release_version from system.peers",
                 &(),
             )
             .map(|pager_res| {
@@ -229,7 +284,7 @@ impl ControlConnection {
             .and_then(|row_result| future::ok((NodeInfoSource::Peer, row_result)));
 
         let local_query_stream = self
-            .query_iter("select host_id, rpc_address, data_center, rack, tokens from system.local WHERE key='local'", &())
+            .query_iter("select host_id, rpc_address, broadcast_address as peer, data_center, rack, tokens, release_version from system.local WHERE key='local'", &())
             .map(|pager_res| {
                 let pager = pager_res?;
                 let rows_stream = pager.rows_stream::<NodeInfoRow>()?;
@@ -252,9 +307,12 @@ impl ControlConnection {
 
         let translated_peers_futures = untranslated_rows.map(|row_result| async {
             match row_result {
-                Ok((source, row)) => Self::create_peer_from_row(source, row, local_address).await,
+                Ok((source, row)) => {
+                    Self::create_peer_from_row(source, row, local_address, address_preference).await
+                }
                 Err(err) => {
                     warn!(
+                        target: log_targets::METADATA,
                         "system.peers or system.local has an invalid row, skipping it: {}",
                         err
                     );
@@ -275,19 +333,23 @@ impl ControlConnection {
         source: NodeInfoSource,
         row: NodeInfoRow,
         local_address: SocketAddr,
+        address_preference: &PeerAddressPreference,
     ) -> Option<Peer> {
         let NodeInfoRow {
             host_id,
             untranslated_ip_addr,
+            listen_address,
             datacenter,
             rack,
             tokens,
+            release_version,
         } = row;
 
         let host_id = match host_id {
             Some(host_id) => host_id,
             None => {
                 warn!(
+                    target: log_targets::METADATA,
                     "{} (untranslated ip: {}, dc: {:?}, rack: {:?}) has Host ID set to null; skipping node.",
                     source.describe(),
                     untranslated_ip_addr,
@@ -299,7 +361,14 @@ impl ControlConnection {
         };
 
         let connect_port = local_address.port();
-        let untranslated_address = SocketAddr::new(untranslated_ip_addr, connect_port);
+        let preferred_ip_addr = address_preference.choose(&PeerAddresses {
+            host_id,
+            broadcast_rpc_address: untranslated_ip_addr,
+            listen_address,
+            datacenter: datacenter.as_deref(),
+            rack: rack.as_deref(),
+        });
+        let untranslated_address = SocketAddr::new(preferred_ip_addr, connect_port);
 
         let node_addr = match source {
             NodeInfoSource::Local => {
@@ -330,6 +399,7 @@ impl ControlConnection {
                 // Also, we could implement support for Cassandra's other standard partitioners
                 // like RandomPartitioner or ByteOrderedPartitioner.
                 trace!(
+                    target: log_targets::METADATA,
                     "Couldn't parse tokens as 64-bit integers: {}, proceeding with a dummy token. If you're using a partitioner with different token size, consider migrating to murmur3",
                     e
                 );
@@ -343,6 +413,7 @@ impl ControlConnection {
             tokens,
             datacenter,
             rack,
+            release_version,
         })
     }
 
@@ -352,7 +423,7 @@ impl ControlConnection {
         keyspaces_to_fetch: &'a [String],
     ) -> impl Stream<Item = Result<R, MetadataFetchErrorKind>> + use<'a, R>
     where
-        R: DeserializeOwnedRow + 'static,
+        R: DeserializeOwnedRow + KeyspaceNamedRow + 'static,
     {
         // This function is extracted to reduce monomorphisation penalty:
         // query_filter_keyspace_name() is going to be monomorphised into 5 distinct functions,
@@ -384,6 +455,9 @@ impl ControlConnection {
         fut.into_stream()
             .map(|result| result.map(|stream| stream.map_err(MetadataFetchErrorKind::NextRowError)))
             .try_flatten()
+            // Rows of keyspaces rejected by the keyspace filter are dropped
+            // before any further processing.
+            .try_filter(move |row| future::ready(self.accepts_keyspace(row.keyspace_name())))
     }
 
     async fn query_keyspaces(
@@ -401,9 +475,23 @@ impl ControlConnection {
                 table: "system_schema.keyspaces",
             });
 
-        let (mut all_tables, mut all_views, mut all_user_defined_types) = if fetch_schema {
+        let (
+            mut all_tables,
+            mut all_views,
+            mut all_user_defined_types,
+            mut all_functions,
+            mut all_aggregates,
+        ) = if fetch_schema {
             let udts = self.query_user_defined_types(keyspaces_to_fetch).await?;
             let mut tables_schema = self.query_tables_schema(keyspaces_to_fetch, &udts).await?;
+            let functions = self.query_functions(keyspaces_to_fetch, &udts).await?;
+            let aggregates = self.query_aggregates(keyspaces_to_fetch, &udts).await?;
+            for (keyspace_and_table_name, indexes) in self.query_indexes(keyspaces_to_fetch).await?
+            {
+                if let Some(Ok(table)) = tables_schema.get_mut(&keyspace_and_table_name) {
+                    table.indexes = indexes;
+                }
+            }
             (
                 // We pass the mutable reference to the same map to the both functions.
                 // First function fetches `system_schema.tables`, and removes found
@@ -417,9 +505,17 @@ impl ControlConnection {
                 self.query_views(keyspaces_to_fetch, &mut tables_schema)
                     .await?,
                 udts,
+                functions,
+                aggregates,
             )
         } else {
-            (HashMap::new(), HashMap::new(), HashMap::new())
+            (
+                HashMap::new(),
+                HashMap::new(),
+                HashMap::new(),
+                HashMap::new(),
+                HashMap::new(),
+            )
         };
 
         rows.map(|row_result| {
@@ -440,30 +536,42 @@ impl ControlConnection {
             let user_defined_types = all_user_defined_types
                 .remove(&keyspace_name)
                 .unwrap_or_else(|| Ok(HashMap::new()));
+            let functions = all_functions
+                .remove(&keyspace_name)
+                .unwrap_or_else(|| Ok(HashMap::new()));
+            let aggregates = all_aggregates
+                .remove(&keyspace_name)
+                .unwrap_or_else(|| Ok(HashMap::new()));
 
             // As you can notice, in this file we generally operate on two layers of errors:
             // - Outer (MetadataError) if something went wrong with querying the cluster.
             // - Inner (SingleKeyspaceMetadataError) if the fetched metadata turned out to not be fully consistent.
             // If there is an inner error, we want to drop metadata for the whole keyspace.
-            // This logic checks if either tables, views, or UDTs have such inner error, and returns it if so.
+            // This logic checks if either tables, views, UDTs, UDFs or UDAs have such inner error,
+            // and returns it if so.
             // Notice that in the error branch, return value is wrapped in `Ok` - but this is the
             // outer error, so it just means there was no error while querying the cluster.
-            let (tables, views, user_defined_types) = match (tables, views, user_defined_types) {
-                (Ok(t), Ok(v), Ok(u)) => (t, v, u),
-                (Err(e), _, _) | (_, Err(e), _) => return Ok((keyspace_name, Err(e))),
-                (_, _, Err(e)) => {
-                    return Ok((
-                        keyspace_name,
-                        Err(SingleKeyspaceMetadataError::MissingUDT(e)),
-                    ));
-                }
-            };
+            let (tables, views, user_defined_types, functions, aggregates) =
+                match (tables, views, user_defined_types, functions, aggregates) {
+                    (Ok(t), Ok(v), Ok(u), Ok(f), Ok(a)) => (t, v, u, f, a),
+                    (Err(e), _, _, _, _) | (_, Err(e), _, _, _) => {
+                        return Ok((keyspace_name, Err(e)));
+                    }
+                    (_, _, Err(e), _, _) | (_, _, _, Err(e), _) | (_, _, _, _, Err(e)) => {
+                        return Ok((
+                            keyspace_name,
+                            Err(SingleKeyspaceMetadataError::MissingUDT(e)),
+                        ));
+                    }
+                };
 
             let keyspace = Keyspace {
                 strategy,
                 tables,
                 views,
                 user_defined_types,
+                functions,
+                aggregates,
             };
 
             Ok((keyspace_name, Ok(keyspace)))
@@ -490,6 +598,205 @@ struct UdtRowWithParsedFieldTypes {
     field_types: Vec<PreColumnType>,
 }
 
+#[derive(DeserializeRow)]
+#[scylla(crate = "crate")]
+struct IndexRow {
+    keyspace_name: String,
+    table_name: String,
+    index_name: String,
+    kind: String,
+    options: Option<HashMap<String, String>>,
+}
+
+impl KeyspaceNamedRow for IndexRow {
+    fn keyspace_name(&self) -> &str {
+        &self.keyspace_name
+    }
+}
+
+#[derive(DeserializeRow)]
+#[scylla(crate = "crate")]
+struct TableRow {
+    keyspace_name: String,
+    table_name: String,
+    extensions: Option<HashMap<String, Vec<u8>>>,
+    caching: Option<HashMap<String, String>>,
+    comment: Option<String>,
+    compaction: Option<HashMap<String, String>>,
+    compression: Option<HashMap<String, String>>,
+    default_time_to_live: Option<i32>,
+    gc_grace_seconds: Option<i32>,
+}
+
+impl KeyspaceNamedRow for TableRow {
+    fn keyspace_name(&self) -> &str {
+        &self.keyspace_name
+    }
+}
+
+#[derive(DeserializeRow)]
+#[scylla(crate = "crate")]
+struct ViewRow {
+    keyspace_name: String,
+    view_name: String,
+    base_table_name: String,
+    caching: Option<HashMap<String, String>>,
+    comment: Option<String>,
+    compaction: Option<HashMap<String, String>>,
+    compression: Option<HashMap<String, String>>,
+    default_time_to_live: Option<i32>,
+    gc_grace_seconds: Option<i32>,
+}
+
+impl KeyspaceNamedRow for ViewRow {
+    fn keyspace_name(&self) -> &str {
+        &self.keyspace_name
+    }
+}
+
+/// Builds [TableOptions] from the option columns shared by
+/// `system_schema.tables` and `system_schema.views`.
+fn table_options(
+    compaction: Option<HashMap<String, String>>,
+    compression: Option<HashMap<String, String>>,
+    caching: Option<HashMap<String, String>>,
+    default_time_to_live: Option<i32>,
+    gc_grace_seconds: Option<i32>,
+    comment: Option<String>,
+) -> TableOptions {
+    let compaction = compaction.unwrap_or_default();
+    let compression = compression.unwrap_or_default();
+    let caching = caching.unwrap_or_default();
+
+    TableOptions {
+        compaction: CompactionOptions {
+            strategy: compaction
+                .get("class")
+                .map(|class| CompactionStrategy::from_class(class)),
+            options: compaction,
+        },
+        compression: CompressionOptions {
+            // Cassandra 2.x used `sstable_compression`, newer versions use `class`.
+            compressor: compression
+                .get("class")
+                .or_else(|| compression.get("sstable_compression"))
+                .filter(|class| !class.is_empty())
+                .cloned(),
+            chunk_length_in_kb: compression
+                .get("chunk_length_in_kb")
+                .or_else(|| compression.get("chunk_length_kb"))
+                .and_then(|length| length.parse().ok()),
+            options: compression,
+        },
+        caching: CachingOptions {
+            keys: caching.get("keys").cloned(),
+            rows_per_partition: caching.get("rows_per_partition").cloned(),
+            options: caching,
+        },
+        default_time_to_live: default_time_to_live.and_then(|ttl| u32::try_from(ttl).ok()),
+        gc_grace_seconds: gc_grace_seconds.and_then(|seconds| u32::try_from(seconds).ok()),
+        comment,
+    }
+}
+
+#[derive(DeserializeRow)]
+#[scylla(crate = "crate")]
+struct FunctionRow {
+    keyspace_name: String,
+    function_name: String,
+    argument_names: Option<Vec<String>>,
+    argument_types: Option<Vec<String>>,
+    return_type: String,
+    language: String,
+    body: String,
+    called_on_null_input: bool,
+}
+
+impl KeyspaceNamedRow for FunctionRow {
+    fn keyspace_name(&self) -> &str {
+        &self.keyspace_name
+    }
+}
+
+#[derive(DeserializeRow)]
+#[scylla(crate = "crate")]
+struct AggregateRow {
+    keyspace_name: String,
+    aggregate_name: String,
+    argument_types: Option<Vec<String>>,
+    return_type: String,
+    state_func: String,
+    state_type: String,
+    final_func: Option<String>,
+    initcond: Option<String>,
+}
+
+impl KeyspaceNamedRow for AggregateRow {
+    fn keyspace_name(&self) -> &str {
+        &self.keyspace_name
+    }
+}
+
+/// Reasons why a type of a function or aggregate could not be resolved.
+enum FunctionTypeError {
+    /// The type string is malformed.
+    Invalid(FunctionsMetadataError),
+    /// The type refers to a UDT missing from the keyspace,
+    /// which invalidates metadata of the keyspace.
+    MissingUdt(MissingUserDefinedType),
+}
+
+/// Resolves a CQL type of a function or aggregate argument, return value or state.
+fn function_cql_type(
+    typ: &str,
+    keyspace_name: &str,
+    udts: &PerKeyspaceResult<PerTable<Arc<UserDefinedType<'static>>>, MissingUserDefinedType>,
+) -> Result<ColumnType<'static>, FunctionTypeError> {
+    let pre_cql_type = map_string_to_cql_type(typ).map_err(|err| {
+        FunctionTypeError::Invalid(FunctionsMetadataError::InvalidCqlType {
+            typ: err.typ,
+            position: err.position,
+            reason: err.reason,
+        })
+    })?;
+    let keyspace_udts = match udts.get(keyspace_name) {
+        Some(Ok(keyspace_udts)) => keyspace_udts,
+        Some(Err(e)) => return Err(FunctionTypeError::MissingUdt(e.clone())),
+        None => &HashMap::new(),
+    };
+    pre_cql_type
+        .into_cql_type(keyspace_name, keyspace_udts)
+        .map_err(FunctionTypeError::MissingUdt)
+}
+
+/// Inserts a function or aggregate into the per-keyspace result.
+/// A missing UDT invalidates all functions of the keyspace.
+fn insert_function_result<T>(
+    result: &mut PerKeyspaceResult<HashMap<FunctionSignature, T>, MissingUserDefinedType>,
+    keyspace_name: String,
+    signature: FunctionSignature,
+    function: Result<T, FunctionTypeError>,
+) -> Result<(), MetadataError> {
+    let entry = result
+        .entry(keyspace_name)
+        .or_insert_with(|| Ok(HashMap::new()));
+    match (entry.as_mut(), function) {
+        (_, Err(FunctionTypeError::Invalid(e))) => return Err(e.into()),
+        (Ok(functions), Ok(function)) => {
+            functions.insert(signature, function);
+        }
+        (Err(_), _) => (),
+        (Ok(_), Err(FunctionTypeError::MissingUdt(e))) => *entry = Err(e),
+    }
+    Ok(())
+}
+
+impl KeyspaceNamedRow for UdtRow {
+    fn keyspace_name(&self) -> &str {
+        &self.keyspace_name
+    }
+}
+
 impl TryFrom<UdtRow> for UdtRowWithParsedFieldTypes {
     type Error = InvalidCqlType;
     fn try_from(udt_row: UdtRow) -> Result<Self, InvalidCqlType> {
@@ -548,6 +855,7 @@ impl ControlConnection {
         topo_sort_udts(&mut udt_rows)?;
         let toposort_elapsed = instant_before_toposort.elapsed();
         debug!(
+            target: log_targets::METADATA,
             "Toposort of UDT definitions took {:.2} ms (udts len: {})",
             toposort_elapsed.as_secs_f64() * 1000.,
             udt_rows.len(),
@@ -575,7 +883,7 @@ impl ControlConnection {
 
             let mut fields = Vec::with_capacity(field_names.len());
 
-            for (field_name, field_type) in field_names.into_iter().zip(field_types.into_iter()) {
+            for (field_name, field_type) in field_names.into_iter().zip(field_types) {
                 match field_type.into_cql_type(&keyspace_name_clone, keyspace_udts) {
                     Ok(cql_type) => fields.push((field_name.into(), cql_type)),
                     Err(e) => {
@@ -866,8 +1174,9 @@ impl ControlConnection {
     ) -> Result<PerKeyspaceResult<PerTable<Table>, SingleKeyspaceMetadataError>, MetadataError>
     {
         let rows = self
-            .query_filter_keyspace_name::<(String, String)>(
-                "SELECT keyspace_name, table_name FROM system_schema.tables",
+            .query_filter_keyspace_name::<TableRow>(
+                "SELECT keyspace_name, table_name, extensions, caching, comment, compaction, \
+                compression, default_time_to_live, gc_grace_seconds FROM system_schema.tables",
                 keyspaces_to_fetch,
             )
             .map_err(|error| MetadataFetchError {
@@ -877,15 +1186,46 @@ impl ControlConnection {
         let mut result = HashMap::new();
 
         rows.map(|row_result| {
-            let keyspace_and_table_name = row_result?;
-
-            let table = tables.remove(&keyspace_and_table_name).unwrap_or(Ok(Table {
-                columns: HashMap::new(),
-                partition_key: vec![],
-                clustering_key: vec![],
-                partitioner: None,
-                pk_column_specs: vec![],
-            }));
+            let TableRow {
+                keyspace_name,
+                table_name,
+                extensions: schema_extensions,
+                caching,
+                comment,
+                compaction,
+                compression,
+                default_time_to_live,
+                gc_grace_seconds,
+            } = row_result?;
+            let options = table_options(
+                compaction,
+                compression,
+                caching,
+                default_time_to_live,
+                gc_grace_seconds,
+                comment,
+            );
+            let keyspace_and_table_name = (keyspace_name, table_name);
+
+            let table = tables
+                .remove(&keyspace_and_table_name)
+                .unwrap_or(Ok(Table {
+                    columns: HashMap::new(),
+                    partition_key: vec![],
+                    clustering_key: vec![],
+                    partitioner: None,
+                    options: TableOptions::default(),
+                    extensions: TableExtensions::default(),
+                    indexes: HashMap::new(),
+                    pk_column_specs: vec![],
+                }))
+                .map(|mut table| {
+                    table.options = options;
+                    if let Some(schema_extensions) = schema_extensions {
+                        apply_schema_extensions(&mut table.extensions, schema_extensions);
+                    }
+                    table
+                });
 
             let mut entry = result
                 .entry(keyspace_and_table_name.0)
@@ -915,8 +1255,9 @@ impl ControlConnection {
         MetadataError,
     > {
         let rows = self
-            .query_filter_keyspace_name::<(String, String, String)>(
-                "SELECT keyspace_name, view_name, base_table_name FROM system_schema.views",
+            .query_filter_keyspace_name::<ViewRow>(
+                "SELECT keyspace_name, view_name, base_table_name, caching, comment, compaction, \
+                compression, default_time_to_live, gc_grace_seconds FROM system_schema.views",
                 keyspaces_to_fetch,
             )
             .map_err(|error| MetadataFetchError {
@@ -927,7 +1268,25 @@ impl ControlConnection {
         let mut result = HashMap::new();
 
         rows.map(|row_result| {
-            let (keyspace_name, view_name, base_table_name) = row_result?;
+            let ViewRow {
+                keyspace_name,
+                view_name,
+                base_table_name,
+                caching,
+                comment,
+                compaction,
+                compression,
+                default_time_to_live,
+                gc_grace_seconds,
+            } = row_result?;
+            let options = table_options(
+                compaction,
+                compression,
+                caching,
+                default_time_to_live,
+                gc_grace_seconds,
+                comment,
+            );
 
             let keyspace_and_view_name = (keyspace_name, view_name);
 
@@ -938,11 +1297,17 @@ impl ControlConnection {
                     partition_key: vec![],
                     clustering_key: vec![],
                     partitioner: None,
+                    options: TableOptions::default(),
+                    extensions: TableExtensions::default(),
+                    indexes: HashMap::new(),
                     pk_column_specs: vec![],
                 }))
-                .map(|table| MaterializedView {
-                    view_metadata: table,
-                    base_table_name,
+                .map(|mut table| {
+                    table.options = options;
+                    MaterializedView {
+                        view_metadata: table,
+                        base_table_name,
+                    }
                 });
 
             let mut entry = result
@@ -965,6 +1330,164 @@ impl ControlConnection {
         Ok(result)
     }
 
+    async fn query_indexes(
+        &self,
+        keyspaces_to_fetch: &[String],
+    ) -> Result<PerKsTable<PerTable<Index>>, MetadataError> {
+        let rows = self
+            .query_filter_keyspace_name::<IndexRow>(
+                "SELECT keyspace_name, table_name, index_name, kind, options FROM system_schema.indexes",
+                keyspaces_to_fetch,
+            )
+            .map_err(|error| MetadataFetchError {
+                error,
+                table: "system_schema.indexes",
+            });
+
+        let mut result: PerKsTable<PerTable<Index>> = HashMap::new();
+
+        rows.map(|row_result| {
+            let IndexRow {
+                keyspace_name,
+                table_name,
+                index_name,
+                kind,
+                options,
+            } = row_result?;
+
+            result
+                .entry((keyspace_name, table_name))
+                .or_default()
+                .insert(
+                    index_name,
+                    Index {
+                        kind: IndexKind::from(kind),
+                        options: options.unwrap_or_default(),
+                    },
+                );
+
+            Ok::<_, MetadataError>(())
+        })
+        .try_for_each(|_| future::ok(()))
+        .await?;
+
+        Ok(result)
+    }
+
+    async fn query_functions(
+        &self,
+        keyspaces_to_fetch: &[String],
+        udts: &PerKeyspaceResult<PerTable<Arc<UserDefinedType<'static>>>, MissingUserDefinedType>,
+    ) -> Result<
+        PerKeyspaceResult<HashMap<FunctionSignature, UserDefinedFunction>, MissingUserDefinedType>,
+        MetadataError,
+    > {
+        let rows = self
+            .query_filter_keyspace_name::<FunctionRow>(
+                "SELECT keyspace_name, function_name, argument_names, argument_types, return_type, \
+                language, body, called_on_null_input FROM system_schema.functions",
+                keyspaces_to_fetch,
+            )
+            .map_err(|error| MetadataFetchError {
+                error,
+                table: "system_schema.functions",
+            });
+
+        let mut result = HashMap::new();
+
+        rows.map(|row_result| {
+            let FunctionRow {
+                keyspace_name,
+                function_name,
+                argument_names,
+                argument_types,
+                return_type,
+                language,
+                body,
+                called_on_null_input,
+            } = row_result?;
+            let argument_types = argument_types.unwrap_or_default();
+
+            let function = (|| {
+                Ok::<_, FunctionTypeError>(UserDefinedFunction {
+                    argument_names: argument_names.unwrap_or_default(),
+                    argument_types: argument_types
+                        .iter()
+                        .map(|typ| function_cql_type(typ, &keyspace_name, udts))
+                        .collect::<Result<_, _>>()?,
+                    return_type: function_cql_type(&return_type, &keyspace_name, udts)?,
+                    language,
+                    body,
+                    called_on_null_input,
+                })
+            })();
+
+            let signature = FunctionSignature::new(function_name, argument_types);
+            insert_function_result(&mut result, keyspace_name, signature, function)
+        })
+        .try_for_each(|_| future::ok(()))
+        .await?;
+
+        Ok(result)
+    }
+
+    async fn query_aggregates(
+        &self,
+        keyspaces_to_fetch: &[String],
+        udts: &PerKeyspaceResult<PerTable<Arc<UserDefinedType<'static>>>, MissingUserDefinedType>,
+    ) -> Result<
+        PerKeyspaceResult<HashMap<FunctionSignature, UserDefinedAggregate>, MissingUserDefinedType>,
+        MetadataError,
+    > {
+        let rows = self
+            .query_filter_keyspace_name::<AggregateRow>(
+                "SELECT keyspace_name, aggregate_name, argument_types, return_type, state_func, \
+                state_type, final_func, initcond FROM system_schema.aggregates",
+                keyspaces_to_fetch,
+            )
+            .map_err(|error| MetadataFetchError {
+                error,
+                table: "system_schema.aggregates",
+            });
+
+        let mut result = HashMap::new();
+
+        rows.map(|row_result| {
+            let AggregateRow {
+                keyspace_name,
+                aggregate_name,
+                argument_types,
+                return_type,
+                state_func,
+                state_type,
+                final_func,
+                initcond,
+            } = row_result?;
+            let argument_types = argument_types.unwrap_or_default();
+
+            let aggregate = (|| {
+                Ok::<_, FunctionTypeError>(UserDefinedAggregate {
+                    argument_types: argument_types
+                        .iter()
+                        .map(|typ| function_cql_type(typ, &keyspace_name, udts))
+                        .collect::<Result<_, _>>()?,
+                    return_type: function_cql_type(&return_type, &keyspace_name, udts)?,
+                    state_function: state_func,
+                    state_type: function_cql_type(&state_type, &keyspace_name, udts)?,
+                    final_function: final_func,
+                    initial_condition: initcond,
+                })
+            })();
+
+            let signature = FunctionSignature::new(aggregate_name, argument_types);
+            insert_function_result(&mut result, keyspace_name, signature, aggregate)
+        })
+        .try_for_each(|_| future::ok(()))
+        .await?;
+
+        Ok(result)
+    }
+
     async fn query_tables_schema(
         &self,
         keyspaces_to_fetch: &[String],
@@ -1089,7 +1612,7 @@ impl ControlConnection {
         .try_for_each(|_| future::ok(()))
         .await?;
 
-        let mut all_partitioners = self.query_table_partitioners().await?;
+        let mut all_scylla_tables = self.query_scylla_tables().await?;
         let mut result = HashMap::new();
 
         'tables_loop: for ((keyspace_name, table_name), table_result) in tables_schema {
@@ -1155,9 +1678,19 @@ impl ControlConnection {
                 }
             };
 
-            let partitioner = all_partitioners
+            let ScyllaTablesRow {
+                partitioner,
+                cdc,
+                tablets,
+                ..
+            } = all_scylla_tables
                 .remove(&keyspace_and_table_name)
                 .unwrap_or_default();
+            let extensions = TableExtensions {
+                cdc: cdc.map(cdc_options_from_map),
+                per_partition_rate_limit: None,
+                tablets: tablets.map(tablet_options_from_map),
+            };
 
             // unwrap of get() result: all column names in `partition_key` are at this
             // point guaranteed to be present in `columns`. See the construction of `partition_key`
@@ -1180,6 +1713,9 @@ impl ControlConnection {
                     partition_key,
                     clustering_key,
                     partitioner,
+                    options: TableOptions::default(),
+                    extensions,
+                    indexes: HashMap::new(),
                     pk_column_specs,
                 }),
             );
@@ -1343,10 +1879,192 @@ fn freeze_type(typ: PreColumnType) -> PreColumnType {
     }
 }
 
+/// A row of `system_schema.scylla_tables`.
+///
+/// Columns of this table differ between ScyllaDB versions, so it is queried
+/// with `SELECT *`, and the columns are matched by name. Unknown columns are ignored.
+#[derive(Debug, Default)]
+struct ScyllaTablesRow {
+    keyspace_name: String,
+    table_name: String,
+    partitioner: Option<String>,
+    cdc: Option<HashMap<String, String>>,
+    tablets: Option<HashMap<String, String>>,
+}
+
+impl ScyllaTablesRow {
+    const REQUIRED_COLUMNS: [&'static str; 2] = ["keyspace_name", "table_name"];
+
+    fn type_check_column(name: &str, typ: &ColumnType) -> Option<Result<(), TypeCheckError>> {
+        match name {
+            "keyspace_name" | "table_name" => Some(<String as DeserializeValue>::type_check(typ)),
+            "partitioner" => Some(<Option<String> as DeserializeValue>::type_check(typ)),
+            "cdc" | "tablets" => {
+                Some(<Option<HashMap<String, String>> as DeserializeValue>::type_check(typ))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<'frame, 'metadata> DeserializeRow<'frame, 'metadata> for ScyllaTablesRow {
+    fn type_check(specs: &[ColumnSpec]) -> Result<(), TypeCheckError> {
+        let mk_err = |kind| {
+            TypeCheckError::new(BuiltinTypeCheckError {
+                rust_name: std::any::type_name::<Self>(),
+                cql_types: specs
+                    .iter()
+                    .map(|spec| spec.typ().clone().into_owned())
+                    .collect(),
+                kind,
+            })
+        };
+
+        for (column_index, spec) in specs.iter().enumerate() {
+            if let Some(Err(err)) = Self::type_check_column(spec.name(), spec.typ()) {
+                return Err(mk_err(BuiltinTypeCheckErrorKind::ColumnTypeCheckFailed {
+                    column_index,
+                    column_name: spec.name().to_owned(),
+                    err,
+                }));
+            }
+        }
+
+        let missing_columns: Vec<&'static str> = Self::REQUIRED_COLUMNS
+            .into_iter()
+            .filter(|name| specs.iter().all(|spec| spec.name() != *name))
+            .collect();
+        if !missing_columns.is_empty() {
+            return Err(mk_err(BuiltinTypeCheckErrorKind::ValuesMissingForColumns {
+                column_names: missing_columns,
+            }));
+        }
+
+        Ok(())
+    }
+
+    fn deserialize(row: ColumnIterator<'frame, 'metadata>) -> Result<Self, DeserializationError> {
+        let mut result = Self::default();
+        for column in row {
+            let column = column?;
+            let typ = column.spec.typ();
+            match column.spec.name() {
+                "keyspace_name" => result.keyspace_name = String::deserialize(typ, column.slice)?,
+                "table_name" => result.table_name = String::deserialize(typ, column.slice)?,
+                "partitioner" => result.partitioner = Option::deserialize(typ, column.slice)?,
+                "cdc" => result.cdc = Option::deserialize(typ, column.slice)?,
+                "tablets" => result.tablets = Option::deserialize(typ, column.slice)?,
+                _ => (),
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// Fills the table extensions that are not known yet with the ones found
+/// in the `extensions` column of `system_schema.tables`.
+fn apply_schema_extensions(
+    extensions: &mut TableExtensions,
+    schema_extensions: HashMap<String, Vec<u8>>,
+) {
+    for (name, blob) in schema_extensions {
+        let decode = || {
+            let options = decode_extension_options(&blob);
+            if options.is_none() {
+                warn!(
+                    target: log_targets::METADATA,
+                    "Failed to decode options of table extension {}, ignoring it",
+                    name
+                );
+            }
+            options
+        };
+        match name.as_str() {
+            "cdc" if extensions.cdc.is_none() => {
+                extensions.cdc = decode().map(cdc_options_from_map);
+            }
+            "per_partition_rate_limit" if extensions.per_partition_rate_limit.is_none() => {
+                extensions.per_partition_rate_limit =
+                    decode().map(per_partition_rate_limit_from_map);
+            }
+            "tablets" if extensions.tablets.is_none() => {
+                extensions.tablets = decode().map(tablet_options_from_map);
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Decodes a `map<text, text>` of options of a schema extension.
+///
+/// ScyllaDB serializes extension options with its internal serialization format,
+/// which uses little-endian 32-bit lengths. The standard CQL serialization
+/// (big-endian 32-bit lengths) is accepted as well.
+fn decode_extension_options(blob: &[u8]) -> Option<HashMap<String, String>> {
+    type ReadLen = fn([u8; 4]) -> u32;
+
+    fn take<'a>(blob: &mut &'a [u8], len: usize) -> Option<&'a [u8]> {
+        let (taken, rest) = blob.split_at_checked(len)?;
+        *blob = rest;
+        Some(taken)
+    }
+
+    fn read_len(blob: &mut &[u8], read_len: ReadLen) -> Option<usize> {
+        usize::try_from(read_len(take(blob, 4)?.try_into().ok()?)).ok()
+    }
+
+    fn read_string(blob: &mut &[u8], read_len_fn: ReadLen) -> Option<String> {
+        let len = read_len(blob, read_len_fn)?;
+        String::from_utf8(take(blob, len)?.to_vec()).ok()
+    }
+
+    fn decode(mut blob: &[u8], read_len_fn: ReadLen) -> Option<HashMap<String, String>> {
+        let count = read_len(&mut blob, read_len_fn)?;
+        let mut options = HashMap::new();
+        for _ in 0..count {
+            let key = read_string(&mut blob, read_len_fn)?;
+            let value = read_string(&mut blob, read_len_fn)?;
+            options.insert(key, value);
+        }
+        blob.is_empty().then_some(options)
+    }
+
+    decode(blob, u32::from_le_bytes).or_else(|| decode(blob, u32::from_be_bytes))
+}
+
+fn cdc_options_from_map(options: HashMap<String, String>) -> CdcOptions {
+    let is_true = |key: &str| {
+        options
+            .get(key)
+            .is_some_and(|value| value.eq_ignore_ascii_case("true"))
+    };
+    CdcOptions {
+        enabled: is_true("enabled"),
+        postimage: is_true("postimage"),
+        ttl: options.get("ttl").and_then(|ttl| ttl.parse().ok()),
+        options,
+    }
+}
+
+fn per_partition_rate_limit_from_map(options: HashMap<String, String>) -> PerPartitionRateLimit {
+    let parse = |key: &str| options.get(key).and_then(|value| value.parse().ok());
+    PerPartitionRateLimit {
+        max_reads_per_second: parse("max_reads_per_second"),
+        max_writes_per_second: parse("max_writes_per_second"),
+    }
+}
+
+fn tablet_options_from_map(options: HashMap<String, String>) -> TabletOptions {
+    TabletOptions {
+        min_tablet_count: options
+            .get("min_tablet_count")
+            .and_then(|value| value.parse().ok()),
+        options,
+    }
+}
+
 impl ControlConnection {
-    async fn query_table_partitioners(
-        &self,
-    ) -> Result<PerKsTable<Option<String>>, MetadataFetchError> {
+    async fn query_scylla_tables(&self) -> Result<PerKsTable<ScyllaTablesRow>, MetadataFetchError> {
         fn create_err(err: impl Into<MetadataFetchErrorKind>) -> MetadataFetchError {
             MetadataFetchError {
                 error: err.into(),
@@ -1355,14 +2073,11 @@ impl ControlConnection {
         }
 
         let rows = self
-            .query_iter(
-                "select keyspace_name, table_name, partitioner from system_schema.scylla_tables",
-                &(),
-            )
+            .query_iter("select * from system_schema.scylla_tables", &())
             .map(|pager_res| {
                 let pager = pager_res.map_err(create_err)?;
                 let stream = pager
-                    .rows_stream::<(String, String, Option<String>)>()
+                    .rows_stream::<ScyllaTablesRow>()
                     // Map the error of Result<TypedRowStream, TypecheckError>
                     .map_err(create_err)?
                     // Map the error of single stream iteration (NextRowError)
@@ -1374,8 +2089,11 @@ impl ControlConnection {
 
         let result = rows
             .map(|row_result| {
-                let (keyspace_name, table_name, partitioner) = row_result?;
-                Ok::<_, MetadataFetchError>(((keyspace_name, table_name), partitioner))
+                let row = row_result?;
+                Ok::<_, MetadataFetchError>((
+                    (row.keyspace_name.clone(), row.table_name.clone()),
+                    row,
+                ))
             })
             .try_collect::<HashMap<_, _>>()
             .await;
@@ -1455,6 +2173,234 @@ mod tests {
 
     use super::*;
 
+    fn encode_extension_options(
+        options: &[(&str, &str)],
+        write_len: fn(u32) -> [u8; 4],
+    ) -> Vec<u8> {
+        let mut blob = write_len(options.len() as u32).to_vec();
+        for (key, value) in options {
+            for s in [key, value] {
+                blob.extend_from_slice(&write_len(s.len() as u32));
+                blob.extend_from_slice(s.as_bytes());
+            }
+        }
+        blob
+    }
+
+    #[test]
+    fn test_function_cql_type() {
+        setup_tracing();
+        let udts = HashMap::new();
+
+        assert_eq!(
+            function_cql_type("frozen<list<int>>", "ks", &udts).ok(),
+            Some(ColumnType::Collection {
+                frozen: true,
+                typ: CollectionType::List(Box::new(ColumnType::Native(NativeType::Int))),
+            })
+        );
+        assert!(matches!(
+            function_cql_type("frozen<my_udt>", "ks", &udts),
+            Err(FunctionTypeError::MissingUdt(_))
+        ));
+        assert!(matches!(
+            function_cql_type("list<int", "ks", &udts),
+            Err(FunctionTypeError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn test_table_options() {
+        setup_tracing();
+        let map = |entries: &[(&str, &str)]| {
+            Some(
+                entries
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect::<HashMap<_, _>>(),
+            )
+        };
+
+        let options = table_options(
+            map(&[
+                (
+                    "class",
+                    "org.apache.cassandra.db.compaction.LeveledCompactionStrategy",
+                ),
+                ("sstable_size_in_mb", "160"),
+            ]),
+            map(&[
+                ("sstable_compression", "LZ4Compressor"),
+                ("chunk_length_kb", "16"),
+            ]),
+            map(&[("keys", "ALL"), ("rows_per_partition", "NONE")]),
+            Some(3600),
+            Some(864000),
+            Some("comment".to_owned()),
+        );
+        assert_eq!(
+            options.compaction.strategy,
+            Some(CompactionStrategy::Leveled)
+        );
+        assert_eq!(options.compaction.options["sstable_size_in_mb"], "160");
+        assert_eq!(
+            options.compression.compressor.as_deref(),
+            Some("LZ4Compressor")
+        );
+        assert_eq!(options.compression.chunk_length_in_kb, Some(16));
+        assert_eq!(options.caching.keys.as_deref(), Some("ALL"));
+        assert_eq!(options.caching.rows_per_partition.as_deref(), Some("NONE"));
+        assert_eq!(options.default_time_to_live, Some(3600));
+        assert_eq!(options.gc_grace_seconds, Some(864000));
+        assert_eq!(options.comment.as_deref(), Some("comment"));
+
+        // Disabled compression and unknown compaction strategies.
+        let options = table_options(
+            map(&[("class", "com.example.CustomStrategy")]),
+            map(&[("sstable_compression", "")]),
+            None,
+            None,
+            None,
+            None,
+        );
+        assert_eq!(
+            options.compaction.strategy,
+            Some(CompactionStrategy::Other(
+                "com.example.CustomStrategy".to_owned()
+            ))
+        );
+        assert_eq!(options.compression.compressor, None);
+        assert_eq!(options.caching, CachingOptions::default());
+    }
+
+    #[test]
+    fn test_decode_extension_options() {
+        setup_tracing();
+        let options = [("enabled", "true"), ("ttl", "3600")];
+        let expected: HashMap<String, String> = options
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+
+        for write_len in [u32::to_le_bytes, u32::to_be_bytes] {
+            let blob = encode_extension_options(&options, write_len);
+            assert_eq!(decode_extension_options(&blob), Some(expected.clone()));
+            // Truncated and trailing data are rejected.
+            assert_eq!(decode_extension_options(&blob[..blob.len() - 1]), None);
+            let mut with_trailing_data = blob.clone();
+            with_trailing_data.push(0);
+            assert_eq!(decode_extension_options(&with_trailing_data), None);
+        }
+
+        assert_eq!(decode_extension_options(&[]), None);
+    }
+
+    #[test]
+    fn test_apply_schema_extensions() {
+        setup_tracing();
+        let schema_extensions: HashMap<String, Vec<u8>> = [
+            (
+                "cdc",
+                encode_extension_options(
+                    &[
+                        ("enabled", "true"),
+                        ("preimage", "full"),
+                        ("postimage", "false"),
+                        ("ttl", "86400"),
+                    ],
+                    u32::to_le_bytes,
+                ),
+            ),
+            (
+                "per_partition_rate_limit",
+                encode_extension_options(&[("max_writes_per_second", "100")], u32::to_le_bytes),
+            ),
+            ("tablets", b"garbage".to_vec()),
+            ("unknown", b"ignored".to_vec()),
+        ]
+        .into_iter()
+        .map(|(name, blob)| (name.to_owned(), blob))
+        .collect();
+
+        let mut extensions = TableExtensions::default();
+        apply_schema_extensions(&mut extensions, schema_extensions.clone());
+
+        let cdc = extensions.cdc.as_ref().unwrap();
+        assert!(cdc.enabled);
+        assert!(!cdc.postimage);
+        assert_eq!(cdc.ttl, Some(86400));
+        assert_eq!(cdc.options["preimage"], "full");
+        assert_eq!(
+            extensions.per_partition_rate_limit,
+            Some(PerPartitionRateLimit {
+                max_reads_per_second: None,
+                max_writes_per_second: Some(100),
+            })
+        );
+        // Undecodable extensions are ignored.
+        assert_eq!(extensions.tablets, None);
+
+        // Options already read from `system_schema.scylla_tables` take precedence.
+        let scylla_tables_cdc =
+            cdc_options_from_map([("enabled".to_owned(), "false".to_owned())].into());
+        let mut extensions = TableExtensions {
+            cdc: Some(scylla_tables_cdc.clone()),
+            ..Default::default()
+        };
+        apply_schema_extensions(&mut extensions, schema_extensions);
+        assert_eq!(extensions.cdc, Some(scylla_tables_cdc));
+    }
+
+    #[test]
+    fn test_scylla_tables_row_type_check() {
+        setup_tracing();
+        let table_spec = TableSpec::borrowed("system_schema", "scylla_tables");
+        let spec = |name: &'static str, typ: ColumnType<'static>| {
+            ColumnSpec::borrowed(name, typ, table_spec.clone())
+        };
+        let text = || ColumnType::Native(NativeType::Text);
+        let text_map = || ColumnType::Collection {
+            frozen: false,
+            typ: CollectionType::Map(Box::new(text()), Box::new(text())),
+        };
+
+        // Unknown columns are accepted.
+        let specs = [
+            spec("keyspace_name", text()),
+            spec("table_name", text()),
+            spec("cdc", text_map()),
+            spec("partitioner", text()),
+            spec("version", ColumnType::Native(NativeType::Uuid)),
+        ];
+        ScyllaTablesRow::type_check(&specs).unwrap();
+
+        // Known columns must have the expected types.
+        let specs = [
+            spec("keyspace_name", text()),
+            spec("table_name", text()),
+            spec("partitioner", ColumnType::Native(NativeType::Int)),
+        ];
+        let err = ScyllaTablesRow::type_check(&specs).unwrap_err();
+        let err = err.downcast_ref::<BuiltinTypeCheckError>().unwrap();
+        assert!(matches!(
+            err.kind,
+            BuiltinTypeCheckErrorKind::ColumnTypeCheckFailed {
+                column_index: 2,
+                ..
+            }
+        ));
+
+        // Key columns are required.
+        let specs = [spec("keyspace_name", text())];
+        let err = ScyllaTablesRow::type_check(&specs).unwrap_err();
+        let err = err.downcast_ref::<BuiltinTypeCheckError>().unwrap();
+        assert!(matches!(
+            &err.kind,
+            BuiltinTypeCheckErrorKind::ValuesMissingForColumns { column_names }
+                if column_names == &["table_name"]
+        ));
+    }
+
     #[test]
     fn test_cql_type_parsing() {
         setup_tracing();
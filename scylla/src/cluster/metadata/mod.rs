@@ -70,6 +70,10 @@ pub struct Peer {
     pub datacenter: Option<String>,
     /// Rack this node is in, if known.
     pub rack: Option<String>,
+    /// Version advertised by the node in the `release_version` column of `system.peers`
+    /// or `system.local`, if known. ScyllaDB nodes advertise the version of Cassandra
+    /// they are compatible with.
+    pub release_version: Option<String>,
 }
 
 /// An endpoint for a node that the driver is to issue connections to,
@@ -152,6 +156,28 @@ pub struct Keyspace {
     ///
     /// Empty HashMap may as well mean that the client disabled schema fetching in SessionConfig.
     pub user_defined_types: HashMap<String, Arc<UserDefinedType<'static>>>,
+    /// User defined functions in the keyspace.
+    ///
+    /// Empty HashMap may as well mean that the client disabled schema fetching in SessionConfig.
+    pub functions: HashMap<FunctionSignature, UserDefinedFunction>,
+    /// User defined aggregates in the keyspace.
+    ///
+    /// Empty HashMap may as well mean that the client disabled schema fetching in SessionConfig.
+    pub aggregates: HashMap<FunctionSignature, UserDefinedAggregate>,
+}
+
+impl Keyspace {
+    /// Returns materialized views whose base table is `table_name`, keyed by view name.
+    ///
+    /// On ScyllaDB, this includes the views backing secondary indexes of the table.
+    pub fn views_of<'a>(
+        &'a self,
+        table_name: &'a str,
+    ) -> impl Iterator<Item = (&'a String, &'a MaterializedView)> + 'a {
+        self.views
+            .iter()
+            .filter(move |(_, view)| view.base_table_name == table_name)
+    }
 }
 
 /// Describes a table in the cluster.
@@ -168,10 +194,155 @@ pub struct Table {
     pub clustering_key: Vec<String>,
     /// Name of the partitioner used by the table.
     pub partitioner: Option<String>,
+    /// Options of the table (compaction, compression, TTL, caching, ...).
+    pub options: TableOptions,
+    /// ScyllaDB-specific options of the table (CDC, per-partition rate limit, tablets).
+    pub extensions: TableExtensions,
+    /// Secondary indexes of the table, keyed by index name.
+    pub indexes: HashMap<String, Index>,
     /// Column specs for the partition key columns.
     pub(crate) pk_column_specs: Vec<ColumnSpec<'static>>,
 }
 
+/// Options of a table or a materialized view, as set with `WITH` clauses
+/// of `CREATE TABLE` and `ALTER TABLE` statements.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TableOptions {
+    /// Compaction options.
+    pub compaction: CompactionOptions,
+    /// Compression options.
+    pub compression: CompressionOptions,
+    /// Caching options.
+    pub caching: CachingOptions,
+    /// Default time to live of written data, in seconds. Zero means no expiration.
+    pub default_time_to_live: Option<u32>,
+    /// Time to wait before garbage collecting tombstones, in seconds.
+    pub gc_grace_seconds: Option<u32>,
+    /// Comment of the table.
+    pub comment: Option<String>,
+}
+
+/// Compaction options of a table.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CompactionOptions {
+    /// Compaction strategy, if known.
+    pub strategy: Option<CompactionStrategy>,
+    /// All compaction options, as stored in the schema (including `class`).
+    pub options: HashMap<String, String>,
+}
+
+/// Compaction strategy of a table.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CompactionStrategy {
+    /// `SizeTieredCompactionStrategy`.
+    SizeTiered,
+    /// `LeveledCompactionStrategy`.
+    Leveled,
+    /// `TimeWindowCompactionStrategy`.
+    TimeWindow,
+    /// `IncrementalCompactionStrategy` (ScyllaDB only).
+    Incremental,
+    /// `UnifiedCompactionStrategy` (Cassandra only).
+    Unified,
+    /// Strategy unknown to the driver, with its class name as stored in the schema.
+    Other(String),
+}
+
+impl CompactionStrategy {
+    /// Parses a compaction strategy class name, either fully qualified
+    /// (e.g. `org.apache.cassandra.db.compaction.LeveledCompactionStrategy`) or not.
+    pub(crate) fn from_class(class: &str) -> Self {
+        match class.rsplit('.').next().unwrap_or(class) {
+            "SizeTieredCompactionStrategy" => Self::SizeTiered,
+            "LeveledCompactionStrategy" => Self::Leveled,
+            "TimeWindowCompactionStrategy" => Self::TimeWindow,
+            "IncrementalCompactionStrategy" => Self::Incremental,
+            "UnifiedCompactionStrategy" => Self::Unified,
+            _ => Self::Other(class.to_owned()),
+        }
+    }
+}
+
+/// Compression options of a table.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CompressionOptions {
+    /// Class of the compressor (e.g. `LZ4Compressor`), or `None` if compression is disabled.
+    pub compressor: Option<String>,
+    /// Size of compressed chunks, in kilobytes.
+    pub chunk_length_in_kb: Option<u32>,
+    /// All compression options, as stored in the schema.
+    pub options: HashMap<String, String>,
+}
+
+/// Caching options of a table.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CachingOptions {
+    /// Which partition keys are cached (`ALL` or `NONE`).
+    pub keys: Option<String>,
+    /// How many rows per partition are cached (`ALL`, `NONE` or a number).
+    pub rows_per_partition: Option<String>,
+    /// All caching options, as stored in the schema.
+    pub options: HashMap<String, String>,
+}
+
+/// ScyllaDB-specific table options, kept by ScyllaDB as schema extensions
+/// of the table (in `system_schema.tables` and `system_schema.scylla_tables`).
+///
+/// All fields are `None` for tables of clusters that don't support
+/// the respective features (e.g. Cassandra clusters).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TableExtensions {
+    /// Change Data Capture options of the table.
+    ///
+    /// Note that the options may be present even if CDC is currently disabled,
+    /// see [CdcOptions::enabled].
+    pub cdc: Option<CdcOptions>,
+    /// Per-partition rate limit of the table.
+    pub per_partition_rate_limit: Option<PerPartitionRateLimit>,
+    /// Tablets options of the table.
+    pub tablets: Option<TabletOptions>,
+}
+
+/// Change Data Capture options of a table.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CdcOptions {
+    /// Whether CDC log is written for the table.
+    pub enabled: bool,
+    /// Whether the CDC log contains postimages of modified rows.
+    pub postimage: bool,
+    /// Time to live of CDC log entries, in seconds.
+    pub ttl: Option<u32>,
+    /// All CDC options, as stored in the schema (e.g. `preimage`, `delta`).
+    pub options: HashMap<String, String>,
+}
+
+/// Per-partition rate limit of a table.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct PerPartitionRateLimit {
+    /// Maximum number of reads per second per partition.
+    pub max_reads_per_second: Option<u32>,
+    /// Maximum number of writes per second per partition.
+    pub max_writes_per_second: Option<u32>,
+}
+
+/// Tablets options of a table.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TabletOptions {
+    /// Minimal number of tablets the table is split into.
+    pub min_tablet_count: Option<u64>,
+    /// All tablets options, as stored in the schema.
+    pub options: HashMap<String, String>,
+}
+
 /// Describes a materialized view in the cluster.
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[non_exhaustive]
@@ -183,6 +354,108 @@ pub struct MaterializedView {
     pub base_table_name: String,
 }
 
+/// Describes a secondary index of a table.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Index {
+    /// Kind of the index.
+    pub kind: IndexKind,
+    /// Options of the index, as stored in the schema.
+    /// For built-in indexes, the `target` option names the indexed column.
+    pub options: HashMap<String, String>,
+}
+
+impl Index {
+    /// Returns the indexed target (usually a column name), if present in the options.
+    pub fn target(&self) -> Option<&str> {
+        self.options.get("target").map(String::as_str)
+    }
+}
+
+/// Kind of a secondary index.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum IndexKind {
+    /// Index on a regular or clustering column (`COMPOSITES` in the schema).
+    Composites,
+    /// Index on keys of a map column (`KEYS` in the schema).
+    Keys,
+    /// Custom index, e.g. a SASI index (`CUSTOM` in the schema).
+    Custom,
+    /// Kind unknown to the driver.
+    Other(String),
+}
+
+impl From<String> for IndexKind {
+    fn from(kind: String) -> Self {
+        match kind.as_str() {
+            "COMPOSITES" => Self::Composites,
+            "KEYS" => Self::Keys,
+            "CUSTOM" => Self::Custom,
+            _ => Self::Other(kind),
+        }
+    }
+}
+
+/// Identifies a user defined function or aggregate within a keyspace.
+///
+/// Functions and aggregates may be overloaded, so the name alone is not enough.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub struct FunctionSignature {
+    /// Name of the function or aggregate.
+    pub name: String,
+    /// CQL types of the arguments, as stored in the schema (e.g. `frozen<list<int>>`).
+    pub argument_types: Vec<String>,
+}
+
+impl FunctionSignature {
+    /// Creates a new signature, e.g. for looking up [Keyspace::functions].
+    pub fn new(name: impl Into<String>, argument_types: Vec<String>) -> Self {
+        Self {
+            name: name.into(),
+            argument_types,
+        }
+    }
+}
+
+/// Describes a user defined function.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct UserDefinedFunction {
+    /// Names of the arguments.
+    pub argument_names: Vec<String>,
+    /// Types of the arguments.
+    pub argument_types: Vec<ColumnType<'static>>,
+    /// Type of the returned value.
+    pub return_type: ColumnType<'static>,
+    /// Language the function is written in (e.g. `lua`, `java`).
+    pub language: String,
+    /// Source code of the function.
+    pub body: String,
+    /// Whether the function is called when any argument is null (`CALLED ON NULL INPUT`),
+    /// as opposed to returning null right away (`RETURNS NULL ON NULL INPUT`).
+    pub called_on_null_input: bool,
+}
+
+/// Describes a user defined aggregate.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct UserDefinedAggregate {
+    /// Types of the arguments.
+    pub argument_types: Vec<ColumnType<'static>>,
+    /// Type of the returned value.
+    pub return_type: ColumnType<'static>,
+    /// Name of the state function (`SFUNC`).
+    pub state_function: String,
+    /// Type of the state (`STYPE`).
+    pub state_type: ColumnType<'static>,
+    /// Name of the final function (`FINALFUNC`), if any.
+    pub final_function: Option<String>,
+    /// Initial state (`INITCOND`), as a CQL literal, if any.
+    pub initial_condition: Option<String>,
+}
+
 /// Describes a column of the table.
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[non_exhaustive]
@@ -296,6 +569,7 @@ impl Metadata {
                     datacenter: None,
                     rack: None,
                     host_id: Uuid::new_v4(),
+                    release_version: None,
                 }
             })
             .collect();
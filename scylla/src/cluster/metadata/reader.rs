@@ -11,31 +11,46 @@
 //! - Automatic reconnection to other known peers on connection failure
 //! - Fallback to initial contact points when all known peers are unreachable
 //! - Host filtering to ensure the control connection is established to an accepted node
+//! - Standby control connections, kept open to other nodes and promoted immediately
+//!   when the current control connection fails
 //!
 
 use std::sync::Arc;
 use std::time::Duration;
 
+use crate::observability::log_targets;
 use rand::rng;
 use rand::seq::{IndexedRandom, SliceRandom};
 use tokio::sync::{mpsc, oneshot};
 use tracing::{debug, error, warn};
 
-use crate::cluster::KnownNode;
 use crate::cluster::control_connection::{ControlConnection, ControlConnectionCache};
-use crate::cluster::metadata::{Metadata, PeerEndpoint, UntranslatedEndpoint};
+use crate::cluster::metadata::{
+    Keyspace, Metadata, PeerEndpoint, SingleKeyspaceMetadataError, UntranslatedEndpoint,
+};
 use crate::cluster::node::resolve_contact_points;
+use crate::cluster::{KnownNode, TopologyEvent};
 use crate::errors::{ConnectionError, ConnectionPoolError, MetadataError, NewSessionError};
 use crate::frame::response::event::Event;
 use crate::network::{ConnectionConfig, open_connection};
+use crate::observability::diagnostics::ControlConnectionDiagnostics;
+use crate::policies::address_translator::PeerAddressPreference;
 use crate::policies::host_filter::HostFilter;
+use crate::policies::keyspace_filter::KeyspaceFilter;
+use crate::policies::resolver::Resolver;
 use crate::utils::safe_format::IteratorSafeFormatExt;
 
 pub(crate) enum ControlConnectionEvent {
     Broken,
+    StandbyBroken,
     ServerEvent(Event),
 }
 
+enum ControlConnectionFailure {
+    Current(ConnectionError),
+    Standby(usize),
+}
+
 struct WorkingControlConnection {
     connection: ControlConnection,
     endpoint: UntranslatedEndpoint,
@@ -68,17 +83,30 @@ pub(crate) struct MetadataReader {
     control_connection_config: ConnectionConfig,
     request_serverside_timeout: Option<Duration>,
     hostname_resolution_timeout: Option<Duration>,
+    // Contact points resolved from hostnames are resolved again before connecting to them
+    // if they were resolved longer ago than this. If `None`, they are never resolved again.
+    hostname_resolution_ttl: Option<Duration>,
+    resolver: Arc<dyn Resolver>,
+    peer_address_preference: PeerAddressPreference,
     keyspaces_to_fetch: Vec<String>,
+    keyspace_filter: Option<Arc<dyn KeyspaceFilter>>,
     fetch_schema: bool,
     host_filter: Option<Arc<dyn HostFilter>>,
     // When no known peer is reachable, initial known nodes are resolved once again as a fallback
     // and establishing control connection to them is attempted.
     initial_known_nodes: Vec<KnownNode>,
 
+    // Number of standby control connections kept open to other nodes.
+    standby_connections_count: usize,
+
     // ====================================================================
     // Mutable state of MetadataReader. It will change during its lifetime.
     // ====================================================================
     control_connection_state: ControlConnectionState,
+    // Established connections to other nodes, ready to replace the control connection.
+    standby_connections: Vec<WorkingControlConnection>,
+    // Changes of the control connection which were not yet reported to topology event subscribers.
+    pending_events: Vec<TopologyEvent>,
     // when control connection fails, MetadataReader tries to connect to one of known_peers
     known_peers: Vec<UntranslatedEndpoint>,
     cc_cache: Arc<ControlConnectionCache>,
@@ -86,17 +114,27 @@ pub(crate) struct MetadataReader {
 
 impl MetadataReader {
     /// Creates new MetadataReader, which connects to initially_known_peers in the background
+    #[expect(clippy::too_many_arguments)]
     pub(crate) async fn new(
         initial_known_nodes: Vec<KnownNode>,
         hostname_resolution_timeout: Option<Duration>,
+        hostname_resolution_ttl: Option<Duration>,
+        resolver: Arc<dyn Resolver>,
+        peer_address_preference: PeerAddressPreference,
         connection_config: ConnectionConfig,
         request_serverside_timeout: Option<Duration>,
         keyspaces_to_fetch: Vec<String>,
+        keyspace_filter: Option<Arc<dyn KeyspaceFilter>>,
         fetch_schema: bool,
         host_filter: &Option<Arc<dyn HostFilter>>,
+        standby_connections_count: usize,
     ) -> Result<Self, NewSessionError> {
-        let (initial_peers, resolved_hostnames) =
-            resolve_contact_points(&initial_known_nodes, hostname_resolution_timeout).await;
+        let (initial_peers, resolved_hostnames) = resolve_contact_points(
+            &initial_known_nodes,
+            &*resolver,
+            hostname_resolution_timeout,
+        )
+        .await;
         // Ensure there is at least one resolved node
         if initial_peers.is_empty() {
             return Err(NewSessionError::FailedToResolveAnyHostname(
@@ -117,6 +155,7 @@ impl MetadataReader {
             control_connection_endpoint,
             connection_config.clone(),
             request_serverside_timeout,
+            keyspace_filter.clone(),
             Arc::clone(&cc_cache),
         )
         .await;
@@ -126,57 +165,179 @@ impl MetadataReader {
             control_connection_state,
             request_serverside_timeout,
             hostname_resolution_timeout,
+            hostname_resolution_ttl,
+            resolver,
+            peer_address_preference,
             known_peers: initial_peers
                 .into_iter()
                 .map(UntranslatedEndpoint::ContactPoint)
                 .collect(),
             keyspaces_to_fetch,
+            keyspace_filter,
             fetch_schema,
             host_filter: host_filter.clone(),
             initial_known_nodes,
+            standby_connections_count,
+            standby_connections: Vec::new(),
+            pending_events: Vec::new(),
             cc_cache,
         })
     }
 
     pub(crate) async fn wait_for_control_connection_event(&mut self) -> ControlConnectionEvent {
-        match &mut self.control_connection_state {
-            ControlConnectionState::Broken { .. } => std::future::pending().await,
-            ControlConnectionState::Working(working_connection) => {
-                tokio::select! {
-                    // Why only `Some`? `None` means that event channel was dropped.
-                    // In current implementation (as of writing this comment)
-                    // this should not be possible: events sender is stored in HostConnectionConfig,
-                    // which is a field of Connection that we own. If we got `None`, then most likely
-                    // two things happened:
-                    //  - The implementation changed, for example by moving event sender to router.
-                    //  - Connection was closed, router shutdown.
-                    //  - `tokio::select!` chose this branch instead of error channel.
-                    // The best thing we can imo do is ignore this `None`. `error_channel` should receive
-                    // info about connection shutdown very soon.
-                    Some(cql_event) = working_connection.events_channel.recv() => {
-                        ControlConnectionEvent::ServerEvent(cql_event)
+        let ControlConnectionState::Working(working_connection) =
+            &mut self.control_connection_state
+        else {
+            return std::future::pending().await;
+        };
+
+        let failure = tokio::select! {
+            // Why only `Some`? `None` means that event channel was dropped.
+            // In current implementation (as of writing this comment)
+            // this should not be possible: events sender is stored in HostConnectionConfig,
+            // which is a field of Connection that we own. If we got `None`, then most likely
+            // two things happened:
+            //  - The implementation changed, for example by moving event sender to router.
+            //  - Connection was closed, router shutdown.
+            //  - `tokio::select!` chose this branch instead of error channel.
+            // The best thing we can imo do is ignore this `None`. `error_channel` should receive
+            // info about connection shutdown very soon.
+            Some(cql_event) = working_connection.events_channel.recv() => {
+                return ControlConnectionEvent::ServerEvent(cql_event);
+            },
+            maybe_control_connection_failed = &mut working_connection.error_channel => {
+                match maybe_control_connection_failed {
+                    Ok(err) => ControlConnectionFailure::Current(err),
+                    Err(_recv_error) => {
+                        // If we got here then error channel, in a Connection that we own,
+                        // was dropped without sending anything. This is definitely a bug in the driver!
+                        // We could theoretically recover by dropping a connection and creating new one,
+                        // but we would need to add an error variant to `BrokenConnectionErrorKind` that
+                        // could basically never happen. Let's panic instead.
+                        panic!("Error sender of control connection unexpectedly dropped. This is a bug in the driver, please open an issue!");
                     },
-                    maybe_control_connection_failed = &mut working_connection.error_channel => {
-                        let err = match maybe_control_connection_failed {
-                            Ok(err) => err,
-                            Err(_recv_error) => {
-                                // If we got here then error channel, in a Connection that we own,
-                                // was dropped without sending anything. This is definitely a bug in the driver!
-                                // We could theoretically recover by dropping a connection and creating new one,
-                                // but we would need to add an error variant to `BrokenConnectionErrorKind` that
-                                // could basically never happen. Let's panic instead.
-                                panic!("Error sender of control connection unexpectedly dropped. This is a bug in the driver, please open an issue!");
-                            },
-                        };
-                        self.control_connection_state = ControlConnectionState::Broken {
-                            last_error: MetadataError::ConnectionPoolError(ConnectionPoolError::Broken { last_connection_error: err }),
-                            last_endpoint: working_connection.endpoint.clone()
-                        };
-                        ControlConnectionEvent::Broken
-                    }
                 }
             }
+            standby_idx = Self::wait_for_standby_failure(&mut self.standby_connections) => {
+                ControlConnectionFailure::Standby(standby_idx)
+            }
+        };
+
+        match failure {
+            ControlConnectionFailure::Current(err) => {
+                let last_endpoint = self.control_connection_state.endpoint().clone();
+                self.set_control_connection_state(ControlConnectionState::Broken {
+                    last_error: MetadataError::ConnectionPoolError(ConnectionPoolError::Broken {
+                        last_connection_error: err,
+                    }),
+                    last_endpoint,
+                });
+                if let Some(standby) = self.standby_connections.pop() {
+                    debug!(
+                        target: log_targets::METADATA,
+                        "Promoting the standby control connection to {}",
+                        standby.endpoint.address()
+                    );
+                    self.set_control_connection_state(ControlConnectionState::Working(standby));
+                }
+                ControlConnectionEvent::Broken
+            }
+            ControlConnectionFailure::Standby(idx) => {
+                let standby = self.standby_connections.swap_remove(idx);
+                debug!(
+                    target: log_targets::METADATA,
+                    "Standby control connection to {} broke",
+                    standby.endpoint.address()
+                );
+                ControlConnectionEvent::StandbyBroken
+            }
+        }
+    }
+
+    /// Discards server events received on standby connections - they are received
+    /// on the current control connection as well - and resolves to the index
+    /// of the first standby connection that breaks.
+    async fn wait_for_standby_failure(
+        standby_connections: &mut [WorkingControlConnection],
+    ) -> usize {
+        if standby_connections.is_empty() {
+            return std::future::pending().await;
         }
+        let failures = standby_connections
+            .iter_mut()
+            .enumerate()
+            .map(|(idx, standby)| {
+                Box::pin(async move {
+                    loop {
+                        tokio::select! {
+                            Some(_cql_event) = standby.events_channel.recv() => {},
+                            _ = &mut standby.error_channel => return idx,
+                        }
+                    }
+                })
+            });
+        futures::future::select_all(failures).await.0
+    }
+
+    /// Replaces the control connection, recording the change
+    /// to be reported to topology event subscribers.
+    fn set_control_connection_state(&mut self, state: ControlConnectionState) {
+        let old_state = std::mem::replace(&mut self.control_connection_state, state);
+        let old_address = old_state.endpoint().address().into_inner();
+        match (&old_state, &self.control_connection_state) {
+            (
+                ControlConnectionState::Working(_),
+                ControlConnectionState::Broken { last_error, .. },
+            ) => self
+                .pending_events
+                .push(TopologyEvent::ControlConnectionLost {
+                    address: old_address,
+                    error: last_error.to_string(),
+                }),
+            (_, ControlConnectionState::Working(new_connection)) => {
+                self.pending_events
+                    .push(TopologyEvent::ControlConnectionMoved {
+                        old_address,
+                        new_address: new_connection.endpoint.address().into_inner(),
+                    })
+            }
+            (ControlConnectionState::Broken { .. }, ControlConnectionState::Broken { .. }) => (),
+        }
+    }
+
+    /// Returns the changes of the control connection since the previous call.
+    pub(crate) fn take_control_connection_events(&mut self) -> Vec<TopologyEvent> {
+        std::mem::take(&mut self.pending_events)
+    }
+
+    /// Describes the current state of the control connection.
+    pub(crate) fn control_connection_diagnostics(&self) -> ControlConnectionDiagnostics {
+        let (working, last_error) = match &self.control_connection_state {
+            ControlConnectionState::Working(_) => (true, None),
+            ControlConnectionState::Broken { last_error, .. } => {
+                (false, Some(last_error.to_string()))
+            }
+        };
+        ControlConnectionDiagnostics {
+            address: self
+                .control_connection_state
+                .endpoint()
+                .address()
+                .into_inner(),
+            working,
+            last_error,
+            standby_addresses: self
+                .standby_connections
+                .iter()
+                .map(|standby| standby.endpoint.address().into_inner())
+                .collect(),
+        }
+    }
+
+    /// Returns metadata consisting only of the known peers and dummy tokens,
+    /// to be used when no real metadata can be fetched.
+    pub(crate) fn dummy_metadata(&self) -> Metadata {
+        Metadata::new_dummy(&self.known_peers)
     }
 
     pub(crate) fn control_connection_works(&self) -> bool {
@@ -191,23 +352,38 @@ impl MetadataReader {
         let mut result = self.fetch_metadata(initial).await;
         let prev_err = match result {
             Ok(metadata) => {
-                debug!("Fetched new metadata");
+                debug!(target: log_targets::METADATA, "Fetched new metadata");
                 self.update_known_peers(&metadata);
                 if initial {
                     self.handle_unaccepted_host_in_control_connection(&metadata)
                         .await;
                 }
+                self.replenish_standby_connections().await;
                 return Ok(metadata);
             }
             Err(err) => err,
         };
 
         // At this point, we known that fetching metadata on current control connection failed.
-        // Therefore, we try to fetch metadata from other known peers, in order.
+        // Standby connections are already established, so they are tried first.
+        result = self
+            .retry_fetch_metadata_on_standbys(initial, prev_err)
+            .await;
+        let prev_err = match result {
+            Ok(metadata) => {
+                self.update_known_peers(&metadata);
+                self.replenish_standby_connections().await;
+                return Ok(metadata);
+            }
+            Err(err) => err,
+        };
+
+        // Then, we try to fetch metadata from other known peers, in order.
 
         // shuffle known_peers to iterate through them in random order later
         self.known_peers.shuffle(&mut rng());
         debug!(
+            target: log_targets::METADATA,
             "Known peers: {:?}",
             self.known_peers.iter().safe_format(", ")
         );
@@ -231,10 +407,12 @@ impl MetadataReader {
                 // If no known peer is reachable, try falling back to initial contact points, in hope that
                 // there are some hostnames there which will resolve to reachable new addresses.
                 warn!(
+                    target: log_targets::METADATA,
                     "Failed to establish control connection and fetch metadata on all known peers. Falling back to initial contact points."
                 );
                 let (initial_peers, _hostnames) = resolve_contact_points(
                     &self.initial_known_nodes,
+                    &*self.resolver,
                     self.hostname_resolution_timeout,
                 )
                 .await;
@@ -258,7 +436,8 @@ impl MetadataReader {
                 self.update_known_peers(metadata);
                 self.handle_unaccepted_host_in_control_connection(metadata)
                     .await;
-                debug!("Fetched new metadata");
+                self.replenish_standby_connections().await;
+                debug!(target: log_targets::METADATA, "Fetched new metadata");
             }
             Err(error) => {
                 let target = self
@@ -267,6 +446,7 @@ impl MetadataReader {
                     .address()
                     .into_inner();
                 error!(
+                    target: log_targets::METADATA,
                     error = %error,
                     target = %target,
                     "Could not fetch metadata"
@@ -277,6 +457,180 @@ impl MetadataReader {
         result
     }
 
+    /// Returns true if metadata of the given keyspace is fetched by this reader.
+    pub(crate) fn fetches_keyspace(&self, keyspace: &str) -> bool {
+        (self.keyspaces_to_fetch.is_empty()
+            || self.keyspaces_to_fetch.iter().any(|ks| ks == keyspace))
+            && self
+                .keyspace_filter
+                .as_ref()
+                .is_none_or(|filter| filter.accept(keyspace))
+    }
+
+    /// Fetches current schema metadata of a single keyspace, using the current control connection.
+    ///
+    /// Unlike [Self::read_metadata], this does not attempt to reconnect the control connection
+    /// if it is broken. Callers are expected to fall back to a full refresh upon an error.
+    /// Returns `None` if the keyspace does not exist.
+    pub(crate) async fn read_keyspace_metadata(
+        &mut self,
+        keyspace: &str,
+    ) -> Result<Option<Result<Keyspace, SingleKeyspaceMetadataError>>, MetadataError> {
+        let working_connection = match &self.control_connection_state {
+            ControlConnectionState::Working(working_connection) => working_connection,
+            ControlConnectionState::Broken { last_error: e, .. } => {
+                return Err(e.clone());
+            }
+        };
+
+        let res = working_connection
+            .connection
+            .query_keyspace_metadata(keyspace, self.fetch_schema)
+            .await;
+
+        // If metadata fetch failed, we consider the connection broken.
+        if let Err(err) = &res {
+            let last_endpoint = working_connection.endpoint.clone();
+            self.set_control_connection_state(ControlConnectionState::Broken {
+                last_error: err.clone(),
+                last_endpoint,
+            });
+        }
+
+        res
+    }
+
+    async fn retry_fetch_metadata_on_standbys(
+        &mut self,
+        initial: bool,
+        prev_err: MetadataError,
+    ) -> Result<Metadata, MetadataError> {
+        let mut result = Err(prev_err);
+        while let Err(err) = &result {
+            let Some(standby) = self.standby_connections.pop() else {
+                break;
+            };
+
+            warn!(
+                target: log_targets::METADATA,
+                control_connection_address = tracing::field::display(self
+                    .control_connection_state.endpoint()
+                    .address()),
+                error = %err,
+                "Failed to fetch metadata using current control connection, \
+                promoting the standby control connection to {}",
+                standby.endpoint.address()
+            );
+
+            self.set_control_connection_state(ControlConnectionState::Working(standby));
+            result = self.fetch_metadata(initial).await;
+        }
+        result
+    }
+
+    /// Opens standby control connections to random known peers,
+    /// until there are as many as configured.
+    async fn replenish_standby_connections(&mut self) {
+        // Standby connections to nodes which left the cluster or are rejected
+        // by the host filter are closed.
+        let known_peers = &self.known_peers;
+        self.standby_connections.retain(|standby| {
+            known_peers
+                .iter()
+                .any(|peer| peer.address() == standby.endpoint.address())
+        });
+
+        if self.standby_connections.len() >= self.standby_connections_count {
+            return;
+        }
+
+        let control_connection_address = self.control_connection_state.endpoint().address();
+        let mut candidates: Vec<UntranslatedEndpoint> = self
+            .known_peers
+            .iter()
+            .filter(|peer| {
+                peer.address() != control_connection_address
+                    && !self
+                        .standby_connections
+                        .iter()
+                        .any(|standby| standby.endpoint.address() == peer.address())
+            })
+            .cloned()
+            .collect();
+        candidates.shuffle(&mut rng());
+
+        for candidate in candidates {
+            if self.standby_connections.len() >= self.standby_connections_count {
+                break;
+            }
+            let candidate = self.resolve_again_if_stale(candidate).await;
+            let address = candidate.address();
+            match Self::make_control_connection(
+                candidate,
+                self.control_connection_config.clone(),
+                self.request_serverside_timeout,
+                self.keyspace_filter.clone(),
+                Arc::clone(&self.cc_cache),
+            )
+            .await
+            {
+                ControlConnectionState::Working(standby) => {
+                    debug!(
+                        target: log_targets::METADATA,
+                        "Opened a standby control connection to {}",
+                        address,
+                    );
+                    self.standby_connections.push(standby);
+                }
+                ControlConnectionState::Broken { last_error, .. } => {
+                    debug!(
+                        target: log_targets::METADATA,
+                        "Failed to open a standby control connection to {}: {}",
+                        address, last_error
+                    );
+                }
+            }
+        }
+    }
+
+    /// Resolves the hostname of a contact point again if its address may be stale,
+    /// e.g. because it points to a load balancer or a Kubernetes service.
+    /// Peers fetched in metadata are returned unchanged.
+    async fn resolve_again_if_stale(
+        &mut self,
+        endpoint: UntranslatedEndpoint,
+    ) -> UntranslatedEndpoint {
+        let (UntranslatedEndpoint::ContactPoint(contact_point), Some(ttl)) =
+            (&endpoint, self.hostname_resolution_ttl)
+        else {
+            return endpoint;
+        };
+        let Some(resolved) = contact_point
+            .resolve_again_if_stale(&*self.resolver, ttl, self.hostname_resolution_timeout)
+            .await
+        else {
+            return endpoint;
+        };
+        if resolved.address != contact_point.address {
+            debug!(
+                target: log_targets::METADATA,
+                "Contact point address changed from {} to {}",
+                contact_point.address, resolved.address
+            );
+        }
+
+        let resolved = UntranslatedEndpoint::ContactPoint(resolved);
+        // Remember the new address, so that the contact point is not resolved again
+        // until the TTL passes.
+        let old_address = endpoint.address();
+        if let Some(known_peer) = self.known_peers.iter_mut().find(|peer| {
+            matches!(peer, UntranslatedEndpoint::ContactPoint(_)) && peer.address() == old_address
+        }) {
+            *known_peer = resolved.clone();
+        }
+        resolved
+    }
+
     async fn retry_fetch_metadata_on_nodes(
         &mut self,
         initial: bool,
@@ -291,6 +645,7 @@ impl MetadataReader {
             };
 
             warn!(
+                target: log_targets::METADATA,
                 control_connection_address = tracing::field::display(self
                     .control_connection_state.endpoint()
                     .address()),
@@ -298,18 +653,22 @@ impl MetadataReader {
                 "Failed to fetch metadata using current control connection"
             );
 
+            let peer = self.resolve_again_if_stale(peer).await;
             debug!(
+                target: log_targets::METADATA,
                 "Retrying to establish the control connection on {}",
                 peer.address()
             );
 
-            self.control_connection_state = Self::make_control_connection(
+            let control_connection_state = Self::make_control_connection(
                 peer,
                 self.control_connection_config.clone(),
                 self.request_serverside_timeout,
+                self.keyspace_filter.clone(),
                 Arc::clone(&self.cc_cache),
             )
             .await;
+            self.set_control_connection_state(control_connection_state);
 
             result = self.fetch_metadata(initial).await;
         }
@@ -329,6 +688,7 @@ impl MetadataReader {
             .connection
             .query_metadata(
                 endpoint.address().port(),
+                &self.peer_address_preference,
                 &self.keyspaces_to_fetch,
                 self.fetch_schema,
             )
@@ -336,15 +696,16 @@ impl MetadataReader {
 
         // If metadata fetch failed, we consider the connection broken.
         if let Err(err) = &res {
-            self.control_connection_state = ControlConnectionState::Broken {
+            self.set_control_connection_state(ControlConnectionState::Broken {
                 last_error: err.clone(),
                 last_endpoint: endpoint,
-            }
+            });
         }
 
         if initial {
             if let Err(err) = res {
                 warn!(
+                    target: log_targets::METADATA,
                     error = ?err,
                     "Initial metadata read failed, proceeding with metadata \
                     consisting only of the initial peer list and dummy tokens. \
@@ -371,6 +732,7 @@ impl MetadataReader {
         // and print an error message about this fact
         if !metadata.peers.is_empty() && self.known_peers.is_empty() {
             error!(
+                target: log_targets::METADATA,
                 node_ips = tracing::field::display(
                     metadata
                         .peers
@@ -393,6 +755,7 @@ impl MetadataReader {
         if let Some(peer) = control_connection_peer {
             if !self.host_filter.as_ref().is_none_or(|f| f.accept(peer)) {
                 warn!(
+                    target: log_targets::METADATA,
                     filtered_node_ips = tracing::field::display(metadata
                         .peers
                         .iter()
@@ -416,13 +779,15 @@ impl MetadataReader {
                         .expect("known_peers is empty - should be impossible")
                         .clone();
 
-                    self.control_connection_state = Self::make_control_connection(
+                    let control_connection_state = Self::make_control_connection(
                         control_connection_endpoint,
                         self.control_connection_config.clone(),
                         self.request_serverside_timeout,
+                        self.keyspace_filter.clone(),
                         Arc::clone(&self.cc_cache),
                     )
                     .await;
+                    self.set_control_connection_state(control_connection_state);
                 }
             }
         }
@@ -432,6 +797,7 @@ impl MetadataReader {
         endpoint: UntranslatedEndpoint,
         mut config: ConnectionConfig,
         request_serverside_timeout: Option<Duration>,
+        keyspace_filter: Option<Arc<dyn KeyspaceFilter>>,
         cache: Arc<ControlConnectionCache>,
     ) -> ControlConnectionState {
         let (sender, receiver) = tokio::sync::mpsc::channel(32);
@@ -449,7 +815,8 @@ impl MetadataReader {
         match open_result {
             Ok((con, recv)) => ControlConnectionState::Working(WorkingControlConnection {
                 connection: ControlConnection::new(Arc::new(con), cache)
-                    .override_serverside_timeout(request_serverside_timeout),
+                    .override_serverside_timeout(request_serverside_timeout)
+                    .with_keyspace_filter(keyspace_filter),
                 error_channel: recv,
                 events_channel: receiver,
                 endpoint,
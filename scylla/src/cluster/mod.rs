@@ -22,7 +22,14 @@ mod state;
 pub use state::ClusterState;
 
 pub(crate) mod node;
-pub use node::{KnownNode, Node, NodeAddr, NodeRef};
+pub use crate::network::{ConnectionEvent, ConnectionEventKind};
+pub use node::{KnownNode, Node, NodeAddr, NodeRef, NodeStats};
+
+mod topology;
+pub use topology::{TopologyEvent, TopologyEventStream};
+
+mod diff;
+pub use diff::{ClusterStateDiff, SchemaChangeKind, SchemaObject, SchemaObjectChange};
 
 mod control_connection;
 
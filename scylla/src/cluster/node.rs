@@ -1,27 +1,31 @@
+use crate::observability::log_targets;
 use itertools::Itertools;
 use thiserror::Error;
-use tokio::net::{ToSocketAddrs, lookup_host};
 use tracing::warn;
 use uuid::Uuid;
 
-use crate::errors::{ConnectionPoolError, UseKeyspaceError};
+use crate::errors::{ConnectionError, ConnectionPoolError, ResolutionError, UseKeyspaceError};
 use crate::network::VerifiedKeyspaceName;
-use crate::network::{Connection, ConnectivityChangeEvent};
+use crate::network::{Connection, ConnectionEvent, ConnectionEventKind, ConnectivityChangeEvent};
 use crate::network::{NodeConnectionPool, PoolConfig};
 #[cfg(feature = "metrics")]
 use crate::observability::metrics::Metrics;
+use crate::policies::resolver::Resolver;
 /// Node represents a cluster node along with it's data and connections
-use crate::routing::{Shard, Sharder};
+use crate::routing::{Shard, ShardCount, Sharder};
+use crate::utils::runtime;
 
 use std::fmt::Display;
 use std::net::IpAddr;
+use std::num::NonZeroUsize;
 #[cfg(test)]
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::time::Duration;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, Instant, SystemTime};
 use std::{
     hash::{Hash, Hasher},
     net::SocketAddr,
-    sync::Arc,
+    sync::{Arc, Mutex},
 };
 
 use crate::cluster::metadata::{PeerEndpoint, UntranslatedEndpoint};
@@ -98,6 +102,14 @@ pub struct Node {
     /// If the node is filtered out by the host filter, this will be [None].
     pool: Option<NodeConnectionPool>,
 
+    /// Request counters and other runtime state exposed by [Node::stats].
+    /// Shared with the instance which replaces this one if the node changes its IP.
+    runtime: Arc<NodeRuntimeState>,
+
+    /// Once this many requests are in flight to the node, it is considered saturated,
+    /// and moved to the end of query plans.
+    max_in_flight_requests: Option<NonZeroUsize>,
+
     // In unit tests Node objects are mocked, and don't have real connection
     // pools. We want DefaultPolicy to use is_connected to filter out nodes,
     // but it would mean that all nodes would be filtered out in unit tests.
@@ -111,6 +123,48 @@ pub struct Node {
 /// A way that Nodes are often passed and accessed in the driver's code.
 pub type NodeRef<'a> = &'a Arc<Node>;
 
+#[derive(Debug, Default)]
+struct NodeRuntimeState {
+    in_flight_requests: AtomicUsize,
+    errors: AtomicU64,
+    release_version: Mutex<Option<String>>,
+}
+
+/// Runtime statistics of a node, returned by [Node::stats].
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct NodeStats {
+    /// Number of open connections to each shard of the node.
+    /// Empty if the node is disabled or has no open connections.
+    pub connections_per_shard: Vec<usize>,
+    /// Number of requests sent to the node which have not completed yet.
+    pub in_flight_requests: usize,
+    /// Total number of request attempts sent to the node which failed.
+    pub errors: u64,
+    /// Time of the last attempt to open a connection to the node.
+    pub last_connection_attempt: Option<SystemTime>,
+    /// Time and error of the last failed connection attempt or broken connection.
+    pub last_connection_error: Option<(SystemTime, ConnectionError)>,
+    /// Version advertised by the node in `system.peers` or `system.local`, if known.
+    /// ScyllaDB nodes advertise the version of Cassandra they are compatible with.
+    pub release_version: Option<String>,
+    /// Number of shards of the node, if it is a ScyllaDB node the driver is connected to.
+    pub shard_count: Option<ShardCount>,
+}
+
+/// Tracks a request sent to a node as in flight, until dropped.
+pub(crate) struct InFlightRequestGuard {
+    runtime: Arc<NodeRuntimeState>,
+}
+
+impl Drop for InFlightRequestGuard {
+    fn drop(&mut self) {
+        self.runtime
+            .in_flight_requests
+            .fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
 impl Node {
     /// Creates a new node which starts connecting in the background.
     pub(crate) fn new(
@@ -146,6 +200,8 @@ impl Node {
             datacenter,
             rack,
             pool,
+            runtime: Default::default(),
+            max_in_flight_requests: pool_config.max_in_flight_requests_per_node,
             #[cfg(test)]
             enabled_as_connected: AtomicBool::new(false),
         }
@@ -170,6 +226,8 @@ impl Node {
             rack: node.rack.clone(),
             host_id: node.host_id,
             pool: node.pool.clone(),
+            runtime: Arc::clone(&node.runtime),
+            max_in_flight_requests: node.max_in_flight_requests,
             #[cfg(test)]
             enabled_as_connected: AtomicBool::new(node.enabled_as_connected.load(Ordering::SeqCst)),
         }
@@ -192,7 +250,9 @@ impl Node {
         &self,
         shard: Shard,
     ) -> Result<Arc<Connection>, ConnectionPoolError> {
-        self.get_pool()?.connection_for_shard(shard)
+        let pool = self.get_pool()?;
+        pool.connect_lazily().await;
+        pool.connection_for_shard(shard)
     }
 
     /// Returns true if the driver has any open connections in the pool for this
@@ -208,6 +268,14 @@ impl Node {
         pool.is_connected()
     }
 
+    /// Returns true if the session connects lazily and the node has not been used yet,
+    /// so no connections to it were opened.
+    ///
+    /// See [SessionBuilder::connect_lazily](crate::client::session_builder::SessionBuilder::connect_lazily).
+    pub fn is_idle(&self) -> bool {
+        self.pool.as_ref().is_some_and(NodeConnectionPool::is_idle)
+    }
+
     /// Returns a boolean which indicates whether this node was is enabled.
     /// Only enabled nodes will have connections open. For disabled nodes,
     /// no connections will be opened.
@@ -215,6 +283,79 @@ impl Node {
         self.pool.is_some()
     }
 
+    /// Returns recent significant connection events for this node, oldest first.
+    ///
+    /// Events include opened connections, failed connection attempts,
+    /// missed keepalives, broken connections and stream id exhaustion.
+    /// Only a bounded number of the most recent events is kept.
+    /// The log survives address changes of the node.
+    /// For disabled nodes, the log is always empty.
+    pub fn recent_events(&self) -> Vec<ConnectionEvent> {
+        self.pool
+            .as_ref()
+            .map(|pool| pool.event_log().snapshot())
+            .unwrap_or_default()
+    }
+
+    /// Returns runtime statistics of the node: its connections, requests and version.
+    ///
+    /// Statistics survive address changes of the node.
+    pub fn stats(&self) -> NodeStats {
+        let event_log = self.pool.as_ref().map(NodeConnectionPool::event_log);
+        NodeStats {
+            connections_per_shard: self.connections_per_shard(),
+            in_flight_requests: self.runtime.in_flight_requests.load(Ordering::Relaxed),
+            errors: self.runtime.errors.load(Ordering::Relaxed),
+            last_connection_attempt: event_log.and_then(|log| log.last_connection_attempt()),
+            last_connection_error: event_log
+                .and_then(|log| log.last_error())
+                .and_then(|event| {
+                    let error = event.kind().error()?.clone();
+                    Some((event.time(), error))
+                }),
+            release_version: self.runtime.release_version.lock().unwrap().clone(),
+            shard_count: self.sharder().map(|sharder| sharder.nr_shards),
+        }
+    }
+
+    /// Marks a request as sent to the node. It is in flight until the guard is dropped.
+    pub(crate) fn start_request(&self) -> InFlightRequestGuard {
+        self.runtime
+            .in_flight_requests
+            .fetch_add(1, Ordering::Relaxed);
+        InFlightRequestGuard {
+            runtime: Arc::clone(&self.runtime),
+        }
+    }
+
+    /// Returns true if the node has reached the configured limit of in-flight requests.
+    ///
+    /// See [SessionBuilder::max_in_flight_requests_per_node](crate::client::session_builder::SessionBuilder::max_in_flight_requests_per_node).
+    pub(crate) fn is_saturated(&self) -> bool {
+        self.max_in_flight_requests
+            .is_some_and(|max| self.runtime.in_flight_requests.load(Ordering::Relaxed) >= max.get())
+    }
+
+    /// Records that a request attempt sent to the node failed.
+    pub(crate) fn record_request_error(&self) {
+        self.runtime.errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Updates the version advertised by the node in metadata.
+    pub(crate) fn update_release_version(&self, release_version: Option<String>) {
+        *self.runtime.release_version.lock().unwrap() = release_version;
+    }
+
+    /// Records that a request to the given shard could not be sent,
+    /// because the connection ran out of stream ids.
+    pub(crate) fn record_stream_ids_exhausted(&self, shard: Shard) {
+        if let Some(pool) = &self.pool {
+            pool.event_log()
+                .record(ConnectionEventKind::StreamIdsExhausted { shard });
+            pool.record_stream_ids_exhausted(shard);
+        }
+    }
+
     pub(crate) async fn use_keyspace(
         &self,
         keyspace_name: VerifiedKeyspaceName,
@@ -231,10 +372,35 @@ impl Node {
         self.get_pool()?.get_working_connections()
     }
 
+    /// Returns the number of open connections to each shard of the node.
+    /// Empty if the node is disabled or its pool has no connections.
+    pub(crate) fn connections_per_shard(&self) -> Vec<usize> {
+        self.pool
+            .as_ref()
+            .map(NodeConnectionPool::connections_per_shard)
+            .unwrap_or_default()
+    }
+
     pub(crate) fn get_random_connection(&self) -> Result<Arc<Connection>, ConnectionPoolError> {
         self.get_pool()?.random_connection()
     }
 
+    /// If the session connects lazily, opens connections to the node
+    /// if it has not been used yet, and waits until the pool is initialized.
+    pub(crate) async fn connect_lazily(&self) {
+        if let Some(pool) = &self.pool {
+            pool.connect_lazily().await;
+        }
+    }
+
+    /// Opens connections to the node if the session connects lazily,
+    /// and waits until its pool has the target number of connections.
+    pub(crate) async fn wait_until_pool_full(&self) {
+        if let Some(pool) = &self.pool {
+            pool.wait_until_full().await;
+        }
+    }
+
     pub(crate) async fn wait_until_pool_initialized(&self) {
         if let Some(pool) = &self.pool {
             pool.wait_until_initialized().await;
@@ -272,102 +438,226 @@ pub enum KnownNode {
     Hostname(String),
     /// A node identified by its IP address + a port.
     Address(SocketAddr),
+    /// Nodes identified by SRV records of the given name.
+    Srv(String),
 }
 
 /// Describes a database server known on Session startup, with already resolved address.
 #[derive(Debug, Clone)]
 pub(crate) struct ResolvedContactPoint {
     pub(crate) address: SocketAddr,
+    /// The hostname the address was resolved from, if any.
+    pub(crate) resolved_from: Option<ResolvedHostname>,
+}
+
+/// A hostname of a contact point, together with the time of its last resolution.
+#[derive(Debug, Clone)]
+pub(crate) struct ResolvedHostname {
+    pub(crate) hostname: String,
+    /// Port used if the hostname does not specify one.
+    pub(crate) default_port: u16,
+    pub(crate) resolved_at: Instant,
+}
+
+impl ResolvedContactPoint {
+    /// Resolves the hostname of the contact point again if the address may be stale,
+    /// i.e. it was resolved more than `ttl` ago.
+    ///
+    /// Returns `None` if the contact point is up to date or was not resolved from a hostname,
+    /// or if resolution fails.
+    pub(crate) async fn resolve_again_if_stale(
+        &self,
+        resolver: &dyn Resolver,
+        ttl: Duration,
+        hostname_resolution_timeout: Option<Duration>,
+    ) -> Option<ResolvedContactPoint> {
+        let resolved_from = self.resolved_from.as_ref()?;
+        if resolved_from.resolved_at.elapsed() < ttl {
+            return None;
+        }
+        match resolve_hostname(
+            resolver,
+            &resolved_from.hostname,
+            resolved_from.default_port,
+            hostname_resolution_timeout,
+        )
+        .await
+        {
+            Ok(address) => Some(ResolvedContactPoint {
+                address,
+                resolved_from: Some(ResolvedHostname {
+                    resolved_at: Instant::now(),
+                    ..resolved_from.clone()
+                }),
+            }),
+            Err(e) => {
+                warn!(
+                    target: log_targets::TOPOLOGY,
+                    "Hostname resolution failed for {}, using previous address {}: {}",
+                    resolved_from.hostname, self.address, &e
+                );
+                None
+            }
+        }
+    }
 }
 
 #[derive(Error, Debug)]
 pub(crate) enum DnsLookupError {
     #[error("Failed to perform DNS lookup within {0}ms")]
     Timeout(u128),
-    #[error("Empty address list returned by DNS for {0}")]
-    EmptyAddressListForHost(String),
     #[error(transparent)]
-    IoError(#[from] std::io::Error),
+    ResolutionError(#[from] ResolutionError),
 }
 
-/// Performs a DNS lookup with provided optional timeout.
-async fn lookup_host_with_timeout(
-    host: impl ToSocketAddrs,
+/// Awaits a DNS lookup with provided optional timeout.
+async fn with_lookup_timeout<T>(
+    lookup: impl Future<Output = Result<T, ResolutionError>>,
     hostname_resolution_timeout: Option<Duration>,
-) -> Result<impl Iterator<Item = SocketAddr>, DnsLookupError> {
+) -> Result<T, DnsLookupError> {
     if let Some(timeout) = hostname_resolution_timeout {
-        match tokio::time::timeout(timeout, lookup_host(host)).await {
+        match runtime::timeout(timeout, lookup).await {
             Ok(res) => res.map_err(Into::into),
-            // Elapsed error from tokio library does not provide any context.
+            // Elapsed error does not provide any context.
             Err(_) => Err(DnsLookupError::Timeout(timeout.as_millis())),
         }
     } else {
-        lookup_host(host).await.map_err(Into::into)
+        lookup.await.map_err(Into::into)
     }
 }
 
-// Resolve the given hostname using a DNS lookup if necessary.
+// Resolve the given hostname using the resolver.
 // The resolution may return multiple IPs and the function returns one of them.
 // It prefers to return IPv4s first, and only if there are none, IPv6s.
 pub(crate) async fn resolve_hostname(
+    resolver: &dyn Resolver,
     hostname: &str,
+    default_port: u16,
     hostname_resolution_timeout: Option<Duration>,
 ) -> Result<SocketAddr, DnsLookupError> {
-    // When passing String to `lookup_host`, it expects it to be in the form "hostname:port".
-    // If it is not, error will be returned immediately. In this case, we want to perform
-    // check with (hostname, default_port) with the same timeout.
-    // If the first check ended with timeout, there is no point in second check, because
-    // reason for failure is not connected to the lack of default port.
-    // There may be other errors than timeout and invalid value, but I don't really see
-    // any harm in trying again in such cases.
-    let addrs = match lookup_host_with_timeout(hostname, hostname_resolution_timeout).await {
-        Ok(addrs) => itertools::Either::Left(addrs),
-        Err(DnsLookupError::Timeout(t)) => return Err(DnsLookupError::Timeout(t)),
-        // Use a default port in case of error, but propagate the original error on failure
-        Err(e) => {
-            let addrs = lookup_host_with_timeout((hostname, 9042), hostname_resolution_timeout)
-                .await
-                .or(Err(e))?;
-            itertools::Either::Right(addrs)
-        }
-    };
+    let addrs = with_lookup_timeout(
+        resolver.resolve_host(hostname, default_port),
+        hostname_resolution_timeout,
+    )
+    .await?;
 
     addrs
+        .into_iter()
         .find_or_last(|addr| matches!(addr, SocketAddr::V4(_)))
-        .ok_or_else(|| DnsLookupError::EmptyAddressListForHost(hostname.to_owned()))
+        .ok_or_else(|| ResolutionError::EmptyAddressList(hostname.to_owned()).into())
+}
+
+// Resolve the given hostname, remembering it in the returned contact point.
+async fn resolve_contact_point(
+    resolver: &dyn Resolver,
+    hostname: &str,
+    default_port: u16,
+    hostname_resolution_timeout: Option<Duration>,
+) -> Result<ResolvedContactPoint, DnsLookupError> {
+    let address = resolve_hostname(
+        resolver,
+        hostname,
+        default_port,
+        hostname_resolution_timeout,
+    )
+    .await?;
+    Ok(ResolvedContactPoint {
+        address,
+        resolved_from: Some(ResolvedHostname {
+            hostname: hostname.to_owned(),
+            default_port,
+            resolved_at: Instant::now(),
+        }),
+    })
+}
+
+// Resolve the SRV records of the given name, and then their targets.
+async fn resolve_srv(
+    resolver: &dyn Resolver,
+    name: &str,
+    hostname_resolution_timeout: Option<Duration>,
+) -> Result<Vec<ResolvedContactPoint>, DnsLookupError> {
+    let targets =
+        with_lookup_timeout(resolver.resolve_srv(name), hostname_resolution_timeout).await?;
+    let resolve_futures = targets.into_iter().map(|target| async move {
+        match resolve_contact_point(
+            resolver,
+            &target.host,
+            target.port,
+            hostname_resolution_timeout,
+        )
+        .await
+        {
+            Ok(contact_point) => Some(contact_point),
+            Err(e) => {
+                warn!(
+                    target: log_targets::TOPOLOGY,
+                    "Hostname resolution failed for SRV target {} of {}: {}",
+                    target.host, name, &e
+                );
+                None
+            }
+        }
+    });
+    Ok(futures::future::join_all(resolve_futures)
+        .await
+        .into_iter()
+        .flatten()
+        .collect())
 }
 
 /// Transforms the given [`InternalKnownNode`]s into [`ContactPoint`]s.
 ///
 /// In case of a hostname, resolves it using a DNS lookup.
+/// In case of an SRV record name, looks it up and resolves all its targets.
 /// In case of a plain IP address, parses it and uses straight.
 pub(crate) async fn resolve_contact_points(
     known_nodes: &[KnownNode],
+    resolver: &dyn Resolver,
     hostname_resolution_timeout: Option<Duration>,
 ) -> (Vec<ResolvedContactPoint>, Vec<String>) {
     // Find IP addresses of all known nodes passed in the config
     let mut initial_peers: Vec<ResolvedContactPoint> = Vec::with_capacity(known_nodes.len());
 
-    let mut to_resolve: Vec<&str> = Vec::new();
+    let mut to_resolve: Vec<&KnownNode> = Vec::new();
     let mut hostnames: Vec<String> = Vec::new();
 
     for node in known_nodes.iter() {
         match node {
-            KnownNode::Hostname(hostname) => {
-                to_resolve.push(hostname.as_str());
+            KnownNode::Hostname(hostname) | KnownNode::Srv(hostname) => {
+                to_resolve.push(node);
                 hostnames.push(hostname.clone());
             }
-            KnownNode::Address(address) => {
-                initial_peers.push(ResolvedContactPoint { address: *address })
-            }
+            KnownNode::Address(address) => initial_peers.push(ResolvedContactPoint {
+                address: *address,
+                resolved_from: None,
+            }),
         };
     }
-    let resolve_futures = to_resolve.into_iter().map(|hostname| async move {
-        match resolve_hostname(hostname, hostname_resolution_timeout).await {
-            Ok(address) => Some(ResolvedContactPoint { address }),
+    let resolve_futures = to_resolve.into_iter().map(|node| async move {
+        let (name, result) = match node {
+            KnownNode::Hostname(hostname) => (
+                hostname,
+                resolve_contact_point(resolver, hostname, 9042, hostname_resolution_timeout)
+                    .await
+                    .map(|contact_point| vec![contact_point]),
+            ),
+            KnownNode::Srv(name) => (
+                name,
+                resolve_srv(resolver, name, hostname_resolution_timeout).await,
+            ),
+            KnownNode::Address(_) => unreachable!("Addresses need no resolution"),
+        };
+        match result {
+            Ok(addresses) => addresses,
             Err(e) => {
-                warn!("Hostname resolution failed for {}: {}", hostname, &e);
-                None
+                warn!(
+                    target: log_targets::TOPOLOGY,
+                    "Hostname resolution failed for {}: {}",
+                    name,
+                    &e,
+                );
+                Vec::new()
             }
         }
     });
@@ -397,12 +687,169 @@ mod tests {
                 datacenter,
                 rack,
                 pool: None,
+                runtime: Default::default(),
+                max_in_flight_requests: None,
                 enabled_as_connected: AtomicBool::new(false),
             }
         }
 
+        pub(crate) fn with_max_in_flight_requests(mut self, max: NonZeroUsize) -> Self {
+            self.max_in_flight_requests = Some(max);
+            self
+        }
+
         pub(crate) fn use_enabled_as_connected(&self) {
             self.enabled_as_connected.store(true, Ordering::SeqCst);
         }
     }
+
+    #[test]
+    fn node_stats() {
+        let node = Node::new_for_test(None, None, None, None);
+        let stats = node.stats();
+        assert!(stats.connections_per_shard.is_empty());
+        assert_eq!(stats.in_flight_requests, 0);
+        assert_eq!(stats.errors, 0);
+        assert!(stats.last_connection_attempt.is_none());
+        assert!(stats.last_connection_error.is_none());
+        assert!(stats.release_version.is_none());
+        assert!(stats.shard_count.is_none());
+
+        let first = node.start_request();
+        let second = node.start_request();
+        node.record_request_error();
+        node.update_release_version(Some("3.0.8".to_owned()));
+        drop(first);
+
+        // Statistics are shared with the node recreated after an IP change.
+        let endpoint = PeerEndpoint {
+            host_id: node.host_id,
+            address: NodeAddr::Translatable(SocketAddr::from(([127, 0, 0, 1], 9042))),
+            datacenter: None,
+            rack: None,
+        };
+        let moved = Node::inherit_with_ip_changed(&node, endpoint);
+        let stats = moved.stats();
+        assert_eq!(stats.in_flight_requests, 1);
+        assert_eq!(stats.errors, 1);
+        assert_eq!(stats.release_version.as_deref(), Some("3.0.8"));
+
+        drop(second);
+        assert_eq!(node.stats().in_flight_requests, 0);
+    }
+
+    struct TestResolver;
+
+    #[async_trait::async_trait]
+    impl Resolver for TestResolver {
+        async fn resolve_host(
+            &self,
+            host: &str,
+            default_port: u16,
+        ) -> Result<Vec<SocketAddr>, ResolutionError> {
+            match host {
+                "v6-and-v4" => Ok(vec![
+                    SocketAddr::from(([0, 0, 0, 0, 0, 0, 0, 1], default_port)),
+                    SocketAddr::from(([127, 0, 0, 1], default_port)),
+                ]),
+                "node2.example.com" => Ok(vec![SocketAddr::from(([127, 0, 0, 2], default_port))]),
+                "node3.example.com" => Ok(vec![SocketAddr::from(([127, 0, 0, 3], default_port))]),
+                _ => Err(ResolutionError::EmptyAddressList(host.to_owned())),
+            }
+        }
+
+        async fn resolve_srv(
+            &self,
+            name: &str,
+        ) -> Result<Vec<crate::policies::resolver::SrvTarget>, ResolutionError> {
+            use crate::policies::resolver::SrvTarget;
+            match name {
+                "_cql._tcp.example.com" => Ok(vec![
+                    SrvTarget::new("node2.example.com", 19042),
+                    SrvTarget::new("node3.example.com", 29042),
+                    SrvTarget::new("unknown.example.com", 9042),
+                ]),
+                _ => Err(ResolutionError::SrvNotSupported),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn resolve_contact_points_with_custom_resolver() {
+        let address = SocketAddr::from(([127, 0, 0, 4], 9042));
+        let known_nodes = [
+            KnownNode::Hostname("v6-and-v4".into()),
+            KnownNode::Srv("_cql._tcp.example.com".into()),
+            KnownNode::Hostname("unknown".into()),
+            KnownNode::Srv("_cql._tcp.unknown.com".into()),
+            KnownNode::Address(address),
+        ];
+
+        let (contact_points, hostnames) =
+            resolve_contact_points(&known_nodes, &TestResolver, None).await;
+        let addresses: Vec<SocketAddr> = contact_points.iter().map(|cp| cp.address).collect();
+        assert_eq!(
+            addresses,
+            [
+                address,
+                // IPv4 addresses are preferred.
+                SocketAddr::from(([127, 0, 0, 1], 9042)),
+                // SRV targets are resolved with ports from the records.
+                SocketAddr::from(([127, 0, 0, 2], 19042)),
+                SocketAddr::from(([127, 0, 0, 3], 29042)),
+            ]
+        );
+        assert_eq!(
+            hostnames,
+            [
+                "v6-and-v4",
+                "_cql._tcp.example.com",
+                "unknown",
+                "_cql._tcp.unknown.com"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn resolve_contact_point_again_if_stale() {
+        let stale_address = SocketAddr::from(([127, 0, 0, 9], 9042));
+        let contact_point = ResolvedContactPoint {
+            address: stale_address,
+            resolved_from: Some(ResolvedHostname {
+                hostname: "node2.example.com".into(),
+                default_port: 9042,
+                resolved_at: Instant::now(),
+            }),
+        };
+
+        // Resolved recently, so it is kept as is.
+        let ttl = Duration::from_secs(3600);
+        assert!(
+            contact_point
+                .resolve_again_if_stale(&TestResolver, ttl, None)
+                .await
+                .is_none()
+        );
+
+        let resolved = contact_point
+            .resolve_again_if_stale(&TestResolver, Duration::ZERO, None)
+            .await
+            .unwrap();
+        assert_eq!(resolved.address, SocketAddr::from(([127, 0, 0, 2], 9042)));
+        let resolved_from = resolved.resolved_from.unwrap();
+        assert_eq!(resolved_from.hostname, "node2.example.com");
+        assert!(resolved_from.resolved_at >= contact_point.resolved_from.unwrap().resolved_at);
+
+        // Contact points given by addresses are never resolved.
+        let address_contact_point = ResolvedContactPoint {
+            address: stale_address,
+            resolved_from: None,
+        };
+        assert!(
+            address_contact_point
+                .resolve_again_if_stale(&TestResolver, Duration::ZERO, None)
+                .await
+                .is_none()
+        );
+    }
 }
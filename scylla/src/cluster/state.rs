@@ -1,14 +1,21 @@
 use crate::errors::{ClusterStateTokenError, ConnectionPoolError};
-use crate::network::{Connection, ConnectivityChangeEvent, PoolConfig, VerifiedKeyspaceName};
+use crate::network::{
+    Connection, ConnectivityChangeEvent, PoolConfig, PoolWarmup, VerifiedKeyspaceName,
+};
+use crate::observability::diagnostics::ClusterStateDump;
 #[cfg(feature = "metrics")]
 use crate::observability::metrics::Metrics;
 use crate::policies::host_filter::HostFilter;
 use crate::routing::locator::ReplicaLocator;
-use crate::routing::locator::tablets::{RawTablet, Tablet, TabletsInfo};
-use crate::routing::partitioner::{PartitionerName, calculate_token_for_partition_key};
-use crate::routing::{Shard, Token};
+use crate::routing::locator::tablets::{RawTablet, TableTablets, Tablet, TabletsInfo};
+use crate::routing::partitioner::{
+    CustomPartitioners, Partitioner, PartitionerName, calculate_token_for_partition_key,
+};
+use crate::routing::{Shard, Token, TokenRange};
+use crate::utils::runtime;
 use crate::utils::safe_format::IteratorSafeFormatExt;
 
+use crate::observability::log_targets;
 use itertools::Itertools;
 use scylla_cql::frame::response::result::TableSpec;
 use scylla_cql::serialize::row::{RowSerializationContext, SerializeRow, SerializedValues};
@@ -18,7 +25,7 @@ use tokio::sync::mpsc;
 use tracing::{debug, warn};
 use uuid::Uuid;
 
-use super::metadata::{Keyspace, Metadata, Strategy};
+use super::metadata::{Keyspace, Metadata, SingleKeyspaceMetadataError, Strategy, Table};
 use super::node::{Node, NodeRef};
 
 /// Represents the state of the cluster, including known nodes, keyspaces, and replica locator.
@@ -48,6 +55,19 @@ pub struct ClusterState {
     /// for a given (token, replication strategy, table) tuple.
     /// It relies on both topology and schema metadata.
     pub(crate) locator: ReplicaLocator,
+
+    /// Version of the token ring, bumped each time the ring changes.
+    pub(crate) ring_version: u64,
+
+    /// Version of the tablet maps, bumped each time any table's tablet map changes.
+    pub(crate) tablets_version: u64,
+
+    /// Nodes rejected by the internal requests host filter. Internal requests,
+    /// such as schema agreement checks, are not sent to them.
+    pub(crate) internal_requests_disabled_nodes: HashSet<Uuid>,
+
+    /// User-provided partitioners, consulted when resolving partitioners of tables.
+    pub(crate) custom_partitioners: CustomPartitioners,
 }
 
 /// Enables printing [ClusterState] struct in a neat way, skipping the clutter involved by
@@ -82,6 +102,57 @@ impl ClusterState {
         }
     }
 
+    /// Waits until the pools of the nodes chosen by `warmup` are full,
+    /// or until the timeout of the warmup passes.
+    /// Returns the number of pools which became full and the number of chosen nodes.
+    pub(crate) async fn warm_up_pools(&self, warmup: &PoolWarmup) -> (usize, usize) {
+        let nodes = self
+            .known_peers
+            .values()
+            .filter(|node| node.is_enabled())
+            .filter(|node| match warmup.local_datacenter() {
+                Some(dc) => node.datacenter.as_deref() == Some(dc),
+                None => true,
+            })
+            .collect::<Vec<_>>();
+        if nodes.is_empty() {
+            warn!(
+                datacenter = warmup.local_datacenter(),
+                "No nodes to warm up connection pools of"
+            );
+        }
+
+        let full_pools = futures::future::join_all(
+            nodes
+                .iter()
+                .map(|node| runtime::timeout(warmup.timeout(), node.wait_until_pool_full())),
+        )
+        .await
+        .into_iter()
+        .filter(Result::is_ok)
+        .count();
+
+        debug!(
+            "Warmed up connection pools: {} of {} pools are full",
+            full_pools,
+            nodes.len()
+        );
+        (full_pools, nodes.len())
+    }
+
+    /// If the session connects lazily and no node is connected yet, opens connections
+    /// to an arbitrary node, so that requests sent to all connected nodes
+    /// (e.g. preparation of statements) have a node to go to.
+    pub(crate) async fn connect_lazily_to_any_node(&self) {
+        if self.known_peers.values().any(|node| node.is_connected()) {
+            return;
+        }
+        // Iteration order of the hashmap is random, so the chosen node is arbitrary.
+        if let Some(node) = self.known_peers.values().find(|node| node.is_idle()) {
+            node.connect_lazily().await;
+        }
+    }
+
     /// Creates new ClusterState using information about topology held in `metadata`.
     /// Uses provided `known_peers` hashmap to recycle nodes if possible.
     #[allow(clippy::too_many_arguments)]
@@ -98,8 +169,10 @@ impl ClusterState {
              ),
         used_keyspace: &Option<VerifiedKeyspaceName>,
         host_filter: Option<&dyn HostFilter>,
+        internal_requests_host_filter: Option<&dyn HostFilter>,
         connectivity_events_sender: &mpsc::UnboundedSender<ConnectivityChangeEvent>,
-        mut tablets: TabletsInfo,
+        custom_partitioners: &CustomPartitioners,
+        previous_locator: Option<&ReplicaLocator>,
         old_keyspaces: &HashMap<String, Keyspace>,
         #[cfg(feature = "metrics")] metrics: &Arc<Metrics>,
     ) -> Self {
@@ -107,13 +180,19 @@ impl ClusterState {
         let mut new_known_peers: HashMap<Uuid, Arc<Node>> =
             HashMap::with_capacity(metadata.peers.len());
         let mut ring: Vec<(Token, Arc<Node>)> = Vec::new();
+        let mut internal_requests_disabled_nodes = HashSet::new();
 
         for peer in metadata.peers {
+            if internal_requests_host_filter.is_some_and(|f| !f.accept(&peer)) {
+                internal_requests_disabled_nodes.insert(peer.host_id);
+            }
+
             // Take existing Arc<Node> if possible, otherwise create new one
             // Changing rack/datacenter but not ip address seems improbable
             // so we can just create new node and connections then
             let peer_host_id = peer.host_id;
             let peer_address = peer.address;
+            let peer_release_version = peer.release_version.clone();
             let peer_tokens;
 
             let node: Arc<Node> = match known_peers.get(&peer_host_id) {
@@ -143,6 +222,7 @@ impl ClusterState {
                 }
             };
 
+            node.update_release_version(peer_release_version);
             new_known_peers.insert(peer_host_id, Arc::clone(&node));
 
             for token in peer_tokens {
@@ -160,6 +240,7 @@ impl ClusterState {
                 Err(e) => {
                     if let Some(old_ks) = old_keyspaces.get(&ks_name) {
                         warn!(
+                            target: log_targets::METADATA,
                             "Encountered an error while processing\
                             metadata of keyspace \"{ks_name}\": {e}.\
                             Re-using older version of this keyspace metadata"
@@ -167,6 +248,7 @@ impl ClusterState {
                         Some((ks_name, old_ks.clone()))
                     } else {
                         warn!(
+                            target: log_targets::METADATA,
                             "Encountered an error while processing metadata\
                             of keyspace \"{ks_name}\": {e}.\
                             No previous version of this keyspace metadata found, so it will not be\
@@ -179,6 +261,8 @@ impl ClusterState {
             .collect();
 
         // Tablets maintenance.
+        let mut tablets =
+            previous_locator.map_or_else(TabletsInfo::new, |locator| locator.tablets.clone());
         {
             let removed_nodes = {
                 let mut removed_nodes = HashSet::new();
@@ -220,20 +304,106 @@ impl ClusterState {
             )
         }
 
-        let (locator, keyspaces) = tokio::task::spawn_blocking(move || {
-            let keyspace_strategies = keyspaces.values().map(|ks| &ks.strategy);
-            let locator = ReplicaLocator::new(ring.into_iter(), keyspace_strategies, tablets);
-            (locator, keyspaces)
-        })
-        .await
-        .unwrap();
+        let (locator, keyspaces) =
+            Self::build_locator(previous_locator, ring, keyspaces, tablets).await;
 
         ClusterState {
             all_nodes: new_known_peers.values().cloned().collect(),
             known_peers: new_known_peers,
             keyspaces,
             locator,
+            ring_version: 0,
+            tablets_version: 0,
+            internal_requests_disabled_nodes,
+            custom_partitioners: custom_partitioners.clone(),
+        }
+    }
+
+    /// Creates a copy of this ClusterState with metadata of a single keyspace replaced.
+    ///
+    /// `keyspace_metadata` is `None` if the keyspace no longer exists.
+    /// Topology is left untouched, but the replica locator is rebuilt, because
+    /// the replication strategy of the keyspace might have changed.
+    pub(crate) async fn with_refreshed_keyspace(
+        &self,
+        keyspace_name: &str,
+        keyspace_metadata: Option<Result<Keyspace, SingleKeyspaceMetadataError>>,
+    ) -> Self {
+        let mut keyspaces = self.keyspaces.clone();
+        match keyspace_metadata {
+            Some(Ok(keyspace)) => {
+                keyspaces.insert(keyspace_name.to_owned(), keyspace);
+            }
+            Some(Err(e)) => {
+                warn!(
+                    target: log_targets::METADATA,
+                    "Encountered an error while processing metadata of keyspace \"{keyspace_name}\": {e}. \
+                    Keeping the previous version of this keyspace metadata, if any."
+                );
+            }
+            None => {
+                keyspaces.remove(keyspace_name);
+            }
+        }
+
+        let mut tablets = self.locator.tablets.clone();
+        tablets.perform_maintenance(
+            &|spec: &TableSpec| {
+                keyspaces
+                    .get(spec.ks_name())
+                    .is_some_and(|ks| ks.tables.contains_key(spec.table_name()))
+            },
+            &HashSet::new(),
+            &self.known_peers,
+            &HashMap::new(),
+        );
+
+        let ring: Vec<(Token, Arc<Node>)> = self.locator.ring().iter().cloned().collect();
+        let (locator, keyspaces) =
+            Self::build_locator(Some(&self.locator), ring, keyspaces, tablets).await;
+
+        ClusterState {
+            known_peers: self.known_peers.clone(),
+            all_nodes: self.all_nodes.clone(),
+            keyspaces,
+            locator,
+            ring_version: 0,
+            tablets_version: 0,
+            internal_requests_disabled_nodes: self.internal_requests_disabled_nodes.clone(),
+            custom_partitioners: self.custom_partitioners.clone(),
+        }
+    }
+
+    /// Builds the replica locator of a new ClusterState.
+    ///
+    /// If `previous_locator` was built for the same ring and replication strategies,
+    /// its precomputed replica sets are shared with the new locator. Otherwise, they are
+    /// precomputed on a blocking thread, so that a refresh does not stall the runtime.
+    /// Either way, the locator is complete before the ClusterState that holds it is
+    /// swapped in, so query plans never observe a partially built replica map.
+    async fn build_locator(
+        previous_locator: Option<&ReplicaLocator>,
+        ring: Vec<(Token, Arc<Node>)>,
+        keyspaces: HashMap<String, Keyspace>,
+        tablets: TabletsInfo,
+    ) -> (ReplicaLocator, HashMap<String, Keyspace>) {
+        if let Some(previous_locator) = previous_locator {
+            let keyspace_strategies = keyspaces.values().map(|ks| &ks.strategy);
+            if previous_locator.token_ring_matches(&ring, keyspace_strategies) {
+                debug!(
+                    target: log_targets::TOPOLOGY,
+                    "Token ring and replication strategies unchanged, reusing replica sets",
+                );
+                return (previous_locator.with_tablets(tablets), keyspaces);
+            }
         }
+
+        runtime::spawn_blocking(move || {
+            let keyspace_strategies = keyspaces.values().map(|ks| &ks.strategy);
+            let locator = ReplicaLocator::new(ring.into_iter(), keyspace_strategies, tablets);
+            (locator, keyspaces)
+        })
+        .await
     }
 
     /// Access keyspace details collected by the driver.
@@ -264,27 +434,33 @@ impl ClusterState {
         table: &str,
         partition_key: &dyn SerializeRow,
     ) -> Result<Token, ClusterStateTokenError> {
-        let Some(table) = self
-            .keyspaces
-            .get(keyspace)
-            .and_then(|k| k.tables.get(table))
-        else {
-            return Err(ClusterStateTokenError::UnknownTable {
-                keyspace: keyspace.to_owned(),
-                table: table.to_owned(),
-            });
-        };
+        let table = self.get_table(keyspace, table)?;
         let values = SerializedValues::from_serializable(
             &RowSerializationContext::from_specs(table.pk_column_specs.as_slice()),
             partition_key,
         )?;
-        let partitioner = table
+        calculate_token_for_partition_key(&values, &self.table_partitioner(table))
+            .map_err(ClusterStateTokenError::TokenCalculation)
+    }
+
+    fn get_table(&self, keyspace: &str, table: &str) -> Result<&Table, ClusterStateTokenError> {
+        self.keyspaces
+            .get(keyspace)
+            .and_then(|k| k.tables.get(table))
+            .ok_or_else(|| ClusterStateTokenError::UnknownTable {
+                keyspace: keyspace.to_owned(),
+                table: table.to_owned(),
+            })
+    }
+
+    /// Resolves the partitioner employed by `table`, honoring the custom partitioners.
+    /// Falls back to the default one if the table's partitioner is not known to the driver.
+    fn table_partitioner(&self, table: &Table) -> PartitionerName {
+        table
             .partitioner
             .as_deref()
-            .and_then(PartitionerName::from_str)
-            .unwrap_or_default();
-        calculate_token_for_partition_key(&values, &partitioner)
-            .map_err(ClusterStateTokenError::TokenCalculation)
+            .and_then(|name| PartitionerName::from_str(name, &self.custom_partitioners))
+            .unwrap_or_default()
     }
 
     /// Access to replicas owning a given token
@@ -333,11 +509,229 @@ impl ClusterState {
         Ok(self.get_token_endpoints(keyspace, table, token))
     }
 
+    /// Returns replicas owning a given token in the given keyspace, together with
+    /// the shards owning the token on those replicas.
+    ///
+    /// Replicas are computed from the token ring and the replication strategy of the keyspace.
+    /// Tables using tablets do not follow the token ring - to find their replicas,
+    /// use [`ClusterState::replicas_for_partition_key`] or [`ClusterState::get_token_endpoints`],
+    /// which take the table into account.
+    ///
+    /// If the keyspace is not known to the driver, it is treated as if it used `LocalStrategy`,
+    /// i.e. only the primary owner of the token is returned.
+    pub fn replicas_for_token(&self, keyspace: &str, token: Token) -> Vec<(NodeRef<'_>, Shard)> {
+        // Tablet tables are never unnamed, so this table spec always resolves to the token ring.
+        let table_spec = TableSpec::borrowed(keyspace, "");
+        self.get_token_endpoints_iter(&table_spec, token).collect()
+    }
+
+    /// Maps an arbitrary byte key onto the token ring, using the partitioner
+    /// of the given table (including custom partitioners registered in the session).
+    ///
+    /// The key does not have to be a partition key of the table. The resulting token
+    /// is the one a partition of the table would have if its key were a single `blob`
+    /// column holding `key`, so it can be used to co-locate non-CQL workloads
+    /// (e.g. local caches or queues) with the data owned by particular nodes.
+    pub fn token_for_key(
+        &self,
+        keyspace: &str,
+        table: &str,
+        key: &[u8],
+    ) -> Result<Token, ClusterStateTokenError> {
+        let table = self.get_table(keyspace, table)?;
+        Ok(self.table_partitioner(table).hash_one(key))
+    }
+
+    /// Maps an arbitrary byte key onto the token ring, like [`ClusterState::token_for_key`],
+    /// and returns the token together with the replicas of the given table owning it.
+    ///
+    /// This works for both token ring-based and tablet-based tables.
+    pub fn replicas_for_key(
+        &self,
+        keyspace: &str,
+        table: &str,
+        key: &[u8],
+    ) -> Result<(Token, Vec<(NodeRef<'_>, Shard)>), ClusterStateTokenError> {
+        let token = self.token_for_key(keyspace, table, key)?;
+        let table_spec = TableSpec::borrowed(keyspace, table);
+        let replicas = self.get_token_endpoints_iter(&table_spec, token).collect();
+        Ok((token, replicas))
+    }
+
+    /// Maps an arbitrary byte key onto the token ring with the given partitioner,
+    /// and returns the token together with the replicas owning it in the given keyspace.
+    ///
+    /// Unlike [`ClusterState::replicas_for_key`], it does not look up any table, so it can
+    /// be used for keys which are not related to any table. Unless the cluster is configured
+    /// otherwise, its partitioner is [`Murmur3Partitioner`](crate::routing::partitioner::Murmur3Partitioner).
+    ///
+    /// As with [`ClusterState::replicas_for_token`], the replicas are computed from the token
+    /// ring and the replication strategy of the keyspace, so tablets are not taken into account.
+    pub fn replicas_for_key_in_keyspace(
+        &self,
+        keyspace: &str,
+        partitioner: &impl Partitioner,
+        key: &[u8],
+    ) -> (Token, Vec<(NodeRef<'_>, Shard)>) {
+        let token = partitioner.hash_one(key);
+        (token, self.replicas_for_token(keyspace, token))
+    }
+
+    /// Returns replicas owning a given partition key, together with the shards owning
+    /// the partition on those replicas.
+    ///
+    /// This works for both token ring-based and tablet-based tables, and is meant
+    /// to allow colocating computation with data (e.g. implementing a partition-aware
+    /// scheduler). Unlike [`ClusterState::get_endpoints`], it does not clone the nodes.
+    ///
+    /// `partition_key` argument contains the values of all partition key
+    /// columns. You can use both unnamed values like a tuple (e.g. `(1, 5, 5)`)
+    /// or named values (e.g. struct that derives `SerializeRow`), as you would
+    /// when executing a request. No additional values are allowed besides values
+    /// for primary key columns.
+    pub fn replicas_for_partition_key(
+        &self,
+        keyspace: &str,
+        table: &str,
+        partition_key: &dyn SerializeRow,
+    ) -> Result<Vec<(NodeRef<'_>, Shard)>, ClusterStateTokenError> {
+        let token = self.compute_token(keyspace, table, partition_key)?;
+        let table_spec = TableSpec::borrowed(keyspace, table);
+        Ok(self.get_token_endpoints_iter(&table_spec, token).collect())
+    }
+
+    /// Returns all token ranges of the token ring, in ring order, together with
+    /// the replicas owning them in the given keyspace (see [`ClusterState::replicas_for_token`]).
+    ///
+    /// Each ring member owns the range between the token of the preceding member (exclusive)
+    /// and its own token (inclusive). The first returned range is the wrapping one.
+    /// Together, the ranges cover the whole ring, so they can be used to split
+    /// a full table scan into parts that are executed in parallel on their replicas.
+    ///
+    /// As with [`ClusterState::replicas_for_token`], tables using tablets don't follow
+    /// the token ring, so their replicas are not reflected by this method.
+    pub fn token_ranges(&self, keyspace: &str) -> Vec<(TokenRange, Vec<(NodeRef<'_>, Shard)>)> {
+        let mut tokens = self
+            .locator
+            .ring()
+            .iter()
+            .map(|(token, _node)| *token)
+            .collect::<Vec<_>>();
+        tokens.dedup();
+
+        let Some(&last_token) = tokens.last() else {
+            return Vec::new();
+        };
+        std::iter::once(last_token)
+            .chain(tokens.iter().copied())
+            .tuple_windows()
+            .map(|(start, end)| {
+                (
+                    TokenRange::new(start, end),
+                    self.replicas_for_token(keyspace, end),
+                )
+            })
+            .collect()
+    }
+
+    /// Returns the version of the token ring.
+    ///
+    /// The version is bumped every time the driver notices that the token ring
+    /// changed (i.e. some node's tokens changed, or a node joined or left the ring).
+    /// This allows detecting that the results of [`ClusterState::token_ranges`]
+    /// (and data derived from them) are outdated.
+    pub fn ring_version(&self) -> u64 {
+        self.ring_version
+    }
+
+    /// Sets the ring version of this state based on the state it replaces,
+    /// bumping the version if the token ring changed.
+    pub(crate) fn inherit_ring_version(&mut self, previous: &ClusterState) {
+        let ring_members = |state: &ClusterState| -> Vec<(Token, Uuid)> {
+            state
+                .locator
+                .ring()
+                .iter()
+                .map(|(token, node)| (*token, node.host_id))
+                .collect()
+        };
+
+        self.ring_version = if ring_members(self) == ring_members(previous) {
+            previous.ring_version
+        } else {
+            previous.ring_version + 1
+        };
+    }
+
+    /// Returns the tablets of the given table known to the driver.
+    ///
+    /// Returns `None` if the table doesn't use tablets, or if the driver hasn't learned
+    /// any of its tablets yet - the driver learns tablets lazily, from responses to requests
+    /// that were sent to a node which is not a replica. Thus, the tablets may not cover
+    /// the whole token ring.
+    pub fn table_tablets(&self, keyspace: &str, table: &str) -> Option<&TableTablets> {
+        self.locator
+            .tablets
+            .tablets_for_table(&TableSpec::borrowed(keyspace, table))
+    }
+
+    /// Iterates over tablets of all tables whose tablets are known to the driver.
+    ///
+    /// See [`ClusterState::table_tablets`] for details.
+    pub fn tablet_tables(&self) -> impl Iterator<Item = &TableTablets> {
+        self.locator.tablets.iter()
+    }
+
+    /// Returns the version of the tablet maps.
+    ///
+    /// The version is bumped every time the driver updates the tablets of any table,
+    /// e.g. after learning about a tablet migration. This allows detecting that results of
+    /// [`ClusterState::table_tablets`] (and data derived from them) are outdated.
+    /// To be notified about updates, configure a
+    /// [TabletListener](crate::policies::tablet_listener::TabletListener).
+    pub fn tablets_version(&self) -> u64 {
+        self.tablets_version
+    }
+
+    /// Returns a snapshot of this state intended for troubleshooting, e.g. of request routing:
+    /// nodes with their connection pools, datacenters and racks, token ownership and keyspaces.
+    ///
+    /// With the `serde` crate feature enabled, the snapshot implements `serde::Serialize`.
+    pub fn dump(&self) -> ClusterStateDump {
+        ClusterStateDump::new(self)
+    }
+
+    /// Sets the tablets version of this state based on the state it replaces,
+    /// bumping the version if any tablet map changed.
+    ///
+    /// Returns the tables whose tablet maps changed.
+    pub(crate) fn inherit_tablets_version(
+        &mut self,
+        previous: &ClusterState,
+    ) -> Vec<TableSpec<'static>> {
+        let changed_tables = self
+            .locator
+            .tablets
+            .changed_tables(&previous.locator.tablets);
+        self.tablets_version = if changed_tables.is_empty() {
+            previous.tablets_version
+        } else {
+            previous.tablets_version + 1
+        };
+        changed_tables
+    }
+
     /// Access replica location info
     pub fn replica_locator(&self) -> &ReplicaLocator {
         &self.locator
     }
 
+    /// Returns whether internal requests, such as schema agreement checks,
+    /// may be sent to the node with the given host ID.
+    pub(crate) fn accepts_internal_requests(&self, host_id: Uuid) -> bool {
+        !self.internal_requests_disabled_nodes.contains(&host_id)
+    }
+
     /// Returns nonempty iterator (over nodes) of iterators (over shards).
     ///
     /// External iterator iterates over nodes.
@@ -350,7 +744,33 @@ impl ClusterState {
     > {
         // The returned iterator is nonempty by nonemptiness invariant of `self.known_peers`.
         assert!(!self.known_peers.is_empty());
-        let nodes_iter = self.known_peers.values();
+        self.iter_working_connections_per_accepted_node(false)
+    }
+
+    /// Like [ClusterState::iter_working_connections_per_node], but skips nodes
+    /// rejected by the internal requests host filter.
+    ///
+    /// Fails with [ConnectionPoolError::NodeDisabledByHostFilter] if all nodes were rejected.
+    pub(crate) fn iter_working_connections_per_node_for_internal_requests(
+        &self,
+    ) -> Result<
+        impl Iterator<Item = (Uuid, impl Iterator<Item = Arc<Connection>> + use<>)> + use<'_>,
+        ConnectionPoolError,
+    > {
+        self.iter_working_connections_per_accepted_node(true)
+    }
+
+    fn iter_working_connections_per_accepted_node(
+        &self,
+        internal_requests: bool,
+    ) -> Result<
+        impl Iterator<Item = (Uuid, impl Iterator<Item = Arc<Connection>> + use<>)> + use<'_>,
+        ConnectionPoolError,
+    > {
+        let nodes_iter = self
+            .known_peers
+            .values()
+            .filter(move |node| !internal_requests || self.accepts_internal_requests(node.host_id));
         let mut connection_pool_per_node_iter = nodes_iter.map(|node| {
             node.get_working_connections()
                 .map(|pool| (node.host_id, pool))
@@ -362,7 +782,7 @@ impl ClusterState {
             connection_pool_per_node_iter
                 .by_ref()
                 .find_or_first(Result::is_ok)
-                .expect("impossible: known_peers was asserted to be nonempty");
+                .unwrap_or(Err(ConnectionPoolError::NodeDisabledByHostFilter));
 
         // We have:
         // 1. either consumed the whole iterator without success and got the first error,
@@ -441,6 +861,7 @@ impl ClusterState {
                 Ok(t) => t,
                 Err((t, f)) => {
                     debug!(
+                        target: log_targets::METADATA,
                         "Nodes ({}) that are replicas for a tablet {{ks: {}, table: {}, range: [{}. {}]}} not present in current ClusterState.known_peers. \
                        Skipping these replicas until topology refresh",
                         f.iter().safe_format(", "),
@@ -456,3 +877,293 @@ impl ClusterState {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    use assert_matches::assert_matches;
+
+    use scylla_cql::frame::response::result::{ColumnType, NativeType};
+    use scylla_cql::serialize::row::SerializedValues;
+
+    use super::ClusterState;
+    use crate::cluster::metadata::Table;
+    use crate::errors::ClusterStateTokenError;
+    use crate::policies::host_filter::DcHostFilter;
+    use crate::routing::locator::test::{
+        A, B, D, F, G, KEYSPACE_NTS_RF_3, KEYSPACE_SS_RF_2, id_to_invalid_addr,
+        mock_metadata_for_token_aware_tests,
+    };
+    use crate::routing::partitioner::{
+        Murmur3Partitioner, Partitioner, PartitionerHasher, PartitionerName,
+        calculate_token_for_partition_key,
+    };
+    use crate::routing::{Token, TokenRange};
+    use crate::test_utils::{
+        MockClusterStateBuilder, mock_cluster_state_for_token_aware_tests, setup_tracing,
+    };
+
+    #[tokio::test]
+    async fn test_internal_requests_host_filter() {
+        setup_tracing();
+        let metadata = mock_metadata_for_token_aware_tests();
+        let us_nodes = metadata
+            .peers
+            .iter()
+            .filter(|peer| peer.datacenter.as_deref() == Some("us"))
+            .map(|peer| peer.host_id)
+            .collect::<HashSet<_>>();
+
+        let host_filter = DcHostFilter::new("eu".to_owned());
+        let state = MockClusterStateBuilder::new(metadata)
+            .internal_requests_host_filter(&host_filter)
+            .build()
+            .await;
+
+        assert!(!us_nodes.is_empty());
+        for node in state.get_nodes_info() {
+            assert_eq!(
+                state.accepts_internal_requests(node.host_id),
+                !us_nodes.contains(&node.host_id)
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replicas_for_token() {
+        setup_tracing();
+        let cluster = mock_cluster_state_for_token_aware_tests().await;
+        let replica_ports = |keyspace: &str, token: Token| {
+            cluster
+                .replicas_for_token(keyspace, token)
+                .into_iter()
+                .map(|(node, _shard)| node.address.port())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            replica_ports(KEYSPACE_SS_RF_2, Token::new(450)),
+            [id_to_invalid_addr(F).port(), id_to_invalid_addr(G).port()]
+        );
+        assert_eq!(replica_ports(KEYSPACE_NTS_RF_3, Token::new(450)).len(), 6);
+        assert!(
+            replica_ports(KEYSPACE_NTS_RF_3, Token::new(450))
+                .contains(&id_to_invalid_addr(D).port())
+        );
+
+        // Unknown keyspace is treated as using `LocalStrategy`, so only the primary owner is returned.
+        assert_eq!(
+            replica_ports("unknown_keyspace", Token::new(450)),
+            [id_to_invalid_addr(F).port()]
+        );
+    }
+
+    struct ConstPartitioner;
+    struct ConstPartitionerHasher;
+
+    impl Partitioner for ConstPartitioner {
+        type Hasher = ConstPartitionerHasher;
+
+        fn build_hasher(&self) -> Self::Hasher {
+            ConstPartitionerHasher
+        }
+    }
+
+    impl PartitionerHasher for ConstPartitionerHasher {
+        fn write(&mut self, _pk_part: &[u8]) {}
+
+        fn finish(&self) -> Token {
+            Token::new(42)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replicas_for_key() {
+        setup_tracing();
+        let table = |partitioner: Option<&str>| Table {
+            columns: HashMap::new(),
+            partition_key: vec![],
+            clustering_key: vec![],
+            partitioner: partitioner.map(str::to_owned),
+            options: Default::default(),
+            extensions: Default::default(),
+            indexes: HashMap::new(),
+            pk_column_specs: vec![],
+        };
+        let mut metadata = mock_metadata_for_token_aware_tests();
+        let tables = &mut metadata
+            .keyspaces
+            .get_mut(KEYSPACE_NTS_RF_3)
+            .unwrap()
+            .as_mut()
+            .unwrap()
+            .tables;
+        tables.insert("murmur3".to_owned(), table(None));
+        tables.insert(
+            "cdc".to_owned(),
+            table(Some("com.scylladb.dht.CDCPartitioner")),
+        );
+        tables.insert("custom".to_owned(), table(Some("RandomPartitioner")));
+        let mut cluster = MockClusterStateBuilder::new(metadata).build().await;
+        cluster
+            .custom_partitioners
+            .register("RandomPartitioner", ConstPartitioner);
+        let key = b"some arbitrary key";
+
+        // The token is the same as the one of a single-column blob partition key.
+        let mut pk = SerializedValues::new();
+        pk.add_value(&&key[..], &ColumnType::Native(NativeType::Blob))
+            .unwrap();
+        for (table, partitioner) in [
+            ("murmur3", PartitionerName::Murmur3),
+            ("cdc", PartitionerName::CDC),
+        ] {
+            let expected_token = calculate_token_for_partition_key(&pk, &partitioner).unwrap();
+            assert_eq!(
+                cluster
+                    .token_for_key(KEYSPACE_NTS_RF_3, table, key)
+                    .unwrap(),
+                expected_token
+            );
+
+            let (token, replicas) = cluster
+                .replicas_for_key(KEYSPACE_NTS_RF_3, table, key)
+                .unwrap();
+            assert_eq!(token, expected_token);
+            assert_eq!(
+                replicas,
+                cluster.replicas_for_token(KEYSPACE_NTS_RF_3, token)
+            );
+        }
+        assert_eq!(
+            cluster
+                .token_for_key(KEYSPACE_NTS_RF_3, "custom", key)
+                .unwrap(),
+            Token::new(42)
+        );
+
+        assert_matches!(
+            cluster.token_for_key(KEYSPACE_NTS_RF_3, "unknown_table", key),
+            Err(ClusterStateTokenError::UnknownTable { .. })
+        );
+        assert_matches!(
+            cluster.replicas_for_key(KEYSPACE_NTS_RF_3, "unknown_table", key),
+            Err(ClusterStateTokenError::UnknownTable { .. })
+        );
+
+        // No table is needed when the partitioner is given explicitly.
+        let (token, replicas) =
+            cluster.replicas_for_key_in_keyspace(KEYSPACE_NTS_RF_3, &Murmur3Partitioner, key);
+        assert_eq!(
+            token,
+            cluster
+                .token_for_key(KEYSPACE_NTS_RF_3, "murmur3", key)
+                .unwrap()
+        );
+        assert_eq!(
+            replicas,
+            cluster.replicas_for_token(KEYSPACE_NTS_RF_3, token)
+        );
+        assert_eq!(
+            cluster
+                .replicas_for_key_in_keyspace(KEYSPACE_NTS_RF_3, &ConstPartitioner, key)
+                .0,
+            Token::new(42)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_replicas_for_partition_key_unknown_table() {
+        setup_tracing();
+        let cluster = mock_cluster_state_for_token_aware_tests().await;
+
+        assert_matches!(
+            cluster.replicas_for_partition_key(KEYSPACE_SS_RF_2, "unknown_table", &(1_i32,)),
+            Err(ClusterStateTokenError::UnknownTable { .. })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_token_ranges() {
+        setup_tracing();
+        let cluster = mock_cluster_state_for_token_aware_tests().await;
+        let port = |id| id_to_invalid_addr(id).port();
+
+        let ranges = cluster.token_ranges(KEYSPACE_SS_RF_2);
+        // Mock ring consists of 17 distinct tokens: 50, 100, ..., 800, 900.
+        assert_eq!(ranges.len(), 17);
+
+        let (first_range, first_replicas) = &ranges[0];
+        assert_eq!(
+            *first_range,
+            TokenRange::new(Token::new(900), Token::new(50))
+        );
+        assert!(first_range.is_wrapping());
+        assert_eq!(
+            first_replicas
+                .iter()
+                .map(|(node, _shard)| node.address.port())
+                .collect::<Vec<_>>(),
+            [port(A), port(B)]
+        );
+
+        // Ranges are contiguous and cover the whole ring.
+        for ((prev, _), (next, _)) in ranges.iter().zip(ranges.iter().skip(1)) {
+            assert_eq!(prev.end(), next.start());
+            assert!(!next.is_wrapping());
+        }
+        assert_eq!(ranges.last().unwrap().0.end(), first_range.start());
+
+        for (range, replicas) in &ranges {
+            assert_eq!(
+                replicas,
+                &cluster.replicas_for_token(KEYSPACE_SS_RF_2, range.end())
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ring_version() {
+        setup_tracing();
+        let metadata_with_host_ids = |state: &ClusterState| {
+            let mut metadata = mock_metadata_for_token_aware_tests();
+            for peer in &mut metadata.peers {
+                peer.host_id = state
+                    .get_nodes_info()
+                    .iter()
+                    .find(|node| node.address == peer.address)
+                    .unwrap()
+                    .host_id;
+            }
+            metadata
+        };
+
+        let initial = mock_cluster_state_for_token_aware_tests().await;
+        assert_eq!(initial.ring_version(), 0);
+
+        // Unchanged ring keeps the version and reuses the precomputed replica sets.
+        let unchanged = MockClusterStateBuilder::new(metadata_with_host_ids(&initial))
+            .previous(&initial)
+            .build()
+            .await;
+        assert_eq!(unchanged.ring_version(), 0);
+        assert!(std::ptr::eq(
+            initial.replica_locator().ring(),
+            unchanged.replica_locator().ring()
+        ));
+
+        // Changing tokens of a node bumps the version.
+        let mut metadata = metadata_with_host_ids(&unchanged);
+        metadata.peers[0].tokens.push(Token::new(1000));
+        let changed = MockClusterStateBuilder::new(metadata)
+            .previous(&unchanged)
+            .build()
+            .await;
+        assert_eq!(changed.ring_version(), 1);
+        assert!(!std::ptr::eq(
+            unchanged.replica_locator().ring(),
+            changed.replica_locator().ring()
+        ));
+    }
+}
@@ -0,0 +1,251 @@
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures::Stream;
+use futures::stream::BoxStream;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use crate::policies::host_listener::HostEvent;
+
+use super::{ClusterStateDiff, Node};
+
+/// Number of topology events buffered for each subscriber.
+/// A subscriber that falls further behind receives [TopologyEvent::EventsMissed].
+pub(crate) const TOPOLOGY_EVENTS_CHANNEL_SIZE: usize = 256;
+
+/// A change in the cluster topology, as observed by the driver.
+///
+/// Events are derived both from server EVENTs received on the control connection
+/// and from periodic metadata refreshes, as well as from connection pools
+/// reporting a node as reachable or unreachable. Changes of the control connection
+/// itself are reported too.
+/// Only nodes accepted by the configured [HostFilter](crate::policies::host_filter::HostFilter)
+/// are reported.
+///
+/// Obtained from [Session::topology_events](crate::client::session::Session::topology_events).
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TopologyEvent {
+    /// A new node has joined the cluster.
+    NodeAdded {
+        /// ID of the node.
+        host_id: Uuid,
+        /// Address of the node.
+        address: SocketAddr,
+        /// Datacenter of the node, if known.
+        datacenter: Option<String>,
+        /// Rack of the node, if known.
+        rack: Option<String>,
+    },
+
+    /// A known node has left the cluster.
+    NodeRemoved {
+        /// ID of the node.
+        host_id: Uuid,
+        /// Last known address of the node.
+        address: SocketAddr,
+    },
+
+    /// A known node has become reachable.
+    ///
+    /// Newly added nodes are optimistically reported as up right after [TopologyEvent::NodeAdded].
+    NodeUp {
+        /// ID of the node.
+        host_id: Uuid,
+        /// Address of the node.
+        address: SocketAddr,
+    },
+
+    /// A known node has become unreachable.
+    ///
+    /// Precedes [TopologyEvent::NodeRemoved] if the removed node was up.
+    NodeDown {
+        /// ID of the node.
+        host_id: Uuid,
+        /// Address of the node.
+        address: SocketAddr,
+    },
+
+    /// A known node has changed its address.
+    AddressChanged {
+        /// ID of the node.
+        host_id: Uuid,
+        /// Previous address of the node.
+        old_address: SocketAddr,
+        /// Current address of the node.
+        new_address: SocketAddr,
+    },
+
+    /// A known node has moved to a different datacenter or rack.
+    LocationChanged {
+        /// ID of the node.
+        host_id: Uuid,
+        /// Address of the node.
+        address: SocketAddr,
+        /// Previous datacenter of the node.
+        old_datacenter: Option<String>,
+        /// Previous rack of the node.
+        old_rack: Option<String>,
+        /// Current datacenter of the node.
+        new_datacenter: Option<String>,
+        /// Current rack of the node.
+        new_rack: Option<String>,
+    },
+
+    /// A metadata refresh changed the cluster state.
+    ///
+    /// Sent after the new state becomes visible through
+    /// [Session::get_cluster_state](crate::client::session::Session::get_cluster_state),
+    /// and after the node events describing the same refresh.
+    ClusterStateChanged {
+        /// Differences between the previous and the new cluster state.
+        diff: Arc<ClusterStateDiff>,
+    },
+
+    /// The control connection, used to fetch metadata and receive server events, broke.
+    ///
+    /// Until [TopologyEvent::ControlConnectionMoved] follows, topology changes are only
+    /// observed by periodic attempts to reestablish the control connection.
+    ControlConnectionLost {
+        /// Address of the node the control connection was opened to.
+        address: SocketAddr,
+        /// Error which broke the control connection.
+        error: String,
+    },
+
+    /// The control connection was established to a node, replacing the previous one.
+    ///
+    /// Sent when a standby control connection is promoted, when the control connection
+    /// is reestablished after [TopologyEvent::ControlConnectionLost] (possibly to the same node),
+    /// or when it is moved away from a node rejected by the host filter.
+    ControlConnectionMoved {
+        /// Address of the node the previous control connection was opened to.
+        old_address: SocketAddr,
+        /// Address of the node the control connection is opened to.
+        new_address: SocketAddr,
+    },
+
+    /// The subscriber did not keep up and `count` events were dropped.
+    ///
+    /// State derived from previous events may be stale. Consumers should
+    /// rebuild it from [Session::get_cluster_state](crate::client::session::Session::get_cluster_state).
+    EventsMissed {
+        /// Number of dropped events.
+        count: u64,
+    },
+}
+
+impl TopologyEvent {
+    /// Translates a host event concerning `node` into a topology event.
+    pub(crate) fn from_host_event(node: &Node, addr: SocketAddr, event: &HostEvent) -> Self {
+        let host_id = node.host_id;
+        match event {
+            HostEvent::Added => TopologyEvent::NodeAdded {
+                host_id,
+                address: addr,
+                datacenter: node.datacenter.clone(),
+                rack: node.rack.clone(),
+            },
+            HostEvent::Removed => TopologyEvent::NodeRemoved {
+                host_id,
+                address: addr,
+            },
+            HostEvent::AddressChanged {
+                old_address,
+                new_address,
+            } => TopologyEvent::AddressChanged {
+                host_id,
+                old_address: *old_address,
+                new_address: *new_address,
+            },
+            HostEvent::Up => TopologyEvent::NodeUp {
+                host_id,
+                address: addr,
+            },
+            HostEvent::Down => TopologyEvent::NodeDown {
+                host_id,
+                address: addr,
+            },
+        }
+    }
+}
+
+/// Stream of [TopologyEvent]s returned by
+/// [Session::topology_events](crate::client::session::Session::topology_events).
+///
+/// Only events which happen after the stream was created are yielded.
+/// The stream ends when the session is dropped.
+pub struct TopologyEventStream {
+    inner: BoxStream<'static, TopologyEvent>,
+}
+
+impl TopologyEventStream {
+    pub(crate) fn new(receiver: broadcast::Receiver<TopologyEvent>) -> Self {
+        let inner = futures::stream::unfold(receiver, |mut receiver| async move {
+            let event = match receiver.recv().await {
+                Ok(event) => event,
+                Err(broadcast::error::RecvError::Lagged(count)) => {
+                    TopologyEvent::EventsMissed { count }
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            };
+            Some((event, receiver))
+        });
+        Self {
+            inner: Box::pin(inner),
+        }
+    }
+}
+
+impl Stream for TopologyEventStream {
+    type Item = TopologyEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+impl std::fmt::Debug for TopologyEventStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TopologyEventStream")
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use tokio::sync::broadcast;
+    use uuid::Uuid;
+
+    use super::{TopologyEvent, TopologyEventStream};
+
+    fn up(n: u16) -> TopologyEvent {
+        TopologyEvent::NodeUp {
+            host_id: Uuid::nil(),
+            address: ([127, 0, 0, 1], n).into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn topology_event_stream_reports_lag_and_end() {
+        let (sender, receiver) = broadcast::channel(2);
+        let mut stream = TopologyEventStream::new(receiver);
+
+        for n in 0..4 {
+            sender.send(up(n)).unwrap();
+        }
+        drop(sender);
+
+        assert_eq!(
+            stream.next().await,
+            Some(TopologyEvent::EventsMissed { count: 2 })
+        );
+        assert_eq!(stream.next().await, Some(up(2)));
+        assert_eq!(stream.next().await, Some(up(3)));
+        assert_eq!(stream.next().await, None);
+    }
+}
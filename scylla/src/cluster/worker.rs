@@ -1,27 +1,37 @@
 use crate::client::session::TABLET_CHANNEL_SIZE;
 use crate::cluster::metadata::reader::ControlConnectionEvent;
-use crate::cluster::{KnownNode, Node};
+use crate::cluster::{ClusterStateDiff, KnownNode, Node};
 use crate::errors::{MetadataError, NewSessionError, RequestAttemptError, UseKeyspaceError};
 use crate::frame::response::event::Event;
 use crate::network::{ConnectivityChangeEvent, PoolConfig, VerifiedKeyspaceName};
+use crate::observability::diagnostics::ControlConnectionDiagnostics;
 #[cfg(feature = "metrics")]
 use crate::observability::metrics::Metrics;
+use crate::policies::address_translator::PeerAddressPreference;
 use crate::policies::host_filter::HostFilter;
 use crate::policies::host_listener::{HostEvent, HostEventContext, HostListener};
-use crate::routing::locator::tablets::{RawTablet, TabletsInfo};
-
+use crate::policies::keyspace_filter::KeyspaceFilter;
+use crate::policies::resolver::Resolver;
+use crate::policies::tablet_listener::{TabletListener, TabletsUpdateEvent};
+use crate::routing::locator::tablets::RawTablet;
+use crate::routing::partitioner::CustomPartitioners;
+use crate::utils::runtime;
+
+use crate::observability::log_targets;
 use arc_swap::ArcSwap;
 use futures::future::join_all;
 use futures::{FutureExt, future::RemoteHandle};
 use scylla_cql::frame::response::result::TableSpec;
 use std::collections::HashMap;
-use std::sync::Arc;
-use std::time::Duration;
-use tracing::{debug, error, info, trace};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, error, info, trace, warn};
 use uuid::Uuid;
 
 use super::metadata::reader::MetadataReader;
 use super::state::{ClusterState, ClusterStateNeatDebug};
+use super::topology::{TOPOLOGY_EVENTS_CHANNEL_SIZE, TopologyEvent, TopologyEventStream};
 
 /// Cluster manages up to date information and connections to database nodes.
 /// All state can be accessed by cloning Arc<ClusterState> in the `state` field
@@ -33,9 +43,24 @@ pub(crate) struct Cluster {
     refresh_channel: tokio::sync::mpsc::Sender<RefreshRequest>,
     use_keyspace_channel: tokio::sync::mpsc::Sender<UseKeyspaceRequest>,
 
+    // Used to create new subscriptions to topology events.
+    topology_events: tokio::sync::broadcast::Sender<TopologyEvent>,
+
+    // Updated by the worker after each metadata refresh, read for diagnostics.
+    metadata_status: Arc<Mutex<MetadataStatus>>,
+
     _worker_handle: RemoteHandle<()>,
 }
 
+/// State of metadata fetching, as last observed by [ClusterWorker].
+struct MetadataStatus {
+    control_connection: ControlConnectionDiagnostics,
+    last_full_refresh: Instant,
+    // Set if the session started without real metadata, cleared by the first
+    // successful full refresh.
+    degraded: bool,
+}
+
 /// Enables printing [Cluster] struct in a neat way, by skipping the rather useless
 /// print of channels state and printing [ClusterState] neatly.
 pub(crate) struct ClusterNeatDebug<'a>(pub(crate) &'a Cluster);
@@ -95,9 +120,25 @@ struct ClusterWorker {
     // connections
     host_filter: Option<Arc<dyn HostFilter>>,
 
+    // Further restricts nodes which internal requests are sent to.
+    internal_requests_host_filter: Option<Arc<dyn HostFilter>>,
+
+    // User-provided partitioners, used to compute tokens for tables
+    // employing partitioners which are not supported natively.
+    custom_partitioners: CustomPartitioners,
+
     // The host listener allows to listen for topology and node status changes.
     host_listener: Option<Arc<dyn HostListener>>,
 
+    // The tablet listener is notified when tablet maps change.
+    tablet_listener: Option<Arc<dyn TabletListener>>,
+
+    // Topology events are broadcast to all subscribers of this channel.
+    topology_events: tokio::sync::broadcast::Sender<TopologyEvent>,
+
+    // Status of metadata fetching, shared with `Cluster`.
+    metadata_status: Arc<Mutex<MetadataStatus>>,
+
     // This value determines how frequently the cluster
     // worker will refresh the cluster metadata
     cluster_metadata_refresh_interval: Duration,
@@ -108,6 +149,8 @@ struct ClusterWorker {
 
 #[derive(Debug)]
 struct RefreshRequest {
+    // If set, only metadata of this keyspace is refreshed.
+    keyspace: Option<String>,
     response_chan: tokio::sync::oneshot::Sender<Result<(), MetadataError>>,
 }
 
@@ -123,11 +166,21 @@ impl Cluster {
         known_nodes: Vec<KnownNode>,
         pool_config: PoolConfig,
         keyspaces_to_fetch: Vec<String>,
+        keyspace_filter: Option<Arc<dyn KeyspaceFilter>>,
         fetch_schema_metadata: bool,
         metadata_request_serverside_timeout: Option<Duration>,
+        initial_metadata_fetch_timeout: Option<Duration>,
+        allow_degraded_startup: bool,
+        control_connection_standbys: usize,
         hostname_resolution_timeout: Option<Duration>,
+        hostname_resolution_ttl: Option<Duration>,
+        resolver: Arc<dyn Resolver>,
+        peer_address_preference: PeerAddressPreference,
         host_filter: Option<Arc<dyn HostFilter>>,
+        internal_requests_host_filter: Option<Arc<dyn HostFilter>>,
+        custom_partitioners: CustomPartitioners,
         host_listener: Option<Arc<dyn HostListener>>,
+        tablet_listener: Option<Arc<dyn TabletListener>>,
         cluster_metadata_refresh_interval: Duration,
         tablet_receiver: tokio::sync::mpsc::Receiver<(TableSpec<'static>, RawTablet)>,
         #[cfg(feature = "metrics")] metrics: Arc<Metrics>,
@@ -141,21 +194,55 @@ impl Cluster {
         // or drop events (if we decide to do so if the channel is full). Both options are bad.
         let (connectivity_events_sender, connectivity_events_receiver) =
             tokio::sync::mpsc::unbounded_channel();
+        let (topology_events, _) = tokio::sync::broadcast::channel(TOPOLOGY_EVENTS_CHANNEL_SIZE);
 
         let mut metadata_reader = MetadataReader::new(
             known_nodes,
             hostname_resolution_timeout,
+            hostname_resolution_ttl,
+            resolver,
+            peer_address_preference,
             pool_config.connection_config.clone(),
             metadata_request_serverside_timeout,
             keyspaces_to_fetch,
+            keyspace_filter,
             fetch_schema_metadata,
             &host_filter,
+            control_connection_standbys,
         )
         .await?;
 
         let mut node_status = HashMap::new();
 
-        let metadata = metadata_reader.read_metadata(true).await?;
+        let initial_read = metadata_reader.read_metadata(true);
+        let initial_read = match initial_metadata_fetch_timeout {
+            Some(timeout) => match runtime::timeout(timeout, initial_read).await {
+                Ok(result) => result.map_err(NewSessionError::from),
+                Err(_elapsed) => Err(NewSessionError::InitialMetadataFetchTimeout(timeout)),
+            },
+            None => initial_read.await.map_err(NewSessionError::from),
+        };
+        let (metadata, degraded) = match initial_read {
+            // The reader falls back to dummy metadata by itself if the control connection
+            // was established, but fetching metadata over it failed.
+            Ok(metadata) => (metadata, !metadata_reader.control_connection_works()),
+            Err(err) if allow_degraded_startup => {
+                warn!(
+                    target: log_targets::TOPOLOGY,
+                    error = %err,
+                    "Initial metadata fetch failed, starting in degraded mode with metadata \
+                    consisting only of the initial peer list and dummy tokens. \
+                    Metadata fetch will be retried periodically."
+                );
+                (metadata_reader.dummy_metadata(), true)
+            }
+            Err(err) => return Err(err),
+        };
+        let metadata_status = Arc::new(Mutex::new(MetadataStatus {
+            control_connection: metadata_reader.control_connection_diagnostics(),
+            last_full_refresh: Instant::now(),
+            degraded,
+        }));
         let cluster_state = ClusterState::new(
             metadata,
             &pool_config,
@@ -164,14 +251,19 @@ impl Cluster {
                 ClusterWorker::handle_topology_changes(
                     old_nodes,
                     new_nodes,
-                    host_listener.as_deref(),
+                    HostEventSink {
+                        host_listener: host_listener.as_deref(),
+                        topology_events: &topology_events,
+                    },
                     &mut node_status,
                 )
             },
             &None,
             host_filter.as_deref(),
+            internal_requests_host_filter.as_deref(),
             &connectivity_events_sender,
-            TabletsInfo::new(),
+            &custom_partitioners,
+            None,
             &HashMap::new(),
             #[cfg(feature = "metrics")]
             &metrics,
@@ -198,7 +290,12 @@ impl Cluster {
             used_keyspace: None,
 
             host_filter,
+            internal_requests_host_filter,
+            custom_partitioners,
             host_listener,
+            tablet_listener,
+            topology_events: topology_events.clone(),
+            metadata_status: Arc::clone(&metadata_status),
             cluster_metadata_refresh_interval,
 
             #[cfg(feature = "metrics")]
@@ -206,12 +303,14 @@ impl Cluster {
         };
 
         let (fut, worker_handle) = worker.work().remote_handle();
-        tokio::spawn(fut);
+        runtime::spawn(fut);
 
         let result = Cluster {
             state: cluster_state,
             refresh_channel: refresh_sender,
             use_keyspace_channel: use_keyspace_sender,
+            topology_events,
+            metadata_status,
             _worker_handle: worker_handle,
         };
 
@@ -222,11 +321,42 @@ impl Cluster {
         self.state.load_full()
     }
 
+    /// Returns the state of the control connection and the age of cluster metadata.
+    pub(crate) fn metadata_status(&self) -> (ControlConnectionDiagnostics, Duration) {
+        let status = self.metadata_status.lock().unwrap();
+        (
+            status.control_connection.clone(),
+            status.last_full_refresh.elapsed(),
+        )
+    }
+
+    /// Returns true if the session runs on dummy metadata, because the initial metadata
+    /// fetch failed and no full refresh has succeeded since.
+    pub(crate) fn is_metadata_degraded(&self) -> bool {
+        self.metadata_status.lock().unwrap().degraded
+    }
+
+    pub(crate) fn subscribe_topology_events(&self) -> TopologyEventStream {
+        TopologyEventStream::new(self.topology_events.subscribe())
+    }
+
     pub(crate) async fn refresh_metadata(&self) -> Result<(), MetadataError> {
+        self.send_refresh_request(None).await
+    }
+
+    pub(crate) async fn refresh_keyspace_metadata(
+        &self,
+        keyspace: String,
+    ) -> Result<(), MetadataError> {
+        self.send_refresh_request(Some(keyspace)).await
+    }
+
+    async fn send_refresh_request(&self, keyspace: Option<String>) -> Result<(), MetadataError> {
         let (response_sender, response_receiver) = tokio::sync::oneshot::channel();
 
         self.refresh_channel
             .send(RefreshRequest {
+                keyspace,
                 response_chan: response_sender,
             })
             .await
@@ -266,20 +396,29 @@ impl ClusterWorker {
         let mut last_refresh_time = Instant::now();
 
         loop {
+            self.report_control_connection_changes();
+
             let mut cur_request: Option<RefreshRequest> = None;
+            // If set, only metadata of this keyspace needs to be refreshed.
+            let mut keyspace_to_refresh: Option<String> = None;
 
-            // Wait until it's time for the next refresh
+            // Wait until it's time for the next refresh. Degraded metadata is refreshed
+            // as often as a broken control connection is repaired.
             let sleep_until: Instant = last_refresh_time
-                .checked_add(if self.metadata_reader.control_connection_works() {
-                    self.cluster_metadata_refresh_interval
-                } else {
-                    control_connection_repair_duration
-                })
+                .checked_add(
+                    if self.metadata_reader.control_connection_works()
+                        && !self.metadata_status.lock().unwrap().degraded
+                    {
+                        self.cluster_metadata_refresh_interval
+                    } else {
+                        control_connection_repair_duration
+                    },
+                )
                 .unwrap_or_else(Instant::now);
 
             let mut tablets = Vec::new();
 
-            let sleep_future = tokio::time::sleep_until(sleep_until);
+            let sleep_future = runtime::sleep_until(sleep_until);
             tokio::pin!(sleep_future);
 
             tokio::select! {
@@ -289,13 +428,20 @@ impl ClusterWorker {
 
                 maybe_refresh_request = self.refresh_channel.recv() => {
                     match maybe_refresh_request {
-                        Some(request) => cur_request = Some(request),
+                        Some(request) => {
+                            keyspace_to_refresh = request.keyspace.clone();
+                            cur_request = Some(request);
+                        }
                         None => return, // If refresh_channel was closed then cluster was dropped, we can stop working
                     }
                 }
 
                 tablets_count = self.tablets_channel.recv_many(&mut tablets, TABLET_CHANNEL_SIZE) => {
-                    tracing::trace!("Performing tablets update - received {} tablets", tablets_count);
+                    tracing::trace!(
+                        target: log_targets::TOPOLOGY,
+                        "Performing tablets update - received {} tablets",
+                        tablets_count,
+                    );
                     if tablets_count == 0 {
                         // If the channel was closed then the cluster was dropped, we can stop working
                         return;
@@ -308,7 +454,8 @@ impl ClusterWorker {
                     // - Topology information update is similar to tablet update - it creates a new ClusterState
                     //   and replaces the old one.
                     // The disadvantage is that we need to have 2 copies of ClusterState, but this happens
-                    // anyway during topology update.
+                    // anyway during topology update. The precomputed token ring replica sets are shared
+                    // between the copies, so they are not copied.
                     //
                     // An alternative solution would be to use some synchronization primitives to update tablet info
                     // in place. This solution avoids ClusterState cloning but:
@@ -319,9 +466,13 @@ impl ClusterWorker {
                     // I decided to stick with the approach that fits with the driver.
                     // Apart from the reasons above, it is much easier to reason about concurrency etc
                     // when reading the code in other parts of the driver.
-                    let mut new_cluster_state: ClusterState = self.cluster_state.load().as_ref().clone();
+                    let cluster_state = self.cluster_state.load_full();
+                    let mut new_cluster_state: ClusterState = cluster_state.as_ref().clone();
                     new_cluster_state.update_tablets(tablets);
-                    self.update_cluster_state(Arc::new(new_cluster_state));
+                    let changed_tables = new_cluster_state.inherit_tablets_version(&cluster_state);
+                    let new_cluster_state = Arc::new(new_cluster_state);
+                    self.update_cluster_state(Arc::clone(&new_cluster_state));
+                    self.notify_tablets_updated(new_cluster_state, changed_tables);
 
                     continue;
                 }
@@ -333,11 +484,27 @@ impl ClusterWorker {
                             // The first reconnect attempt will be immediate (by attempting metadata refresh below),
                             // and if it does not succeed, then `ControlConnectionState` will be set to `Broken`, so
                             // subsequent attempts will be issued every second.
+                            // If a standby control connection was promoted, the refresh
+                            // fetches the metadata missed in the meantime.
+                        },
+                        ControlConnectionEvent::StandbyBroken => {
+                            // A replacement is opened after the next successful refresh.
+                            self.metadata_status.lock().unwrap().control_connection =
+                                self.metadata_reader.control_connection_diagnostics();
+                            continue;
                         },
                         ControlConnectionEvent::ServerEvent(event) => {
-                            debug!("Received server event: {:?}", event);
+                            debug!(
+                                target: log_targets::TOPOLOGY,
+                                "Received server event: {:?}",
+                                event,
+                            );
                             match event {
                                 Event::TopologyChange(_) => (), // Refresh immediately
+                                Event::SchemaChange(schema_change) => {
+                                    // Refresh only the affected keyspace.
+                                    keyspace_to_refresh = Some(schema_change.keyspace_name().to_owned());
+                                }
                                 Event::StatusChange(_status) => {
                                     // TODO: Tracking status using events is unreliable because of
                                     // the possibility of losing events when control connection is broken.
@@ -350,7 +517,6 @@ impl ClusterWorker {
                                     //   then try to open new connections.
                                     continue;
                                 },
-                                _ => continue, // Don't go to refreshing
                             }
                         }
                     }
@@ -363,7 +529,11 @@ impl ClusterWorker {
                         // However, if it is closed, we can't do anything useful, so just stop working.
                         return;
                     };
-                    debug!("Received connectivity event: {:?}", event);
+                    debug!(
+                        target: log_targets::TOPOLOGY,
+                        "Received connectivity event: {:?}",
+                        event,
+                    );
 
                     self.handle_connectivity_change_event(&event);
 
@@ -377,7 +547,7 @@ impl ClusterWorker {
 
                             let cluster_state = self.cluster_state.load_full();
                             let use_keyspace_future = Self::handle_use_keyspace_request(cluster_state, request);
-                            tokio::spawn(use_keyspace_future);
+                            runtime::spawn(use_keyspace_future);
                         },
                         None => return, // If use_keyspace_channel was closed then cluster was dropped, we can stop working
                     }
@@ -386,10 +556,37 @@ impl ClusterWorker {
                 }
             }
 
+            if let Some(keyspace) = keyspace_to_refresh {
+                debug!(
+                    target: log_targets::TOPOLOGY,
+                    "Requesting metadata refresh of keyspace {}",
+                    keyspace,
+                );
+                match self.perform_keyspace_refresh(&keyspace).await {
+                    Ok(()) => {
+                        if let Some(request) = cur_request {
+                            // We can ignore sending error - if no one waits for the response we can drop it
+                            let _ = request.response_chan.send(Ok(()));
+                        }
+                        continue;
+                    }
+                    Err(err) => {
+                        // The control connection is likely broken. A full refresh
+                        // takes care of reestablishing it.
+                        debug!(
+                            target: log_targets::TOPOLOGY,
+                            "Failed to refresh metadata of keyspace {}: {}. Falling back to full refresh",
+                            keyspace, err
+                        );
+                    }
+                }
+            }
+
             // Perform the refresh
-            debug!("Requesting metadata refresh");
+            debug!(target: log_targets::TOPOLOGY, "Requesting metadata refresh");
             last_refresh_time = Instant::now();
             let refresh_res = self.perform_refresh().await;
+            self.update_metadata_status(refresh_res.is_ok());
 
             // Send refresh result if there was a request
             if let Some(request) = cur_request {
@@ -399,6 +596,30 @@ impl ClusterWorker {
         }
     }
 
+    /// Sends changes of the control connection to topology event subscribers.
+    fn report_control_connection_changes(&mut self) {
+        let events = self.metadata_reader.take_control_connection_events();
+        if events.is_empty() {
+            return;
+        }
+        self.metadata_status.lock().unwrap().control_connection =
+            self.metadata_reader.control_connection_diagnostics();
+        for event in events {
+            debug!(target: log_targets::TOPOLOGY, "Control connection changed: {:?}", event);
+            // Sending fails only if there are no subscribers, which is fine.
+            let _ = self.topology_events.send(event);
+        }
+    }
+
+    fn update_metadata_status(&self, refreshed: bool) {
+        let mut status = self.metadata_status.lock().unwrap();
+        status.control_connection = self.metadata_reader.control_connection_diagnostics();
+        if refreshed {
+            status.last_full_refresh = Instant::now();
+            status.degraded = false;
+        }
+    }
+
     async fn handle_use_keyspace_request(
         cluster_state: Arc<ClusterState>,
         request: UseKeyspaceRequest,
@@ -423,40 +644,78 @@ impl ClusterWorker {
         use_keyspace_result(use_keyspace_results.into_iter())
     }
 
+    async fn perform_keyspace_refresh(&mut self, keyspace: &str) -> Result<(), MetadataError> {
+        if !self.metadata_reader.fetches_keyspace(keyspace) {
+            trace!(
+                target: log_targets::TOPOLOGY,
+                "Keyspace {} is not tracked, skipping its refresh",
+                keyspace,
+            );
+            return Ok(());
+        }
+
+        let keyspace_metadata = self
+            .metadata_reader
+            .read_keyspace_metadata(keyspace)
+            .await?;
+        let cluster_state: Arc<ClusterState> = self.cluster_state.load_full();
+
+        let mut new_cluster_state = cluster_state
+            .with_refreshed_keyspace(keyspace, keyspace_metadata)
+            .await;
+        new_cluster_state.inherit_ring_version(&cluster_state);
+        let changed_tables = new_cluster_state.inherit_tablets_version(&cluster_state);
+        let new_cluster_state = Arc::new(new_cluster_state);
+
+        self.update_cluster_state(Arc::clone(&new_cluster_state));
+        self.broadcast_cluster_state_diff(&cluster_state, &new_cluster_state);
+        self.notify_tablets_updated(new_cluster_state, changed_tables);
+
+        Ok(())
+    }
+
     async fn perform_refresh(&mut self) -> Result<(), MetadataError> {
         // Read latest Metadata
         let metadata = self.metadata_reader.read_metadata(false).await?;
         let cluster_state: Arc<ClusterState> = self.cluster_state.load_full();
 
-        let new_cluster_state = Arc::new(
-            ClusterState::new(
-                metadata,
-                &self.pool_config,
-                &cluster_state.known_peers,
-                &mut |old_nodes, new_nodes| {
-                    ClusterWorker::handle_topology_changes(
-                        old_nodes,
-                        new_nodes,
-                        self.host_listener.as_deref(),
-                        &mut self.node_status,
-                    )
-                },
-                &self.used_keyspace,
-                self.host_filter.as_deref(),
-                &self.connectivity_events_sender,
-                cluster_state.locator.tablets.clone(),
-                &cluster_state.keyspaces,
-                #[cfg(feature = "metrics")]
-                &self.metrics,
-            )
-            .await,
-        );
+        let mut new_cluster_state = ClusterState::new(
+            metadata,
+            &self.pool_config,
+            &cluster_state.known_peers,
+            &mut |old_nodes, new_nodes| {
+                ClusterWorker::handle_topology_changes(
+                    old_nodes,
+                    new_nodes,
+                    HostEventSink {
+                        host_listener: self.host_listener.as_deref(),
+                        topology_events: &self.topology_events,
+                    },
+                    &mut self.node_status,
+                )
+            },
+            &self.used_keyspace,
+            self.host_filter.as_deref(),
+            self.internal_requests_host_filter.as_deref(),
+            &self.connectivity_events_sender,
+            &self.custom_partitioners,
+            Some(&cluster_state.locator),
+            &cluster_state.keyspaces,
+            #[cfg(feature = "metrics")]
+            &self.metrics,
+        )
+        .await;
+        new_cluster_state.inherit_ring_version(&cluster_state);
+        let changed_tables = new_cluster_state.inherit_tablets_version(&cluster_state);
+        let new_cluster_state = Arc::new(new_cluster_state);
 
         new_cluster_state
             .wait_until_all_pools_are_initialized()
             .await;
 
-        self.update_cluster_state(new_cluster_state);
+        self.update_cluster_state(Arc::clone(&new_cluster_state));
+        self.broadcast_cluster_state_diff(&cluster_state, &new_cluster_state);
+        self.notify_tablets_updated(new_cluster_state, changed_tables);
 
         Ok(())
     }
@@ -465,13 +724,51 @@ impl ClusterWorker {
         self.cluster_state.store(new_cluster_state);
     }
 
-    /// Handle node addition/removal/address changes.
+    /// Sends a [TopologyEvent::ClusterStateChanged] to topology event subscribers,
+    /// if there are any and if the state changed.
+    fn broadcast_cluster_state_diff(&self, old: &ClusterState, new: &ClusterState) {
+        // The diff compares whole schemas, so skip it if nobody listens.
+        if self.topology_events.receiver_count() == 0 {
+            return;
+        }
+        let diff = ClusterStateDiff::between(old, new);
+        if !diff.is_empty() {
+            // Sending fails only if there are no subscribers, which is fine.
+            let _ = self
+                .topology_events
+                .send(TopologyEvent::ClusterStateChanged {
+                    diff: Arc::new(diff),
+                });
+        }
+    }
+
+    /// Emits a [TabletsUpdateEvent] to the [TabletListener], if configured
+    /// and if any tablet map changed.
+    fn notify_tablets_updated(
+        &self,
+        cluster_state: Arc<ClusterState>,
+        changed_tables: Vec<TableSpec<'static>>,
+    ) {
+        let Some(tablet_listener) = self.tablet_listener.as_deref() else {
+            return;
+        };
+        if changed_tables.is_empty() {
+            return;
+        }
+        tablet_listener.on_tablets_updated(&TabletsUpdateEvent {
+            cluster_state,
+            changed_tables,
+        });
+    }
+
+    /// Handle node addition/removal/address/location changes.
     ///
-    /// Emit respective events to the [HostListener], if configured.
+    /// Emit respective events to the [HostListener], if configured,
+    /// and to topology event subscribers.
     fn handle_topology_changes(
         known_peers: &HashMap<Uuid, Arc<Node>>,
         new_known_peers: &HashMap<Uuid, Arc<Node>>,
-        host_listener: Option<&dyn HostListener>,
+        sink: HostEventSink<'_>,
         node_status: &mut HashMap<Uuid, NodeConnectivityStatus>,
     ) {
         // Nodes that were previously in the cluster but are not present anymore.
@@ -524,47 +821,57 @@ impl ClusterWorker {
                     })
                     .map(|new_node| (old_node, new_node))
             });
+        // Nodes that were present in both old and new cluster state, but have moved
+        // to another datacenter or rack.
+        let nodes_with_changed_location = known_peers
+            .iter()
+            .filter(|(_host_id, old_node)| old_node.is_enabled())
+            .filter_map(|(host_id, old_node)| {
+                new_known_peers
+                    .get(host_id)
+                    .filter(|new_node| {
+                        new_node.is_enabled()
+                            && (old_node.datacenter != new_node.datacenter
+                                || old_node.rack != new_node.rack)
+                    })
+                    .map(|new_node| (old_node, new_node))
+            });
 
         // Handle node removal.
         for (host_id, node) in removed_nodes {
             info!(
+                target: log_targets::TOPOLOGY,
                 "Node removed from cluster: {} - {}",
                 node.host_id, node.address,
             );
 
             let Some(connectivity) = node_status.remove(host_id) else {
                 error!(
+                    target: log_targets::TOPOLOGY,
                     "BUG: Inconsistent node status: missing entry for removed node {} - {}",
                     node.host_id, node.address
                 );
                 continue;
             };
 
-            let ctx = HostEventContext {
-                host_id: node.host_id,
-                addr: node.address.into_inner(),
-            };
-            // Notify listener about node removal.
-            let Some(host_listener) = host_listener else {
-                // No listener configured, nothing to do.
-                continue;
-            };
+            let addr = node.address.into_inner();
 
             // First signal DOWN event, if needed.
             match connectivity {
                 NodeConnectivityStatus::Connected => {
-                    host_listener.on_event(&ctx, &HostEvent::Down);
+                    sink.emit(node, addr, &HostEvent::Down);
                 }
                 NodeConnectivityStatus::Unreachable => { /* No need to signal anything */ }
             }
 
             // Then signal REMOVED event.
-            host_listener.on_event(&ctx, &HostEvent::Removed);
+            sink.emit(node, addr, &HostEvent::Removed);
         }
 
         // Handle node address changes.
         for (old_node, new_node) in nodes_with_changed_address {
             info!(
+                target: log_targets::TOPOLOGY,
                 "Node address changed in cluster: {} - {} -> {}",
                 old_node.host_id, old_node.address, new_node.address,
             );
@@ -572,6 +879,7 @@ impl ClusterWorker {
             // Update node address in node_status map.
             let Some(connectivity) = node_status.get_mut(&old_node.host_id) else {
                 error!(
+                    target: log_targets::TOPOLOGY,
                     "BUG: Inconsistent node status: missing entry for node with changed address {} - {}",
                     new_node.host_id, new_node.address
                 );
@@ -580,35 +888,22 @@ impl ClusterWorker {
                 continue;
             };
 
-            // Notify listener about node address change.
-            let Some(host_listener) = host_listener else {
-                // No listener configured, nothing to do.
-                continue;
-            };
-
             // We need to make sure that this event is only signaled when the node is DOWN.
             // Otherwise, we need to first emit DOWN event, then ADDRESS_CHANGED event, then UP event.
 
             if *connectivity == NodeConnectivityStatus::Connected {
                 // First signal DOWN event.
-                let down_ctx = HostEventContext {
-                    host_id: new_node.host_id,
-                    addr: old_node.address.into_inner(),
-                };
-                host_listener.on_event(&down_ctx, &HostEvent::Down);
+                sink.emit(new_node, old_node.address.into_inner(), &HostEvent::Down);
             }
 
-            let ctx = HostEventContext {
-                host_id: new_node.host_id,
-                // We need to decide which address to send in the context - old or new.
-                // I decided to send the new address, as it is more useful - after the address change
-                // the driver will use the new address to connect to the node.
-                // Both addresses are sent in the AddressChanged event itself.
-                addr: new_node.address.into_inner(),
-            };
             // Signal ADDRESS_CHANGED event.
-            host_listener.on_event(
-                &ctx,
+            // We need to decide which address to send in the context - old or new.
+            // I decided to send the new address, as it is more useful - after the address change
+            // the driver will use the new address to connect to the node.
+            // Both addresses are sent in the AddressChanged event itself.
+            sink.emit(
+                new_node,
+                new_node.address.into_inner(),
                 &HostEvent::AddressChanged {
                     old_address: old_node.address.into_inner(),
                     new_address: new_node.address.into_inner(),
@@ -617,23 +912,48 @@ impl ClusterWorker {
 
             if *connectivity == NodeConnectivityStatus::Connected {
                 // We first signaled artificial DOWN event, so now we must signal UP event.
-                let up_ctx = HostEventContext {
-                    host_id: new_node.host_id,
-                    addr: new_node.address.into_inner(),
-                };
-                host_listener.on_event(&up_ctx, &HostEvent::Up);
+                sink.emit(new_node, new_node.address.into_inner(), &HostEvent::Up);
             }
         }
 
+        // Handle datacenter/rack changes.
+        // The host listener has no corresponding event, so these are only broadcast.
+        for (old_node, new_node) in nodes_with_changed_location {
+            info!(
+                target: log_targets::TOPOLOGY,
+                "Node location changed in cluster: {} - {:?}/{:?} -> {:?}/{:?}",
+                new_node.host_id,
+                old_node.datacenter,
+                old_node.rack,
+                new_node.datacenter,
+                new_node.rack,
+            );
+
+            sink.broadcast(TopologyEvent::LocationChanged {
+                host_id: new_node.host_id,
+                address: new_node.address.into_inner(),
+                old_datacenter: old_node.datacenter.clone(),
+                old_rack: old_node.rack.clone(),
+                new_datacenter: new_node.datacenter.clone(),
+                new_rack: new_node.rack.clone(),
+            });
+        }
+
         // Handle node addition.
         for (&host_id, node) in added_nodes {
-            info!("Node added to cluster: {} - {}", node.host_id, node.address,);
+            info!(
+                target: log_targets::TOPOLOGY,
+                "Node added to cluster: {} - {}",
+                node.host_id,
+                node.address,
+            );
 
             // Update node_status map.
             // New nodes are always initially marked as Connected.
             let prev = node_status.insert(host_id, NodeConnectivityStatus::Connected);
             if prev.is_some() {
                 error!(
+                    target: log_targets::TOPOLOGY,
                     "BUG: Inconsistent node status: entry for newly added node {} - {} already existed",
                     node.host_id, node.address
                 );
@@ -642,19 +962,10 @@ impl ClusterWorker {
                 continue;
             }
 
-            // Notify listener about new nodes in the cluster.
-            let Some(host_listener) = host_listener else {
-                continue;
-            };
-
-            let ctx = HostEventContext {
-                host_id: node.host_id,
-                addr: node.address.into_inner(),
-            };
-
-            // First signal ADDED event.
-            host_listener.on_event(&ctx, &HostEvent::Added);
-            host_listener.on_event(&ctx, &HostEvent::Up);
+            // Signal ADDED event, then UP event.
+            let addr = node.address.into_inner();
+            sink.emit(node, addr, &HostEvent::Added);
+            sink.emit(node, addr, &HostEvent::Up);
         }
     }
 
@@ -671,19 +982,22 @@ impl ClusterWorker {
             cluster_state.known_peers.get(&host_id),
             self.node_status.get_mut(&host_id),
         ) else {
-            trace!("Received connectivity change event for unknown host_id: {host_id}");
+            trace!(
+                target: log_targets::TOPOLOGY,
+                "Received connectivity change event for unknown host_id: {host_id}",
+            );
             return;
         };
 
         let addr = node.address.into_inner();
         let maybe_event: Option<HostEvent> = match (*connectivity, event) {
             (NodeConnectivityStatus::Connected, ConnectivityChangeEvent::Lost { .. }) => {
-                debug!("Node is no longer reachable: {}", addr);
+                debug!(target: log_targets::TOPOLOGY, "Node is no longer reachable: {}", addr);
                 *connectivity = NodeConnectivityStatus::Unreachable;
                 Some(HostEvent::Down)
             }
             (NodeConnectivityStatus::Unreachable, ConnectivityChangeEvent::Established { .. }) => {
-                debug!("Node is now reachable again: {}", addr);
+                debug!(target: log_targets::TOPOLOGY, "Node is now reachable again: {}", addr);
                 *connectivity = NodeConnectivityStatus::Connected;
                 Some(HostEvent::Up)
             }
@@ -693,17 +1007,42 @@ impl ClusterWorker {
             }
         };
 
-        let Some(host_listener) = self.host_listener.as_deref() else {
-            // No host listener configured, nothing to do.
-            return;
-        };
         let Some(event) = maybe_event else {
             // No event to signal, nothing to do.
             return;
         };
 
-        let ctx = HostEventContext { host_id, addr };
-        host_listener.on_event(&ctx, &event);
+        HostEventSink {
+            host_listener: self.host_listener.as_deref(),
+            topology_events: &self.topology_events,
+        }
+        .emit(node, addr, &event);
+    }
+}
+
+/// Destinations of host events: the [HostListener], if configured,
+/// and subscribers of [TopologyEvent]s.
+#[derive(Clone, Copy)]
+struct HostEventSink<'a> {
+    host_listener: Option<&'a dyn HostListener>,
+    topology_events: &'a tokio::sync::broadcast::Sender<TopologyEvent>,
+}
+
+impl HostEventSink<'_> {
+    fn emit(&self, node: &Node, addr: SocketAddr, event: &HostEvent) {
+        if let Some(host_listener) = self.host_listener {
+            let ctx = HostEventContext {
+                host_id: node.host_id,
+                addr,
+            };
+            host_listener.on_event(&ctx, event);
+        }
+        self.broadcast(TopologyEvent::from_host_event(node, addr, event));
+    }
+
+    fn broadcast(&self, event: TopologyEvent) {
+        // Sending fails only if there are no subscribers, which is fine.
+        let _ = self.topology_events.send(event);
     }
 }
 
@@ -3,7 +3,7 @@
 use std::error::Error;
 use std::io::ErrorKind;
 use std::net::{AddrParseError, IpAddr, SocketAddr};
-use std::num::ParseIntError;
+use std::num::{NonZeroUsize, ParseIntError};
 use std::sync::Arc;
 use thiserror::Error;
 use uuid::Uuid;
@@ -14,6 +14,9 @@ use crate::frame::response;
 pub use crate::client::pager::{NextPageError, NextRowError};
 
 use crate::statement::prepared::TokenCalculationError;
+// Re-export error type from scan module.
+pub use crate::scan::TableScanError;
+
 // Re-export error types from query_result module.
 pub use crate::response::query_result::{
     FirstRowError, IntoRowsResultError, MaybeFirstRowError, ResultNotRowsError, RowsError,
@@ -156,9 +159,46 @@ pub enum NewSessionError {
     #[error("Failed to perform initial cluster metadata fetch: {0}")]
     MetadataError(#[from] MetadataError),
 
+    /// Initial cluster metadata fetch did not complete within
+    /// [`SessionConfig::initial_metadata_fetch_timeout`](crate::client::session::SessionConfig::initial_metadata_fetch_timeout).
+    #[error(
+        "Initial cluster metadata fetch exceeded a timeout of {}ms",
+        std::time::Duration::as_millis(.0)
+    )]
+    InitialMetadataFetchTimeout(std::time::Duration),
+
     /// 'USE KEYSPACE <>' request failed.
     #[error("'USE KEYSPACE <>' request failed: {0}")]
     UseKeyspaceError(#[from] UseKeyspaceError),
+
+    /// Too few connection pools were full when the warmup configured with
+    /// [`SessionConfig::pool_warmup`](crate::client::session::SessionConfig::pool_warmup) timed out.
+    #[error(
+        "Connection pool warmup timed out with only {full_pools} of {warmed_up_pools} pools full"
+    )]
+    PoolWarmupFailed {
+        /// Number of pools which were full.
+        full_pools: usize,
+        /// Number of pools which were warmed up.
+        warmed_up_pools: usize,
+    },
+}
+
+/// An error returned by [Statement::set_server_timeout](crate::statement::unprepared::Statement::set_server_timeout).
+#[derive(Error, Debug, Clone)]
+#[non_exhaustive]
+pub enum ServerTimeoutError {
+    /// The statement is not a `SELECT`, `INSERT`, `UPDATE` or `DELETE` statement.
+    #[error("USING TIMEOUT can only be added to SELECT, INSERT, UPDATE and DELETE statements")]
+    UnsupportedStatement,
+
+    /// The statement already has a `USING TIMEOUT` clause.
+    #[error("The statement already has a USING TIMEOUT clause")]
+    TimeoutAlreadySet,
+
+    /// The timeout is shorter than a millisecond.
+    #[error("Server-side timeout must be at least 1ms long, got {0:?}")]
+    TimeoutTooShort(std::time::Duration),
 }
 
 /// An error that occurred during `USE KEYSPACE <>` request.
@@ -232,6 +272,15 @@ pub enum SchemaAgreementError {
     RequiredHostAbsent(Uuid),
 }
 
+/// An error that occurred when awaiting stable topology.
+#[derive(Error, Debug, Clone)]
+#[non_exhaustive]
+pub enum StableTopologyError {
+    /// Topology kept changing until the timeout elapsed.
+    #[error("Topology did not settle within {}ms", std::time::Duration::as_millis(.0))]
+    Timeout(std::time::Duration),
+}
+
 /// An error that occurred during tracing info fetch.
 #[derive(Error, Debug, Clone)]
 #[non_exhaustive]
@@ -320,6 +369,10 @@ pub enum MetadataError {
     /// Bad tables metadata.
     #[error("Bad tables metadata: {0}")]
     Tables(#[from] TablesMetadataError),
+
+    /// Bad user-defined functions or aggregates metadata.
+    #[error("Bad functions metadata: {0}")]
+    Functions(#[from] FunctionsMetadataError),
 }
 
 /// An error occurred during metadata fetch.
@@ -463,6 +516,26 @@ pub enum TablesMetadataError {
     },
 }
 
+/// An error that occurred during user-defined functions or aggregates metadata fetch.
+#[derive(Error, Debug, Clone)]
+#[non_exhaustive]
+pub enum FunctionsMetadataError {
+    /// Failed to parse CQL type returned from system_schema.functions
+    /// or system_schema.aggregates query.
+    #[error(
+        "Failed to parse a CQL type returned from system_schema.functions or \
+        system_schema.aggregates query. Type '{typ}', at position {position}: {reason}"
+    )]
+    InvalidCqlType {
+        /// (Invalid) name of the invalid CQL type.
+        typ: String,
+        /// Position in the CQL type string where the error occurred.
+        position: usize,
+        /// Reason why the CQL type name is invalid.
+        reason: String,
+    },
+}
+
 /// Error caused by caller creating an invalid statement.
 #[derive(Error, Debug, Clone)]
 #[error("Invalid statement passed to Session")]
@@ -624,11 +697,85 @@ pub enum TranslationError {
     #[error("An I/O error occurred during address translation: {0}")]
     IoError(Arc<std::io::Error>),
 
+    /// Address translation did not finish within the given time.
+    #[error("Address translation timed out after {0:?}")]
+    Timeout(std::time::Duration),
+
+    /// A temporary failure, e.g. of an external service used by a user-implemented policy.
+    /// Translation may succeed if it is retried.
+    #[error("Temporary address translation failure: {0}")]
+    Transient(CustomTranslationError),
+
     /// Custom error, for example from user-implemented policy.
     #[error(transparent)]
     Custom(#[from] CustomTranslationError),
 }
 
+impl TranslationError {
+    /// Returns true if the translation may succeed if it is retried.
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            TranslationError::IoError(_)
+                | TranslationError::Timeout(_)
+                | TranslationError::Transient(_)
+        )
+    }
+}
+
+/// Error returned when a rule of a
+/// [StaticAddressTranslator](crate::policies::address_translator::StaticAddressTranslator)
+/// is invalid.
+#[non_exhaustive]
+#[derive(Debug, Clone, Error)]
+pub enum TranslationRuleError {
+    /// The address pattern could not be parsed.
+    #[error("Invalid address pattern in translation rule: {0:?}")]
+    InvalidPattern(String),
+
+    /// The rule maps a subnet to a subnet of a different size or address family.
+    #[error("Translation rule {from:?} -> {to:?} maps subnets of different sizes")]
+    MismatchedSubnets {
+        /// The pattern of addresses to translate.
+        from: String,
+        /// The pattern of translated addresses.
+        to: String,
+    },
+}
+
+/// Error returned when the bounds of a [DynamicPoolSize](crate::client::DynamicPoolSize)
+/// are invalid.
+#[non_exhaustive]
+#[derive(Debug, Clone, Error)]
+pub enum DynamicPoolSizeError {
+    /// The minimum number of connections per shard is greater than the maximum.
+    #[error("Minimum number of connections per shard ({min}) exceeds the maximum ({max})")]
+    MinExceedsMax {
+        /// The requested minimum number of connections per shard.
+        min: NonZeroUsize,
+        /// The requested maximum number of connections per shard.
+        max: NonZeroUsize,
+    },
+}
+
+/// Error returned by a [Resolver](crate::policies::resolver::Resolver)
+/// when resolving a contact point.
+#[non_exhaustive]
+#[derive(Debug, Clone, Error)]
+pub enum ResolutionError {
+    /// The lookup failed.
+    #[error("DNS lookup failed: {0}")]
+    LookupFailed(Arc<dyn Error + Send + Sync>),
+
+    /// The lookup returned no addresses or SRV targets.
+    #[error("Empty address list returned by DNS for {0}")]
+    EmptyAddressList(String),
+
+    /// The resolver does not support SRV records.
+    #[error("The resolver does not support SRV records")]
+    SrvNotSupported,
+}
+
 /// An error that occurred during connection setup request execution.
 /// It indicates that request needed to initiate a connection failed.
 #[derive(Error, Debug, Clone)]
@@ -1054,6 +1201,38 @@ pub enum ClusterStateTokenError {
     },
 }
 
+/// Error returned when importing an externally supplied paging state
+/// with [PagingStateCodec::import](crate::response::PagingStateCodec::import).
+#[cfg(feature = "paging-state-codec")]
+#[derive(Clone, Debug, Error)]
+#[non_exhaustive]
+pub enum PagingStateImportError {
+    /// The envelope is truncated or otherwise structurally invalid.
+    #[error("Malformed paging state envelope")]
+    Malformed,
+
+    /// The envelope was produced by an incompatible version of the driver.
+    #[error("Unsupported paging state envelope version: {0}")]
+    UnsupportedVersion(u8),
+
+    /// The paging state was exported for a different statement.
+    #[error("Paging state does not belong to this statement")]
+    StatementMismatch,
+
+    /// The signature does not match the envelope contents - it was tampered with
+    /// or signed with a different key.
+    #[error("Paging state signature verification failed")]
+    InvalidSignature,
+
+    /// The envelope is not signed, but a paging state key is configured.
+    #[error("Paging state is not signed, but a signature is required")]
+    MissingSignature,
+
+    /// The envelope is signed, but no paging state key is configured to verify it.
+    #[error("Paging state is signed, but no key is configured to verify it")]
+    MissingKey,
+}
+
 #[cfg(test)]
 mod tests {
     use scylla_cql::Consistency;
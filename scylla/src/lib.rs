@@ -104,7 +104,9 @@ pub mod _macro_internal {
     pub use scylla_cql::_macro_internal::*;
 }
 
-pub use scylla_cql::{DeserializeRow, DeserializeValue, SerializeRow, SerializeValue};
+pub use scylla_cql::{
+    DeserializeRow, DeserializeValue, SerializeDeserializeRow, SerializeRow, SerializeValue,
+};
 
 pub mod value {
     //! Defines CQL values of various types and their representations,
@@ -113,8 +115,8 @@ pub mod value {
     // Every `pub` item is re-exported here, apart from `deser_cql_value`.
     pub use scylla_cql::value::{
         Counter, CqlDate, CqlDecimal, CqlDecimalBorrowed, CqlDuration, CqlTime, CqlTimestamp,
-        CqlTimeuuid, CqlValue, CqlVarint, CqlVarintBorrowed, Emptiable, MaybeEmpty, MaybeUnset,
-        Row, Unset, ValueOverflow,
+        CqlTimeuuid, CqlUdt, CqlValue, CqlVarint, CqlVarintBorrowed, Emptiable, MaybeEmpty,
+        MaybeUnset, Row, Unset, ValueOverflow,
     };
 }
 
@@ -123,8 +125,9 @@ pub mod frame {
 
     pub use scylla_cql::frame::{Authenticator, Compression, frame_errors};
     pub(crate) use scylla_cql::frame::{
-        FrameParams, SerializedRequest, parse_response_body_extensions, protocol_features,
-        read_response_frame, request, server_event_type,
+        FrameParams, ResponseFrameLimits, SerializedRequest,
+        parse_response_body_extensions_with_limits, protocol_features, read_response_frame_into,
+        request, server_event_type,
     };
 
     pub mod types {
@@ -173,7 +176,9 @@ pub mod serialize {
     /// Contains the [SerializeRow][row::SerializeRow] trait and its implementations.
     pub mod row {
         // Main types
-        pub use scylla_cql::serialize::row::{RowSerializationContext, SerializeRow};
+        pub use scylla_cql::serialize::row::{
+            RowSerializationContext, SerializeRow, SerializedValues,
+        };
 
         // Errors
         pub use scylla_cql::serialize::row::{
@@ -227,9 +232,9 @@ pub mod deserialize {
     pub mod value {
         pub use scylla_cql::deserialize::value::{
             BuiltinDeserializationError, BuiltinDeserializationErrorKind, BuiltinTypeCheckError,
-            BuiltinTypeCheckErrorKind, DeserializeValue, ListlikeIterator,
-            MapDeserializationErrorKind, MapIterator, MapTypeCheckErrorKind,
-            SetOrListDeserializationErrorKind, SetOrListTypeCheckErrorKind,
+            BuiltinTypeCheckErrorKind, DeserializeValue, EnumDeserializationErrorKind,
+            EnumTypeCheckErrorKind, ListlikeIterator, MapDeserializationErrorKind, MapIterator,
+            MapTypeCheckErrorKind, SetOrListDeserializationErrorKind, SetOrListTypeCheckErrorKind,
             TupleDeserializationErrorKind, TupleTypeCheckErrorKind, UdtIterator,
             UdtTypeCheckErrorKind,
         };
@@ -253,6 +258,7 @@ pub mod deserialize {
 }
 
 pub mod authentication;
+pub mod cdc;
 pub mod client;
 
 pub mod cluster;
@@ -262,7 +268,10 @@ pub mod observability;
 pub mod policies;
 pub mod response;
 pub mod routing;
+pub mod scan;
 pub mod statement;
+pub mod system_views;
+pub mod timeseries;
 
 pub(crate) mod utils;
 
@@ -0,0 +1,265 @@
+//! Reusable buffers for serializing requests and assembling responses.
+//!
+//! Under high load, allocating a fresh buffer for every frame sent or received
+//! on a connection makes up a significant part of the driver's CPU usage.
+//! The types below keep buffers around between frames and size new ones
+//! according to the sizes of recently seen frames.
+
+use std::sync::Mutex as StdMutex;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+use bytes::BytesMut;
+
+/// Number of buckets in [FrameSizeHistogram]. Bucket `i` holds sizes
+/// in range `[2^(i-1), 2^i)`, and the last one holds all the bigger sizes.
+const SIZE_BUCKETS: usize = 28;
+
+/// How many samples are collected before the recommended size is recomputed.
+const RECOMPUTE_PERIOD: u64 = 256;
+
+/// Once the histogram holds that many samples, all counts are halved,
+/// so that the recommended size follows changes in the workload.
+const DECAY_THRESHOLD: u64 = 64 * 1024;
+
+/// The percentile of frame sizes that new buffers should fit.
+const SIZE_PERCENTILE: f64 = 0.9;
+
+/// The smallest buffer ever recommended.
+const MIN_RECOMMENDED_SIZE: usize = 256;
+
+/// A lock-free histogram of frame sizes with power-of-two buckets.
+///
+/// It is intentionally coarse: it only needs to tell the typical frame size
+/// in a workload, in order to choose capacities of new buffers.
+pub(crate) struct FrameSizeHistogram {
+    buckets: [AtomicU64; SIZE_BUCKETS],
+    samples: AtomicU64,
+    recommended_size: AtomicUsize,
+}
+
+impl FrameSizeHistogram {
+    pub(crate) fn new() -> Self {
+        Self {
+            buckets: std::array::from_fn(|_| AtomicU64::new(0)),
+            samples: AtomicU64::new(0),
+            recommended_size: AtomicUsize::new(MIN_RECOMMENDED_SIZE),
+        }
+    }
+
+    fn bucket_of(size: usize) -> usize {
+        let bits = (usize::BITS - size.leading_zeros()) as usize;
+        bits.min(SIZE_BUCKETS - 1)
+    }
+
+    /// Records the size of a frame.
+    pub(crate) fn record(&self, size: usize) {
+        self.buckets[Self::bucket_of(size)].fetch_add(1, Ordering::Relaxed);
+        let samples = self.samples.fetch_add(1, Ordering::Relaxed) + 1;
+        if samples % RECOMPUTE_PERIOD == 0 {
+            self.recompute(samples);
+        }
+    }
+
+    fn recompute(&self, samples: u64) {
+        let counts: [u64; SIZE_BUCKETS] =
+            std::array::from_fn(|i| self.buckets[i].load(Ordering::Relaxed));
+        let total: u64 = counts.iter().sum();
+        let threshold = (total as f64 * SIZE_PERCENTILE).ceil() as u64;
+
+        let mut cumulative = 0;
+        let bucket = counts
+            .iter()
+            .position(|count| {
+                cumulative += count;
+                cumulative >= threshold
+            })
+            .unwrap_or(SIZE_BUCKETS - 1);
+        // The upper bound of the bucket, so that all sizes in it fit.
+        let size = 1usize
+            .checked_shl(bucket as u32)
+            .unwrap_or(usize::MAX)
+            .max(MIN_RECOMMENDED_SIZE);
+        self.recommended_size.store(size, Ordering::Relaxed);
+
+        // Concurrent updates may race with the decay, but the histogram
+        // is only a heuristic, so a few lost samples do not matter.
+        if samples >= DECAY_THRESHOLD {
+            for bucket in &self.buckets {
+                bucket.store(bucket.load(Ordering::Relaxed) / 2, Ordering::Relaxed);
+            }
+            self.samples.store(samples / 2, Ordering::Relaxed);
+        }
+    }
+
+    /// Returns the size that fits most of the recently recorded frames.
+    pub(crate) fn recommended_size(&self) -> usize {
+        self.recommended_size.load(Ordering::Relaxed)
+    }
+}
+
+/// Maximum number of free buffers kept by a [RequestBufferPool].
+const MAX_POOLED_REQUEST_BUFFERS: usize = 128;
+
+/// Buffers with capacity above `recommended_size * OVERSIZED_BUFFER_FACTOR`
+/// are not returned to the pool, so that a single huge request does not
+/// keep a lot of memory occupied.
+const OVERSIZED_BUFFER_FACTOR: usize = 4;
+
+/// A pool of buffers in which requests are serialized.
+///
+/// It is shared by the tasks that serialize requests sent on a connection
+/// and the connection's writer, which gives the buffers back once the
+/// requests are written to the socket.
+pub(crate) struct RequestBufferPool {
+    free: StdMutex<Vec<Vec<u8>>>,
+    sizes: FrameSizeHistogram,
+}
+
+impl RequestBufferPool {
+    pub(crate) fn new() -> Self {
+        Self {
+            free: StdMutex::new(Vec::new()),
+            sizes: FrameSizeHistogram::new(),
+        }
+    }
+
+    /// Takes a buffer from the pool, or allocates a new one of recommended size.
+    pub(crate) fn take(&self) -> Vec<u8> {
+        self.free
+            .lock()
+            .unwrap()
+            .pop()
+            .unwrap_or_else(|| Vec::with_capacity(self.sizes.recommended_size()))
+    }
+
+    /// Gives a buffer back to the pool, after the request serialized in it was sent.
+    pub(crate) fn give_back(&self, mut buffer: Vec<u8>) {
+        self.sizes.record(buffer.len());
+        if buffer.capacity() > self.sizes.recommended_size() * OVERSIZED_BUFFER_FACTOR {
+            return;
+        }
+
+        let mut free = self.free.lock().unwrap();
+        if free.len() < MAX_POOLED_REQUEST_BUFFERS {
+            buffer.clear();
+            free.push(buffer);
+        }
+    }
+}
+
+/// Maximum size of a chunk in which multiple responses are assembled.
+const MAX_RESPONSE_CHUNK_SIZE: usize = 64 * 1024;
+
+/// How many typical responses should fit in a single chunk.
+const RESPONSES_PER_CHUNK: usize = 16;
+
+/// Buffers in which a connection's reader assembles response frames.
+///
+/// Small responses are packed together into chunks, each of which is a single
+/// allocation. Once all responses assembled in a chunk are dropped, the chunk
+/// is reused. Responses bigger than a fraction of the chunk get their own
+/// allocations, so that they do not exhaust the chunk, and so that dropping
+/// them frees memory immediately.
+pub(crate) struct ResponseBufferPool {
+    chunk: BytesMut,
+    oversized: BytesMut,
+    sizes: FrameSizeHistogram,
+}
+
+impl ResponseBufferPool {
+    pub(crate) fn new() -> Self {
+        Self {
+            chunk: BytesMut::new(),
+            oversized: BytesMut::new(),
+            sizes: FrameSizeHistogram::new(),
+        }
+    }
+
+    fn chunk_size(&self) -> usize {
+        (self.sizes.recommended_size() * RESPONSES_PER_CHUNK).min(MAX_RESPONSE_CHUNK_SIZE)
+    }
+
+    /// Returns the buffer in which a response body of the given length should be assembled.
+    pub(crate) fn buffer_for_body(&mut self, length: usize) -> &mut BytesMut {
+        self.sizes.record(length);
+
+        let chunk_size = self.chunk_size();
+        if length > chunk_size / 4 {
+            self.oversized = BytesMut::with_capacity(length);
+            return &mut self.oversized;
+        }
+        if self.chunk.capacity() < length {
+            // Reuses the allocation if all responses assembled in it were dropped.
+            self.chunk.reserve(chunk_size);
+        }
+        &mut self.chunk
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        FrameSizeHistogram, MIN_RECOMMENDED_SIZE, RECOMPUTE_PERIOD, RequestBufferPool,
+        ResponseBufferPool,
+    };
+    use crate::test_utils::setup_tracing;
+
+    #[test]
+    fn recommended_size_follows_frame_sizes() {
+        setup_tracing();
+        let histogram = FrameSizeHistogram::new();
+        assert_eq!(histogram.recommended_size(), MIN_RECOMMENDED_SIZE);
+
+        for _ in 0..RECOMPUTE_PERIOD {
+            histogram.record(3000);
+        }
+        assert_eq!(histogram.recommended_size(), 4096);
+
+        // A few outliers do not affect the recommendation.
+        for i in 0..RECOMPUTE_PERIOD {
+            histogram.record(if i % 32 == 0 { 1 << 20 } else { 3000 });
+        }
+        assert_eq!(histogram.recommended_size(), 4096);
+    }
+
+    #[test]
+    fn request_buffers_are_reused() {
+        setup_tracing();
+        let pool = RequestBufferPool::new();
+
+        let mut buffer = pool.take();
+        buffer.extend_from_slice(&[1, 2, 3]);
+        let ptr = buffer.as_ptr();
+        pool.give_back(buffer);
+
+        let buffer = pool.take();
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.as_ptr(), ptr);
+
+        // Oversized buffers are dropped.
+        pool.give_back(Vec::with_capacity(1 << 20));
+        assert!(pool.take().capacity() < 1 << 20);
+    }
+
+    #[test]
+    fn small_responses_share_chunks() {
+        setup_tracing();
+        let mut pool = ResponseBufferPool::new();
+
+        let mut take_body = |length: usize| {
+            let buffer = pool.buffer_for_body(length);
+            buffer.resize(length, 0);
+            buffer.split_to(length).freeze()
+        };
+
+        let first = take_body(16);
+        let second = take_body(16);
+        assert_eq!(first.as_ptr().wrapping_add(16), second.as_ptr());
+
+        // A big response does not land in the chunk.
+        let big = take_body(1 << 20);
+        let third = take_body(16);
+        assert_eq!(second.as_ptr().wrapping_add(16), third.as_ptr());
+        assert_eq!(big.len(), 1 << 20);
+    }
+}
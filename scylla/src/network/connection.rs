@@ -1,7 +1,11 @@
+use super::buffer_pool::{RequestBufferPool, ResponseBufferPool};
+use super::priority_queue::{PriorityReceiver, PrioritySender, priority_channel};
+use super::proxy::{Proxy, ProxyRules};
 use super::tls::{TlsConfig, TlsProvider};
 use crate::authentication::AuthenticatorProvider;
 use crate::client::Compression;
 use crate::client::SelfIdentity;
+use crate::client::execution_profile::{ExecutionProfileInner, RequestPriority};
 use crate::client::pager::{NextRowError, QueryPager};
 use crate::cluster::NodeAddr;
 use crate::cluster::metadata::{PeerEndpoint, UntranslatedEndpoint};
@@ -13,11 +17,15 @@ use crate::errors::{
 };
 use crate::frame::protocol_features::ProtocolFeatures;
 use crate::frame::{
-    self, FrameParams, SerializedRequest,
+    self, FrameParams, ResponseFrameLimits, SerializedRequest,
     request::{self, SerializableRequest, batch, execute, query, register},
     response::{Response, ResponseOpcode, event::Event, result},
     server_event_type::EventType,
 };
+use crate::observability::instrumentation::{TRACEPARENT_CUSTOM_PAYLOAD_KEY, TraceParent};
+use crate::observability::log_targets;
+#[cfg(feature = "metrics")]
+use crate::observability::metrics::{ConnectionGauge, GaugeGuard, Metrics, NodeConnectionGauges};
 use crate::policies::address_translator::{AddressTranslator, UntranslatedPeer};
 use crate::policies::timestamp_generator::TimestampGenerator;
 #[cfg(test)]
@@ -29,6 +37,7 @@ use crate::statement::batch::{Batch, BatchStatement};
 use crate::statement::prepared::{PreparedStatement, RawPreparedStatement};
 use crate::statement::unprepared::Statement;
 use crate::statement::{Consistency, PageSize};
+use crate::utils::runtime::{self, TcpStream};
 use bytes::Bytes;
 use futures::{FutureExt, future::RemoteHandle};
 use scylla_cql::frame::frame_errors::CqlResponseParseError;
@@ -49,20 +58,20 @@ use std::borrow::Cow;
 use std::collections::{BTreeSet, HashMap, HashSet};
 use std::convert::TryFrom;
 use std::net::{IpAddr, SocketAddr};
-use std::num::NonZeroU64;
+use std::num::{NonZeroU64, NonZeroUsize};
 use std::sync::Arc;
 use std::sync::Mutex as StdMutex;
-use std::sync::atomic::AtomicU64;
+use std::sync::atomic::{AtomicU64, AtomicUsize};
 use std::time::Duration;
 use std::{
     cmp::Ordering,
     net::{Ipv4Addr, Ipv6Addr},
 };
 use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt, BufReader, BufWriter, split};
-use tokio::net::{TcpSocket, TcpStream};
 use tokio::sync::{mpsc, oneshot};
 use tokio::time::Instant;
 use tracing::{debug, error, trace, warn};
+use zeroize::Zeroize;
 
 // FIXME: Make this constants configurable
 // The term "orphan" refers to stream ids, that were allocated for a {request, response} that no
@@ -73,6 +82,24 @@ use tracing::{debug, error, trace, warn};
 const OLD_ORPHAN_COUNT_THRESHOLD: usize = 1024;
 const OLD_AGE_ORPHAN_THRESHOLD: std::time::Duration = std::time::Duration::from_secs(1);
 
+// Key of the custom payload entry which carries the role on behalf of which
+// a request is executed (proxy execution).
+const PROXY_EXECUTE_CUSTOM_PAYLOAD_KEY: &str = "ProxyExecute";
+
+/// The request sent periodically on each connection to check that it is alive.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum KeepaliveRequest {
+    /// An `OPTIONS` request, which the node answers without reading any data.
+    #[default]
+    Options,
+
+    /// A query, e.g. `SELECT now() FROM system.local`, sent with consistency `ONE`.
+    /// Unlike `OPTIONS`, it also checks that the node is able to serve reads.
+    /// A query which fails on the node counts as a failed keepalive.
+    Query(String),
+}
+
 /// Represents a write coalescing delay configuration option.
 #[derive(Debug, Clone)]
 #[non_exhaustive]
@@ -102,7 +129,7 @@ pub(crate) struct Connection {
 }
 
 struct RouterHandle {
-    submit_channel: mpsc::Sender<Task>,
+    submit_channel: PrioritySender<Task>,
 
     // Each request send by `Connection::send_request` needs a unique request id.
     // This field is a monotonic generator of such ids.
@@ -113,9 +140,73 @@ struct RouterHandle {
     // pushing values in a synchronous way (without an `.await`), which is
     // needed for pushing values in `Drop` implementations.
     orphan_notification_sender: mpsc::UnboundedSender<RequestId>,
+
+    // Number of requests sent through this handle which have not completed yet.
+    // Used by the connection pool to estimate the load of the connection.
+    in_flight_requests: AtomicUsize,
+
+    // When the connection was opened.
+    created_at: Instant,
+    // When a request was last sent or completed, in milliseconds since `created_at`.
+    // Used by the connection pool to find idle connections.
+    last_activity_millis: AtomicU64,
+
+    // Buffers in which requests are serialized, given back by the writer
+    // once the requests are sent.
+    request_buffers: Arc<RequestBufferPool>,
+    // Request bodies shorter than that are not compressed.
+    compression_threshold: usize,
+    #[cfg(feature = "metrics")]
+    metrics: Option<Arc<Metrics>>,
+    #[cfg(feature = "metrics")]
+    connection_gauges: Option<Arc<NodeConnectionGauges>>,
+}
+
+// Decrements the number of in-flight requests of a connection when dropped,
+// i.e. also when the request is cancelled.
+struct InFlightRequestCounter<'a>(&'a RouterHandle);
+
+impl<'a> InFlightRequestCounter<'a> {
+    fn new(router_handle: &'a RouterHandle) -> Self {
+        router_handle
+            .in_flight_requests
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        router_handle.record_activity();
+        Self(router_handle)
+    }
+}
+
+impl Drop for InFlightRequestCounter<'_> {
+    fn drop(&mut self) {
+        self.0.record_activity();
+        self.0
+            .in_flight_requests
+            .fetch_sub(1, std::sync::atomic::Ordering::Relaxed);
+    }
 }
 
 impl RouterHandle {
+    fn record_activity(&self) {
+        let millis = self.created_at.elapsed().as_millis() as u64;
+        self.last_activity_millis
+            .fetch_max(millis, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    fn idle_time(&self) -> Duration {
+        if self
+            .in_flight_requests
+            .load(std::sync::atomic::Ordering::Relaxed)
+            > 0
+        {
+            return Duration::ZERO;
+        }
+        let last_activity = Duration::from_millis(
+            self.last_activity_millis
+                .load(std::sync::atomic::Ordering::Relaxed),
+        );
+        self.created_at.elapsed().saturating_sub(last_activity)
+    }
+
     fn allocate_request_id(&self) -> RequestId {
         self.request_id_generator
             .fetch_add(1, std::sync::atomic::Ordering::Relaxed)
@@ -126,9 +217,39 @@ impl RouterHandle {
         request: &impl SerializableRequest,
         compression: Option<Compression>,
         tracing: bool,
+        priority: RequestPriority,
+        trace_parent: Option<TraceParent>,
+        execute_as: Option<&str>,
     ) -> Result<TaskResponse, InternalRequestError> {
-        let serialized_request = SerializedRequest::make(request, compression, tracing)?;
+        let encoded_trace_parent = trace_parent.map(|trace_parent| trace_parent.encode());
+        let mut custom_payload_entries: [(&str, &[u8]); 2] = [("", &[]); 2];
+        let mut custom_payload_len = 0;
+        if let Some(encoded) = &encoded_trace_parent {
+            custom_payload_entries[custom_payload_len] = (TRACEPARENT_CUSTOM_PAYLOAD_KEY, encoded);
+            custom_payload_len += 1;
+        }
+        if let Some(role) = execute_as {
+            custom_payload_entries[custom_payload_len] =
+                (PROXY_EXECUTE_CUSTOM_PAYLOAD_KEY, role.as_bytes());
+            custom_payload_len += 1;
+        }
+        let custom_payload = &custom_payload_entries[..custom_payload_len];
+        let serialized_request = SerializedRequest::make_in_buffer(
+            request,
+            compression,
+            self.compression_threshold,
+            tracing,
+            custom_payload,
+            self.request_buffers.take(),
+        )?;
+        #[cfg(feature = "metrics")]
+        if let (Some(metrics), Some(uncompressed_length)) =
+            (&self.metrics, serialized_request.uncompressed_body_length())
+        {
+            metrics.log_request_compression(uncompressed_length, serialized_request.body_length());
+        }
         let request_id = self.allocate_request_id();
+        let _in_flight = InFlightRequestCounter::new(self);
 
         let (response_sender, receiver) = oneshot::channel();
         let response_handler = ResponseHandler {
@@ -141,11 +262,23 @@ impl RouterHandle {
         // with this request as orphaned and free associated resources.
         let notifier = OrphanhoodNotifier::new(request_id, &self.orphan_notification_sender);
 
+        #[cfg(feature = "metrics")]
+        let queued = self.connection_gauges.as_ref().map(|gauges| {
+            let mut queued = gauges.track(ConnectionGauge::QueuedRequests);
+            queued.add(1);
+            queued
+        });
+
         self.submit_channel
-            .send(Task {
-                serialized_request,
-                response_handler,
-            })
+            .send(
+                priority,
+                Task {
+                    serialized_request,
+                    response_handler,
+                    #[cfg(feature = "metrics")]
+                    queued,
+                },
+            )
             .await
             .map_err(|_| -> BrokenConnectionError {
                 BrokenConnectionErrorKind::ChannelError.into()
@@ -210,9 +343,110 @@ impl Drop for OrphanhoodNotifier<'_> {
     }
 }
 
+/// Settings of a request which are determined by its execution profile.
+#[derive(Debug, Clone)]
+pub(crate) struct RequestSettings {
+    /// Whether the request may be compressed, if compression was negotiated.
+    pub(crate) compress: bool,
+    pub(crate) priority: RequestPriority,
+    /// Trace context propagated to the cluster in the custom payload of the request.
+    pub(crate) trace_parent: Option<TraceParent>,
+    /// Role on behalf of which the request is executed, sent in the custom payload of the request.
+    pub(crate) execute_as: Option<Arc<str>>,
+}
+
+impl RequestSettings {
+    /// Settings of requests sent during the connection handshake,
+    /// before compression is negotiated.
+    const HANDSHAKE: Self = Self {
+        compress: false,
+        priority: RequestPriority::Normal,
+        trace_parent: None,
+        execute_as: None,
+    };
+
+    /// Settings of driver-internal requests, which do not consult execution profiles.
+    pub(crate) const INTERNAL: Self = Self {
+        compress: true,
+        priority: RequestPriority::Normal,
+        trace_parent: None,
+        execute_as: None,
+    };
+
+    pub(crate) fn from_profile(profile: &ExecutionProfileInner) -> Self {
+        Self {
+            compress: profile.compression_enabled,
+            priority: profile.priority,
+            trace_parent: None,
+            execute_as: profile.execute_as.clone(),
+        }
+    }
+
+    pub(crate) fn with_trace_parent(self, trace_parent: Option<TraceParent>) -> Self {
+        Self {
+            trace_parent,
+            ..self
+        }
+    }
+}
+
+/// Writes the serialized request, returning the buffer it was serialized in.
+///
+/// AUTH_RESPONSE frames may carry credentials (e.g. the password sent by
+/// [PlainTextAuthenticator](crate::authentication::PlainTextAuthenticator)),
+/// so they bypass the buffer of `write_half`, and the returned buffer is wiped.
+/// This way, the credentials don't outlive the request.
+async fn write_request(
+    write_half: &mut BufWriter<impl AsyncWrite + Unpin>,
+    req: SerializedRequest,
+) -> (Vec<u8>, std::io::Result<()>) {
+    let req_data = req.get_data();
+    if req_data.get(4) != Some(&(request::RequestOpcode::AuthResponse as u8)) {
+        let write_result = write_half.write_all(req_data).await;
+        return (req.into_data(), write_result);
+    }
+
+    let write_result = match write_half.flush().await {
+        Ok(()) => write_half.get_mut().write_all(req_data).await,
+        Err(err) => Err(err),
+    };
+    let mut req_buffer = req.into_data();
+    req_buffer.as_mut_slice().zeroize();
+    (req_buffer, write_result)
+}
+
 struct Task {
     serialized_request: SerializedRequest,
     response_handler: ResponseHandler,
+    // Counts the task as queued until it is taken by the writer.
+    #[cfg(feature = "metrics")]
+    queued: Option<GaugeGuard>,
+}
+
+// Settings of the keepalive requests sent on a connection.
+struct KeepaliveSettings {
+    interval: Option<Duration>,
+    timeout: Option<Duration>,
+    request: KeepaliveRequest,
+    close_on_failure: bool,
+    // Needed to parse responses to keepalive queries.
+    compression: Option<Compression>,
+    response_frame_limits: ResponseFrameLimits,
+    #[cfg(feature = "metrics")]
+    metrics: Option<Arc<Metrics>>,
+}
+
+// Settings of the writer half of a connection.
+struct WriterSettings {
+    coalescing_delay: Option<WriteCoalescingDelay>,
+    // Limits the number of bytes written between flushes.
+    coalescing_max_bytes: Option<NonZeroUsize>,
+    #[cfg(feature = "metrics")]
+    metrics: Option<Arc<Metrics>>,
+    #[cfg(feature = "metrics")]
+    connection_gauges: Option<Arc<NodeConnectionGauges>>,
+    // Serialized requests' buffers are given back here once sent.
+    request_buffers: Arc<RequestBufferPool>,
 }
 
 struct TaskResponse {
@@ -272,10 +506,13 @@ pub(crate) struct ConnectionConfig {
     pub(crate) local_ip_address: Option<IpAddr>,
     pub(crate) shard_aware_local_port_range: ShardAwarePortRange,
     pub(crate) compression: Option<Compression>,
+    pub(crate) compression_threshold: usize,
     pub(crate) tcp_nodelay: bool,
     pub(crate) tcp_keepalive_interval: Option<Duration>,
+    pub(crate) socket_options: SocketOptions,
     pub(crate) timestamp_generator: Option<Arc<dyn TimestampGenerator>>,
     pub(crate) tls_provider: Option<TlsProvider>,
+    pub(crate) proxy_rules: ProxyRules,
     pub(crate) connect_timeout: std::time::Duration,
     // should be Some only in control connections,
     pub(crate) event_sender: Option<mpsc::Sender<Event>>,
@@ -283,9 +520,15 @@ pub(crate) struct ConnectionConfig {
     pub(crate) authenticator: Option<Arc<dyn AuthenticatorProvider>>,
     pub(crate) address_translator: Option<Arc<dyn AddressTranslator>>,
     pub(crate) write_coalescing_delay: Option<WriteCoalescingDelay>,
+    pub(crate) write_coalescing_max_bytes: Option<NonZeroUsize>,
+    pub(crate) response_frame_limits: ResponseFrameLimits,
 
     pub(crate) keepalive_interval: Option<Duration>,
     pub(crate) keepalive_timeout: Option<Duration>,
+    pub(crate) keepalive_request: KeepaliveRequest,
+    pub(crate) close_on_keepalive_failure: bool,
+    #[cfg(feature = "metrics")]
+    pub(crate) metrics: Option<Arc<Metrics>>,
     pub(crate) tablet_sender: Option<mpsc::Sender<(TableSpec<'static>, RawTablet)>>,
 
     pub(crate) identity: SelfIdentity<'static>,
@@ -306,18 +549,34 @@ impl ConnectionConfig {
             local_ip_address: self.local_ip_address,
             shard_aware_local_port_range: self.shard_aware_local_port_range.clone(),
             compression: self.compression,
+            compression_threshold: self.compression_threshold,
             tcp_nodelay: self.tcp_nodelay,
             tcp_keepalive_interval: self.tcp_keepalive_interval,
+            socket_options: self.socket_options.clone(),
             timestamp_generator: self.timestamp_generator.clone(),
             tls_config,
+            proxy: self.proxy_rules.for_endpoint(endpoint),
             connect_timeout: self.connect_timeout,
             event_sender: self.event_sender.clone(),
             default_consistency: self.default_consistency,
             authenticator: self.authenticator.clone(),
             address_translator: self.address_translator.clone(),
             write_coalescing_delay: self.write_coalescing_delay.clone(),
+            write_coalescing_max_bytes: self.write_coalescing_max_bytes,
+            response_frame_limits: self.response_frame_limits,
             keepalive_interval: self.keepalive_interval,
             keepalive_timeout: self.keepalive_timeout,
+            keepalive_request: self.keepalive_request.clone(),
+            close_on_keepalive_failure: self.close_on_keepalive_failure,
+            #[cfg(feature = "metrics")]
+            metrics: self.metrics.clone(),
+            #[cfg(feature = "metrics")]
+            connection_gauges: match (&self.metrics, endpoint) {
+                (Some(metrics), UntranslatedEndpoint::Peer(peer)) => {
+                    Some(metrics.node_connection_gauges(peer.host_id))
+                }
+                _ => None,
+            },
             tablet_sender: self.tablet_sender.clone(),
             identity: self.identity.clone(),
         }
@@ -332,10 +591,13 @@ pub(crate) struct HostConnectionConfig {
     pub(crate) local_ip_address: Option<IpAddr>,
     pub(crate) shard_aware_local_port_range: ShardAwarePortRange,
     pub(crate) compression: Option<Compression>,
+    pub(crate) compression_threshold: usize,
     pub(crate) tcp_nodelay: bool,
     pub(crate) tcp_keepalive_interval: Option<Duration>,
+    pub(crate) socket_options: SocketOptions,
     pub(crate) timestamp_generator: Option<Arc<dyn TimestampGenerator>>,
     pub(crate) tls_config: Option<TlsConfig>,
+    pub(crate) proxy: Option<Proxy>,
     pub(crate) connect_timeout: std::time::Duration,
     // should be Some only in control connections,
     pub(crate) event_sender: Option<mpsc::Sender<Event>>,
@@ -343,9 +605,18 @@ pub(crate) struct HostConnectionConfig {
     pub(crate) authenticator: Option<Arc<dyn AuthenticatorProvider>>,
     pub(crate) address_translator: Option<Arc<dyn AddressTranslator>>,
     pub(crate) write_coalescing_delay: Option<WriteCoalescingDelay>,
+    pub(crate) write_coalescing_max_bytes: Option<NonZeroUsize>,
+    pub(crate) response_frame_limits: ResponseFrameLimits,
 
     pub(crate) keepalive_interval: Option<Duration>,
     pub(crate) keepalive_timeout: Option<Duration>,
+    pub(crate) keepalive_request: KeepaliveRequest,
+    pub(crate) close_on_keepalive_failure: bool,
+    #[cfg(feature = "metrics")]
+    pub(crate) metrics: Option<Arc<Metrics>>,
+    // Gauges of connections to the node, if it is a known peer.
+    #[cfg(feature = "metrics")]
+    pub(crate) connection_gauges: Option<Arc<NodeConnectionGauges>>,
     pub(crate) tablet_sender: Option<mpsc::Sender<(TableSpec<'static>, RawTablet)>>,
 
     pub(crate) identity: SelfIdentity<'static>,
@@ -358,20 +629,31 @@ impl Default for HostConnectionConfig {
             local_ip_address: None,
             shard_aware_local_port_range: ShardAwarePortRange::EPHEMERAL_PORT_RANGE,
             compression: None,
+            compression_threshold: 0,
             tcp_nodelay: true,
             tcp_keepalive_interval: None,
+            socket_options: SocketOptions::default(),
             timestamp_generator: None,
             event_sender: None,
             tls_config: None,
+            proxy: None,
             connect_timeout: std::time::Duration::from_secs(5),
             default_consistency: Default::default(),
             authenticator: None,
             address_translator: None,
             write_coalescing_delay: Some(WriteCoalescingDelay::SmallNondeterministic),
+            write_coalescing_max_bytes: None,
+            response_frame_limits: ResponseFrameLimits::default(),
 
             // Note: this is different than SessionConfig default values.
             keepalive_interval: None,
             keepalive_timeout: None,
+            keepalive_request: KeepaliveRequest::Options,
+            close_on_keepalive_failure: true,
+            #[cfg(feature = "metrics")]
+            metrics: None,
+            #[cfg(feature = "metrics")]
+            connection_gauges: None,
 
             tablet_sender: None,
 
@@ -387,20 +669,29 @@ impl Default for ConnectionConfig {
             local_ip_address: None,
             shard_aware_local_port_range: ShardAwarePortRange::EPHEMERAL_PORT_RANGE,
             compression: None,
+            compression_threshold: 0,
             tcp_nodelay: true,
             tcp_keepalive_interval: None,
+            socket_options: SocketOptions::default(),
             timestamp_generator: None,
             event_sender: None,
             tls_provider: None,
+            proxy_rules: ProxyRules::default(),
             connect_timeout: std::time::Duration::from_secs(5),
             default_consistency: Default::default(),
             authenticator: None,
             address_translator: None,
             write_coalescing_delay: Some(WriteCoalescingDelay::SmallNondeterministic),
+            write_coalescing_max_bytes: None,
+            response_frame_limits: ResponseFrameLimits::default(),
 
             // Note: this is different than SessionConfig default values.
             keepalive_interval: None,
             keepalive_timeout: None,
+            keepalive_request: KeepaliveRequest::Options,
+            close_on_keepalive_failure: true,
+            #[cfg(feature = "metrics")]
+            metrics: None,
 
             tablet_sender: None,
 
@@ -427,25 +718,46 @@ impl Connection {
         source_port: Option<u16>,
         config: HostConnectionConfig,
     ) -> Result<(Self, ErrorReceiver), ConnectionError> {
-        let stream_connector = tokio::time::timeout(
-            config.connect_timeout,
-            connect_with_source_ip_and_port(connect_address, config.local_ip_address, source_port),
-        )
-        .await;
+        let stream_connector = match &config.proxy {
+            // The source port is chosen by the proxy, so it is ignored.
+            Some(proxy) => {
+                runtime::timeout(
+                    config.connect_timeout,
+                    proxy.connect(
+                        connect_address,
+                        config.local_ip_address,
+                        &config.socket_options,
+                    ),
+                )
+                .await
+            }
+            None => {
+                runtime::timeout(
+                    config.connect_timeout,
+                    connect_with_source_ip_and_port(
+                        connect_address,
+                        config.local_ip_address,
+                        source_port,
+                        &config.socket_options,
+                    ),
+                )
+                .await
+            }
+        };
         let stream = match stream_connector {
             Ok(stream) => stream?,
             Err(_) => {
                 return Err(ConnectionError::ConnectTimeout);
             }
         };
-        stream.set_nodelay(config.tcp_nodelay)?;
+        runtime::sock_ref(&stream).set_nodelay(config.tcp_nodelay)?;
 
         if let Some(tcp_keepalive_interval) = config.tcp_keepalive_interval {
             Self::setup_tcp_keepalive(&stream, tcp_keepalive_interval)?;
         }
 
         // TODO: What should be the size of the channel?
-        let (sender, receiver) = mpsc::channel(1024);
+        let (sender, receiver) = priority_channel(1024);
         let (error_sender, error_receiver) = tokio::sync::oneshot::channel();
         // Unbounded because it allows for synchronous pushes
         let (orphan_notification_sender, orphan_notification_receiver) = mpsc::unbounded_channel();
@@ -454,6 +766,15 @@ impl Connection {
             submit_channel: sender,
             request_id_generator: AtomicU64::new(0),
             orphan_notification_sender,
+            in_flight_requests: AtomicUsize::new(0),
+            created_at: Instant::now(),
+            last_activity_millis: AtomicU64::new(0),
+            request_buffers: Arc::new(RequestBufferPool::new()),
+            compression_threshold: config.compression_threshold,
+            #[cfg(feature = "metrics")]
+            metrics: config.metrics.clone(),
+            #[cfg(feature = "metrics")]
+            connection_gauges: config.connection_gauges.clone(),
         });
 
         let _worker_handle = Self::run_router(
@@ -526,8 +847,7 @@ impl Connection {
             tcp_keepalive = tcp_keepalive.with_retries(10);
         }
 
-        let sf = SockRef::from(&stream);
-        sf.set_tcp_keepalive(&tcp_keepalive)
+        runtime::sock_ref(stream).set_tcp_keepalive(&tcp_keepalive)
     }
 
     async fn startup(
@@ -539,7 +859,12 @@ impl Connection {
         };
 
         let req_result = self
-            .send_request(&request::Startup { options }, false, false, None)
+            .send_request(
+                &request::Startup { options },
+                RequestSettings::HANDSHAKE,
+                false,
+                None,
+            )
             .await;
 
         // Extract the response to STARTUP request and tidy up the errors.
@@ -590,7 +915,12 @@ impl Connection {
         };
 
         let req_result = self
-            .send_request(&request::Options {}, false, false, None)
+            .send_request(
+                &request::Options {},
+                RequestSettings::HANDSHAKE,
+                false,
+                None,
+            )
             .await;
 
         // Extract the supported options and tidy up the errors.
@@ -642,7 +972,7 @@ impl Connection {
                 &request::Prepare {
                     query: &statement.contents,
                 },
-                true,
+                RequestSettings::INTERNAL,
                 statement.config.tracing,
                 None,
             )
@@ -724,7 +1054,7 @@ impl Connection {
         match self.config.authenticator {
             Some(ref authenticator_provider) => {
                 let (mut response, mut auth_session) = authenticator_provider
-                    .start_authentication_session(authenticator)
+                    .start_authentication_session_for_node(authenticator, self.connect_address)
                     .await
                     .map_err(|e| err(ConnectionSetupRequestErrorKind::StartAuthSessionError(e)))?;
 
@@ -768,9 +1098,12 @@ impl Connection {
             ConnectionSetupRequestError::new(CqlRequestKind::AuthResponse, kind)
         };
 
+        let mut request = request::AuthResponse { response };
         let req_result = self
-            .send_request(&request::AuthResponse { response }, false, false, None)
+            .send_request(&request, RequestSettings::HANDSHAKE, false, None)
             .await;
+        // The response may carry credentials, so it is wiped as soon as it is sent.
+        request.response.zeroize();
 
         // Extract non-error response to AUTH_RESPONSE request and tidy up errors.
         let response = match req_result {
@@ -845,6 +1178,7 @@ impl Connection {
                 .config
                 .determine_consistency(self.config.default_consistency),
             statement.config.serial_consistency.flatten(),
+            RequestSettings::INTERNAL,
             None,
             PagingState::start(),
         )
@@ -856,6 +1190,7 @@ impl Connection {
         statement: &Statement,
         consistency: Consistency,
         serial_consistency: Option<SerialConsistency>,
+        settings: RequestSettings,
         page_size: Option<PageSize>,
         paging_state: PagingState,
     ) -> Result<QueryResponse, RequestAttemptError> {
@@ -881,7 +1216,7 @@ impl Connection {
         };
 
         let response = self
-            .send_request(&query_frame, true, statement.config.tracing, None)
+            .send_request(&query_frame, settings, statement.config.tracing, None)
             .await?;
 
         Ok(response)
@@ -900,6 +1235,7 @@ impl Connection {
                 .config
                 .determine_consistency(self.config.default_consistency),
             prepared.config.serial_consistency.flatten(),
+            RequestSettings::INTERNAL,
             None,
             PagingState::start(),
         )
@@ -951,12 +1287,14 @@ impl Connection {
         }
     }
 
+    #[expect(clippy::too_many_arguments)]
     pub(crate) async fn execute_raw_with_consistency(
         &self,
         prepared_statement: &PreparedStatement,
         values: &SerializedValues,
         consistency: Consistency,
         serial_consistency: Option<SerialConsistency>,
+        settings: RequestSettings,
         page_size: Option<PageSize>,
         paging_state: PagingState,
     ) -> Result<QueryResponse, RequestAttemptError> {
@@ -999,7 +1337,7 @@ impl Connection {
         let query_response = self
             .send_request(
                 &execute_frame,
-                true,
+                settings.clone(),
                 prepared_statement.config.tracing,
                 cached_metadata.as_ref(),
             )
@@ -1010,7 +1348,11 @@ impl Connection {
                 .update_tablets_from_response(spec, &query_response)
                 .await
             {
-                tracing::warn!("Error while parsing tablet info from custom payload: {}", e);
+                tracing::warn!(
+                    target: log_targets::METADATA,
+                    "Error while parsing tablet info from custom payload: {}",
+                    e,
+                );
             }
         }
 
@@ -1022,6 +1364,7 @@ impl Connection {
                 ..
             }) => {
                 debug!(
+                    target: log_targets::REQUEST,
                     "Connection::execute: Got DbError::Unprepared - repreparing statement with id {:?}",
                     statement_id
                 );
@@ -1038,7 +1381,7 @@ impl Connection {
                             result_metadata_id: result_id.map(Into::into),
                             ..execute_frame
                         },
-                        true,
+                        settings,
                         prepared_statement.config.tracing,
                         cached_metadata.as_ref(),
                     )
@@ -1047,6 +1390,7 @@ impl Connection {
                 if let Some(spec) = prepared_statement.get_table_spec() {
                     if let Err(e) = self.update_tablets_from_response(spec, &new_response).await {
                         tracing::warn!(
+                            target: log_targets::METADATA,
                             "Error while parsing tablet info from custom payload: {}",
                             e
                         );
@@ -1090,6 +1434,7 @@ impl Connection {
         values: impl BatchValues,
         consistency: Consistency,
         serial_consistency: Option<SerialConsistency>,
+        settings: RequestSettings,
     ) -> Result<QueryResponse, RequestAttemptError> {
         let batch = self.prepare_batch(init_batch, &values).await?;
 
@@ -1121,7 +1466,7 @@ impl Connection {
 
         loop {
             let query_response = self
-                .send_request(&batch_frame, true, batch.config.tracing, None)
+                .send_request(&batch_frame, settings.clone(), batch.config.tracing, None)
                 .await
                 .map_err(RequestAttemptError::from)?;
 
@@ -1129,6 +1474,7 @@ impl Connection {
                 ResponseWithDeserializedMetadata::Error(err) => match err.error {
                     DbError::Unprepared { statement_id } => {
                         debug!(
+                            target: log_targets::REQUEST,
                             "Connection::batch: got DbError::Unprepared - repreparing statement with id {:?}",
                             statement_id
                         );
@@ -1263,7 +1609,10 @@ impl Connection {
         };
 
         // Extract the response and tidy up the errors.
-        match self.send_request(&register_frame, true, false, None).await {
+        match self
+            .send_request(&register_frame, RequestSettings::INTERNAL, false, None)
+            .await
+        {
             Ok(r) => match r.response {
                 ResponseWithDeserializedMetadata::Ready => Ok(()),
                 ResponseWithDeserializedMetadata::Error(Error { error, reason }) => {
@@ -1294,11 +1643,11 @@ impl Connection {
     async fn send_request(
         &self,
         request: &impl SerializableRequest,
-        compress: bool,
+        settings: RequestSettings,
         tracing: bool,
         cached_metadata: Option<&Arc<ResultMetadata<'static>>>,
     ) -> Result<QueryResponse, InternalRequestError> {
-        let compression = if compress {
+        let compression = if settings.compress {
             self.config.compression
         } else {
             None
@@ -1306,12 +1655,20 @@ impl Connection {
 
         let task_response = self
             .router_handle
-            .send_request(request, compression, tracing)
+            .send_request(
+                request,
+                compression,
+                tracing,
+                settings.priority,
+                settings.trace_parent,
+                settings.execute_as.as_deref(),
+            )
             .await?;
 
         let response = Self::parse_response(
             task_response,
             self.config.compression,
+            &self.config.response_frame_limits,
             &self.features.protocol_features,
             cached_metadata,
         )?;
@@ -1322,17 +1679,20 @@ impl Connection {
     fn parse_response(
         task_response: TaskResponse,
         compression: Option<Compression>,
+        response_frame_limits: &ResponseFrameLimits,
         features: &ProtocolFeatures,
         cached_metadata: Option<&Arc<ResultMetadata<'static>>>,
     ) -> Result<QueryResponse, ResponseParseError> {
-        let body_with_ext = frame::parse_response_body_extensions(
+        let body_with_ext = frame::parse_response_body_extensions_with_limits(
             task_response.params.flags,
             compression,
             task_response.body,
+            response_frame_limits,
         )?;
 
         for warn_description in &body_with_ext.warnings {
             warn!(
+                target: log_targets::REQUEST,
                 warning = warn_description.as_str(),
                 "Response from the database contains a warning",
             );
@@ -1362,7 +1722,7 @@ impl Connection {
     async fn run_router(
         config: HostConnectionConfig,
         stream: TcpStream,
-        receiver: mpsc::Receiver<Task>,
+        receiver: PriorityReceiver<Task>,
         error_sender: tokio::sync::oneshot::Sender<ConnectionError>,
         orphan_notification_receiver: mpsc::UnboundedReceiver<RequestId>,
         router_handle: Arc<RouterHandle>,
@@ -1371,7 +1731,7 @@ impl Connection {
         async fn spawn_router_and_get_handle(
             config: HostConnectionConfig,
             stream: impl AsyncRead + AsyncWrite + Send + 'static,
-            receiver: mpsc::Receiver<Task>,
+            receiver: PriorityReceiver<Task>,
             error_sender: tokio::sync::oneshot::Sender<ConnectionError>,
             orphan_notification_receiver: mpsc::UnboundedReceiver<RequestId>,
             router_handle: Arc<RouterHandle>,
@@ -1387,19 +1747,16 @@ impl Connection {
                 node_address,
             )
             .remote_handle();
-            tokio::task::spawn(task);
+            runtime::spawn(task);
             handle
         }
 
         if let Some(tls_config) = &config.tls_config {
             // To silence warnings when TlsContext is an empty enum (tls features are disabled).
             #[allow(unreachable_code)]
-            match tls_config.new_tls()? {
+            match tls_config.new_tls(node_address)? {
                 #[cfg(feature = "openssl-010")]
-                crate::network::tls::Tls::OpenSsl010(mut ssl) => {
-                    ssl.param_mut()
-                        .set_ip(node_address)
-                        .map_err(crate::network::tls::TlsError::OpenSsl010)?;
+                crate::network::tls::Tls::OpenSsl010(ssl) => {
                     let mut stream = tokio_openssl::SslStream::new(ssl, stream)
                         .map_err(crate::network::tls::TlsError::OpenSsl010)?;
                     std::pin::Pin::new(&mut stream)
@@ -1418,9 +1775,10 @@ impl Connection {
                     .await);
                 }
                 #[cfg(feature = "rustls-023")]
-                crate::network::tls::Tls::Rustls023 { connector } => {
-                    use rustls::pki_types::ServerName;
-                    let server_name = ServerName::IpAddress(node_address.into());
+                crate::network::tls::Tls::Rustls023 {
+                    connector,
+                    server_name,
+                } => {
                     let stream = connector.connect(server_name, stream).await?;
                     return Ok(spawn_router_and_get_handle(
                         config,
@@ -1451,7 +1809,7 @@ impl Connection {
     async fn router(
         config: HostConnectionConfig,
         stream: impl AsyncRead + AsyncWrite,
-        receiver: mpsc::Receiver<Task>,
+        receiver: PriorityReceiver<Task>,
         error_sender: tokio::sync::oneshot::Sender<ConnectionError>,
         orphan_notification_receiver: mpsc::UnboundedReceiver<RequestId>,
         router_handle: Arc<RouterHandle>,
@@ -1470,14 +1828,41 @@ impl Connection {
         // and writer futures are run on the same fiber, and both of them
         // are carefully written in such a way that they do not hold the lock
         // across .await points. Therefore, it should not be too expensive.
-        let handler_map = StdMutex::new(ResponseHandlerMap::new());
+        #[allow(unused_mut)]
+        let mut handler_map = ResponseHandlerMap::new();
+
+        #[cfg(feature = "metrics")]
+        let _open_connection = config.connection_gauges.as_ref().map(|gauges| {
+            handler_map.stream_ids_in_use = Some(gauges.track(ConnectionGauge::StreamIdsInUse));
+            let mut open_connection = gauges.track(ConnectionGauge::OpenConnections);
+            open_connection.add(1);
+            open_connection
+        });
 
-        let write_coalescing_delay = config.write_coalescing_delay;
+        let handler_map = StdMutex::new(handler_map);
+
+        let writer_settings = WriterSettings {
+            coalescing_delay: config.write_coalescing_delay,
+            coalescing_max_bytes: config.write_coalescing_max_bytes,
+            #[cfg(feature = "metrics")]
+            metrics: config.metrics.clone(),
+            #[cfg(feature = "metrics")]
+            connection_gauges: config.connection_gauges.clone(),
+            request_buffers: Arc::clone(&router_handle.request_buffers),
+        };
 
         let k = Self::keepaliver(
             router_handle,
-            config.keepalive_interval,
-            config.keepalive_timeout,
+            KeepaliveSettings {
+                interval: config.keepalive_interval,
+                timeout: config.keepalive_timeout,
+                request: config.keepalive_request,
+                close_on_failure: config.close_on_keepalive_failure,
+                compression: config.compression,
+                response_frame_limits: config.response_frame_limits,
+                #[cfg(feature = "metrics")]
+                metrics: config.metrics,
+            },
             node_address,
         );
 
@@ -1486,12 +1871,13 @@ impl Connection {
             &handler_map,
             config.event_sender,
             config.compression,
+            config.response_frame_limits,
         );
         let w = Self::writer(
             BufWriter::with_capacity(8192, write_half),
             &handler_map,
             receiver,
-            write_coalescing_delay,
+            writer_settings,
         );
         let o = Self::orphaner(&handler_map, orphan_notification_receiver);
 
@@ -1520,9 +1906,15 @@ impl Connection {
         handler_map: &StdMutex<ResponseHandlerMap>,
         event_sender: Option<mpsc::Sender<Event>>,
         compression: Option<Compression>,
+        response_frame_limits: ResponseFrameLimits,
     ) -> Result<(), BrokenConnectionError> {
+        let mut response_buffers = ResponseBufferPool::new();
         loop {
-            let (params, opcode, body) = frame::read_response_frame(&mut read_half)
+            let response_buffers = &mut response_buffers;
+            let (params, opcode, body) =
+                frame::read_response_frame_into(&mut read_half, &response_frame_limits, |length| {
+                    response_buffers.buffer_for_body(length)
+                })
                 .await
                 .map_err(BrokenConnectionErrorKind::FrameHeaderParseError)?;
             let response = TaskResponse {
@@ -1540,9 +1932,14 @@ impl Connection {
                 }
                 Ordering::Equal => {
                     if let Some(event_sender) = event_sender.as_ref() {
-                        Self::handle_event(response, compression, event_sender)
-                            .await
-                            .map_err(BrokenConnectionErrorKind::CqlEventHandlingError)?
+                        Self::handle_event(
+                            response,
+                            compression,
+                            &response_frame_limits,
+                            event_sender,
+                        )
+                        .await
+                        .map_err(BrokenConnectionErrorKind::CqlEventHandlingError)?
                     }
                     continue;
                 }
@@ -1568,6 +1965,7 @@ impl Connection {
                     // Unsolicited frame. This should not happen and indicates
                     // a bug either in the driver, or in the database
                     debug!(
+                        target: log_targets::FRAME,
                         "Received response with unexpected StreamId {}",
                         params.stream
                     );
@@ -1591,7 +1989,7 @@ impl Connection {
         match handler_map_guard.allocate(response_handler) {
             Ok(stream_id) => Some(stream_id),
             Err(response_handler) => {
-                error!("Could not allocate stream id");
+                error!(target: log_targets::FRAME, "Could not allocate stream id");
                 let _ = response_handler
                     .response_sender
                     .send(Err(InternalRequestError::UnableToAllocStreamId));
@@ -1601,56 +1999,87 @@ impl Connection {
     }
 
     async fn writer(
-        mut write_half: impl AsyncWrite + Unpin,
+        mut write_half: BufWriter<impl AsyncWrite + Unpin>,
         handler_map: &StdMutex<ResponseHandlerMap>,
-        mut task_receiver: mpsc::Receiver<Task>,
-        write_coalescing_delay: Option<WriteCoalescingDelay>,
+        mut task_receiver: PriorityReceiver<Task>,
+        settings: WriterSettings,
     ) -> Result<(), BrokenConnectionError> {
         // When the Connection object is dropped, the sender half
         // of the channel will be dropped, this task will return an error
         // and the whole worker will be stopped
+        #[cfg(feature = "metrics")]
+        let mut unflushed = settings
+            .connection_gauges
+            .as_ref()
+            .map(|gauges| gauges.track(ConnectionGauge::UnflushedRequests));
+
         while let Some(mut task) = task_receiver.recv().await {
             let mut num_requests = 0;
             let mut total_sent = 0;
+            let mut coalescing_delay = Duration::ZERO;
             while let Some(stream_id) = Self::alloc_stream_id(handler_map, task.response_handler) {
+                #[cfg(feature = "metrics")]
+                drop(task.queued);
                 let mut req = task.serialized_request;
                 req.set_stream(stream_id);
-                let req_data: &[u8] = req.get_data();
-                total_sent += req_data.len();
+                total_sent += req.get_data().len();
                 num_requests += 1;
-                write_half
-                    .write_all(req_data)
-                    .await
-                    .map_err(BrokenConnectionErrorKind::WriteError)?;
+                let (req_buffer, write_result) = write_request(&mut write_half, req).await;
+                settings.request_buffers.give_back(req_buffer);
+                write_result.map_err(BrokenConnectionErrorKind::WriteError)?;
+                #[cfg(feature = "metrics")]
+                if let Some(unflushed) = &mut unflushed {
+                    unflushed.add(1);
+                }
+                if settings
+                    .coalescing_max_bytes
+                    .is_some_and(|max_bytes| total_sent >= max_bytes.get())
+                {
+                    // Enough data was collected; flush it without waiting for more.
+                    break;
+                }
                 task = match task_receiver.try_recv() {
-                    Ok(t) => t,
-                    Err(_) => match write_coalescing_delay {
-                        Some(WriteCoalescingDelay::SmallNondeterministic) => {
-                            // Yielding was empirically tested to inject a 1-300µs delay,
-                            // much better than tokio::time::sleep's 1ms granularity.
-                            // Also, yielding in a busy system let's the queue catch up with new items.
-                            tokio::task::yield_now().await;
-                            match task_receiver.try_recv() {
-                                Ok(t) => t,
-                                Err(_) => break,
+                    Some(t) => t,
+                    None => {
+                        let delay_start = Instant::now();
+                        match settings.coalescing_delay {
+                            Some(WriteCoalescingDelay::SmallNondeterministic) => {
+                                // Yielding was empirically tested to inject a 1-300µs delay,
+                                // much better than tokio::time::sleep's 1ms granularity.
+                                // Also, yielding in a busy system let's the queue catch up with new items.
+                                runtime::yield_now().await;
                             }
-                        }
-                        Some(WriteCoalescingDelay::Milliseconds(ms)) => {
-                            tokio::time::sleep(Duration::from_millis(ms.get())).await;
-                            match task_receiver.try_recv() {
-                                Ok(t) => t,
-                                Err(_) => break,
+                            Some(WriteCoalescingDelay::Milliseconds(ms)) => {
+                                runtime::sleep(Duration::from_millis(ms.get())).await;
                             }
+                            None => break,
+                        }
+                        coalescing_delay += delay_start.elapsed();
+                        match task_receiver.try_recv() {
+                            Some(t) => t,
+                            None => break,
                         }
-                        None => break,
-                    },
+                    }
                 }
             }
-            trace!("Sending {} requests; {} bytes", num_requests, total_sent);
+            trace!(
+                target: log_targets::FRAME,
+                "Sending {} requests; {} bytes; coalesced for {:?}",
+                num_requests, total_sent, coalescing_delay
+            );
             write_half
                 .flush()
                 .await
                 .map_err(BrokenConnectionErrorKind::WriteError)?;
+
+            #[cfg(feature = "metrics")]
+            if let Some(metrics) = &settings.metrics {
+                metrics.log_write_flush(num_requests, total_sent, coalescing_delay);
+            }
+            #[cfg(feature = "metrics")]
+            if let Some(unflushed) = &mut unflushed {
+                unflushed.sub(num_requests as u64);
+            }
         }
 
         Ok(())
@@ -1664,7 +2093,7 @@ impl Connection {
         handler_map: &StdMutex<ResponseHandlerMap>,
         mut orphan_receiver: mpsc::UnboundedReceiver<RequestId>,
     ) -> Result<(), BrokenConnectionError> {
-        let mut interval = tokio::time::interval(OLD_AGE_ORPHAN_THRESHOLD);
+        let mut interval = runtime::interval(OLD_AGE_ORPHAN_THRESHOLD);
         loop {
             tokio::select! {
                 _ = interval.tick() => {
@@ -1674,6 +2103,7 @@ impl Connection {
                     let old_orphan_count = handler_map_guard.old_orphans_count();
                     if old_orphan_count > OLD_ORPHAN_COUNT_THRESHOLD {
                         warn!(
+                            target: log_targets::FRAME,
                             "Too many old orphaned stream ids: {}",
                             old_orphan_count,
                         );
@@ -1682,6 +2112,7 @@ impl Connection {
                 }
                 Some(request_id) = orphan_receiver.recv() => {
                     trace!(
+                        target: log_targets::FRAME,
                         "Trying to orphan stream id associated with request_id = {}",
                         request_id,
                     );
@@ -1697,71 +2128,116 @@ impl Connection {
 
     async fn keepaliver(
         router_handle: Arc<RouterHandle>,
-        keepalive_interval: Option<Duration>,
-        keepalive_timeout: Option<Duration>,
+        settings: KeepaliveSettings,
         node_address: IpAddr, // This address is only used to enrich the log messages
     ) -> Result<(), BrokenConnectionError> {
-        async fn issue_keepalive_query(
+        async fn issue_keepalive_request(
             router_handle: &RouterHandle,
+            settings: &KeepaliveSettings,
         ) -> Result<(), BrokenConnectionError> {
-            router_handle
-                .send_request(&Options, None, false)
-                .await
-                .map(|_| ())
-                .map_err(|req_err| {
-                    BrokenConnectionErrorKind::KeepaliveRequestError(Arc::new(req_err)).into()
-                })
+            let to_broken_connection_error = |err: Arc<dyn std::error::Error + Send + Sync>| {
+                BrokenConnectionError::from(BrokenConnectionErrorKind::KeepaliveRequestError(err))
+            };
+            match &settings.request {
+                KeepaliveRequest::Options => router_handle
+                    .send_request(&Options, None, false, RequestPriority::High, None, None)
+                    .await
+                    .map(|_| ())
+                    .map_err(|req_err| to_broken_connection_error(Arc::new(req_err))),
+                KeepaliveRequest::Query(contents) => {
+                    let query = query::Query {
+                        contents: Cow::Borrowed(contents),
+                        parameters: query::QueryParameters {
+                            consistency: Consistency::One,
+                            ..Default::default()
+                        },
+                    };
+                    let task_response = router_handle
+                        .send_request(&query, None, false, RequestPriority::High, None, None)
+                        .await
+                        .map_err(|req_err| to_broken_connection_error(Arc::new(req_err)))?;
+                    Connection::parse_response(
+                        task_response,
+                        settings.compression,
+                        &settings.response_frame_limits,
+                        &ProtocolFeatures::default(),
+                        None,
+                    )
+                    .map_err(|err| to_broken_connection_error(Arc::new(err)))?
+                    .into_non_error_query_response()
+                    .map(|_| ())
+                    .map_err(|err| to_broken_connection_error(Arc::new(err)))
+                }
+            }
         }
 
-        if let Some(keepalive_interval) = keepalive_interval {
-            let mut interval = tokio::time::interval(keepalive_interval);
-            interval.tick().await; // Use up the first, instant tick.
-
-            // Default behaviour (Burst) is not suitable for sending keepalives.
-            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
-
-            loop {
-                interval.tick().await;
-
-                let keepalive_query = issue_keepalive_query(&router_handle);
-                let query_result = if let Some(timeout) = keepalive_timeout {
-                    match tokio::time::timeout(timeout, keepalive_query).await {
-                        Ok(res) => res,
-                        Err(_) => {
-                            warn!(
-                                "Timed out while waiting for response to keepalive request on connection to node {}",
-                                node_address
-                            );
-                            return Err(
-                                BrokenConnectionErrorKind::KeepaliveTimeout(node_address).into()
-                            );
-                        }
+        let Some(keepalive_interval) = settings.interval else {
+            // No keepalives are to be sent.
+            return Ok(());
+        };
+
+        let mut interval = runtime::interval(keepalive_interval);
+        interval.tick().await; // Use up the first, instant tick.
+
+        loop {
+            interval.tick().await;
+
+            #[cfg(feature = "metrics")]
+            if let Some(metrics) = &settings.metrics {
+                metrics.inc_keepalive_requests();
+            }
+
+            let keepalive_request = issue_keepalive_request(&router_handle, &settings);
+            let request_result = if let Some(timeout) = settings.timeout {
+                match runtime::timeout(timeout, keepalive_request).await {
+                    Ok(res) => res,
+                    Err(_) => {
+                        warn!(
+                            target: log_targets::CONNECTION,
+                            "Timed out while waiting for response to keepalive request on connection to node {}",
+                            node_address
+                        );
+                        Err(BrokenConnectionErrorKind::KeepaliveTimeout(node_address).into())
                     }
-                } else {
-                    keepalive_query.await
-                };
-                if let Err(err) = query_result {
-                    warn!(
-                        "Failed to execute keepalive request on connection to node {} - {}",
-                        node_address, err
-                    );
-                    return Err(err);
                 }
+            } else {
+                keepalive_request.await
+            };
 
-                trace!(
+            match request_result {
+                Ok(()) => trace!(
+                    target: log_targets::CONNECTION,
                     "Keepalive request successful on connection to node {}",
                     node_address
-                );
+                ),
+                Err(err) => {
+                    #[cfg(feature = "metrics")]
+                    if let Some(metrics) = &settings.metrics {
+                        metrics.inc_keepalive_failures();
+                    }
+
+                    if settings.close_on_failure {
+                        warn!(
+                            target: log_targets::CONNECTION,
+                            "Failed to execute keepalive request on connection to node {} - {}",
+                            node_address, err
+                        );
+                        return Err(err);
+                    }
+                    warn!(
+                        target: log_targets::CONNECTION,
+                        "Failed to execute keepalive request on connection to node {}, keeping the connection open - {}",
+                        node_address, err
+                    );
+                }
             }
-        } else {
-            // No keepalives are to be sent.
-            Ok(())
         }
     }
 
     async fn handle_event(
         task_response: TaskResponse,
         compression: Option<Compression>,
+        response_frame_limits: &ResponseFrameLimits,
         event_sender: &mpsc::Sender<Event>,
     ) -> Result<(), CqlEventHandlingError> {
         // Protocol features are negotiated during connection handshake.
@@ -1775,11 +2251,21 @@ impl Connection {
         // future implementers.
         let features = ProtocolFeatures::default(); // TODO: Use the right features
 
-        let event = match Self::parse_response(task_response, compression, &features, None) {
+        let event = match Self::parse_response(
+            task_response,
+            compression,
+            response_frame_limits,
+            &features,
+            None,
+        ) {
             Ok(r) => match r.response {
                 ResponseWithDeserializedMetadata::Event(event) => event,
                 _ => {
-                    error!("Expected to receive Event response, got {:?}", r.response);
+                    error!(
+                        target: log_targets::TOPOLOGY,
+                        "Expected to receive Event response, got {:?}",
+                        r.response,
+                    );
                     return Err(CqlEventHandlingError::UnexpectedResponse(
                         r.response.to_response_kind(),
                     ));
@@ -1817,6 +2303,24 @@ impl Connection {
         self.features = features;
     }
 
+    /// Returns the number of requests sent on this connection which have not completed yet.
+    pub(crate) fn in_flight_requests(&self) -> usize {
+        self.router_handle
+            .in_flight_requests
+            .load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Returns the time since the connection was opened.
+    pub(crate) fn age(&self) -> Duration {
+        self.router_handle.created_at.elapsed()
+    }
+
+    /// Returns the time since a request was last sent or completed on this connection,
+    /// or zero if it has requests in flight.
+    pub(crate) fn idle_time(&self) -> Duration {
+        self.router_handle.idle_time()
+    }
+
     pub(crate) fn get_connect_address(&self) -> SocketAddr {
         self.connect_address
     }
@@ -1836,6 +2340,7 @@ impl Connection {
                 None => return Ok(()),
             };
             tracing::trace!(
+                target: log_targets::METADATA,
                 "Received tablet info for table {}.{} in custom payload: {:?}",
                 table.ks_name(),
                 table.table_name(),
@@ -1872,7 +2377,12 @@ async fn maybe_translated_addr(
                         })
                         .await;
                     if let Err(ref err) = res {
-                        error!("Address translation failed for addr {}: {}", addr, err);
+                        error!(
+                            target: log_targets::CONNECTION,
+                            "Address translation failed for addr {}: {}",
+                            addr,
+                            err,
+                        );
                     }
                     res
                 } else {
@@ -1919,16 +2429,18 @@ pub(crate) async fn open_connection(
         Ok(info) => Some(info),
         Err(ShardingError::NoShardInfo) => {
             tracing::info!(
-                "[{}] No sharding information received. Proceeding with no sharding info.",
-                addr
+                target: log_targets::CONNECTION,
+                node = %addr,
+                "No sharding information received. Proceeding with no sharding info.",
             );
             None
         }
         Err(e) => {
             tracing::error!(
-                "[{}] Error while parsing sharding information: {}. Proceeding with no sharding info.",
-                addr,
-                e
+                target: log_targets::CONNECTION,
+                node = %addr,
+                "Error while parsing sharding information: {}. Proceeding with no sharding info.",
+                e,
             );
             None
         }
@@ -1982,6 +2494,7 @@ pub(crate) async fn open_connection(
         } else {
             // Fall back to no compression
             tracing::warn!(
+                target: log_targets::CONNECTION,
                 "Requested compression <{}> is not supported by the cluster. Falling back to no compression",
                 compression_str
             );
@@ -2034,29 +2547,102 @@ pub(super) async fn open_connection_to_shard_aware_port(
     Err(ConnectionError::NoSourcePortForShard(shard))
 }
 
-async fn connect_with_source_ip_and_port(
+pub(super) async fn connect_with_source_ip_and_port(
     connect_address: SocketAddr,
     source_ip: Option<IpAddr>,
     source_port: Option<u16>,
+    socket_options: &SocketOptions,
 ) -> Result<TcpStream, std::io::Error> {
     // Binding to port 0 is equivalent to choosing random ephemeral port.
     let source_port = source_port.unwrap_or(0);
 
-    match connect_address {
-        SocketAddr::V4(_) => {
-            // If source_ip not provided, bind to INADDR_ANY.
-            let source_ipv4 = source_ip.unwrap_or(Ipv4Addr::UNSPECIFIED.into());
-            let socket = TcpSocket::new_v4()?;
-            socket.bind(SocketAddr::new(source_ipv4, source_port))?;
-            Ok(socket.connect(connect_address).await?)
+    let (source_ip, is_ipv6) = match connect_address {
+        // If source_ip not provided, bind to INADDR_ANY.
+        SocketAddr::V4(_) => (source_ip.unwrap_or(Ipv4Addr::UNSPECIFIED.into()), false),
+        // If source_ip not provided, bind to in6addr_any.
+        SocketAddr::V6(_) => (source_ip.unwrap_or(Ipv6Addr::UNSPECIFIED.into()), true),
+    };
+    runtime::connect_tcp(
+        connect_address,
+        SocketAddr::new(source_ip, source_port),
+        |sf| socket_options.apply(sf, is_ipv6),
+    )
+    .await
+}
+
+/// Options applied to TCP sockets before connecting.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct SocketOptions {
+    pub(crate) recv_buffer_size: Option<usize>,
+    pub(crate) send_buffer_size: Option<usize>,
+    pub(crate) ip_tos: Option<u8>,
+    pub(crate) bind_interface: Option<String>,
+}
+
+impl SocketOptions {
+    fn apply(&self, sf: &SockRef, is_ipv6: bool) -> Result<(), std::io::Error> {
+        if let Some(size) = self.recv_buffer_size {
+            sf.set_recv_buffer_size(size)?;
         }
-        SocketAddr::V6(_) => {
-            // If source_ip not provided, bind to in6addr_any.
-            let source_ipv6 = source_ip.unwrap_or(Ipv6Addr::UNSPECIFIED.into());
-            let socket = TcpSocket::new_v6()?;
-            socket.bind(SocketAddr::new(source_ipv6, source_port))?;
-            Ok(socket.connect(connect_address).await?)
+        if let Some(size) = self.send_buffer_size {
+            sf.set_send_buffer_size(size)?;
         }
+        if let Some(tos) = self.ip_tos {
+            Self::set_ip_tos(sf, tos, is_ipv6)?;
+        }
+        if let Some(interface) = &self.bind_interface {
+            Self::bind_interface(sf, interface)?;
+        }
+        Ok(())
+    }
+
+    // These cfg values are taken from socket2 library, as the intersection of platforms
+    // supporting IP_TOS and IPV6_TCLASS.
+    #[cfg(any(
+        target_os = "android",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    fn set_ip_tos(sf: &SockRef, tos: u8, is_ipv6: bool) -> Result<(), std::io::Error> {
+        if is_ipv6 {
+            sf.set_tclass_v6(tos.into())
+        } else {
+            sf.set_tos(tos.into())
+        }
+    }
+
+    #[cfg(not(any(
+        target_os = "android",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    )))]
+    fn set_ip_tos(_sf: &SockRef, _tos: u8, _is_ipv6: bool) -> Result<(), std::io::Error> {
+        warn!(
+            target: log_targets::CONNECTION,
+            "Setting IP TOS is not supported on this platform, ignoring it",
+        );
+        Ok(())
+    }
+
+    #[cfg(any(target_os = "android", target_os = "fuchsia", target_os = "linux"))]
+    fn bind_interface(sf: &SockRef, interface: &str) -> Result<(), std::io::Error> {
+        sf.bind_device(Some(interface.as_bytes()))
+    }
+
+    #[cfg(not(any(target_os = "android", target_os = "fuchsia", target_os = "linux")))]
+    fn bind_interface(_sf: &SockRef, _interface: &str) -> Result<(), std::io::Error> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "binding sockets to a network interface is not supported on this platform",
+        ))
     }
 }
 
@@ -2106,6 +2692,10 @@ struct ResponseHandlerMap {
 
     request_to_stream: HashMap<RequestId, i16>,
     orphanage_tracker: OrphanageTracker,
+
+    // Counts the stream ids allocated in `stream_set` in the gauges of the node.
+    #[cfg(feature = "metrics")]
+    stream_ids_in_use: Option<GaugeGuard>,
 }
 
 enum HandlerLookupResult {
@@ -2121,11 +2711,17 @@ impl ResponseHandlerMap {
             handlers: HashMap::new(),
             request_to_stream: HashMap::new(),
             orphanage_tracker: OrphanageTracker::new(),
+            #[cfg(feature = "metrics")]
+            stream_ids_in_use: None,
         }
     }
 
     fn allocate(&mut self, response_handler: ResponseHandler) -> Result<i16, ResponseHandler> {
         if let Some(stream_id) = self.stream_set.allocate() {
+            #[cfg(feature = "metrics")]
+            if let Some(stream_ids_in_use) = &mut self.stream_ids_in_use {
+                stream_ids_in_use.add(1);
+            }
             self.request_to_stream
                 .insert(response_handler.request_id, stream_id);
             let prev_handler = self.handlers.insert(stream_id, response_handler);
@@ -2142,6 +2738,7 @@ impl ResponseHandlerMap {
     fn orphan(&mut self, request_id: RequestId) {
         if let Some(stream_id) = self.request_to_stream.get(&request_id) {
             debug!(
+                target: log_targets::FRAME,
                 "Orphaning stream_id = {} associated with request_id = {}",
                 stream_id, request_id
             );
@@ -2158,6 +2755,10 @@ impl ResponseHandlerMap {
 
     fn lookup(&mut self, stream_id: i16) -> HandlerLookupResult {
         self.stream_set.free(stream_id);
+        #[cfg(feature = "metrics")]
+        if let Some(stream_ids_in_use) = &mut self.stream_ids_in_use {
+            stream_ids_in_use.sub(1);
+        }
 
         if self.orphanage_tracker.contains(stream_id) {
             self.orphanage_tracker.remove(stream_id);
@@ -2287,22 +2888,39 @@ mod tests {
     use scylla_cql::serialize::row::SerializedValues;
     use scylla_proxy::{
         Condition, Node, Proxy, Reaction, RequestFrame, RequestOpcode, RequestReaction,
-        RequestRule, ResponseFrame, ShardAwareness,
+        RequestRule, ResponseFrame, ResponseOpcode, ShardAwareness,
     };
 
+    use async_trait::async_trait;
+    use bytes::{BufMut, Bytes, BytesMut};
+    use scylla_cql::frame::response::ResponseWithDeserializedMetadata;
+    use tokio::io::BufWriter;
     use tokio::select;
     use tokio::sync::mpsc;
 
-    use super::{HostConnectionConfig, open_connection};
+    use super::{
+        HostConnectionConfig, RequestSettings, SocketOptions, connect_with_source_ip_and_port,
+        open_connection, write_request,
+    };
+    use crate::authentication::{
+        AuthError, AuthenticatorProvider, AuthenticatorSession, PlainTextAuthenticator,
+    };
+    use crate::client::execution_profile::ExecutionProfile;
     use crate::cluster::metadata::UntranslatedEndpoint;
     use crate::cluster::node::ResolvedContactPoint;
+    use crate::errors::DbError;
+    use crate::frame::SerializedRequest;
+    use crate::frame::request::{self, options::Options};
+    use crate::response::PagingState;
+    use crate::statement::Consistency;
     use crate::statement::unprepared::Statement;
     use crate::test_utils::setup_tracing;
+    use crate::utils::runtime;
     use crate::utils::test_utils::{PerformDDL, resolve_hostname, unique_keyspace_name};
     use futures::{StreamExt, TryStreamExt};
     use std::collections::HashMap;
     use std::net::SocketAddr;
-    use std::sync::Arc;
+    use std::sync::{Arc, Mutex};
     use std::time::Duration;
 
     /// Tests for Connection::execute_iter
@@ -2319,7 +2937,10 @@ mod tests {
         let addr: SocketAddr = resolve_hostname(&uri).await;
 
         let (connection, _) = super::open_connection(
-            &UntranslatedEndpoint::ContactPoint(ResolvedContactPoint { address: addr }),
+            &UntranslatedEndpoint::ContactPoint(ResolvedContactPoint {
+                address: addr,
+                resolved_from: None,
+            }),
             None,
             &HostConnectionConfig::default(),
         )
@@ -2453,7 +3074,10 @@ mod tests {
 
         let subtest = |write_coalescing_delay: Option<WriteCoalescingDelay>, ks: String| async move {
             let (connection, _) = super::open_connection(
-                &UntranslatedEndpoint::ContactPoint(ResolvedContactPoint { address: addr }),
+                &UntranslatedEndpoint::ContactPoint(ResolvedContactPoint {
+                    address: addr,
+                    resolved_from: None,
+                }),
                 None,
                 &HostConnectionConfig {
                     write_coalescing_delay,
@@ -2603,6 +3227,7 @@ mod tests {
         // We must interrupt the driver's full connection opening, because our proxy does not interact further after Startup.
         let endpoint = UntranslatedEndpoint::ContactPoint(ResolvedContactPoint {
             address: proxy_addr,
+            resolved_from: None,
         });
         let (startup_without_lwt_optimisation, _shard) = select! {
             _ = open_connection(&endpoint, None, &config) => unreachable!(),
@@ -2673,6 +3298,7 @@ mod tests {
         let (conn, mut error_receiver) = open_connection(
             &UntranslatedEndpoint::ContactPoint(ResolvedContactPoint {
                 address: proxy_addr,
+                resolved_from: None,
             }),
             None,
             &config,
@@ -2713,4 +3339,375 @@ mod tests {
 
         let _ = proxy.finish().await;
     }
+
+    #[tokio::test]
+    #[ntest::timeout(20000)]
+    async fn connection_is_kept_open_on_keepalive_failure_if_configured() {
+        setup_tracing();
+
+        let proxy_addr = SocketAddr::new(scylla_proxy::get_exclusive_local_address(), 9042);
+        let handshake_rules = vec![
+            RequestRule(
+                Condition::RequestOpcode(RequestOpcode::Options),
+                RequestReaction::forge_response(Arc::new(|frame: RequestFrame| {
+                    ResponseFrame::forged_supported(frame.params, &HashMap::new()).unwrap()
+                })),
+            ),
+            RequestRule(
+                Condition::RequestOpcode(RequestOpcode::Startup),
+                RequestReaction::forge_response(Arc::new(|frame: RequestFrame| {
+                    ResponseFrame::forged_ready(frame.params)
+                })),
+            ),
+        ];
+        let mut proxy = Proxy::builder()
+            .with_node(
+                Node::builder()
+                    .proxy_address(proxy_addr)
+                    .request_rules(handshake_rules)
+                    .build_dry_mode(),
+            )
+            .build()
+            .run()
+            .await
+            .unwrap();
+
+        let config = HostConnectionConfig {
+            keepalive_interval: Some(Duration::from_millis(100)),
+            keepalive_timeout: Some(Duration::from_millis(100)),
+            close_on_keepalive_failure: false,
+            ..Default::default()
+        };
+        let (_conn, mut error_receiver) = open_connection(
+            &UntranslatedEndpoint::ContactPoint(ResolvedContactPoint {
+                address: proxy_addr,
+                resolved_from: None,
+            }),
+            None,
+            &config,
+        )
+        .await
+        .unwrap();
+
+        // Keepalives are not answered from now on.
+        proxy.running_nodes[0].change_request_rules(Some(vec![RequestRule(
+            Condition::RequestOpcode(RequestOpcode::Options),
+            RequestReaction::drop_frame(),
+        )]));
+        tokio::time::sleep(Duration::from_millis(1000)).await;
+
+        // Several keepalives timed out, but the connection was not closed.
+        assert_matches!(
+            error_receiver.try_recv(),
+            Err(tokio::sync::oneshot::error::TryRecvError::Empty)
+        );
+
+        let _ = proxy.finish().await;
+    }
+
+    #[tokio::test]
+    async fn socket_options_are_applied() {
+        setup_tracing();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        let default_stream =
+            connect_with_source_ip_and_port(address, None, None, &SocketOptions::default())
+                .await
+                .unwrap();
+        let default_size = runtime::sock_ref(&default_stream)
+            .recv_buffer_size()
+            .unwrap();
+
+        let socket_options = SocketOptions {
+            recv_buffer_size: Some(default_size * 2),
+            send_buffer_size: Some(64 * 1024),
+            ..Default::default()
+        };
+        let stream = connect_with_source_ip_and_port(address, None, None, &socket_options)
+            .await
+            .unwrap();
+        let sf = runtime::sock_ref(&stream);
+        // The operating system may adjust the sizes, e.g. Linux doubles them.
+        assert!(sf.recv_buffer_size().unwrap() >= default_size * 2);
+        assert!(sf.send_buffer_size().unwrap() >= 64 * 1024);
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(20000)]
+    async fn multi_round_authentication() {
+        setup_tracing();
+
+        struct ChallengeAnsweringSession {
+            challenges: Arc<Mutex<Vec<Vec<u8>>>>,
+        }
+
+        #[async_trait]
+        impl AuthenticatorSession for ChallengeAnsweringSession {
+            async fn evaluate_challenge(
+                &mut self,
+                token: Option<&[u8]>,
+            ) -> Result<Option<Vec<u8>>, AuthError> {
+                let token = token.unwrap().to_vec();
+                let response = [b"answer to ", token.as_slice()].concat();
+                self.challenges.lock().unwrap().push(token);
+                Ok(Some(response))
+            }
+
+            async fn success(&mut self, token: Option<&[u8]>) -> Result<(), AuthError> {
+                assert_eq!(token, Some(&b"welcome"[..]));
+                Ok(())
+            }
+        }
+
+        struct SaslAuthenticator {
+            node_address: Mutex<Option<SocketAddr>>,
+            challenges: Arc<Mutex<Vec<Vec<u8>>>>,
+        }
+
+        #[async_trait]
+        impl AuthenticatorProvider for SaslAuthenticator {
+            async fn start_authentication_session(
+                &self,
+                _authenticator_name: &str,
+            ) -> Result<(Option<Vec<u8>>, Box<dyn AuthenticatorSession>), AuthError> {
+                unreachable!("The driver passes the node address")
+            }
+
+            async fn start_authentication_session_for_node(
+                &self,
+                authenticator_name: &str,
+                node_address: SocketAddr,
+            ) -> Result<(Option<Vec<u8>>, Box<dyn AuthenticatorSession>), AuthError> {
+                assert_eq!(authenticator_name, "com.example.SaslAuthenticator");
+                *self.node_address.lock().unwrap() = Some(node_address);
+                let session = ChallengeAnsweringSession {
+                    challenges: self.challenges.clone(),
+                };
+                Ok((Some(b"initial".to_vec()), Box::new(session)))
+            }
+        }
+
+        // Builds a response frame whose body is a single `[bytes]` or `[string]` value.
+        fn response(request: RequestFrame, opcode: ResponseOpcode, value: &[u8]) -> ResponseFrame {
+            let mut body = BytesMut::new();
+            match opcode {
+                ResponseOpcode::Authenticate => body.put_u16(value.len() as u16),
+                _ => body.put_i32(value.len() as i32),
+            }
+            body.put_slice(value);
+            ResponseFrame {
+                params: request.params.for_response(),
+                opcode,
+                body: body.freeze(),
+            }
+        }
+
+        let auth_response = |token: &'static [u8], reply: ResponseOpcode, value: &'static [u8]| {
+            RequestRule(
+                Condition::RequestOpcode(RequestOpcode::AuthResponse)
+                    .and(Condition::BodyContainsCaseSensitive(token.into())),
+                RequestReaction::forge_response(Arc::new(move |frame: RequestFrame| {
+                    response(frame, reply, value)
+                })),
+            )
+        };
+
+        let proxy_addr = SocketAddr::new(scylla_proxy::get_exclusive_local_address(), 9042);
+        let rules = vec![
+            RequestRule(
+                Condition::RequestOpcode(RequestOpcode::Options),
+                RequestReaction::forge_response(Arc::new(|frame: RequestFrame| {
+                    ResponseFrame::forged_supported(frame.params, &HashMap::new()).unwrap()
+                })),
+            ),
+            RequestRule(
+                Condition::RequestOpcode(RequestOpcode::Startup),
+                RequestReaction::forge_response(Arc::new(|frame: RequestFrame| {
+                    response(
+                        frame,
+                        ResponseOpcode::Authenticate,
+                        b"com.example.SaslAuthenticator",
+                    )
+                })),
+            ),
+            auth_response(b"initial", ResponseOpcode::AuthChallenge, b"first"),
+            auth_response(b"answer to first", ResponseOpcode::AuthChallenge, b"second"),
+            auth_response(b"answer to second", ResponseOpcode::AuthSuccess, b"welcome"),
+        ];
+        let proxy = Proxy::builder()
+            .with_node(
+                Node::builder()
+                    .proxy_address(proxy_addr)
+                    .request_rules(rules)
+                    .build_dry_mode(),
+            )
+            .build()
+            .run()
+            .await
+            .unwrap();
+
+        let authenticator = Arc::new(SaslAuthenticator {
+            node_address: Mutex::new(None),
+            challenges: Arc::new(Mutex::new(Vec::new())),
+        });
+        let config = HostConnectionConfig {
+            authenticator: Some(authenticator.clone()),
+            ..Default::default()
+        };
+        open_connection(
+            &UntranslatedEndpoint::ContactPoint(ResolvedContactPoint {
+                address: proxy_addr,
+                resolved_from: None,
+            }),
+            None,
+            &config,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            *authenticator.node_address.lock().unwrap(),
+            Some(proxy_addr)
+        );
+        assert_eq!(
+            *authenticator.challenges.lock().unwrap(),
+            vec![b"first".to_vec(), b"second".to_vec()]
+        );
+
+        let _ = proxy.finish().await;
+    }
+
+    #[tokio::test]
+    async fn auth_response_is_not_retained_after_writing() {
+        setup_tracing();
+        let contains_password = |data: &[u8]| data.windows(8).any(|w| w == b"pa55word");
+
+        let (response, _) = PlainTextAuthenticator::new("cassandra".into(), "pa55word".into())
+            .start_authentication_session("PasswordAuthenticator")
+            .await
+            .unwrap();
+        let auth_response =
+            SerializedRequest::make(&request::AuthResponse { response }, None, false).unwrap();
+        let options = SerializedRequest::make(&Options, None, false).unwrap();
+        let expected_written = [options.get_data(), auth_response.get_data()].concat();
+        assert!(contains_password(auth_response.get_data()));
+
+        let mut write_half = BufWriter::new(Vec::new());
+
+        // Other requests are buffered, and their buffers are returned intact.
+        let (options_buffer, write_result) = write_request(&mut write_half, options).await;
+        write_result.unwrap();
+        assert_eq!(write_half.buffer(), options_buffer);
+
+        // The AUTH_RESPONSE is written after the buffered requests, but bypasses the buffer,
+        // and its serialization buffer is wiped, so no copy of the password outlives it.
+        let (auth_response_buffer, write_result) =
+            write_request(&mut write_half, auth_response).await;
+        write_result.unwrap();
+        assert!(write_half.buffer().is_empty());
+        assert!(auth_response_buffer.iter().all(|byte| *byte == 0));
+        assert_eq!(*write_half.get_ref(), expected_written);
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(20000)]
+    async fn execute_as_is_sent_in_custom_payload() {
+        setup_tracing();
+
+        let proxy_addr = SocketAddr::new(scylla_proxy::get_exclusive_local_address(), 9042);
+        let rules = vec![
+            RequestRule(
+                Condition::RequestOpcode(RequestOpcode::Options),
+                RequestReaction::forge_response(Arc::new(|frame: RequestFrame| {
+                    ResponseFrame::forged_supported(frame.params, &HashMap::new()).unwrap()
+                })),
+            ),
+            RequestRule(
+                Condition::RequestOpcode(RequestOpcode::Startup),
+                RequestReaction::forge_response(Arc::new(|frame: RequestFrame| {
+                    ResponseFrame::forged_ready(frame.params)
+                })),
+            ),
+            // Only requests executed on behalf of the tenant are allowed.
+            RequestRule(
+                Condition::RequestOpcode(RequestOpcode::Query)
+                    .and(Condition::BodyContainsCaseSensitive(
+                        (*b"ProxyExecute").into(),
+                    ))
+                    .and(Condition::BodyContainsCaseSensitive((*b"tenant_a").into())),
+                RequestReaction::forge_response(Arc::new(|frame: RequestFrame| {
+                    let mut params = frame.params.for_response();
+                    // The response has no custom payload, unlike the request.
+                    params.flags = 0;
+                    ResponseFrame {
+                        params,
+                        opcode: ResponseOpcode::Result,
+                        // A `Void` result.
+                        body: Bytes::from_static(&[0, 0, 0, 1]),
+                    }
+                })),
+            ),
+            RequestRule(
+                Condition::RequestOpcode(RequestOpcode::Query),
+                RequestReaction::forge_response(Arc::new(|frame: RequestFrame| {
+                    ResponseFrame::forged_error(frame.params, DbError::Unauthorized, None).unwrap()
+                })),
+            ),
+        ];
+        let proxy = Proxy::builder()
+            .with_node(
+                Node::builder()
+                    .proxy_address(proxy_addr)
+                    .request_rules(rules)
+                    .build_dry_mode(),
+            )
+            .build()
+            .run()
+            .await
+            .unwrap();
+
+        let (connection, _) = open_connection(
+            &UntranslatedEndpoint::ContactPoint(ResolvedContactPoint {
+                address: proxy_addr,
+                resolved_from: None,
+            }),
+            None,
+            &HostConnectionConfig::default(),
+        )
+        .await
+        .unwrap();
+
+        let query = |profile: ExecutionProfile| {
+            let connection = &connection;
+            async move {
+                connection
+                    .query_raw_with_consistency(
+                        &Statement::new("SELECT * FROM ks.t"),
+                        Consistency::One,
+                        None,
+                        RequestSettings::from_profile(&profile.0),
+                        None,
+                        PagingState::start(),
+                    )
+                    .await
+                    .unwrap()
+                    .response
+            }
+        };
+
+        let as_tenant = ExecutionProfile::builder()
+            .execute_as(Some("tenant_a".to_owned()))
+            .build();
+        assert_matches!(
+            query(as_tenant).await,
+            ResponseWithDeserializedMetadata::Result(_)
+        );
+        assert_matches!(
+            query(ExecutionProfile::builder().build()).await,
+            ResponseWithDeserializedMetadata::Error(_)
+        );
+
+        let _ = proxy.finish().await;
+    }
 }
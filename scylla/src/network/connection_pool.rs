@@ -4,12 +4,16 @@ use super::connection::{
 };
 
 use crate::errors::{
-    BrokenConnectionErrorKind, ConnectionError, ConnectionPoolError, UseKeyspaceError,
+    BrokenConnectionErrorKind, ConnectionError, ConnectionPoolError, DynamicPoolSizeError,
+    UseKeyspaceError,
 };
 #[cfg(test)]
 use crate::policies::reconnect::ExponentialReconnectPolicy;
 use crate::policies::reconnect::{ReconnectPolicy, ReconnectPolicySession};
 use crate::routing::{Shard, ShardCount, Sharder};
+use crate::utils::runtime;
+
+use super::event_log::{ConnectionEventKind, ConnectionEventLog};
 
 use crate::cluster::metadata::{PeerEndpoint, UntranslatedEndpoint};
 
@@ -22,15 +26,33 @@ use crate::utils::safe_format::IteratorSafeFormatExt;
 use arc_swap::ArcSwap;
 use futures::{Future, FutureExt, StreamExt, future::RemoteHandle, stream::FuturesUnordered};
 use rand::Rng;
+use std::collections::HashSet;
 use std::convert::TryInto;
 use std::num::NonZeroUsize;
 use std::panic::{RefUnwindSafe, UnwindSafe};
 use std::pin::Pin;
-use std::sync::{Arc, RwLock, Weak};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock, Weak};
+use std::time::Duration;
 use uuid::Uuid;
 
 use tokio::sync::{Notify, mpsc};
-use tracing::{debug, error, trace, warn};
+use tokio::time::Instant;
+use tracing::{debug, error, info, trace, warn};
+
+use crate::observability::log_targets;
+
+/// Logs an event of a connection's lifecycle under the [CONNECTION](log_targets::CONNECTION) target,
+/// at `info` level if verbose connection logging is enabled, and at `debug` level otherwise.
+macro_rules! connection_event {
+    ($verbose:expr, $($arg:tt)+) => {
+        if $verbose {
+            info!(target: log_targets::CONNECTION, $($arg)+);
+        } else {
+            debug!(target: log_targets::CONNECTION, $($arg)+);
+        }
+    };
+}
 
 /// The target size of a per-node connection pool.
 #[derive(Debug, Clone, Copy)]
@@ -56,12 +78,220 @@ impl Default for PoolSize {
     }
 }
 
+// The target size of a pool. Dynamic sizing, if enabled, takes precedence over `PoolSize`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum TargetPoolSize {
+    PerHost(NonZeroUsize),
+    PerShard(NonZeroUsize),
+    Dynamic(DynamicPoolSize),
+}
+
+#[cfg(test)]
+impl Default for TargetPoolSize {
+    fn default() -> Self {
+        TargetPoolSize::PerShard(NonZeroUsize::new(1).unwrap())
+    }
+}
+
+/// Bounds and thresholds of a pool whose number of connections to each shard follows the load.
+///
+/// The load of each shard is sampled periodically. If the connections to the shard
+/// have on average at least [DynamicPoolSize::with_scale_up_threshold] requests in flight,
+/// or a request could not be sent because the connection ran out of stream ids,
+/// a connection to the shard is added. If the average stays below
+/// [DynamicPoolSize::with_scale_down_threshold] for [DynamicPoolSize::with_idle_timeout],
+/// a connection is closed, once its requests complete.
+///
+/// Dynamic sizing is enabled with
+/// [SessionBuilder::dynamic_pool_size](crate::client::session_builder::SessionBuilder::dynamic_pool_size).
+#[derive(Debug, Clone, Copy)]
+pub struct DynamicPoolSize {
+    min: NonZeroUsize,
+    max: NonZeroUsize,
+    scale_up_threshold: usize,
+    scale_down_threshold: usize,
+    idle_timeout: Duration,
+    sampling_interval: Duration,
+}
+
+impl DynamicPoolSize {
+    /// Creates a pool size with at least `min` and at most `max` connections per shard.
+    ///
+    /// By default, a connection is added when connections have 1024 requests in flight on average,
+    /// and one is closed after the average stays below 256 requests for 60 seconds.
+    /// The load is sampled every second.
+    ///
+    /// Returns an error if `min` is greater than `max`.
+    pub fn new(min: NonZeroUsize, max: NonZeroUsize) -> Result<Self, DynamicPoolSizeError> {
+        if min > max {
+            return Err(DynamicPoolSizeError::MinExceedsMax { min, max });
+        }
+        Ok(Self {
+            min,
+            max,
+            scale_up_threshold: 1024,
+            scale_down_threshold: 256,
+            idle_timeout: Duration::from_secs(60),
+            sampling_interval: Duration::from_secs(1),
+        })
+    }
+
+    /// Sets the average number of in-flight requests per connection at which
+    /// a connection to the shard is added.
+    pub fn with_scale_up_threshold(mut self, in_flight_requests: usize) -> Self {
+        self.scale_up_threshold = in_flight_requests;
+        self
+    }
+
+    /// Sets the average number of in-flight requests per connection below which
+    /// the connections to the shard are considered idle.
+    pub fn with_scale_down_threshold(mut self, in_flight_requests: usize) -> Self {
+        self.scale_down_threshold = in_flight_requests;
+        self
+    }
+
+    /// Sets how long connections to a shard must stay idle before one of them is closed.
+    pub fn with_idle_timeout(mut self, idle_timeout: Duration) -> Self {
+        self.idle_timeout = idle_timeout;
+        self
+    }
+
+    /// Sets how often the load of the shards is sampled.
+    pub fn with_sampling_interval(mut self, sampling_interval: Duration) -> Self {
+        self.sampling_interval = sampling_interval;
+        self
+    }
+
+    /// Minimum number of connections per shard.
+    pub fn min(&self) -> NonZeroUsize {
+        self.min
+    }
+
+    /// Maximum number of connections per shard.
+    pub fn max(&self) -> NonZeroUsize {
+        self.max
+    }
+}
+
+/// Makes creating a session wait until connection pools are full, so that the first
+/// requests sent through the session don't pay the latency of establishing connections.
+///
+/// By default, a session is returned as soon as each pool has at least one connection
+/// (or failed to open one). With a warmup, the session is returned once the pools of all
+/// the chosen nodes have the target number of connections (see [PoolSize]) to each shard,
+/// or once the timeout passes - whichever comes first.
+///
+/// When the timeout passes, creating the session fails if less than
+/// [PoolWarmup::with_required_fraction] of the pools are full.
+/// Pools which are not full continue to be filled in the background.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PoolWarmup {
+    timeout: Duration,
+    local_datacenter: Option<String>,
+    required_fraction: f64,
+}
+
+impl PoolWarmup {
+    /// Creates a warmup of the pools of all nodes, which waits for them to be full
+    /// for at most `timeout`.
+    ///
+    /// By default, the session is created even if no pool becomes full before the timeout.
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            local_datacenter: None,
+            required_fraction: 0.0,
+        }
+    }
+
+    /// Limits the warmup to the pools of the nodes in the given datacenter.
+    /// Pools of other nodes are filled in the background as usual.
+    pub fn with_local_datacenter(mut self, datacenter: impl Into<String>) -> Self {
+        self.local_datacenter = Some(datacenter.into());
+        self
+    }
+
+    /// Sets the fraction of the warmed up pools which must be full when the timeout passes,
+    /// for the session to be created. `1.0` requires all pools to be full.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `fraction` is not between 0.0 and 1.0.
+    pub fn with_required_fraction(mut self, fraction: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&fraction),
+            "Required fraction of full pools must be between 0.0 and 1.0"
+        );
+        self.required_fraction = fraction;
+        self
+    }
+
+    /// Maximum time to wait for the pools to be full.
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// The datacenter whose nodes' pools are warmed up, or `None` if all nodes' pools are.
+    pub fn local_datacenter(&self) -> Option<&str> {
+        self.local_datacenter.as_deref()
+    }
+
+    /// Fraction of the warmed up pools which must be full for the session to be created.
+    pub fn required_fraction(&self) -> f64 {
+        self.required_fraction
+    }
+}
+
+/// Controls whether and how the driver connects to the shard-aware port
+/// (19042, or 19142 with TLS) of ScyllaDB nodes.
+///
+/// Connecting to the shard-aware port lets the driver choose the shard
+/// of each connection, so the pool is filled quickly and evenly.
+/// It requires the client's source ports to be preserved on the way to the node,
+/// which is not the case e.g. behind some NATs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ShardAwarePortUsage {
+    /// Never connect to the shard-aware port; let ScyllaDB assign shards to connections.
+    Disabled,
+
+    /// Connect to the shard-aware port whenever the node advertises it.
+    /// Each attempt that fails or lands on a wrong shard is retried on the regular port.
+    #[default]
+    Preferred,
+
+    /// Like [ShardAwarePortUsage::Preferred], but after `max_failures` consecutive
+    /// attempts to a node's shard-aware port fail or land on a wrong shard,
+    /// the driver stops using that port for the node and connects only to
+    /// the regular port. This avoids paying for a failed attempt on every
+    /// reconnection when the shard-aware port is unreachable.
+    ///
+    /// The shard-aware port is tried again if the node advertises a different one.
+    PreferredWithFallback {
+        /// Number of consecutive failures after which the shard-aware port is abandoned.
+        max_failures: NonZeroUsize,
+    },
+}
+
 #[derive(Clone)]
 pub(crate) struct PoolConfig {
     pub(crate) connection_config: ConnectionConfig,
     pub(crate) pool_size: PoolSize,
-    pub(crate) can_use_shard_aware_port: bool,
+    /// If set, the number of connections to each shard follows the load, and `pool_size` is ignored.
+    pub(crate) dynamic_pool_size: Option<DynamicPoolSize>,
+    pub(crate) shard_aware_port_usage: ShardAwarePortUsage,
     pub(crate) reconnect_policy: Arc<dyn ReconnectPolicy>,
+    /// If true, the pool does not open connections until it is first used.
+    pub(crate) connect_lazily: bool,
+    /// If set, connections of the pool are closed once they all have been idle for this long,
+    /// and opened again when the pool is used.
+    pub(crate) connection_idle_timeout: Option<Duration>,
+    /// If set, connections older than this are replaced with new ones.
+    pub(crate) connection_max_age: Option<Duration>,
+    /// If set, nodes with that many requests in flight are moved to the end of query plans.
+    pub(crate) max_in_flight_requests_per_node: Option<NonZeroUsize>,
+    /// If true, connection lifecycle events are logged at `info` level instead of `debug`.
+    pub(crate) verbose_connection_logging: bool,
 }
 
 #[cfg(test)]
@@ -70,43 +300,60 @@ impl Default for PoolConfig {
         Self {
             connection_config: Default::default(),
             pool_size: Default::default(),
-            can_use_shard_aware_port: true,
+            dynamic_pool_size: None,
+            shard_aware_port_usage: ShardAwarePortUsage::default(),
             reconnect_policy: Arc::new(ExponentialReconnectPolicy::new()),
+            connect_lazily: false,
+            connection_idle_timeout: None,
+            connection_max_age: None,
+            max_in_flight_requests_per_node: None,
+            verbose_connection_logging: false,
         }
     }
 }
 
 impl PoolConfig {
+    fn target_pool_size(&self) -> TargetPoolSize {
+        match (self.dynamic_pool_size, self.pool_size) {
+            (Some(size), _) => TargetPoolSize::Dynamic(size),
+            (None, PoolSize::PerHost(target)) => TargetPoolSize::PerHost(target),
+            (None, PoolSize::PerShard(target)) => TargetPoolSize::PerShard(target),
+        }
+    }
+
     fn to_host_pool_config(
         &self,
         endpoint: &UntranslatedEndpoint,
     ) -> (HostPoolConfig, Box<dyn ReconnectPolicySession>) {
         let host_reconnect_policy = self.reconnect_policy.new_session();
+        let connection_config = self.connection_config.to_host_connection_config(endpoint);
+        // Connections through a proxy have the source port chosen by the proxy,
+        // so the shard-aware port would assign them to random shards.
+        let shard_aware_port_usage = match connection_config.proxy {
+            Some(_) => ShardAwarePortUsage::Disabled,
+            None => self.shard_aware_port_usage,
+        };
         let host_pool_config = HostPoolConfig {
-            connection_config: self.connection_config.to_host_connection_config(endpoint),
-            pool_size: self.pool_size,
-            can_use_shard_aware_port: self.can_use_shard_aware_port,
+            connection_config,
+            pool_size: self.target_pool_size(),
+            shard_aware_port_usage,
+            connection_idle_timeout: self.connection_idle_timeout,
+            connection_max_age: self.connection_max_age,
+            verbose_connection_logging: self.verbose_connection_logging,
         };
         (host_pool_config, host_reconnect_policy)
     }
 }
 
 #[derive(Clone)]
+#[cfg_attr(test, derive(Default))]
 struct HostPoolConfig {
     pub(crate) connection_config: HostConnectionConfig,
-    pub(crate) pool_size: PoolSize,
-    pub(crate) can_use_shard_aware_port: bool,
-}
-
-#[cfg(test)]
-impl Default for HostPoolConfig {
-    fn default() -> Self {
-        Self {
-            connection_config: Default::default(),
-            pool_size: Default::default(),
-            can_use_shard_aware_port: true,
-        }
-    }
+    pub(crate) pool_size: TargetPoolSize,
+    pub(crate) shard_aware_port_usage: ShardAwarePortUsage,
+    pub(crate) connection_idle_timeout: Option<Duration>,
+    pub(crate) connection_max_age: Option<Duration>,
+    pub(crate) verbose_connection_logging: bool,
 }
 
 enum MaybePoolConnections {
@@ -191,6 +438,35 @@ pub(crate) struct NodeConnectionPool {
     _refiller_handle: Arc<RemoteHandle<()>>,
     pool_updated_notify: Arc<Notify>,
     endpoint: Arc<RwLock<UntranslatedEndpoint>>,
+    event_log: Arc<ConnectionEventLog>,
+    /// Present if the pool starts filling only on its first use.
+    lazy_start: Option<Arc<LazyStart>>,
+    /// Present if the size of the pool follows the load.
+    exhausted_shards: Option<Arc<Mutex<HashSet<Shard>>>>,
+    pool_size: TargetPoolSize,
+}
+
+/// Delays the first filling of a pool until the pool is used.
+/// Also used to fill the pool again after it was closed because of being idle.
+struct LazyStart {
+    started: AtomicBool,
+    notify: Notify,
+}
+
+impl LazyStart {
+    fn new(started: bool) -> Self {
+        Self {
+            started: AtomicBool::new(started),
+            notify: Notify::new(),
+        }
+    }
+
+    async fn wait_for_start(lazy_start: Option<&Self>) {
+        match lazy_start {
+            Some(lazy_start) => lazy_start.notify.notified().await,
+            None => std::future::pending().await,
+        }
+    }
 }
 
 impl std::fmt::Debug for NodeConnectionPool {
@@ -198,6 +474,7 @@ impl std::fmt::Debug for NodeConnectionPool {
         f.debug_struct("NodeConnectionPool")
             .field("conns", &self.conns)
             .field("endpoint", &self.endpoint)
+            .field("is_idle", &self.is_idle())
             .finish_non_exhaustive()
     }
 }
@@ -227,11 +504,13 @@ impl NodeConnectionPool {
         let (host_pool_config, host_reconnect_policy) = pool_config.to_host_pool_config(&endpoint);
 
         let arced_endpoint = Arc::new(RwLock::new(endpoint));
+        let event_log = Arc::new(ConnectionEventLog::default());
 
         let refiller = PoolRefiller::new(
             arced_endpoint.clone(),
             host_pool_config,
             connectivity_events_sender,
+            event_log.clone(),
             current_keyspace,
             pool_updated_notify.clone(),
             pool_empty_notifier,
@@ -240,9 +519,16 @@ impl NodeConnectionPool {
             host_reconnect_policy,
         );
 
+        let lazy_start = (pool_config.connect_lazily
+            || pool_config.connection_idle_timeout.is_some())
+        .then(|| Arc::new(LazyStart::new(!pool_config.connect_lazily)));
+
         let conns = refiller.get_shared_connections();
-        let (fut, refiller_handle) = refiller.run(use_keyspace_request_receiver).remote_handle();
-        tokio::spawn(fut);
+        let exhausted_shards = refiller.get_exhausted_shards();
+        let (fut, refiller_handle) = refiller
+            .run(use_keyspace_request_receiver, lazy_start.clone())
+            .remote_handle();
+        runtime::spawn(fut);
 
         Self {
             conns,
@@ -250,9 +536,17 @@ impl NodeConnectionPool {
             _refiller_handle: Arc::new(refiller_handle),
             pool_updated_notify,
             endpoint: arced_endpoint,
+            event_log,
+            lazy_start,
+            exhausted_shards,
+            pool_size: pool_config.target_pool_size(),
         }
     }
 
+    pub(crate) fn event_log(&self) -> &ConnectionEventLog {
+        &self.event_log
+    }
+
     pub(crate) fn is_connected(&self) -> bool {
         let maybe_conns = self.conns.load();
         match maybe_conns.as_ref() {
@@ -263,6 +557,44 @@ impl NodeConnectionPool {
         }
     }
 
+    /// Returns true if the pool is lazy and has not been used yet,
+    /// so it has not tried to open any connections.
+    pub(crate) fn is_idle(&self) -> bool {
+        self.lazy_start
+            .as_ref()
+            .is_some_and(|lazy_start| !lazy_start.started.load(Ordering::Acquire))
+    }
+
+    /// If the pool is lazy, starts filling it on its first use
+    /// and waits until it becomes initialized. Does nothing otherwise.
+    pub(crate) async fn connect_lazily(&self) {
+        let Some(lazy_start) = &self.lazy_start else {
+            return;
+        };
+        // Register for the notification before the refiller can send it.
+        let notified = self.pool_updated_notify.notified();
+        if !lazy_start.started.swap(true, Ordering::AcqRel) {
+            debug!(
+                target: log_targets::POOL,
+                node = %self.endpoint.read().unwrap().address(),
+                "Pool used for the first time, opening connections"
+            );
+            lazy_start.notify.notify_one();
+        }
+        if let MaybePoolConnections::Initializing = **self.conns.load() {
+            notified.await;
+        }
+    }
+
+    /// Records that a request to the given shard could not be sent,
+    /// because the connection ran out of stream ids.
+    /// If the size of the pool follows the load, a connection to the shard is added.
+    pub(crate) fn record_stream_ids_exhausted(&self, shard: Shard) {
+        if let Some(exhausted_shards) = &self.exhausted_shards {
+            exhausted_shards.lock().unwrap().insert(shard);
+        }
+    }
+
     pub(crate) fn update_endpoint(&self, new_endpoint: PeerEndpoint) {
         *self.endpoint.write().unwrap() = UntranslatedEndpoint::Peer(new_endpoint);
     }
@@ -279,7 +611,7 @@ impl NodeConnectionPool {
         &self,
         shard: Shard,
     ) -> Result<Arc<Connection>, ConnectionPoolError> {
-        trace!(shard = shard, "Selecting connection for shard");
+        trace!(target: log_targets::POOL, shard = shard, "Selecting connection for shard");
         self.with_connections(|pool_conns| match pool_conns {
             PoolConnections::NotSharded(conns) => {
                 Self::choose_random_connection_from_slice(conns).unwrap()
@@ -293,7 +625,11 @@ impl NodeConnectionPool {
                     // It's safer to use 0 rather that panic here, as shards are returned by `LoadBalancingPolicy`
                     // now, which can be implemented by a user in an arbitrary way.
                     .unwrap_or_else(|_| {
-                        error!("The provided shard number: {} does not fit u16! Using 0 as the shard number. Check your LoadBalancingPolicy implementation.", shard);
+                        error!(
+                            target: log_targets::POOL,
+                            "The provided shard number: {} does not fit u16! Using 0 as the shard number. Check your LoadBalancingPolicy implementation.",
+                            shard,
+                        );
                         0
                     });
                 Self::connection_for_shard_helper(shard, sharder.nr_shards, connections.as_slice())
@@ -302,7 +638,7 @@ impl NodeConnectionPool {
     }
 
     pub(crate) fn random_connection(&self) -> Result<Arc<Connection>, ConnectionPoolError> {
-        trace!("Selecting random connection");
+        trace!(target: log_targets::POOL, "Selecting random connection");
         self.with_connections(|pool_conns| match pool_conns {
             PoolConnections::NotSharded(conns) => {
                 Self::choose_random_connection_from_slice(conns).unwrap()
@@ -328,16 +664,21 @@ impl NodeConnectionPool {
             .get(shard as usize)
             .or_else(|| {
                 warn!(
+                    target: log_targets::POOL,
                     shard = shard,
                     "Requested shard is out of bounds.\
                     This is most probably a bug in custom LoadBalancingPolicy implementation!\
-                    Targeting a random/arbitrary shard."
+                    Targeting a random/arbitrary shard.",
                 );
                 None
             })
             .and_then(|shard_conns| Self::choose_random_connection_from_slice(shard_conns))
         {
-            trace!(shard = shard, "Found connection for the target shard");
+            trace!(
+                target: log_targets::POOL,
+                shard = shard,
+                "Found connection for the target shard",
+            );
             return conn;
         }
 
@@ -353,9 +694,10 @@ impl NodeConnectionPool {
                 Self::choose_random_connection_from_slice(&shard_conns[shard as usize])
             {
                 trace!(
+                    target: log_targets::POOL,
                     orig_shard = orig_shard,
                     shard = shard,
-                    "Choosing connection for a different shard"
+                    "Choosing connection for a different shard",
                 );
                 return conn;
             }
@@ -385,7 +727,12 @@ impl NodeConnectionPool {
     // Waits until the pool becomes initialized.
     // The pool is considered initialized either if the first connection has been
     // established or after first filling ends, whichever comes first.
+    // Lazy pools which have not been used yet are not waited for.
     pub(crate) async fn wait_until_initialized(&self) {
+        if self.is_idle() {
+            return;
+        }
+
         // First, register for the notification
         // so that we don't miss it
         let notified = self.pool_updated_notify.notified();
@@ -396,6 +743,39 @@ impl NodeConnectionPool {
         }
     }
 
+    // Returns true if the pool has the target number of connections to each shard.
+    // Pools which follow the load are full once they have the minimum number of connections.
+    fn is_full(&self) -> bool {
+        let connections_per_shard = self.connections_per_shard();
+        if connections_per_shard.is_empty() {
+            return false;
+        }
+        match self.pool_size {
+            TargetPoolSize::PerHost(target) => {
+                connections_per_shard.iter().sum::<usize>() >= target.get()
+            }
+            TargetPoolSize::PerShard(target) => connections_per_shard
+                .iter()
+                .all(|&count| count >= target.get()),
+            TargetPoolSize::Dynamic(size) => connections_per_shard
+                .iter()
+                .all(|&count| count >= size.min().get()),
+        }
+    }
+
+    // Starts filling the pool if it is lazy, and waits until the pool is full.
+    pub(crate) async fn wait_until_full(&self) {
+        self.connect_lazily().await;
+        loop {
+            // First, register for the notification so that we don't miss it
+            let notified = self.pool_updated_notify.notified();
+            if self.is_full() {
+                return;
+            }
+            notified.await;
+        }
+    }
+
     pub(crate) fn get_working_connections(
         &self,
     ) -> Result<Vec<Arc<Connection>>, ConnectionPoolError> {
@@ -407,14 +787,27 @@ impl NodeConnectionPool {
         })
     }
 
+    /// Returns the number of open connections to each shard of the node.
+    /// Connections to non-sharded nodes are reported as a single shard.
+    pub(crate) fn connections_per_shard(&self) -> Vec<usize> {
+        self.with_connections(|pool_conns| match pool_conns {
+            PoolConnections::NotSharded(conns) => vec![conns.len()],
+            PoolConnections::Sharded { connections, .. } => {
+                connections.iter().map(Vec::len).collect()
+            }
+        })
+        .unwrap_or_default()
+    }
+
     fn choose_random_connection_from_slice(v: &[Arc<Connection>]) -> Option<Arc<Connection>> {
         trace!(
+            target: log_targets::POOL,
             connections = tracing::field::display(
                 v.iter()
                     .map(|conn| conn.get_connect_address())
                     .safe_format(", ")
             ),
-            "Available"
+            "Available",
         );
         if v.is_empty() {
             None
@@ -443,6 +836,10 @@ impl NodeConnectionPool {
 
 const EXCESS_CONNECTION_BOUND_PER_SHARD_MULTIPLIER: usize = 10;
 
+// Bounds of the period of looking for idle and old connections.
+const MIN_MAINTENANCE_PERIOD: Duration = Duration::from_millis(10);
+const MAX_MAINTENANCE_PERIOD: Duration = Duration::from_secs(1);
+
 struct PoolRefiller {
     // Following information identify the pool and do not change
     pool_config: HostPoolConfig,
@@ -450,6 +847,9 @@ struct PoolRefiller {
     /// If set, used to send connectivity change events about node with given host_id.
     connectivity_events_sender: Option<(Uuid, mpsc::UnboundedSender<ConnectivityChangeEvent>)>,
 
+    /// Significant connection events are recorded here for postmortem inspection.
+    event_log: Arc<ConnectionEventLog>,
+
     // Following information is subject to updates on topology refresh
     endpoint: Arc<RwLock<UntranslatedEndpoint>>,
 
@@ -457,6 +857,10 @@ struct PoolRefiller {
     shard_aware_port: Option<u16>,
     sharder: Option<Sharder>,
 
+    // Number of consecutive shard-aware port connection attempts which failed
+    // or landed on a wrong shard. Reset when the advertised port changes.
+    shard_aware_port_failures: usize,
+
     // `shared_conns` is updated only after `conns` change
     shared_conns: Arc<ArcSwap<MaybePoolConnections>>,
     conns: Vec<Vec<Arc<Connection>>>,
@@ -467,6 +871,10 @@ struct PoolRefiller {
 
     refill_delay_strategy: Box<dyn ReconnectPolicySession>,
 
+    // Set to true if the scheduled refill follows an error, i.e. it reconnects.
+    #[cfg(feature = "metrics")]
+    refill_after_error: bool,
+
     // Receives information about connections becoming ready, i.e. newly connected
     // or after its keyspace was correctly set.
     // TODO: This should probably be a channel
@@ -493,6 +901,9 @@ struct PoolRefiller {
 
     current_keyspace: Option<VerifiedKeyspaceName>,
 
+    // Present if the size of the pool follows the load, i.e. with `TargetPoolSize::Dynamic`.
+    load_tracker: Option<LoadTracker>,
+
     // Signaled when the connection pool is updated
     pool_updated_notify: Arc<Notify>,
 
@@ -503,6 +914,49 @@ struct PoolRefiller {
     metrics: Arc<Metrics>,
 }
 
+// Tracks the load of each shard of a pool with `TargetPoolSize::Dynamic`.
+struct LoadTracker {
+    size: DynamicPoolSize,
+
+    // Target number of connections to each shard.
+    targets: Vec<usize>,
+
+    // Since when the connections to each shard have been idle.
+    idle_since: Vec<Option<Instant>>,
+
+    // Shards to which a request could not be sent because of exhausted stream ids,
+    // since the last sample. Shared with `NodeConnectionPool`.
+    exhausted_shards: Arc<Mutex<HashSet<Shard>>>,
+}
+
+impl LoadTracker {
+    fn new(size: DynamicPoolSize) -> Self {
+        let mut tracker = Self {
+            size,
+            targets: Vec::new(),
+            idle_since: Vec::new(),
+            exhausted_shards: Default::default(),
+        };
+        tracker.reset(1);
+        tracker
+    }
+
+    fn reset(&mut self, shard_count: usize) {
+        self.targets = vec![self.size.min.get(); shard_count];
+        self.idle_since = vec![None; shard_count];
+    }
+}
+
+// Waits for the next tick of the interval, or forever if there is no interval.
+async fn tick(interval: Option<&mut runtime::Interval>) {
+    match interval {
+        Some(interval) => {
+            interval.tick().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
 #[derive(Debug)]
 struct UseKeyspaceRequest {
     keyspace_name: VerifiedKeyspaceName,
@@ -516,6 +970,7 @@ impl PoolRefiller {
         endpoint: Arc<RwLock<UntranslatedEndpoint>>,
         pool_config: HostPoolConfig,
         connectivity_events_sender: Option<(Uuid, mpsc::UnboundedSender<ConnectivityChangeEvent>)>,
+        event_log: Arc<ConnectionEventLog>,
         current_keyspace: Option<VerifiedKeyspaceName>,
         pool_updated_notify: Arc<Notify>,
         pool_empty_notifier: mpsc::Sender<()>,
@@ -526,20 +981,28 @@ impl PoolRefiller {
         // and assume that the node is a Cassandra node
         let conns = vec![Vec::new()];
         let shared_conns = Arc::new(ArcSwap::new(Arc::new(MaybePoolConnections::Initializing)));
+        let load_tracker = match pool_config.pool_size {
+            TargetPoolSize::Dynamic(size) => Some(LoadTracker::new(size)),
+            TargetPoolSize::PerHost(_) | TargetPoolSize::PerShard(_) => None,
+        };
 
         Self {
             endpoint,
             pool_config,
             connectivity_events_sender,
+            event_log,
 
             shard_aware_port: None,
             sharder: None,
+            shard_aware_port_failures: 0,
 
             shared_conns,
             conns,
 
             had_error_since_last_refill: false,
             refill_delay_strategy: reconnect_policy,
+            #[cfg(feature = "metrics")]
+            refill_after_error: false,
 
             ready_connections: FuturesUnordered::new(),
             connection_errors: FuturesUnordered::new(),
@@ -548,6 +1011,8 @@ impl PoolRefiller {
 
             current_keyspace,
 
+            load_tracker,
+
             pool_updated_notify,
             pool_empty_notifier,
 
@@ -564,22 +1029,77 @@ impl PoolRefiller {
         self.shared_conns.clone()
     }
 
+    pub(crate) fn get_exhausted_shards(&self) -> Option<Arc<Mutex<HashSet<Shard>>>> {
+        self.load_tracker
+            .as_ref()
+            .map(|tracker| tracker.exhausted_shards.clone())
+    }
+
     // The main loop of the pool refiller
     pub(crate) async fn run(
         mut self,
         mut use_keyspace_request_receiver: mpsc::Receiver<UseKeyspaceRequest>,
+        lazy_start: Option<Arc<LazyStart>>,
     ) {
         debug!(
-            "[{}] Started asynchronous pool worker",
-            self.endpoint_description()
+            target: log_targets::POOL,
+            node = %self.endpoint_description(),
+            "Started asynchronous pool worker",
         );
 
+        // A lazy pool does not fill until it is used for the first time.
+        let mut awaiting_first_use = lazy_start
+            .as_ref()
+            .is_some_and(|lazy_start| !lazy_start.started.load(Ordering::Acquire));
         let mut next_refill_time = tokio::time::Instant::now();
-        let mut refill_scheduled = true;
+        let mut refill_scheduled = !awaiting_first_use;
+
+        let mut load_sampling = self
+            .load_tracker
+            .as_ref()
+            .map(|tracker| runtime::interval(tracker.size.sampling_interval));
+
+        // Idle and old connections are looked for periodically.
+        let mut maintenance = [
+            self.pool_config.connection_idle_timeout,
+            self.pool_config.connection_max_age,
+        ]
+        .into_iter()
+        .flatten()
+        .min()
+        .map(|threshold| {
+            let period = (threshold / 2).clamp(MIN_MAINTENANCE_PERIOD, MAX_MAINTENANCE_PERIOD);
+            runtime::interval(period)
+        });
 
         loop {
             tokio::select! {
-                _ = tokio::time::sleep_until(next_refill_time), if refill_scheduled => {
+                _ = LazyStart::wait_for_start(lazy_start.as_deref()), if awaiting_first_use => {
+                    awaiting_first_use = false;
+                    next_refill_time = tokio::time::Instant::now();
+                    refill_scheduled = true;
+                }
+
+                _ = tick(load_sampling.as_mut()), if load_sampling.is_some() => {
+                    self.adjust_to_load();
+                }
+
+                _ = tick(maintenance.as_mut()), if maintenance.is_some() => {
+                    // Expired connections make the pool need filling,
+                    // so their replacements are opened below.
+                    if let Some(lazy_start) = lazy_start.as_deref() {
+                        if !awaiting_first_use && self.is_idle_for_too_long() {
+                            // Mark the pool as unused before closing the connections,
+                            // so that a request which arrives meanwhile starts filling it again.
+                            lazy_start.started.store(false, Ordering::Release);
+                            self.close_idle_connections();
+                            awaiting_first_use = true;
+                            refill_scheduled = false;
+                        }
+                    }
+                }
+
+                _ = runtime::sleep_until(next_refill_time), if refill_scheduled => {
                     self.had_error_since_last_refill = false;
                     self.start_filling();
                     refill_scheduled = false;
@@ -590,9 +1110,10 @@ impl PoolRefiller {
 
                     if self.is_full() {
                         debug!(
-                            "[{}] Pool is full, clearing {} excess connections",
-                            self.endpoint_description(),
-                            self.excess_connections.len()
+                            target: log_targets::POOL,
+                            node = %self.endpoint_description(),
+                            "Pool is full, clearing {} excess connections",
+                            self.excess_connections.len(),
                         );
                         self.excess_connections.clear();
                     }
@@ -600,39 +1121,53 @@ impl PoolRefiller {
 
                 evt = self.connection_errors.select_next_some(), if !self.connection_errors.is_empty() => {
                     if let Some(conn) = evt.connection.upgrade() {
-                        debug!("[{}] Got error for connection {:p}: {:?}", self.endpoint_description(), Arc::as_ptr(&conn), evt.error);
-                        self.remove_connection(conn, evt.error);
+                        self.handle_broken_connection(conn, evt.error);
                     }
                 }
 
                 req = use_keyspace_request_receiver.recv() => {
                     if let Some(req) = req {
-                        debug!("[{}] Requested keyspace change: {}", self.endpoint_description(), req.keyspace_name.as_str());
+                        debug!(
+                            target: log_targets::POOL,
+                            node = %self.endpoint_description(),
+                            "Requested keyspace change: {}",
+                            req.keyspace_name.as_str(),
+                        );
                         self.use_keyspace(req.keyspace_name, req.response_sender);
                     } else {
                         // The keyspace request channel is dropped.
                         // This means that the corresponding pool is dropped.
                         // We can stop here.
-                        trace!("[{}] Keyspace request channel dropped, stopping asynchronous pool worker", self.endpoint_description());
+                        trace!(
+                            target: log_targets::POOL,
+                            node = %self.endpoint_description(),
+                            "Keyspace request channel dropped, stopping asynchronous pool worker",
+                        );
                         return;
                     }
                 }
             }
             trace!(
-                pool_state = ?ShardedConnectionVectorWrapper(&self.conns)
+                target: log_targets::POOL,
+                pool_state = ?ShardedConnectionVectorWrapper(&self.conns),
             );
 
             // Schedule refilling here
-            if !refill_scheduled && self.need_filling() {
+            if !refill_scheduled && !awaiting_first_use && self.need_filling() {
                 if self.had_error_since_last_refill {
                     self.refill_delay_strategy.on_fill_error();
                 } else {
                     self.refill_delay_strategy.on_successful_fill();
                 }
+                #[cfg(feature = "metrics")]
+                {
+                    self.refill_after_error = self.had_error_since_last_refill;
+                }
                 let delay = self.refill_delay_strategy.get_delay();
                 debug!(
-                    "[{}] Scheduling next refill in {} ms",
-                    self.endpoint_description(),
+                    target: log_targets::POOL,
+                    node = %self.endpoint_description(),
+                    "Scheduling next refill in {} ms",
                     delay.as_millis(),
                 );
 
@@ -648,11 +1183,202 @@ impl PoolRefiller {
 
     fn is_full(&self) -> bool {
         match self.pool_config.pool_size {
-            PoolSize::PerHost(target) => self.active_connection_count() >= target.get(),
-            PoolSize::PerShard(target) => {
-                self.conns.iter().all(|conns| conns.len() >= target.get())
+            TargetPoolSize::PerHost(target) => self.fresh_connection_count() >= target.get(),
+            TargetPoolSize::PerShard(_) | TargetPoolSize::Dynamic(_) => self
+                .conns
+                .iter()
+                .enumerate()
+                .all(|(shard, conns)| self.count_fresh(conns) >= self.shard_target(shard)),
+        }
+    }
+
+    // Returns true if the connection is older than the maximum age, so it should be replaced.
+    fn is_expired(&self, connection: &Connection) -> bool {
+        self.pool_config
+            .connection_max_age
+            .is_some_and(|max_age| connection.age() >= max_age)
+    }
+
+    // Counts the connections which are not expired.
+    fn count_fresh(&self, conns: &[Arc<Connection>]) -> usize {
+        conns.iter().filter(|conn| !self.is_expired(conn)).count()
+    }
+
+    fn fresh_connection_count(&self) -> usize {
+        self.conns.iter().map(|conns| self.count_fresh(conns)).sum()
+    }
+
+    // Records and logs an error which broke a connection of the pool, and removes it.
+    fn handle_broken_connection(&mut self, conn: Arc<Connection>, error: ConnectionError) {
+        let event = ConnectionEventKind::from_broken_connection(error.clone());
+        let node = self.endpoint_description();
+        if let ConnectionEventKind::KeepaliveMissed { .. } = event {
+            connection_event!(
+                self.pool_config.verbose_connection_logging,
+                %node,
+                connection = ?Arc::as_ptr(&conn),
+                %error,
+                "Connection closed after a missed keepalive"
+            );
+        } else {
+            connection_event!(
+                self.pool_config.verbose_connection_logging,
+                %node,
+                connection = ?Arc::as_ptr(&conn),
+                %error,
+                "Connection broken"
+            );
+        }
+        self.event_log.record(event);
+        self.remove_connection(conn, error);
+    }
+
+    // Removes the oldest expired connection to the given shard (or to any shard,
+    // if `shard` is None) from the pool, after a replacement was added.
+    // The connection is closed once the requests in flight on it complete,
+    // as they hold references to it.
+    fn retire_expired_connection(&mut self, shard: Option<usize>) {
+        let oldest_expired = self
+            .conns
+            .iter()
+            .enumerate()
+            .filter(|(shard_id, _)| shard.is_none_or(|shard| shard == *shard_id))
+            .flat_map(|(shard_id, conns)| {
+                conns
+                    .iter()
+                    .enumerate()
+                    .map(move |(idx, conn)| (shard_id, idx, conn))
+            })
+            .filter(|(_, _, conn)| self.is_expired(conn))
+            .max_by_key(|(_, _, conn)| conn.age())
+            .map(|(shard_id, idx, _)| (shard_id, idx));
+
+        if let Some((shard_id, idx)) = oldest_expired {
+            let conn = self.conns[shard_id].swap_remove(idx);
+            connection_event!(
+                self.pool_config.verbose_connection_logging,
+                node = %self.endpoint_description(),
+                connection = ?Arc::as_ptr(&conn),
+                shard = shard_id,
+                "Retiring connection which exceeded the maximum age"
+            );
+        }
+    }
+
+    // Returns true if the pool has connections, and all of them have been idle
+    // for longer than the idle timeout.
+    fn is_idle_for_too_long(&self) -> bool {
+        let Some(idle_timeout) = self.pool_config.connection_idle_timeout else {
+            return false;
+        };
+        !self.is_filling()
+            && !self.is_empty()
+            && self
+                .conns
+                .iter()
+                .flatten()
+                .all(|conn| conn.idle_time() >= idle_timeout)
+    }
+
+    // Closes all connections of an idle pool. The pool is filled again when it is used.
+    fn close_idle_connections(&mut self) {
+        connection_event!(
+            self.pool_config.verbose_connection_logging,
+            node = %self.endpoint_description(),
+            idle_timeout = ?self.pool_config.connection_idle_timeout.unwrap_or_default(),
+            "All connections have been idle, closing them until the pool is used"
+        );
+        for conns in self.conns.iter_mut() {
+            conns.clear();
+        }
+        self.excess_connections.clear();
+        self.shared_conns
+            .store(Arc::new(MaybePoolConnections::Initializing));
+    }
+
+    // Returns the target number of connections to the given shard.
+    fn shard_target(&self, shard: usize) -> usize {
+        match self.pool_config.pool_size {
+            // Per-host pools do not distribute connections across shards,
+            // so any shard may hold all of them.
+            TargetPoolSize::PerHost(target) | TargetPoolSize::PerShard(target) => target.get(),
+            TargetPoolSize::Dynamic(size) => self
+                .load_tracker
+                .as_ref()
+                .map_or(size.min.get(), |tracker| tracker.targets[shard]),
+        }
+    }
+
+    // Adjusts the target number of connections to each shard to its load.
+    // A connection is added if the connections to a shard are overloaded,
+    // and closed if they have been idle for long enough.
+    fn adjust_to_load(&mut self) {
+        let endpoint = self.endpoint_description();
+        let Some(tracker) = &mut self.load_tracker else {
+            return;
+        };
+        let size = tracker.size;
+        let exhausted_shards = std::mem::take(&mut *tracker.exhausted_shards.lock().unwrap());
+        let now = Instant::now();
+        let mut closed_any = false;
+
+        for (shard, conns) in self.conns.iter_mut().enumerate() {
+            let target = &mut tracker.targets[shard];
+            // The load is not meaningful until the shard is filled up to its target.
+            if conns.is_empty() || conns.len() < *target {
+                tracker.idle_since[shard] = None;
+                continue;
+            }
+
+            let in_flight_requests: usize =
+                conns.iter().map(|conn| conn.in_flight_requests()).sum();
+            let average = in_flight_requests / conns.len();
+
+            if average >= size.scale_up_threshold || exhausted_shards.contains(&(shard as Shard)) {
+                tracker.idle_since[shard] = None;
+                if *target < size.max.get() {
+                    *target += 1;
+                    debug!(
+                        target: log_targets::POOL,
+                        node = %endpoint,
+                        "Shard {} is overloaded ({} requests in flight), increasing its target to {} connections",
+                        shard,
+                        in_flight_requests,
+                        target,
+                    );
+                }
+            } else if average < size.scale_down_threshold {
+                let idle_since = *tracker.idle_since[shard].get_or_insert(now);
+                if *target > size.min.get() && now - idle_since >= size.idle_timeout {
+                    *target -= 1;
+                    // Further shrinking requires another idle period.
+                    tracker.idle_since[shard] = Some(now);
+                    // Requests in flight on the closed connection still complete,
+                    // as they hold references to it.
+                    if let Some((idx, _)) = conns
+                        .iter()
+                        .enumerate()
+                        .min_by_key(|(_, conn)| conn.in_flight_requests())
+                    {
+                        conns.swap_remove(idx);
+                        closed_any = true;
+                    }
+                    debug!(
+                        target: log_targets::POOL,
+                        node = %endpoint,
+                        "Shard {} is idle, decreasing its target to {} connections",
+                        shard,
+                        target,
+                    );
+                }
+            } else {
+                tracker.idle_since[shard] = None;
             }
         }
+
+        if closed_any {
+            self.update_shared_conns(None);
+        }
     }
 
     fn is_empty(&self) -> bool {
@@ -664,9 +1390,43 @@ impl PoolRefiller {
     }
 
     fn can_use_shard_aware_port(&self) -> bool {
-        self.sharder.is_some()
-            && self.shard_aware_port.is_some()
-            && self.pool_config.can_use_shard_aware_port
+        let allowed = match self.pool_config.shard_aware_port_usage {
+            ShardAwarePortUsage::Disabled => false,
+            ShardAwarePortUsage::Preferred => true,
+            ShardAwarePortUsage::PreferredWithFallback { max_failures } => {
+                self.shard_aware_port_failures < max_failures.get()
+            }
+        };
+        self.sharder.is_some() && self.shard_aware_port.is_some() && allowed
+    }
+
+    // Records the outcome of a connection attempt to the shard-aware port.
+    fn record_shard_aware_port_outcome(&mut self, landed_on_target_shard: bool) {
+        #[cfg(feature = "metrics")]
+        if landed_on_target_shard {
+            self.metrics.inc_shard_aware_connections_on_target_shard();
+        } else {
+            self.metrics.inc_shard_aware_connections_off_target_shard();
+        }
+
+        if landed_on_target_shard {
+            self.shard_aware_port_failures = 0;
+            return;
+        }
+
+        self.shard_aware_port_failures += 1;
+        if let ShardAwarePortUsage::PreferredWithFallback { max_failures } =
+            self.pool_config.shard_aware_port_usage
+        {
+            if self.shard_aware_port_failures == max_failures.get() {
+                warn!(
+                    target: log_targets::POOL,
+                    node = %self.endpoint_description(),
+                    "Shard-aware port failed {} times in a row; falling back to the regular port",
+                    max_failures,
+                );
+            }
+        }
     }
 
     // Begins opening a number of connections in order to fill the connection pool.
@@ -679,23 +1439,32 @@ impl PoolRefiller {
             // If the pool is empty, it might mean that the node is not alive.
             // It is more likely than not that the next connection attempt will
             // fail, so there is no use in opening more than one connection now.
-            trace!("[{}] Will open the first connection to the node", endpoint,);
+            trace!(
+                target: log_targets::POOL,
+                node = %endpoint,
+                "Will open the first connection to the node",
+            );
             self.start_opening_connection(None);
             return;
         }
 
         if self.can_use_shard_aware_port() {
-            // Only use the shard-aware port if we have a PerShard strategy
-            if let PoolSize::PerShard(target) = self.pool_config.pool_size {
-                // Try to fill up each shard up to `target` connections
+            // Only use the shard-aware port if we have a per-shard strategy
+            if !matches!(self.pool_config.pool_size, TargetPoolSize::PerHost(_)) {
+                // Try to fill up each shard up to its target number of connections
                 for (shard_id, shard_conns) in self.conns.iter().enumerate() {
-                    let to_open_count = target.get().saturating_sub(shard_conns.len());
+                    let to_open_count = self
+                        .shard_target(shard_id)
+                        .saturating_sub(self.count_fresh(shard_conns));
                     if to_open_count == 0 {
                         continue;
                     }
                     trace!(
-                        "[{}] Will open {} connections to shard {}",
-                        endpoint, to_open_count, shard_id,
+                        target: log_targets::POOL,
+                        node = %endpoint,
+                        "Will open {} connections to shard {}",
+                        to_open_count,
+                        shard_id,
                     );
                     for _ in 0..to_open_count {
                         self.start_opening_connection(Some(shard_id as Shard));
@@ -707,13 +1476,17 @@ impl PoolRefiller {
         // Calculate how many more connections we need to open in order
         // to achieve the target connection count.
         let to_open_count = match self.pool_config.pool_size {
-            PoolSize::PerHost(target) => {
-                target.get().saturating_sub(self.active_connection_count())
+            TargetPoolSize::PerHost(target) => {
+                target.get().saturating_sub(self.fresh_connection_count())
             }
-            PoolSize::PerShard(target) => self
+            TargetPoolSize::PerShard(_) | TargetPoolSize::Dynamic(_) => self
                 .conns
                 .iter()
-                .map(|conns| target.get().saturating_sub(conns.len()))
+                .enumerate()
+                .map(|(shard, conns)| {
+                    self.shard_target(shard)
+                        .saturating_sub(self.count_fresh(conns))
+                })
                 .sum::<usize>(),
         };
         // When connecting to ScyllaDB through non-shard-aware port,
@@ -722,8 +1495,10 @@ impl PoolRefiller {
         // but we have no guarantee, so we might have to retry
         // connecting later.
         trace!(
-            "[{}] Will open {} non-shard-aware connections",
-            endpoint, to_open_count,
+            target: log_targets::POOL,
+            node = %endpoint,
+            "Will open {} non-shard-aware connections",
+            to_open_count,
         );
         for _ in 0..to_open_count {
             self.start_opening_connection(None);
@@ -735,7 +1510,12 @@ impl PoolRefiller {
         let endpoint = self.endpoint_description();
         match evt.result {
             Err(err) => {
+                self.event_log.record(ConnectionEventKind::ConnectFailed {
+                    shard_aware_port: evt.requested_shard.is_some(),
+                    error: err.clone(),
+                });
                 if evt.requested_shard.is_some() {
+                    self.record_shard_aware_port_outcome(false);
                     // If we failed to connect to a shard-aware port,
                     // fall back to the non-shard-aware port.
                     // Don't set `had_error_since_last_refill` here;
@@ -747,9 +1527,12 @@ impl PoolRefiller {
                     // necessary, so increasing the backoff would delay
                     // filling the pool even if the non-shard-aware port works
                     // and does not cause any errors.
-                    debug!(
-                        "[{}] Failed to open connection to the shard-aware port: {:?}, will retry with regular port",
-                        endpoint, err,
+                    connection_event!(
+                        self.pool_config.verbose_connection_logging,
+                        node = %endpoint,
+                        shard = evt.requested_shard,
+                        error = %err,
+                        "Failed to open connection to the shard-aware port, will retry with regular port"
                     );
                     self.start_opening_connection(None);
                 } else {
@@ -757,9 +1540,11 @@ impl PoolRefiller {
                     // port. Set the `had_error_since_last_refill` flag so that
                     // the next refill will be delayed more than this one.
                     self.had_error_since_last_refill = true;
-                    debug!(
-                        "[{}] Failed to open connection to the non-shard-aware port: {:?}",
-                        endpoint, err,
+                    connection_event!(
+                        self.pool_config.verbose_connection_logging,
+                        node = %endpoint,
+                        error = %err,
+                        "Failed to open connection to the non-shard-aware port"
                     );
 
                     // If all connection attempts in this fill attempt failed
@@ -774,16 +1559,19 @@ impl PoolRefiller {
                 let shard_info = connection.get_shard_info().as_ref();
                 let sharder = shard_info.map(|s| s.get_sharder());
                 let shard_id = shard_info.map_or(0, |s| s.shard as usize);
+                let is_sharded = shard_info.is_some();
                 self.maybe_reshard(sharder);
 
                 // Update the shard-aware port
                 if self.shard_aware_port != connection.get_shard_aware_port() {
                     debug!(
-                        "[{}] Updating shard aware port: {:?}",
-                        endpoint,
+                        target: log_targets::POOL,
+                        node = %endpoint,
+                        "Updating shard aware port: {:?}",
                         connection.get_shard_aware_port(),
                     );
                     self.shard_aware_port = connection.get_shard_aware_port();
+                    self.shard_aware_port_failures = 0;
                 }
 
                 // Before the connection can be put to the pool, we need
@@ -804,11 +1592,17 @@ impl PoolRefiller {
                     }
                 }
 
+                if let Some(requested_shard) = evt.requested_shard {
+                    self.record_shard_aware_port_outcome(requested_shard as usize == shard_id);
+                }
+
                 // Decide if the connection can be accepted, according to
                 // the pool filling strategy
                 let can_be_accepted = match self.pool_config.pool_size {
-                    PoolSize::PerHost(target) => self.active_connection_count() < target.get(),
-                    PoolSize::PerShard(target) => self.conns[shard_id].len() < target.get(),
+                    TargetPoolSize::PerHost(target) => self.fresh_connection_count() < target.get(),
+                    TargetPoolSize::PerShard(_) | TargetPoolSize::Dynamic(_) => {
+                        self.count_fresh(&self.conns[shard_id]) < self.shard_target(shard_id)
+                    }
                 };
 
                 if can_be_accepted {
@@ -818,8 +1612,9 @@ impl PoolRefiller {
                     // because it fills our pool.
                     let conn = Arc::new(connection);
                     trace!(
-                        "[{}] Adding connection {:p} to shard {} pool, now there are {} for the shard, total {}",
-                        endpoint,
+                        target: log_targets::POOL,
+                        node = %endpoint,
+                        "Adding connection {:p} to shard {} pool, now there are {} for the shard, total {}",
                         Arc::as_ptr(&conn),
                         shard_id,
                         self.conns[shard_id].len() + 1,
@@ -829,6 +1624,26 @@ impl PoolRefiller {
                     self.connection_errors
                         .push(wait_for_error(Arc::downgrade(&conn), error_receiver).boxed());
                     self.conns[shard_id].push(conn);
+                    self.event_log.record(ConnectionEventKind::Connected {
+                        shard: is_sharded.then_some(shard_id as Shard),
+                    });
+                    connection_event!(
+                        self.pool_config.verbose_connection_logging,
+                        node = %endpoint,
+                        shard = is_sharded.then_some(shard_id),
+                        "Connection opened"
+                    );
+
+                    // The new connection may replace one which exceeded the maximum age.
+                    if self.pool_config.connection_max_age.is_some() {
+                        let shard = match self.pool_config.pool_size {
+                            TargetPoolSize::PerHost(_) => None,
+                            TargetPoolSize::PerShard(_) | TargetPoolSize::Dynamic(_) => {
+                                Some(shard_id)
+                            }
+                        };
+                        self.retire_expired_connection(shard);
+                    }
 
                     self.update_shared_conns(None);
                 } else if evt.requested_shard.is_some() {
@@ -839,8 +1654,10 @@ impl PoolRefiller {
                     // of choosing the shard to Scylla. We will retry
                     // immediately with a non-shard-aware port here.
                     debug!(
-                        "[{}] Excess shard-aware port connection for shard {}; will retry with non-shard-aware port",
-                        endpoint, shard_id,
+                        target: log_targets::POOL,
+                        node = %endpoint,
+                        "Excess shard-aware port connection for shard {}; will retry with non-shard-aware port",
+                        shard_id,
                     );
 
                     self.start_opening_connection(None);
@@ -852,8 +1669,9 @@ impl PoolRefiller {
                     // pool.
                     let conn = Arc::new(connection);
                     trace!(
-                        "[{}] Storing excess connection {:p} for shard {}",
-                        endpoint,
+                        target: log_targets::POOL,
+                        node = %endpoint,
+                        "Storing excess connection {:p} for shard {}",
                         Arc::as_ptr(&conn),
                         shard_id,
                     );
@@ -865,8 +1683,10 @@ impl PoolRefiller {
                     let excess_connection_limit = self.excess_connection_limit();
                     if self.excess_connections.len() > excess_connection_limit {
                         debug!(
-                            "[{}] Excess connection pool exceeded limit of {} connections - clearing",
-                            endpoint, excess_connection_limit,
+                            target: log_targets::POOL,
+                            node = %endpoint,
+                            "Excess connection pool exceeded limit of {} connections - clearing",
+                            excess_connection_limit,
                         );
                         self.excess_connections.clear();
                     }
@@ -880,15 +1700,23 @@ impl PoolRefiller {
     // the shard aware port is available, it will attempt to connect directly
     // to the shard using the port.
     fn start_opening_connection(&self, shard: Option<Shard>) {
+        self.event_log.record_connection_attempt();
         let cfg = self.pool_config.connection_config.clone();
         let mut endpoint = self.endpoint.read().unwrap().clone();
 
         #[cfg(feature = "metrics")]
         let count_in_metrics = {
             let metrics = Arc::clone(&self.metrics);
+            let is_reconnect = self.refill_after_error;
+            if is_reconnect {
+                metrics.inc_reconnect_attempts();
+            }
             move |connect_result: &Result<_, ConnectionError>| {
                 if connect_result.is_ok() {
                     metrics.inc_total_connections();
+                    if is_reconnect {
+                        metrics.inc_reconnect_successes();
+                    }
                 } else if let Err(ConnectionError::ConnectTimeout) = &connect_result {
                     metrics.inc_connection_timeouts();
                 }
@@ -943,8 +1771,9 @@ impl PoolRefiller {
         }
 
         debug!(
-            "[{}] New sharder: {:?}, clearing all connections",
-            self.endpoint_description(),
+            target: log_targets::POOL,
+            node = %self.endpoint_description(),
+            "New sharder: {:?}, clearing all connections",
             new_sharder,
         );
 
@@ -957,6 +1786,9 @@ impl PoolRefiller {
 
         let shard_count = new_sharder.map_or(1, |s| s.nr_shards.get() as usize);
         self.conns.resize_with(shard_count, Vec::new);
+        if let Some(tracker) = &mut self.load_tracker {
+            tracker.reset(shard_count);
+        }
 
         self.excess_connections.clear();
     }
@@ -1035,15 +1867,19 @@ impl PoolRefiller {
         let endpoint = self.endpoint_description();
         match event {
             ConnectivityChangeEvent::Established { .. } => {
-                debug!(
-                    "[{} - {}] Connection pool is no longer empty, notifying listeners",
-                    host_id, endpoint,
+                connection_event!(
+                    self.pool_config.verbose_connection_logging,
+                    %host_id,
+                    node = %endpoint,
+                    "Connection pool is no longer empty, notifying listeners"
                 );
             }
             ConnectivityChangeEvent::Lost { .. } => {
-                debug!(
-                    "[{} - {}] Connection pool is now empty, notifying listeners",
-                    host_id, endpoint,
+                connection_event!(
+                    self.pool_config.verbose_connection_logging,
+                    %host_id,
+                    node = %endpoint,
+                    "Connection pool is now empty, notifying listeners"
                 );
             }
         }
@@ -1084,8 +1920,9 @@ impl PoolRefiller {
             .map_or(0, |s| s.shard as usize);
         if shard_id < self.conns.len() && maybe_remove_in_vec(&mut self.conns[shard_id]) {
             trace!(
-                "[{}] Connection {:p} removed from shard {} pool, now there is {} for the shard, total {}",
-                endpoint,
+                target: log_targets::POOL,
+                node = %endpoint,
+                "Connection {:p} removed from shard {} pool, now there is {} for the shard, total {}",
                 ptr,
                 shard_id,
                 self.conns[shard_id].len(),
@@ -1105,13 +1942,20 @@ impl PoolRefiller {
         // If we didn't find it, it might sit in the excess_connections bucket
         if maybe_remove_in_vec(&mut self.excess_connections) {
             trace!(
-                "[{}] Connection {:p} removed from excess connection pool",
-                endpoint, ptr,
+                target: log_targets::POOL,
+                node = %endpoint,
+                "Connection {:p} removed from excess connection pool",
+                ptr,
             );
             return;
         }
 
-        trace!("[{}] Connection {:p} was already removed", endpoint, ptr,);
+        trace!(
+            target: log_targets::POOL,
+            node = %endpoint,
+            "Connection {:p} was already removed",
+            ptr,
+        );
     }
 
     // Sets current keyspace for available connections.
@@ -1144,7 +1988,7 @@ impl PoolRefiller {
                 return Ok(());
             }
 
-            let use_keyspace_results: Vec<Result<(), UseKeyspaceError>> = tokio::time::timeout(
+            let use_keyspace_results: Vec<Result<(), UseKeyspaceError>> = runtime::timeout(
                 connect_timeout,
                 futures::future::join_all(use_keyspace_futures),
             )
@@ -1155,11 +1999,20 @@ impl PoolRefiller {
             crate::cluster::use_keyspace_result(use_keyspace_results.into_iter())
         };
 
-        tokio::task::spawn(async move {
+        runtime::spawn(async move {
             let res = fut.await;
             match &res {
-                Ok(()) => debug!("[{}] Successfully changed current keyspace", address),
-                Err(err) => warn!("[{}] Failed to change keyspace: {:?}", address, err),
+                Ok(()) => debug!(
+                    target: log_targets::POOL,
+                    node = %address,
+                    "Successfully changed current keyspace",
+                ),
+                Err(err) => warn!(
+                    target: log_targets::POOL,
+                    node = %address,
+                    "Failed to change keyspace: {:?}",
+                    err,
+                ),
             }
             let _ = response_sender.send(res);
         });
@@ -1181,8 +2034,9 @@ impl PoolRefiller {
                 let result = connection.use_keyspace(&keyspace_name).await;
                 if let Err(err) = result {
                     warn!(
-                        "[{}] Failed to set keyspace for new connection: {}",
-                        connection.get_connect_address().ip(),
+                        target: log_targets::POOL,
+                        node = %connection.get_connect_address().ip(),
+                        "Failed to set keyspace for new connection: {}",
                         err,
                     );
                 }
@@ -1202,7 +2056,7 @@ impl PoolRefiller {
 
     fn excess_connection_limit(&self) -> usize {
         match self.pool_config.pool_size {
-            PoolSize::PerShard(_) => {
+            TargetPoolSize::PerShard(_) | TargetPoolSize::Dynamic(_) => {
                 EXCESS_CONNECTION_BOUND_PER_SHARD_MULTIPLIER
                     * self
                         .sharder
@@ -1211,7 +2065,7 @@ impl PoolRefiller {
             }
 
             // In PerHost mode we do not need to keep excess connections
-            PoolSize::PerHost(_) => 0,
+            TargetPoolSize::PerHost(_) => 0,
         }
     }
 }
@@ -1261,11 +2115,29 @@ impl ConnectivityChangeEvent {
 #[cfg(test)]
 mod tests {
     use super::super::connection::{HostConnectionConfig, open_connection_to_shard_aware_port};
+    use super::super::event_log::ConnectionEventLog;
+    use super::{
+        DynamicPoolSize, HostPoolConfig, NodeConnectionPool, PoolConfig, PoolRefiller, PoolSize,
+        ShardAwarePortUsage, TargetPoolSize,
+    };
     use crate::cluster::metadata::UntranslatedEndpoint;
     use crate::cluster::node::ResolvedContactPoint;
+    use crate::errors::DynamicPoolSizeError;
+    use crate::policies::reconnect::{ExponentialReconnectPolicy, ReconnectPolicy};
     use crate::routing::{ShardCount, Sharder};
     use crate::test_utils::setup_tracing;
+    use assert_matches::assert_matches;
+    use scylla_proxy::{
+        Condition, Node, Proxy, Reaction, RequestOpcode, RequestReaction, RequestRule,
+        ResponseFrame, RunningProxy,
+    };
+    use std::collections::HashMap;
     use std::net::{SocketAddr, ToSocketAddrs};
+    use std::num::NonZeroUsize;
+    use std::sync::{Arc, RwLock};
+    use std::time::Duration;
+    use tokio::net::TcpListener;
+    use tokio::sync::Notify;
 
     // Open many connections to a node
     // Port collision should occur
@@ -1296,6 +2168,7 @@ mod tests {
 
         let endpoint = UntranslatedEndpoint::ContactPoint(ResolvedContactPoint {
             address: connect_address,
+            resolved_from: None,
         });
 
         // Open the connections
@@ -1310,4 +2183,285 @@ mod tests {
             res.unwrap();
         }
     }
+
+    #[tokio::test]
+    async fn lazy_pool_connects_on_first_use() {
+        setup_tracing();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = UntranslatedEndpoint::ContactPoint(ResolvedContactPoint {
+            address: listener.local_addr().unwrap(),
+            resolved_from: None,
+        });
+        let pool_config = PoolConfig {
+            connect_lazily: true,
+            ..Default::default()
+        };
+        let (pool_empty_notifier, _) = tokio::sync::mpsc::channel(1);
+        let pool = NodeConnectionPool::new(
+            endpoint,
+            &pool_config,
+            None,
+            None,
+            pool_empty_notifier,
+            #[cfg(feature = "metrics")]
+            Default::default(),
+        );
+
+        // The pool neither connects, nor is waited for, until it is used.
+        pool.wait_until_initialized().await;
+        assert!(pool.is_idle());
+        assert!(
+            tokio::time::timeout(Duration::from_millis(200), listener.accept())
+                .await
+                .is_err()
+        );
+
+        let first_use = tokio::spawn({
+            let pool = pool.clone();
+            async move { pool.connect_lazily().await }
+        });
+        let (socket, _) = listener.accept().await.unwrap();
+        assert!(!pool.is_idle());
+
+        // The node does not speak CQL, so the first filling fails
+        // and the pool becomes initialized as broken.
+        drop(socket);
+        first_use.await.unwrap();
+        assert!(!pool.is_connected());
+    }
+
+    #[test]
+    fn dynamic_pool_size() {
+        let size =
+            DynamicPoolSize::new(NonZeroUsize::new(1).unwrap(), NonZeroUsize::new(4).unwrap())
+                .unwrap()
+                .with_scale_up_threshold(100)
+                .with_scale_down_threshold(10)
+                .with_idle_timeout(Duration::from_secs(5))
+                .with_sampling_interval(Duration::from_millis(200));
+        assert_eq!(size.min().get(), 1);
+        assert_eq!(size.max().get(), 4);
+        assert_eq!(size.scale_up_threshold, 100);
+        assert_eq!(size.scale_down_threshold, 10);
+        assert_eq!(size.idle_timeout, Duration::from_secs(5));
+        assert_eq!(size.sampling_interval, Duration::from_millis(200));
+    }
+
+    #[test]
+    fn dynamic_pool_size_min_above_max() {
+        let min = NonZeroUsize::new(4).unwrap();
+        let max = NonZeroUsize::new(2).unwrap();
+        assert_matches!(
+            DynamicPoolSize::new(min, max),
+            Err(DynamicPoolSizeError::MinExceedsMax { min: m, max: n }) if m == min && n == max
+        );
+    }
+
+    #[test]
+    fn dynamic_pool_size_takes_precedence_over_pool_size() {
+        let per_host = PoolSize::PerHost(NonZeroUsize::new(3).unwrap());
+        let config = PoolConfig {
+            pool_size: per_host,
+            ..Default::default()
+        };
+        assert_matches!(config.target_pool_size(), TargetPoolSize::PerHost(target) if target.get() == 3);
+
+        let dynamic =
+            DynamicPoolSize::new(NonZeroUsize::new(1).unwrap(), NonZeroUsize::new(2).unwrap())
+                .unwrap();
+        let config = PoolConfig {
+            pool_size: per_host,
+            dynamic_pool_size: Some(dynamic),
+            ..Default::default()
+        };
+        assert_matches!(config.target_pool_size(), TargetPoolSize::Dynamic(size) if size.max().get() == 2);
+    }
+
+    // Runs a fake node which accepts connections, but does not handle any requests.
+    async fn run_fake_node() -> (RunningProxy, SocketAddr) {
+        let address = SocketAddr::new(scylla_proxy::get_exclusive_local_address(), 9042);
+        let rules = vec![
+            RequestRule(
+                Condition::RequestOpcode(RequestOpcode::Options),
+                RequestReaction::forge_response(Arc::new(|frame| {
+                    ResponseFrame::forged_supported(frame.params, &HashMap::new()).unwrap()
+                })),
+            ),
+            RequestRule(
+                Condition::RequestOpcode(RequestOpcode::Startup),
+                RequestReaction::forge_response(Arc::new(|frame| {
+                    ResponseFrame::forged_ready(frame.params)
+                })),
+            ),
+        ];
+        let proxy = Proxy::builder()
+            .with_node(
+                Node::builder()
+                    .proxy_address(address)
+                    .request_rules(rules)
+                    .build_dry_mode(),
+            )
+            .build()
+            .run()
+            .await
+            .unwrap();
+        (proxy, address)
+    }
+
+    fn new_pool(address: SocketAddr, pool_config: &PoolConfig) -> NodeConnectionPool {
+        let (pool_empty_notifier, _) = tokio::sync::mpsc::channel(1);
+        NodeConnectionPool::new(
+            UntranslatedEndpoint::ContactPoint(ResolvedContactPoint {
+                address,
+                resolved_from: None,
+            }),
+            pool_config,
+            None,
+            None,
+            pool_empty_notifier,
+            #[cfg(feature = "metrics")]
+            Default::default(),
+        )
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(20000)]
+    async fn idle_pool_is_closed_and_reopened_on_use() {
+        setup_tracing();
+        let (proxy, address) = run_fake_node().await;
+        let pool_config = PoolConfig {
+            connection_idle_timeout: Some(Duration::from_millis(100)),
+            ..Default::default()
+        };
+        let pool = new_pool(address, &pool_config);
+
+        pool.wait_until_initialized().await;
+        assert!(pool.is_connected());
+        assert!(!pool.is_idle());
+
+        while pool.is_connected() {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(pool.is_idle());
+
+        pool.connect_lazily().await;
+        assert!(pool.is_connected());
+        assert!(!pool.is_idle());
+
+        let _ = proxy.finish().await;
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(20000)]
+    async fn old_connections_are_replaced() {
+        setup_tracing();
+        let (proxy, address) = run_fake_node().await;
+        let pool_config = PoolConfig {
+            connection_max_age: Some(Duration::from_millis(200)),
+            ..Default::default()
+        };
+        let pool = new_pool(address, &pool_config);
+
+        pool.wait_until_initialized().await;
+        let old_connection = pool.random_connection().unwrap();
+
+        // The old connection stays open while it is referenced, e.g. by requests in flight,
+        // but the pool switches to its replacement.
+        loop {
+            let connection = pool.random_connection().unwrap();
+            if !Arc::ptr_eq(&connection, &old_connection) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(pool.get_working_connections().unwrap().len(), 1);
+
+        let _ = proxy.finish().await;
+    }
+
+    // Creates a refiller of a node which advertises the shard-aware port.
+    fn refiller_with_shard_aware_port(shard_aware_port_usage: ShardAwarePortUsage) -> PoolRefiller {
+        let endpoint = UntranslatedEndpoint::ContactPoint(ResolvedContactPoint {
+            address: "127.0.0.1:9042".parse().unwrap(),
+            resolved_from: None,
+        });
+        let (pool_empty_notifier, _) = tokio::sync::mpsc::channel(1);
+        let mut refiller = PoolRefiller::new(
+            Arc::new(RwLock::new(endpoint)),
+            HostPoolConfig {
+                shard_aware_port_usage,
+                ..Default::default()
+            },
+            None,
+            Arc::new(ConnectionEventLog::default()),
+            None,
+            Arc::new(Notify::new()),
+            pool_empty_notifier,
+            #[cfg(feature = "metrics")]
+            Default::default(),
+            ExponentialReconnectPolicy::new().new_session(),
+        );
+        refiller.sharder = Some(Sharder::new(ShardCount::new(3).unwrap(), 12));
+        refiller.shard_aware_port = Some(19042);
+        refiller
+    }
+
+    #[test]
+    fn shard_aware_port_disabled_and_preferred() {
+        setup_tracing();
+        let mut disabled = refiller_with_shard_aware_port(ShardAwarePortUsage::Disabled);
+        assert!(!disabled.can_use_shard_aware_port());
+
+        // Without a fallback, the port is used regardless of failures.
+        let mut preferred = refiller_with_shard_aware_port(ShardAwarePortUsage::Preferred);
+        for _ in 0..10 {
+            preferred.record_shard_aware_port_outcome(false);
+            assert!(preferred.can_use_shard_aware_port());
+        }
+
+        // The port cannot be used if the node does not advertise it.
+        preferred.shard_aware_port = None;
+        assert!(!preferred.can_use_shard_aware_port());
+        disabled.shard_aware_port = None;
+        assert!(!disabled.can_use_shard_aware_port());
+    }
+
+    #[test]
+    fn shard_aware_port_fallback_after_max_failures() {
+        setup_tracing();
+        let mut refiller =
+            refiller_with_shard_aware_port(ShardAwarePortUsage::PreferredWithFallback {
+                max_failures: NonZeroUsize::new(3).unwrap(),
+            });
+
+        // A success resets the count of consecutive failures.
+        refiller.record_shard_aware_port_outcome(false);
+        refiller.record_shard_aware_port_outcome(false);
+        refiller.record_shard_aware_port_outcome(true);
+        refiller.record_shard_aware_port_outcome(false);
+        refiller.record_shard_aware_port_outcome(false);
+        assert!(refiller.can_use_shard_aware_port());
+
+        // The third consecutive failure switches to the regular port, for good.
+        refiller.record_shard_aware_port_outcome(false);
+        assert!(!refiller.can_use_shard_aware_port());
+        refiller.record_shard_aware_port_outcome(false);
+        assert!(!refiller.can_use_shard_aware_port());
+
+        #[cfg(feature = "metrics")]
+        {
+            assert_eq!(
+                refiller
+                    .metrics
+                    .get_shard_aware_connections_on_target_shard(),
+                1
+            );
+            assert_eq!(
+                refiller
+                    .metrics
+                    .get_shard_aware_connections_off_target_shard(),
+                6
+            );
+        }
+    }
 }
@@ -0,0 +1,236 @@
+//! Bounded per-node log of significant connection events.
+//!
+//! The log is kept regardless of the logging configuration, so that transient
+//! connectivity incidents can be inspected after the fact through
+//! [Node::recent_events](crate::cluster::Node::recent_events).
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use crate::errors::{BrokenConnectionErrorKind, ConnectionError};
+use crate::routing::Shard;
+
+/// Maximum number of events kept in a single node's log.
+/// When exceeded, the oldest events are discarded.
+pub(crate) const CONNECTION_EVENT_LOG_CAPACITY: usize = 64;
+
+/// A significant event concerning connections to a node.
+#[derive(Clone, Debug)]
+pub struct ConnectionEvent {
+    time: SystemTime,
+    kind: ConnectionEventKind,
+}
+
+impl ConnectionEvent {
+    /// Wall-clock time at which the event was recorded.
+    pub fn time(&self) -> SystemTime {
+        self.time
+    }
+
+    /// What happened.
+    pub fn kind(&self) -> &ConnectionEventKind {
+        &self.kind
+    }
+}
+
+/// Kind of a [ConnectionEvent].
+#[non_exhaustive]
+#[derive(Clone, Debug)]
+pub enum ConnectionEventKind {
+    /// A connection was opened and added to the pool.
+    Connected {
+        /// Shard the connection is bound to, if the node is sharded.
+        shard: Option<Shard>,
+    },
+
+    /// An attempt to open a connection failed.
+    ConnectFailed {
+        /// Whether the attempt targeted the shard-aware port.
+        /// Such failures are followed by a retry on the regular port.
+        shard_aware_port: bool,
+        /// Reason of the failure.
+        error: ConnectionError,
+    },
+
+    /// The node did not respond to a keepalive request in time,
+    /// or the request failed, and the connection was closed.
+    KeepaliveMissed {
+        /// Error which broke the connection.
+        error: ConnectionError,
+    },
+
+    /// An established connection was broken for a reason other than keepalive.
+    ConnectionBroken {
+        /// Error which broke the connection.
+        error: ConnectionError,
+    },
+
+    /// A request could not be sent because all stream ids
+    /// of the chosen connection were in use.
+    StreamIdsExhausted {
+        /// Shard targeted by the request.
+        shard: Shard,
+    },
+}
+
+impl ConnectionEventKind {
+    /// The error carried by the event, if it is a failure.
+    pub fn error(&self) -> Option<&ConnectionError> {
+        match self {
+            ConnectionEventKind::ConnectFailed { error, .. }
+            | ConnectionEventKind::KeepaliveMissed { error }
+            | ConnectionEventKind::ConnectionBroken { error } => Some(error),
+            ConnectionEventKind::Connected { .. }
+            | ConnectionEventKind::StreamIdsExhausted { .. } => None,
+        }
+    }
+
+    /// Classifies an error which broke an established connection.
+    pub(crate) fn from_broken_connection(error: ConnectionError) -> Self {
+        let keepalive = match &error {
+            ConnectionError::BrokenConnection(broken) => matches!(
+                broken.downcast_ref::<BrokenConnectionErrorKind>(),
+                Some(
+                    BrokenConnectionErrorKind::KeepaliveTimeout(_)
+                        | BrokenConnectionErrorKind::KeepaliveRequestError(_)
+                )
+            ),
+            _ => false,
+        };
+        if keepalive {
+            ConnectionEventKind::KeepaliveMissed { error }
+        } else {
+            ConnectionEventKind::ConnectionBroken { error }
+        }
+    }
+}
+
+/// Ring buffer of recent [ConnectionEvent]s of a single node.
+///
+/// Apart from the ring buffer, the log remembers the last connection attempt
+/// and the last failure, which may have been discarded from the buffer.
+#[derive(Debug)]
+pub(crate) struct ConnectionEventLog {
+    capacity: usize,
+    events: Mutex<VecDeque<ConnectionEvent>>,
+    last_connection_attempt: Mutex<Option<SystemTime>>,
+    last_error: Mutex<Option<ConnectionEvent>>,
+}
+
+impl ConnectionEventLog {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            events: Mutex::new(VecDeque::with_capacity(capacity)),
+            last_connection_attempt: Mutex::new(None),
+            last_error: Mutex::new(None),
+        }
+    }
+
+    pub(crate) fn record_connection_attempt(&self) {
+        *self.last_connection_attempt.lock().unwrap() = Some(SystemTime::now());
+    }
+
+    pub(crate) fn record(&self, kind: ConnectionEventKind) {
+        let event = ConnectionEvent {
+            time: SystemTime::now(),
+            kind,
+        };
+        if event.kind.error().is_some() {
+            *self.last_error.lock().unwrap() = Some(event.clone());
+        }
+        let mut events = self.events.lock().unwrap();
+        if events.len() == self.capacity {
+            events.pop_front();
+        }
+        events.push_back(event);
+    }
+
+    /// Returns the recorded events, oldest first.
+    pub(crate) fn snapshot(&self) -> Vec<ConnectionEvent> {
+        self.events.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// Returns the time of the last attempt to open a connection.
+    pub(crate) fn last_connection_attempt(&self) -> Option<SystemTime> {
+        *self.last_connection_attempt.lock().unwrap()
+    }
+
+    /// Returns the last event which carried an error.
+    pub(crate) fn last_error(&self) -> Option<ConnectionEvent> {
+        self.last_error.lock().unwrap().clone()
+    }
+}
+
+impl Default for ConnectionEventLog {
+    fn default() -> Self {
+        Self::new(CONNECTION_EVENT_LOG_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::{ConnectionEventKind, ConnectionEventLog};
+    use crate::errors::{BrokenConnectionErrorKind, ConnectionError};
+
+    #[test]
+    fn event_log_discards_oldest_events() {
+        let log = ConnectionEventLog::new(3);
+        for shard in 0..5 {
+            log.record(ConnectionEventKind::StreamIdsExhausted { shard });
+        }
+
+        let shards: Vec<_> = log
+            .snapshot()
+            .iter()
+            .map(|event| match event.kind() {
+                ConnectionEventKind::StreamIdsExhausted { shard } => *shard,
+                other => panic!("Unexpected event: {other:?}"),
+            })
+            .collect();
+        assert_eq!(shards, [2, 3, 4]);
+    }
+
+    #[test]
+    fn event_log_remembers_last_error() {
+        let log = ConnectionEventLog::new(2);
+        assert!(log.last_connection_attempt().is_none());
+        assert!(log.last_error().is_none());
+
+        log.record_connection_attempt();
+        log.record(ConnectionEventKind::ConnectFailed {
+            shard_aware_port: false,
+            error: ConnectionError::ConnectTimeout,
+        });
+        // The failure is discarded from the ring buffer, but not forgotten.
+        for shard in 0..2 {
+            log.record(ConnectionEventKind::StreamIdsExhausted { shard });
+        }
+        assert!(log.last_connection_attempt().is_some());
+        assert_matches!(
+            log.last_error().unwrap().kind().error(),
+            Some(ConnectionError::ConnectTimeout)
+        );
+    }
+
+    #[test]
+    fn broken_connection_classification() {
+        let keepalive = ConnectionError::BrokenConnection(
+            BrokenConnectionErrorKind::KeepaliveTimeout([127, 0, 0, 1].into()).into(),
+        );
+        assert_matches!(
+            ConnectionEventKind::from_broken_connection(keepalive),
+            ConnectionEventKind::KeepaliveMissed { .. }
+        );
+
+        let other =
+            ConnectionError::BrokenConnection(BrokenConnectionErrorKind::ChannelError.into());
+        assert_matches!(
+            ConnectionEventKind::from_broken_connection(other),
+            ConnectionEventKind::ConnectionBroken { .. }
+        );
+    }
+}
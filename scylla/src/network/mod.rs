@@ -4,15 +4,26 @@
 //! - Connection - a single, possibly encrypted, connection to a ScyllaDB node over CQL protocol,
 //! - NodeConnectionPool - a manager that keeps a desired number of connections opened to each shard.
 
+mod buffer_pool;
 mod connection;
+mod priority_queue;
 
 pub(crate) use connection::open_connection;
 
-pub(crate) use connection::{Connection, ConnectionConfig, VerifiedKeyspaceName};
+pub(crate) use connection::{
+    Connection, ConnectionConfig, RequestSettings, SocketOptions, VerifiedKeyspaceName,
+};
 
 mod connection_pool;
 
-pub use connection::WriteCoalescingDelay;
-pub use connection_pool::PoolSize;
+mod event_log;
+pub use event_log::{ConnectionEvent, ConnectionEventKind};
+
+pub use connection::{KeepaliveRequest, WriteCoalescingDelay};
 pub(crate) use connection_pool::{ConnectivityChangeEvent, NodeConnectionPool, PoolConfig};
+pub use connection_pool::{DynamicPoolSize, PoolSize, PoolWarmup, ShardAwarePortUsage};
+mod proxy;
+pub use proxy::Proxy;
+pub(crate) use proxy::ProxyRules;
+
 pub(crate) mod tls;
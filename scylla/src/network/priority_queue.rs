@@ -0,0 +1,169 @@
+//! A channel of requests waiting to be written to a connection,
+//! which schedules them fairly according to their priorities.
+//!
+//! Each priority class has its own queue. When several queues are non-empty,
+//! items are taken from them in a weighted round-robin fashion, so that
+//! a flood of low-priority requests (e.g. pages fetched by pagers) cannot
+//! delay high-priority ones indefinitely, and vice versa - low-priority requests
+//! still make progress when there is a lot of high-priority traffic.
+
+use tokio::sync::mpsc;
+
+use crate::client::execution_profile::RequestPriority;
+
+const CLASSES: usize = 3;
+
+/// How many items of each class are taken in a single round,
+/// in order of decreasing priority.
+const WEIGHTS: [u32; CLASSES] = [4, 2, 1];
+
+fn class_of(priority: RequestPriority) -> usize {
+    match priority {
+        RequestPriority::High => 0,
+        RequestPriority::Normal => 1,
+        RequestPriority::Low => 2,
+    }
+}
+
+/// Creates a channel with a separate queue of size `capacity` for each priority class.
+pub(crate) fn priority_channel<T>(capacity: usize) -> (PrioritySender<T>, PriorityReceiver<T>) {
+    let (high_sender, high_receiver) = mpsc::channel(capacity);
+    let (normal_sender, normal_receiver) = mpsc::channel(capacity);
+    let (low_sender, low_receiver) = mpsc::channel(capacity);
+
+    (
+        PrioritySender {
+            senders: [high_sender, normal_sender, low_sender],
+        },
+        PriorityReceiver {
+            receivers: [high_receiver, normal_receiver, low_receiver],
+            credits: WEIGHTS,
+        },
+    )
+}
+
+pub(crate) struct PrioritySender<T> {
+    senders: [mpsc::Sender<T>; CLASSES],
+}
+
+impl<T> PrioritySender<T> {
+    /// Waits for space in the queue of the given priority class, and enqueues the item.
+    pub(crate) async fn send(
+        &self,
+        priority: RequestPriority,
+        item: T,
+    ) -> Result<(), mpsc::error::SendError<T>> {
+        self.senders[class_of(priority)].send(item).await
+    }
+}
+
+pub(crate) struct PriorityReceiver<T> {
+    receivers: [mpsc::Receiver<T>; CLASSES],
+    // How many more items of each class can be taken in the current round.
+    credits: [u32; CLASSES],
+}
+
+impl<T> PriorityReceiver<T> {
+    /// Takes the next item without waiting, if any is available.
+    pub(crate) fn try_recv(&mut self) -> Option<T> {
+        // The second pass starts a new round, in case all the classes
+        // which still have credits in the current round are empty.
+        for _ in 0..2 {
+            for class in 0..CLASSES {
+                if self.credits[class] == 0 {
+                    continue;
+                }
+                if let Ok(item) = self.receivers[class].try_recv() {
+                    self.credits[class] -= 1;
+                    return Some(item);
+                }
+            }
+            self.credits = WEIGHTS;
+        }
+        None
+    }
+
+    /// Waits for the next item. Returns `None` once all the senders are dropped
+    /// and all the queues are drained.
+    pub(crate) async fn recv(&mut self) -> Option<T> {
+        if let Some(item) = self.try_recv() {
+            return Some(item);
+        }
+
+        let [high, normal, low] = &mut self.receivers;
+        let (item, class) = tokio::select! {
+            biased;
+            Some(item) = high.recv() => (item, 0),
+            Some(item) = normal.recv() => (item, 1),
+            Some(item) = low.recv() => (item, 2),
+            else => return None,
+        };
+        self.credits[class] = self.credits[class].saturating_sub(1);
+        Some(item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::priority_channel;
+    use crate::client::execution_profile::RequestPriority;
+    use crate::test_utils::setup_tracing;
+
+    #[tokio::test]
+    async fn backlogged_classes_are_served_by_weight() {
+        setup_tracing();
+        let (sender, mut receiver) = priority_channel(64);
+
+        for i in 0..14 {
+            sender.send(RequestPriority::Low, ('L', i)).await.unwrap();
+            sender
+                .send(RequestPriority::Normal, ('N', i))
+                .await
+                .unwrap();
+            sender.send(RequestPriority::High, ('H', i)).await.unwrap();
+        }
+
+        let mut order = String::new();
+        for _ in 0..14 {
+            order.push(receiver.recv().await.unwrap().0);
+        }
+        assert_eq!(order, "HHHHNNLHHHHNNL");
+
+        // Classes without pending items do not hold the others back.
+        let (sender, mut receiver) = priority_channel(64);
+        for i in 0..6 {
+            sender.send(RequestPriority::Low, ('L', i)).await.unwrap();
+            sender
+                .send(RequestPriority::Normal, ('N', i))
+                .await
+                .unwrap();
+        }
+        let mut order = String::new();
+        while let Some((class, _)) = receiver.try_recv() {
+            order.push(class);
+        }
+        assert_eq!(order, "NNLNNLNNLLLL");
+    }
+
+    #[tokio::test]
+    async fn receiver_waits_for_any_class_and_ends_when_senders_are_dropped() {
+        setup_tracing();
+        let (sender, mut receiver) = priority_channel(1);
+
+        let receiving = tokio::spawn(async move {
+            let mut items = Vec::new();
+            while let Some(item) = receiver.recv().await {
+                items.push(item);
+            }
+            items
+        });
+
+        sender.send(RequestPriority::Low, 1).await.unwrap();
+        sender.send(RequestPriority::High, 2).await.unwrap();
+        drop(sender);
+
+        let mut items = receiving.await.unwrap();
+        items.sort();
+        assert_eq!(items, [1, 2]);
+    }
+}
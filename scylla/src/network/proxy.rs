@@ -0,0 +1,452 @@
+//! Tunneling connections to nodes through SOCKS5 or HTTP CONNECT proxies.
+
+use std::collections::HashMap;
+use std::io::{Error, ErrorKind};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use zeroize::Zeroizing;
+
+use super::connection::{SocketOptions, connect_with_source_ip_and_port};
+use crate::cluster::metadata::UntranslatedEndpoint;
+use crate::utils::runtime::TcpStream;
+
+/// A proxy through which connections to nodes are tunneled.
+///
+/// Proxied connections cannot use the shard-aware port, because the node sees
+/// the source port of the proxy, so connections are assigned to shards by the node.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Proxy {
+    kind: ProxyKind,
+    address: SocketAddr,
+    credentials: Option<(String, Zeroizing<String>)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ProxyKind {
+    Socks5,
+    HttpConnect,
+}
+
+impl Proxy {
+    /// A SOCKS5 proxy listening on the given address.
+    pub fn socks5(address: SocketAddr) -> Self {
+        Self {
+            kind: ProxyKind::Socks5,
+            address,
+            credentials: None,
+        }
+    }
+
+    /// An HTTP proxy listening on the given address, supporting the `CONNECT` method.
+    pub fn http_connect(address: SocketAddr) -> Self {
+        Self {
+            kind: ProxyKind::HttpConnect,
+            address,
+            credentials: None,
+        }
+    }
+
+    /// Authenticates to the proxy with the given username and password.
+    ///
+    /// SOCKS5 proxies use username/password authentication (RFC 1929),
+    /// HTTP proxies use the `Basic` scheme. The password is never included in `Debug`
+    /// output and is wiped from memory, along with the handshake messages containing it,
+    /// when no longer needed.
+    pub fn with_credentials(
+        mut self,
+        username: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Self {
+        self.credentials = Some((username.into(), Zeroizing::new(password.into())));
+        self
+    }
+
+    /// The address of the proxy.
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Opens a connection to `target` through the proxy.
+    pub(crate) async fn connect(
+        &self,
+        target: SocketAddr,
+        source_ip: Option<IpAddr>,
+        socket_options: &SocketOptions,
+    ) -> Result<TcpStream, Error> {
+        let mut stream =
+            connect_with_source_ip_and_port(self.address, source_ip, None, socket_options).await?;
+        match self.kind {
+            ProxyKind::Socks5 => self.socks5_handshake(&mut stream, target).await?,
+            ProxyKind::HttpConnect => self.http_connect_handshake(&mut stream, target).await?,
+        }
+        Ok(stream)
+    }
+
+    async fn socks5_handshake(
+        &self,
+        stream: &mut TcpStream,
+        target: SocketAddr,
+    ) -> Result<(), Error> {
+        const VERSION: u8 = 5;
+        const NO_AUTHENTICATION: u8 = 0;
+        const USERNAME_PASSWORD: u8 = 2;
+
+        let greeting: &[u8] = match self.credentials {
+            Some(_) => &[VERSION, 2, NO_AUTHENTICATION, USERNAME_PASSWORD],
+            None => &[VERSION, 1, NO_AUTHENTICATION],
+        };
+        stream.write_all(greeting).await?;
+
+        let mut choice = [0u8; 2];
+        stream.read_exact(&mut choice).await?;
+        if choice[0] != VERSION {
+            return Err(proxy_error("SOCKS5 proxy replied with an invalid version"));
+        }
+        match (choice[1], &self.credentials) {
+            (NO_AUTHENTICATION, _) => {}
+            (USERNAME_PASSWORD, Some((username, password))) => {
+                let (Ok(username_len), Ok(password_len)) =
+                    (u8::try_from(username.len()), u8::try_from(password.len()))
+                else {
+                    return Err(proxy_error("SOCKS5 credentials are longer than 255 bytes"));
+                };
+                // Allocated with the exact capacity, so that the request isn't reallocated,
+                // leaving unwiped copies of the password behind.
+                let mut request =
+                    Zeroizing::new(Vec::with_capacity(3 + username.len() + password.len()));
+                request.extend_from_slice(&[1, username_len]);
+                request.extend_from_slice(username.as_bytes());
+                request.push(password_len);
+                request.extend_from_slice(password.as_bytes());
+                stream.write_all(&request).await?;
+
+                let mut status = [0u8; 2];
+                stream.read_exact(&mut status).await?;
+                if status[1] != 0 {
+                    return Err(proxy_error("SOCKS5 proxy rejected the credentials"));
+                }
+            }
+            _ => {
+                return Err(proxy_error(
+                    "SOCKS5 proxy does not accept any of the offered authentication methods",
+                ));
+            }
+        }
+
+        let mut request = vec![VERSION, 1 /* CONNECT */, 0];
+        match target.ip() {
+            IpAddr::V4(ip) => {
+                request.push(1);
+                request.extend_from_slice(&ip.octets());
+            }
+            IpAddr::V6(ip) => {
+                request.push(4);
+                request.extend_from_slice(&ip.octets());
+            }
+        }
+        request.extend_from_slice(&target.port().to_be_bytes());
+        stream.write_all(&request).await?;
+
+        let mut reply = [0u8; 4];
+        stream.read_exact(&mut reply).await?;
+        if reply[1] != 0 {
+            return Err(proxy_error(format!(
+                "SOCKS5 proxy failed to connect to {}: {}",
+                target,
+                socks5_reply_description(reply[1])
+            )));
+        }
+        // Skip the bound address, which is not needed.
+        let address_len = match reply[3] {
+            1 => 4,
+            3 => usize::from(stream.read_u8().await?),
+            4 => 16,
+            _ => {
+                return Err(proxy_error(
+                    "SOCKS5 proxy replied with an invalid address type",
+                ));
+            }
+        };
+        let mut bound_address = vec![0u8; address_len + 2];
+        stream.read_exact(&mut bound_address).await?;
+        Ok(())
+    }
+
+    async fn http_connect_handshake(
+        &self,
+        stream: &mut TcpStream,
+        target: SocketAddr,
+    ) -> Result<(), Error> {
+        // The size of the response is limited, so that a misbehaving proxy can't exhaust memory.
+        const MAX_RESPONSE_LEN: usize = 8192;
+
+        const AUTHORIZATION: &str = "Proxy-Authorization: Basic ";
+
+        let connect = format!("CONNECT {target} HTTP/1.1\r\nHost: {target}\r\n");
+        let token = self.credentials.as_ref().map(|(username, password)| {
+            let mut user_pass =
+                Zeroizing::new(String::with_capacity(username.len() + 1 + password.len()));
+            user_pass.push_str(username);
+            user_pass.push(':');
+            user_pass.push_str(password);
+            Zeroizing::new(base64_encode(user_pass.as_bytes()))
+        });
+        // Like the token, the request is allocated once and wiped after being sent.
+        let token_len = token
+            .as_ref()
+            .map_or(0, |token| AUTHORIZATION.len() + token.len() + 2);
+        let mut request = Zeroizing::new(String::with_capacity(connect.len() + token_len + 2));
+        request.push_str(&connect);
+        if let Some(token) = &token {
+            request.push_str(AUTHORIZATION);
+            request.push_str(token);
+            request.push_str("\r\n");
+        }
+        request.push_str("\r\n");
+        stream.write_all(request.as_bytes()).await?;
+
+        // Read byte by byte, so that no bytes sent by the node after the response are consumed.
+        let mut response = Vec::new();
+        while !response.ends_with(b"\r\n\r\n") {
+            if response.len() == MAX_RESPONSE_LEN {
+                return Err(proxy_error("HTTP proxy response is too long"));
+            }
+            response.push(stream.read_u8().await?);
+        }
+
+        let response = String::from_utf8_lossy(&response);
+        let status_line = response.lines().next().unwrap_or_default();
+        let mut parts = status_line.splitn(3, ' ');
+        match (parts.next(), parts.next()) {
+            (Some(version), Some(status)) if version.starts_with("HTTP/1.") => {
+                if !status.starts_with('2') {
+                    return Err(proxy_error(format!(
+                        "HTTP proxy failed to connect to {target}: {status_line}"
+                    )));
+                }
+                Ok(())
+            }
+            _ => Err(proxy_error(format!(
+                "HTTP proxy replied with an invalid status line: {status_line}"
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Debug for Proxy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Don't print the credentials.
+        f.debug_struct("Proxy")
+            .field("kind", &self.kind)
+            .field("address", &self.address)
+            .field(
+                "username",
+                &self.credentials.as_ref().map(|(username, _)| username),
+            )
+            .finish()
+    }
+}
+
+/// Chooses the proxy to use for connections to a node.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ProxyRules {
+    pub(crate) default: Option<Proxy>,
+    pub(crate) per_datacenter: Arc<HashMap<String, Option<Proxy>>>,
+}
+
+impl ProxyRules {
+    /// Returns the proxy for the given endpoint. Contact points, whose datacenter
+    /// is not known, use the default proxy.
+    pub(crate) fn for_endpoint(&self, endpoint: &UntranslatedEndpoint) -> Option<Proxy> {
+        let datacenter = match endpoint {
+            UntranslatedEndpoint::Peer(peer) => peer.datacenter.as_deref(),
+            UntranslatedEndpoint::ContactPoint(_) => None,
+        };
+        match datacenter.and_then(|dc| self.per_datacenter.get(dc)) {
+            Some(proxy) => proxy.clone(),
+            None => self.default.clone(),
+        }
+    }
+}
+
+fn proxy_error(message: impl Into<String>) -> Error {
+    Error::new(ErrorKind::ConnectionRefused, message.into())
+}
+
+fn socks5_reply_description(reply: u8) -> &'static str {
+    match reply {
+        1 => "general failure",
+        2 => "connection not allowed by ruleset",
+        3 => "network unreachable",
+        4 => "host unreachable",
+        5 => "connection refused",
+        6 => "TTL expired",
+        7 => "command not supported",
+        8 => "address type not supported",
+        _ => "unknown error",
+    }
+}
+
+fn base64_encode(input: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut output = String::with_capacity(input.len().div_ceil(3) * 4);
+    for chunk in input.chunks(3) {
+        let bytes = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let triple = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                let index = (triple >> (18 - 6 * i)) & 0x3f;
+                output.push(char::from(ALPHABET[index as usize]));
+            } else {
+                output.push('=');
+            }
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    use super::{Proxy, SocketOptions, base64_encode};
+    use crate::test_utils::setup_tracing;
+
+    const TARGET: &str = "10.0.0.1:9042";
+
+    #[test]
+    fn test_base64_encode() {
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_encode(b"f"), "Zg==");
+        assert_eq!(base64_encode(b"fo"), "Zm8=");
+        assert_eq!(base64_encode(b"foo"), "Zm9v");
+        assert_eq!(base64_encode(b"user:pass"), "dXNlcjpwYXNz");
+    }
+
+    #[test]
+    fn proxy_debug_does_not_print_password() {
+        let proxy = Proxy::socks5("127.0.0.1:1080".parse().unwrap())
+            .with_credentials("user", "secret-password");
+        let debug = format!("{proxy:?}");
+        assert!(debug.contains("user"));
+        assert!(!debug.contains("secret-password"));
+    }
+
+    #[tokio::test]
+    async fn socks5_proxy() {
+        setup_tracing();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy_address = listener.local_addr().unwrap();
+
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut greeting = [0u8; 4];
+            stream.read_exact(&mut greeting).await.unwrap();
+            assert_eq!(greeting, [5, 2, 0, 2]);
+            stream.write_all(&[5, 2]).await.unwrap();
+
+            let mut auth = [0u8; 11];
+            stream.read_exact(&mut auth).await.unwrap();
+            assert_eq!(&auth, b"\x01\x04user\x04pass");
+            stream.write_all(&[1, 0]).await.unwrap();
+
+            let mut request = [0u8; 10];
+            stream.read_exact(&mut request).await.unwrap();
+            assert_eq!(request, [5, 1, 0, 1, 10, 0, 0, 1, 0x23, 0x52]);
+            stream
+                .write_all(&[5, 0, 0, 1, 127, 0, 0, 1, 0, 0, b'!'])
+                .await
+                .unwrap();
+        });
+
+        let mut stream = Proxy::socks5(proxy_address)
+            .with_credentials("user", "pass")
+            .connect(TARGET.parse().unwrap(), None, &SocketOptions::default())
+            .await
+            .unwrap();
+        // Data sent after the reply is not consumed by the handshake.
+        assert_eq!(stream.read_u8().await.unwrap(), b'!');
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn socks5_proxy_connection_refused() {
+        setup_tracing();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy_address = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut greeting = [0u8; 3];
+            stream.read_exact(&mut greeting).await.unwrap();
+            stream.write_all(&[5, 0]).await.unwrap();
+            let mut request = [0u8; 10];
+            stream.read_exact(&mut request).await.unwrap();
+            stream
+                .write_all(&[5, 5, 0, 1, 0, 0, 0, 0, 0, 0])
+                .await
+                .unwrap();
+        });
+
+        let err = Proxy::socks5(proxy_address)
+            .connect(TARGET.parse().unwrap(), None, &SocketOptions::default())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("connection refused"), "{err}");
+    }
+
+    async fn http_proxy(
+        status_line: &'static str,
+    ) -> (SocketAddr, tokio::task::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy_address = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            while !request.ends_with(b"\r\n\r\n") {
+                request.push(stream.read_u8().await.unwrap());
+            }
+            stream
+                .write_all(format!("{status_line}\r\nVia: test\r\n\r\n!").as_bytes())
+                .await
+                .unwrap();
+            String::from_utf8(request).unwrap()
+        });
+        (proxy_address, server)
+    }
+
+    #[tokio::test]
+    async fn http_connect_proxy() {
+        setup_tracing();
+        let (proxy_address, server) = http_proxy("HTTP/1.1 200 Connection established").await;
+
+        let mut stream = Proxy::http_connect(proxy_address)
+            .with_credentials("user", "pass")
+            .connect(TARGET.parse().unwrap(), None, &SocketOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(stream.read_u8().await.unwrap(), b'!');
+        assert_eq!(
+            server.await.unwrap(),
+            "CONNECT 10.0.0.1:9042 HTTP/1.1\r\nHost: 10.0.0.1:9042\r\n\
+            Proxy-Authorization: Basic dXNlcjpwYXNz\r\n\r\n"
+        );
+
+        let (proxy_address, _server) = http_proxy("HTTP/1.1 403 Forbidden").await;
+        let err = Proxy::http_connect(proxy_address)
+            .connect(TARGET.parse().unwrap(), None, &SocketOptions::default())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("403 Forbidden"), "{err}");
+    }
+}
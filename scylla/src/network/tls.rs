@@ -23,16 +23,294 @@
 //! which had additional field for SNI hostname.
 //! We could remove `TlsProvider`, and maybe even `TlsConfig`, but for now we kept it - it may be useful in the future,
 //! for example if we wanted to support more elastic hostname verification.
+//!
+//! A `TlsProvider` may also be backed by a [ReloadableTlsContext], whose `TlsContext` can be replaced
+//! at runtime. Each `TlsConfig` is then produced from the context which is current at that moment.
+//! Finally, it may be backed by a user-provided [NodeTlsProvider], which chooses TLS settings
+//! (including the server name) separately for each node.
 
+use std::fmt::{Debug, Display};
 use std::io;
+use std::net::{IpAddr, SocketAddr};
+use std::path::PathBuf;
+use std::sync::{Arc, Weak};
+use std::time::{Duration, SystemTime};
+
+use arc_swap::ArcSwap;
+use tracing::{info, warn};
+
+use uuid::Uuid;
 
 use crate::client::session::TlsContext;
-use crate::cluster::metadata::UntranslatedEndpoint;
+use crate::cluster::metadata::{PeerEndpoint, UntranslatedEndpoint};
+use crate::cluster::node::ResolvedContactPoint;
+use crate::observability::log_targets;
+use crate::utils::runtime;
+
+/// A [TlsContext] which can be replaced while the session is running,
+/// e.g. to rotate short-lived certificates without restarting the application.
+///
+/// Connections opened after the context is replaced use the new context.
+/// Established connections keep using the context they were opened with;
+/// they can be recycled by setting the maximum age of connections
+/// ([SessionBuilder::connection_max_age](crate::client::session_builder::GenericSessionBuilder::connection_max_age)).
+///
+/// Clones share the context, so the application can keep a clone
+/// to replace the context used by the session.
+#[derive(Clone)]
+pub struct ReloadableTlsContext {
+    current: Arc<ArcSwap<TlsContext>>,
+}
+
+impl ReloadableTlsContext {
+    /// Creates a reloadable context, initially holding the given context.
+    #[cfg_attr(
+        not(any(feature = "openssl-010", feature = "rustls-023")),
+        // TODO: make this expect() once MSRV is 1.92+.
+        allow(unreachable_code)
+    )]
+    pub fn new(context: impl Into<TlsContext>) -> Self {
+        Self {
+            current: Arc::new(ArcSwap::from_pointee(context.into())),
+        }
+    }
+
+    /// Returns the context which is used for new connections.
+    pub fn current(&self) -> TlsContext {
+        TlsContext::clone(&self.current.load())
+    }
+
+    /// Replaces the context used for new connections.
+    #[cfg_attr(
+        not(any(feature = "openssl-010", feature = "rustls-023")),
+        // TODO: make this expect() once MSRV is 1.92+.
+        allow(unreachable_code)
+    )]
+    pub fn replace(&self, context: impl Into<TlsContext>) {
+        self.current.store(Arc::new(context.into()));
+    }
+
+    /// Watches the given files, e.g. the certificate, the private key and the CA certificate,
+    /// and rebuilds the context with `build_context` when any of them is modified.
+    ///
+    /// The files are checked every `poll_interval`. If `build_context` fails, e.g. because
+    /// the files are in the middle of being rewritten, a warning is logged and the previous
+    /// context stays in use; the context is rebuilt again after the next modification.
+    /// Watching stops once all clones of this context are dropped.
+    ///
+    /// Must be called within the async runtime, as the files are watched in a background task.
+    pub fn reload_on_file_change<F, E>(
+        &self,
+        paths: impl IntoIterator<Item = impl Into<PathBuf>>,
+        poll_interval: Duration,
+        build_context: F,
+    ) where
+        F: Fn() -> Result<TlsContext, E> + Send + Sync + 'static,
+        E: Display + Send + 'static,
+    {
+        let paths: Arc<[PathBuf]> = paths.into_iter().map(Into::into).collect();
+        // The initial state is captured here, so that modifications made right after
+        // this call are not missed.
+        let last_seen = file_versions(&paths);
+        let current = Arc::downgrade(&self.current);
+        let build_context = Arc::new(build_context);
+        runtime::spawn(watch_files(
+            paths,
+            last_seen,
+            poll_interval,
+            current,
+            build_context,
+        ));
+    }
+}
+
+/// Identifies versions of the files by their modification times and sizes.
+/// Files which cannot be accessed are represented by `None`.
+fn file_versions(paths: &[PathBuf]) -> Vec<Option<(SystemTime, u64)>> {
+    paths
+        .iter()
+        .map(|path| {
+            let metadata = std::fs::metadata(path).ok()?;
+            Some((metadata.modified().ok()?, metadata.len()))
+        })
+        .collect()
+}
+
+async fn watch_files<F, E>(
+    paths: Arc<[PathBuf]>,
+    mut last_seen: Vec<Option<(SystemTime, u64)>>,
+    poll_interval: Duration,
+    current: Weak<ArcSwap<TlsContext>>,
+    build_context: Arc<F>,
+) where
+    F: Fn() -> Result<TlsContext, E> + Send + Sync + 'static,
+    E: Display + Send + 'static,
+{
+    let mut interval = runtime::interval(poll_interval);
+    interval.tick().await;
+    loop {
+        interval.tick().await;
+        if current.strong_count() == 0 {
+            return;
+        }
+        // Reading file metadata is blocking, so it is done outside of the async runtime's threads.
+        let paths = paths.clone();
+        let seen = runtime::spawn_blocking(move || file_versions(&paths)).await;
+        if seen == last_seen {
+            continue;
+        }
+        last_seen = seen;
+
+        let build = build_context.clone();
+        let result = runtime::spawn_blocking(move || build()).await;
+        let Some(current) = current.upgrade() else {
+            return;
+        };
+        match result {
+            Ok(context) => {
+                current.store(Arc::new(context));
+                info!(
+                    target: log_targets::CONNECTION,
+                    "TLS files changed, new connections will use the reloaded TLS context"
+                );
+            }
+            Err(error) => warn!(
+                target: log_targets::CONNECTION,
+                %error,
+                "TLS files changed, but the TLS context could not be rebuilt; keeping the previous one"
+            ),
+        }
+    }
+}
+
+/// Chooses TLS settings separately for each node, e.g. to use different certificates
+/// or server names in different datacenters, or to enable TLS only for some nodes
+/// while a cluster is being migrated to TLS.
+///
+/// The provider is consulted whenever the driver sets up connections to a node,
+/// i.e. when the node is first seen, and when its address changes.
+pub trait NodeTlsProvider: Debug + Send + Sync {
+    /// Returns TLS settings for connections to the given node,
+    /// or `None` if connections to it should not use TLS.
+    fn tls_settings(&self, node: &TlsTarget<'_>) -> Option<NodeTlsSettings>;
+}
+
+/// A node to which a [NodeTlsProvider] chooses TLS settings.
+#[derive(Debug)]
+pub struct TlsTarget<'a> {
+    address: SocketAddr,
+    host_id: Option<Uuid>,
+    datacenter: Option<&'a str>,
+    rack: Option<&'a str>,
+    hostname: Option<&'a str>,
+}
+
+impl<'a> TlsTarget<'a> {
+    fn from_endpoint(endpoint: &'a UntranslatedEndpoint) -> Self {
+        match endpoint {
+            UntranslatedEndpoint::ContactPoint(ResolvedContactPoint {
+                address,
+                resolved_from,
+            }) => Self {
+                address: *address,
+                host_id: None,
+                datacenter: None,
+                rack: None,
+                hostname: resolved_from.as_ref().map(|r| r.hostname.as_str()),
+            },
+            UntranslatedEndpoint::Peer(PeerEndpoint {
+                host_id,
+                address,
+                datacenter,
+                rack,
+            }) => Self {
+                address: address.into_inner(),
+                host_id: Some(*host_id),
+                datacenter: datacenter.as_deref(),
+                rack: rack.as_deref(),
+                hostname: None,
+            },
+        }
+    }
+
+    /// The address of the node, before address translation.
+    #[inline]
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// The unique identifier of the node in the cluster.
+    /// Unknown for contact points, which are connected to before the cluster metadata is fetched.
+    #[inline]
+    pub fn host_id(&self) -> Option<Uuid> {
+        self.host_id
+    }
+
+    /// The datacenter the node resides in. Unknown for contact points.
+    #[inline]
+    pub fn datacenter(&self) -> Option<&'a str> {
+        self.datacenter
+    }
+
+    /// The rack the node resides in. Unknown for contact points.
+    #[inline]
+    pub fn rack(&self) -> Option<&'a str> {
+        self.rack
+    }
+
+    /// The hostname the address was resolved from, for contact points given as hostnames.
+    #[inline]
+    pub fn hostname(&self) -> Option<&'a str> {
+        self.hostname
+    }
+}
+
+/// TLS settings of connections to a node, chosen by a [NodeTlsProvider].
+#[derive(Clone)]
+pub struct NodeTlsSettings {
+    context: TlsConfigContext,
+    server_name: Option<String>,
+}
+
+impl NodeTlsSettings {
+    /// Settings using the given context. Client certificates, trusted CAs and
+    /// the verification mode are configured in the context.
+    #[cfg_attr(
+        not(any(feature = "openssl-010", feature = "rustls-023")),
+        // TODO: make this expect() once MSRV is 1.92+.
+        allow(unreachable_code)
+    )]
+    pub fn new(context: impl Into<TlsContext>) -> Self {
+        Self {
+            context: TlsConfigContext::Fixed(context.into()),
+            server_name: None,
+        }
+    }
+
+    /// Settings using the current context of the given [ReloadableTlsContext]
+    /// whenever a connection is opened.
+    pub fn with_reloadable_context(context: ReloadableTlsContext) -> Self {
+        Self {
+            context: TlsConfigContext::Reloadable(context),
+            server_name: None,
+        }
+    }
+
+    /// Sets the server name, which is sent in the SNI extension, and against which
+    /// the certificate of the node is verified.
+    /// By default, no SNI is sent, and the certificate is verified against the node's IP address.
+    pub fn with_server_name(mut self, server_name: impl Into<String>) -> Self {
+        self.server_name = Some(server_name.into());
+        self
+    }
+}
 
 /// Abstraction capable of producing [TlsConfig] for connections on-demand.
 #[derive(Clone)] // Cheaply clonable (reference-counted)
 pub(crate) enum TlsProvider {
     GlobalContext(TlsContext),
+    Reloadable(ReloadableTlsContext),
+    PerNode(Arc<dyn NodeTlsProvider>),
 }
 
 impl TlsProvider {
@@ -41,13 +319,21 @@ impl TlsProvider {
         Self::GlobalContext(context)
     }
 
+    /// Used in case when the user provided a [ReloadableTlsContext], whose current context
+    /// is used for each new connection.
+    pub(crate) fn new_with_reloadable_context(context: ReloadableTlsContext) -> Self {
+        Self::Reloadable(context)
+    }
+
+    /// Used in case when the user provided a [NodeTlsProvider], which is asked
+    /// for settings of each node.
+    pub(crate) fn new_with_node_provider(provider: Arc<dyn NodeTlsProvider>) -> Self {
+        Self::PerNode(provider)
+    }
+
     /// Produces a [TlsConfig] that is specific for the given endpoint.
-    pub(crate) fn make_tls_config(
-        &self,
-        // This was only used for serverless cloud; but it makes abstract sense to pass endpoint here
-        // also for non-cloud cases, so let's just allow(unused).
-        #[expect(unused)] endpoint: &UntranslatedEndpoint,
-    ) -> Option<TlsConfig> {
+    /// Returns `None` if connections to the endpoint should not use TLS.
+    pub(crate) fn make_tls_config(&self, endpoint: &UntranslatedEndpoint) -> Option<TlsConfig> {
         match self {
             TlsProvider::GlobalContext(context) => {
                 #[cfg_attr(
@@ -57,16 +343,37 @@ impl TlsProvider {
                 )]
                 Some(TlsConfig::new_with_global_context(context.clone()))
             }
+            TlsProvider::Reloadable(context) => {
+                Some(TlsConfig::new_with_reloadable_context(context.clone()))
+            }
+            TlsProvider::PerNode(provider) => provider
+                .tls_settings(&TlsTarget::from_endpoint(endpoint))
+                .map(|settings| TlsConfig {
+                    context: settings.context,
+                    server_name: settings.server_name,
+                }),
         }
     }
 }
 
 /// Encapsulates TLS-regarding configuration that is specific for a particular endpoint.
-///
-/// Currently we don't need any host-specific parameters, but that may change in the future.
 #[derive(Clone)]
 pub(crate) struct TlsConfig {
-    context: TlsContext,
+    context: TlsConfigContext,
+    /// If set, sent in SNI and used to verify the node's certificate instead of its IP address.
+    #[cfg_attr(
+        not(any(feature = "openssl-010", feature = "rustls-023")),
+        expect(dead_code)
+    )]
+    server_name: Option<String>,
+}
+
+/// A TlsConfig is made once per node, so a reloadable context is only resolved
+/// when a connection is opened, for connections to pick up the replaced context.
+#[derive(Clone)]
+enum TlsConfigContext {
+    Fixed(TlsContext),
+    Reloadable(ReloadableTlsContext),
 }
 
 /// An abstraction over connection's TLS layer which holds its state and configuration.
@@ -76,6 +383,7 @@ pub(crate) enum Tls {
     #[cfg(feature = "rustls-023")]
     Rustls023 {
         connector: tokio_rustls::TlsConnector,
+        server_name: rustls::pki_types::ServerName<'static>,
     },
 }
 
@@ -118,25 +426,239 @@ impl From<TlsError> for io::Error {
 impl TlsConfig {
     /// Used in case when the user provided their own TlsContext to be used in all connections.
     pub(crate) fn new_with_global_context(context: TlsContext) -> Self {
-        Self { context }
+        Self {
+            context: TlsConfigContext::Fixed(context),
+            server_name: None,
+        }
     }
 
-    /// Produces a new Tls object that is able to wrap a TCP stream.
-    pub(crate) fn new_tls(&self) -> Result<Tls, TlsError> {
-        match self.context {
+    /// Used in case when the user provided a [ReloadableTlsContext].
+    pub(crate) fn new_with_reloadable_context(context: ReloadableTlsContext) -> Self {
+        Self {
+            context: TlsConfigContext::Reloadable(context),
+            server_name: None,
+        }
+    }
+
+    /// Produces a new Tls object that is able to wrap a TCP stream to the node at `node_address`.
+    /// The node's certificate is verified against the server name, if one is set,
+    /// and against `node_address` otherwise.
+    #[cfg_attr(
+        not(any(feature = "openssl-010", feature = "rustls-023")),
+        // TODO: make this expect() once MSRV is 1.92+.
+        allow(unreachable_code, unused_variables)
+    )]
+    pub(crate) fn new_tls(&self, node_address: IpAddr) -> Result<Tls, TlsError> {
+        let context = match &self.context {
+            TlsConfigContext::Fixed(context) => context.clone(),
+            TlsConfigContext::Reloadable(context) => context.current(),
+        };
+        match context {
             #[cfg(feature = "openssl-010")]
-            TlsContext::OpenSsl010(ref context) => {
+            TlsContext::OpenSsl010(context) => {
                 #[allow(unused_mut)]
-                let mut ssl = openssl::ssl::Ssl::new(context)?;
+                let mut ssl = openssl::ssl::Ssl::new(&context)?;
                 ssl.set_connect_state();
+                match &self.server_name {
+                    Some(server_name) => {
+                        ssl.set_hostname(server_name)?;
+                        ssl.param_mut().set_host(server_name)?;
+                    }
+                    None => ssl.param_mut().set_ip(node_address)?,
+                }
                 Ok(Tls::OpenSsl010(ssl))
             }
             #[cfg(feature = "rustls-023")]
-            TlsContext::Rustls023(ref config) => {
-                let connector = tokio_rustls::TlsConnector::from(config.clone());
+            TlsContext::Rustls023(config) => {
+                use rustls::pki_types::ServerName;
+                let connector = tokio_rustls::TlsConnector::from(config);
+                let server_name = match &self.server_name {
+                    Some(server_name) => ServerName::try_from(server_name.clone())?,
+                    None => ServerName::IpAddress(node_address.into()),
+                };
 
-                Ok(Tls::Rustls023 { connector })
+                Ok(Tls::Rustls023 {
+                    connector,
+                    server_name,
+                })
             }
         }
     }
 }
+
+#[cfg(all(test, feature = "rustls-023"))]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use super::{
+        NodeTlsProvider, NodeTlsSettings, ReloadableTlsContext, Tls, TlsConfig, TlsProvider,
+        TlsTarget,
+    };
+    use crate::client::session::TlsContext;
+    use crate::cluster::metadata::{PeerEndpoint, UntranslatedEndpoint};
+    use crate::cluster::node::{NodeAddr, ResolvedContactPoint};
+    use crate::errors::TlsError;
+    use crate::test_utils::setup_tracing;
+
+    // Contexts are told apart by their ALPN protocols.
+    fn context_with_alpn(protocol: &[u8]) -> TlsContext {
+        let mut config = rustls::ClientConfig::builder()
+            .with_root_certificates(rustls::RootCertStore::empty())
+            .with_no_client_auth();
+        config.alpn_protocols = vec![protocol.to_vec()];
+        Arc::new(config).into()
+    }
+
+    fn alpn_of(context: &TlsContext) -> Vec<u8> {
+        match context {
+            TlsContext::Rustls023(config) => config.alpn_protocols[0].clone(),
+            #[allow(unreachable_patterns)]
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn connections_use_replaced_context() {
+        setup_tracing();
+        let endpoint = UntranslatedEndpoint::ContactPoint(ResolvedContactPoint {
+            address: "127.0.0.1:9042".parse().unwrap(),
+            resolved_from: None,
+        });
+        let context = ReloadableTlsContext::new(context_with_alpn(b"old"));
+        let provider = TlsProvider::new_with_reloadable_context(context.clone());
+        // Made once per node, before the context is replaced.
+        let tls_config = provider.make_tls_config(&endpoint).unwrap();
+        let address = "127.0.0.1".parse().unwrap();
+        let alpn_of_new_connection = || match tls_config.new_tls(address).unwrap() {
+            Tls::Rustls023 { connector, .. } => connector.config().alpn_protocols[0].clone(),
+            #[allow(unreachable_patterns)]
+            _ => unreachable!(),
+        };
+        assert_eq!(alpn_of_new_connection(), b"old");
+
+        context.replace(context_with_alpn(b"new"));
+        assert_eq!(alpn_of_new_connection(), b"new");
+    }
+
+    #[tokio::test]
+    #[ntest::timeout(10000)]
+    async fn context_is_reloaded_when_files_change() {
+        setup_tracing();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("protocol");
+        std::fs::write(&path, "first").unwrap();
+
+        let read_path = path.clone();
+        let load = move || {
+            std::fs::read(&read_path).and_then(|protocol| {
+                if protocol.is_empty() {
+                    Err(std::io::Error::other("empty file"))
+                } else {
+                    Ok(context_with_alpn(&protocol))
+                }
+            })
+        };
+        let context = ReloadableTlsContext::new(load().unwrap());
+        context.reload_on_file_change([&path], Duration::from_millis(10), load);
+
+        std::fs::write(&path, "second").unwrap();
+        while alpn_of(&context.current()) != b"second" {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        // A context which fails to build does not replace the current one.
+        std::fs::write(&path, "").unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(alpn_of(&context.current()), b"second");
+
+        std::fs::write(&path, "third").unwrap();
+        while alpn_of(&context.current()) != b"third" {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+
+    #[derive(Debug)]
+    struct PerDatacenterProvider;
+
+    impl NodeTlsProvider for PerDatacenterProvider {
+        fn tls_settings(&self, node: &TlsTarget<'_>) -> Option<NodeTlsSettings> {
+            match node.datacenter() {
+                Some("plaintext") => None,
+                Some(dc) => Some(
+                    NodeTlsSettings::new(context_with_alpn(dc.as_bytes()))
+                        .with_server_name(format!("{dc}.example.com")),
+                ),
+                None => Some(NodeTlsSettings::new(context_with_alpn(b"contact point"))),
+            }
+        }
+    }
+
+    #[test]
+    fn node_provider_chooses_settings_per_node() {
+        setup_tracing();
+        let provider = TlsProvider::new_with_node_provider(Arc::new(PerDatacenterProvider));
+        let address = "127.0.0.1".parse().unwrap();
+        let peer = |dc: &str| {
+            UntranslatedEndpoint::Peer(PeerEndpoint {
+                host_id: uuid::Uuid::new_v4(),
+                address: NodeAddr::Translatable("127.0.0.1:9042".parse().unwrap()),
+                datacenter: Some(dc.to_owned()),
+                rack: None,
+            })
+        };
+
+        assert!(provider.make_tls_config(&peer("plaintext")).is_none());
+
+        let tls_config = provider.make_tls_config(&peer("dc2")).unwrap();
+        match tls_config.new_tls(address).unwrap() {
+            Tls::Rustls023 {
+                connector,
+                server_name,
+            } => {
+                assert_eq!(connector.config().alpn_protocols[0], b"dc2");
+                assert_eq!(server_name.to_str(), "dc2.example.com");
+            }
+            #[allow(unreachable_patterns)]
+            _ => unreachable!(),
+        }
+
+        // Without a server name, the certificate is verified against the address.
+        let contact_point = UntranslatedEndpoint::ContactPoint(ResolvedContactPoint {
+            address: "127.0.0.1:9042".parse().unwrap(),
+            resolved_from: None,
+        });
+        let tls_config = provider.make_tls_config(&contact_point).unwrap();
+        match tls_config.new_tls(address).unwrap() {
+            Tls::Rustls023 { server_name, .. } => assert_eq!(server_name.to_str(), "127.0.0.1"),
+            #[allow(unreachable_patterns)]
+            _ => unreachable!(),
+        }
+
+        let tls_config = provider.make_tls_config(&peer("not a dns name!")).unwrap();
+        assert!(matches!(
+            tls_config.new_tls(address),
+            Err(TlsError::InvalidName(_))
+        ));
+    }
+
+    #[test]
+    fn custom_rustls_config_is_used_as_is() {
+        setup_tracing();
+        // The crypto provider, certificate verifier and client auth are all chosen by the user.
+        let provider = Arc::new(rustls::crypto::aws_lc_rs::default_provider());
+        let config = Arc::new(
+            rustls::ClientConfig::builder_with_provider(provider)
+                .with_protocol_versions(&[&rustls::version::TLS13])
+                .unwrap()
+                .with_root_certificates(rustls::RootCertStore::empty())
+                .with_no_client_auth(),
+        );
+        let tls_config = TlsConfig::new_with_global_context(config.clone().into());
+        match tls_config.new_tls("127.0.0.1".parse().unwrap()).unwrap() {
+            Tls::Rustls023 { connector, .. } => assert!(Arc::ptr_eq(connector.config(), &config)),
+            #[allow(unreachable_patterns)]
+            _ => unreachable!(),
+        }
+    }
+}
@@ -0,0 +1,425 @@
+//! Driver self-diagnostics.
+//!
+//! [DiagnosticsReport] gathers, in a single call, the state that is usually
+//! requested when investigating driver issues: connection pools of all nodes,
+//! the control connection, the age of cluster metadata, the default execution
+//! profile and (with the `metrics` feature) a summary of driver metrics.
+//!
+//! With the `serde` feature enabled, the report implements `serde::Serialize`,
+//! so it can be dumped e.g. as JSON and attached to a bug report.
+//! Obtained from [Session::diagnostics_report](crate::client::session::Session::diagnostics_report).
+//!
+//! [ClusterStateDump] is a similar snapshot of the driver's view of the cluster:
+//! its topology, token ownership and schema, useful when debugging request routing.
+//! Obtained from [ClusterState::dump](crate::cluster::ClusterState::dump).
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use uuid::Uuid;
+
+use crate::client::execution_profile::ExecutionProfile;
+use crate::cluster::metadata::Keyspace;
+use crate::cluster::{ClusterState, ConnectionEvent, Node};
+use crate::routing::Token;
+
+/// A snapshot of the driver's state, intended for troubleshooting.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub struct DiagnosticsReport {
+    /// Wall-clock time at which the report was generated.
+    pub generated_at: SystemTime,
+    /// Version of the `scylla` crate.
+    pub driver_version: &'static str,
+    /// Keyspace set with `USE`, if any.
+    pub keyspace: Option<String>,
+    /// State of the control connection, used to fetch metadata and receive server events.
+    pub control_connection: ControlConnectionDiagnostics,
+    /// Time elapsed since the last successful full metadata refresh.
+    pub metadata_age: Duration,
+    /// Whether the session runs on dummy metadata, because no metadata could be fetched.
+    pub metadata_degraded: bool,
+    /// Connection pools of all known nodes.
+    pub nodes: Vec<NodeDiagnostics>,
+    /// Settings of the default execution profile.
+    pub default_profile: ExecutionProfileDiagnostics,
+    /// Summary of driver metrics.
+    #[cfg(feature = "metrics")]
+    pub metrics: MetricsDiagnostics,
+}
+
+/// State of the control connection.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub struct ControlConnectionDiagnostics {
+    /// Address of the node the control connection is (or was last) opened to.
+    pub address: SocketAddr,
+    /// Whether the control connection is working.
+    pub working: bool,
+    /// Error which broke the control connection, if it is not working.
+    pub last_error: Option<String>,
+    /// Addresses of the nodes standby control connections are opened to.
+    /// A standby connection replaces the control connection immediately when it breaks.
+    pub standby_addresses: Vec<SocketAddr>,
+}
+
+/// State of a single node and its connection pool.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub struct NodeDiagnostics {
+    /// ID of the node.
+    pub host_id: Uuid,
+    /// Address of the node.
+    pub address: SocketAddr,
+    /// Datacenter of the node, if known.
+    pub datacenter: Option<String>,
+    /// Rack of the node, if known.
+    pub rack: Option<String>,
+    /// Whether the node is accepted by the host filter.
+    pub enabled: bool,
+    /// Whether the pool has any open connections.
+    pub connected: bool,
+    /// Number of open connections to each shard. A single entry for non-sharded nodes.
+    pub connections_per_shard: Vec<usize>,
+    /// Recent significant connection events, oldest first.
+    pub recent_events: Vec<ConnectionEventDiagnostics>,
+}
+
+/// A [ConnectionEvent], rendered for the report.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub struct ConnectionEventDiagnostics {
+    /// Wall-clock time at which the event was recorded.
+    pub time: SystemTime,
+    /// Description of the event.
+    pub event: String,
+}
+
+/// Settings of an execution profile.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub struct ExecutionProfileDiagnostics {
+    /// Client-side request timeout.
+    pub request_timeout: Option<Duration>,
+    /// Consistency.
+    pub consistency: String,
+    /// Serial consistency.
+    pub serial_consistency: Option<String>,
+    /// Load balancing policy.
+    pub load_balancing_policy: String,
+    /// Retry policy.
+    pub retry_policy: String,
+    /// Speculative execution policy.
+    pub speculative_execution_policy: Option<String>,
+    /// Whether requests may be compressed.
+    pub compression_enabled: bool,
+    /// Priority of requests.
+    pub priority: String,
+}
+
+/// Summary of driver metrics.
+#[cfg(feature = "metrics")]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub struct MetricsDiagnostics {
+    /// Number of queries executed without `QueryPager`.
+    pub queries_num: u64,
+    /// Number of errors in queries executed without `QueryPager`.
+    pub errors_num: u64,
+    /// Number of pages fetched with `QueryPager`.
+    pub queries_iter_num: u64,
+    /// Number of errors in queries executed with `QueryPager`.
+    pub errors_iter_num: u64,
+    /// Number of retries.
+    pub retries_num: u64,
+    /// Average latency in milliseconds, if any request has completed.
+    pub latency_avg_ms: Option<u64>,
+    /// Number of active connections.
+    pub total_connections: u64,
+    /// Number of connection timeouts.
+    pub connection_timeouts: u64,
+    /// Number of client-side request timeouts.
+    pub request_timeouts: u64,
+}
+
+/// A snapshot of [ClusterState], intended for troubleshooting.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub struct ClusterStateDump {
+    /// Version of the token ring, see [ClusterState::ring_version].
+    pub ring_version: u64,
+    /// Version of the tablet maps, see [ClusterState::tablets_version].
+    pub tablets_version: u64,
+    /// All known nodes and their connection pools, sorted by address.
+    pub nodes: Vec<NodeDiagnostics>,
+    /// Datacenters of the known nodes, sorted by name.
+    pub datacenters: Vec<DatacenterDiagnostics>,
+    /// Share of the token ring owned by each node of the ring, sorted by address.
+    pub token_ownership: Vec<TokenOwnershipDiagnostics>,
+    /// Keyspaces present in cluster metadata, sorted by name.
+    pub keyspaces: Vec<KeyspaceDiagnostics>,
+}
+
+/// A datacenter, as seen by the driver.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub struct DatacenterDiagnostics {
+    /// Name of the datacenter.
+    pub name: String,
+    /// Names of the racks of the datacenter, sorted.
+    pub racks: Vec<String>,
+    /// Number of known nodes in the datacenter.
+    pub nodes: usize,
+}
+
+/// Share of the token ring owned by a single node.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub struct TokenOwnershipDiagnostics {
+    /// ID of the node.
+    pub host_id: Uuid,
+    /// Address of the node.
+    pub address: SocketAddr,
+    /// Number of tokens of the node.
+    pub tokens: usize,
+    /// Fraction of the token ring, from 0 to 1, for which the node is the primary replica
+    /// of vnode-based keyspaces.
+    pub ownership: f64,
+}
+
+/// A keyspace present in cluster metadata.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub struct KeyspaceDiagnostics {
+    /// Name of the keyspace.
+    pub name: String,
+    /// Replication strategy of the keyspace.
+    pub replication: String,
+    /// Names of the tables of the keyspace, sorted.
+    pub tables: Vec<String>,
+    /// Names of the materialized views of the keyspace, sorted.
+    pub views: Vec<String>,
+}
+
+impl ClusterStateDump {
+    pub(crate) fn new(state: &ClusterState) -> Self {
+        let mut nodes = state.get_nodes_info().to_vec();
+        nodes.sort_by_key(|node| node.address);
+
+        let mut datacenters: BTreeMap<&str, (BTreeSet<&str>, usize)> = BTreeMap::new();
+        for node in &nodes {
+            if let Some(datacenter) = &node.datacenter {
+                let (racks, count) = datacenters.entry(datacenter).or_default();
+                racks.extend(node.rack.as_deref());
+                *count += 1;
+            }
+        }
+
+        let mut keyspaces = state
+            .keyspaces_iter()
+            .map(|(name, keyspace)| KeyspaceDiagnostics::new(name, keyspace))
+            .collect::<Vec<_>>();
+        keyspaces.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Self {
+            ring_version: state.ring_version(),
+            tablets_version: state.tablets_version(),
+            datacenters: datacenters
+                .into_iter()
+                .map(|(name, (racks, nodes))| DatacenterDiagnostics {
+                    name: name.to_owned(),
+                    racks: racks.into_iter().map(str::to_owned).collect(),
+                    nodes,
+                })
+                .collect(),
+            token_ownership: token_ownership(
+                &state
+                    .replica_locator()
+                    .ring()
+                    .iter()
+                    .map(|(token, node)| (*token, node))
+                    .collect::<Vec<_>>(),
+            ),
+            nodes: nodes
+                .iter()
+                .map(|node| NodeDiagnostics::new(node))
+                .collect(),
+            keyspaces,
+        }
+    }
+}
+
+impl KeyspaceDiagnostics {
+    fn new(name: &str, keyspace: &Keyspace) -> Self {
+        Self {
+            name: name.to_owned(),
+            replication: format!("{:?}", keyspace.strategy),
+            tables: sorted_names(keyspace.tables.keys()),
+            views: sorted_names(keyspace.views.keys()),
+        }
+    }
+}
+
+fn sorted_names<'a>(names: impl Iterator<Item = &'a String>) -> Vec<String> {
+    let mut names = names.cloned().collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+/// Computes the share of the ring owned by each node, given the ring sorted by tokens.
+///
+/// Each token owns the range from the previous token (exclusive) to itself (inclusive).
+fn token_ownership(ring: &[(Token, &Arc<Node>)]) -> Vec<TokenOwnershipDiagnostics> {
+    const RING_SIZE: f64 = (1u128 << 64) as f64;
+
+    let mut owned: HashMap<Uuid, (&Arc<Node>, usize, i128)> = HashMap::new();
+    // The first token owns the range wrapping around the ring, starting at the last token.
+    let Some(&(mut previous, _)) = ring.last() else {
+        return Vec::new();
+    };
+    for &(token, node) in ring {
+        let mut width = token.value() as i128 - previous.value() as i128;
+        if width <= 0 {
+            width += 1 << 64;
+        }
+        let (_, tokens, total) = owned.entry(node.host_id).or_insert((node, 0, 0));
+        *tokens += 1;
+        *total += width;
+        previous = token;
+    }
+
+    let mut ownership = owned
+        .into_values()
+        .map(|(node, tokens, total)| TokenOwnershipDiagnostics {
+            host_id: node.host_id,
+            address: node.address.into_inner(),
+            tokens,
+            ownership: total as f64 / RING_SIZE,
+        })
+        .collect::<Vec<_>>();
+    ownership.sort_by_key(|node| node.address);
+    ownership
+}
+
+impl NodeDiagnostics {
+    pub(crate) fn new(node: &Node) -> Self {
+        Self {
+            host_id: node.host_id,
+            address: node.address.into_inner(),
+            datacenter: node.datacenter.clone(),
+            rack: node.rack.clone(),
+            enabled: node.is_enabled(),
+            connected: node.is_connected(),
+            connections_per_shard: node.connections_per_shard(),
+            recent_events: node
+                .recent_events()
+                .iter()
+                .map(ConnectionEventDiagnostics::new)
+                .collect(),
+        }
+    }
+}
+
+impl ConnectionEventDiagnostics {
+    fn new(event: &ConnectionEvent) -> Self {
+        Self {
+            time: event.time(),
+            event: format!("{:?}", event.kind()),
+        }
+    }
+}
+
+impl ExecutionProfileDiagnostics {
+    pub(crate) fn new(profile: &ExecutionProfile) -> Self {
+        Self {
+            request_timeout: profile.get_request_timeout(),
+            consistency: profile.get_consistency().to_string(),
+            serial_consistency: profile
+                .get_serial_consistency()
+                .map(|consistency| consistency.to_string()),
+            load_balancing_policy: profile.get_load_balancing_policy().name(),
+            retry_policy: format!("{:?}", profile.get_retry_policy()),
+            speculative_execution_policy: profile
+                .get_speculative_execution_policy()
+                .map(|policy| format!("{policy:?}")),
+            compression_enabled: profile.get_compression_enabled(),
+            priority: format!("{:?}", profile.get_priority()),
+        }
+    }
+}
+
+#[cfg(feature = "metrics")]
+impl MetricsDiagnostics {
+    pub(crate) fn new(metrics: &crate::observability::metrics::Metrics) -> Self {
+        Self {
+            queries_num: metrics.get_queries_num(),
+            errors_num: metrics.get_errors_num(),
+            queries_iter_num: metrics.get_queries_iter_num(),
+            errors_iter_num: metrics.get_errors_iter_num(),
+            retries_num: metrics.get_retries_num(),
+            latency_avg_ms: metrics.get_latency_avg_ms().ok(),
+            total_connections: metrics.get_total_connections(),
+            connection_timeouts: metrics.get_connection_timeouts(),
+            request_timeouts: metrics.get_request_timeouts(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use uuid::Uuid;
+
+    use super::token_ownership;
+    use crate::cluster::{Node, NodeAddr};
+    use crate::routing::Token;
+
+    fn node(port: u16) -> Arc<Node> {
+        Arc::new(Node::new_for_test(
+            Some(Uuid::new_v4()),
+            Some(NodeAddr::Translatable(([127, 0, 0, 1], port).into())),
+            None,
+            None,
+        ))
+    }
+
+    #[test]
+    fn test_token_ownership() {
+        assert!(token_ownership(&[]).is_empty());
+
+        let (a, b) = (node(1), node(2));
+        let single = token_ownership(&[(Token::new(42), &a)]);
+        assert_eq!(single.len(), 1);
+        assert_eq!(single[0].ownership, 1.0);
+
+        // `a` owns (i64::MIN / 2, i64::MAX / 2], `b` owns the rest of the ring,
+        // wrapping around from i64::MAX / 2.
+        let ownership = token_ownership(&[
+            (Token::new(i64::MIN / 2), &b),
+            (Token::new(0), &a),
+            (Token::new(i64::MAX / 2), &a),
+        ]);
+        assert_eq!(
+            ownership
+                .iter()
+                .map(|node| (node.address.port(), node.tokens))
+                .collect::<Vec<_>>(),
+            [(1, 2), (2, 1)]
+        );
+        assert!((ownership[0].ownership - 0.5).abs() < 1e-9);
+        assert!((ownership[1].ownership - 0.5).abs() < 1e-9);
+    }
+}
@@ -1,21 +1,32 @@
 use crate::cluster::node::Node;
 use crate::network::Connection;
+use crate::observability::instrumentation::TracingInstrumentation;
 use crate::response::query_result::QueryResult;
 use crate::routing::{Shard, Token};
+use crate::statement::StatementTags;
 use crate::utils::safe_format::IteratorSafeFormatExt;
 use itertools::Either;
-use scylla_cql::frame::response::result::{ColumnSpec, DeserializedMetadataAndRawRows};
+use scylla_cql::frame::response::result::{ColumnSpec, DeserializedMetadataAndRawRows, TableSpec};
+use scylla_cql::frame::types::Consistency;
 use scylla_cql::value::deser_cql_value;
 use std::borrow::Borrow;
 use std::fmt::Display;
+use std::net::SocketAddr;
 use std::sync::Arc;
 use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
-use tracing::trace_span;
+use tracing::{info_span, trace_span};
+use uuid::Uuid;
 
 pub(crate) struct RequestSpan {
     span: tracing::Span,
     speculative_executions: AtomicUsize,
+    // Name of the operation, as recorded in instrumented spans.
+    operation: &'static str,
+    // The span created if the session has TracingInstrumentation set,
+    // disabled otherwise.
+    instrumented_span: tracing::Span,
+    attempt_spans: bool,
 }
 
 impl RequestSpan {
@@ -33,11 +44,15 @@ impl RequestSpan {
             replicas = Empty,
             shard = Empty,
             speculative_executions = Empty,
+            tags = Empty,
         );
 
         Self {
             span,
             speculative_executions: 0.into(),
+            operation: "QUERY",
+            instrumented_span: tracing::Span::none(),
+            attempt_spans: false,
         }
     }
 
@@ -60,6 +75,7 @@ impl RequestSpan {
             replicas = Empty,
             shard = Empty,
             speculative_executions = Empty,
+            tags = Empty,
         );
 
         if let Some(partition_key) = partition_key {
@@ -77,6 +93,9 @@ impl RequestSpan {
         Self {
             span,
             speculative_executions: 0.into(),
+            operation: "EXECUTE",
+            instrumented_span: tracing::Span::none(),
+            attempt_spans: false,
         }
     }
 
@@ -93,14 +112,63 @@ impl RequestSpan {
             replicas = Empty,
             shard = Empty,
             speculative_executions = Empty,
+            tags = Empty,
         );
 
         Self {
             span,
             speculative_executions: 0.into(),
+            operation: "BATCH",
+            instrumented_span: tracing::Span::none(),
+            attempt_spans: false,
         }
     }
 
+    /// Creates the span defined by the session's [TracingInstrumentation], if it is set.
+    ///
+    /// The instrumented span is a child of the driver's own span, if that one is enabled,
+    /// and of `parent` otherwise.
+    pub(crate) fn with_instrumentation(
+        mut self,
+        instrumentation: Option<&TracingInstrumentation>,
+        statement: Option<&str>,
+        parent: &tracing::Span,
+    ) -> Self {
+        use tracing::field::Empty;
+
+        let Some(instrumentation) = instrumentation else {
+            return self;
+        };
+        let parent = if self.span.is_disabled() {
+            parent
+        } else {
+            &self.span
+        };
+        let span = info_span!(
+            parent: parent,
+            "scylla.request",
+            otel.name = self.operation,
+            otel.kind = "client",
+            otel.status_code = Empty,
+            otel.status_message = Empty,
+            db.system = "cassandra",
+            db.operation.name = self.operation,
+            db.query.text = Empty,
+            db.namespace = Empty,
+            db.collection.name = Empty,
+            db.cassandra.consistency_level = Empty,
+            db.cassandra.speculative_execution_count = Empty,
+            db.cassandra.tracing_id = Empty,
+        );
+        if let Some(statement) = statement.filter(|_| instrumentation.statement_text()) {
+            span.record("db.query.text", statement);
+        }
+
+        self.instrumented_span = span;
+        self.attempt_spans = instrumentation.attempt_spans();
+        self
+    }
+
     pub(crate) fn record_shard_id(&self, conn: &Connection) {
         if let Some(info) = conn.get_shard_info() {
             self.span.record("shard", info.shard);
@@ -142,10 +210,83 @@ impl RequestSpan {
         );
     }
 
+    pub(crate) fn record_tags(&self, tags: &StatementTags) {
+        if !tags.is_empty() {
+            self.span.record("tags", tracing::field::display(tags));
+        }
+    }
+
     pub(crate) fn record_request_size(&self, size: usize) {
         self.span.record("request_size", size);
     }
 
+    pub(crate) fn record_routing_info(
+        &self,
+        table: Option<&TableSpec<'_>>,
+        consistency: Consistency,
+    ) {
+        if self.instrumented_span.is_disabled() {
+            return;
+        }
+        if let Some(table) = table {
+            self.instrumented_span
+                .record("db.namespace", table.ks_name())
+                .record("db.collection.name", table.table_name());
+        }
+        self.instrumented_span.record(
+            "db.cassandra.consistency_level",
+            tracing::field::display(consistency),
+        );
+    }
+
+    pub(crate) fn record_outcome<E: Display + ?Sized>(&self, result: Result<(), &E>) {
+        record_outcome(&self.instrumented_span, result);
+    }
+
+    /// Records the id of the server-side tracing session of the request, if it was traced.
+    pub(crate) fn record_tracing_id(&self, tracing_id: Option<Uuid>) {
+        if let Some(tracing_id) = tracing_id {
+            self.instrumented_span.record(
+                "db.cassandra.tracing_id",
+                tracing::field::display(tracing_id),
+            );
+        }
+    }
+
+    /// Creates a span for an attempt of the request, if the session's [TracingInstrumentation]
+    /// enables them. Otherwise, the returned span is disabled.
+    pub(crate) fn new_attempt_span(
+        &self,
+        address: SocketAddr,
+        host_id: Uuid,
+        shard: Option<Shard>,
+        consistency: Consistency,
+    ) -> AttemptSpan {
+        use tracing::field::Empty;
+
+        if !self.attempt_spans || self.instrumented_span.is_disabled() {
+            return AttemptSpan(tracing::Span::none());
+        }
+        let span = info_span!(
+            parent: &self.instrumented_span,
+            "scylla.attempt",
+            otel.name = "scylla.attempt",
+            otel.kind = "client",
+            otel.status_code = Empty,
+            otel.status_message = Empty,
+            server.address = %address.ip(),
+            server.port = address.port(),
+            db.cassandra.coordinator.id = %host_id,
+            shard = Empty,
+            db.cassandra.consistency_level = %consistency,
+            retry_decision = Empty,
+        );
+        if let Some(shard) = shard {
+            span.record("shard", shard);
+        }
+        AttemptSpan(span)
+    }
+
     pub(crate) fn inc_speculative_executions(&self) {
         self.speculative_executions.fetch_add(1, Ordering::Relaxed);
     }
@@ -153,17 +294,61 @@ impl RequestSpan {
     pub(crate) fn span(&self) -> &tracing::Span {
         &self.span
     }
+
+    /// The span which should be entered while the request is executed:
+    /// the instrumented span if there is one, and the driver's own span otherwise.
+    pub(crate) fn entered_span(&self) -> &tracing::Span {
+        if self.instrumented_span.is_disabled() {
+            &self.span
+        } else {
+            &self.instrumented_span
+        }
+    }
 }
 
 impl Drop for RequestSpan {
     fn drop(&mut self) {
-        self.span.record(
-            "speculative_executions",
-            self.speculative_executions.load(Ordering::Relaxed),
+        let speculative_executions = self.speculative_executions.load(Ordering::Relaxed);
+        self.span
+            .record("speculative_executions", speculative_executions);
+        self.instrumented_span.record(
+            "db.cassandra.speculative_execution_count",
+            speculative_executions,
         );
     }
 }
 
+/// A span of a single attempt of a request, created if
+/// the session's [TracingInstrumentation] enables them.
+pub(crate) struct AttemptSpan(tracing::Span);
+
+impl AttemptSpan {
+    pub(crate) fn span(&self) -> &tracing::Span {
+        &self.0
+    }
+
+    pub(crate) fn record_retry_decision(&self, decision: &impl std::fmt::Debug) {
+        self.0
+            .record("retry_decision", tracing::field::debug(decision));
+    }
+
+    pub(crate) fn record_outcome<E: Display + ?Sized>(&self, result: Result<(), &E>) {
+        record_outcome(&self.0, result);
+    }
+}
+
+fn record_outcome<E: Display + ?Sized>(span: &tracing::Span, result: Result<(), &E>) {
+    match result {
+        Ok(()) => {
+            span.record("otel.status_code", "OK");
+        }
+        Err(error) => {
+            span.record("otel.status_code", "ERROR")
+                .record("otel.status_message", tracing::field::display(error));
+        }
+    }
+}
+
 fn partition_key_displayer<
     'ps,
     'res,
@@ -183,3 +368,166 @@ fn partition_key_displayer<
     })
     .safe_format(", ")
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    use scylla_cql::frame::response::result::TableSpec;
+    use scylla_cql::frame::types::Consistency;
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing_subscriber::Layer;
+    use tracing_subscriber::filter::LevelFilter;
+    use tracing_subscriber::layer::{Context, SubscriberExt};
+    use uuid::Uuid;
+
+    use super::RequestSpan;
+    use crate::observability::instrumentation::TracingInstrumentation;
+    use crate::policies::retry::RetryDecision;
+
+    #[derive(Default, Debug)]
+    struct RecordedSpan {
+        parent: Option<&'static str>,
+        fields: HashMap<String, String>,
+    }
+
+    impl Visit for RecordedSpan {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.fields
+                .insert(field.name().to_owned(), value.to_owned());
+        }
+
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.fields
+                .insert(field.name().to_owned(), format!("{value:?}"));
+        }
+    }
+
+    /// Records the fields of spans by their names.
+    #[derive(Clone, Default)]
+    struct SpanRecorder(Arc<Mutex<HashMap<&'static str, RecordedSpan>>>);
+
+    impl<S> Layer<S> for SpanRecorder
+    where
+        S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+    {
+        fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+            let mut span = RecordedSpan {
+                parent: ctx
+                    .span(id)
+                    .and_then(|span| span.parent())
+                    .map(|parent| parent.name()),
+                ..Default::default()
+            };
+            attrs.record(&mut span);
+            self.0.lock().unwrap().insert(attrs.metadata().name(), span);
+        }
+
+        fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+            let name = ctx.span(id).unwrap().name();
+            values.record(self.0.lock().unwrap().get_mut(name).unwrap());
+        }
+    }
+
+    #[test]
+    fn instrumented_spans_follow_opentelemetry_conventions() {
+        let recorder = SpanRecorder::default();
+        let subscriber =
+            tracing_subscriber::registry().with(recorder.clone().with_filter(LevelFilter::INFO));
+        let statement = "SELECT * FROM ks.t";
+
+        tracing::subscriber::with_default(subscriber, || {
+            let _app_span = tracing::info_span!("app").entered();
+
+            let request_span = RequestSpan::new_query(statement).with_instrumentation(
+                Some(&TracingInstrumentation::new()),
+                Some(statement),
+                &tracing::Span::current(),
+            );
+            request_span
+                .record_routing_info(Some(&TableSpec::borrowed("ks", "t")), Consistency::Quorum);
+            let attempt_span = request_span.new_attempt_span(
+                "127.0.0.1:9042".parse().unwrap(),
+                Uuid::nil(),
+                Some(3),
+                Consistency::Quorum,
+            );
+            attempt_span.record_outcome(Err("Overloaded"));
+            attempt_span.record_retry_decision(&RetryDecision::DontRetry);
+            request_span.inc_speculative_executions();
+            request_span.record_outcome::<str>(Ok(()));
+            request_span.record_tracing_id(Some(Uuid::nil()));
+        });
+
+        let spans = std::mem::take(&mut *recorder.0.lock().unwrap());
+        // The driver's own spans are at TRACE level, so the instrumented ones
+        // are seen as children of the application's span.
+        let request = &spans["scylla.request"];
+        assert_eq!(request.parent, Some("app"));
+        for (field, value) in [
+            ("otel.name", "QUERY"),
+            ("otel.kind", "client"),
+            ("otel.status_code", "OK"),
+            ("db.operation.name", "QUERY"),
+            ("db.namespace", "ks"),
+            ("db.collection.name", "t"),
+            ("db.cassandra.consistency_level", "Quorum"),
+            ("db.cassandra.speculative_execution_count", "1"),
+            ("db.cassandra.tracing_id", &Uuid::nil().to_string()),
+        ] {
+            assert_eq!(request.fields[field], value, "{field}");
+        }
+        // The statement text is not recorded by default.
+        assert!(!request.fields.contains_key("db.query.text"));
+
+        let attempt = &spans["scylla.attempt"];
+        assert_eq!(attempt.parent, Some("scylla.request"));
+        for (field, value) in [
+            ("otel.status_code", "ERROR"),
+            ("otel.status_message", "Overloaded"),
+            ("server.address", "127.0.0.1"),
+            ("server.port", "9042"),
+            ("db.cassandra.coordinator.id", &Uuid::nil().to_string()),
+            ("shard", "3"),
+            ("retry_decision", "DontRetry"),
+        ] {
+            assert_eq!(attempt.fields[field], value, "{field}");
+        }
+
+        let subscriber =
+            tracing_subscriber::registry().with(recorder.clone().with_filter(LevelFilter::INFO));
+        tracing::subscriber::with_default(subscriber, || {
+            let instrumentation = TracingInstrumentation::new()
+                .with_statement_text(true)
+                .with_attempt_spans(false);
+            let request_span = RequestSpan::new_batch().with_instrumentation(
+                Some(&instrumentation),
+                Some(statement),
+                &tracing::Span::none(),
+            );
+            let attempt_span = request_span.new_attempt_span(
+                "127.0.0.1:9042".parse().unwrap(),
+                Uuid::nil(),
+                None,
+                Consistency::One,
+            );
+            assert!(attempt_span.span().is_disabled());
+
+            // Without instrumentation, no spans are created at INFO level.
+            let _uninstrumented = RequestSpan::new_query(statement).with_instrumentation(
+                None,
+                Some(statement),
+                &tracing::Span::none(),
+            );
+        });
+
+        let spans = recorder.0.lock().unwrap();
+        assert_eq!(spans.len(), 1);
+        let request = &spans["scylla.request"];
+        assert_eq!(request.parent, None);
+        assert_eq!(request.fields["otel.name"], "BATCH");
+        assert_eq!(request.fields["db.query.text"], statement);
+    }
+}
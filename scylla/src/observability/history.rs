@@ -1,16 +1,27 @@
 //! Collecting history of request executions - retries, speculative, etc.
+//!
+//! A [HistoryCollector] set on a statement collects the history of all requests
+//! executed with that statement. A [RecentHistoryCollector] set with
+//! [SessionBuilder::session_history](crate::client::session_builder::SessionBuilder::session_history)
+//! collects the history of all requests executed by a session, but keeps only the most recent ones,
+//! so it can be enabled permanently in order to dump the history of requests preceding an error.
+//!
+//! With the `serde` feature enabled, [StructuredHistory] and [DetailedHistory] implement `serde::Serialize`,
+//! so it can be dumped e.g. as JSON or exposed over an admin endpoint.
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, HashMap, VecDeque},
     fmt::{Debug, Display},
     net::SocketAddr,
     sync::Mutex,
-    time::SystemTime,
+    time::{Duration, SystemTime},
 };
 
 use crate::errors::{RequestAttemptError, RequestError};
 use crate::policies::retry::RetryDecision;
+use crate::statement::StatementTags;
 use chrono::{DateTime, Utc};
 
+use crate::observability::log_targets;
 use tracing::warn;
 
 /// Id of a single request, i.e. a single call to Session::{query,execute}_{unpaged,single_page}/etc.
@@ -39,6 +50,22 @@ pub trait HistoryListener: Debug + Send + Sync {
     /// Log that a request has started on request start - right after the call to Session::{query,execute}_*/batch.
     fn log_request_start(&self) -> RequestId;
 
+    /// Log tags of the request's statement - called right after `log_request_start`,
+    /// if the statement has any tags.
+    ///
+    /// The default implementation ignores the tags.
+    fn log_request_tags(&self, request_id: RequestId, tags: &StatementTags) {
+        let _ = (request_id, tags);
+    }
+
+    /// Log the text of the request's statement - called right after `log_request_start`.
+    /// For batches, texts of all the batch's statements are separated by `; `.
+    ///
+    /// The default implementation ignores the statement.
+    fn log_request_statement(&self, request_id: RequestId, statement: &str) {
+        let _ = (request_id, statement);
+    }
+
     /// Log that request was successful - called right before returning the result from Session::query_*, execute_*, etc.
     fn log_request_success(&self, request_id: RequestId);
 
@@ -48,6 +75,15 @@ pub trait HistoryListener: Debug + Send + Sync {
     /// Log that a new speculative fiber has started.
     fn log_new_speculative_fiber(&self, request_id: RequestId) -> SpeculativeId;
 
+    /// Log that a fiber of the request was cancelled before finishing - because another fiber
+    /// completed the request first, or the request timed out. `speculative_id` is `None`
+    /// for the non-speculative fiber. The attempt running in the fiber, if any, is abandoned.
+    ///
+    /// The default implementation ignores the cancellation.
+    fn log_fiber_cancelled(&self, request_id: RequestId, speculative_id: Option<SpeculativeId>) {
+        let _ = (request_id, speculative_id);
+    }
+
     /// Log that an attempt has started - request has been sent on some Connection, now awaiting for an answer.
     fn log_attempt_start(
         &self,
@@ -81,7 +117,7 @@ pub struct HistoryCollector {
 /// Data collected by [HistoryCollector].
 #[derive(Debug, Clone)]
 pub struct HistoryCollectorData {
-    events: Vec<(HistoryEvent, TimePoint)>,
+    events: Vec<(CollectedEvent, TimePoint)>,
     next_request_id: RequestId,
     next_speculative_fiber_id: SpeculativeId,
     next_attempt_id: AttemptId,
@@ -109,6 +145,27 @@ pub enum HistoryEvent {
     AttemptError(AttemptId, RequestAttemptError, RetryDecision),
 }
 
+/// Events stored by the collectors. Apart from [HistoryEvent]s, they include
+/// events carrying additional data about requests, which is exposed only
+/// through the accessors of [StructuredHistory] parts.
+#[derive(Debug, Clone)]
+enum CollectedEvent {
+    Event(HistoryEvent),
+    /// Request with [RequestId] has been tagged with [StatementTags].
+    RequestTags(RequestId, StatementTags),
+    /// Request with [RequestId] executes a statement with the given text.
+    RequestStatement(RequestId, String),
+    /// A fiber of a request with [RequestId] has been cancelled - the speculative one
+    /// with [SpeculativeId], or the non-speculative one if it is `None`.
+    FiberCancelled(RequestId, Option<SpeculativeId>),
+}
+
+impl From<HistoryEvent> for CollectedEvent {
+    fn from(event: HistoryEvent) -> CollectedEvent {
+        CollectedEvent::Event(event)
+    }
+}
+
 impl HistoryCollectorData {
     fn new() -> HistoryCollectorData {
         HistoryCollectorData {
@@ -119,9 +176,9 @@ impl HistoryCollectorData {
         }
     }
 
-    fn add_event(&mut self, event: HistoryEvent) {
+    fn add_event(&mut self, event: impl Into<CollectedEvent>) {
         let event_time: TimePoint = SystemTime::now().into();
-        self.events.push((event, event_time));
+        self.events.push((event.into(), event_time));
     }
 }
 
@@ -168,6 +225,16 @@ impl HistoryCollector {
         StructuredHistory::from(&self.take_collected())
     }
 
+    /// Clone the collected events and convert them to DetailedHistory.
+    pub fn clone_detailed_history(&self) -> DetailedHistory {
+        DetailedHistory::from(&self.clone_collected())
+    }
+
+    /// Take the collected events out, just like in `take_collected` and convert them to DetailedHistory.
+    pub fn take_detailed_history(&self) -> DetailedHistory {
+        DetailedHistory::from(&self.take_collected())
+    }
+
     /// Lock the data mutex and perform an operation on it.
     fn do_with_data<OpRetType>(
         &self,
@@ -178,7 +245,11 @@ impl HistoryCollector {
             Err(poison_error) => {
                 // Avoid panicking on poisoned mutex - HistoryCollector isn't that important.
                 // Print a warning and do the operation on dummy data so that the code compiles.
-                warn!("HistoryCollector - mutex poisoned! Error: {}", poison_error);
+                warn!(
+                    target: log_targets::REQUEST,
+                    "HistoryCollector - mutex poisoned! Error: {}",
+                    poison_error,
+                );
                 let mut dummy_data: HistoryCollectorData = HistoryCollectorData::default();
                 do_fn(&mut dummy_data)
             }
@@ -196,6 +267,21 @@ impl HistoryListener for HistoryCollector {
         })
     }
 
+    fn log_request_tags(&self, request_id: RequestId, tags: &StatementTags) {
+        self.do_with_data(|data| {
+            data.add_event(CollectedEvent::RequestTags(request_id, tags.clone()))
+        })
+    }
+
+    fn log_request_statement(&self, request_id: RequestId, statement: &str) {
+        self.do_with_data(|data| {
+            data.add_event(CollectedEvent::RequestStatement(
+                request_id,
+                statement.to_owned(),
+            ))
+        })
+    }
+
     fn log_request_success(&self, request_id: RequestId) {
         self.do_with_data(|data| {
             data.add_event(HistoryEvent::RequestSuccess(request_id));
@@ -220,6 +306,12 @@ impl HistoryListener for HistoryCollector {
         })
     }
 
+    fn log_fiber_cancelled(&self, request_id: RequestId, speculative_id: Option<SpeculativeId>) {
+        self.do_with_data(|data| {
+            data.add_event(CollectedEvent::FiberCancelled(request_id, speculative_id))
+        })
+    }
+
     fn log_attempt_start(
         &self,
         request_id: RequestId,
@@ -259,18 +351,409 @@ impl HistoryListener for HistoryCollector {
     }
 }
 
+/// RecentHistoryCollector can be used as [HistoryListener] to collect the history
+/// of the most recent requests, in a ring buffer of bounded size.
+///
+/// Unlike [HistoryCollector], it does not grow indefinitely, so it can be set
+/// for the whole session with
+/// [SessionBuilder::session_history](crate::client::session_builder::SessionBuilder::session_history)
+/// and kept enabled permanently.
+///
+/// The history of a request is kept until `capacity` requests finish after it.
+/// Additionally, at most `capacity` requests are tracked while they are running - if more
+/// requests are running at once (or requests are cancelled before finishing), the oldest
+/// ones are moved to the ring buffer as unfinished. Events concerning them which come
+/// afterwards are ignored.
+#[derive(Debug)]
+pub struct RecentHistoryCollector {
+    capacity: usize,
+    data: Mutex<RecentHistoryData>,
+}
+
+#[derive(Debug, Default)]
+struct RecentHistoryData {
+    next_request_id: usize,
+    next_speculative_fiber_id: usize,
+    next_attempt_id: usize,
+    // Events of requests which have not finished yet.
+    running: BTreeMap<RequestId, Vec<(CollectedEvent, TimePoint)>>,
+    // Requests of attempts and speculative fibers of running requests.
+    attempts: HashMap<AttemptId, RequestId>,
+    fibers: HashMap<SpeculativeId, RequestId>,
+    finished: VecDeque<DetailedRequestHistory>,
+}
+
+impl RecentHistoryData {
+    fn add_event(&mut self, request_id: RequestId, event: impl Into<CollectedEvent>) {
+        if let Some(events) = self.running.get_mut(&request_id) {
+            events.push((event.into(), SystemTime::now().into()));
+        }
+    }
+
+    /// Removes the request from running ones and converts its events to [DetailedRequestHistory].
+    fn take_running(&mut self, request_id: RequestId) -> Option<DetailedRequestHistory> {
+        let events = self.running.remove(&request_id)?;
+        for (event, _) in &events {
+            match event {
+                CollectedEvent::Event(HistoryEvent::NewAttempt(attempt_id, ..)) => {
+                    self.attempts.remove(attempt_id);
+                }
+                CollectedEvent::Event(HistoryEvent::NewSpeculativeFiber(speculative_id, _)) => {
+                    self.fibers.remove(speculative_id);
+                }
+                _ => {}
+            }
+        }
+
+        let data = HistoryCollectorData {
+            events,
+            ..Default::default()
+        };
+        DetailedHistory::from(&data).requests.pop()
+    }
+
+    fn push_finished(&mut self, capacity: usize, request: DetailedRequestHistory) {
+        if self.finished.len() >= capacity {
+            self.finished.pop_front();
+        }
+        if capacity > 0 {
+            self.finished.push_back(request);
+        }
+    }
+}
+
+impl RecentHistoryCollector {
+    /// Creates a new RecentHistoryCollector keeping the history of at most `capacity` finished requests.
+    pub fn new(capacity: usize) -> RecentHistoryCollector {
+        RecentHistoryCollector {
+            capacity,
+            data: Mutex::new(RecentHistoryData::default()),
+        }
+    }
+
+    /// Returns the maximum number of finished requests whose history is kept.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Converts the history of kept requests to StructuredHistory, ordered by start time.
+    /// It includes the requests which are still running.
+    pub fn clone_structured_history(&self) -> StructuredHistory {
+        StructuredHistory::from(self.clone_detailed_history())
+    }
+
+    /// Converts the history of kept requests to DetailedHistory, ordered by start time.
+    /// It includes the requests which are still running.
+    pub fn clone_detailed_history(&self) -> DetailedHistory {
+        let (mut requests, running_events) = self.do_with_data(|data| {
+            let finished: Vec<DetailedRequestHistory> = data.finished.iter().cloned().collect();
+            let running_events: Vec<(CollectedEvent, TimePoint)> =
+                data.running.values().flatten().cloned().collect();
+            (finished, running_events)
+        });
+
+        let running = HistoryCollectorData {
+            events: running_events,
+            ..Default::default()
+        };
+        requests.extend(DetailedHistory::from(&running).requests);
+        requests.sort_by_key(|request| request.history.start_time);
+        DetailedHistory { requests }
+    }
+
+    /// Forgets the history of all kept requests, including the running ones.
+    pub fn clear(&self) {
+        self.do_with_data(|data| {
+            data.running.clear();
+            data.attempts.clear();
+            data.fibers.clear();
+            data.finished.clear();
+        })
+    }
+
+    fn finish_request(&self, request_id: RequestId, event: HistoryEvent) {
+        self.do_with_data(|data| {
+            data.add_event(request_id, event);
+            if let Some(request) = data.take_running(request_id) {
+                data.push_finished(self.capacity, request);
+            }
+        })
+    }
+
+    fn do_with_data<OpRetType>(
+        &self,
+        do_fn: impl FnOnce(&mut RecentHistoryData) -> OpRetType,
+    ) -> OpRetType {
+        match self.data.lock() {
+            Ok(mut data) => do_fn(&mut data),
+            Err(poison_error) => {
+                // Same as in HistoryCollector - history isn't important enough to panic.
+                warn!(
+                    target: log_targets::REQUEST,
+                    "RecentHistoryCollector - mutex poisoned! Error: {}",
+                    poison_error
+                );
+                do_fn(&mut RecentHistoryData::default())
+            }
+        }
+    }
+}
+
+impl HistoryListener for RecentHistoryCollector {
+    fn log_request_start(&self) -> RequestId {
+        self.do_with_data(|data| {
+            let request_id = RequestId(data.next_request_id);
+            data.next_request_id += 1;
+
+            if data.running.len() >= self.capacity {
+                if let Some(&oldest) = data.running.keys().next() {
+                    if let Some(request) = data.take_running(oldest) {
+                        data.push_finished(self.capacity, request);
+                    }
+                }
+            }
+            if self.capacity > 0 {
+                data.running.insert(request_id, Vec::new());
+                data.add_event(request_id, HistoryEvent::NewRequest(request_id));
+            }
+            request_id
+        })
+    }
+
+    fn log_request_tags(&self, request_id: RequestId, tags: &StatementTags) {
+        self.do_with_data(|data| {
+            data.add_event(
+                request_id,
+                CollectedEvent::RequestTags(request_id, tags.clone()),
+            )
+        })
+    }
+
+    fn log_request_statement(&self, request_id: RequestId, statement: &str) {
+        self.do_with_data(|data| {
+            data.add_event(
+                request_id,
+                CollectedEvent::RequestStatement(request_id, statement.to_owned()),
+            )
+        })
+    }
+
+    fn log_request_success(&self, request_id: RequestId) {
+        self.finish_request(request_id, HistoryEvent::RequestSuccess(request_id));
+    }
+
+    fn log_request_error(&self, request_id: RequestId, error: &RequestError) {
+        self.finish_request(
+            request_id,
+            HistoryEvent::RequestError(request_id, error.clone()),
+        );
+    }
+
+    fn log_new_speculative_fiber(&self, request_id: RequestId) -> SpeculativeId {
+        self.do_with_data(|data| {
+            let speculative_id = SpeculativeId(data.next_speculative_fiber_id);
+            data.next_speculative_fiber_id += 1;
+            if data.running.contains_key(&request_id) {
+                data.fibers.insert(speculative_id, request_id);
+                data.add_event(
+                    request_id,
+                    HistoryEvent::NewSpeculativeFiber(speculative_id, request_id),
+                );
+            }
+            speculative_id
+        })
+    }
+
+    fn log_fiber_cancelled(&self, request_id: RequestId, speculative_id: Option<SpeculativeId>) {
+        self.do_with_data(|data| {
+            data.add_event(
+                request_id,
+                CollectedEvent::FiberCancelled(request_id, speculative_id),
+            )
+        })
+    }
+
+    fn log_attempt_start(
+        &self,
+        request_id: RequestId,
+        speculative_id: Option<SpeculativeId>,
+        node_addr: SocketAddr,
+    ) -> AttemptId {
+        self.do_with_data(|data| {
+            let attempt_id = AttemptId(data.next_attempt_id);
+            data.next_attempt_id += 1;
+            if data.running.contains_key(&request_id) {
+                data.attempts.insert(attempt_id, request_id);
+                data.add_event(
+                    request_id,
+                    HistoryEvent::NewAttempt(attempt_id, request_id, speculative_id, node_addr),
+                );
+            }
+            attempt_id
+        })
+    }
+
+    fn log_attempt_success(&self, attempt_id: AttemptId) {
+        self.do_with_data(|data| {
+            if let Some(&request_id) = data.attempts.get(&attempt_id) {
+                data.add_event(request_id, HistoryEvent::AttemptSuccess(attempt_id));
+            }
+        })
+    }
+
+    fn log_attempt_error(
+        &self,
+        attempt_id: AttemptId,
+        error: &RequestAttemptError,
+        retry_decision: &RetryDecision,
+    ) {
+        self.do_with_data(|data| {
+            if let Some(&request_id) = data.attempts.get(&attempt_id) {
+                data.add_event(
+                    request_id,
+                    HistoryEvent::AttemptError(attempt_id, error.clone(), retry_decision.clone()),
+                );
+            }
+        })
+    }
+}
+
 /// Structured representation of requests history.\
 /// [HistoryCollector] collects raw events which later can be converted
 /// to this pretty representation.\
 /// It has a `Display` impl which can be used for printing pretty request history.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct StructuredHistory {
     /// List of requests with their history.
     pub requests: Vec<RequestHistory>,
 }
 
+/// [StructuredHistory] which also includes the details of requests which are not
+/// part of [RequestHistory], such as the tags and texts of their statements
+/// and the cancellation times of their fibers.\
+/// It can be created by calling `clone_detailed_history()` on a history collector.
+/// Like [StructuredHistory], it has a `Display` impl which can be used for printing
+/// pretty request history.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub struct DetailedHistory {
+    /// List of requests with their history and details.
+    pub requests: Vec<DetailedRequestHistory>,
+}
+
+/// History of a single request together with its details.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub struct DetailedRequestHistory {
+    /// History of the request.
+    pub history: RequestHistory,
+    /// Tags of the request's statement.
+    pub tags: StatementTags,
+    /// Text of the request's statement, if it was logged.
+    pub statement: Option<String>,
+    /// Time when the non-speculative fiber was cancelled, because another fiber completed
+    /// the request first or the request timed out. `None` if the fiber finished by itself.
+    pub non_speculative_fiber_cancel_time: Option<TimePoint>,
+    /// Times when the speculative fibers were cancelled, in the order of
+    /// [RequestHistory::speculative_fibers].
+    pub speculative_fibers_cancel_times: Vec<Option<TimePoint>>,
+}
+
+impl DetailedHistory {
+    /// Returns the requests matching the filter.
+    pub fn matching<'a>(
+        &'a self,
+        filter: &HistoryFilter,
+    ) -> impl Iterator<Item = &'a DetailedRequestHistory> {
+        self.requests
+            .iter()
+            .filter(|request| filter.matches(request))
+    }
+}
+
+impl From<DetailedHistory> for StructuredHistory {
+    fn from(history: DetailedHistory) -> StructuredHistory {
+        StructuredHistory {
+            requests: history
+                .requests
+                .into_iter()
+                .map(|request| request.history)
+                .collect(),
+        }
+    }
+}
+
+/// Selects requests from [DetailedHistory] by their statements and start times.
+/// An empty filter matches all requests.
+///
+/// # Example
+/// ```
+/// # use scylla::observability::history::{DetailedHistory, HistoryFilter};
+/// # fn example(history: &DetailedHistory) {
+/// let last_minute = chrono::Utc::now() - chrono::Duration::minutes(1);
+/// let filter = HistoryFilter::new()
+///     .with_statement_containing("users")
+///     .since(last_minute);
+/// for request in history.matching(&filter) {
+///     println!("{:?}: {:?}", request.statement, request.history.result);
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct HistoryFilter {
+    statement: Option<String>,
+    since: Option<TimePoint>,
+    until: Option<TimePoint>,
+}
+
+impl HistoryFilter {
+    /// Creates a filter matching all requests.
+    pub fn new() -> HistoryFilter {
+        HistoryFilter::default()
+    }
+
+    /// Matches only requests whose statement text contains `fragment`.
+    /// Requests without a logged statement do not match.
+    pub fn with_statement_containing(mut self, fragment: impl Into<String>) -> HistoryFilter {
+        self.statement = Some(fragment.into());
+        self
+    }
+
+    /// Matches only requests started at or after `time`.
+    pub fn since(mut self, time: TimePoint) -> HistoryFilter {
+        self.since = Some(time);
+        self
+    }
+
+    /// Matches only requests started before `time`.
+    pub fn until(mut self, time: TimePoint) -> HistoryFilter {
+        self.until = Some(time);
+        self
+    }
+
+    /// Checks whether the request matches the filter.
+    pub fn matches(&self, request: &DetailedRequestHistory) -> bool {
+        let statement_matches = match (&self.statement, &request.statement) {
+            (None, _) => true,
+            (Some(fragment), Some(statement)) => statement.contains(fragment.as_str()),
+            (Some(_), None) => false,
+        };
+        statement_matches
+            && self
+                .since
+                .is_none_or(|since| request.history.start_time >= since)
+            && self
+                .until
+                .is_none_or(|until| request.history.start_time < until)
+    }
+}
+
 /// History of a single request, including its speculative fibers and attempts.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct RequestHistory {
     /// Time when the request started.
     pub start_time: TimePoint,
@@ -283,18 +766,74 @@ pub struct RequestHistory {
     pub result: Option<RequestHistoryResult>,
 }
 
+impl RequestHistory {
+    /// Returns the attempts of all fibers of the request, the non-speculative fiber first.
+    pub fn attempts(&self) -> impl Iterator<Item = &AttemptHistory> {
+        std::iter::once(&self.non_speculative_fiber)
+            .chain(&self.speculative_fibers)
+            .flat_map(|fiber| &fiber.attempts)
+    }
+
+    /// Returns the decisions made by the retry policy after failed attempts
+    /// of all fibers of the request, together with the errors that caused them.
+    ///
+    /// Along with the number of speculative fibers, they explain why the request
+    /// resulted in more than one attempt.
+    pub fn retry_decisions(&self) -> impl Iterator<Item = (&RequestAttemptError, &RetryDecision)> {
+        self.attempts().filter_map(|attempt| match &attempt.result {
+            Some(AttemptResult::Error(_, error, decision)) => Some((error, decision)),
+            Some(AttemptResult::Success(_)) | None => None,
+        })
+    }
+
+    /// Returns the time when the request finished, if it has finished.
+    pub fn end_time(&self) -> Option<TimePoint> {
+        match self.result.as_ref()? {
+            RequestHistoryResult::Success(time) | RequestHistoryResult::Error(time, _) => {
+                Some(*time)
+            }
+        }
+    }
+
+    /// Returns the time between the start and the end of the request, if it has finished.
+    pub fn latency(&self) -> Option<Duration> {
+        elapsed_between(self.start_time, self.end_time()?)
+    }
+
+    /// Returns the attempt which completed a successful request - the successful one,
+    /// or the one whose error was ignored by the retry policy.
+    /// With speculative execution, it tells which fiber won the race.
+    pub fn winning_attempt(&self) -> Option<&AttemptHistory> {
+        if !matches!(self.result, Some(RequestHistoryResult::Success(_))) {
+            return None;
+        }
+        self.attempts().find(|attempt| match &attempt.result {
+            Some(AttemptResult::Success(_)) => true,
+            Some(AttemptResult::Error(_, _, decision)) => {
+                matches!(decision, RetryDecision::IgnoreWriteError)
+            }
+            None => false,
+        })
+    }
+}
+
 /// Result of a request execution, either successful or with an error,
 /// including the time when it finished execution.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum RequestHistoryResult {
     /// Request was successful, with the time it finished.
     Success(TimePoint),
     /// Request ended with an error, with the time it finished and the error itself.
-    Error(TimePoint, RequestError),
+    Error(
+        TimePoint,
+        #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_display"))] RequestError,
+    ),
 }
 
 /// History of a speculative fiber, which includes its start time and attempts made within it.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct FiberHistory {
     /// Time when the speculative fiber started.
     pub start_time: TimePoint,
@@ -305,6 +844,7 @@ pub struct FiberHistory {
 /// History of a single attempt, including the time it was sent, the node it was sent to,
 /// and the result of the attempt (success or error).
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct AttemptHistory {
     /// Time when the attempt was sent.
     pub send_time: TimePoint,
@@ -316,25 +856,87 @@ pub struct AttemptHistory {
     pub result: Option<AttemptResult>,
 }
 
+impl AttemptHistory {
+    /// Returns the time when the attempt finished, if it has finished.
+    pub fn end_time(&self) -> Option<TimePoint> {
+        match self.result.as_ref()? {
+            AttemptResult::Success(time) | AttemptResult::Error(time, _, _) => Some(*time),
+        }
+    }
+
+    /// Returns the time between sending the attempt and receiving its response, if it has finished.
+    pub fn latency(&self) -> Option<Duration> {
+        elapsed_between(self.send_time, self.end_time()?)
+    }
+}
+
+/// Returns the time elapsed between the two time points, or `None` if the clock went backwards.
+fn elapsed_between(start: TimePoint, end: TimePoint) -> Option<Duration> {
+    (end - start).to_std().ok()
+}
+
 /// Result of an attempt execution, either successful or with an error,
 /// including the time when it finished execution and the retry decision made.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum AttemptResult {
     /// Attempt was successful, with the time it finished.
     Success(TimePoint),
     /// Attempt ended with an error, with the time it finished,
     /// the error itself, and the retry decision made in response to the error.
-    Error(TimePoint, RequestAttemptError, RetryDecision),
+    Error(
+        TimePoint,
+        #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_display"))]
+        RequestAttemptError,
+        #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_debug"))] RetryDecision,
+    ),
 }
 
 impl From<&HistoryCollectorData> for StructuredHistory {
     fn from(data: &HistoryCollectorData) -> StructuredHistory {
+        StructuredHistory::from(DetailedHistory::from(data))
+    }
+}
+
+impl From<&HistoryCollectorData> for DetailedHistory {
+    fn from(data: &HistoryCollectorData) -> DetailedHistory {
         let mut attempts: BTreeMap<AttemptId, AttemptHistory> = BTreeMap::new();
-        let mut requests: BTreeMap<RequestId, RequestHistory> = BTreeMap::new();
+        let mut requests: BTreeMap<RequestId, DetailedRequestHistory> = BTreeMap::new();
         let mut fibers: BTreeMap<SpeculativeId, FiberHistory> = BTreeMap::new();
+        let mut fibers_cancel_times: HashMap<SpeculativeId, TimePoint> = HashMap::new();
 
         // Collect basic data about requests, attempts and speculative fibers
         for (event, event_time) in &data.events {
+            let event = match event {
+                CollectedEvent::Event(event) => event,
+                CollectedEvent::RequestTags(request_id, tags) => {
+                    if let Some(request) = requests.get_mut(request_id) {
+                        request.tags = tags.clone();
+                    }
+                    continue;
+                }
+                CollectedEvent::RequestStatement(request_id, statement) => {
+                    if let Some(request) = requests.get_mut(request_id) {
+                        request.statement = Some(statement.clone());
+                    }
+                    continue;
+                }
+                CollectedEvent::FiberCancelled(request_id, speculative_id) => {
+                    match speculative_id {
+                        Some(spec_id) => {
+                            if fibers.contains_key(spec_id) {
+                                fibers_cancel_times.insert(*spec_id, *event_time);
+                            }
+                        }
+                        None => {
+                            if let Some(request) = requests.get_mut(request_id) {
+                                request.non_speculative_fiber_cancel_time = Some(*event_time);
+                            }
+                        }
+                    }
+                    continue;
+                }
+            };
             match event {
                 HistoryEvent::NewAttempt(attempt_id, _, _, node_addr) => {
                     attempts.insert(
@@ -356,6 +958,7 @@ impl From<&HistoryCollectorData> for StructuredHistory {
                         Some(attempt) => {
                             if attempt.result.is_some() {
                                 warn!(
+                                    target: log_targets::REQUEST,
                                     "StructuredHistory - attempt with id {:?} has multiple results",
                                     attempt_id
                                 );
@@ -367,6 +970,7 @@ impl From<&HistoryCollectorData> for StructuredHistory {
                             ));
                         }
                         None => warn!(
+                            target: log_targets::REQUEST,
                             "StructuredHistory - attempt with id {:?} finished with an error but not created",
                             attempt_id
                         ),
@@ -375,25 +979,31 @@ impl From<&HistoryCollectorData> for StructuredHistory {
                 HistoryEvent::NewRequest(request_id) => {
                     requests.insert(
                         *request_id,
-                        RequestHistory {
-                            start_time: *event_time,
-                            non_speculative_fiber: FiberHistory {
+                        DetailedRequestHistory {
+                            history: RequestHistory {
                                 start_time: *event_time,
-                                attempts: Vec::new(),
+                                non_speculative_fiber: FiberHistory {
+                                    start_time: *event_time,
+                                    attempts: Vec::new(),
+                                },
+                                speculative_fibers: Vec::new(),
+                                result: None,
                             },
-                            speculative_fibers: Vec::new(),
-                            result: None,
+                            tags: StatementTags::default(),
+                            statement: None,
+                            non_speculative_fiber_cancel_time: None,
+                            speculative_fibers_cancel_times: Vec::new(),
                         },
                     );
                 }
                 HistoryEvent::RequestSuccess(request_id) => {
                     if let Some(request) = requests.get_mut(request_id) {
-                        request.result = Some(RequestHistoryResult::Success(*event_time));
+                        request.history.result = Some(RequestHistoryResult::Success(*event_time));
                     }
                 }
                 HistoryEvent::RequestError(request_id, error) => {
                     if let Some(request) = requests.get_mut(request_id) {
-                        request.result =
+                        request.history.result =
                             Some(RequestHistoryResult::Error(*event_time, error.clone()));
                     }
                 }
@@ -411,7 +1021,13 @@ impl From<&HistoryCollectorData> for StructuredHistory {
 
         // Move attempts to their speculative fibers
         for (event, _) in &data.events {
-            if let HistoryEvent::NewAttempt(attempt_id, request_id, speculative_id, _) = event {
+            if let CollectedEvent::Event(HistoryEvent::NewAttempt(
+                attempt_id,
+                request_id,
+                speculative_id,
+                _,
+            )) = event
+            {
                 if let Some(attempt) = attempts.remove(attempt_id) {
                     match speculative_id {
                         Some(spec_id) => {
@@ -421,7 +1037,7 @@ impl From<&HistoryCollectorData> for StructuredHistory {
                         }
                         None => {
                             if let Some(request) = requests.get_mut(request_id) {
-                                request.non_speculative_fiber.attempts.push(attempt);
+                                request.history.non_speculative_fiber.attempts.push(attempt);
                             }
                         }
                     }
@@ -431,16 +1047,23 @@ impl From<&HistoryCollectorData> for StructuredHistory {
 
         // Move speculative fibers to their requests
         for (event, _) in &data.events {
-            if let HistoryEvent::NewSpeculativeFiber(speculative_id, request_id) = event {
+            if let CollectedEvent::Event(HistoryEvent::NewSpeculativeFiber(
+                speculative_id,
+                request_id,
+            )) = event
+            {
                 if let Some(fiber) = fibers.remove(speculative_id) {
                     if let Some(request) = requests.get_mut(request_id) {
-                        request.speculative_fibers.push(fiber);
+                        request.history.speculative_fibers.push(fiber);
+                        request
+                            .speculative_fibers_cancel_times
+                            .push(fibers_cancel_times.get(speculative_id).copied());
                     }
                 }
             }
         }
 
-        StructuredHistory {
+        DetailedHistory {
             requests: requests.into_values().collect(),
         }
     }
@@ -451,35 +1074,74 @@ impl Display for StructuredHistory {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "Requests History:")?;
         for (i, request) in self.requests.iter().enumerate() {
-            writeln!(f, "=== Request #{i} ===")?;
-            writeln!(f, "| start_time: {}", request.start_time)?;
-            writeln!(f, "| Non-speculative attempts:")?;
-            write_fiber_attempts(&request.non_speculative_fiber, f)?;
-            for (spec_i, speculative_fiber) in request.speculative_fibers.iter().enumerate() {
-                writeln!(f, "|")?;
-                writeln!(f, "|")?;
-                writeln!(f, "| > Speculative fiber #{spec_i}")?;
-                writeln!(f, "| fiber start time: {}", speculative_fiber.start_time)?;
-                write_fiber_attempts(speculative_fiber, f)?;
-            }
-            writeln!(f, "|")?;
-            match &request.result {
-                Some(RequestHistoryResult::Success(succ_time)) => {
-                    writeln!(f, "| Request successful at {succ_time}")?;
-                }
-                Some(RequestHistoryResult::Error(err_time, error)) => {
-                    writeln!(f, "| Request failed at {err_time}")?;
-                    writeln!(f, "| Error: {error}")?;
-                }
-                None => writeln!(f, "| Request still running - no final result yet")?,
-            };
-            writeln!(f, "=================")?;
+            write_request(i, request, None, f)?;
+        }
+        Ok(())
+    }
+}
+
+/// DetailedHistory is printed like [StructuredHistory], with the details of requests added.
+impl Display for DetailedHistory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Requests History:")?;
+        for (i, request) in self.requests.iter().enumerate() {
+            write_request(i, &request.history, Some(request), f)?;
         }
         Ok(())
     }
 }
 
-fn write_fiber_attempts(fiber: &FiberHistory, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+fn write_request(
+    i: usize,
+    request: &RequestHistory,
+    details: Option<&DetailedRequestHistory>,
+    f: &mut std::fmt::Formatter<'_>,
+) -> std::fmt::Result {
+    writeln!(f, "=== Request #{i} ===")?;
+    writeln!(f, "| start_time: {}", request.start_time)?;
+    if let Some(details) = details.filter(|details| !details.tags.is_empty()) {
+        writeln!(f, "| tags: {}", details.tags)?;
+    }
+    writeln!(f, "| Non-speculative attempts:")?;
+    write_fiber_attempts(
+        &request.non_speculative_fiber,
+        details.and_then(|details| details.non_speculative_fiber_cancel_time),
+        f,
+    )?;
+    for (spec_i, speculative_fiber) in request.speculative_fibers.iter().enumerate() {
+        writeln!(f, "|")?;
+        writeln!(f, "|")?;
+        writeln!(f, "| > Speculative fiber #{spec_i}")?;
+        writeln!(f, "| fiber start time: {}", speculative_fiber.start_time)?;
+        let cancel_time = details.and_then(|details| {
+            details
+                .speculative_fibers_cancel_times
+                .get(spec_i)
+                .copied()
+                .flatten()
+        });
+        write_fiber_attempts(speculative_fiber, cancel_time, f)?;
+    }
+    writeln!(f, "|")?;
+    match &request.result {
+        Some(RequestHistoryResult::Success(succ_time)) => {
+            writeln!(f, "| Request successful at {succ_time}")?;
+        }
+        Some(RequestHistoryResult::Error(err_time, error)) => {
+            writeln!(f, "| Request failed at {err_time}")?;
+            writeln!(f, "| Error: {error}")?;
+        }
+        None => writeln!(f, "| Request still running - no final result yet")?,
+    };
+    writeln!(f, "=================")?;
+    Ok(())
+}
+
+fn write_fiber_attempts(
+    fiber: &FiberHistory,
+    cancel_time: Option<TimePoint>,
+    f: &mut std::fmt::Formatter<'_>,
+) -> std::fmt::Result {
     for (i, attempt) in fiber.attempts.iter().enumerate() {
         if i != 0 {
             writeln!(f, "|")?;
@@ -496,10 +1158,29 @@ fn write_fiber_attempts(fiber: &FiberHistory, f: &mut std::fmt::Formatter<'_>) -
             None => writeln!(f, "|   No result yet")?,
         };
     }
+    if let Some(time) = cancel_time {
+        writeln!(f, "| Fiber cancelled at {time}")?;
+    }
 
     Ok(())
 }
 
+#[cfg(feature = "serde")]
+fn serialize_display<S: serde::Serializer>(
+    value: &impl Display,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(value)
+}
+
+#[cfg(feature = "serde")]
+fn serialize_debug<S: serde::Serializer>(
+    value: &impl Debug,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(&format_args!("{value:?}"))
+}
+
 #[cfg(test)]
 mod tests {
     use std::net::{IpAddr, Ipv4Addr, SocketAddr};
@@ -507,12 +1188,14 @@ mod tests {
     use crate::{
         errors::{DbError, RequestAttemptError, RequestError},
         policies::retry::RetryDecision,
+        statement::StatementTags,
         test_utils::setup_tracing,
     };
 
     use super::{
-        AttemptId, AttemptResult, HistoryCollector, HistoryListener, RequestHistoryResult,
-        RequestId, SpeculativeId, StructuredHistory, TimePoint,
+        AttemptId, AttemptResult, DetailedHistory, HistoryCollector, HistoryFilter,
+        HistoryListener, RecentHistoryCollector, RequestHistory, RequestHistoryResult, RequestId,
+        SpeculativeId, StructuredHistory, TimePoint,
     };
     use assert_matches::assert_matches;
     use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
@@ -522,6 +1205,21 @@ mod tests {
     // HistoryCollector sets the timestamp to current time which changes with each test.
     // Setting it to one makes it possible to test displaying consistently.
     fn set_one_time(mut history: StructuredHistory) -> StructuredHistory {
+        history
+            .requests
+            .iter_mut()
+            .for_each(set_one_time_in_request);
+        history
+    }
+
+    fn set_one_time_detailed(mut history: DetailedHistory) -> DetailedHistory {
+        for request in &mut history.requests {
+            set_one_time_in_request(&mut request.history);
+        }
+        history
+    }
+
+    fn set_one_time_in_request(request: &mut RequestHistory) {
         let the_time: TimePoint = DateTime::<Utc>::from_naive_utc_and_offset(
             NaiveDateTime::new(
                 NaiveDate::from_ymd_opt(2022, 2, 22).unwrap(),
@@ -530,30 +1228,26 @@ mod tests {
             Utc,
         );
 
-        for request in &mut history.requests {
-            request.start_time = the_time;
-            match &mut request.result {
-                Some(RequestHistoryResult::Success(succ_time)) => *succ_time = the_time,
-                Some(RequestHistoryResult::Error(err_time, _)) => *err_time = the_time,
-                None => {}
-            };
+        request.start_time = the_time;
+        match &mut request.result {
+            Some(RequestHistoryResult::Success(succ_time)) => *succ_time = the_time,
+            Some(RequestHistoryResult::Error(err_time, _)) => *err_time = the_time,
+            None => {}
+        };
 
-            for fiber in std::iter::once(&mut request.non_speculative_fiber)
-                .chain(request.speculative_fibers.iter_mut())
-            {
-                fiber.start_time = the_time;
-                for attempt in &mut fiber.attempts {
-                    attempt.send_time = the_time;
-                    match &mut attempt.result {
-                        Some(AttemptResult::Success(succ_time)) => *succ_time = the_time,
-                        Some(AttemptResult::Error(err_time, _, _)) => *err_time = the_time,
-                        None => {}
-                    }
+        for fiber in std::iter::once(&mut request.non_speculative_fiber)
+            .chain(request.speculative_fibers.iter_mut())
+        {
+            fiber.start_time = the_time;
+            for attempt in &mut fiber.attempts {
+                attempt.send_time = the_time;
+                match &mut attempt.result {
+                    Some(AttemptResult::Success(succ_time)) => *succ_time = the_time,
+                    Some(AttemptResult::Error(err_time, _, _)) => *err_time = the_time,
+                    None => {}
                 }
             }
         }
-
-        history
     }
 
     fn node1_addr() -> SocketAddr {
@@ -630,6 +1324,194 @@ mod tests {
         assert_eq!(displayed, format!("{}", set_one_time(history)));
     }
 
+    #[test]
+    fn tagged_request() {
+        setup_tracing();
+        let history_collector = HistoryCollector::new();
+
+        let request_id: RequestId = history_collector.log_request_start();
+        let tags: StatementTags = [("app", "billing"), ("op", "charge")].into_iter().collect();
+        history_collector.log_request_tags(request_id, &tags);
+        history_collector.log_request_success(request_id);
+
+        let history: DetailedHistory = history_collector.clone_detailed_history();
+        assert_eq!(history.requests[0].tags, tags);
+
+        let history = set_one_time_detailed(history);
+        let displayed = "Requests History:
+=== Request #0 ===
+| start_time: 2022-02-22 20:22:22 UTC
+| tags: app=billing,op=charge
+| Non-speculative attempts:
+|
+| Request successful at 2022-02-22 20:22:22 UTC
+=================
+";
+        assert_eq!(displayed, format!("{history}"));
+
+        // Tags are not a part of StructuredHistory.
+        let structured = StructuredHistory::from(history);
+        assert!(!format!("{structured}").contains("tags"));
+    }
+
+    #[test]
+    fn cancelled_fiber_and_winning_attempt() {
+        setup_tracing();
+        let history_collector = HistoryCollector::new();
+
+        let request_id = history_collector.log_request_start();
+        history_collector.log_attempt_start(request_id, None, node1_addr());
+        let speculative_id = history_collector.log_new_speculative_fiber(request_id);
+        let attempt_id =
+            history_collector.log_attempt_start(request_id, Some(speculative_id), node2_addr());
+        history_collector.log_attempt_success(attempt_id);
+        history_collector.log_fiber_cancelled(request_id, None);
+        history_collector.log_request_success(request_id);
+
+        let history = history_collector.clone_detailed_history();
+        let details = &history.requests[0];
+        assert!(details.non_speculative_fiber_cancel_time.is_some());
+        assert_eq!(details.speculative_fibers_cancel_times, [None]);
+        let request = &details.history;
+        assert!(request.latency().is_some());
+
+        let cancelled_attempt = &request.non_speculative_fiber.attempts[0];
+        assert_eq!(cancelled_attempt.latency(), None);
+        let winner = request.winning_attempt().unwrap();
+        assert_eq!(winner.node_addr, node2_addr());
+        assert!(winner.latency().unwrap() <= request.latency().unwrap());
+
+        let mut history = set_one_time_detailed(history);
+        history.requests[0].non_speculative_fiber_cancel_time =
+            Some(history.requests[0].history.start_time);
+        let displayed = "Requests History:
+=== Request #0 ===
+| start_time: 2022-02-22 20:22:22 UTC
+| Non-speculative attempts:
+| - Attempt #0 sent to 127.0.0.1:19042
+|   request send time: 2022-02-22 20:22:22 UTC
+|   No result yet
+| Fiber cancelled at 2022-02-22 20:22:22 UTC
+|
+|
+| > Speculative fiber #0
+| fiber start time: 2022-02-22 20:22:22 UTC
+| - Attempt #0 sent to 127.0.0.2:19042
+|   request send time: 2022-02-22 20:22:22 UTC
+|   Success at 2022-02-22 20:22:22 UTC
+|
+| Request successful at 2022-02-22 20:22:22 UTC
+=================
+";
+        assert_eq!(displayed, format!("{history}"));
+    }
+
+    #[test]
+    fn recent_history_keeps_last_requests() {
+        setup_tracing();
+        let collector = RecentHistoryCollector::new(2);
+
+        let run_request = |statement: &str| {
+            let request_id = collector.log_request_start();
+            collector.log_request_statement(request_id, statement);
+            let attempt_id = collector.log_attempt_start(request_id, None, node1_addr());
+            collector.log_attempt_success(attempt_id);
+            collector.log_request_success(request_id);
+            attempt_id
+        };
+        let first_attempt = run_request("SELECT 1");
+        run_request("SELECT 2");
+        run_request("SELECT 3");
+        // Events which come after the request finished are ignored.
+        collector.log_attempt_error(
+            first_attempt,
+            &unavailable_error(),
+            &RetryDecision::DontRetry,
+        );
+
+        let running = collector.log_request_start();
+        collector.log_request_statement(running, "SELECT 4");
+        collector.log_attempt_start(running, None, node2_addr());
+
+        let history = collector.clone_detailed_history();
+        let statements: Vec<_> = history
+            .requests
+            .iter()
+            .map(|request| request.statement.as_deref().unwrap())
+            .collect();
+        assert_eq!(statements, ["SELECT 2", "SELECT 3", "SELECT 4"]);
+        let history = StructuredHistory::from(history);
+        assert_matches!(
+            history.requests[0].non_speculative_fiber.attempts[0].result,
+            Some(AttemptResult::Success(_))
+        );
+        assert!(history.requests[2].result.is_none());
+        assert_eq!(history.requests[2].attempts().count(), 1);
+
+        // Running requests over capacity are moved to the finished ones as unfinished.
+        let second_running = collector.log_request_start();
+        let third_running = collector.log_request_start();
+        collector.log_request_success(running);
+        collector.log_request_error(third_running, &RequestError::EmptyPlan);
+        let history = collector.clone_detailed_history();
+        assert_eq!(history.requests.len(), 3);
+        assert_eq!(history.requests[0].statement.as_deref(), Some("SELECT 4"));
+        let history = StructuredHistory::from(history);
+        assert!(history.requests[0].result.is_none());
+        assert!(history.requests[1].result.is_none());
+        assert_matches!(
+            history.requests[2].result,
+            Some(RequestHistoryResult::Error(_, RequestError::EmptyPlan))
+        );
+
+        collector.log_request_success(second_running);
+        let history = collector.clone_structured_history();
+        assert_eq!(history.requests.len(), 2);
+        assert_matches!(
+            history.requests[0].result,
+            Some(RequestHistoryResult::Success(_))
+        );
+
+        collector.clear();
+        assert!(collector.clone_structured_history().requests.is_empty());
+    }
+
+    #[test]
+    fn history_filter() {
+        setup_tracing();
+        let history_collector = HistoryCollector::new();
+        for statement in ["SELECT * FROM users", "INSERT INTO orders (id) VALUES (?)"] {
+            let request_id = history_collector.log_request_start();
+            history_collector.log_request_statement(request_id, statement);
+            history_collector.log_request_success(request_id);
+        }
+        history_collector.log_request_start();
+
+        let history = history_collector.clone_detailed_history();
+        let start = history.requests[0].history.start_time;
+        let matching = |filter: HistoryFilter| {
+            history
+                .matching(&filter)
+                .map(|request| request.statement.as_deref())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(matching(HistoryFilter::new()).len(), 3);
+        assert_eq!(
+            matching(HistoryFilter::new().with_statement_containing("users")),
+            [Some("SELECT * FROM users")]
+        );
+        assert_eq!(matching(HistoryFilter::new().until(start)), []);
+        assert_eq!(
+            matching(
+                HistoryFilter::new()
+                    .with_statement_containing("INTO")
+                    .since(start)
+            ),
+            [Some("INSERT INTO orders (id) VALUES (?)")]
+        );
+    }
+
     #[test]
     fn one_attempt() {
         setup_tracing();
@@ -881,6 +1763,18 @@ mod tests {
 | Request successful at 2022-02-22 20:22:22 UTC
 =================
 ";
+        assert_eq!(history.requests[0].attempts().count(), 6);
+        assert_eq!(
+            history.requests[0]
+                .retry_decisions()
+                .map(|(_, decision)| decision.clone())
+                .collect::<Vec<_>>(),
+            [
+                RetryDecision::RetryNextTarget(Some(Consistency::Quorum)),
+                RetryDecision::RetryNextTarget(Some(Consistency::Quorum)),
+                RetryDecision::RetrySameTarget(Some(Consistency::Quorum)),
+            ]
+        );
         assert_eq!(displayed, format!("{}", set_one_time(history)));
     }
 
@@ -0,0 +1,349 @@
+//! Instrumenting requests with [tracing] spans suitable for distributed tracing.
+//!
+//! The driver's own spans (see the [logging docs](https://rust-driver.docs.scylladb.com/stable/logging/logging.html))
+//! are emitted at `TRACE` level and are meant for debugging the driver.
+//! When [TracingInstrumentation] is set with
+//! [SessionBuilder::tracing_instrumentation](crate::client::session_builder::SessionBuilder::tracing_instrumentation),
+//! the session additionally creates an `INFO` level span for every executed request,
+//! and a child span for each of its attempts.
+//!
+//! The spans follow the conventions of the `tracing-opentelemetry` crate: their fields
+//! named `otel.name`, `otel.kind`, `otel.status_code` and `otel.status_message`
+//! become the name, kind and status of the exported OpenTelemetry spans, and the
+//! other fields are named after OpenTelemetry semantic conventions for databases.
+//! Therefore, with the `tracing-opentelemetry` layer installed in the subscriber,
+//! the requests appear in the traces of the application, as children of the spans
+//! in which they are executed.
+//!
+//! A request span (named `scylla.request`) records:
+//! - `db.operation.name` - kind of the request: `QUERY`, `EXECUTE` or `BATCH`,
+//! - `db.query.text` - the text of the statement, only if enabled with
+//!   [TracingInstrumentation::with_statement_text], as it may contain sensitive data,
+//! - `db.namespace` and `db.collection.name` - keyspace and table, if known,
+//! - `db.cassandra.consistency_level` - consistency requested for the request,
+//! - `db.cassandra.speculative_execution_count` - number of speculative executions,
+//! - `db.cassandra.tracing_id` - id of the server-side tracing session, if the request was traced,
+//! - the outcome of the request.
+//!
+//! An attempt span (named `scylla.attempt`) records:
+//! - `server.address` and `server.port` - address of the node which the attempt was sent to,
+//! - `db.cassandra.coordinator.id` - host id of that node,
+//! - `shard` - the shard which the attempt was sent to, if the node is shard-aware,
+//! - `db.cassandra.consistency_level` - consistency with which the attempt was sent,
+//! - `retry_decision` - the decision of the retry policy, if the attempt failed
+//!   (not recorded for pages fetched by pagers),
+//! - the outcome of the attempt.
+//!
+//! Each page fetched by a [QueryPager](crate::client::pager::QueryPager) is instrumented
+//! as a separate request.
+//!
+//! # Propagating the trace context to the cluster
+//!
+//! A [TraceContextProvider] set with
+//! [SessionBuilder::trace_context_provider](crate::client::session_builder::SessionBuilder::trace_context_provider)
+//! is asked for the trace context active when a request is executed (i.e. inside
+//! the request span, if instrumentation is enabled). The returned [TraceParent]
+//! is sent to the cluster in the custom payload of the request,
+//! under the [TRACEPARENT_CUSTOM_PAYLOAD_KEY] key, in the format of the
+//! [W3C Trace Context](https://www.w3.org/TR/trace-context/#traceparent-header) `traceparent` header.
+//!
+//! Moreover, if a request is traced by the cluster (see [Statement::set_tracing](crate::statement::unprepared::Statement::set_tracing)),
+//! the id of the server-side tracing session is recorded in the `db.cassandra.tracing_id` field
+//! of the request span, which correlates the traces of the application with the tracing data
+//! stored by the cluster, even if the cluster ignores the propagated trace context.
+
+use std::fmt::{Debug, Display};
+use std::str::FromStr;
+
+use thiserror::Error;
+
+/// Configuration of the spans created for requests executed by a session.
+/// See the [module docs](self) for details.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TracingInstrumentation {
+    statement_text: bool,
+    attempt_spans: bool,
+}
+
+impl TracingInstrumentation {
+    /// Creates the default configuration: spans are created for requests and their attempts,
+    /// and the text of statements is not recorded.
+    pub fn new() -> Self {
+        Self {
+            statement_text: false,
+            attempt_spans: true,
+        }
+    }
+
+    /// Sets whether the text of executed statements is recorded in the `db.query.text` field.
+    ///
+    /// Disabled by default, because statements may include sensitive data
+    /// in literal values.
+    pub fn with_statement_text(mut self, statement_text: bool) -> Self {
+        self.statement_text = statement_text;
+        self
+    }
+
+    /// Sets whether a child span is created for each attempt of a request.
+    ///
+    /// Enabled by default.
+    pub fn with_attempt_spans(mut self, attempt_spans: bool) -> Self {
+        self.attempt_spans = attempt_spans;
+        self
+    }
+
+    /// Returns whether the text of executed statements is recorded.
+    pub fn statement_text(&self) -> bool {
+        self.statement_text
+    }
+
+    /// Returns whether a child span is created for each attempt of a request.
+    pub fn attempt_spans(&self) -> bool {
+        self.attempt_spans
+    }
+}
+
+impl Default for TracingInstrumentation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The key under which the [TraceParent] is sent in the custom payload of requests.
+pub const TRACEPARENT_CUSTOM_PAYLOAD_KEY: &str = "traceparent";
+
+/// Length of a [TraceParent] in its textual form.
+const TRACEPARENT_LENGTH: usize = 55;
+
+/// Identifies the position of a request in a distributed trace,
+/// as defined by the `traceparent` header of
+/// [W3C Trace Context](https://www.w3.org/TR/trace-context/#traceparent-header).
+///
+/// It is displayed and parsed in the textual form of the header,
+/// e.g. `00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TraceParent {
+    trace_id: [u8; 16],
+    parent_id: [u8; 8],
+    trace_flags: u8,
+}
+
+impl TraceParent {
+    /// Creates a trace parent from the id of the trace, the id of the span
+    /// in which the request is executed, and the trace flags.
+    pub fn new(trace_id: [u8; 16], parent_id: [u8; 8], trace_flags: u8) -> Self {
+        Self {
+            trace_id,
+            parent_id,
+            trace_flags,
+        }
+    }
+
+    /// Returns the id of the trace.
+    pub fn trace_id(&self) -> [u8; 16] {
+        self.trace_id
+    }
+
+    /// Returns the id of the span in which the request is executed.
+    pub fn parent_id(&self) -> [u8; 8] {
+        self.parent_id
+    }
+
+    /// Returns the trace flags.
+    pub fn trace_flags(&self) -> u8 {
+        self.trace_flags
+    }
+
+    /// Returns whether the trace is sampled, according to the trace flags.
+    pub fn is_sampled(&self) -> bool {
+        self.trace_flags & 0x01 != 0
+    }
+
+    /// Encodes the trace parent in its textual form, without allocating.
+    pub(crate) fn encode(&self) -> [u8; TRACEPARENT_LENGTH] {
+        const HEX: &[u8; 16] = b"0123456789abcdef";
+
+        let mut encoded = [b'-'; TRACEPARENT_LENGTH];
+        let mut position = 0;
+        let mut push_hex = |bytes: &[u8]| {
+            for byte in bytes {
+                encoded[position] = HEX[(byte >> 4) as usize];
+                encoded[position + 1] = HEX[(byte & 0x0f) as usize];
+                position += 2;
+            }
+            // Skip the separator.
+            position += 1;
+        };
+        push_hex(&[0]);
+        push_hex(&self.trace_id);
+        push_hex(&self.parent_id);
+        push_hex(&[self.trace_flags]);
+        encoded
+    }
+}
+
+impl Display for TraceParent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let encoded = self.encode();
+        // The encoded form consists of ASCII characters only.
+        f.write_str(std::str::from_utf8(&encoded).unwrap())
+    }
+}
+
+/// An error returned when parsing a [TraceParent] fails.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TraceParentParseError {
+    /// The string is not of the form `version-trace_id-parent_id-trace_flags`
+    /// with hexadecimal fields of the right lengths.
+    #[error("Malformed traceparent: {0:?}")]
+    Malformed(String),
+    /// The version of the format is not supported.
+    #[error("Unsupported traceparent version: {0:?}")]
+    UnsupportedVersion(String),
+    /// The trace id or the parent id consists of zeros only,
+    /// which W3C Trace Context forbids.
+    #[error("Traceparent has an all-zero trace id or parent id")]
+    InvalidId,
+}
+
+impl FromStr for TraceParent {
+    type Err = TraceParentParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        fn parse_hex<const N: usize>(field: &str) -> Option<[u8; N]> {
+            if field.len() != 2 * N
+                || !field
+                    .bytes()
+                    .all(|c| c.is_ascii_digit() || (b'a'..=b'f').contains(&c))
+            {
+                return None;
+            }
+            let mut bytes = [0; N];
+            for (i, byte) in bytes.iter_mut().enumerate() {
+                *byte = u8::from_str_radix(&field[2 * i..2 * i + 2], 16).ok()?;
+            }
+            Some(bytes)
+        }
+
+        let malformed = || TraceParentParseError::Malformed(s.to_owned());
+        let mut fields = s.split('-');
+        let (Some(version), Some(trace_id), Some(parent_id), Some(trace_flags), None) = (
+            fields.next(),
+            fields.next(),
+            fields.next(),
+            fields.next(),
+            fields.next(),
+        ) else {
+            return Err(malformed());
+        };
+
+        let [version] = parse_hex::<1>(version).ok_or_else(malformed)?;
+        if version != 0 {
+            return Err(TraceParentParseError::UnsupportedVersion(s.to_owned()));
+        }
+        let trace_id = parse_hex::<16>(trace_id).ok_or_else(malformed)?;
+        let parent_id = parse_hex::<8>(parent_id).ok_or_else(malformed)?;
+        let [trace_flags] = parse_hex::<1>(trace_flags).ok_or_else(malformed)?;
+        if trace_id == [0; 16] || parent_id == [0; 8] {
+            return Err(TraceParentParseError::InvalidId);
+        }
+
+        Ok(Self::new(trace_id, parent_id, trace_flags))
+    }
+}
+
+/// Provides the trace context of the application, which is propagated
+/// to the cluster in the custom payload of requests.
+/// See the [module docs](self#propagating-the-trace-context-to-the-cluster) for details.
+///
+/// It is called synchronously when a request starts, so it should be cheap and must not block.
+///
+/// With the `tracing-opentelemetry` crate, a provider can build the [TraceParent]
+/// from the span context of `tracing::Span::current().context()`.
+///
+/// # Example
+/// ```
+/// # use scylla::observability::instrumentation::{TraceContextProvider, TraceParent};
+/// # use std::sync::Mutex;
+/// // A provider returning the context received by the application
+/// // in the `traceparent` header of an incoming HTTP request.
+/// #[derive(Debug)]
+/// struct IncomingRequestContext(Mutex<Option<TraceParent>>);
+///
+/// impl TraceContextProvider for IncomingRequestContext {
+///     fn current_trace_parent(&self) -> Option<TraceParent> {
+///         *self.0.lock().unwrap()
+///     }
+/// }
+///
+/// let provider = IncomingRequestContext(Mutex::new(None));
+/// let header = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+/// *provider.0.lock().unwrap() = Some(header.parse().unwrap());
+/// assert!(provider.current_trace_parent().unwrap().is_sampled());
+/// ```
+pub trait TraceContextProvider: Send + Sync + Debug {
+    /// Returns the trace context in which the current request is executed,
+    /// or `None` if there is no active trace.
+    fn current_trace_parent(&self) -> Option<TraceParent>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TraceParent, TraceParentParseError};
+
+    #[test]
+    fn trace_parent_round_trips_through_text() {
+        let text = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        let trace_parent: TraceParent = text.parse().unwrap();
+        assert_eq!(
+            trace_parent.trace_id(),
+            [
+                0x4b, 0xf9, 0x2f, 0x35, 0x77, 0xb3, 0x4d, 0xa6, 0xa3, 0xce, 0x92, 0x9d, 0x0e, 0x0e,
+                0x47, 0x36
+            ]
+        );
+        assert_eq!(
+            trace_parent.parent_id(),
+            [0x00, 0xf0, 0x67, 0xaa, 0x0b, 0xa9, 0x02, 0xb7]
+        );
+        assert!(trace_parent.is_sampled());
+        assert_eq!(trace_parent.to_string(), text);
+
+        let not_sampled = TraceParent::new([1; 16], [2; 8], 0);
+        assert!(!not_sampled.is_sampled());
+        assert_eq!(
+            not_sampled.to_string().parse::<TraceParent>().unwrap(),
+            not_sampled
+        );
+    }
+
+    #[test]
+    fn invalid_trace_parents_are_rejected() {
+        for malformed in [
+            "",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-00",
+            "00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e47-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902bx-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-+0f067aa0ba902b7-01",
+        ] {
+            assert!(
+                matches!(
+                    malformed.parse::<TraceParent>(),
+                    Err(TraceParentParseError::Malformed(_))
+                ),
+                "{malformed}"
+            );
+        }
+        assert!(matches!(
+            "01-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01".parse::<TraceParent>(),
+            Err(TraceParentParseError::UnsupportedVersion(_))
+        ));
+        assert_eq!(
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01".parse::<TraceParent>(),
+            Err(TraceParentParseError::InvalidId)
+        );
+    }
+}
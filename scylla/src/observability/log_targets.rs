@@ -0,0 +1,63 @@
+//! Targets of the events which the driver emits through [`tracing`](https://docs.rs/tracing).
+//!
+//! Each subsystem of the driver logs under its own, stable target, so that its verbosity
+//! can be adjusted independently of the others, e.g. with an
+//! [`EnvFilter`](https://docs.rs/tracing-subscriber/latest/tracing_subscriber/filter/struct.EnvFilter.html)
+//! directive such as `scylla=warn,scylla::pool=debug`.
+//!
+//! Targets form a hierarchy with `scylla` at its root, so a directive for `scylla`
+//! applies to all of them.
+//!
+//! Events carry their context in structured fields rather than in the message.
+//! Fields with the same meaning have the same names across targets:
+//! - `node` - address of the node concerned,
+//! - `host_id` - host id of the node concerned,
+//! - `shard` - shard concerned,
+//! - `keyspace` and `table` - schema object concerned,
+//! - `error` - error which caused the event, in its `Display` form.
+//!
+//! # Example
+//! ```
+//! # fn example() {
+//! use scylla::observability::log_targets;
+//! use tracing_subscriber::filter::{EnvFilter, LevelFilter};
+//!
+//! // Reports how connections are opened and lost, without per-request noise.
+//! let filter = EnvFilter::builder()
+//!     .with_default_directive(LevelFilter::WARN.into())
+//!     .parse_lossy(format!("{}=info", log_targets::CONNECTION));
+//! tracing_subscriber::fmt().with_env_filter(filter).init();
+//! # }
+//! ```
+
+/// Discovery of nodes, their state and the token ring, and reacting to cluster events.
+pub const TOPOLOGY: &str = "scylla::topology";
+
+/// Fetching and parsing of schema metadata and of tablet information.
+pub const METADATA: &str = "scylla::metadata";
+
+/// Connection pools: filling and refilling them, and choosing connections for requests.
+pub const POOL: &str = "scylla::pool";
+
+/// Lifecycle of single connections: opening them, keepalives, and errors which break them.
+///
+/// These events are logged at `debug` level, unless
+/// [verbose connection logging](crate::client::session_builder::GenericSessionBuilder::verbose_connection_logging)
+/// is enabled, in which case they are logged at `info` level.
+pub const CONNECTION: &str = "scylla::connection";
+
+/// Frame-level activity of connections: writing and reading frames, and managing stream ids.
+/// Very noisy at `trace` level.
+pub const FRAME: &str = "scylla::frame";
+
+/// Executing requests: sending them to nodes, paging, and handling their responses.
+pub const REQUEST: &str = "scylla::request";
+
+/// Decisions of retry and speculative execution policies.
+pub const RETRY: &str = "scylla::retry";
+
+/// Decisions of load balancing policies.
+pub const LOAD_BALANCING: &str = "scylla::load_balancing";
+
+/// Requests reported by the [slow query log](crate::observability::slow_query_log).
+pub const SLOW_QUERY: &str = "scylla::slow_query";
@@ -1,9 +1,19 @@
 //! Collecting metrics of driver operations.
 
+use arc_swap::ArcSwap;
 use histogram::{AtomicHistogram, Histogram};
-use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::{Arc, Mutex};
+use scylla_cql::frame::response::result::TableSpec;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
 use thiserror::Error;
+use uuid::Uuid;
+
+use crate::errors::{DbError, RequestAttemptError};
+#[cfg(feature = "metrics-024")]
+use crate::policies::load_balancing::CircuitState;
+use crate::policies::retry::RetryDecisionKind;
 
 const ORDER_TYPE: Ordering = Ordering::Relaxed;
 
@@ -17,6 +27,16 @@ pub enum MetricsError {
     /// Histogram is empty, so statistics cannot be computed.
     #[error("Histogram is empty")]
     Empty,
+    /// Latencies are not collected in a sliding window of the requested length.
+    #[error(
+        "Latencies are not collected in a sliding window of {requested:?}, the longest available window is {available:?}"
+    )]
+    WindowUnavailable {
+        /// The requested length of the window.
+        requested: Duration,
+        /// The length of the configured sliding window, if any.
+        available: Option<Duration>,
+    },
 }
 
 /// Snapshot is a structure that contains histogram statistics such as
@@ -45,6 +65,350 @@ pub struct Snapshot {
     pub percentile_99: u64,
     /// 99.9th percentile value in the histogram.
     pub percentile_99_9: u64,
+    /// Values of the percentiles configured with [`LatencyHistogramConfig::with_percentiles`],
+    /// as pairs of the percentile and its value.
+    pub percentiles: Vec<(f64, u64)>,
+}
+
+/// Configuration of the histograms in which [Metrics] collect latencies of requests.
+///
+/// By default, latencies are collected in a single cumulative histogram since
+/// the session was created, with a relative error of about 0.025%.
+///
+/// Additionally, latencies can be collected in a sliding time window,
+/// which allows computing statistics of recent latencies only
+/// (see [`Metrics::get_window_snapshot`]) - e.g. for alerting.
+#[derive(Debug, Clone)]
+pub struct LatencyHistogramConfig {
+    grouping_power: u8,
+    max_value_power: u8,
+    sliding_window: Option<(Duration, Duration)>,
+    percentiles: Vec<f64>,
+}
+
+impl LatencyHistogramConfig {
+    /// Creates the default configuration.
+    pub fn new() -> Self {
+        Self {
+            grouping_power: 12,
+            max_value_power: 16,
+            sliding_window: None,
+            percentiles: Vec::new(),
+        }
+    }
+
+    /// Sets the precision of latency histograms.
+    ///
+    /// Latencies are collected with a relative error of `2^-grouping_power`,
+    /// and latencies up to `2^max_value_power` milliseconds can be recorded.
+    /// The memory used by a histogram is proportional to
+    /// `(max_value_power - grouping_power + 1) * 2^grouping_power`.
+    /// The default values are 12 and 16, which amount to 1.7 MiB per histogram.
+    ///
+    /// Returns an error if the values do not form a valid histogram configuration,
+    /// i.e. if `grouping_power >= max_value_power` or `max_value_power > 64`.
+    pub fn with_precision(
+        mut self,
+        grouping_power: u8,
+        max_value_power: u8,
+    ) -> Result<Self, MetricsError> {
+        histogram::Config::new(grouping_power, max_value_power)
+            .map_err(|err| MetricsError::HistogramError(Arc::new(err)))?;
+        self.grouping_power = grouping_power;
+        self.max_value_power = max_value_power;
+        Ok(self)
+    }
+
+    /// Enables collecting latencies in a sliding window of the given length,
+    /// divided into slots of the given length.
+    ///
+    /// Snapshots of any window up to `length` can then be taken, with the granularity
+    /// of `slot`: a window covers the current, partially filled slot and as many
+    /// preceding slots as needed. Each slot is a separate histogram of the configured
+    /// precision, so consider lowering the precision when enabling long windows
+    /// with short slots.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `slot` is zero or longer than `length`.
+    pub fn with_sliding_window(mut self, length: Duration, slot: Duration) -> Self {
+        assert!(
+            !slot.is_zero() && slot <= length,
+            "Sliding window slot must be non-zero and not longer than the window"
+        );
+        self.sliding_window = Some((length, slot));
+        self
+    }
+
+    /// Sets additional percentiles (between 0.0 and 100.0) which are computed
+    /// in snapshots and returned in [`Snapshot::percentiles`].
+    pub fn with_percentiles(mut self, percentiles: impl IntoIterator<Item = f64>) -> Self {
+        self.percentiles = percentiles.into_iter().collect();
+        self
+    }
+
+    fn new_histogram(&self) -> AtomicHistogram {
+        // The values were validated in `with_precision()`.
+        AtomicHistogram::new(self.grouping_power, self.max_value_power).unwrap()
+    }
+}
+
+impl Default for LatencyHistogramConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Number of stream ids available on a single connection.
+const STREAM_IDS_PER_CONNECTION: u64 = 1 << 15;
+
+/// Gauges of the connections to a node (or to all nodes) at some moment.
+///
+/// They are leading indicators of client-side saturation: when stream ids run out
+/// or requests pile up in write queues, latencies grow before errors appear.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConnectionGauges {
+    /// Number of open connections.
+    pub open_connections: u64,
+    /// Number of stream ids assigned to requests waiting for responses,
+    /// including requests which were abandoned by the driver, but not responded to.
+    pub stream_ids_in_use: u64,
+    /// Number of stream ids which can still be assigned to requests on the open connections.
+    pub stream_ids_available: u64,
+    /// Number of requests waiting in write queues of connections to be assigned
+    /// a stream id and written.
+    pub queued_requests: u64,
+    /// Number of requests written to connections' buffers, but not flushed to sockets yet,
+    /// e.g. because the writers wait for more requests to coalesce them.
+    pub unflushed_requests: u64,
+}
+
+/// A gauge of [NodeConnectionGauges].
+#[derive(Clone, Copy)]
+pub(crate) enum ConnectionGauge {
+    OpenConnections,
+    StreamIdsInUse,
+    QueuedRequests,
+    UnflushedRequests,
+}
+
+/// Gauges of the connections to a single node, updated by the connections
+/// through [GaugeGuard]s.
+#[derive(Default)]
+pub(crate) struct NodeConnectionGauges {
+    gauges: [AtomicU64; 4],
+}
+
+impl NodeConnectionGauges {
+    /// Returns a guard through which the given gauge can be updated.
+    /// All its updates are reverted once the guard is dropped.
+    pub(crate) fn track(self: &Arc<Self>, gauge: ConnectionGauge) -> GaugeGuard {
+        GaugeGuard {
+            gauges: Arc::clone(self),
+            gauge,
+            value: 0,
+        }
+    }
+
+    fn load(&self) -> ConnectionGauges {
+        let [
+            open_connections,
+            stream_ids_in_use,
+            queued_requests,
+            unflushed_requests,
+        ] = self.gauges.each_ref().map(|gauge| gauge.load(ORDER_TYPE));
+        ConnectionGauges {
+            open_connections,
+            stream_ids_in_use,
+            stream_ids_available: (open_connections * STREAM_IDS_PER_CONNECTION)
+                .saturating_sub(stream_ids_in_use),
+            queued_requests,
+            unflushed_requests,
+        }
+    }
+}
+
+/// Holds a contribution of a connection to a gauge of [NodeConnectionGauges],
+/// and withdraws it when dropped - i.e. also when the connection is closed.
+pub(crate) struct GaugeGuard {
+    gauges: Arc<NodeConnectionGauges>,
+    gauge: ConnectionGauge,
+    value: u64,
+}
+
+impl GaugeGuard {
+    pub(crate) fn add(&mut self, value: u64) {
+        self.value += value;
+        self.gauges.gauges[self.gauge as usize].fetch_add(value, ORDER_TYPE);
+    }
+
+    pub(crate) fn sub(&mut self, value: u64) {
+        let value = value.min(self.value);
+        self.value -= value;
+        self.gauges.gauges[self.gauge as usize].fetch_sub(value, ORDER_TYPE);
+    }
+}
+
+impl Drop for GaugeGuard {
+    fn drop(&mut self) {
+        self.gauges.gauges[self.gauge as usize].fetch_sub(self.value, ORDER_TYPE);
+    }
+}
+
+/// A slot of [SlidingWindowHistogram], holding latencies recorded in one period.
+struct WindowSlot {
+    /// Number of the period since the window was created which this slot holds.
+    period: AtomicU64,
+    histogram: AtomicHistogram,
+}
+
+/// Histograms of latencies recorded in consecutive periods, stored in a ring.
+struct SlidingWindowHistogram {
+    start: std::time::Instant,
+    slot_duration: Duration,
+    slots: Box<[WindowSlot]>,
+}
+
+impl SlidingWindowHistogram {
+    fn new(config: &LatencyHistogramConfig, length: Duration, slot: Duration) -> Self {
+        // One more slot than needed to cover the window, because the current one
+        // is only partially filled.
+        let slot_count = length.as_nanos().div_ceil(slot.as_nanos()) as usize + 1;
+        Self {
+            start: std::time::Instant::now(),
+            slot_duration: slot,
+            slots: (0..slot_count)
+                .map(|_| WindowSlot {
+                    period: AtomicU64::new(0),
+                    histogram: config.new_histogram(),
+                })
+                .collect(),
+        }
+    }
+
+    fn length(&self) -> Duration {
+        self.slot_duration * (self.slots.len() as u32 - 1)
+    }
+
+    fn current_period(&self) -> u64 {
+        (self.start.elapsed().as_nanos() / self.slot_duration.as_nanos()) as u64
+    }
+
+    fn increment(&self, latency: u64) -> Result<(), histogram::Error> {
+        let period = self.current_period();
+        let slot = &self.slots[(period % self.slots.len() as u64) as usize];
+        let slot_period = slot.period.load(ORDER_TYPE);
+        // Only one thread succeeds in moving the slot to the new period, and clears it.
+        // Latencies recorded concurrently by other threads may be lost, but it only
+        // happens at slot boundaries, so it does not affect the statistics noticeably.
+        if slot_period != period
+            && slot
+                .period
+                .compare_exchange(slot_period, period, ORDER_TYPE, ORDER_TYPE)
+                .is_ok()
+        {
+            slot.histogram.drain();
+        }
+        slot.histogram.increment(latency)
+    }
+
+    /// Merges histograms of the slots covering the last `window`.
+    fn load(&self, window: Duration) -> Result<Histogram, MetricsError> {
+        let current_period = self.current_period();
+        let periods = window.as_nanos().div_ceil(self.slot_duration.as_nanos()) as u64;
+        let mut merged: Option<Histogram> = None;
+        for period in current_period.saturating_sub(periods)..=current_period {
+            let slot = &self.slots[(period % self.slots.len() as u64) as usize];
+            if slot.period.load(ORDER_TYPE) != period {
+                continue;
+            }
+            let histogram = slot.histogram.load();
+            merged = Some(match merged {
+                Some(merged) => merged
+                    .wrapping_add(&histogram)
+                    .map_err(|err| MetricsError::HistogramError(Arc::new(err)))?,
+                None => histogram,
+            });
+        }
+        merged.ok_or(MetricsError::Empty)
+    }
+}
+
+/// Upper bound on the number of shards of a [ShardedCounter].
+const MAX_COUNTER_SHARDS: usize = 64;
+
+/// An atomic counter aligned to a cache line, so that neighbouring counters
+/// updated by different threads do not contend.
+#[derive(Default)]
+#[repr(align(128))]
+struct CachePaddedCounter(AtomicU64);
+
+/// Returns the number of shards of counters created by this process,
+/// derived from the available parallelism.
+fn counter_shards() -> usize {
+    static SHARDS: OnceLock<usize> = OnceLock::new();
+    *SHARDS.get_or_init(|| {
+        std::thread::available_parallelism()
+            .map_or(1, |parallelism| parallelism.get())
+            .next_power_of_two()
+            .min(MAX_COUNTER_SHARDS)
+    })
+}
+
+/// Returns the shard which the current thread should update.
+fn current_counter_shard() -> usize {
+    static NEXT_THREAD: AtomicUsize = AtomicUsize::new(0);
+    thread_local! {
+        static THREAD: usize = NEXT_THREAD.fetch_add(1, ORDER_TYPE);
+    }
+    THREAD.with(|thread| thread & (counter_shards() - 1))
+}
+
+/// A counter which is cheap to update from many threads at once.
+///
+/// Under a high request rate, a single atomic counter updated by all threads
+/// becomes a point of contention, as its cache line keeps moving between cores.
+/// Here, each thread updates one of several shards, and reads sum all of them up.
+struct ShardedCounter {
+    shards: Box<[CachePaddedCounter]>,
+}
+
+impl ShardedCounter {
+    fn new() -> Self {
+        Self {
+            shards: (0..counter_shards())
+                .map(|_| CachePaddedCounter::default())
+                .collect(),
+        }
+    }
+
+    fn add(&self, value: u64) {
+        self.shards[current_counter_shard()]
+            .0
+            .fetch_add(value, ORDER_TYPE);
+    }
+
+    fn load(&self) -> u64 {
+        self.shards
+            .iter()
+            .map(|shard| shard.0.load(ORDER_TYPE))
+            .fold(0, u64::wrapping_add)
+    }
+
+    /// Resets the counter, returning its value.
+    fn take(&self) -> u64 {
+        self.shards
+            .iter()
+            .map(|shard| shard.0.swap(0, ORDER_TYPE))
+            .fold(0, u64::wrapping_add)
+    }
+}
+
+impl std::fmt::Debug for ShardedCounter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.load().fmt(f)
+    }
 }
 
 /// The interval in seconds for which the rate is calculated.
@@ -55,9 +419,6 @@ const INTERVAL: u64 = 5;
 struct ExponentiallyWeightedMovingAverage {
     /// Smoothing factor, a value between 0 and 1.
     alpha: f64,
-    /// Atomic counter to keep track of the number of requests. \
-    /// Indicates the number of requests that have not been accounted for EWMA yet.
-    uncounted: AtomicU64,
     /// To check if the EWMA has been initialized.
     is_initialized: Mutex<bool>,
     ///  Atomic value representing the current rate of requests per second. \
@@ -69,7 +430,6 @@ impl ExponentiallyWeightedMovingAverage {
     fn new(alpha: f64) -> Self {
         Self {
             alpha,
-            uncounted: AtomicU64::new(0),
             is_initialized: Mutex::new(false),
             rate: AtomicU64::new(0),
         }
@@ -80,13 +440,7 @@ impl ExponentiallyWeightedMovingAverage {
         f64::from_bits(self.rate.load(Ordering::Acquire))
     }
 
-    /// Increments the `uncounted` requests counter. \
-    /// Should be called every time a new request is made.
-    fn update(&self) {
-        self.uncounted.fetch_add(1, ORDER_TYPE);
-    }
-
-    /// Updates the `rate` based on the current number of requests. \
+    /// Updates the `rate` based on the number of requests made in the last interval. \
     /// Should be called every time the interval has passed.
     ///
     /// The rate is updated using the formula: \
@@ -94,10 +448,9 @@ impl ExponentiallyWeightedMovingAverage {
     /// where `instant_rate` is the number of requests in the last interval.
     ///
     /// The first time this function is called, the `rate` is set to the `instant_rate`.
-    fn tick(&self) {
+    fn tick(&self, count: u64) {
         let mut is_initialized = self.is_initialized.lock().unwrap();
 
-        let count = self.uncounted.swap(0, ORDER_TYPE);
         let instant_rate = count as f64 / INTERVAL as f64;
 
         if *is_initialized {
@@ -119,7 +472,9 @@ struct RequestRateMeter {
     one_minute_rate: ExponentiallyWeightedMovingAverage,
     five_minute_rate: ExponentiallyWeightedMovingAverage,
     fifteen_minute_rate: ExponentiallyWeightedMovingAverage,
-    count: AtomicU64,
+    count: ShardedCounter,
+    /// Number of requests that have not been accounted for in the EWMAs yet.
+    uncounted: ShardedCounter,
     start_time: std::time::Instant,
     last_tick: AtomicU64,
 }
@@ -137,7 +492,8 @@ impl RequestRateMeter {
             fifteen_minute_rate: ExponentiallyWeightedMovingAverage::new(
                 1.0 - (-(INTERVAL as f64) / 60.0 / 15.0).exp(),
             ),
-            count: AtomicU64::new(0),
+            count: ShardedCounter::new(),
+            uncounted: ShardedCounter::new(),
             start_time: now,
             last_tick: AtomicU64::new(now.elapsed().as_nanos() as u64),
         }
@@ -145,10 +501,8 @@ impl RequestRateMeter {
 
     fn mark(&self) {
         self.tick_if_necessary();
-        self.count.fetch_add(1, ORDER_TYPE);
-        self.one_minute_rate.update();
-        self.five_minute_rate.update();
-        self.fifteen_minute_rate.update();
+        self.count.add(1);
+        self.uncounted.add(1);
     }
 
     fn one_minute_rate(&self) -> f64 {
@@ -174,7 +528,7 @@ impl RequestRateMeter {
     }
 
     fn count(&self) -> u64 {
-        self.count.load(ORDER_TYPE)
+        self.count.load()
     }
 
     fn tick_if_necessary(&self) {
@@ -199,10 +553,12 @@ impl RequestRateMeter {
                 // This is extremely unlikely, because then the loop would have to take
                 // 5 seconds! (INTERVAL * 1e9), BUT even it it happens, we have a mutex
                 // in ECMA to guard against logical race.
+                let mut count = self.uncounted.take();
                 for _ in 0..required_ticks {
-                    self.one_minute_rate.tick();
-                    self.five_minute_rate.tick();
-                    self.fifteen_minute_rate.tick();
+                    self.one_minute_rate.tick(count);
+                    self.five_minute_rate.tick(count);
+                    self.fifteen_minute_rate.tick(count);
+                    count = 0;
                 }
             }
         }
@@ -218,28 +574,78 @@ impl Default for RequestRateMeter {
 /// Various metrics collected by the driver.
 pub struct Metrics {
     /// Number of errors that occurred in queries executed without `QueryPager`.
-    errors_num: AtomicU64,
+    errors_num: ShardedCounter,
     /// Number of queries executed without `QueryPager`.
-    queries_num: AtomicU64,
+    queries_num: ShardedCounter,
     /// Number of errors that occurred in queries executed with `QueryPager`.
-    errors_iter_num: AtomicU64,
+    errors_iter_num: ShardedCounter,
     /// Number of queries executed with `QueryPager`.
-    queries_iter_num: AtomicU64,
+    queries_iter_num: ShardedCounter,
     /// Number of times a retry policy has decided to retry a query.
-    retries_num: AtomicU64,
+    retries_num: ShardedCounter,
     /// Histogram that collects latencies of queries executed by the driver.
     histogram: Arc<AtomicHistogram>,
+    /// Histograms that collect latencies of recent queries, if enabled.
+    window_histogram: Option<SlidingWindowHistogram>,
+    /// Additional percentiles computed in snapshots.
+    snapshot_percentiles: Vec<f64>,
+    /// Gauges of connections to each node, by host ID.
+    node_connection_gauges: ArcSwap<HashMap<Uuid, Arc<NodeConnectionGauges>>>,
+    /// Histograms that collect latencies of queries executed on each node, by host ID.
+    node_histograms: ArcSwap<HashMap<Uuid, Arc<AtomicHistogram>>>,
     /// Collects rates of queries executed by the driver.
     meter: Arc<RequestRateMeter>,
     /// Total number of connections ever opened to the cluster by the driver.
     total_connections: AtomicU64,
     connection_timeouts: AtomicU64,
     request_timeouts: AtomicU64,
+    /// Number of connections to the shard-aware port which landed on the requested shard.
+    shard_aware_connections_on_target_shard: AtomicU64,
+    /// Number of connections to the shard-aware port which failed or landed on a different shard.
+    shard_aware_connections_off_target_shard: AtomicU64,
+    /// Number of connection attempts made when reconnecting after a failure.
+    reconnect_attempts: AtomicU64,
+    /// Number of connection attempts made when reconnecting after a failure which succeeded.
+    reconnect_successes: AtomicU64,
+    /// Number of keepalive requests sent on connections.
+    keepalive_requests: AtomicU64,
+    /// Number of keepalive requests which failed or timed out.
+    keepalive_failures: AtomicU64,
+    /// Number of times connection writers flushed data to their sockets.
+    write_flushes: ShardedCounter,
+    /// Number of requests written in all flushes.
+    write_flushed_requests: ShardedCounter,
+    /// Number of bytes written in all flushes.
+    write_flushed_bytes: ShardedCounter,
+    /// Total time in microseconds spent by connection writers on write coalescing delays.
+    write_coalescing_delay_us: ShardedCounter,
+    /// Number of requests sent with a compressed body.
+    compressed_requests: ShardedCounter,
+    /// Total length of bodies of compressed requests, before compression.
+    request_bytes_before_compression: ShardedCounter,
+    /// Total length of bodies of compressed requests, after compression.
+    request_bytes_after_compression: ShardedCounter,
+    /// Number of times a node was moved to the end of a query plan,
+    /// because it reached the limit of requests in flight.
+    saturated_node_skips: AtomicU64,
+    /// Number of request attempts rejected by per-partition rate limiting.
+    rate_limited_requests: AtomicU64,
+    /// Number of request attempts rejected by per-partition rate limiting, by keyspace and table.
+    rate_limited_requests_per_table: Mutex<HashMap<(String, String), u64>>,
+    /// Number of retry decisions, by decision kind and kind of the error.
+    retry_decisions: Mutex<HashMap<(RetryDecisionKind, &'static str), u64>>,
+    /// Number of speculative executions started.
+    speculative_executions: ShardedCounter,
 }
 
 impl Metrics {
+    #[cfg(test)]
     pub(crate) fn new() -> Self {
-        // Configuration:
+        Self::with_latency_histogram_config(&LatencyHistogramConfig::default())
+    }
+
+    pub(crate) fn with_latency_histogram_config(config: &LatencyHistogramConfig) -> Self {
+        // Default configuration:
         //  - exponent of max value: n = 16
         //  - inverse exponent of relative error: p = 12,
         //  - max value: N = 65535,
@@ -248,71 +654,255 @@ impl Metrics {
         //  - histogram size: 1.7 MiB.
         // Reference for calculating these values:
         //  - https://observablehq.com/@iopsystems/h2histogram
-        let max_value_power = 16;
-        let grouping_power = 12;
+        #[cfg(feature = "metrics-024")]
+        describe_published_metrics();
 
         Self {
-            errors_num: AtomicU64::new(0),
-            queries_num: AtomicU64::new(0),
-            errors_iter_num: AtomicU64::new(0),
-            queries_iter_num: AtomicU64::new(0),
-            retries_num: AtomicU64::new(0),
-            histogram: Arc::new(AtomicHistogram::new(grouping_power, max_value_power).unwrap()),
+            errors_num: ShardedCounter::new(),
+            queries_num: ShardedCounter::new(),
+            errors_iter_num: ShardedCounter::new(),
+            queries_iter_num: ShardedCounter::new(),
+            retries_num: ShardedCounter::new(),
+            histogram: Arc::new(config.new_histogram()),
+            window_histogram: config
+                .sliding_window
+                .map(|(length, slot)| SlidingWindowHistogram::new(config, length, slot)),
+            snapshot_percentiles: config.percentiles.clone(),
+            node_histograms: ArcSwap::default(),
+            node_connection_gauges: ArcSwap::default(),
             meter: Arc::new(RequestRateMeter::new()),
             total_connections: AtomicU64::new(0),
             connection_timeouts: AtomicU64::new(0),
             request_timeouts: AtomicU64::new(0),
+            shard_aware_connections_on_target_shard: AtomicU64::new(0),
+            shard_aware_connections_off_target_shard: AtomicU64::new(0),
+            reconnect_attempts: AtomicU64::new(0),
+            reconnect_successes: AtomicU64::new(0),
+            keepalive_requests: AtomicU64::new(0),
+            keepalive_failures: AtomicU64::new(0),
+            saturated_node_skips: AtomicU64::new(0),
+            rate_limited_requests: AtomicU64::new(0),
+            rate_limited_requests_per_table: Mutex::new(HashMap::new()),
+            retry_decisions: Mutex::new(HashMap::new()),
+            speculative_executions: ShardedCounter::new(),
+            write_flushes: ShardedCounter::new(),
+            write_flushed_requests: ShardedCounter::new(),
+            write_flushed_bytes: ShardedCounter::new(),
+            write_coalescing_delay_us: ShardedCounter::new(),
+            compressed_requests: ShardedCounter::new(),
+            request_bytes_before_compression: ShardedCounter::new(),
+            request_bytes_after_compression: ShardedCounter::new(),
         }
     }
 
     /// Increments counter for errors that occurred in nonpaged queries.
     pub(crate) fn inc_failed_nonpaged_queries(&self) {
-        self.errors_num.fetch_add(1, ORDER_TYPE);
+        self.errors_num.add(1);
+        #[cfg(feature = "metrics-024")]
+        metrics_024::counter!("scylla.request_errors", "paged" => "false").increment(1);
     }
 
     /// Increments counter for nonpaged queries.
     pub(crate) fn inc_total_nonpaged_queries(&self) {
-        self.queries_num.fetch_add(1, ORDER_TYPE);
+        self.queries_num.add(1);
         self.meter.mark();
+        #[cfg(feature = "metrics-024")]
+        metrics_024::counter!("scylla.requests", "paged" => "false").increment(1);
     }
 
     /// Increments counter for errors that occurred in paged queries.
     pub(crate) fn inc_failed_paged_queries(&self) {
-        self.errors_iter_num.fetch_add(1, ORDER_TYPE);
+        self.errors_iter_num.add(1);
+        #[cfg(feature = "metrics-024")]
+        metrics_024::counter!("scylla.request_errors", "paged" => "true").increment(1);
     }
 
     /// Increments counter for page queries in paged queries.
     /// If query_iter would return 4 pages then this counter should be incremented 4 times.
     pub(crate) fn inc_total_paged_queries(&self) {
-        self.queries_iter_num.fetch_add(1, ORDER_TYPE);
+        self.queries_iter_num.add(1);
         self.meter.mark();
+        #[cfg(feature = "metrics-024")]
+        metrics_024::counter!("scylla.requests", "paged" => "true").increment(1);
     }
 
     /// Increments counter measuring how many times a retry policy has decided to retry a query
     pub(crate) fn inc_retries_num(&self) {
-        self.retries_num.fetch_add(1, ORDER_TYPE);
+        self.retries_num.add(1);
+        #[cfg(feature = "metrics-024")]
+        metrics_024::counter!("scylla.retries").increment(1);
     }
 
     /// Increments counter for active number of connections to the cluster.
     /// Should be called when opening new connections, once per connection.
     pub(crate) fn inc_total_connections(&self) {
         self.total_connections.fetch_add(1, ORDER_TYPE);
+        #[cfg(feature = "metrics-024")]
+        metrics_024::gauge!("scylla.connections").increment(1);
     }
 
     /// Decrements counter for number of active connections to the cluster.
     /// Should be called when closing the connections, once per connection.
     pub(crate) fn dec_total_connections(&self) {
         self.total_connections.fetch_sub(1, ORDER_TYPE);
+        #[cfg(feature = "metrics-024")]
+        metrics_024::gauge!("scylla.connections").decrement(1);
     }
 
     /// Increments counter for timeouts for new connections to the cluster.
     pub(crate) fn inc_connection_timeouts(&self) {
         self.connection_timeouts.fetch_add(1, ORDER_TYPE);
+        #[cfg(feature = "metrics-024")]
+        metrics_024::counter!("scylla.connection_timeouts").increment(1);
     }
 
     /// Increments counter for client request timeouts.
     pub(crate) fn inc_request_timeouts(&self) {
         self.request_timeouts.fetch_add(1, ORDER_TYPE);
+        #[cfg(feature = "metrics-024")]
+        metrics_024::counter!("scylla.request_timeouts").increment(1);
+    }
+
+    /// Increments counter for shard-aware port connections which landed on the requested shard.
+    pub(crate) fn inc_shard_aware_connections_on_target_shard(&self) {
+        self.shard_aware_connections_on_target_shard
+            .fetch_add(1, ORDER_TYPE);
+        #[cfg(feature = "metrics-024")]
+        metrics_024::counter!("scylla.shard_aware_connections", "target_shard" => "hit")
+            .increment(1);
+    }
+
+    /// Increments counter for shard-aware port connections which failed or missed the requested shard.
+    pub(crate) fn inc_shard_aware_connections_off_target_shard(&self) {
+        self.shard_aware_connections_off_target_shard
+            .fetch_add(1, ORDER_TYPE);
+        #[cfg(feature = "metrics-024")]
+        metrics_024::counter!("scylla.shard_aware_connections", "target_shard" => "miss")
+            .increment(1);
+    }
+
+    /// Increments counter for connection attempts made when reconnecting after a failure.
+    pub(crate) fn inc_reconnect_attempts(&self) {
+        self.reconnect_attempts.fetch_add(1, ORDER_TYPE);
+        #[cfg(feature = "metrics-024")]
+        metrics_024::counter!("scylla.reconnect_attempts").increment(1);
+    }
+
+    /// Increments counter for successful connection attempts made when reconnecting after a failure.
+    pub(crate) fn inc_reconnect_successes(&self) {
+        self.reconnect_successes.fetch_add(1, ORDER_TYPE);
+        #[cfg(feature = "metrics-024")]
+        metrics_024::counter!("scylla.reconnect_successes").increment(1);
+    }
+
+    /// Increments counter for keepalive requests sent on connections.
+    pub(crate) fn inc_keepalive_requests(&self) {
+        self.keepalive_requests.fetch_add(1, ORDER_TYPE);
+        #[cfg(feature = "metrics-024")]
+        metrics_024::counter!("scylla.keepalive_requests").increment(1);
+    }
+
+    /// Increments counter for keepalive requests which failed or timed out.
+    pub(crate) fn inc_keepalive_failures(&self) {
+        self.keepalive_failures.fetch_add(1, ORDER_TYPE);
+        #[cfg(feature = "metrics-024")]
+        metrics_024::counter!("scylla.keepalive_failures").increment(1);
+    }
+
+    /// Increments counter for nodes moved to the end of query plans due to saturation.
+    pub(crate) fn inc_saturated_node_skips(&self) {
+        self.saturated_node_skips.fetch_add(1, ORDER_TYPE);
+        #[cfg(feature = "metrics-024")]
+        metrics_024::counter!("scylla.saturated_node_skips").increment(1);
+    }
+
+    /// Increments counters for request attempts rejected by per-partition rate limiting.
+    /// The per-table counter is incremented only if the table is known.
+    pub(crate) fn inc_rate_limited_requests(&self, table: Option<&TableSpec>) {
+        self.rate_limited_requests.fetch_add(1, ORDER_TYPE);
+        #[cfg(feature = "metrics-024")]
+        match table {
+            Some(table) => metrics_024::counter!(
+                "scylla.rate_limited_requests",
+                "keyspace" => table.ks_name().to_owned(),
+                "table" => table.table_name().to_owned(),
+            )
+            .increment(1),
+            None => metrics_024::counter!("scylla.rate_limited_requests").increment(1),
+        }
+        if let Some(table) = table {
+            let key = (table.ks_name().to_owned(), table.table_name().to_owned());
+            *self
+                .rate_limited_requests_per_table
+                .lock()
+                .unwrap()
+                .entry(key)
+                .or_insert(0) += 1;
+        }
+    }
+
+    /// Increments counter for retry decisions of the given kind, made after the given error.
+    pub(crate) fn log_retry_decision(&self, kind: RetryDecisionKind, error: &RequestAttemptError) {
+        #[cfg(feature = "metrics-024")]
+        metrics_024::counter!(
+            "scylla.retry_decisions",
+            "decision" => retry_decision_label(kind),
+            "error" => error_kind(error),
+        )
+        .increment(1);
+        *self
+            .retry_decisions
+            .lock()
+            .unwrap()
+            .entry((kind, error_kind(error)))
+            .or_insert(0) += 1;
+    }
+
+    /// Increments counter for speculative executions started.
+    pub(crate) fn inc_speculative_executions(&self) {
+        self.speculative_executions.add(1);
+        #[cfg(feature = "metrics-024")]
+        metrics_024::counter!("scylla.speculative_executions").increment(1);
+    }
+
+    /// Records a single flush of a connection writer.
+    ///
+    /// # Arguments
+    ///
+    /// * `requests` - number of requests written before the flush
+    /// * `bytes` - number of bytes written before the flush
+    /// * `coalescing_delay` - time spent waiting for more requests to coalesce
+    pub(crate) fn log_write_flush(
+        &self,
+        requests: usize,
+        bytes: usize,
+        coalescing_delay: Duration,
+    ) {
+        self.write_flushes.add(1);
+        #[cfg(feature = "metrics-024")]
+        {
+            metrics_024::counter!("scylla.write_flushes").increment(1);
+            metrics_024::counter!("scylla.write_flushed_bytes").increment(bytes as u64);
+        }
+        self.write_flushed_requests.add(requests as u64);
+        self.write_flushed_bytes.add(bytes as u64);
+        self.write_coalescing_delay_us
+            .add(coalescing_delay.as_micros().try_into().unwrap_or(u64::MAX));
+    }
+
+    /// Records the body lengths of a compressed request.
+    pub(crate) fn log_request_compression(
+        &self,
+        uncompressed_length: usize,
+        compressed_length: usize,
+    ) {
+        self.compressed_requests.add(1);
+        #[cfg(feature = "metrics-024")]
+        metrics_024::counter!("scylla.compressed_requests").increment(1);
+        self.request_bytes_before_compression
+            .add(uncompressed_length as u64);
+        self.request_bytes_after_compression
+            .add(compressed_length as u64);
     }
 
     /// Saves to histogram latency of completing single query.
@@ -322,6 +912,13 @@ impl Metrics {
     ///
     /// * `latency` - time in milliseconds that should be logged
     pub(crate) fn log_query_latency(&self, latency: u64) -> Result<(), MetricsError> {
+        #[cfg(feature = "metrics-024")]
+        metrics_024::histogram!("scylla.request_latency").record(latency as f64);
+        if let Some(window_histogram) = &self.window_histogram {
+            window_histogram
+                .increment(latency)
+                .map_err(|err| MetricsError::HistogramError(Arc::new(err)))?;
+        }
         if let Err(err) = self.histogram.increment(latency) {
             Err(MetricsError::HistogramError(Arc::new(err)))
         } else {
@@ -329,6 +926,50 @@ impl Metrics {
         }
     }
 
+    /// Saves to the histogram of the given node latency of completing single query on it.
+    ///
+    /// # Arguments
+    ///
+    /// * `host_id` - ID of the node which executed the query
+    /// * `latency` - time in milliseconds that should be logged
+    pub(crate) fn log_node_latency(&self, host_id: Uuid, latency: u64) -> Result<(), MetricsError> {
+        #[cfg(feature = "metrics-024")]
+        metrics_024::histogram!("scylla.node_latency", "host_id" => host_id.to_string())
+            .record(latency as f64);
+        let mut node_histograms = self.node_histograms.load();
+        if !node_histograms.contains_key(&host_id) {
+            // Per-node histograms are smaller than the global one (about 10 KiB each),
+            // at the cost of a relative error of about 1%.
+            let new_histogram = Arc::new(AtomicHistogram::new(7, 16).unwrap());
+            // New nodes are rare, so the map is copied on insertion,
+            // which keeps recording latencies free of locks.
+            self.node_histograms.rcu(|node_histograms| {
+                let mut node_histograms = HashMap::clone(node_histograms);
+                node_histograms
+                    .entry(host_id)
+                    .or_insert_with(|| Arc::clone(&new_histogram));
+                node_histograms
+            });
+            node_histograms = self.node_histograms.load();
+        }
+        node_histograms[&host_id]
+            .increment(latency)
+            .map_err(|err| MetricsError::HistogramError(Arc::new(err)))
+    }
+
+    /// Returns the gauges of connections to the given node, creating them if needed.
+    pub(crate) fn node_connection_gauges(&self, host_id: Uuid) -> Arc<NodeConnectionGauges> {
+        if let Some(gauges) = self.node_connection_gauges.load().get(&host_id) {
+            return Arc::clone(gauges);
+        }
+        self.node_connection_gauges.rcu(|node_connection_gauges| {
+            let mut node_connection_gauges = HashMap::clone(node_connection_gauges);
+            node_connection_gauges.entry(host_id).or_default();
+            node_connection_gauges
+        });
+        Arc::clone(&self.node_connection_gauges.load()[&host_id])
+    }
+
     /// Returns average latency in milliseconds
     pub fn get_latency_avg_ms(&self) -> Result<u64, MetricsError> {
         Self::mean(&self.histogram.load())
@@ -339,28 +980,99 @@ impl Metrics {
     ///
     /// * `percentile` - float value (0.0 - 100.0)
     pub fn get_latency_percentile_ms(&self, percentile: f64) -> Result<u64, MetricsError> {
-        let res = self.histogram.load().percentile(percentile);
+        Self::percentile(&self.histogram.load(), percentile)
+    }
 
-        match res {
-            Err(err) => Err(MetricsError::HistogramError(Arc::new(err))),
+    /// Returns latency of queries executed on the given node for a given percentile.
+    /// # Arguments
+    ///
+    /// * `host_id` - ID of the node
+    /// * `percentile` - float value (0.0 - 100.0)
+    pub fn get_node_latency_percentile_ms(
+        &self,
+        host_id: Uuid,
+        percentile: f64,
+    ) -> Result<u64, MetricsError> {
+        let histogram = self.node_histograms.load().get(&host_id).cloned();
+        match histogram {
+            Some(histogram) => Self::percentile(&histogram.load(), percentile),
+            None => Err(MetricsError::Empty),
+        }
+    }
 
-            Ok(None) => Err(MetricsError::Empty),
+    /// Returns the number of latencies of queries executed on the given node
+    /// collected so far.
+    pub fn get_node_latency_count(&self, host_id: Uuid) -> u64 {
+        let histogram = self.node_histograms.load().get(&host_id).cloned();
+        histogram.map_or(0, |histogram| {
+            histogram
+                .load()
+                .into_iter()
+                .map(|bucket| bucket.count())
+                .sum()
+        })
+    }
 
-            Ok(Some(p)) => Ok(p.count()),
-        }
+    /// Returns the current gauges of connections to the given node.
+    pub fn get_node_connection_gauges(&self, host_id: Uuid) -> ConnectionGauges {
+        self.node_connection_gauges
+            .load()
+            .get(&host_id)
+            .map(|gauges| gauges.load())
+            .unwrap_or_default()
+    }
+
+    /// Returns the current gauges of connections to all nodes, summed up.
+    pub fn get_connection_gauges(&self) -> ConnectionGauges {
+        self.node_connection_gauges.load().values().fold(
+            ConnectionGauges::default(),
+            |total, gauges| {
+                let gauges = gauges.load();
+                ConnectionGauges {
+                    open_connections: total.open_connections + gauges.open_connections,
+                    stream_ids_in_use: total.stream_ids_in_use + gauges.stream_ids_in_use,
+                    stream_ids_available: total.stream_ids_available + gauges.stream_ids_available,
+                    queued_requests: total.queued_requests + gauges.queued_requests,
+                    unflushed_requests: total.unflushed_requests + gauges.unflushed_requests,
+                }
+            },
+        )
     }
 
     /// Returns snapshot of histogram metrics taken at the moment of calling this function. \
     /// Available metrics: min, max, mean, std_dev, median,
     ///                    percentile_75, percentile_95, percentile_98,
-    ///                    percentile_99, and percentile_99_9.
+    ///                    percentile_99, percentile_99_9, and the percentiles
+    ///                    configured with [`LatencyHistogramConfig::with_percentiles`].
     pub fn get_snapshot(&self) -> Result<Snapshot, MetricsError> {
-        let h = self.histogram.load();
+        self.snapshot(&self.histogram.load())
+    }
+
+    /// Returns snapshot of histogram metrics of latencies collected in the given window
+    /// of recent time, with the granularity of sliding window slots.
+    ///
+    /// Requires a sliding window to be enabled with [`LatencyHistogramConfig::with_sliding_window`]
+    /// and to be at least as long as `window`. Otherwise, [`MetricsError::WindowUnavailable`] is returned.
+    pub fn get_window_snapshot(&self, window: Duration) -> Result<Snapshot, MetricsError> {
+        let window_histogram = self
+            .window_histogram
+            .as_ref()
+            .filter(|window_histogram| window <= window_histogram.length())
+            .ok_or_else(|| MetricsError::WindowUnavailable {
+                requested: window,
+                available: self
+                    .window_histogram
+                    .as_ref()
+                    .map(SlidingWindowHistogram::length),
+            })?;
+        self.snapshot(&window_histogram.load(window)?)
+    }
 
-        let (min, max) = Self::minmax(&h)?;
+    fn snapshot(&self, h: &Histogram) -> Result<Snapshot, MetricsError> {
+        let (min, max) = Self::minmax(h)?;
 
         let percentile_args = [50.0, 75.0, 95.0, 98.0, 99.0, 99.9];
-        let mut percentiles = Self::percentiles(&h, &percentile_args)?;
+        let mut percentiles = Self::percentiles(h, &percentile_args)?;
 
         // SAFETY: `unwrap()`s are OK here, because `Self::percentiles()` returned iterator's length
         // is equal to number of elements in `percentile_args`.
@@ -374,40 +1086,49 @@ impl Metrics {
         Ok(Snapshot {
             min,
             max,
-            mean: Self::mean(&h)?,
-            stddev: Self::stddev(&h)?,
+            mean: Self::mean(h)?,
+            stddev: Self::stddev(h)?,
             median,
             percentile_75,
             percentile_95,
             percentile_98,
             percentile_99,
             percentile_99_9,
+            percentiles: if self.snapshot_percentiles.is_empty() {
+                Vec::new()
+            } else {
+                self.snapshot_percentiles
+                    .iter()
+                    .copied()
+                    .zip(Self::percentiles(h, &self.snapshot_percentiles)?)
+                    .collect()
+            },
         })
     }
 
     /// Returns counter for errors occurred in nonpaged queries
     pub fn get_errors_num(&self) -> u64 {
-        self.errors_num.load(ORDER_TYPE)
+        self.errors_num.load()
     }
 
     /// Returns counter for nonpaged queries
     pub fn get_queries_num(&self) -> u64 {
-        self.queries_num.load(ORDER_TYPE)
+        self.queries_num.load()
     }
 
     /// Returns counter for errors occurred in paged queries
     pub fn get_errors_iter_num(&self) -> u64 {
-        self.errors_iter_num.load(ORDER_TYPE)
+        self.errors_iter_num.load()
     }
 
     /// Returns counter for pages requested in paged queries
     pub fn get_queries_iter_num(&self) -> u64 {
-        self.queries_iter_num.load(ORDER_TYPE)
+        self.queries_iter_num.load()
     }
 
     /// Returns counter measuring how many times a retry policy has decided to retry a query
     pub fn get_retries_num(&self) -> u64 {
-        self.retries_num.load(ORDER_TYPE)
+        self.retries_num.load()
     }
 
     /// Returns mean rate of queries per second
@@ -445,6 +1166,127 @@ impl Metrics {
         self.request_timeouts.load(ORDER_TYPE)
     }
 
+    /// Returns counter for connections to the shard-aware port which landed on the requested shard
+    pub fn get_shard_aware_connections_on_target_shard(&self) -> u64 {
+        self.shard_aware_connections_on_target_shard
+            .load(ORDER_TYPE)
+    }
+
+    /// Returns counter for connections to the shard-aware port which failed
+    /// or landed on a shard other than the requested one
+    pub fn get_shard_aware_connections_off_target_shard(&self) -> u64 {
+        self.shard_aware_connections_off_target_shard
+            .load(ORDER_TYPE)
+    }
+
+    /// Returns counter for connection attempts made when reconnecting to nodes after a failure,
+    /// as scheduled by the [ReconnectPolicy](crate::policies::reconnect::ReconnectPolicy)
+    pub fn get_reconnect_attempts(&self) -> u64 {
+        self.reconnect_attempts.load(ORDER_TYPE)
+    }
+
+    /// Returns counter for successful connection attempts made when reconnecting to nodes
+    /// after a failure
+    pub fn get_reconnect_successes(&self) -> u64 {
+        self.reconnect_successes.load(ORDER_TYPE)
+    }
+
+    /// Returns counter for keepalive requests sent on connections
+    pub fn get_keepalive_requests(&self) -> u64 {
+        self.keepalive_requests.load(ORDER_TYPE)
+    }
+
+    /// Returns counter for keepalive requests which failed or timed out
+    pub fn get_keepalive_failures(&self) -> u64 {
+        self.keepalive_failures.load(ORDER_TYPE)
+    }
+
+    /// Returns counter for nodes moved to the end of query plans, because they reached
+    /// the limit of requests in flight set with
+    /// [`SessionBuilder::max_in_flight_requests_per_node`](crate::client::session_builder::SessionBuilder::max_in_flight_requests_per_node)
+    pub fn get_saturated_node_skips(&self) -> u64 {
+        self.saturated_node_skips.load(ORDER_TYPE)
+    }
+
+    /// Returns counter for request attempts rejected by ScyllaDB's per-partition rate limiting
+    pub fn get_rate_limited_requests(&self) -> u64 {
+        self.rate_limited_requests.load(ORDER_TYPE)
+    }
+
+    /// Returns counter for request attempts against the given table
+    /// rejected by ScyllaDB's per-partition rate limiting
+    pub fn get_rate_limited_requests_for_table(&self, keyspace: &str, table: &str) -> u64 {
+        self.rate_limited_requests_per_table
+            .lock()
+            .unwrap()
+            .get(&(keyspace.to_owned(), table.to_owned()))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Returns counters for decisions made by retry policies, by decision kind
+    /// and kind of the error (e.g. `"ReadTimeout"`, `"BrokenConnectionError"`).
+    ///
+    /// Every failed attempt results in exactly one decision, so together with
+    /// [`Metrics::get_speculative_executions`] it explains why the number of attempts
+    /// is higher than the number of requests.
+    pub fn get_retry_decisions(&self) -> HashMap<(RetryDecisionKind, &'static str), u64> {
+        self.retry_decisions.lock().unwrap().clone()
+    }
+
+    /// Returns counter for speculative executions started
+    pub fn get_speculative_executions(&self) -> u64 {
+        self.speculative_executions.load()
+    }
+
+    /// Returns counter for flushes done by connection writers
+    pub fn get_write_flushes(&self) -> u64 {
+        self.write_flushes.load()
+    }
+
+    /// Returns counter for requests written in flushes done by connection writers.
+    /// Divided by [`Metrics::get_write_flushes`], gives the average number of requests per flush.
+    pub fn get_write_flushed_requests(&self) -> u64 {
+        self.write_flushed_requests.load()
+    }
+
+    /// Returns counter for bytes written in flushes done by connection writers.
+    /// Divided by [`Metrics::get_write_flushes`], gives the average flush size.
+    pub fn get_write_flushed_bytes(&self) -> u64 {
+        self.write_flushed_bytes.load()
+    }
+
+    /// Returns total time in microseconds spent by connection writers on write coalescing delays
+    pub fn get_write_coalescing_delay_us(&self) -> u64 {
+        self.write_coalescing_delay_us.load()
+    }
+
+    /// Returns counter for requests sent with a compressed body
+    pub fn get_compressed_requests(&self) -> u64 {
+        self.compressed_requests.load()
+    }
+
+    /// Returns total length of bodies of compressed requests, before compression
+    pub fn get_request_bytes_before_compression(&self) -> u64 {
+        self.request_bytes_before_compression.load()
+    }
+
+    /// Returns total length of bodies of compressed requests, after compression
+    pub fn get_request_bytes_after_compression(&self) -> u64 {
+        self.request_bytes_after_compression.load()
+    }
+
+    /// Returns the ratio of compressed to uncompressed length of bodies of compressed requests.
+    /// The lower it is, the more effective the compression.
+    /// Returns 1.0 if no request was compressed yet.
+    pub fn get_request_compression_ratio(&self) -> f64 {
+        let before = self.get_request_bytes_before_compression();
+        if before == 0 {
+            return 1.0;
+        }
+        self.get_request_bytes_after_compression() as f64 / before as f64
+    }
+
     // Metric implementations
 
     // histogram crate used to implement Histogram::mean() method. Why did they remove it?
@@ -467,10 +1309,20 @@ impl Metrics {
             count += bucket.count() as u128;
         }
 
-        if count != 0 {
-            Ok((weighted_sum / count) as u64)
-        } else {
-            Err(MetricsError::Empty)
+        weighted_sum
+            .checked_div(count)
+            .map(|mean| mean as u64)
+            .ok_or(MetricsError::Empty)
+    }
+
+    fn percentile(h: &Histogram, percentile: f64) -> Result<u64, MetricsError> {
+        match h.percentile(percentile) {
+            Err(err) => Err(MetricsError::HistogramError(Arc::new(err))),
+
+            Ok(None) => Err(MetricsError::Empty),
+
+            // Get the mean value from the bucket.
+            Ok(Some(bucket)) => Ok((bucket.start() + bucket.end()) / 2),
         }
     }
 
@@ -533,6 +1385,160 @@ impl Metrics {
     }
 }
 
+/// Name of the kind of the error, used to label retry decision counters.
+fn error_kind(error: &RequestAttemptError) -> &'static str {
+    match error {
+        RequestAttemptError::DbError(db_error, _) => match db_error {
+            DbError::SyntaxError => "SyntaxError",
+            DbError::Invalid => "Invalid",
+            DbError::AlreadyExists { .. } => "AlreadyExists",
+            DbError::FunctionFailure { .. } => "FunctionFailure",
+            DbError::AuthenticationError => "AuthenticationError",
+            DbError::Unauthorized => "Unauthorized",
+            DbError::ConfigError => "ConfigError",
+            DbError::Unavailable { .. } => "Unavailable",
+            DbError::Overloaded => "Overloaded",
+            DbError::IsBootstrapping => "IsBootstrapping",
+            DbError::TruncateError => "TruncateError",
+            DbError::ReadTimeout { .. } => "ReadTimeout",
+            DbError::WriteTimeout { .. } => "WriteTimeout",
+            DbError::ReadFailure { .. } => "ReadFailure",
+            DbError::WriteFailure { .. } => "WriteFailure",
+            DbError::Unprepared { .. } => "Unprepared",
+            DbError::ServerError => "ServerError",
+            DbError::ProtocolError => "ProtocolError",
+            DbError::RateLimitReached { .. } => "RateLimitReached",
+            DbError::Other(_) | _ => "OtherDbError",
+        },
+        RequestAttemptError::SerializationError(_) => "SerializationError",
+        RequestAttemptError::CqlRequestSerialization(_) => "CqlRequestSerialization",
+        RequestAttemptError::UnableToAllocStreamId => "UnableToAllocStreamId",
+        RequestAttemptError::BrokenConnectionError(_) => "BrokenConnectionError",
+        RequestAttemptError::BodyExtensionsParseError(_) => "BodyExtensionsParseError",
+        RequestAttemptError::CqlResultParseError(_) => "CqlResultParseError",
+        RequestAttemptError::CqlErrorParseError(_) => "CqlErrorParseError",
+        RequestAttemptError::UnexpectedResponse(_) => "UnexpectedResponse",
+        RequestAttemptError::RepreparedIdChanged { .. } => "RepreparedIdChanged",
+        RequestAttemptError::RepreparedIdMissingInBatch => "RepreparedIdMissingInBatch",
+        RequestAttemptError::NonfinishedPagingState => "NonfinishedPagingState",
+    }
+}
+
+#[cfg(feature = "metrics-024")]
+fn retry_decision_label(kind: RetryDecisionKind) -> &'static str {
+    match kind {
+        RetryDecisionKind::RetrySameTarget => "RetrySameTarget",
+        RetryDecisionKind::RetryNextTarget => "RetryNextTarget",
+        RetryDecisionKind::Downgrade => "Downgrade",
+        RetryDecisionKind::DontRetry => "DontRetry",
+        RetryDecisionKind::IgnoreWriteError => "IgnoreWriteError",
+    }
+}
+
+/// Increments the published counter of state changes of circuit breakers
+/// of [CircuitBreakerPolicy](crate::policies::load_balancing::CircuitBreakerPolicy).
+///
+/// The policy is not tied to a session, so the counter is only published
+/// and not kept in [Metrics].
+#[cfg(feature = "metrics-024")]
+pub(crate) fn inc_circuit_breaker_transitions(new_state: CircuitState) {
+    let state = match new_state {
+        CircuitState::Closed => "Closed",
+        CircuitState::Open => "Open",
+        CircuitState::HalfOpen => "HalfOpen",
+    };
+    metrics_024::counter!("scylla.circuit_breaker_transitions", "state" => state).increment(1);
+}
+
+/// Registers descriptions of the metrics published through the facade
+/// of the `metrics` crate in the currently installed recorder.
+#[cfg(feature = "metrics-024")]
+fn describe_published_metrics() {
+    use metrics_024::{Unit, describe_counter, describe_gauge, describe_histogram};
+
+    describe_counter!(
+        "scylla.requests",
+        "Number of requests executed, and of pages fetched by pagers"
+    );
+    describe_counter!("scylla.request_errors", "Number of failed requests");
+    describe_histogram!(
+        "scylla.request_latency",
+        Unit::Milliseconds,
+        "Latencies of requests"
+    );
+    describe_histogram!(
+        "scylla.node_latency",
+        Unit::Milliseconds,
+        "Latencies of requests, by the node which executed them"
+    );
+    describe_counter!(
+        "scylla.retries",
+        "Number of times a retry policy decided to retry a request"
+    );
+    describe_counter!(
+        "scylla.retry_decisions",
+        "Number of retry decisions, by decision and kind of the error"
+    );
+    describe_counter!(
+        "scylla.speculative_executions",
+        "Number of speculative executions started"
+    );
+    describe_counter!(
+        "scylla.rate_limited_requests",
+        "Number of request attempts rejected by per-partition rate limiting"
+    );
+    describe_counter!(
+        "scylla.request_timeouts",
+        "Number of client request timeouts"
+    );
+    describe_gauge!("scylla.connections", "Number of open connections");
+    describe_counter!(
+        "scylla.connection_timeouts",
+        "Number of timeouts when opening connections"
+    );
+    describe_counter!(
+        "scylla.shard_aware_connections",
+        "Number of connections to the shard-aware port, by whether they landed on the requested shard"
+    );
+    describe_counter!(
+        "scylla.reconnect_attempts",
+        "Number of connection attempts made when reconnecting"
+    );
+    describe_counter!(
+        "scylla.reconnect_successes",
+        "Number of successful connection attempts made when reconnecting"
+    );
+    describe_counter!(
+        "scylla.keepalive_requests",
+        "Number of keepalive requests sent"
+    );
+    describe_counter!(
+        "scylla.keepalive_failures",
+        "Number of keepalive requests which failed or timed out"
+    );
+    describe_counter!(
+        "scylla.saturated_node_skips",
+        "Number of times a saturated node was moved to the end of a query plan"
+    );
+    describe_counter!(
+        "scylla.circuit_breaker_transitions",
+        "Number of state changes of per-node circuit breakers, by the new state"
+    );
+    describe_counter!(
+        "scylla.write_flushes",
+        "Number of times connection writers flushed data to their sockets"
+    );
+    describe_counter!(
+        "scylla.write_flushed_bytes",
+        Unit::Bytes,
+        "Number of bytes written by connection writers"
+    );
+    describe_counter!(
+        "scylla.compressed_requests",
+        "Number of requests sent with a compressed body"
+    );
+}
+
 #[cfg(test)]
 impl Default for Metrics {
     fn default() -> Self {
@@ -564,7 +1570,13 @@ mod tests {
 
     use crate::observability::metrics::Snapshot;
 
-    use super::Metrics;
+    use super::{
+        ConnectionGauge, ConnectionGauges, DbError, LatencyHistogramConfig, Metrics, MetricsError,
+        RequestAttemptError, RetryDecisionKind, ShardedCounter, TableSpec,
+    };
+    use std::sync::Arc;
+    use std::time::Duration;
+    use uuid::Uuid;
 
     // A regression test for a bug where we would return
     // the number of observations in the bucket for the given percentile.
@@ -588,6 +1600,7 @@ mod tests {
             percentile_98,
             percentile_99,
             percentile_99_9,
+            percentiles,
         } = metrics.get_snapshot().unwrap();
 
         assert_eq!(min, 0);
@@ -602,6 +1615,204 @@ mod tests {
         assert_eq!(percentile_98, 0);
         assert_eq!(percentile_99, 0);
         assert_eq!(percentile_99_9, 0);
+        assert!(percentiles.is_empty());
+        assert_eq!(metrics.get_latency_percentile_ms(99.0).unwrap(), 0);
+    }
+
+    #[test]
+    fn window_snapshots_cover_recent_latencies_only() {
+        let slot = Duration::from_secs(60);
+        // Latencies below 2^7 are recorded exactly with this precision.
+        let config = LatencyHistogramConfig::new()
+            .with_precision(7, 16)
+            .unwrap()
+            .with_sliding_window(5 * slot, slot)
+            .with_percentiles([10.0, 90.0]);
+        let mut metrics = Metrics::with_latency_histogram_config(&config);
+
+        // Simulates the passage of time by moving the start of the window back.
+        let advance = |metrics: &mut Metrics, by: Duration| {
+            let window_histogram = metrics.window_histogram.as_mut().unwrap();
+            window_histogram.start = window_histogram.start.checked_sub(by).unwrap();
+        };
+
+        for _ in 0..10 {
+            metrics.log_query_latency(100).unwrap();
+        }
+        advance(&mut metrics, 3 * slot);
+        for _ in 0..10 {
+            metrics.log_query_latency(10).unwrap();
+        }
+
+        let last_slot = metrics.get_window_snapshot(slot).unwrap();
+        assert_eq!(last_slot.max, 10);
+        let last_five_slots = metrics.get_window_snapshot(5 * slot).unwrap();
+        assert_eq!(last_five_slots.max, 100);
+        assert_eq!(last_five_slots.percentiles.len(), 2);
+        assert_eq!(last_five_slots.percentiles[0], (10.0, 10));
+        assert_eq!(last_five_slots.percentiles[1], (90.0, 100));
+
+        // The cumulative histogram still holds all latencies.
+        assert_eq!(metrics.get_snapshot().unwrap().max, 100);
+
+        // Latencies recorded in a slot are dropped once the ring wraps around to it.
+        advance(&mut metrics, 3 * slot);
+        metrics.log_query_latency(20).unwrap();
+        assert_eq!(metrics.get_window_snapshot(5 * slot).unwrap().max, 20);
+
+        assert!(matches!(
+            metrics.get_window_snapshot(6 * slot),
+            Err(MetricsError::WindowUnavailable { .. })
+        ));
+        assert!(matches!(
+            Metrics::new().get_window_snapshot(slot),
+            Err(MetricsError::WindowUnavailable {
+                available: None,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn retry_decisions_are_counted_by_kind() {
+        let metrics = Metrics::new();
+        let overloaded = RequestAttemptError::DbError(DbError::Overloaded, String::new());
+
+        metrics.log_retry_decision(RetryDecisionKind::RetryNextTarget, &overloaded);
+        metrics.log_retry_decision(RetryDecisionKind::RetryNextTarget, &overloaded);
+        metrics.log_retry_decision(
+            RetryDecisionKind::DontRetry,
+            &RequestAttemptError::UnableToAllocStreamId,
+        );
+
+        let decisions = metrics.get_retry_decisions();
+        assert_eq!(decisions.len(), 2);
+        assert_eq!(
+            decisions[&(RetryDecisionKind::RetryNextTarget, "Overloaded")],
+            2
+        );
+        assert_eq!(
+            decisions[&(RetryDecisionKind::DontRetry, "UnableToAllocStreamId")],
+            1
+        );
+    }
+
+    #[test]
+    fn rate_limited_requests_are_counted_per_table() {
+        let metrics = Metrics::new();
+        let table = TableSpec::borrowed("ks", "t");
+
+        metrics.inc_rate_limited_requests(Some(&table));
+        metrics.inc_rate_limited_requests(Some(&table));
+        metrics.inc_rate_limited_requests(None);
+
+        assert_eq!(metrics.get_rate_limited_requests(), 3);
+        assert_eq!(metrics.get_rate_limited_requests_for_table("ks", "t"), 2);
+        assert_eq!(
+            metrics.get_rate_limited_requests_for_table("ks", "other"),
+            0
+        );
+    }
+
+    #[test]
+    fn node_latencies_are_collected_separately() {
+        let metrics = Metrics::new();
+        let fast_node = uuid::Uuid::new_v4();
+        let slow_node = uuid::Uuid::new_v4();
+
+        for _ in 0..100 {
+            metrics.log_node_latency(fast_node, 10).unwrap();
+            metrics.log_node_latency(slow_node, 1000).unwrap();
+        }
+
+        assert_eq!(metrics.get_node_latency_count(fast_node), 100);
+        assert_eq!(
+            metrics
+                .get_node_latency_percentile_ms(fast_node, 99.0)
+                .unwrap(),
+            10
+        );
+        let slow_p99 = metrics
+            .get_node_latency_percentile_ms(slow_node, 99.0)
+            .unwrap();
+        assert!(slow_p99.abs_diff(1000) <= 10);
+
+        let unknown_node = uuid::Uuid::new_v4();
+        assert_eq!(metrics.get_node_latency_count(unknown_node), 0);
+        assert!(
+            metrics
+                .get_node_latency_percentile_ms(unknown_node, 99.0)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn sharded_counter_sums_updates_from_all_threads() {
+        let counter = ShardedCounter::new();
+
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    for _ in 0..1000 {
+                        counter.add(2);
+                    }
+                });
+            }
+        });
+        assert_eq!(counter.load(), 16000);
+
+        assert_eq!(counter.take(), 16000);
+        assert_eq!(counter.load(), 0);
+    }
+
+    #[test]
+    fn connection_gauges_are_withdrawn_with_guards() {
+        let metrics = Metrics::new();
+        let (node_a, node_b) = (Uuid::new_v4(), Uuid::new_v4());
+        let gauges_a = metrics.node_connection_gauges(node_a);
+        assert!(Arc::ptr_eq(
+            &gauges_a,
+            &metrics.node_connection_gauges(node_a)
+        ));
+        let gauges_b = metrics.node_connection_gauges(node_b);
+
+        let mut open_a = gauges_a.track(ConnectionGauge::OpenConnections);
+        open_a.add(1);
+        let mut open_b = gauges_b.track(ConnectionGauge::OpenConnections);
+        open_b.add(1);
+        let mut stream_ids_a = gauges_a.track(ConnectionGauge::StreamIdsInUse);
+        stream_ids_a.add(3);
+        stream_ids_a.sub(1);
+        let mut queued_b = gauges_b.track(ConnectionGauge::QueuedRequests);
+        queued_b.add(5);
+
+        let expected_a = ConnectionGauges {
+            open_connections: 1,
+            stream_ids_in_use: 2,
+            stream_ids_available: 32766,
+            queued_requests: 0,
+            unflushed_requests: 0,
+        };
+        assert_eq!(metrics.get_node_connection_gauges(node_a), expected_a);
+        assert_eq!(
+            metrics.get_connection_gauges(),
+            ConnectionGauges {
+                open_connections: 2,
+                stream_ids_available: 32766 + 32768,
+                queued_requests: 5,
+                ..expected_a
+            }
+        );
+
+        // Dropping the guards of a closed connection withdraws all its contributions.
+        drop((open_b, queued_b));
+        assert_eq!(metrics.get_connection_gauges(), expected_a);
+        drop((open_a, stream_ids_a));
+        assert_eq!(metrics.get_connection_gauges(), ConnectionGauges::default());
+        assert_eq!(
+            metrics.get_node_connection_gauges(Uuid::new_v4()),
+            ConnectionGauges::default()
+        );
     }
 
     #[test]
@@ -640,4 +1851,79 @@ mod tests {
         test_with_seed(42);
         test_with_seed(0xDEADCAFE);
     }
+
+    #[cfg(feature = "metrics-024")]
+    #[test]
+    fn metrics_are_published_to_installed_recorder() {
+        use super::{CircuitState, inc_circuit_breaker_transitions};
+        use metrics_util_020::debugging::{DebugValue, DebuggingRecorder};
+
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+
+        metrics_024::with_local_recorder(&recorder, || {
+            let metrics = Metrics::new();
+            metrics.inc_total_nonpaged_queries();
+            metrics.inc_total_nonpaged_queries();
+            metrics.inc_total_paged_queries();
+            metrics.inc_total_connections();
+            metrics.inc_total_connections();
+            metrics.dec_total_connections();
+            metrics.log_query_latency(12).unwrap();
+            metrics.inc_rate_limited_requests(Some(&TableSpec::borrowed("ks", "tab")));
+            metrics.log_retry_decision(
+                RetryDecisionKind::DontRetry,
+                &RequestAttemptError::UnableToAllocStreamId,
+            );
+            inc_circuit_breaker_transitions(CircuitState::Open);
+        });
+
+        let published = snapshotter
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .map(|(key, _unit, _description, value)| {
+                let key = key.key();
+                let labels = key
+                    .labels()
+                    .map(|label| format!("{}={}", label.key(), label.value()))
+                    .collect::<Vec<_>>()
+                    .join(",");
+                ((key.name().to_owned(), labels), value)
+            })
+            .collect::<std::collections::HashMap<_, _>>();
+        let value_of = |name: &str, labels: &str| &published[&(name.to_owned(), labels.to_owned())];
+
+        assert_eq!(
+            value_of("scylla.requests", "paged=false"),
+            &DebugValue::Counter(2)
+        );
+        assert_eq!(
+            value_of("scylla.requests", "paged=true"),
+            &DebugValue::Counter(1)
+        );
+        assert_eq!(
+            value_of("scylla.connections", ""),
+            &DebugValue::Gauge(1.0.into())
+        );
+        assert_eq!(
+            value_of("scylla.request_latency", ""),
+            &DebugValue::Histogram(vec![12.0.into()])
+        );
+        assert_eq!(
+            value_of("scylla.rate_limited_requests", "keyspace=ks,table=tab"),
+            &DebugValue::Counter(1)
+        );
+        assert_eq!(
+            value_of(
+                "scylla.retry_decisions",
+                "decision=DontRetry,error=UnableToAllocStreamId"
+            ),
+            &DebugValue::Counter(1)
+        );
+        assert_eq!(
+            value_of("scylla.circuit_breaker_transitions", "state=Open"),
+            &DebugValue::Counter(1)
+        );
+    }
 }
@@ -3,10 +3,20 @@
 //! - driver-side tracing,
 //! - cluster-side tracing,
 //! - request execution history,
-//! - driver metrics.
+//! - driver metrics,
+//! - observing requests executed by the session,
+//! - instrumenting requests with spans for distributed tracing,
+//! - logging slow requests,
+//! - targets of the driver's log events,
+//! - driver self-diagnostics.
 
+pub mod diagnostics;
 pub(crate) mod driver_tracing;
 pub mod history;
+pub mod instrumentation;
+pub mod log_targets;
 #[cfg(feature = "metrics")]
 pub mod metrics;
+pub mod request_observer;
+pub mod slow_query_log;
 pub mod tracing;
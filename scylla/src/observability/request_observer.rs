@@ -0,0 +1,114 @@
+//! Observing requests executed by a [Session](crate::client::session::Session).
+//!
+//! A [RequestObserver] set with
+//! [SessionBuilder::request_observer](crate::client::session_builder::SessionBuilder::request_observer)
+//! is notified when every request starts, when each of its attempts completes, and when
+//! the request completes. It is the integration point for APM tools and custom metrics.
+//!
+//! Unlike [history](crate::observability::history), which is enabled per statement
+//! and collects all events in memory, the observer applies to all requests of the session,
+//! and does not store anything by itself. The callbacks are called synchronously on the
+//! request's execution path, so they should be cheap and must not block.
+//!
+//! Each page fetched by a [QueryPager](crate::client::pager::QueryPager) is observed
+//! as a separate request.
+
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use scylla_cql::frame::response::result::TableSpec;
+
+use crate::cluster::NodeRef;
+use crate::errors::{RequestAttemptError, RequestError};
+use crate::routing::Shard;
+use crate::statement::StatementTags;
+
+/// Identifier of an observed request, unique within the process.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct ObservedRequestId(pub u64);
+
+impl ObservedRequestId {
+    pub(crate) fn next() -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        ObservedRequestId(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+/// Information about a request which has just started.
+#[non_exhaustive]
+pub struct RequestStart<'a> {
+    /// Identifier of the request, passed to the other callbacks concerning it.
+    pub id: ObservedRequestId,
+    /// Keyspace and table that the request is executed against, if known.
+    pub table: Option<&'a TableSpec<'a>>,
+    /// Tags of the request's statement.
+    pub tags: &'a StatementTags,
+}
+
+/// Information about a completed attempt of a request - sending it to a single node
+/// and receiving the response.
+#[non_exhaustive]
+pub struct AttemptEnd<'a> {
+    /// Identifier of the request.
+    pub request_id: ObservedRequestId,
+    /// The node to which the attempt was sent.
+    pub node: NodeRef<'a>,
+    /// The shard to which the attempt was sent, if the node is shard-aware.
+    pub shard: Option<Shard>,
+    /// Time elapsed between sending the request and receiving the response.
+    pub latency: Duration,
+    /// Outcome of the attempt.
+    pub result: Result<(), &'a RequestAttemptError>,
+}
+
+/// Information about a completed request.
+#[non_exhaustive]
+pub struct RequestEnd<'a> {
+    /// Identifier of the request.
+    pub id: ObservedRequestId,
+    /// Time elapsed since the start of the request, including all its attempts.
+    pub latency: Duration,
+    /// Tags of the request's statement, which make it possible
+    /// to attribute the outcome, e.g. in metrics labelled by a tag.
+    pub tags: &'a StatementTags,
+    /// Outcome of the request.
+    pub result: Result<(), &'a RequestError>,
+}
+
+/// Observes requests executed by a session. All methods do nothing by default.
+///
+/// # Example
+/// ```
+/// # use std::sync::atomic::{AtomicU64, Ordering};
+/// # use scylla::observability::request_observer::{AttemptEnd, RequestObserver};
+/// #[derive(Debug, Default)]
+/// struct FailedAttemptsCounter(AtomicU64);
+///
+/// impl RequestObserver for FailedAttemptsCounter {
+///     fn on_attempt_end(&self, attempt: &AttemptEnd<'_>) {
+///         if attempt.result.is_err() {
+///             self.0.fetch_add(1, Ordering::Relaxed);
+///         }
+///     }
+/// }
+/// ```
+pub trait RequestObserver: Debug + Send + Sync {
+    /// Called when a request starts, before any attempt is made.
+    fn on_request_start(&self, request: &RequestStart<'_>) {
+        let _ = request;
+    }
+
+    /// Called when an attempt of a request completes, successfully or not.
+    ///
+    /// With speculative execution, attempts of the same request can be made concurrently.
+    /// Attempts which are still running when the request completes are not reported.
+    fn on_attempt_end(&self, attempt: &AttemptEnd<'_>) {
+        let _ = attempt;
+    }
+
+    /// Called when a request completes, successfully or not.
+    fn on_request_end(&self, request: &RequestEnd<'_>) {
+        let _ = request;
+    }
+}
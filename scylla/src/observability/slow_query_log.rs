@@ -0,0 +1,452 @@
+//! Logging requests which take too long to complete.
+//!
+//! A [SlowQueryLogger] set with
+//! [SessionBuilder::slow_query_logger](crate::client::session_builder::SessionBuilder::slow_query_logger)
+//! checks the latency of every request executed by the session. Requests which took
+//! at least the configured threshold (including all their attempts and retries)
+//! are reported to a [SlowQuerySink]. By default, the sink emits a `WARN` event
+//! with [`tracing`](https://docs.rs/tracing).
+//!
+//! Requests are identified by the fingerprint of their statement - the statement's
+//! text with literals replaced by `?` and whitespace normalized - so that requests
+//! differing only in the values embedded in the statement can be grouped together.
+//!
+//! In workloads where a lot of requests are expected to be slow, only a fraction
+//! of them can be reported, by setting a sampling rate.
+//!
+//! Each page fetched by a [QueryPager](crate::client::pager::QueryPager) is checked
+//! as a separate request.
+
+use std::borrow::Cow;
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::{Duration, Instant};
+
+use scylla_cql::frame::response::result::TableSpec;
+
+use crate::cluster::{Node, NodeRef};
+use crate::errors::RequestError;
+use crate::observability::log_targets;
+use crate::routing::Token;
+use crate::statement::StatementTags;
+use crate::statement::batch::{Batch, BatchStatement};
+
+/// Information about a request whose latency exceeded the threshold.
+#[non_exhaustive]
+pub struct SlowQuery<'a> {
+    /// Fingerprint of the request's statement, see [statement_fingerprint].
+    /// For batches, fingerprints of all the batch's statements, separated by `; `.
+    pub fingerprint: &'a str,
+    /// Token of the partition key bound to the statement, if it was computed.
+    /// Tokens are only computed for prepared statements, and for batches
+    /// whose first statement is prepared.
+    pub token: Option<Token>,
+    /// Keyspace and table that the request was executed against, if known.
+    pub table: Option<&'a TableSpec<'a>>,
+    /// The node to which the last attempt of the request was sent, if any.
+    pub node: Option<NodeRef<'a>>,
+    /// Number of attempts made, including retries and speculative executions.
+    pub attempts: usize,
+    /// Time elapsed since the start of the request, including all its attempts.
+    pub latency: Duration,
+    /// Tags of the request's statement.
+    pub tags: &'a StatementTags,
+    /// Outcome of the request.
+    pub result: Result<(), &'a RequestError>,
+}
+
+/// Receives slow requests reported by a [SlowQueryLogger].
+///
+/// # Example
+/// ```
+/// # use std::sync::Mutex;
+/// # use scylla::observability::slow_query_log::{SlowQuery, SlowQuerySink};
+/// #[derive(Debug, Default)]
+/// struct SlowestQuery(Mutex<Option<(String, std::time::Duration)>>);
+///
+/// impl SlowQuerySink for SlowestQuery {
+///     fn on_slow_query(&self, query: &SlowQuery<'_>) {
+///         let mut slowest = self.0.lock().unwrap();
+///         if slowest.as_ref().is_none_or(|(_, latency)| *latency < query.latency) {
+///             *slowest = Some((query.fingerprint.to_owned(), query.latency));
+///         }
+///     }
+/// }
+/// ```
+pub trait SlowQuerySink: Debug + Send + Sync {
+    /// Called synchronously on the request's execution path after a slow request
+    /// completes, so it should be cheap and must not block.
+    fn on_slow_query(&self, query: &SlowQuery<'_>);
+}
+
+/// The default [SlowQuerySink], which emits a `WARN` event
+/// with [`tracing`](https://docs.rs/tracing) for every slow request.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TracingSlowQuerySink;
+
+impl SlowQuerySink for TracingSlowQuerySink {
+    fn on_slow_query(&self, query: &SlowQuery<'_>) {
+        tracing::warn!(
+            target: log_targets::SLOW_QUERY,
+            fingerprint = query.fingerprint,
+            token = query.token.map(|token| token.value()),
+            keyspace = query.table.map(|table| table.ks_name()),
+            table = query.table.map(|table| table.table_name()),
+            node = query.node.map(|node| tracing::field::display(node.address)),
+            attempts = query.attempts,
+            latency_ms = query.latency.as_millis() as u64,
+            error = query.result.err().map(tracing::field::display),
+            "Slow query"
+        );
+    }
+}
+
+/// Configuration of the slow query log of a session.
+///
+/// # Example
+/// ```
+/// # use std::time::Duration;
+/// # use scylla::observability::slow_query_log::SlowQueryLogger;
+/// // Report one in ten requests which took at least 500ms.
+/// let logger = SlowQueryLogger::new(Duration::from_millis(500)).with_sampling_rate(0.1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SlowQueryLogger {
+    threshold: Duration,
+    sampling_rate: f64,
+    sink: Arc<dyn SlowQuerySink>,
+}
+
+impl SlowQueryLogger {
+    /// Creates a logger which reports all requests that took at least `threshold`
+    /// to a [TracingSlowQuerySink].
+    pub fn new(threshold: Duration) -> Self {
+        Self {
+            threshold,
+            sampling_rate: 1.0,
+            sink: Arc::new(TracingSlowQuerySink),
+        }
+    }
+
+    /// Sets the fraction of slow requests which are reported, between 0.0 and 1.0.
+    /// Requests to report are chosen randomly. The default is 1.0.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sampling_rate` is not between 0.0 and 1.0.
+    pub fn with_sampling_rate(mut self, sampling_rate: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&sampling_rate),
+            "sampling rate must be between 0.0 and 1.0, got {sampling_rate}"
+        );
+        self.sampling_rate = sampling_rate;
+        self
+    }
+
+    /// Sets the sink to which slow requests are reported.
+    pub fn with_sink(mut self, sink: Arc<dyn SlowQuerySink>) -> Self {
+        self.sink = sink;
+        self
+    }
+
+    /// Returns the latency above which requests are reported.
+    pub fn threshold(&self) -> Duration {
+        self.threshold
+    }
+
+    /// Returns the fraction of slow requests which are reported.
+    pub fn sampling_rate(&self) -> f64 {
+        self.sampling_rate
+    }
+
+    /// Starts measuring a request.
+    pub(crate) fn start_request(&self) -> SlowQueryTracker {
+        SlowQueryTracker {
+            start: Instant::now(),
+            attempts: AtomicUsize::new(0),
+            last_node: StdMutex::new(None),
+        }
+    }
+
+    /// Reports the request to the sink if it was slow and it is sampled.
+    pub(crate) fn finish_request(
+        &self,
+        tracker: &SlowQueryTracker,
+        statement: LoggedStatement<'_>,
+        token: Option<Token>,
+        table: Option<&TableSpec<'_>>,
+        tags: &StatementTags,
+        result: Result<(), &RequestError>,
+    ) {
+        let latency = tracker.start.elapsed();
+        if latency < self.threshold
+            || self.sampling_rate < 1.0 && !rand::random_bool(self.sampling_rate)
+        {
+            return;
+        }
+
+        let last_node = tracker.last_node.lock().unwrap().clone();
+        self.sink.on_slow_query(&SlowQuery {
+            fingerprint: &statement.fingerprint(),
+            token,
+            table,
+            node: last_node.as_ref(),
+            attempts: tracker.attempts.load(Ordering::Relaxed),
+            latency,
+            tags,
+            result,
+        });
+    }
+}
+
+/// Counts attempts of a request checked by a [SlowQueryLogger].
+/// With speculative execution, attempts can be recorded concurrently.
+pub(crate) struct SlowQueryTracker {
+    start: Instant,
+    attempts: AtomicUsize,
+    last_node: StdMutex<Option<Arc<Node>>>,
+}
+
+impl SlowQueryTracker {
+    pub(crate) fn record_attempt(&self, node: NodeRef<'_>) {
+        self.attempts.fetch_add(1, Ordering::Relaxed);
+        *self.last_node.lock().unwrap() = Some(Arc::clone(node));
+    }
+}
+
+/// The statement of a request checked by a [SlowQueryLogger] and logged to the request history.
+/// Its fingerprint is only computed if the request turns out to be slow.
+#[derive(Clone, Copy)]
+pub(crate) enum LoggedStatement<'a> {
+    Text(&'a str),
+    Batch(&'a Batch),
+}
+
+impl<'a> LoggedStatement<'a> {
+    /// Returns the text of the statement. For batches, texts of all the batch's statements
+    /// are separated by `; `.
+    pub(crate) fn text(self) -> Cow<'a, str> {
+        match self {
+            LoggedStatement::Text(text) => Cow::Borrowed(text),
+            LoggedStatement::Batch(batch) => Cow::Owned(
+                batch
+                    .statements
+                    .iter()
+                    .map(|statement| match statement {
+                        BatchStatement::Query(statement) => statement.contents.as_str(),
+                        BatchStatement::PreparedStatement(prepared) => prepared.get_statement(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("; "),
+            ),
+        }
+    }
+
+    fn fingerprint(self) -> String {
+        match self {
+            LoggedStatement::Text(text) => statement_fingerprint(text),
+            LoggedStatement::Batch(batch) => batch
+                .statements
+                .iter()
+                .map(|statement| match statement {
+                    BatchStatement::Query(statement) => statement_fingerprint(&statement.contents),
+                    BatchStatement::PreparedStatement(prepared) => {
+                        statement_fingerprint(prepared.get_statement())
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("; "),
+        }
+    }
+}
+
+/// Computes the fingerprint of a CQL statement: string, numeric, blob and UUID literals
+/// are replaced by `?`, and whitespace is collapsed into single spaces.
+/// Quoted identifiers are left untouched.
+///
+/// ```
+/// # use scylla::observability::slow_query_log::statement_fingerprint;
+/// assert_eq!(
+///     statement_fingerprint("SELECT *  FROM ks.t\n WHERE a = 42 AND b = 'x'"),
+///     "SELECT * FROM ks.t WHERE a = ? AND b = ?",
+/// );
+/// ```
+pub fn statement_fingerprint(statement: &str) -> String {
+    let mut fingerprint = String::with_capacity(statement.len());
+    let mut chars = statement.chars().peekable();
+    // Whether the previous character belongs to an identifier or a keyword,
+    // in which case a digit does not start a numeric literal.
+    let mut in_word = false;
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                while chars.next_if(|c| c.is_whitespace()).is_some() {}
+                if !fingerprint.is_empty() && chars.peek().is_some() {
+                    fingerprint.push(' ');
+                }
+                in_word = false;
+            }
+            '\'' => {
+                // A quote inside a string literal is escaped by doubling it.
+                while let Some(c) = chars.next() {
+                    if c == '\'' && chars.next_if_eq(&'\'').is_none() {
+                        break;
+                    }
+                }
+                fingerprint.push('?');
+                in_word = false;
+            }
+            '$' if chars.next_if_eq(&'$').is_some() => {
+                let mut previous = None;
+                for c in chars.by_ref() {
+                    if previous == Some('$') && c == '$' {
+                        break;
+                    }
+                    previous = Some(c);
+                }
+                fingerprint.push('?');
+                in_word = false;
+            }
+            '"' => {
+                fingerprint.push(c);
+                while let Some(c) = chars.next() {
+                    fingerprint.push(c);
+                    if c == '"' {
+                        // A quote inside a quoted identifier is escaped by doubling it.
+                        match chars.next_if_eq(&'"') {
+                            Some(c) => fingerprint.push(c),
+                            None => break,
+                        }
+                    }
+                }
+                in_word = false;
+            }
+            c if c.is_ascii_digit() && !in_word => {
+                // Covers integers, floats with exponents, blobs (0x...) and UUIDs.
+                let mut previous = c;
+                while let Some(c) = chars.next_if(|&c| {
+                    c.is_ascii_alphanumeric()
+                        || c == '.'
+                        || c == '-'
+                        || c == '+' && matches!(previous, 'e' | 'E')
+                }) {
+                    previous = c;
+                }
+                fingerprint.push('?');
+                in_word = false;
+            }
+            c => {
+                fingerprint.push(c);
+                in_word = c.is_alphanumeric() || c == '_';
+            }
+        }
+    }
+
+    fingerprint
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use super::{
+        LoggedStatement, SlowQuery, SlowQueryLogger, SlowQuerySink, statement_fingerprint,
+    };
+    use crate::routing::Token;
+    use crate::statement::StatementTags;
+    use crate::statement::batch::Batch;
+    use crate::test_utils::setup_tracing;
+
+    #[test]
+    fn fingerprint_replaces_literals() {
+        setup_tracing();
+        let cases = [
+            (
+                "SELECT a, b2 FROM ks.t_1 WHERE a = 1 AND b IN (2.5, -3e+10)",
+                "SELECT a, b2 FROM ks.t_1 WHERE a = ? AND b IN (?, -?)",
+            ),
+            (
+                "INSERT INTO t (a, b, c) VALUES ('it''s', 0xdeadbeef, 123e4567-e89b-12d3-a456-426614174000)",
+                "INSERT INTO t (a, b, c) VALUES (?, ?, ?)",
+            ),
+            (
+                "  UPDATE \"T 1\"\n\tSET \"v\"\"9\" = $$ a 'b' $$  WHERE k = ?  ",
+                "UPDATE \"T 1\" SET \"v\"\"9\" = ? WHERE k = ?",
+            ),
+            (
+                "SELECT * FROM t WHERE k = :key LIMIT 10",
+                "SELECT * FROM t WHERE k = :key LIMIT ?",
+            ),
+        ];
+        for (statement, expected) in cases {
+            assert_eq!(statement_fingerprint(statement), expected);
+        }
+    }
+
+    #[derive(Debug, Default)]
+    struct CollectingSink(Mutex<Vec<(String, Option<Token>, usize)>>);
+
+    impl SlowQuerySink for CollectingSink {
+        fn on_slow_query(&self, query: &SlowQuery<'_>) {
+            self.0.lock().unwrap().push((
+                query.fingerprint.to_owned(),
+                query.token,
+                query.attempts,
+            ));
+        }
+    }
+
+    #[test]
+    fn only_slow_sampled_requests_are_reported() {
+        setup_tracing();
+        let sink = Arc::new(CollectingSink::default());
+        let tags = StatementTags::default();
+        let token = Some(Token::new(42));
+
+        let report = |logger: SlowQueryLogger, statement: LoggedStatement<'_>| {
+            let tracker = logger.start_request();
+            logger.finish_request(&tracker, statement, token, None, &tags, Ok(()));
+        };
+
+        let logger = SlowQueryLogger::new(Duration::ZERO).with_sink(sink.clone());
+        report(
+            logger.clone(),
+            LoggedStatement::Text("SELECT * FROM t WHERE a = 1"),
+        );
+        let mut batch = Batch::default();
+        batch.append_statement("INSERT INTO t (a) VALUES (1)");
+        batch.append_statement("DELETE FROM t WHERE a = ?");
+        report(logger.clone(), LoggedStatement::Batch(&batch));
+
+        // Fast requests and requests which are not sampled are not reported.
+        report(
+            SlowQueryLogger::new(Duration::from_secs(3600)).with_sink(sink.clone()),
+            LoggedStatement::Text("SELECT 1"),
+        );
+        report(
+            logger.with_sampling_rate(0.0),
+            LoggedStatement::Text("SELECT 2"),
+        );
+
+        assert_eq!(
+            *sink.0.lock().unwrap(),
+            [
+                ("SELECT * FROM t WHERE a = ?".to_owned(), token, 0),
+                (
+                    "INSERT INTO t (a) VALUES (?); DELETE FROM t WHERE a = ?".to_owned(),
+                    token,
+                    0
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn sampling_rate_out_of_range_is_rejected() {
+        let _ = SlowQueryLogger::new(Duration::ZERO).with_sampling_rate(1.5);
+    }
+}
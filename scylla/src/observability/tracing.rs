@@ -7,6 +7,9 @@
 //! record the statement execution details in `system_traces.sessions` and `system_traces.events`,
 //! as well as return a tracing ID in the response, which can be used to query the tracing
 //! info later.
+//!
+//! The raw [TracingInfo] can be analysed with a [TracingReport], which computes durations
+//! of the traced events and summarizes the activity of each node involved in the execution.
 
 use crate::DeserializeRow;
 use crate::value::CqlTimestamp;
@@ -14,6 +17,8 @@ use itertools::Itertools;
 use scylla_cql::value::CqlTimeuuid;
 use std::collections::HashMap;
 use std::net::IpAddr;
+use std::time::Duration;
+use uuid::Uuid;
 
 /// Tracing info retrieved from `system_traces.sessions`
 /// with all events from `system_traces.events`
@@ -104,6 +109,198 @@ impl TracingInfo {
     }
 }
 
+/// An analysis of a traced execution, built from its [TracingInfo].
+///
+/// Obtained with [Session::get_tracing_report](crate::client::session::Session::get_tracing_report),
+/// or from already fetched tracing info with [TracingReport::new].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TracingReport {
+    /// Id of the tracing session.
+    pub tracing_id: Uuid,
+
+    /// Address of the coordinator node that executed the request.
+    pub coordinator: Option<IpAddr>,
+
+    /// Address of the client that requested the execution.
+    pub client: Option<IpAddr>,
+
+    /// Kind of the request, textually described, e.g. `Execute CQL3 query`.
+    pub request: Option<String>,
+
+    /// Various parameters of the execution, e.g. the consistency level and the statement.
+    pub parameters: HashMap<String, String>,
+
+    /// Point in time when the execution started.
+    pub started_at: Option<CqlTimestamp>,
+
+    /// Events that happened during the execution, in order of their occurrence.
+    pub events: Vec<TracedEvent>,
+
+    /// Activity of each node that generated events, in order of their first events.
+    pub replicas: Vec<ReplicaActivity>,
+
+    duration: Option<Duration>,
+}
+
+/// An event that happened during a traced execution, with its duration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TracedEvent {
+    /// Description of the event, e.g. `Sending a mutation to /127.0.0.1`.
+    pub activity: Option<String>,
+
+    /// Address of the node that generated the event.
+    pub source: Option<IpAddr>,
+
+    /// Thread on which the event was generated, e.g. `shard 0`.
+    pub thread: Option<String>,
+
+    /// Time elapsed since the start of the execution on the node that generated the event.
+    pub elapsed: Option<Duration>,
+
+    /// Time elapsed between the event and the next event generated
+    /// on the same node and thread, i.e. the time spent on the activity of the event.
+    /// `None` for the last event of each thread.
+    pub duration: Option<Duration>,
+}
+
+/// Summary of the events generated by a single node during a traced execution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ReplicaActivity {
+    /// Address of the node.
+    pub node: IpAddr,
+
+    /// Whether the node coordinated the execution.
+    pub is_coordinator: bool,
+
+    /// Number of events generated by the node.
+    pub events: usize,
+
+    /// Time elapsed since the start of the execution on the node until its first event.
+    pub first_event_elapsed: Duration,
+
+    /// Time elapsed since the start of the execution on the node until its last event.
+    pub last_event_elapsed: Duration,
+}
+
+impl ReplicaActivity {
+    /// Time between the first and the last event generated by the node.
+    pub fn active_duration(&self) -> Duration {
+        self.last_event_elapsed
+            .saturating_sub(self.first_event_elapsed)
+    }
+}
+
+fn micros_to_duration(micros: Option<i32>) -> Option<Duration> {
+    micros
+        .and_then(|micros| u64::try_from(micros).ok())
+        .map(Duration::from_micros)
+}
+
+impl TracingReport {
+    /// Analyses the tracing info of the tracing session with the given id.
+    pub fn new(tracing_id: Uuid, info: TracingInfo) -> Self {
+        let mut raw_events = info.events;
+        raw_events.sort_by_key(|event| event.event_id);
+
+        // Index of the last seen event of each node and thread,
+        // whose duration ends with the next event on that thread.
+        let mut last_on_thread: HashMap<(Option<IpAddr>, Option<String>), usize> = HashMap::new();
+        let mut events: Vec<TracedEvent> = Vec::with_capacity(raw_events.len());
+        for raw_event in raw_events {
+            let event = TracedEvent {
+                activity: raw_event.activity,
+                source: raw_event.source,
+                thread: raw_event.thread,
+                elapsed: micros_to_duration(raw_event.source_elapsed),
+                duration: None,
+            };
+            let thread_key = (event.source, event.thread.clone());
+            if let Some(previous) = last_on_thread.insert(thread_key, events.len()) {
+                let previous = &mut events[previous];
+                if let (Some(start), Some(end)) = (previous.elapsed, event.elapsed) {
+                    previous.duration = Some(end.saturating_sub(start));
+                }
+            }
+            events.push(event);
+        }
+
+        let mut replicas: Vec<ReplicaActivity> = Vec::new();
+        for event in &events {
+            let Some(node) = event.source else {
+                continue;
+            };
+            let elapsed = event.elapsed.unwrap_or_default();
+            match replicas.iter_mut().find(|replica| replica.node == node) {
+                Some(replica) => {
+                    replica.events += 1;
+                    replica.first_event_elapsed = replica.first_event_elapsed.min(elapsed);
+                    replica.last_event_elapsed = replica.last_event_elapsed.max(elapsed);
+                }
+                None => replicas.push(ReplicaActivity {
+                    node,
+                    is_coordinator: info.coordinator == Some(node),
+                    events: 1,
+                    first_event_elapsed: elapsed,
+                    last_event_elapsed: elapsed,
+                }),
+            }
+        }
+
+        Self {
+            tracing_id,
+            coordinator: info.coordinator,
+            client: info.client,
+            request: info.request,
+            parameters: info.parameters.unwrap_or_default(),
+            started_at: info.started_at,
+            events,
+            replicas,
+            duration: micros_to_duration(info.duration),
+        }
+    }
+
+    /// Total duration of the execution, as measured by the coordinator.
+    ///
+    /// If the coordinator has not recorded the duration yet, e.g. because the execution
+    /// did not complete when the tracing info was fetched, the time of the latest event
+    /// is returned instead.
+    pub fn total_duration(&self) -> Option<Duration> {
+        self.duration
+            .or_else(|| self.events.iter().filter_map(|event| event.elapsed).max())
+    }
+
+    /// Returns at most `n` events which took the longest, from the slowest.
+    /// Events of unknown duration are omitted.
+    pub fn slowest_events(&self, n: usize) -> Vec<&TracedEvent> {
+        self.events
+            .iter()
+            .filter(|event| event.duration.is_some())
+            .sorted_by(|a, b| b.duration.cmp(&a.duration))
+            .take(n)
+            .collect()
+    }
+
+    /// Returns the activity of the given node, if it generated any events.
+    pub fn replica(&self, node: IpAddr) -> Option<&ReplicaActivity> {
+        self.replicas.iter().find(|replica| replica.node == node)
+    }
+}
+
+/// Delays between consecutive attempts to fetch tracing info: starting with `interval`,
+/// and doubling after each attempt up to `max_interval`, if it is set.
+pub(crate) fn tracing_info_fetch_delays(
+    interval: Duration,
+    max_interval: Option<Duration>,
+) -> impl Iterator<Item = Duration> {
+    let max_interval = max_interval.map_or(interval, |max| max.max(interval));
+    std::iter::successors(Some(interval), move |delay| {
+        Some(delay.saturating_mul(2).min(max_interval))
+    })
+}
+
 // A query used to query TracingInfo from system_traces.sessions
 pub(crate) const TRACES_SESSION_QUERY_STR: &str = "SELECT client, command, coordinator, duration, parameters, request, started_at \
     FROM system_traces.sessions WHERE session_id = ?";
@@ -113,3 +310,122 @@ pub(crate) const TRACES_EVENTS_QUERY_STR: &str = "SELECT event_id, activity, sou
     FROM system_traces.events WHERE session_id = ?";
 
 pub(crate) const TRACING_QUERY_PAGE_SIZE: i32 = 1024;
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+    use std::time::Duration;
+
+    use scylla_cql::value::CqlTimeuuid;
+    use uuid::Uuid;
+
+    use super::{TracingEvent, TracingInfo, TracingReport, tracing_info_fetch_delays};
+    use crate::test_utils::setup_tracing;
+
+    fn event(id: u8, source: IpAddr, thread: &str, elapsed: i32, activity: &str) -> TracingEvent {
+        TracingEvent {
+            // Version 1 UUID, with `id` as the lowest part of its timestamp.
+            event_id: CqlTimeuuid::from_fields(id.into(), 0, 0x1000, &[0; 8]),
+            activity: Some(activity.to_owned()),
+            source: Some(source),
+            source_elapsed: Some(elapsed),
+            thread: Some(thread.to_owned()),
+        }
+    }
+
+    #[test]
+    fn tracing_report_computes_event_durations_and_replica_activity() {
+        setup_tracing();
+        let coordinator = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+        let replica = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 2));
+        let info = TracingInfo {
+            client: None,
+            command: Some("QUERY".to_owned()),
+            coordinator: Some(coordinator),
+            duration: None,
+            parameters: None,
+            request: Some("Execute CQL3 query".to_owned()),
+            started_at: None,
+            // Deliberately out of order.
+            events: vec![
+                event(3, replica, "shard 0", 300, "Applying mutation"),
+                event(1, coordinator, "shard 0", 10, "Parsing a statement"),
+                event(2, coordinator, "shard 0", 50, "Sending a mutation"),
+                event(4, replica, "shard 0", 900, "Sending response"),
+                event(5, coordinator, "shard 0", 1000, "Request complete"),
+            ],
+        };
+
+        let report = TracingReport::new(Uuid::nil(), info);
+        let activities: Vec<_> = report
+            .events
+            .iter()
+            .map(|event| event.activity.as_deref().unwrap())
+            .collect();
+        assert_eq!(
+            activities,
+            [
+                "Parsing a statement",
+                "Sending a mutation",
+                "Applying mutation",
+                "Sending response",
+                "Request complete"
+            ]
+        );
+        let durations: Vec<_> = report.events.iter().map(|event| event.duration).collect();
+        assert_eq!(
+            durations,
+            [
+                Some(Duration::from_micros(40)),
+                Some(Duration::from_micros(950)),
+                Some(Duration::from_micros(600)),
+                None,
+                None
+            ]
+        );
+
+        // Without the duration recorded by the coordinator, the latest event is used.
+        assert_eq!(report.total_duration(), Some(Duration::from_micros(1000)));
+
+        let slowest: Vec<_> = report
+            .slowest_events(2)
+            .into_iter()
+            .map(|event| event.activity.as_deref().unwrap())
+            .collect();
+        assert_eq!(slowest, ["Sending a mutation", "Applying mutation"]);
+
+        assert_eq!(report.replicas.len(), 2);
+        assert_eq!(report.replicas[0].node, coordinator);
+        assert!(report.replicas[0].is_coordinator);
+        let replica_activity = report.replica(replica).unwrap();
+        assert!(!replica_activity.is_coordinator);
+        assert_eq!(replica_activity.events, 2);
+        assert_eq!(
+            replica_activity.active_duration(),
+            Duration::from_micros(600)
+        );
+    }
+
+    #[test]
+    fn tracing_info_fetch_delays_back_off_up_to_limit() {
+        setup_tracing();
+        let millis = Duration::from_millis;
+
+        let constant: Vec<_> = tracing_info_fetch_delays(millis(3), None).take(3).collect();
+        assert_eq!(constant, [millis(3); 3]);
+
+        let backoff: Vec<_> = tracing_info_fetch_delays(millis(3), Some(millis(20)))
+            .take(5)
+            .collect();
+        assert_eq!(
+            backoff,
+            [millis(3), millis(6), millis(12), millis(20), millis(20)]
+        );
+
+        // The limit never shortens the initial interval.
+        let below: Vec<_> = tracing_info_fetch_delays(millis(3), Some(millis(1)))
+            .take(2)
+            .collect();
+        assert_eq!(below, [millis(3); 2]);
+    }
+}
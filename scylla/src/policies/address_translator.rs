@@ -3,15 +3,24 @@
 //! These are needed when a DB node broadcasts an address that is not reachable
 //! from the client, or when the address is not the preferred one to use to reach
 //! the node. In such cases, the driver may translate the address to another one.
+//!
+//! If the nodes advertise both a public and a private address, choosing which one to use
+//! with a [PeerAddressPreference] is often enough, without writing an [AddressTranslator].
 
 use std::collections::HashMap;
-use std::net::SocketAddr;
+use std::fmt;
+use std::net::{IpAddr, SocketAddr};
 use std::str::FromStr as _;
+use std::sync::Arc;
+use std::time::Duration;
 
+use crate::observability::log_targets;
 use async_trait::async_trait;
+use tracing::debug;
 use uuid::Uuid;
 
-use crate::errors::TranslationError;
+use crate::errors::{TranslationError, TranslationRuleError};
+use crate::utils::runtime;
 
 /// Data used to issue connections to a node that is possibly subject to address translation.
 ///
@@ -65,6 +74,12 @@ impl UntranslatedPeer<'_> {
 
 /// Translates IP addresses received from ScyllaDB nodes into locally reachable addresses.
 ///
+/// Translation is asynchronous, so implementations may query external services,
+/// e.g. cloud APIs or Kubernetes, without blocking the runtime. Temporary failures
+/// should be reported with [TranslationError::Transient], so that they can be retried,
+/// e.g. by wrapping the translator in a [RetryingAddressTranslator]. If translation fails,
+/// connecting to the node is retried later by the connection pool.
+///
 /// The driver auto-detects new ScyllaDB nodes added to the cluster through server side pushed
 /// notifications and through checking the system tables. For each node, the address the driver
 /// receives corresponds to the address set as `rpc_address` in the node yaml file. In most
@@ -130,3 +145,796 @@ impl AddressTranslator for HashMap<&'static str, &'static str> {
         ))
     }
 }
+
+/// An [AddressTranslator] configured with declarative rules, for the common case of
+/// nodes behind NAT or port forwarding.
+///
+/// Each rule maps a pattern of addresses broadcast by the nodes to the address
+/// the driver should connect to. A pattern has the form `<ip>[:<port>]`, where `<ip>` is:
+/// - a single address, e.g. `10.0.0.1` or `[fd00::1]:9042` (IPv6 addresses with a port
+///   have to be enclosed in brackets),
+/// - a subnet, e.g. `10.0.0.0/24`,
+/// - `*`, matching any address.
+///
+/// If the port is omitted, the pattern matches any port.
+///
+/// In the translated address, an omitted port or `*` in place of the address keeps
+/// the original one. A translated subnet must have the same size as the matched one,
+/// and keeps the host part of the original address, e.g. `10.0.0.0/24` -> `203.0.113.0/24`
+/// translates `10.0.0.7` to `203.0.113.7`.
+///
+/// When multiple rules match an address, the most specific one wins: the one with
+/// the longest subnet prefix, preferring rules with a port. Translating an address
+/// that matches no rule fails with [TranslationError::NoRuleForAddress].
+///
+/// With the `serde` feature, the translator can be deserialized from a map of rules,
+/// e.g. to load it from a configuration file together with other settings.
+///
+/// # Example
+/// ```
+/// # use std::sync::Arc;
+/// # use scylla::client::session::Session;
+/// # use scylla::client::session_builder::SessionBuilder;
+/// # use scylla::policies::address_translator::StaticAddressTranslator;
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let translator = StaticAddressTranslator::new()
+///     // Nodes of the private subnet are exposed under public addresses...
+///     .with_rule("10.0.0.0/24", "203.0.113.0/24")?
+///     // ...except for one, which is reached through port forwarding.
+///     .with_rule("10.0.0.42", "198.51.100.1:19042")?;
+/// let session: Session = SessionBuilder::new()
+///     .known_node("203.0.113.1:9042")
+///     .address_translator(Arc::new(translator))
+///     .build()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize),
+    serde(try_from = "HashMap<String, String>")
+)]
+pub struct StaticAddressTranslator {
+    // Sorted from the most specific.
+    rules: Vec<TranslationRule>,
+}
+
+impl StaticAddressTranslator {
+    /// Creates a translator without any rules.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a translator from `(from, to)` pairs of address patterns.
+    pub fn from_rules<I, F, T>(rules: I) -> Result<Self, TranslationRuleError>
+    where
+        I: IntoIterator<Item = (F, T)>,
+        F: AsRef<str>,
+        T: AsRef<str>,
+    {
+        rules
+            .into_iter()
+            .try_fold(Self::new(), |translator, (from, to)| {
+                translator.with_rule(from.as_ref(), to.as_ref())
+            })
+    }
+
+    /// Adds a rule translating addresses matching the `from` pattern according to the `to` pattern.
+    pub fn with_rule(mut self, from: &str, to: &str) -> Result<Self, TranslationRuleError> {
+        let from_pattern = AddressPattern::parse(from)
+            .ok_or_else(|| TranslationRuleError::InvalidPattern(from.to_owned()))?;
+        let to_pattern = AddressPattern::parse(to)
+            .ok_or_else(|| TranslationRuleError::InvalidPattern(to.to_owned()))?;
+
+        if let Some(to_subnet) = to_pattern.subnet {
+            let sizes_match = from_pattern.subnet.is_some_and(|from_subnet| {
+                from_subnet.prefix_len == to_subnet.prefix_len
+                    && from_subnet.network.is_ipv4() == to_subnet.network.is_ipv4()
+            });
+            if !to_subnet.is_single_address() && !sizes_match {
+                return Err(TranslationRuleError::MismatchedSubnets {
+                    from: from.to_owned(),
+                    to: to.to_owned(),
+                });
+            }
+        }
+
+        self.rules.push(TranslationRule {
+            from: from_pattern,
+            to: to_pattern,
+        });
+        // Stable sort keeps the order of equally specific rules.
+        self.rules
+            .sort_by_key(|rule| std::cmp::Reverse(rule.from.specificity()));
+        Ok(self)
+    }
+
+    fn translate(&self, address: SocketAddr) -> Option<SocketAddr> {
+        self.rules
+            .iter()
+            .find(|rule| rule.from.matches(address))
+            .map(|rule| rule.apply(address))
+    }
+}
+
+impl TryFrom<HashMap<String, String>> for StaticAddressTranslator {
+    type Error = TranslationRuleError;
+
+    fn try_from(rules: HashMap<String, String>) -> Result<Self, Self::Error> {
+        Self::from_rules(rules)
+    }
+}
+
+#[async_trait]
+impl AddressTranslator for StaticAddressTranslator {
+    async fn translate_address(
+        &self,
+        untranslated_peer: &UntranslatedPeer,
+    ) -> Result<SocketAddr, TranslationError> {
+        let address = untranslated_peer.untranslated_address();
+        self.translate(address)
+            .ok_or(TranslationError::NoRuleForAddress(address))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct TranslationRule {
+    from: AddressPattern,
+    to: AddressPattern,
+}
+
+impl TranslationRule {
+    fn apply(&self, address: SocketAddr) -> SocketAddr {
+        let ip = match self.to.subnet {
+            Some(subnet) => subnet.with_host_of(address.ip()),
+            None => address.ip(),
+        };
+        SocketAddr::new(ip, self.to.port.unwrap_or(address.port()))
+    }
+}
+
+/// A pattern of socket addresses. `None` matches any IP address or port.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct AddressPattern {
+    subnet: Option<Subnet>,
+    port: Option<u16>,
+}
+
+impl AddressPattern {
+    fn parse(pattern: &str) -> Option<Self> {
+        let pattern = pattern.trim();
+        let (host, port) = if let Some(rest) = pattern.strip_prefix('[') {
+            let (host, after_host) = rest.split_once(']')?;
+            let port = match after_host {
+                "" => None,
+                _ => Some(after_host.strip_prefix(':')?.parse().ok()?),
+            };
+            (host, port)
+        } else if pattern.matches(':').count() == 1 {
+            // An IPv4 address or a wildcard with a port.
+            let (host, port) = pattern.split_once(':')?;
+            (host, Some(port.parse().ok()?))
+        } else {
+            (pattern, None)
+        };
+
+        let subnet = match host {
+            "*" => None,
+            _ => {
+                let (network, prefix_len) = match host.split_once('/') {
+                    Some((network, prefix_len)) => {
+                        let network: IpAddr = network.parse().ok()?;
+                        (network, prefix_len.parse().ok()?)
+                    }
+                    None => {
+                        let address: IpAddr = host.parse().ok()?;
+                        (address, max_prefix_len(address))
+                    }
+                };
+                if prefix_len > max_prefix_len(network) {
+                    return None;
+                }
+                Some(Subnet {
+                    network,
+                    prefix_len,
+                })
+            }
+        };
+        Some(Self { subnet, port })
+    }
+
+    fn matches(&self, address: SocketAddr) -> bool {
+        self.subnet
+            .is_none_or(|subnet| subnet.contains(address.ip()))
+            && self.port.is_none_or(|port| port == address.port())
+    }
+
+    fn specificity(&self) -> (Option<u8>, bool) {
+        (
+            self.subnet.map(|subnet| subnet.prefix_len),
+            self.port.is_some(),
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Subnet {
+    network: IpAddr,
+    prefix_len: u8,
+}
+
+impl Subnet {
+    fn is_single_address(&self) -> bool {
+        self.prefix_len == max_prefix_len(self.network)
+    }
+
+    fn contains(&self, address: IpAddr) -> bool {
+        match (self.network, address) {
+            (IpAddr::V4(network), IpAddr::V4(address)) => {
+                (u32::from(network) ^ u32::from(address)) & v4_mask(self.prefix_len) == 0
+            }
+            (IpAddr::V6(network), IpAddr::V6(address)) => {
+                (u128::from(network) ^ u128::from(address)) & v6_mask(self.prefix_len) == 0
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the address in this subnet with the host part of `address`.
+    fn with_host_of(&self, address: IpAddr) -> IpAddr {
+        match (self.network, address) {
+            (IpAddr::V4(network), IpAddr::V4(address)) => {
+                let mask = v4_mask(self.prefix_len);
+                IpAddr::V4((u32::from(network) & mask | u32::from(address) & !mask).into())
+            }
+            (IpAddr::V6(network), IpAddr::V6(address)) => {
+                let mask = v6_mask(self.prefix_len);
+                IpAddr::V6((u128::from(network) & mask | u128::from(address) & !mask).into())
+            }
+            // Rules mapping between address families translate to a single address.
+            (network, _) => network,
+        }
+    }
+}
+
+fn max_prefix_len(address: IpAddr) -> u8 {
+    match address {
+        IpAddr::V4(_) => 32,
+        IpAddr::V6(_) => 128,
+    }
+}
+
+fn v4_mask(prefix_len: u8) -> u32 {
+    u32::MAX
+        .checked_shl(32 - u32::from(prefix_len))
+        .unwrap_or(0)
+}
+
+fn v6_mask(prefix_len: u8) -> u128 {
+    u128::MAX
+        .checked_shl(128 - u32::from(prefix_len))
+        .unwrap_or(0)
+}
+
+/// Wraps an [AddressTranslator], retrying translations that fail with
+/// a [transient](TranslationError::is_transient) error and bounding the time of each attempt.
+///
+/// Useful for translators backed by external services, like cloud APIs or Kubernetes,
+/// which may be temporarily unavailable or slow. Such translators should report temporary
+/// failures with [TranslationError::Transient]; other errors are returned immediately.
+///
+/// # Example
+/// ```
+/// # use std::sync::Arc;
+/// # use std::time::Duration;
+/// # use scylla::client::session::Session;
+/// # use scylla::client::session_builder::SessionBuilder;
+/// # use scylla::policies::address_translator::{AddressTranslator, RetryingAddressTranslator};
+/// # async fn example(
+/// #     cloud_translator: impl AddressTranslator + 'static,
+/// # ) -> Result<(), Box<dyn std::error::Error>> {
+/// let translator = RetryingAddressTranslator::new(cloud_translator)
+///     .with_max_attempts(5)
+///     .with_attempt_timeout(Some(Duration::from_secs(2)));
+/// let session: Session = SessionBuilder::new()
+///     .known_node("127.0.0.1:9042")
+///     .address_translator(Arc::new(translator))
+///     .build()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RetryingAddressTranslator<T> {
+    inner: T,
+    max_attempts: u32,
+    attempt_timeout: Option<Duration>,
+    base_delay: Duration,
+    max_delay: Duration,
+}
+
+impl<T: AddressTranslator> RetryingAddressTranslator<T> {
+    /// Wraps the given translator. By default, it makes at most 3 attempts, each limited
+    /// to 5 seconds, and waits from 100 milliseconds up to 2 seconds between them.
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            max_attempts: 3,
+            attempt_timeout: Some(Duration::from_secs(5)),
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(2),
+        }
+    }
+
+    /// Sets the maximum number of attempts, including the first one. At least one attempt is made.
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Sets the time limit of a single attempt. If `None`, attempts are not limited.
+    pub fn with_attempt_timeout(mut self, attempt_timeout: Option<Duration>) -> Self {
+        self.attempt_timeout = attempt_timeout;
+        self
+    }
+
+    /// Sets the delay before the first retry and the maximum delay between attempts.
+    /// The delay doubles after each failed attempt.
+    pub fn with_backoff(mut self, base_delay: Duration, max_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self.max_delay = max_delay.max(base_delay);
+        self
+    }
+
+    /// Returns the wrapped translator.
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    async fn attempt(
+        &self,
+        untranslated_peer: &UntranslatedPeer<'_>,
+    ) -> Result<SocketAddr, TranslationError> {
+        let translation = self.inner.translate_address(untranslated_peer);
+        match self.attempt_timeout {
+            Some(timeout) => runtime::timeout(timeout, translation)
+                .await
+                .unwrap_or(Err(TranslationError::Timeout(timeout))),
+            None => translation.await,
+        }
+    }
+}
+
+#[async_trait]
+impl<T: AddressTranslator> AddressTranslator for RetryingAddressTranslator<T> {
+    async fn translate_address(
+        &self,
+        untranslated_peer: &UntranslatedPeer,
+    ) -> Result<SocketAddr, TranslationError> {
+        let mut delay = self.base_delay;
+        let mut attempt = 1;
+        loop {
+            match self.attempt(untranslated_peer).await {
+                Err(err) if err.is_transient() && attempt < self.max_attempts => {
+                    debug!(
+                        target: log_targets::CONNECTION,
+                        "Translation of address {} failed (attempt {}), retrying in {:?}: {}",
+                        untranslated_peer.untranslated_address(),
+                        attempt,
+                        delay,
+                        err
+                    );
+                    runtime::sleep(delay).await;
+                    delay = (delay * 2).min(self.max_delay);
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// One of the addresses advertised by a node in `system.peers`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum PeerAddressKind {
+    /// The address clients should connect to (`broadcast_rpc_address` in the node
+    /// configuration), taken from the `rpc_address` column. This is the default.
+    #[default]
+    BroadcastRpc,
+
+    /// The address the node uses to communicate with other nodes (`broadcast_address`,
+    /// or `listen_address` if it is not set), taken from the `peer` column.
+    /// It is usually an internal address, reachable from within the datacenter.
+    Listen,
+}
+
+/// Addresses advertised by a node, passed to the selector
+/// of [PeerAddressPreference::with_selector].
+#[derive(Debug)]
+pub struct PeerAddresses<'a> {
+    pub(crate) host_id: Uuid,
+    pub(crate) broadcast_rpc_address: IpAddr,
+    pub(crate) listen_address: Option<IpAddr>,
+    pub(crate) datacenter: Option<&'a str>,
+    pub(crate) rack: Option<&'a str>,
+}
+
+impl PeerAddresses<'_> {
+    /// The unique identifier of the node in the cluster.
+    #[inline]
+    pub fn host_id(&self) -> Uuid {
+        self.host_id
+    }
+
+    /// The address clients should connect to, see [PeerAddressKind::BroadcastRpc].
+    #[inline]
+    pub fn broadcast_rpc_address(&self) -> IpAddr {
+        self.broadcast_rpc_address
+    }
+
+    /// The address used for communication between nodes, see [PeerAddressKind::Listen].
+    #[inline]
+    pub fn listen_address(&self) -> Option<IpAddr> {
+        self.listen_address
+    }
+
+    /// The datacenter the node resides in.
+    #[inline]
+    pub fn datacenter(&self) -> Option<&str> {
+        self.datacenter
+    }
+
+    /// The rack the node resides in.
+    #[inline]
+    pub fn rack(&self) -> Option<&str> {
+        self.rack
+    }
+
+    /// Returns the address of the given kind, if the node advertises it.
+    pub fn address(&self, kind: PeerAddressKind) -> Option<IpAddr> {
+        match kind {
+            PeerAddressKind::BroadcastRpc => Some(self.broadcast_rpc_address),
+            PeerAddressKind::Listen => self.listen_address,
+        }
+    }
+}
+
+type PeerAddressSelector = dyn Fn(&PeerAddresses) -> Option<IpAddr> + Send + Sync;
+
+/// Chooses which of the addresses advertised by a discovered node the driver connects to.
+///
+/// Useful in mixed public/private network topologies, e.g. when the driver runs
+/// in the same datacenter as some nodes and should reach them by their internal addresses,
+/// but has to use public addresses of the nodes in other datacenters.
+///
+/// The chosen address is still subject to the [AddressTranslator], if one is set.
+/// If the preferred address is not advertised by a node, its [PeerAddressKind::BroadcastRpc]
+/// address is used.
+///
+/// # Example
+/// ```
+/// # use scylla::policies::address_translator::{PeerAddressKind, PeerAddressPreference};
+/// // Use internal addresses in the local datacenter and public ones elsewhere.
+/// let preference = PeerAddressPreference::new(PeerAddressKind::BroadcastRpc)
+///     .with_datacenter("eu-west", PeerAddressKind::Listen);
+/// ```
+#[derive(Clone, Default)]
+pub struct PeerAddressPreference {
+    default_kind: PeerAddressKind,
+    per_datacenter: HashMap<String, PeerAddressKind>,
+    selector: Option<Arc<PeerAddressSelector>>,
+}
+
+impl PeerAddressPreference {
+    /// Creates a preference for the given kind of address in all datacenters.
+    pub fn new(kind: PeerAddressKind) -> Self {
+        Self {
+            default_kind: kind,
+            ..Default::default()
+        }
+    }
+
+    /// Prefers the given kind of address for nodes in the given datacenter.
+    pub fn with_datacenter(mut self, datacenter: impl Into<String>, kind: PeerAddressKind) -> Self {
+        self.per_datacenter.insert(datacenter.into(), kind);
+        self
+    }
+
+    /// Sets a callback that chooses the address of a node directly.
+    ///
+    /// If the callback returns `None`, the address is chosen according
+    /// to the per-datacenter and default preferences.
+    pub fn with_selector(
+        mut self,
+        selector: impl Fn(&PeerAddresses) -> Option<IpAddr> + Send + Sync + 'static,
+    ) -> Self {
+        self.selector = Some(Arc::new(selector));
+        self
+    }
+
+    /// Returns the address of the node that the driver should connect to.
+    pub(crate) fn choose(&self, addresses: &PeerAddresses) -> IpAddr {
+        if let Some(address) = self.selector.as_ref().and_then(|select| select(addresses)) {
+            return address;
+        }
+        let kind = addresses
+            .datacenter
+            .and_then(|dc| self.per_datacenter.get(dc))
+            .copied()
+            .unwrap_or(self.default_kind);
+        addresses
+            .address(kind)
+            .unwrap_or(addresses.broadcast_rpc_address)
+    }
+}
+
+impl fmt::Debug for PeerAddressPreference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PeerAddressPreference")
+            .field("default_kind", &self.default_kind)
+            .field("per_datacenter", &self.per_datacenter)
+            .field("selector", &self.selector.as_ref().map(|_| "<callback>"))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+    use uuid::Uuid;
+
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::Duration;
+
+    use async_trait::async_trait;
+
+    use super::{
+        AddressTranslator, PeerAddressKind, PeerAddressPreference, PeerAddresses,
+        RetryingAddressTranslator, StaticAddressTranslator, UntranslatedPeer,
+    };
+    use crate::errors::{CustomTranslationError, TranslationError, TranslationRuleError};
+
+    const PUBLIC: IpAddr = IpAddr::V4(Ipv4Addr::new(203, 0, 113, 1));
+    const PRIVATE: IpAddr = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+
+    fn addresses(datacenter: Option<&str>, listen_address: Option<IpAddr>) -> PeerAddresses<'_> {
+        PeerAddresses {
+            host_id: Uuid::nil(),
+            broadcast_rpc_address: PUBLIC,
+            listen_address,
+            datacenter,
+            rack: None,
+        }
+    }
+
+    #[test]
+    fn peer_address_preference() {
+        let default = PeerAddressPreference::default();
+        assert_eq!(
+            default.choose(&addresses(Some("dc1"), Some(PRIVATE))),
+            PUBLIC
+        );
+
+        let listen = PeerAddressPreference::new(PeerAddressKind::Listen);
+        assert_eq!(
+            listen.choose(&addresses(Some("dc1"), Some(PRIVATE))),
+            PRIVATE
+        );
+        // Falls back to the broadcast RPC address if the listen address is unknown.
+        assert_eq!(listen.choose(&addresses(Some("dc1"), None)), PUBLIC);
+
+        let per_dc = PeerAddressPreference::new(PeerAddressKind::BroadcastRpc)
+            .with_datacenter("dc1", PeerAddressKind::Listen);
+        assert_eq!(
+            per_dc.choose(&addresses(Some("dc1"), Some(PRIVATE))),
+            PRIVATE
+        );
+        assert_eq!(
+            per_dc.choose(&addresses(Some("dc2"), Some(PRIVATE))),
+            PUBLIC
+        );
+        assert_eq!(per_dc.choose(&addresses(None, Some(PRIVATE))), PUBLIC);
+
+        let overridden = IpAddr::V4(Ipv4Addr::new(192, 168, 0, 1));
+        let custom = per_dc
+            .with_selector(move |peer| (peer.datacenter() == Some("dc2")).then_some(overridden));
+        assert_eq!(
+            custom.choose(&addresses(Some("dc2"), Some(PRIVATE))),
+            overridden
+        );
+        assert_eq!(
+            custom.choose(&addresses(Some("dc1"), Some(PRIVATE))),
+            PRIVATE
+        );
+    }
+
+    fn translate(translator: &StaticAddressTranslator, address: &str) -> Option<SocketAddr> {
+        translator.translate(address.parse().unwrap())
+    }
+
+    #[test]
+    fn static_translator_rules() {
+        let translator = StaticAddressTranslator::from_rules([
+            ("10.0.0.0/24", "203.0.113.0/24"),
+            ("10.0.0.42", "198.51.100.1:19042"),
+            ("10.0.0.43:9042", "198.51.100.2"),
+            ("10.0.1.0/24:9042", "*:29042"),
+            ("[fd00::1]:9042", "[2001:db8::1]:9042"),
+            ("fd00:1::/64", "2001:db8:1::/64"),
+        ])
+        .unwrap();
+
+        // Subnet mapping keeps the host part.
+        assert_eq!(
+            translate(&translator, "10.0.0.7:9042"),
+            Some("203.0.113.7:9042".parse().unwrap())
+        );
+        // More specific rules win, regardless of the order they were given in.
+        assert_eq!(
+            translate(&translator, "10.0.0.42:9042"),
+            Some("198.51.100.1:19042".parse().unwrap())
+        );
+        assert_eq!(
+            translate(&translator, "10.0.0.43:9042"),
+            Some("198.51.100.2:9042".parse().unwrap())
+        );
+        assert_eq!(
+            translate(&translator, "10.0.0.43:19042"),
+            Some("203.0.113.43:19042".parse().unwrap())
+        );
+        // Port-only rewrite.
+        assert_eq!(
+            translate(&translator, "10.0.1.5:9042"),
+            Some("10.0.1.5:29042".parse().unwrap())
+        );
+        assert_eq!(translate(&translator, "10.0.1.5:9043"), None);
+        // IPv6.
+        assert_eq!(
+            translate(&translator, "[fd00::1]:9042"),
+            Some("[2001:db8::1]:9042".parse().unwrap())
+        );
+        assert_eq!(
+            translate(&translator, "[fd00:1::abcd]:9042"),
+            Some("[2001:db8:1::abcd]:9042".parse().unwrap())
+        );
+        assert_eq!(translate(&translator, "192.168.0.1:9042"), None);
+
+        // A wildcard rule is the least specific one.
+        let translator = translator.with_rule("*", "127.0.0.1").unwrap();
+        assert_eq!(
+            translate(&translator, "192.168.0.1:9042"),
+            Some("127.0.0.1:9042".parse().unwrap())
+        );
+        assert_eq!(
+            translate(&translator, "10.0.0.7:9042"),
+            Some("203.0.113.7:9042".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn static_translator_invalid_rules() {
+        for pattern in [
+            "",
+            "10.0.0",
+            "10.0.0.0/33",
+            "10.0.0.1:port",
+            "fd00::1:9042:",
+            "[fd00::1]9042",
+            "*/8",
+        ] {
+            assert!(
+                matches!(
+                    StaticAddressTranslator::new().with_rule(pattern, "*"),
+                    Err(TranslationRuleError::InvalidPattern(p)) if p == pattern
+                ),
+                "{pattern:?} should be invalid"
+            );
+        }
+
+        for (from, to) in [
+            ("10.0.0.0/24", "203.0.113.0/16"),
+            ("10.0.0.1", "203.0.113.0/24"),
+            ("*", "203.0.113.0/24"),
+            ("10.0.0.0/24", "2001:db8::/24"),
+        ] {
+            assert!(matches!(
+                StaticAddressTranslator::new().with_rule(from, to),
+                Err(TranslationRuleError::MismatchedSubnets { .. })
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn static_translator_translates_peers() {
+        let translator = StaticAddressTranslator::new()
+            .with_rule("10.0.0.1", "203.0.113.1")
+            .unwrap();
+        let peer = |address: &str| UntranslatedPeer {
+            host_id: Uuid::nil(),
+            untranslated_address: address.parse().unwrap(),
+            datacenter: None,
+            rack: None,
+        };
+
+        assert_eq!(
+            translator
+                .translate_address(&peer("10.0.0.1:9042"))
+                .await
+                .unwrap(),
+            "203.0.113.1:9042".parse().unwrap()
+        );
+        assert!(matches!(
+            translator.translate_address(&peer("10.0.0.2:9042")).await,
+            Err(TranslationError::NoRuleForAddress(_))
+        ));
+    }
+
+    /// Fails transiently the given number of times, hanging on the first attempt.
+    struct FlakyTranslator {
+        failures: u32,
+        attempts: AtomicU32,
+    }
+
+    #[async_trait]
+    impl AddressTranslator for FlakyTranslator {
+        async fn translate_address(
+            &self,
+            untranslated_peer: &UntranslatedPeer,
+        ) -> Result<SocketAddr, TranslationError> {
+            let attempt = self.attempts.fetch_add(1, Ordering::SeqCst);
+            if attempt == 0 {
+                std::future::pending::<()>().await;
+            }
+            if attempt < self.failures {
+                return Err(TranslationError::Transient(CustomTranslationError::new(
+                    std::io::Error::other("service unavailable"),
+                )));
+            }
+            Ok(untranslated_peer.untranslated_address())
+        }
+    }
+
+    fn flaky(failures: u32) -> FlakyTranslator {
+        FlakyTranslator {
+            failures,
+            attempts: AtomicU32::new(0),
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn retrying_translator() {
+        let peer = UntranslatedPeer {
+            host_id: Uuid::nil(),
+            untranslated_address: "10.0.0.1:9042".parse().unwrap(),
+            datacenter: None,
+            rack: None,
+        };
+
+        // The first attempt times out and the second one fails, the third one succeeds.
+        let translator = RetryingAddressTranslator::new(flaky(2))
+            .with_attempt_timeout(Some(Duration::from_secs(1)));
+        assert_eq!(
+            translator.translate_address(&peer).await.unwrap(),
+            peer.untranslated_address()
+        );
+        assert_eq!(translator.inner().attempts.load(Ordering::SeqCst), 3);
+
+        let translator = RetryingAddressTranslator::new(flaky(5)).with_max_attempts(3);
+        assert!(matches!(
+            translator.translate_address(&peer).await,
+            Err(TranslationError::Transient(_))
+        ));
+        assert_eq!(translator.inner().attempts.load(Ordering::SeqCst), 3);
+
+        // Errors which are not transient are not retried.
+        let translator = RetryingAddressTranslator::new(StaticAddressTranslator::new());
+        assert!(matches!(
+            translator.translate_address(&peer).await,
+            Err(TranslationError::NoRuleForAddress(_))
+        ));
+    }
+}
@@ -3,7 +3,8 @@
 //! Host filters are essentially just a predicate over
 //! [`Peer`]s. Currently, they are used
 //! by the [`Session`](crate::client::session::Session) to determine whether
-//! connections should be opened to a given node or not.
+//! connections should be opened to a given node or not, and whether
+//! internal requests (e.g. schema agreement checks) should be sent to it.
 
 use std::collections::HashSet;
 use std::io::Error;
@@ -82,3 +83,16 @@ impl HostFilter for DcHostFilter {
         peer.datacenter.as_ref() == Some(&self.local_dc)
     }
 }
+
+/// Accepts only nodes which own some tokens, rejecting coordinator-only
+/// (zero-token) nodes.
+///
+/// Coordinator-only nodes store no data, so it may be desirable
+/// to keep internal requests, such as schema agreement checks, away from them.
+pub struct TokenOwnerHostFilter;
+
+impl HostFilter for TokenOwnerHostFilter {
+    fn accept(&self, peer: &Peer) -> bool {
+        !peer.tokens.is_empty()
+    }
+}
@@ -0,0 +1,90 @@
+//! Keyspace filters.
+//!
+//! Keyspace filters are essentially just a predicate over keyspace names.
+//! They are used by the [`Session`](crate::client::session::Session) to determine
+//! whether schema metadata of a given keyspace should be fetched or not.
+//! Skipping keyspaces the application does not use saves memory and shortens
+//! metadata refreshes on clusters with large schemas.
+//!
+//! Any `Fn(&str) -> bool` closure can be used as a keyspace filter.
+
+use std::collections::HashSet;
+
+/// The `KeyspaceFilter` trait.
+pub trait KeyspaceFilter: Send + Sync {
+    /// Returns whether metadata of the keyspace should be fetched or not.
+    fn accept(&self, keyspace: &str) -> bool;
+}
+
+impl<F> KeyspaceFilter for F
+where
+    F: Fn(&str) -> bool + Send + Sync,
+{
+    fn accept(&self, keyspace: &str) -> bool {
+        self(keyspace)
+    }
+}
+
+/// Accepts keyspaces whose names are present in the allow list provided
+/// during filter's construction.
+pub struct AllowListKeyspaceFilter {
+    allowed: HashSet<String>,
+}
+
+impl AllowListKeyspaceFilter {
+    /// Creates a new `AllowListKeyspaceFilter` which only accepts keyspaces
+    /// from the list.
+    pub fn new(allowed: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            allowed: allowed.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl KeyspaceFilter for AllowListKeyspaceFilter {
+    fn accept(&self, keyspace: &str) -> bool {
+        self.allowed.contains(keyspace)
+    }
+}
+
+/// Accepts all keyspaces except those present in the deny list provided
+/// during filter's construction.
+pub struct DenyListKeyspaceFilter {
+    denied: HashSet<String>,
+}
+
+impl DenyListKeyspaceFilter {
+    /// Creates a new `DenyListKeyspaceFilter` which accepts all keyspaces
+    /// except those from the list.
+    pub fn new(denied: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            denied: denied.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl KeyspaceFilter for DenyListKeyspaceFilter {
+    fn accept(&self, keyspace: &str) -> bool {
+        !self.denied.contains(keyspace)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AllowListKeyspaceFilter, DenyListKeyspaceFilter, KeyspaceFilter};
+
+    #[test]
+    fn keyspace_filters() {
+        let allow = AllowListKeyspaceFilter::new(["ks1", "ks2"]);
+        assert!(allow.accept("ks1"));
+        assert!(!allow.accept("ks3"));
+
+        let deny = DenyListKeyspaceFilter::new(["ks1"]);
+        assert!(!deny.accept("ks1"));
+        assert!(deny.accept("ks2"));
+
+        let predicate = |ks: &str| !ks.starts_with("tenant_");
+        assert!(predicate.accept("shared"));
+        assert!(!predicate.accept("tenant_42"));
+    }
+}
@@ -0,0 +1,979 @@
+//! A load balancing policy wrapper which implements a per-node circuit breaker.
+
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::observability::log_targets;
+use rand::{Rng, rng};
+use tokio::time::Instant;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use super::{FallbackPlan, LoadBalancingPolicy, RoutingInfo};
+use crate::cluster::{ClusterState, NodeRef};
+use crate::errors::{DbError, RequestAttemptError};
+use crate::routing::Shard;
+
+/// State of a circuit breaker associated with a single node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CircuitState {
+    /// The node is considered healthy and requests are routed to it normally.
+    Closed,
+
+    /// The node has failed too many times in a row. Requests are not routed to it
+    /// (it is only put at the very end of plans) until the cooldown elapses.
+    Open,
+
+    /// The cooldown has elapsed. Only a fraction of requests is routed to the node
+    /// in order to probe whether it has recovered. A successful probe closes the circuit,
+    /// a failed one opens it again.
+    HalfOpen,
+}
+
+/// An event signalled when a circuit breaker of a node changes its state.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct CircuitBreakerEvent {
+    /// ID of the host whose circuit breaker changed its state.
+    pub host_id: Uuid,
+
+    /// Address of the host whose circuit breaker changed its state.
+    pub address: SocketAddr,
+
+    /// The state before the change.
+    pub previous_state: CircuitState,
+
+    /// The state after the change.
+    pub new_state: CircuitState,
+}
+
+/// Allows listening to state changes of per-node circuit breakers
+/// of [CircuitBreakerPolicy].
+pub trait CircuitBreakerListener: Send + Sync + std::fmt::Debug {
+    /// Invoked each time a circuit breaker of some node changes its state.
+    ///
+    /// This is called synchronously on the request execution path,
+    /// so the implementation should be cheap and must not block.
+    /// It is called with no locks of the policy held, so it may query the policy,
+    /// e.g. with [CircuitBreakerPolicy::state_of].
+    fn on_state_change(&self, event: &CircuitBreakerEvent);
+}
+
+/// Counters describing the activity of [CircuitBreakerPolicy].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CircuitBreakerMetrics {
+    /// How many times some circuit was opened.
+    pub circuits_opened: u64,
+
+    /// How many times some circuit was closed after a successful probe.
+    pub circuits_closed: u64,
+
+    /// How many probe requests were let through to nodes with half-open circuits.
+    pub probes: u64,
+
+    /// How many times a node was moved out of the front of a plan due to its circuit
+    /// being open (or half-open and not chosen for probing).
+    pub diverted: u64,
+
+    /// Number of nodes whose circuits are currently open.
+    pub open_circuits: u64,
+
+    /// Number of nodes whose circuits are currently half-open.
+    pub half_open_circuits: u64,
+}
+
+#[derive(Debug, Default)]
+struct AtomicMetrics {
+    circuits_opened: AtomicU64,
+    circuits_closed: AtomicU64,
+    probes: AtomicU64,
+    diverted: AtomicU64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct NodeBreaker {
+    state: CircuitState,
+    consecutive_failures: usize,
+    opened_at: Option<Instant>,
+}
+
+impl NodeBreaker {
+    fn new() -> Self {
+        Self {
+            state: CircuitState::Closed,
+            consecutive_failures: 0,
+            opened_at: None,
+        }
+    }
+}
+
+/// What a request outcome tells about the health of the node that handled it.
+enum NodeHealthVerdict {
+    /// The node responded in a way that shows it is operational.
+    Healthy,
+
+    /// The node failed in a way that suggests it is unhealthy or overloaded.
+    Unhealthy,
+
+    /// The outcome says nothing about the node (e.g. a driver-side error).
+    Irrelevant,
+}
+
+/// A load balancing policy that wraps another policy and adds a per-node circuit breaker.
+///
+/// When a node fails `failure_threshold` requests in a row (failures being broken connections,
+/// server-side timeouts, overload and similar errors suggesting that the node is unhealthy),
+/// its circuit gets *open*: the node is moved to the very end of load balancing plans,
+/// so that it is only contacted if all other targets fail.
+///
+/// After `cooldown` elapses, the circuit becomes *half-open*: a `probe_ratio` fraction
+/// of requests is routed to the node normally, in order to probe whether it has recovered.
+/// A successful request closes the circuit again, while a failed one opens it anew.
+///
+/// Note that client-side request timeouts are not reported to load balancing policies,
+/// so only server-side timeouts (`ReadTimeout`, `WriteTimeout`) are taken into account.
+///
+/// State of the breakers can be observed using [CircuitBreakerPolicy::state_of],
+/// [CircuitBreakerPolicy::metrics] and by registering a [CircuitBreakerListener].
+/// With the `metrics-024` feature, state changes are also counted in the
+/// `scylla.circuit_breaker_transitions` counter, labeled with the new state.
+///
+/// # Example
+/// ```
+/// # fn example() {
+/// use std::time::Duration;
+/// use scylla::policies::load_balancing::{CircuitBreakerPolicy, DefaultPolicy};
+///
+/// let policy = CircuitBreakerPolicy::builder(DefaultPolicy::builder().build())
+///     .failure_threshold(5)
+///     .cooldown(Duration::from_secs(10))
+///     .probe_ratio(0.1)
+///     .build();
+/// # }
+/// ```
+pub struct CircuitBreakerPolicy {
+    inner: Arc<dyn LoadBalancingPolicy>,
+    failure_threshold: usize,
+    cooldown: Duration,
+    probe_ratio: f64,
+    listener: Option<Arc<dyn CircuitBreakerListener>>,
+    breakers: Mutex<HashMap<Uuid, NodeBreaker>>,
+    // Address of the `ClusterState` against which `breakers` were last pruned.
+    // It is only used to detect that the cluster state changed.
+    pruned_for_cluster: AtomicUsize,
+    metrics: AtomicMetrics,
+}
+
+impl std::fmt::Debug for CircuitBreakerPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CircuitBreakerPolicy")
+            .field("inner", &self.inner)
+            .field("failure_threshold", &self.failure_threshold)
+            .field("cooldown", &self.cooldown)
+            .field("probe_ratio", &self.probe_ratio)
+            .field("listener", &self.listener)
+            .finish_non_exhaustive()
+    }
+}
+
+impl CircuitBreakerPolicy {
+    /// Creates a builder of a circuit breaker wrapping the given policy.
+    pub fn builder(inner: Arc<dyn LoadBalancingPolicy>) -> CircuitBreakerPolicyBuilder {
+        CircuitBreakerPolicyBuilder::new(inner)
+    }
+
+    /// Returns the current state of the circuit breaker of the node with the given host ID.
+    ///
+    /// Nodes that have never failed are reported as [CircuitState::Closed].
+    /// Note that an open circuit whose cooldown has already elapsed is reported
+    /// as [CircuitState::Open] until the policy routes the next request.
+    pub fn state_of(&self, host_id: Uuid) -> CircuitState {
+        self.breakers
+            .lock()
+            .unwrap()
+            .get(&host_id)
+            .map(|breaker| breaker.state)
+            .unwrap_or(CircuitState::Closed)
+    }
+
+    /// Returns a snapshot of the circuit breaker counters.
+    pub fn metrics(&self) -> CircuitBreakerMetrics {
+        let (open_circuits, half_open_circuits) =
+            self.breakers
+                .lock()
+                .unwrap()
+                .values()
+                .fold((0, 0), |(open, half_open), breaker| match breaker.state {
+                    CircuitState::Closed => (open, half_open),
+                    CircuitState::Open => (open + 1, half_open),
+                    CircuitState::HalfOpen => (open, half_open + 1),
+                });
+
+        CircuitBreakerMetrics {
+            circuits_opened: self.metrics.circuits_opened.load(Ordering::Relaxed),
+            circuits_closed: self.metrics.circuits_closed.load(Ordering::Relaxed),
+            probes: self.metrics.probes.load(Ordering::Relaxed),
+            diverted: self.metrics.diverted.load(Ordering::Relaxed),
+            open_circuits,
+            half_open_circuits,
+        }
+    }
+
+    /// Checks whether the circuit of the given node is closed, without any side effects.
+    fn is_closed(&self, node: NodeRef<'_>) -> bool {
+        self.breakers
+            .lock()
+            .unwrap()
+            .get(&node.host_id)
+            .is_none_or(|breaker| breaker.state == CircuitState::Closed)
+    }
+
+    /// Decides whether the given node may be put at its regular position in a plan.
+    ///
+    /// For nodes whose circuit is not closed, this updates the counters and (for half-open
+    /// circuits) rolls whether to probe the node, so it must be called at most once
+    /// per node and plan.
+    fn admits(&self, node: NodeRef<'_>) -> bool {
+        let (admitted, event) = self.decide_admission(node);
+        if let Some(event) = event {
+            self.notify(&event);
+        }
+        admitted
+    }
+
+    fn decide_admission(&self, node: NodeRef<'_>) -> (bool, Option<CircuitBreakerEvent>) {
+        let mut breakers = self.breakers.lock().unwrap();
+        let Some(breaker) = breakers.get_mut(&node.host_id) else {
+            return (true, None);
+        };
+
+        let mut event = None;
+        if breaker.state == CircuitState::Open
+            && breaker
+                .opened_at
+                .is_some_and(|opened_at| opened_at.elapsed() >= self.cooldown)
+        {
+            event = Some(self.transition(breaker, node, CircuitState::HalfOpen));
+        }
+
+        let admitted = match breaker.state {
+            CircuitState::Closed => true,
+            CircuitState::Open => false,
+            CircuitState::HalfOpen => {
+                let probe = rng().random_bool(self.probe_ratio);
+                if probe {
+                    self.metrics.probes.fetch_add(1, Ordering::Relaxed);
+                }
+                probe
+            }
+        };
+        if !admitted {
+            self.metrics.diverted.fetch_add(1, Ordering::Relaxed);
+        }
+        (admitted, event)
+    }
+
+    /// Drops the breakers of nodes which are no longer part of the cluster,
+    /// e.g. because they were decommissioned or replaced by nodes with new host IDs.
+    ///
+    /// The breakers are pruned only when the cluster state is seen for the first time.
+    /// If a new cluster state happens to be allocated at the address of the previous one,
+    /// pruning is postponed until the next change.
+    fn forget_removed_nodes(&self, cluster: &ClusterState) {
+        let cluster_addr = cluster as *const ClusterState as usize;
+        if self
+            .pruned_for_cluster
+            .swap(cluster_addr, Ordering::Relaxed)
+            == cluster_addr
+        {
+            return;
+        }
+        let mut breakers = self.breakers.lock().unwrap();
+        breakers.retain(|host_id, _| cluster.known_peers.contains_key(host_id));
+    }
+
+    fn report(&self, node: NodeRef<'_>, verdict: NodeHealthVerdict) {
+        if let Some(event) = self.update_breaker(node, verdict) {
+            self.notify(&event);
+        }
+    }
+
+    fn update_breaker(
+        &self,
+        node: NodeRef<'_>,
+        verdict: NodeHealthVerdict,
+    ) -> Option<CircuitBreakerEvent> {
+        let mut breakers = self.breakers.lock().unwrap();
+        match verdict {
+            NodeHealthVerdict::Irrelevant => None,
+            NodeHealthVerdict::Healthy => {
+                // A closed breaker with no failures is the same as no breaker at all,
+                // so only the breakers of nodes which are failing are kept.
+                let mut breaker = breakers.remove(&node.host_id)?;
+                if breaker.state == CircuitState::Closed {
+                    return None;
+                }
+                self.metrics.circuits_closed.fetch_add(1, Ordering::Relaxed);
+                Some(self.transition(&mut breaker, node, CircuitState::Closed))
+            }
+            NodeHealthVerdict::Unhealthy => {
+                let breaker = breakers
+                    .entry(node.host_id)
+                    .or_insert_with(NodeBreaker::new);
+                breaker.consecutive_failures += 1;
+                let should_open = match breaker.state {
+                    CircuitState::Closed => breaker.consecutive_failures >= self.failure_threshold,
+                    // A failed probe reopens the circuit.
+                    CircuitState::HalfOpen => true,
+                    CircuitState::Open => false,
+                };
+                if !should_open {
+                    return None;
+                }
+                breaker.opened_at = Some(Instant::now());
+                self.metrics.circuits_opened.fetch_add(1, Ordering::Relaxed);
+                Some(self.transition(breaker, node, CircuitState::Open))
+            }
+        }
+    }
+
+    /// Changes the state of the breaker and returns the event describing the change.
+    /// The event must be passed to [Self::notify] once the lock of `breakers` is released.
+    fn transition(
+        &self,
+        breaker: &mut NodeBreaker,
+        node: NodeRef<'_>,
+        new_state: CircuitState,
+    ) -> CircuitBreakerEvent {
+        let previous_state = std::mem::replace(&mut breaker.state, new_state);
+        if new_state == CircuitState::Open {
+            warn!(
+                target: log_targets::LOAD_BALANCING,
+                "Circuit breaker: opening circuit for node {} (host_id: {}) after {} consecutive failures",
+                node.address, node.host_id, breaker.consecutive_failures
+            );
+        } else {
+            debug!(
+                target: log_targets::LOAD_BALANCING,
+                "Circuit breaker: node {} (host_id: {}) transitioned from {:?} to {:?}",
+                node.address, node.host_id, previous_state, new_state
+            );
+        }
+
+        CircuitBreakerEvent {
+            host_id: node.host_id,
+            address: node.address.into_inner(),
+            previous_state,
+            new_state,
+        }
+    }
+
+    fn notify(&self, event: &CircuitBreakerEvent) {
+        #[cfg(feature = "metrics-024")]
+        crate::observability::metrics::inc_circuit_breaker_transitions(event.new_state);
+
+        if let Some(listener) = self.listener.as_ref() {
+            listener.on_state_change(event);
+        }
+    }
+
+    fn health_verdict(error: &RequestAttemptError) -> NodeHealthVerdict {
+        // Do not remove this lint!
+        // It's there for a reason - we don't want new variants
+        // automatically fall under `_` pattern when they are introduced.
+        #[deny(clippy::wildcard_enum_match_arm)]
+        match error {
+            // Errors that originate on the driver side, before the request reaches the node.
+            RequestAttemptError::CqlRequestSerialization(_)
+            | RequestAttemptError::SerializationError(_) => NodeHealthVerdict::Irrelevant,
+
+            // Errors that mean the node can't handle requests properly.
+            RequestAttemptError::BrokenConnectionError(_)
+            | RequestAttemptError::UnableToAllocStreamId => NodeHealthVerdict::Unhealthy,
+
+            // The node responded, although with an unexpected response.
+            RequestAttemptError::CqlResultParseError(_)
+            | RequestAttemptError::CqlErrorParseError(_)
+            | RequestAttemptError::BodyExtensionsParseError(_)
+            | RequestAttemptError::RepreparedIdChanged { .. }
+            | RequestAttemptError::RepreparedIdMissingInBatch
+            | RequestAttemptError::UnexpectedResponse(_)
+            | RequestAttemptError::NonfinishedPagingState => NodeHealthVerdict::Healthy,
+
+            RequestAttemptError::DbError(db_error, _) => {
+                // Do not remove this lint!
+                // It's there for a reason - we don't want new variants
+                // automatically fall under `_` pattern when they are introduced.
+                #[deny(clippy::wildcard_enum_match_arm)]
+                match db_error {
+                    // Errors suggesting that the node is unhealthy, overloaded or too slow.
+                    DbError::Overloaded
+                    | DbError::IsBootstrapping
+                    | DbError::ReadTimeout { .. }
+                    | DbError::WriteTimeout { .. }
+                    | DbError::ServerError => NodeHealthVerdict::Unhealthy,
+
+                    // Errors which are a proper response of an operational node.
+                    DbError::SyntaxError
+                    | DbError::Invalid
+                    | DbError::AlreadyExists { .. }
+                    | DbError::FunctionFailure { .. }
+                    | DbError::AuthenticationError
+                    | DbError::Unauthorized
+                    | DbError::ConfigError
+                    | DbError::Unavailable { .. }
+                    | DbError::TruncateError
+                    | DbError::ReadFailure { .. }
+                    | DbError::WriteFailure { .. }
+                    | DbError::Unprepared { .. }
+                    | DbError::ProtocolError
+                    | DbError::RateLimitReached { .. }
+                    | DbError::Other(_) => NodeHealthVerdict::Healthy,
+
+                    // Driver may be used with newer version of scylla-cql, which may define additional error variants.
+                    // `wildcard_enum_match_arm will` will still trigger if we add new variants to DbError,
+                    // so there is no risk of forgetting to update this match.
+                    _ => NodeHealthVerdict::Irrelevant,
+                }
+            }
+        }
+    }
+}
+
+impl LoadBalancingPolicy for CircuitBreakerPolicy {
+    fn pick<'a>(
+        &'a self,
+        request: &'a RoutingInfo,
+        cluster: &'a ClusterState,
+    ) -> Option<(NodeRef<'a>, Option<Shard>)> {
+        // Only nodes with closed circuits are picked. Admission of the other ones
+        // is decided once, in `fallback()`, which is then called to find a target.
+        self.inner
+            .pick(request, cluster)
+            .filter(|(node, _shard)| self.is_closed(node))
+    }
+
+    fn fallback<'a>(
+        &'a self,
+        request: &'a RoutingInfo,
+        cluster: &'a ClusterState,
+    ) -> FallbackPlan<'a> {
+        self.forget_removed_nodes(cluster);
+
+        // Move all targets with open circuits to the end of the plan, in a stable way.
+        Box::new(DivertingPlan {
+            policy: self,
+            inner: self.inner.fallback(request, cluster),
+            decided: HashSet::new(),
+            diverted: Vec::new(),
+            draining: None,
+        })
+    }
+
+    fn on_request_success(&self, request: &RoutingInfo, latency: Duration, node: NodeRef<'_>) {
+        self.report(node, NodeHealthVerdict::Healthy);
+        self.inner.on_request_success(request, latency, node);
+    }
+
+    fn on_request_failure(
+        &self,
+        request: &RoutingInfo,
+        latency: Duration,
+        node: NodeRef<'_>,
+        error: &RequestAttemptError,
+    ) {
+        self.report(node, Self::health_verdict(error));
+        self.inner.on_request_failure(request, latency, node, error);
+    }
+
+    fn name(&self) -> String {
+        format!("CircuitBreakerPolicy({})", self.inner.name())
+    }
+}
+
+/// A fallback plan which lazily yields the targets admitted by the circuit breakers,
+/// in the order of the inner plan, and then the diverted ones.
+///
+/// Each node is yielded at most once, so that its admission is decided once per plan,
+/// even if the inner plan returns it repeatedly.
+struct DivertingPlan<'a> {
+    policy: &'a CircuitBreakerPolicy,
+    inner: FallbackPlan<'a>,
+    // Host IDs of the nodes whose admission has been decided, i.e. which have been
+    // either yielded or diverted.
+    decided: HashSet<Uuid>,
+    // Targets diverted so far. Usually none, so nothing gets allocated.
+    diverted: Vec<(NodeRef<'a>, Option<Shard>)>,
+    // Set once the inner plan is exhausted.
+    draining: Option<std::vec::IntoIter<(NodeRef<'a>, Option<Shard>)>>,
+}
+
+impl<'a> Iterator for DivertingPlan<'a> {
+    type Item = (NodeRef<'a>, Option<Shard>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(draining) = self.draining.as_mut() {
+            return draining.next();
+        }
+
+        for (node, shard) in self.inner.by_ref() {
+            if !self.decided.insert(node.host_id) {
+                continue;
+            }
+            if self.policy.admits(node) {
+                return Some((node, shard));
+            }
+            self.diverted.push((node, shard));
+        }
+
+        let draining = self
+            .draining
+            .insert(std::mem::take(&mut self.diverted).into_iter());
+        draining.next()
+    }
+}
+
+/// The intended way to instantiate the [CircuitBreakerPolicy].
+#[derive(Clone, Debug)]
+pub struct CircuitBreakerPolicyBuilder {
+    inner: Arc<dyn LoadBalancingPolicy>,
+    failure_threshold: usize,
+    cooldown: Duration,
+    probe_ratio: f64,
+    listener: Option<Arc<dyn CircuitBreakerListener>>,
+}
+
+impl CircuitBreakerPolicyBuilder {
+    /// Creates a builder of a circuit breaker wrapping the given policy.
+    pub fn new(inner: Arc<dyn LoadBalancingPolicy>) -> Self {
+        Self {
+            inner,
+            failure_threshold: 5,
+            cooldown: Duration::from_secs(10),
+            probe_ratio: 0.1,
+            listener: None,
+        }
+    }
+
+    /// Sets the number of consecutive failures after which the circuit of a node is open.
+    ///
+    /// Default is 5. Values less than 1 are treated as 1.
+    pub fn failure_threshold(mut self, failure_threshold: usize) -> Self {
+        self.failure_threshold = failure_threshold.max(1);
+        self
+    }
+
+    /// Sets the time for which an open circuit stays open before it becomes half-open
+    /// and starts being probed.
+    ///
+    /// Default is 10 seconds.
+    pub fn cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// Sets the fraction of requests that is routed to a node with a half-open circuit.
+    ///
+    /// Default is 0.1. The value is clamped to the range `[0, 1]`.
+    pub fn probe_ratio(mut self, probe_ratio: f64) -> Self {
+        self.probe_ratio = if probe_ratio.is_nan() {
+            0.
+        } else {
+            probe_ratio.clamp(0., 1.)
+        };
+        self
+    }
+
+    /// Sets the listener notified about state changes of circuit breakers.
+    pub fn listener(mut self, listener: Arc<dyn CircuitBreakerListener>) -> Self {
+        self.listener = Some(listener);
+        self
+    }
+
+    /// Builds a new [CircuitBreakerPolicy] with the previously set configuration.
+    ///
+    /// The returned `Arc` can be coerced to `Arc<dyn LoadBalancingPolicy>`,
+    /// while a clone of it can be kept to observe the state of the breakers.
+    pub fn build(self) -> Arc<CircuitBreakerPolicy> {
+        Arc::new(CircuitBreakerPolicy {
+            inner: self.inner,
+            failure_threshold: self.failure_threshold,
+            cooldown: self.cooldown,
+            probe_ratio: self.probe_ratio,
+            listener: self.listener,
+            breakers: Mutex::new(HashMap::new()),
+            pruned_for_cluster: AtomicUsize::new(0),
+            metrics: AtomicMetrics::default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex, OnceLock, Weak};
+    use std::time::Duration;
+
+    use scylla_cql::frame::response::error::DbError;
+
+    use super::{CircuitBreakerEvent, CircuitBreakerListener, CircuitBreakerPolicy, CircuitState};
+    use crate::cluster::metadata::{Metadata, Peer};
+    use crate::cluster::{ClusterState, NodeRef};
+    use crate::errors::RequestAttemptError;
+    use crate::policies::load_balancing::default::tests::framework::get_plan_and_collect_node_identifiers;
+    use crate::policies::load_balancing::{FallbackPlan, LoadBalancingPolicy, RoutingInfo};
+    use crate::routing::{Shard, Token};
+    use crate::test_utils::{
+        MockClusterStateBuilder, mock_cluster_state_for_token_unaware_tests, setup_tracing,
+    };
+
+    /// Returns all nodes, ordered by their port.
+    #[derive(Debug)]
+    struct OrderedPolicy;
+
+    impl LoadBalancingPolicy for OrderedPolicy {
+        fn pick<'a>(
+            &'a self,
+            request: &'a RoutingInfo,
+            cluster: &'a ClusterState,
+        ) -> Option<(NodeRef<'a>, Option<Shard>)> {
+            self.fallback(request, cluster).next()
+        }
+
+        fn fallback<'a>(
+            &'a self,
+            _request: &'a RoutingInfo,
+            cluster: &'a ClusterState,
+        ) -> FallbackPlan<'a> {
+            let mut nodes: Vec<_> = cluster.get_nodes_info().iter().collect();
+            nodes.sort_by_key(|node| node.address.port());
+            Box::new(nodes.into_iter().map(|node| (node, None)))
+        }
+
+        fn name(&self) -> String {
+            "OrderedPolicy".to_string()
+        }
+    }
+
+    /// Returns all nodes ordered by their port, twice.
+    #[derive(Debug)]
+    struct RepeatingPolicy;
+
+    impl LoadBalancingPolicy for RepeatingPolicy {
+        fn pick<'a>(
+            &'a self,
+            request: &'a RoutingInfo,
+            cluster: &'a ClusterState,
+        ) -> Option<(NodeRef<'a>, Option<Shard>)> {
+            OrderedPolicy.pick(request, cluster)
+        }
+
+        fn fallback<'a>(
+            &'a self,
+            request: &'a RoutingInfo,
+            cluster: &'a ClusterState,
+        ) -> FallbackPlan<'a> {
+            Box::new(
+                OrderedPolicy
+                    .fallback(request, cluster)
+                    .chain(OrderedPolicy.fallback(request, cluster)),
+            )
+        }
+
+        fn name(&self) -> String {
+            "RepeatingPolicy".to_string()
+        }
+    }
+
+    #[derive(Debug, Default)]
+    struct RecordingListener {
+        events: Mutex<Vec<(u16, CircuitState)>>,
+    }
+
+    impl CircuitBreakerListener for RecordingListener {
+        fn on_state_change(&self, event: &CircuitBreakerEvent) {
+            self.events
+                .lock()
+                .unwrap()
+                .push((event.address.port(), event.new_state));
+        }
+    }
+
+    /// Queries the policy it listens to, which must not deadlock.
+    #[derive(Debug, Default)]
+    struct QueryingListener {
+        policy: OnceLock<Weak<CircuitBreakerPolicy>>,
+        states: Mutex<Vec<CircuitState>>,
+    }
+
+    impl CircuitBreakerListener for QueryingListener {
+        fn on_state_change(&self, event: &CircuitBreakerEvent) {
+            let policy = self.policy.get().unwrap().upgrade().unwrap();
+            let _ = policy.metrics();
+            self.states
+                .lock()
+                .unwrap()
+                .push(policy.state_of(event.host_id));
+        }
+    }
+
+    fn node_by_port(cluster: &ClusterState, port: u16) -> NodeRef<'_> {
+        cluster
+            .get_nodes_info()
+            .iter()
+            .find(|node| node.address.port() == port)
+            .unwrap()
+    }
+
+    fn fail(policy: &CircuitBreakerPolicy, node: NodeRef<'_>) {
+        policy.on_request_failure(
+            &RoutingInfo::default(),
+            Duration::ZERO,
+            node,
+            &RequestAttemptError::DbError(DbError::Overloaded, String::new()),
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn circuit_breaker_opens_probes_and_closes() {
+        setup_tracing();
+        let cluster = mock_cluster_state_for_token_unaware_tests().await;
+        let listener = Arc::new(RecordingListener::default());
+        let policy = CircuitBreakerPolicy::builder(Arc::new(OrderedPolicy))
+            .failure_threshold(2)
+            .cooldown(Duration::from_secs(5))
+            .probe_ratio(1.)
+            .listener(listener.clone())
+            .build();
+        let routing_info = RoutingInfo::default();
+        let node_1 = node_by_port(&cluster, 1);
+
+        // A single failure does not open the circuit.
+        fail(&policy, node_1);
+        assert_eq!(policy.state_of(node_1.host_id), CircuitState::Closed);
+        assert_eq!(
+            get_plan_and_collect_node_identifiers(policy.as_ref(), &routing_info, &cluster),
+            vec![1, 2, 3, 4, 5]
+        );
+
+        // The second consecutive one does, and the node is moved to the end of plans.
+        fail(&policy, node_1);
+        assert_eq!(policy.state_of(node_1.host_id), CircuitState::Open);
+        assert_eq!(
+            get_plan_and_collect_node_identifiers(policy.as_ref(), &routing_info, &cluster),
+            vec![2, 3, 4, 5, 1]
+        );
+
+        // After the cooldown, the node is probed.
+        tokio::time::advance(Duration::from_secs(5)).await;
+        assert_eq!(
+            get_plan_and_collect_node_identifiers(policy.as_ref(), &routing_info, &cluster),
+            vec![1, 2, 3, 4, 5]
+        );
+        assert_eq!(policy.state_of(node_1.host_id), CircuitState::HalfOpen);
+
+        // A failed probe opens the circuit again.
+        fail(&policy, node_1);
+        assert_eq!(policy.state_of(node_1.host_id), CircuitState::Open);
+
+        // A successful probe closes it.
+        tokio::time::advance(Duration::from_secs(5)).await;
+        assert_eq!(
+            get_plan_and_collect_node_identifiers(policy.as_ref(), &routing_info, &cluster),
+            vec![1, 2, 3, 4, 5]
+        );
+        policy.on_request_success(&routing_info, Duration::ZERO, node_1);
+        assert_eq!(policy.state_of(node_1.host_id), CircuitState::Closed);
+
+        assert_eq!(
+            *listener.events.lock().unwrap(),
+            vec![
+                (1, CircuitState::Open),
+                (1, CircuitState::HalfOpen),
+                (1, CircuitState::Open),
+                (1, CircuitState::HalfOpen),
+                (1, CircuitState::Closed),
+            ]
+        );
+
+        let metrics = policy.metrics();
+        assert_eq!(metrics.circuits_opened, 2);
+        assert_eq!(metrics.circuits_closed, 1);
+        assert_eq!(metrics.open_circuits, 0);
+        assert_eq!(metrics.half_open_circuits, 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn circuit_breaker_listener_may_query_policy() {
+        setup_tracing();
+        let cluster = mock_cluster_state_for_token_unaware_tests().await;
+        let listener = Arc::new(QueryingListener::default());
+        let policy = CircuitBreakerPolicy::builder(Arc::new(OrderedPolicy))
+            .failure_threshold(1)
+            .cooldown(Duration::from_secs(5))
+            .probe_ratio(1.)
+            .listener(listener.clone())
+            .build();
+        listener.policy.set(Arc::downgrade(&policy)).unwrap();
+        let routing_info = RoutingInfo::default();
+        let node_1 = node_by_port(&cluster, 1);
+
+        // Opening (in `report()`).
+        fail(&policy, node_1);
+        // Half-opening (in `admits()`).
+        tokio::time::advance(Duration::from_secs(5)).await;
+        get_plan_and_collect_node_identifiers(policy.as_ref(), &routing_info, &cluster);
+        // Closing (in `report()`).
+        policy.on_request_success(&routing_info, Duration::ZERO, node_1);
+
+        assert_eq!(
+            *listener.states.lock().unwrap(),
+            vec![
+                CircuitState::Open,
+                CircuitState::HalfOpen,
+                CircuitState::Closed
+            ]
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn circuit_breaker_success_resets_failure_count() {
+        setup_tracing();
+        let cluster = mock_cluster_state_for_token_unaware_tests().await;
+        let policy = CircuitBreakerPolicy::builder(Arc::new(OrderedPolicy))
+            .failure_threshold(2)
+            .build();
+        let node_2 = node_by_port(&cluster, 2);
+
+        fail(&policy, node_2);
+        policy.on_request_success(&RoutingInfo::default(), Duration::ZERO, node_2);
+        fail(&policy, node_2);
+        assert_eq!(policy.state_of(node_2.host_id), CircuitState::Closed);
+
+        // Errors being a proper response of the node reset the failure count, too.
+        policy.on_request_failure(
+            &RoutingInfo::default(),
+            Duration::ZERO,
+            node_2,
+            &RequestAttemptError::RepreparedIdMissingInBatch,
+        );
+        fail(&policy, node_2);
+        assert_eq!(policy.state_of(node_2.host_id), CircuitState::Closed);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn circuit_breaker_forgets_removed_nodes() {
+        setup_tracing();
+        let cluster = mock_cluster_state_for_token_unaware_tests().await;
+        let policy = CircuitBreakerPolicy::builder(Arc::new(OrderedPolicy))
+            .failure_threshold(1)
+            .build();
+        let node_1 = node_by_port(&cluster, 1);
+        let node_2 = node_by_port(&cluster, 2);
+
+        fail(&policy, node_1);
+        fail(&policy, node_2);
+        assert_eq!(policy.metrics().open_circuits, 2);
+
+        // Node 1 leaves the cluster.
+        let peers = cluster
+            .get_nodes_info()
+            .iter()
+            .filter(|node| node.address.port() != 1)
+            .map(|node| Peer {
+                host_id: node.host_id,
+                address: node.address,
+                tokens: vec![Token::new(node.address.port() as i64 * 100)],
+                datacenter: node.datacenter.clone(),
+                rack: node.rack.clone(),
+                release_version: None,
+            })
+            .collect();
+        let metadata = Metadata {
+            peers,
+            keyspaces: HashMap::new(),
+        };
+        let new_cluster = MockClusterStateBuilder::new(metadata).build().await;
+        assert_eq!(
+            get_plan_and_collect_node_identifiers(
+                policy.as_ref(),
+                &RoutingInfo::default(),
+                &new_cluster
+            ),
+            vec![3, 4, 5, 2]
+        );
+
+        assert_eq!(policy.state_of(node_1.host_id), CircuitState::Closed);
+        assert_eq!(policy.state_of(node_2.host_id), CircuitState::Open);
+        assert_eq!(policy.metrics().open_circuits, 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn circuit_breaker_decides_admission_once_per_plan() {
+        setup_tracing();
+        let cluster = mock_cluster_state_for_token_unaware_tests().await;
+        let policy = CircuitBreakerPolicy::builder(Arc::new(OrderedPolicy))
+            .failure_threshold(1)
+            .cooldown(Duration::from_secs(5))
+            .probe_ratio(1.)
+            .build();
+        let routing_info = RoutingInfo::default();
+
+        // Node 1 is both picked and returned by fallback of the inner policy.
+        fail(&policy, node_by_port(&cluster, 1));
+        assert_eq!(
+            get_plan_and_collect_node_identifiers(policy.as_ref(), &routing_info, &cluster),
+            vec![2, 3, 4, 5, 1]
+        );
+        assert_eq!(policy.metrics().diverted, 1);
+
+        tokio::time::advance(Duration::from_secs(5)).await;
+        assert_eq!(
+            get_plan_and_collect_node_identifiers(policy.as_ref(), &routing_info, &cluster),
+            vec![1, 2, 3, 4, 5]
+        );
+        let metrics = policy.metrics();
+        assert_eq!(metrics.probes, 1);
+        assert_eq!(metrics.diverted, 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn circuit_breaker_decides_admission_once_per_repeated_node() {
+        setup_tracing();
+        let cluster = mock_cluster_state_for_token_unaware_tests().await;
+        let policy = CircuitBreakerPolicy::builder(Arc::new(RepeatingPolicy))
+            .failure_threshold(1)
+            .cooldown(Duration::from_secs(5))
+            .probe_ratio(1.)
+            .build();
+        let routing_info = RoutingInfo::default();
+
+        // Every node appears twice in the inner plan, but only once in the resulting one.
+        assert_eq!(
+            get_plan_and_collect_node_identifiers(policy.as_ref(), &routing_info, &cluster),
+            vec![1, 2, 3, 4, 5]
+        );
+
+        fail(&policy, node_by_port(&cluster, 1));
+        assert_eq!(
+            get_plan_and_collect_node_identifiers(policy.as_ref(), &routing_info, &cluster),
+            vec![2, 3, 4, 5, 1]
+        );
+        assert_eq!(policy.metrics().diverted, 1);
+
+        // The half-open node is probed once, although it appears twice in the inner plan.
+        tokio::time::advance(Duration::from_secs(5)).await;
+        assert_eq!(
+            get_plan_and_collect_node_identifiers(policy.as_ref(), &routing_info, &cluster),
+            vec![1, 2, 3, 4, 5]
+        );
+        let metrics = policy.metrics();
+        assert_eq!(metrics.probes, 1);
+        assert_eq!(metrics.diverted, 1);
+    }
+}
@@ -3,6 +3,7 @@ pub use self::latency_awareness::LatencyAwarenessBuilder;
 
 use super::{FallbackPlan, LoadBalancingPolicy, NodeRef, RoutingInfo};
 use crate::cluster::ClusterState;
+use crate::observability::log_targets;
 use crate::{
     cluster::metadata::Strategy,
     cluster::node::Node,
@@ -14,6 +15,7 @@ use itertools::{Either, Itertools};
 use rand::{Rng, prelude::SliceRandom, rng};
 use rand_pcg::Pcg32;
 use scylla_cql::frame::response::result::TableSpec;
+use std::collections::{HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 use std::{fmt, sync::Arc, time::Duration};
 use tracing::{debug, warn};
@@ -122,6 +124,20 @@ pub struct DefaultPolicy {
     /// If no preferred DC is set, this has no effect.
     permit_dc_failover: bool,
 
+    /// Whether to permit remote nodes in plans for requests executed with a DC-local
+    /// consistency (`LOCAL_ONE`, `LOCAL_QUORUM`, `LOCAL_SERIAL`).
+    /// If DC failover is not permitted at all, this has no effect.
+    permit_dc_failover_for_local_consistency: bool,
+
+    /// If set, remote replicas are put in plans only after all local nodes
+    /// (both replicas and non-replicas). Otherwise, remote replicas are preferred
+    /// over local non-replicas.
+    dc_failover_after_local_nodes: bool,
+
+    /// The maximum number of nodes from each remote DC that may be put in a plan.
+    /// `None` means no limit.
+    used_nodes_per_remote_dc: Option<usize>,
+
     /// A predicate that a target (node + shard) must satisfy in order to be picked.
     /// This was introduced to make latency awareness cleaner.
     /// - if latency awareness is disabled, then `pick_predicate` is just `Self::is_alive()`;
@@ -151,6 +167,15 @@ impl fmt::Debug for DefaultPolicy {
             .field("preferences", &self.preferences)
             .field("is_token_aware", &self.is_token_aware)
             .field("permit_dc_failover", &self.permit_dc_failover)
+            .field(
+                "permit_dc_failover_for_local_consistency",
+                &self.permit_dc_failover_for_local_consistency,
+            )
+            .field(
+                "dc_failover_after_local_nodes",
+                &self.dc_failover_after_local_nodes,
+            )
+            .field("used_nodes_per_remote_dc", &self.used_nodes_per_remote_dc)
             .field("latency_awareness", &self.latency_awareness)
             .field("fixed_seed", &self.fixed_seed)
             .finish_non_exhaustive()
@@ -163,171 +188,18 @@ impl LoadBalancingPolicy for DefaultPolicy {
         query: &'a RoutingInfo,
         cluster: &'a ClusterState,
     ) -> Option<(NodeRef<'a>, Option<Shard>)> {
-        /* For prepared statements, token-aware logic is available, we know what are the replicas
-         * for the statement, so that we can pick one of them. */
-        let routing_info = self.routing_info(query, cluster);
-
-        if let Some(ref token_with_strategy) = routing_info.token_with_strategy {
-            if self.preferences.datacenter().is_some()
-                && !self.permit_dc_failover
-                && matches!(
-                    token_with_strategy.strategy,
-                    Strategy::SimpleStrategy { .. }
-                )
-            {
-                warn!(
-                    "\
-Combining SimpleStrategy with preferred_datacenter set to Some and disabled datacenter failover may lead to empty query plans for some tokens.\
-It is better to give up using one of them: either operate in a keyspace with NetworkTopologyStrategy, which explicitly states\
-how many replicas there are in each datacenter (you probably want at least 1 to avoid empty plans while preferring that datacenter), \
-or refrain from preferring datacenters (which may ban all other datacenters, if datacenter failover happens to be not possible)."
-                );
-            }
-        }
-
-        /* LWT statements need to be routed differently: always to the same replica, to avoid Paxos contention. */
-        let statement_type = if query.is_confirmed_lwt {
-            StatementType::Lwt
-        } else {
-            StatementType::NonLwt
-        };
-
-        /* Token-aware logic - if routing info is available, we know what are the replicas
-         * for the statement. Try to pick one of them. */
-        if let (Some(ts), Some(table_spec)) = (&routing_info.token_with_strategy, query.table) {
-            if let NodeLocationPreference::DatacenterAndRack(dc, rack) = &self.preferences {
-                // Try to pick some alive local rack random replica.
-                let local_rack_picked = self.pick_replica(
-                    ts,
-                    NodeLocationCriteria::DatacenterAndRack(dc, rack),
-                    |node, shard| (self.pick_predicate)(node, Some(shard)),
-                    cluster,
-                    statement_type,
-                    table_spec,
-                );
-
-                if let Some(picked) = local_rack_picked {
-                    return match picked {
-                        PickedReplica::Computed((alive_local_rack_replica, shard)) => {
-                            Some((alive_local_rack_replica, Some(shard)))
-                        }
-                        // Let call to fallback() compute the replica, because it requires allocation.
-                        PickedReplica::ToBeComputedInFallback => None,
-                    };
-                }
-            }
-
-            if let NodeLocationPreference::DatacenterAndRack(dc, _)
-            | NodeLocationPreference::Datacenter(dc) = &self.preferences
-            {
-                // Try to pick some alive local random replica.
-                let picked = self.pick_replica(
-                    ts,
-                    NodeLocationCriteria::Datacenter(dc),
-                    |node, shard| (self.pick_predicate)(node, Some(shard)),
-                    cluster,
-                    statement_type,
-                    table_spec,
-                );
-
-                if let Some(picked) = picked {
-                    return match picked {
-                        PickedReplica::Computed((alive_local_replica, shard)) => {
-                            Some((alive_local_replica, Some(shard)))
-                        }
-                        // Let call to fallback() compute the replica, because it requires allocation.
-                        PickedReplica::ToBeComputedInFallback => None,
-                    };
-                }
-            }
-
-            // If preferred datacenter is not specified, or if datacenter failover is possible, loosen restriction about locality.
-            if self.preferences.datacenter().is_none() || self.is_datacenter_failover_possible() {
-                // Try to pick some alive random replica.
-                let picked = self.pick_replica(
-                    ts,
-                    NodeLocationCriteria::Any,
-                    |node, shard| (self.pick_predicate)(node, Some(shard)),
-                    cluster,
-                    statement_type,
-                    table_spec,
-                );
-                if let Some(picked) = picked {
-                    return match picked {
-                        PickedReplica::Computed((alive_remote_replica, shard)) => {
-                            Some((alive_remote_replica, Some(shard)))
-                        }
-                        // Let call to fallback() compute the replica, because it requires allocation.
-                        PickedReplica::ToBeComputedInFallback => None,
-                    };
-                }
-            }
-        };
-
-        /* Token-unaware logic - if routing info is not available (e.g. for unprepared statements),
-         * or no replica was suitable for targeting it (e.g. disabled or down), try to choose
-         * a random node, not necessarily a replica. */
-
-        /* We start having not alive nodes filtered out. This is done by `pick_predicate`,
-         * which always contains `Self::is_alive()`. */
-
-        // Let's start with local nodes, i.e. those in the preferred datacenter.
-        // If there was no preferred datacenter specified, all nodes are treated as local.
-        let local_nodes = self.preferred_node_set(cluster);
-
-        if let NodeLocationPreference::DatacenterAndRack(dc, rack) = &self.preferences {
-            // Try to pick some alive random local rack node.
-            let rack_predicate = Self::make_rack_predicate(
-                |node| (self.pick_predicate)(node, None),
-                NodeLocationCriteria::DatacenterAndRack(dc, rack),
-            );
-            let local_rack_node_picked = self.pick_node(local_nodes, rack_predicate);
-
-            if let Some(alive_local_rack_node) = local_rack_node_picked {
-                return Some((alive_local_rack_node, None));
-            }
-        }
-
-        // Try to pick some alive random local node.
-        let local_node_picked =
-            self.pick_node(local_nodes, |node| (self.pick_predicate)(node, None));
-        if let Some(alive_local_node) = local_node_picked {
-            return Some((alive_local_node, None));
-        }
-
-        let all_nodes = cluster.replica_locator().unique_nodes_in_global_ring();
-        // If a datacenter failover is possible, loosen restriction about locality.
-        if self.is_datacenter_failover_possible() {
-            let maybe_remote_node_picked =
-                self.pick_node(all_nodes, |node| (self.pick_predicate)(node, None));
-            if let Some(alive_maybe_remote_node) = maybe_remote_node_picked {
-                return Some((alive_maybe_remote_node, None));
-            }
-        }
-
-        /* As we are here, we failed to pick any alive node. Now let's consider even down nodes. */
-
-        // Previous checks imply that every node we could have selected is down.
-        // Let's try to return a down node that wasn't disabled.
-        let maybe_down_local_node_picked = self.pick_node(local_nodes, |node| node.is_enabled());
-        if let Some(down_but_enabled_local_node) = maybe_down_local_node_picked {
-            return Some((down_but_enabled_local_node, None));
-        }
-
-        // If a datacenter failover is possible, loosen restriction about locality.
-        if self.is_datacenter_failover_possible() {
-            let maybe_down_maybe_remote_node_picked =
-                self.pick_node(all_nodes, |node| node.is_enabled());
-            if let Some(down_but_enabled_maybe_remote_node) = maybe_down_maybe_remote_node_picked {
-                return Some((down_but_enabled_maybe_remote_node, None));
-            }
+        let (node, shard) = self.pick_target(query, cluster)?;
+        if self.used_nodes_per_remote_dc.is_some()
+            && self
+                .preferences
+                .datacenter()
+                .is_some_and(|local_dc| node.datacenter.as_deref() != Some(local_dc))
+        {
+            // The picked node is remote, so it has to count towards `used_nodes_per_remote_dc`.
+            // Let call to fallback() compute the target, so that the limit holds for the whole plan.
+            return None;
         }
-
-        // Every node is disabled. This could be due to a bad host filter - configuration error.
-        // It makes no sense to return disabled nodes (there are no open connections to them anyway),
-        // so let's return None. `fallback()` will return empty iterator, and so the whole plan
-        // will be empty.
-        None
+        Some((node, shard))
     }
 
     fn fallback<'a>(
@@ -350,7 +222,7 @@ or refrain from preferring datacenters (which may ban all other datacenters, if
          * Get a list of alive replicas:
          * - shuffled list in case of non-LWTs,
          * - deterministically ordered in case of LWTs. */
-        let maybe_replicas = if let (Some(ts), Some(table_spec)) =
+        let (maybe_replicas, maybe_late_remote_replicas) = if let (Some(ts), Some(table_spec)) =
             (&routing_info.token_with_strategy, query.table)
         {
             // Iterator over alive local rack replicas (shuffled or deterministically ordered,
@@ -391,7 +263,7 @@ or refrain from preferring datacenters (which may ban all other datacenters, if
 
             // If no datacenter is preferred, or datacenter failover is possible, loosen restriction about locality.
             let maybe_remote_replicas = if self.preferences.datacenter().is_none()
-                || self.is_datacenter_failover_possible()
+                || self.is_datacenter_failover_possible(query)
             {
                 // Iterator over alive replicas (shuffled or deterministically ordered,
                 // depending on the statement being LWT or not).
@@ -408,16 +280,36 @@ or refrain from preferring datacenters (which may ban all other datacenters, if
                 Either::Right(std::iter::empty())
             };
 
-            // Produce an iterator, prioritizing local replicas.
-            // If preferred datacenter is not specified, every replica is treated as a remote one.
-            Either::Left(
-                maybe_local_rack_replicas
-                    .chain(maybe_local_replicas)
-                    .chain(maybe_remote_replicas)
-                    .map(|(node, shard)| (node, Some(shard))),
+            // Remote replicas are put either right after local replicas, or - if configured
+            // so - after all local nodes. If preferred datacenter is not specified, every replica
+            // is treated as a remote one, and they are always put right after local replicas.
+            let (early_remote_replicas, late_remote_replicas) =
+                if self.preferences.datacenter().is_some() && self.dc_failover_after_local_nodes {
+                    (None, Some(maybe_remote_replicas))
+                } else {
+                    (Some(maybe_remote_replicas), None)
+                };
+
+            // Produce iterators, prioritizing local replicas.
+            (
+                Either::Left(
+                    maybe_local_rack_replicas
+                        .chain(maybe_local_replicas)
+                        .chain(early_remote_replicas.into_iter().flatten())
+                        .map(|(node, shard)| (node, Some(shard))),
+                ),
+                Either::Left(
+                    late_remote_replicas
+                        .into_iter()
+                        .flatten()
+                        .map(|(node, shard)| (node, Some(shard))),
+                ),
             )
         } else {
-            Either::Right(std::iter::empty::<(NodeRef<'a>, Option<Shard>)>())
+            (
+                Either::Right(std::iter::empty::<(NodeRef<'a>, Option<Shard>)>()),
+                Either::Right(std::iter::empty::<(NodeRef<'a>, Option<Shard>)>()),
+            )
         };
 
         /* Token-unaware logic - if routing info is not available (e.g. for unprepared statements),
@@ -451,7 +343,7 @@ or refrain from preferring datacenters (which may ban all other datacenters, if
         let all_nodes = cluster.replica_locator().unique_nodes_in_global_ring();
 
         // If a datacenter failover is possible, loosen restriction about locality.
-        let maybe_remote_nodes = if self.is_datacenter_failover_possible() {
+        let maybe_remote_nodes = if self.is_datacenter_failover_possible(query) {
             let robinned_all_nodes =
                 self.round_robin_nodes(all_nodes, |node| Self::is_alive(node, None));
 
@@ -467,7 +359,7 @@ or refrain from preferring datacenters (which may ban all other datacenters, if
             .map(|node| (node, None));
 
         // If a datacenter failover is possible, loosen restriction about locality.
-        let maybe_down_nodes = if self.is_datacenter_failover_possible() {
+        let maybe_down_nodes = if self.is_datacenter_failover_possible(query) {
             Either::Left(
                 all_nodes
                     .iter()
@@ -526,63 +418,248 @@ or refrain from preferring datacenters (which may ban all other datacenters, if
         // Construct a fallback plan as a composition of:
         // - local rack alive replicas,
         // - local datacenter alive replicas (or all alive replicas is no DC is preferred),
-        // - remote alive replicas (if DC failover is enabled),
+        // - remote alive replicas (if DC failover is enabled and remote replicas
+        //   are not configured to be used only after local nodes),
         // - local rack alive nodes,
         // - local datacenter alive nodes (or all alive nodes is no DC is preferred),
+        // - remote alive replicas (if DC failover is enabled and remote replicas
+        //   are configured to be used only after local nodes),
         // - remote alive nodes (if DC failover is enabled),
         // - local datacenter nodes,
         // - remote nodes (if DC failover is enabled).
+        // Then, if the number of used nodes per remote DC is limited, excess remote nodes
+        // are filtered out.
         let plan = maybe_replicas
             .chain(robinned_local_rack_nodes)
             .chain(robinned_local_nodes)
+            .chain(maybe_late_remote_replicas)
             .chain(maybe_remote_nodes)
             .chain(maybe_down_local_nodes)
             .chain(maybe_down_nodes)
             .unique_by(|(node, shard)| DefaultPolicyTargetComparator {
                 host_id: node.host_id,
                 shard: *shard,
-            });
+            })
+            .filter(self.make_remote_dc_limit_predicate());
+
+        // If latency awareness is enabled, wrap the plan by applying latency penalisation:
+        // all penalised nodes are moved behind non-penalised nodes, in a stable fashion.
+        if let Some(latency_awareness) = self.latency_awareness.as_ref() {
+            Box::new(latency_awareness.wrap(plan))
+        } else {
+            Box::new(plan)
+        }
+    }
+
+    fn name(&self) -> String {
+        "DefaultPolicy".to_string()
+    }
+
+    fn on_request_success(
+        &self,
+        _routing_info: &RoutingInfo,
+        latency: Duration,
+        node: NodeRef<'_>,
+    ) {
+        if let Some(latency_awareness) = self.latency_awareness.as_ref() {
+            latency_awareness.report_request(node, latency);
+        }
+    }
+
+    fn on_request_failure(
+        &self,
+        _routing_info: &RoutingInfo,
+        latency: Duration,
+        node: NodeRef<'_>,
+        error: &RequestAttemptError,
+    ) {
+        if let Some(latency_awareness) = self.latency_awareness.as_ref() {
+            if LatencyAwareness::reliable_latency_measure(error) {
+                latency_awareness.report_request(node, latency);
+            }
+        }
+    }
+}
+
+impl DefaultPolicy {
+    /// Picks the target of the first attempt, regardless of `used_nodes_per_remote_dc`.
+    fn pick_target<'a>(
+        &'a self,
+        query: &'a RoutingInfo,
+        cluster: &'a ClusterState,
+    ) -> Option<(NodeRef<'a>, Option<Shard>)> {
+        /* For prepared statements, token-aware logic is available, we know what are the replicas
+         * for the statement, so that we can pick one of them. */
+        let routing_info = self.routing_info(query, cluster);
+
+        if let Some(ref token_with_strategy) = routing_info.token_with_strategy {
+            if self.preferences.datacenter().is_some()
+                && !self.permit_dc_failover
+                && matches!(
+                    token_with_strategy.strategy,
+                    Strategy::SimpleStrategy { .. }
+                )
+            {
+                warn!(
+                    target: log_targets::LOAD_BALANCING,
+                    "\
+                Combining SimpleStrategy with preferred_datacenter set to Some and disabled datacenter failover may lead to empty query plans for some tokens.\
+                It is better to give up using one of them: either operate in a keyspace with NetworkTopologyStrategy, which explicitly states\
+                how many replicas there are in each datacenter (you probably want at least 1 to avoid empty plans while preferring that datacenter), \
+                or refrain from preferring datacenters (which may ban all other datacenters, if datacenter failover happens to be not possible)."
+                );
+            }
+        }
+
+        /* LWT statements need to be routed differently: always to the same replica, to avoid Paxos contention. */
+        let statement_type = if query.is_confirmed_lwt {
+            StatementType::Lwt
+        } else {
+            StatementType::NonLwt
+        };
+
+        /* Token-aware logic - if routing info is available, we know what are the replicas
+         * for the statement. Try to pick one of them. */
+        if let (Some(ts), Some(table_spec)) = (&routing_info.token_with_strategy, query.table) {
+            if let NodeLocationPreference::DatacenterAndRack(dc, rack) = &self.preferences {
+                // Try to pick some alive local rack random replica.
+                let local_rack_picked = self.pick_replica(
+                    ts,
+                    NodeLocationCriteria::DatacenterAndRack(dc, rack),
+                    |node, shard| (self.pick_predicate)(node, Some(shard)),
+                    cluster,
+                    statement_type,
+                    table_spec,
+                );
+
+                if let Some(picked) = local_rack_picked {
+                    return match picked {
+                        PickedReplica::Computed((alive_local_rack_replica, shard)) => {
+                            Some((alive_local_rack_replica, Some(shard)))
+                        }
+                        // Let call to fallback() compute the replica, because it requires allocation.
+                        PickedReplica::ToBeComputedInFallback => None,
+                    };
+                }
+            }
+
+            if let NodeLocationPreference::DatacenterAndRack(dc, _)
+            | NodeLocationPreference::Datacenter(dc) = &self.preferences
+            {
+                // Try to pick some alive local random replica.
+                let picked = self.pick_replica(
+                    ts,
+                    NodeLocationCriteria::Datacenter(dc),
+                    |node, shard| (self.pick_predicate)(node, Some(shard)),
+                    cluster,
+                    statement_type,
+                    table_spec,
+                );
+
+                if let Some(picked) = picked {
+                    return match picked {
+                        PickedReplica::Computed((alive_local_replica, shard)) => {
+                            Some((alive_local_replica, Some(shard)))
+                        }
+                        // Let call to fallback() compute the replica, because it requires allocation.
+                        PickedReplica::ToBeComputedInFallback => None,
+                    };
+                }
+            }
+
+            // If preferred datacenter is not specified, or if datacenter failover is possible
+            // and remote replicas are preferred over local nodes, loosen restriction about locality.
+            if self.preferences.datacenter().is_none()
+                || (self.is_datacenter_failover_possible(query)
+                    && !self.dc_failover_after_local_nodes)
+            {
+                // Try to pick some alive random replica.
+                if let Some(picked) = self.pick_any_replica(ts, cluster, statement_type, table_spec)
+                {
+                    return picked;
+                }
+            }
+        };
+
+        /* Token-unaware logic - if routing info is not available (e.g. for unprepared statements),
+         * or no replica was suitable for targeting it (e.g. disabled or down), try to choose
+         * a random node, not necessarily a replica. */
+
+        /* We start having not alive nodes filtered out. This is done by `pick_predicate`,
+         * which always contains `Self::is_alive()`. */
+
+        // Let's start with local nodes, i.e. those in the preferred datacenter.
+        // If there was no preferred datacenter specified, all nodes are treated as local.
+        let local_nodes = self.preferred_node_set(cluster);
+
+        if let NodeLocationPreference::DatacenterAndRack(dc, rack) = &self.preferences {
+            // Try to pick some alive random local rack node.
+            let rack_predicate = Self::make_rack_predicate(
+                |node| (self.pick_predicate)(node, None),
+                NodeLocationCriteria::DatacenterAndRack(dc, rack),
+            );
+            let local_rack_node_picked = self.pick_node(local_nodes, rack_predicate);
+
+            if let Some(alive_local_rack_node) = local_rack_node_picked {
+                return Some((alive_local_rack_node, None));
+            }
+        }
 
-        // If latency awareness is enabled, wrap the plan by applying latency penalisation:
-        // all penalised nodes are moved behind non-penalised nodes, in a stable fashion.
-        if let Some(latency_awareness) = self.latency_awareness.as_ref() {
-            Box::new(latency_awareness.wrap(plan))
-        } else {
-            Box::new(plan)
+        // Try to pick some alive random local node.
+        let local_node_picked =
+            self.pick_node(local_nodes, |node| (self.pick_predicate)(node, None));
+        if let Some(alive_local_node) = local_node_picked {
+            return Some((alive_local_node, None));
         }
-    }
 
-    fn name(&self) -> String {
-        "DefaultPolicy".to_string()
-    }
+        let all_nodes = cluster.replica_locator().unique_nodes_in_global_ring();
+        // If a datacenter failover is possible, loosen restriction about locality.
+        if self.is_datacenter_failover_possible(query) {
+            // If remote replicas were not considered before local nodes, try them now.
+            if self.dc_failover_after_local_nodes {
+                if let (Some(ts), Some(table_spec)) =
+                    (&routing_info.token_with_strategy, query.table)
+                {
+                    if let Some(picked) =
+                        self.pick_any_replica(ts, cluster, statement_type, table_spec)
+                    {
+                        return picked;
+                    }
+                }
+            }
 
-    fn on_request_success(
-        &self,
-        _routing_info: &RoutingInfo,
-        latency: Duration,
-        node: NodeRef<'_>,
-    ) {
-        if let Some(latency_awareness) = self.latency_awareness.as_ref() {
-            latency_awareness.report_request(node, latency);
+            let maybe_remote_node_picked =
+                self.pick_node(all_nodes, |node| (self.pick_predicate)(node, None));
+            if let Some(alive_maybe_remote_node) = maybe_remote_node_picked {
+                return Some((alive_maybe_remote_node, None));
+            }
         }
-    }
 
-    fn on_request_failure(
-        &self,
-        _routing_info: &RoutingInfo,
-        latency: Duration,
-        node: NodeRef<'_>,
-        error: &RequestAttemptError,
-    ) {
-        if let Some(latency_awareness) = self.latency_awareness.as_ref() {
-            if LatencyAwareness::reliable_latency_measure(error) {
-                latency_awareness.report_request(node, latency);
+        /* As we are here, we failed to pick any alive node. Now let's consider even down nodes. */
+
+        // Previous checks imply that every node we could have selected is down.
+        // Let's try to return a down node that wasn't disabled.
+        let maybe_down_local_node_picked = self.pick_node(local_nodes, |node| node.is_enabled());
+        if let Some(down_but_enabled_local_node) = maybe_down_local_node_picked {
+            return Some((down_but_enabled_local_node, None));
+        }
+
+        // If a datacenter failover is possible, loosen restriction about locality.
+        if self.is_datacenter_failover_possible(query) {
+            let maybe_down_maybe_remote_node_picked =
+                self.pick_node(all_nodes, |node| node.is_enabled());
+            if let Some(down_but_enabled_maybe_remote_node) = maybe_down_maybe_remote_node_picked {
+                return Some((down_but_enabled_maybe_remote_node, None));
             }
         }
+
+        // Every node is disabled. This could be due to a bad host filter - configuration error.
+        // It makes no sense to return disabled nodes (there are no open connections to them anyway),
+        // so let's return None. `fallback()` will return empty iterator, and so the whole plan
+        // will be empty.
+        None
     }
-}
 
-impl DefaultPolicy {
     /// Creates a builder used to customise configuration of a new DefaultPolicy.
     pub fn builder() -> DefaultPolicyBuilder {
         DefaultPolicyBuilder::new()
@@ -614,6 +691,7 @@ impl DefaultPolicy {
                 nodes
             } else {
                 tracing::warn!(
+                    target: log_targets::LOAD_BALANCING,
                     "Datacenter specified as the preferred one ({}) does not exist!",
                     preferred_datacenter
                 );
@@ -905,12 +983,74 @@ impl DefaultPolicy {
         // We could theoretically only return true if we have a connection open to given shard, but:
         //  - There is no public API to check that, and I don't want DefaultPolicy to use private APIs.
         //  - Shards returned from policy are only a hint anyway, so it probably makes no sense to throw out the whole host.
-        node.is_connected()
+        // Nodes which are not connected yet because the session connects lazily are considered alive,
+        // as connections to them are opened once they are picked.
+        node.is_connected() || node.is_idle()
+    }
+
+    /// Picks a random replica (or the first one, for LWTs) regardless of its location.
+    ///
+    /// The returned value is the result of `pick()`, wrapped in `Some` if `pick()`
+    /// should return it, or `None` if no suitable replica was found.
+    fn pick_any_replica<'a>(
+        &'a self,
+        ts: &TokenWithStrategy<'a>,
+        cluster: &'a ClusterState,
+        statement_type: StatementType,
+        table_spec: &TableSpec,
+    ) -> Option<Option<(NodeRef<'a>, Option<Shard>)>> {
+        let picked = self.pick_replica(
+            ts,
+            NodeLocationCriteria::Any,
+            |node, shard| (self.pick_predicate)(node, Some(shard)),
+            cluster,
+            statement_type,
+            table_spec,
+        )?;
+        Some(match picked {
+            PickedReplica::Computed((alive_remote_replica, shard)) => {
+                Some((alive_remote_replica, Some(shard)))
+            }
+            // Let call to fallback() compute the replica, because it requires allocation.
+            PickedReplica::ToBeComputedInFallback => None,
+        })
+    }
+
+    /// Returns a stateful predicate that lets through at most `used_nodes_per_remote_dc`
+    /// distinct nodes from each remote datacenter. Local nodes are always let through.
+    fn make_remote_dc_limit_predicate<'a>(
+        &'a self,
+    ) -> impl FnMut(&(NodeRef<'a>, Option<Shard>)) -> bool + 'a {
+        let mut used_remote_nodes: HashMap<Option<&'a str>, HashSet<Uuid>> = HashMap::new();
+        move |(node, _shard)| {
+            let (Some(limit), Some(local_dc)) =
+                (self.used_nodes_per_remote_dc, self.preferences.datacenter())
+            else {
+                return true;
+            };
+            let node_dc = node.datacenter.as_deref();
+            if node_dc == Some(local_dc) {
+                return true;
+            }
+            let used_in_dc = used_remote_nodes.entry(node_dc).or_default();
+            if used_in_dc.contains(&node.host_id) {
+                // The node is already in the plan (with another shard).
+                true
+            } else if used_in_dc.len() < limit {
+                used_in_dc.insert(node.host_id);
+                true
+            } else {
+                false
+            }
+        }
     }
 
     /// Returns true iff the datacenter failover is permitted for the statement being executed.
-    fn is_datacenter_failover_possible(&self) -> bool {
-        self.preferences.datacenter().is_some() && self.permit_dc_failover
+    fn is_datacenter_failover_possible(&self, query: &RoutingInfo) -> bool {
+        self.preferences.datacenter().is_some()
+            && self.permit_dc_failover
+            && self.used_nodes_per_remote_dc != Some(0)
+            && (self.permit_dc_failover_for_local_consistency || !query.consistency.is_dc_local())
     }
 }
 
@@ -920,6 +1060,9 @@ impl Default for DefaultPolicy {
             preferences: NodeLocationPreference::Any,
             is_token_aware: true,
             permit_dc_failover: false,
+            permit_dc_failover_for_local_consistency: true,
+            dc_failover_after_local_nodes: false,
+            used_nodes_per_remote_dc: None,
             pick_predicate: Box::new(Self::is_alive),
             latency_awareness: None,
             fixed_seed: None,
@@ -946,6 +1089,9 @@ pub struct DefaultPolicyBuilder {
     preferences: NodeLocationPreference,
     is_token_aware: bool,
     permit_dc_failover: bool,
+    permit_dc_failover_for_local_consistency: bool,
+    dc_failover_after_local_nodes: bool,
+    used_nodes_per_remote_dc: Option<usize>,
     latency_awareness: Option<LatencyAwarenessBuilder>,
     enable_replica_shuffle: bool,
 }
@@ -957,6 +1103,9 @@ impl DefaultPolicyBuilder {
             preferences: NodeLocationPreference::Any,
             is_token_aware: true,
             permit_dc_failover: false,
+            permit_dc_failover_for_local_consistency: true,
+            dc_failover_after_local_nodes: false,
+            used_nodes_per_remote_dc: None,
             latency_awareness: None,
             enable_replica_shuffle: true,
         }
@@ -979,11 +1128,18 @@ impl DefaultPolicyBuilder {
             preferences: self.preferences,
             is_token_aware: self.is_token_aware,
             permit_dc_failover: self.permit_dc_failover,
+            permit_dc_failover_for_local_consistency: self.permit_dc_failover_for_local_consistency,
+            dc_failover_after_local_nodes: self.dc_failover_after_local_nodes,
+            used_nodes_per_remote_dc: self.used_nodes_per_remote_dc,
             pick_predicate,
             latency_awareness,
             fixed_seed: (!self.enable_replica_shuffle).then(|| {
                 let seed = rand::random();
-                debug!("DefaultPolicy: setting fixed seed to {}", seed);
+                debug!(
+                    target: log_targets::LOAD_BALANCING,
+                    "DefaultPolicy: setting fixed seed to {}",
+                    seed,
+                );
                 seed
             }),
         })
@@ -1077,6 +1233,52 @@ impl DefaultPolicyBuilder {
         self
     }
 
+    /// Sets whether datacenter failover is permitted for requests executed
+    /// with a DC-local consistency, i.e. `LOCAL_ONE`, `LOCAL_QUORUM` or `LOCAL_SERIAL`.
+    ///
+    /// Sending such a request to a remote datacenter makes the remote datacenter
+    /// the "local" one from the coordinator's point of view, which may not be
+    /// what the user intended. Setting this to `false` makes the policy never
+    /// include remote nodes in plans for such requests, even if datacenter
+    /// failover is permitted in general.
+    ///
+    /// This option has no effect if datacenter failover is not permitted
+    /// (see [DefaultPolicyBuilder::permit_dc_failover]). It is enabled by default.
+    pub fn permit_dc_failover_for_local_consistency(mut self, permit: bool) -> Self {
+        self.permit_dc_failover_for_local_consistency = permit;
+        self
+    }
+
+    /// Sets whether remote datacenters are only failed over to after
+    /// all local nodes are exhausted.
+    ///
+    /// By default, if the request is token-aware, remote replicas are put
+    /// in plans before local non-replicas. When this option is enabled,
+    /// remote replicas are instead put after all local nodes, so that
+    /// a request leaves the preferred datacenter only if every local node
+    /// has been tried.
+    ///
+    /// This option has no effect if datacenter failover is not permitted
+    /// (see [DefaultPolicyBuilder::permit_dc_failover]). It is disabled by default.
+    pub fn dc_failover_after_local_nodes(mut self, enable: bool) -> Self {
+        self.dc_failover_after_local_nodes = enable;
+        self
+    }
+
+    /// Sets the maximum number of nodes from each remote datacenter that
+    /// the policy puts in a plan, similarly to the "used hosts per remote DC"
+    /// setting known from other drivers.
+    ///
+    /// The limit counts both replicas and non-replicas. Setting it to `0`
+    /// effectively disables datacenter failover.
+    ///
+    /// This option has no effect if datacenter failover is not permitted
+    /// (see [DefaultPolicyBuilder::permit_dc_failover]). By default, there is no limit.
+    pub fn used_nodes_per_remote_dc(mut self, limit: usize) -> Self {
+        self.used_nodes_per_remote_dc = Some(limit);
+        self
+    }
+
     /// Latency awareness is a mechanism that penalises nodes whose measured
     /// recent average latency classifies it as falling behind the others.
     ///
@@ -1144,53 +1346,41 @@ impl<'a> TokenWithStrategy<'a> {
 }
 
 #[cfg(test)]
-mod tests {
-    use std::collections::HashMap;
+pub(crate) mod tests {
+    use std::collections::{HashMap, HashSet};
 
     use scylla_cql::{Consistency, frame::types::SerialConsistency};
     use tracing::info;
 
     use self::framework::{
         ExpectedGroups, ExpectedGroupsBuilder, get_plan_and_collect_node_identifiers,
-        mock_cluster_state_for_token_unaware_tests,
     };
     use crate::policies::host_filter::HostFilter;
-    use crate::routing::locator::tablets::TabletsInfo;
     use crate::routing::locator::test::{
         TABLE_NTS_RF_2, TABLE_NTS_RF_3, TABLE_SS_RF_2, id_to_invalid_addr,
         mock_metadata_for_token_aware_tests,
     };
     use crate::{
         cluster::ClusterState,
-        policies::load_balancing::{
-            Plan, RoutingInfo, default::tests::framework::mock_cluster_state_for_token_aware_tests,
-        },
+        policies::load_balancing::{Plan, RoutingInfo},
         routing::Token,
-        test_utils::setup_tracing,
+        test_utils::{
+            mock_cluster_state_for_token_aware_tests, mock_cluster_state_for_token_unaware_tests,
+            setup_tracing,
+        },
     };
 
     use super::{DefaultPolicy, NodeLocationPreference};
 
     pub(crate) mod framework {
-        use crate::routing::locator::test::{
-            id_to_invalid_addr, mock_metadata_for_token_aware_tests,
-        };
-        use std::collections::{HashMap, HashSet};
-
-        use uuid::Uuid;
+        use std::collections::HashSet;
 
         use crate::{
-            cluster::{
-                ClusterState,
-                metadata::{Metadata, Peer},
-            },
+            cluster::ClusterState,
             policies::load_balancing::{LoadBalancingPolicy, Plan, RoutingInfo},
-            routing::Token,
             test_utils::setup_tracing,
         };
 
-        use super::TabletsInfo;
-
         #[derive(Debug)]
         enum ExpectedGroup {
             NonDeterministic(HashSet<u16>),
@@ -1392,74 +1582,6 @@ mod tests {
             expected_groups.assert_proper_grouping_in_plans(&[got]);
         }
 
-        // based on locator mock cluster
-        pub(crate) async fn mock_cluster_state_for_token_aware_tests() -> ClusterState {
-            let (connectivity_events_sender, _) = tokio::sync::mpsc::unbounded_channel();
-            let metadata = mock_metadata_for_token_aware_tests();
-            let state = ClusterState::new(
-                metadata,
-                &Default::default(),
-                &HashMap::new(),
-                &mut |_, _| (),
-                &None,
-                None,
-                &connectivity_events_sender,
-                TabletsInfo::new(),
-                &HashMap::new(),
-                #[cfg(feature = "metrics")]
-                &Default::default(),
-            )
-            .await;
-
-            for node in state.get_nodes_info() {
-                node.use_enabled_as_connected();
-            }
-
-            state
-        }
-
-        // creates ClusterState with info about 5 nodes living in 2 different datacenters
-        // ring field is minimal, not intended to influence the tests
-        pub(crate) async fn mock_cluster_state_for_token_unaware_tests() -> ClusterState {
-            let peers = [("eu", 1), ("eu", 2), ("eu", 3), ("us", 4), ("us", 5)]
-                .iter()
-                .map(|(dc, id)| Peer {
-                    datacenter: Some(dc.to_string()),
-                    rack: None,
-                    address: id_to_invalid_addr(*id),
-                    tokens: vec![Token::new(*id as i64 * 100)],
-                    host_id: Uuid::new_v4(),
-                })
-                .collect::<Vec<_>>();
-
-            let info = Metadata {
-                peers,
-                keyspaces: HashMap::new(),
-            };
-
-            let (connectivity_events_sender, _) = tokio::sync::mpsc::unbounded_channel();
-            let state = ClusterState::new(
-                info,
-                &Default::default(),
-                &HashMap::new(),
-                &mut |_, _| (),
-                &None,
-                None,
-                &connectivity_events_sender,
-                TabletsInfo::new(),
-                &HashMap::new(),
-                #[cfg(feature = "metrics")]
-                &Default::default(),
-            )
-            .await;
-
-            for node in state.get_nodes_info() {
-                node.use_enabled_as_connected();
-            }
-
-            state
-        }
-
         pub(crate) fn get_plan_and_collect_node_identifiers(
             policy: &impl LoadBalancingPolicy,
             query_info: &RoutingInfo,
@@ -1553,6 +1675,50 @@ mod tests {
         .await;
     }
 
+    #[tokio::test]
+    async fn test_default_policy_limits_used_nodes_per_remote_dc() {
+        setup_tracing();
+        let cluster = mock_cluster_state_for_token_unaware_tests().await;
+        let policy = DefaultPolicy {
+            preferences: NodeLocationPreference::Datacenter("eu".to_owned()),
+            permit_dc_failover: true,
+            used_nodes_per_remote_dc: Some(1),
+            ..Default::default()
+        };
+
+        for _ in 0..32 {
+            let plan =
+                get_plan_and_collect_node_identifiers(&policy, &EMPTY_ROUTING_INFO, &cluster);
+            assert_eq!(plan.len(), 4, "Unexpected plan: {plan:?}");
+            assert_eq!(
+                plan[..3].iter().copied().collect::<HashSet<_>>(),
+                HashSet::from([1, 2, 3])
+            );
+            assert!([4, 5].contains(&plan[3]), "Unexpected plan: {plan:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_default_policy_limits_used_nodes_per_remote_dc_when_picking_remote_node() {
+        setup_tracing();
+        let cluster = mock_cluster_state_for_token_unaware_tests().await;
+        // Local nodes are never picked, so `pick()` fails over to a remote node.
+        let policy = DefaultPolicy {
+            preferences: NodeLocationPreference::Datacenter("eu".to_owned()),
+            permit_dc_failover: true,
+            used_nodes_per_remote_dc: Some(1),
+            pick_predicate: Box::new(|node, _shard| node.datacenter.as_deref() != Some("eu")),
+            ..Default::default()
+        };
+
+        for _ in 0..32 {
+            let plan =
+                get_plan_and_collect_node_identifiers(&policy, &EMPTY_ROUTING_INFO, &cluster);
+            let remote_nodes = plan.iter().filter(|id| [4, 5].contains(*id)).count();
+            assert_eq!(remote_nodes, 1, "Unexpected plan: {plan:?}");
+        }
+    }
+
     #[tokio::test]
     async fn test_default_policy_with_token_aware_statements() {
         setup_tracing();
@@ -1664,6 +1830,127 @@ mod tests {
                     .group([C, B]) // local nodes
                     .build(), // failover is explicitly forbidden
             },
+            // Keyspace NTS with RF=2 with enabled DC failover, but forbidden for local consistencies
+            Test {
+                policy: DefaultPolicy {
+                    preferences: NodeLocationPreference::Datacenter("eu".to_owned()),
+                    is_token_aware: true,
+                    permit_dc_failover: true,
+                    permit_dc_failover_for_local_consistency: false,
+                    ..Default::default()
+                },
+                routing_info: RoutingInfo {
+                    token: Some(Token::new(160)),
+                    table: Some(TABLE_NTS_RF_2),
+                    consistency: Consistency::LocalQuorum,
+                    ..Default::default()
+                },
+                // going through the ring, we get order: F , A , C , D , G , B , E
+                //                                      us  eu  eu  us  eu  eu  us
+                //                                      r2  r1  r1  r1  r2  r1  r1
+                expected_groups: ExpectedGroupsBuilder::new()
+                    .group([A, G]) // pick + fallback local replicas
+                    .group([C, B]) // local nodes
+                    .build(), // failover is forbidden for local consistency
+            },
+            // Keyspace NTS with RF=2 with enabled DC failover, but forbidden for local consistencies,
+            // with non-local consistency.
+            Test {
+                policy: DefaultPolicy {
+                    preferences: NodeLocationPreference::Datacenter("eu".to_owned()),
+                    is_token_aware: true,
+                    permit_dc_failover: true,
+                    permit_dc_failover_for_local_consistency: false,
+                    ..Default::default()
+                },
+                routing_info: RoutingInfo {
+                    token: Some(Token::new(160)),
+                    table: Some(TABLE_NTS_RF_2),
+                    consistency: Consistency::Quorum,
+                    ..Default::default()
+                },
+                // going through the ring, we get order: F , A , C , D , G , B , E
+                //                                      us  eu  eu  us  eu  eu  us
+                //                                      r2  r1  r1  r1  r2  r1  r1
+                expected_groups: ExpectedGroupsBuilder::new()
+                    .group([A, G]) // pick + fallback local replicas
+                    .group([F, D]) // remote replicas
+                    .group([C, B]) // local nodes
+                    .group([E]) // remote nodes
+                    .build(),
+            },
+            // Keyspace NTS with RF=2 with enabled DC failover, after local nodes are exhausted
+            Test {
+                policy: DefaultPolicy {
+                    preferences: NodeLocationPreference::Datacenter("eu".to_owned()),
+                    is_token_aware: true,
+                    permit_dc_failover: true,
+                    dc_failover_after_local_nodes: true,
+                    ..Default::default()
+                },
+                routing_info: RoutingInfo {
+                    token: Some(Token::new(160)),
+                    table: Some(TABLE_NTS_RF_2),
+                    consistency: Consistency::Two,
+                    ..Default::default()
+                },
+                // going through the ring, we get order: F , A , C , D , G , B , E
+                //                                      us  eu  eu  us  eu  eu  us
+                //                                      r2  r1  r1  r1  r2  r1  r1
+                expected_groups: ExpectedGroupsBuilder::new()
+                    .group([A, G]) // pick + fallback local replicas
+                    .group([C, B]) // local nodes
+                    .group([F, D]) // remote replicas
+                    .group([E]) // remote nodes
+                    .build(),
+            },
+            // Keyspace NTS with RF=2 with enabled DC failover, with no remote nodes allowed
+            Test {
+                policy: DefaultPolicy {
+                    preferences: NodeLocationPreference::Datacenter("eu".to_owned()),
+                    is_token_aware: true,
+                    permit_dc_failover: true,
+                    used_nodes_per_remote_dc: Some(0),
+                    ..Default::default()
+                },
+                routing_info: RoutingInfo {
+                    token: Some(Token::new(160)),
+                    table: Some(TABLE_NTS_RF_2),
+                    consistency: Consistency::Two,
+                    ..Default::default()
+                },
+                // going through the ring, we get order: F , A , C , D , G , B , E
+                //                                      us  eu  eu  us  eu  eu  us
+                //                                      r2  r1  r1  r1  r2  r1  r1
+                expected_groups: ExpectedGroupsBuilder::new()
+                    .group([A, G]) // pick + fallback local replicas
+                    .group([C, B]) // local nodes
+                    .build(),
+            },
+            // Keyspace NTS with RF=2 with enabled DC failover, with up to 2 remote nodes per DC
+            Test {
+                policy: DefaultPolicy {
+                    preferences: NodeLocationPreference::Datacenter("eu".to_owned()),
+                    is_token_aware: true,
+                    permit_dc_failover: true,
+                    used_nodes_per_remote_dc: Some(2),
+                    ..Default::default()
+                },
+                routing_info: RoutingInfo {
+                    token: Some(Token::new(160)),
+                    table: Some(TABLE_NTS_RF_2),
+                    consistency: Consistency::Two,
+                    ..Default::default()
+                },
+                // going through the ring, we get order: F , A , C , D , G , B , E
+                //                                      us  eu  eu  us  eu  eu  us
+                //                                      r2  r1  r1  r1  r2  r1  r1
+                expected_groups: ExpectedGroupsBuilder::new()
+                    .group([A, G]) // pick + fallback local replicas
+                    .group([F, D]) // remote replicas
+                    .group([C, B]) // local nodes
+                    .build(), // no more remote nodes due to the limit
+            },
             // Keyspace NTS with RF=3 with enabled DC failover
             Test {
                 policy: DefaultPolicy {
@@ -2514,8 +2801,10 @@ mod tests {
 
                 Some(&FHostFilter)
             },
+            None,
             &connectivity_events_sender,
-            TabletsInfo::new(),
+            &Default::default(),
+            None,
             &HashMap::new(),
             #[cfg(feature = "metrics")]
             &Default::default(),
@@ -2584,8 +2873,10 @@ mod latency_awareness {
 
     use crate::cluster::node::Node;
     use crate::errors::{DbError, RequestAttemptError};
+    use crate::observability::log_targets;
     use crate::policies::load_balancing::NodeRef;
     use crate::routing::Shard;
+    use crate::utils::runtime;
     use std::{
         collections::HashMap,
         ops::Deref,
@@ -2657,6 +2948,7 @@ mod latency_awareness {
                         Ok(ts) => ts,
                         Err(e) => {
                             warn!(
+                                target: log_targets::LOAD_BALANCING,
                                 "Error while calculating average: {e}. \
                                 prev_avg_secs: {prev_avg_secs}, \
                                 last_latency_secs: {last_latency_secs}, \
@@ -2760,14 +3052,14 @@ mod latency_awareness {
             );
 
             let (updater_fut, updater_handle) = async move {
-                let mut update_scheduler = tokio::time::interval(update_rate);
+                let mut update_scheduler = runtime::interval(update_rate);
                 loop {
                     update_scheduler.tick().await;
                     updater.tick().await;
                 }
             }
             .remote_handle();
-            tokio::task::spawn(updater_fut);
+            runtime::spawn(updater_fut);
 
             Self {
                 _updater_handle: Some(updater_handle),
@@ -2786,7 +3078,7 @@ mod latency_awareness {
                 last_min_latency.load().map(|min_avg| match fast_enough(&node_avgs.read().unwrap(), node.host_id, exclusion_threshold, retry_period, minimum_measurements, min_avg) {
                     FastEnough::Yes => true,
                     FastEnough::No { average } => {
-                        trace!("Latency awareness: Penalising node {{address={}, datacenter={:?}, rack={:?}}} for being on average at least {} times slower (latency: {}ms) than the fastest ({}ms).",
+                        trace!(target: log_targets::LOAD_BALANCING, "Latency awareness: Penalising node {{address={}, datacenter={:?}, rack={:?}}} for being on average at least {} times slower (latency: {}ms) than the fastest ({}ms).",
                                 node.address, node.datacenter, node.rack, exclusion_threshold, average.as_millis(), min_avg.as_millis());
                         false
                     }
@@ -2817,7 +3109,7 @@ mod latency_awareness {
                 ) {
                     FastEnough::Yes => true,
                     FastEnough::No { average } => {
-                        trace!("Latency awareness: Penalising node {{address={}, datacenter={:?}, rack={:?}}} for being on average at least {} times slower (latency: {}ms) than the fastest ({}ms).",
+                        trace!(target: log_targets::LOAD_BALANCING, "Latency awareness: Penalising node {{address={}, datacenter={:?}, rack={:?}}} for being on average at least {} times slower (latency: {}ms) than the fastest ({}ms).",
                                 node.address, node.datacenter, node.rack, self.exclusion_threshold, average.as_millis(), min_avg_latency.as_millis());
                         false
                     }
@@ -2959,6 +3251,7 @@ mod latency_awareness {
             if let Some(min_avg) = min_avg {
                 self.min_latency.store(min_avg);
                 trace!(
+                    target: log_targets::LOAD_BALANCING,
                     "Latency awareness: updated min average latency to {} ms",
                     min_avg.as_secs_f64() * 1000.
                 );
@@ -3195,7 +3488,10 @@ mod latency_awareness {
             routing::Token,
             routing::locator::test::{A, B, C, D, E, F, G, id_to_invalid_addr},
             routing::locator::test::{TABLE_INVALID, TABLE_NTS_RF_2, TABLE_NTS_RF_3},
-            test_utils::setup_tracing,
+            test_utils::{
+                mock_cluster_state_for_token_aware_tests,
+                mock_cluster_state_for_token_unaware_tests, setup_tracing,
+            },
         };
         use tokio::time::Instant;
 
@@ -3255,6 +3551,7 @@ mod latency_awareness {
                 pick_predicate,
                 latency_awareness: Some(latency_awareness),
                 fixed_seed: None,
+                ..Default::default()
             }
         }
 
@@ -3288,7 +3585,7 @@ mod latency_awareness {
         async fn latency_aware_default_policy_does_not_penalise_if_no_latency_info_available_yet() {
             setup_tracing();
             let policy = latency_aware_default_policy();
-            let cluster = tests::mock_cluster_state_for_token_unaware_tests().await;
+            let cluster = mock_cluster_state_for_token_unaware_tests().await;
 
             let expected_groups = ExpectedGroupsBuilder::new()
                 .group([1, 2, 3]) // pick + fallback local nodes
@@ -3307,7 +3604,7 @@ mod latency_awareness {
         async fn latency_aware_default_policy_does_not_penalise_if_not_enough_measurements() {
             setup_tracing();
             let policy = latency_aware_default_policy();
-            let cluster = tests::mock_cluster_state_for_token_unaware_tests().await;
+            let cluster = mock_cluster_state_for_token_unaware_tests().await;
 
             let min_avg = Duration::from_millis(10);
 
@@ -3368,7 +3665,7 @@ mod latency_awareness {
         {
             setup_tracing();
             let policy = latency_aware_default_policy();
-            let cluster = tests::mock_cluster_state_for_token_unaware_tests().await;
+            let cluster = mock_cluster_state_for_token_unaware_tests().await;
 
             let min_avg = Duration::from_millis(10);
 
@@ -3430,7 +3727,7 @@ mod latency_awareness {
                 b.retry_period(Duration::from_millis(10))
             });
 
-            let cluster = tests::mock_cluster_state_for_token_unaware_tests().await;
+            let cluster = mock_cluster_state_for_token_unaware_tests().await;
 
             let min_avg = Duration::from_millis(10);
 
@@ -3491,7 +3788,7 @@ mod latency_awareness {
         async fn latency_aware_default_policy_penalises_if_conditions_met() {
             setup_tracing();
             let (policy, updater) = latency_aware_default_policy_with_explicit_updater();
-            let cluster = tests::mock_cluster_state_for_token_unaware_tests().await;
+            let cluster = mock_cluster_state_for_token_unaware_tests().await;
 
             let min_avg = Duration::from_millis(10);
 
@@ -3579,7 +3876,7 @@ mod latency_awareness {
 
             let (policy, updater) = latency_aware_default_policy_with_explicit_updater();
 
-            let cluster = tests::mock_cluster_state_for_token_unaware_tests().await;
+            let cluster = mock_cluster_state_for_token_unaware_tests().await;
 
             let min_avg = Duration::from_millis(10);
 
@@ -3708,7 +4005,7 @@ mod latency_awareness {
                 expected_groups: ExpectedGroups,
             }
 
-            let cluster = tests::mock_cluster_state_for_token_aware_tests().await;
+            let cluster = mock_cluster_state_for_token_aware_tests().await;
             let latency_awareness_defaults =
                 latency_aware_default_policy().latency_awareness.unwrap();
             let min_avg = Duration::from_millis(10);
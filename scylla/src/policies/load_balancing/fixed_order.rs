@@ -0,0 +1,140 @@
+use std::sync::Arc;
+
+use crate::cluster::{ClusterState, NodeRef};
+use crate::observability::log_targets;
+use crate::routing::Shard;
+
+use super::{FallbackPlan, LoadBalancingPolicy, NodeIdentifier, RoutingInfo};
+
+/// Load balancing policy that routes requests strictly to a fixed, ordered list of targets.
+///
+/// The plan always consists of the given targets (nodes with optional shards),
+/// in the given order. Targets that cannot be found in the cluster metadata
+/// are skipped. No other nodes are ever contacted, regardless of their state.
+///
+/// This is mostly useful in tests (e.g. together with `scylla-proxy`), where it is
+/// desirable to pin which node receives which request and in which order the nodes
+/// are tried upon retries and speculative executions.
+///
+/// # Example
+/// ```
+/// # fn example() {
+/// use std::net::SocketAddr;
+/// use scylla::policies::load_balancing::{FixedOrderPolicy, NodeIdentifier};
+///
+/// let first: SocketAddr = "127.0.0.1:9042".parse().unwrap();
+/// let second: SocketAddr = "127.0.0.2:9042".parse().unwrap();
+/// let policy = FixedOrderPolicy::new([
+///     (NodeIdentifier::NodeAddress(first), Some(0)),
+///     (NodeIdentifier::NodeAddress(second), None),
+/// ]);
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct FixedOrderPolicy {
+    targets: Vec<(NodeIdentifier, Option<Shard>)>,
+}
+
+impl FixedOrderPolicy {
+    /// Creates a new instance of [`FixedOrderPolicy`] routing requests to the given targets,
+    /// in the given order.
+    #[expect(clippy::new_ret_no_self)]
+    pub fn new(
+        targets: impl IntoIterator<Item = (NodeIdentifier, Option<Shard>)>,
+    ) -> Arc<dyn LoadBalancingPolicy> {
+        Arc::new(Self {
+            targets: targets.into_iter().collect(),
+        })
+    }
+
+    fn resolved_targets<'a>(
+        &'a self,
+        cluster: &'a ClusterState,
+    ) -> impl Iterator<Item = (NodeRef<'a>, Option<Shard>)> {
+        self.targets
+            .iter()
+            .filter_map(move |(node_identifier, shard)| {
+                match node_identifier.resolve(cluster) {
+                    Some(node) => Some((node, *shard)),
+                    None => {
+                        tracing::warn!(
+                            target: log_targets::LOAD_BALANCING,
+                            "FixedOrderPolicy failed to find requested node {:?} in cluster metadata.",
+                            node_identifier
+                        );
+                        None
+                    }
+                }
+            })
+    }
+}
+
+impl LoadBalancingPolicy for FixedOrderPolicy {
+    fn pick<'a>(
+        &'a self,
+        _request: &'a RoutingInfo,
+        cluster: &'a ClusterState,
+    ) -> Option<(NodeRef<'a>, Option<Shard>)> {
+        self.resolved_targets(cluster).next()
+    }
+
+    fn fallback<'a>(
+        &'a self,
+        _request: &'a RoutingInfo,
+        cluster: &'a ClusterState,
+    ) -> FallbackPlan<'a> {
+        // The picked target is filtered out by `Plan`, so we can simply return all targets.
+        Box::new(self.resolved_targets(cluster))
+    }
+
+    fn name(&self) -> String {
+        "FixedOrderPolicy".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use super::FixedOrderPolicy;
+    use crate::policies::load_balancing::{NodeIdentifier, Plan, RoutingInfo};
+    use crate::routing::locator::test::id_to_invalid_addr;
+    use crate::test_utils::{mock_cluster_state_for_token_unaware_tests, setup_tracing};
+
+    #[tokio::test]
+    async fn test_fixed_order_policy() {
+        setup_tracing();
+        let cluster = mock_cluster_state_for_token_unaware_tests().await;
+        let address = |id| {
+            let addr = id_to_invalid_addr(id);
+            NodeIdentifier::NodeAddress(SocketAddr::new(addr.ip(), addr.port()))
+        };
+        let host_id_of = |id| {
+            cluster
+                .get_nodes_info()
+                .iter()
+                .find(|node| node.address.port() == id)
+                .unwrap()
+                .host_id
+        };
+
+        let policy = FixedOrderPolicy::new([
+            (address(4), Some(1)),
+            // Nonexistent node is skipped.
+            (address(42), None),
+            (NodeIdentifier::HostId(host_id_of(2)), None),
+            (address(4), Some(0)),
+            (address(1), None),
+        ]);
+
+        for _ in 0..16 {
+            let routing_info = RoutingInfo::default();
+            let plan = Plan::new(policy.as_ref(), &routing_info, &cluster)
+                .map(|(node, shard)| (node.address.port(), shard))
+                .collect::<Vec<_>>();
+            // Shard-less targets get a random shard, and the mock nodes have no sharder,
+            // so their shard is always 0.
+            assert_eq!(plan, vec![(4, 1), (2, 0), (4, 0), (1, 0)]);
+        }
+    }
+}
@@ -11,11 +11,19 @@ use scylla_cql::frame::{response::result::TableSpec, types};
 
 use std::time::Duration;
 
+mod circuit_breaker;
 mod default;
+mod fixed_order;
 mod plan;
 mod single_target;
+pub use circuit_breaker::{
+    CircuitBreakerEvent, CircuitBreakerListener, CircuitBreakerMetrics, CircuitBreakerPolicy,
+    CircuitBreakerPolicyBuilder, CircuitState,
+};
 pub use default::{DefaultPolicy, DefaultPolicyBuilder, LatencyAwarenessBuilder};
+pub use fixed_order::FixedOrderPolicy;
 pub use plan::Plan;
+pub(crate) use plan::SaturationAwarePlan;
 pub use single_target::{NodeIdentifier, SingleTargetLoadBalancingPolicy};
 
 /// Represents info about statement that can be used by load balancing policies.
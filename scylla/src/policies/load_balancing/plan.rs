@@ -1,8 +1,15 @@
+use std::collections::VecDeque;
+#[cfg(feature = "metrics")]
+use std::sync::Arc;
+
+use crate::observability::log_targets;
 use rand::{Rng, rng};
-use tracing::error;
+use tracing::{error, trace};
 
 use super::{FallbackPlan, LoadBalancingPolicy, NodeRef, RoutingInfo};
 use crate::cluster::ClusterState;
+#[cfg(feature = "metrics")]
+use crate::observability::metrics::Metrics;
 use crate::routing::Shard;
 
 enum PlanState<'a> {
@@ -133,6 +140,7 @@ impl<'a> Iterator for Plan<'a> {
                         Some(Self::with_random_shard_if_unknown(node))
                     } else {
                         error!(
+                            target: log_targets::LOAD_BALANCING,
                             "Load balancing policy returned an empty plan! The query cannot be executed. Routing info: {:?}",
                             self.routing_info
                         );
@@ -168,6 +176,58 @@ impl<'a> Iterator for Plan<'a> {
     }
 }
 
+/// Moves saturated nodes, i.e. ones which reached the limit of requests in flight,
+/// to the end of a plan, so that requests are sent to the next nodes in the plan instead.
+///
+/// Saturation is checked lazily, when a target is about to be returned,
+/// so that the plan reflects the load of nodes at the time they are tried.
+pub(crate) struct SaturationAwarePlan<'a, I> {
+    plan: I,
+    deferred: VecDeque<(NodeRef<'a>, Shard)>,
+    #[cfg(feature = "metrics")]
+    metrics: Arc<Metrics>,
+}
+
+impl<'a, I> SaturationAwarePlan<'a, I>
+where
+    I: Iterator<Item = (NodeRef<'a>, Shard)>,
+{
+    pub(crate) fn new(plan: I, #[cfg(feature = "metrics")] metrics: Arc<Metrics>) -> Self {
+        Self {
+            plan,
+            deferred: VecDeque::new(),
+            #[cfg(feature = "metrics")]
+            metrics,
+        }
+    }
+}
+
+impl<'a, I> Iterator for SaturationAwarePlan<'a, I>
+where
+    I: Iterator<Item = (NodeRef<'a>, Shard)>,
+{
+    type Item = (NodeRef<'a>, Shard);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for target in self.plan.by_ref() {
+            if !target.0.is_saturated() {
+                return Some(target);
+            }
+            trace!(
+                target: log_targets::LOAD_BALANCING,
+                "Node {} is saturated, moving it to the end of the plan",
+                target.0.address
+            );
+            #[cfg(feature = "metrics")]
+            self.metrics.inc_saturated_node_skips();
+            self.deferred.push_back(target);
+        }
+
+        // Saturated nodes are still tried if all the other ones fail.
+        self.deferred.pop_front()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::{net::SocketAddr, str::FromStr, sync::Arc};
@@ -236,6 +296,10 @@ mod tests {
             all_nodes: Default::default(),
             keyspaces: Default::default(),
             locator,
+            ring_version: 0,
+            tablets_version: 0,
+            internal_requests_disabled_nodes: Default::default(),
+            custom_partitioners: Default::default(),
         };
         let routing_info = RoutingInfo::default();
         let plan = Plan::new(&policy, &routing_info, &cluster_state);
@@ -244,4 +308,36 @@ mod tests {
             policy.expected_nodes
         );
     }
+
+    #[test]
+    fn saturated_nodes_are_moved_to_the_end_of_plan() {
+        setup_tracing();
+        let limit = std::num::NonZeroUsize::new(1).unwrap();
+        let nodes: Vec<Arc<Node>> = (0..3)
+            .map(|_| {
+                Arc::new(
+                    Node::new_for_test(None, None, None, None).with_max_in_flight_requests(limit),
+                )
+            })
+            .collect();
+        #[cfg(feature = "metrics")]
+        let metrics = Arc::new(Metrics::new());
+
+        let plan_order = |nodes: &[Arc<Node>]| -> Vec<uuid::Uuid> {
+            SaturationAwarePlan::new(
+                nodes.iter().map(|node| (node, 0)),
+                #[cfg(feature = "metrics")]
+                Arc::clone(&metrics),
+            )
+            .map(|(node, _)| node.host_id)
+            .collect()
+        };
+        let ids: Vec<_> = nodes.iter().map(|node| node.host_id).collect();
+        assert_eq!(plan_order(&nodes), ids);
+
+        let _in_flight = [nodes[0].start_request(), nodes[1].start_request()];
+        assert_eq!(plan_order(&nodes), [ids[2], ids[0], ids[1]]);
+        #[cfg(feature = "metrics")]
+        assert_eq!(metrics.get_saturated_node_skips(), 2);
+    }
 }
@@ -4,6 +4,7 @@ use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::cluster::{ClusterState, Node, NodeRef};
+use crate::observability::log_targets;
 use crate::routing::Shard;
 
 use super::{LoadBalancingPolicy, RoutingInfo};
@@ -45,6 +46,20 @@ pub enum NodeIdentifier {
     NodeAddress(SocketAddr),
 }
 
+impl NodeIdentifier {
+    /// Looks up the identified node in the cluster metadata.
+    pub(crate) fn resolve<'a>(&'a self, cluster: &'a ClusterState) -> Option<NodeRef<'a>> {
+        match self {
+            NodeIdentifier::Node(node) => Some(node),
+            NodeIdentifier::HostId(host_id) => cluster.known_peers.get(host_id),
+            NodeIdentifier::NodeAddress(addr) => cluster
+                .all_nodes
+                .iter()
+                .find(|node| SocketAddr::new(node.address.ip(), node.address.port()) == *addr),
+        }
+    }
+}
+
 /// Load balancing policy that enforces a single target.
 ///
 /// It may be useful for queries to node-local system tables.
@@ -74,19 +89,11 @@ impl LoadBalancingPolicy for SingleTargetLoadBalancingPolicy {
         _request: &'a RoutingInfo,
         cluster: &'a ClusterState,
     ) -> Option<(NodeRef<'a>, Option<Shard>)> {
-        let node = match &self.node_identifier {
-            NodeIdentifier::Node(node) => Some(node),
-            NodeIdentifier::HostId(host_id) => cluster.known_peers.get(host_id),
-            NodeIdentifier::NodeAddress(addr) => cluster
-                .all_nodes
-                .iter()
-                .find(|node| SocketAddr::new(node.address.ip(), node.address.port()) == *addr),
-        };
-
-        match node {
+        match self.node_identifier.resolve(cluster) {
             Some(node) => Some((node, self.shard)),
             None => {
                 tracing::warn!(
+                    target: log_targets::LOAD_BALANCING,
                     "SingleTargetLoadBalancingPolicy failed to find requested node {:?} in cluster metadata.",
                     self.node_identifier
                 );
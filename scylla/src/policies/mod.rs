@@ -6,13 +6,19 @@
 //! This includes:
 //! - HostFilter, which is a way to filter out some nodes and thus
 //!   not contact them at all on any condition.
+//! - KeyspaceFilter, which decides whether schema metadata of a keyspace
+//!   is fetched or skipped.
 //! - AddressTranslator, which allows contacting a node through a different address
 //!   than its broadcast address (e.g., when it's behind a NAT).
 //! - LoadBalancingPolicy, which decides which nodes and shards to contact for each
 //!   request.
 //! - SpeculativeExecutionPolicy, which decides if the driver will send speculative
 //!   requests to the next hosts when the current host takes too long to respond.
+//! - ReconnectPolicy, which decides how long to wait before reconnecting to a node.
+//! - Resolver, which resolves hostnames and SRV records of contact points.
 //! - RetryPolicy, which decides whether and how to retry a request.
+//! - TabletListener, which is notified when the driver updates its tablet maps.
+//! - SchemaAgreementPolicy, which configures awaiting schema agreement.
 //! - TODO
 
 pub mod address_translator;
@@ -21,11 +27,12 @@ pub mod host_filter;
 pub mod host_listener;
 #[cfg(not(all(scylla_unstable, feature = "unstable-host-listener")))]
 pub(crate) mod host_listener;
+pub mod keyspace_filter;
 pub mod load_balancing;
-#[cfg(all(scylla_unstable, feature = "unstable-reconnect-policy"))]
 pub mod reconnect;
-#[cfg(not(all(scylla_unstable, feature = "unstable-reconnect-policy")))]
-pub(crate) mod reconnect;
+pub mod resolver;
 pub mod retry;
+pub mod schema_agreement;
 pub mod speculative_execution;
+pub mod tablet_listener;
 pub mod timestamp_generator;
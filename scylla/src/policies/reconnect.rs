@@ -1,11 +1,14 @@
-#![cfg_attr(
-    not(all(scylla_unstable, feature = "unstable-reconnect-policy")),
-    expect(unreachable_pub)
-)]
-
 //! Policy that controls delays between connection pool fill attempts.
 //!
-//! UNSTABLE API, guarded by `scylla_unstable` cfg and `unstable-reconnect-policy` feature.
+//! The driver keeps a connection pool for each node, which opens connections to all
+//! shards of the node. When connections fail to open or break, the pool reconnects
+//! after a delay chosen by the [ReconnectPolicy] set with
+//! [SessionBuilder::reconnect_policy](crate::client::session_builder::SessionBuilder::reconnect_policy).
+//!
+//! The driver provides [ExponentialReconnectPolicy] (the default) and [ConstantReconnectPolicy],
+//! both applying random jitter to the delays, so that many clients do not reconnect
+//! simultaneously after a network blip. Custom schedules can be implemented with
+//! the [ReconnectPolicy] and [ReconnectPolicySession] traits.
 
 use std::ops::RangeInclusive;
 use std::time::Duration;
@@ -33,6 +36,8 @@ pub trait ReconnectPolicySession: Send + std::fmt::Debug {
 /// this trait is not used directly by connection pools. Instead, the only
 /// purpose of this trait is to generate boxed instances of per-host policy objects.
 /// The generated objects are used by a single pool, and so can have methods with `&mut self`.
+/// A pool fills connections to all shards of its node in a single batch, so the schedule
+/// is shared by the shards of the node.
 pub trait ReconnectPolicy: Send + Sync + std::fmt::Debug {
     /// Returns a new instance of the policy for the single host.
     fn new_session(&self) -> Box<dyn ReconnectPolicySession>;
@@ -84,10 +89,6 @@ pub struct ExponentialReconnectPolicy {
     jitter_range: RangeInclusive<f64>,
 }
 
-#[cfg_attr(
-    not(all(scylla_unstable, feature = "unstable-reconnect-policy")),
-    expect(dead_code)
-)]
 impl ExponentialReconnectPolicy {
     /// Creates a new exponential reconnect policy with default values.
     pub fn new() -> Self {
@@ -165,10 +166,6 @@ pub struct ConstantReconnectPolicy {
     jitter_range: RangeInclusive<f64>,
 }
 
-#[cfg_attr(
-    not(all(scylla_unstable, feature = "unstable-reconnect-policy")),
-    expect(dead_code)
-)]
 impl ConstantReconnectPolicy {
     /// Creates a new constant reconnect policy with the given delay.
     pub fn new(duration: Duration) -> Self {
@@ -216,3 +213,53 @@ impl ReconnectPolicySession for ConstantReconnectPolicy {
 
     fn on_fill_error(&mut self) {}
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{ConstantReconnectPolicy, ExponentialReconnectPolicy, ReconnectPolicy};
+
+    #[test]
+    fn exponential_reconnect_policy() {
+        let policy = ExponentialReconnectPolicy::new()
+            .with_backoff_limits(Duration::from_millis(100), Duration::from_millis(1000))
+            .with_jitter_range(1.0..=1.0);
+        let mut session = policy.new_session();
+        assert_eq!(session.get_delay(), Duration::from_millis(100));
+
+        let mut delays = Vec::new();
+        for _ in 0..5 {
+            session.on_fill_error();
+            delays.push(session.get_delay().as_millis());
+        }
+        assert_eq!(delays, [200, 400, 800, 1000, 1000]);
+
+        session.on_successful_fill();
+        assert_eq!(session.get_delay(), Duration::from_millis(100));
+
+        // Sessions of different nodes are independent.
+        let other_session = policy.new_session();
+        session.on_fill_error();
+        assert_eq!(other_session.get_delay(), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn reconnect_policy_jitter() {
+        let exponential = ExponentialReconnectPolicy::new()
+            .with_backoff_limits(Duration::from_millis(100), Duration::from_secs(10))
+            .with_jitter_range(0.5..=1.5)
+            .new_session();
+        let constant = ConstantReconnectPolicy::new(Duration::from_millis(200))
+            .with_jitter_range(0.5..=1.5)
+            .new_session();
+        for _ in 0..100 {
+            // The delay is clamped to the minimum backoff.
+            let delay = exponential.get_delay();
+            assert!(Duration::from_millis(100) <= delay && delay <= Duration::from_millis(150));
+
+            let delay = constant.get_delay();
+            assert!(Duration::from_millis(100) <= delay && delay <= Duration::from_millis(300));
+        }
+    }
+}
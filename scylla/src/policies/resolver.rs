@@ -0,0 +1,123 @@
+//! Hostname resolution of contact points.
+//!
+//! Hostnames of known nodes, as well as SRV records given with
+//! [SessionBuilder::known_srv](crate::client::session_builder::SessionBuilder::known_srv),
+//! are resolved by a [Resolver]. They are resolved when the session is created and again
+//! when the driver falls back to the contact points, after no known peer is reachable.
+//! Hostnames are also resolved again before reconnecting to a contact point whose address
+//! is older than [SessionBuilder::hostname_resolution_ttl](crate::client::session_builder::SessionBuilder::hostname_resolution_ttl).
+//!
+//! [DefaultResolver] uses the resolver of the operating system. A custom resolver
+//! can be used, e.g., to query a service discovery mechanism directly.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::errors::ResolutionError;
+use crate::utils::runtime;
+
+/// A target of an SRV record: a host serving the service and its port.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SrvTarget {
+    /// Hostname of the target.
+    pub host: String,
+    /// Port on which the target serves the service.
+    pub port: u16,
+}
+
+impl SrvTarget {
+    /// Creates a new SRV target.
+    pub fn new(host: impl Into<String>, port: u16) -> Self {
+        Self {
+            host: host.into(),
+            port,
+        }
+    }
+}
+
+/// Resolves hostnames and SRV records of contact points into addresses.
+#[async_trait]
+pub trait Resolver: Send + Sync {
+    /// Resolves `host` into addresses.
+    ///
+    /// `host` is either a bare hostname or has the form `hostname:port`. In the former case,
+    /// `default_port` should be used. If multiple addresses are returned, the driver
+    /// prefers the first IPv4 one.
+    async fn resolve_host(
+        &self,
+        host: &str,
+        default_port: u16,
+    ) -> Result<Vec<SocketAddr>, ResolutionError>;
+
+    /// Looks up SRV records of `name`, e.g. `_cql._tcp.scylla.example.com`.
+    ///
+    /// Targets are then resolved with [Resolver::resolve_host].
+    /// The default implementation does not support SRV records.
+    async fn resolve_srv(&self, name: &str) -> Result<Vec<SrvTarget>, ResolutionError> {
+        let _ = name;
+        Err(ResolutionError::SrvNotSupported)
+    }
+}
+
+/// The default [Resolver], which uses the resolver of the operating system.
+///
+/// SRV records are looked up only if the `dns-srv` feature is enabled.
+#[derive(Debug, Clone, Default)]
+pub struct DefaultResolver {
+    _priv: (),
+}
+
+impl DefaultResolver {
+    /// Creates a new default resolver.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl Resolver for DefaultResolver {
+    async fn resolve_host(
+        &self,
+        host: &str,
+        default_port: u16,
+    ) -> Result<Vec<SocketAddr>, ResolutionError> {
+        // When passing str to `lookup_host`, it expects it to be in the form "hostname:port".
+        // If it is not, error will be returned immediately. In this case, we want to perform
+        // lookup with (hostname, default_port).
+        // There may be other errors than invalid value, but I don't really see
+        // any harm in trying again in such cases.
+        let addrs: Vec<SocketAddr> = match runtime::lookup_host(host).await {
+            Ok(addrs) => addrs,
+            // Use a default port in case of error, but propagate the original error on failure
+            Err(e) => runtime::lookup_host((host, default_port))
+                .await
+                .map_err(|_| ResolutionError::LookupFailed(Arc::new(e)))?,
+        };
+        if addrs.is_empty() {
+            return Err(ResolutionError::EmptyAddressList(host.to_owned()));
+        }
+        Ok(addrs)
+    }
+
+    #[cfg(feature = "dns-srv")]
+    async fn resolve_srv(&self, name: &str) -> Result<Vec<SrvTarget>, ResolutionError> {
+        let resolver = hickory_resolver::TokioResolver::builder_tokio()
+            .map_err(|e| ResolutionError::LookupFailed(Arc::new(e)))?
+            .build();
+        let lookup = resolver
+            .srv_lookup(name)
+            .await
+            .map_err(|e| ResolutionError::LookupFailed(Arc::new(e)))?;
+        let targets: Vec<SrvTarget> = lookup
+            .iter()
+            .map(|srv| SrvTarget::new(srv.target().to_ascii(), srv.port()))
+            .collect();
+        if targets.is_empty() {
+            return Err(ResolutionError::EmptyAddressList(name.to_owned()));
+        }
+        Ok(targets)
+    }
+}
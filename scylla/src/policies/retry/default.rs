@@ -1,18 +1,71 @@
-use scylla_cql::frame::response::error::{DbError, WriteType};
+use std::time::Duration;
+
+use crate::observability::log_targets;
+use scylla_cql::frame::response::error::{DbError, OperationType, WriteType};
+use tracing::debug;
 
 use crate::errors::RequestAttemptError;
 
+use super::exponential_backoff::jittered_backoff;
 use super::{RequestInfo, RetryDecision, RetryPolicy, RetrySession};
 
 /// Default retry policy - retries when there is a high chance that a retry might help.\
 /// Behaviour based on [DataStax Java Driver](https://docs.datastax.com/en/developer/java-driver/4.10/manual/core/retries/)
+///
+/// Requests rejected by ScyllaDB's per-partition rate limiting are not retried by default,
+/// see [with_rate_limit_handling](DefaultRetryPolicy::with_rate_limit_handling).
 #[derive(Debug)]
-pub struct DefaultRetryPolicy;
+pub struct DefaultRetryPolicy {
+    rate_limit_handling: RateLimitHandling,
+}
+
+/// Decides how [DefaultRetryPolicy] handles requests rejected by ScyllaDB's
+/// per-partition rate limiting ([DbError::RateLimitReached]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RateLimitHandling {
+    /// The error is returned to the user immediately.
+    FailFast,
+    /// The request is retried on the same target after a random delay between zero
+    /// and `base_delay * 2^n` (capped at `max_delay`), where `n` is the number of
+    /// rate limit retries so far, at most `max_retries` times.
+    ///
+    /// Reads, writes rejected by the coordinator (which are guaranteed not to be applied)
+    /// and idempotent requests are retried. Other requests fail immediately.
+    Backoff {
+        /// Maximum number of retries of a single request.
+        max_retries: u32,
+        /// Upper bound of the first delay.
+        base_delay: Duration,
+        /// Upper bound of all delays.
+        max_delay: Duration,
+    },
+}
 
 impl DefaultRetryPolicy {
     /// Creates a new instance of [DefaultRetryPolicy].
     pub fn new() -> DefaultRetryPolicy {
-        DefaultRetryPolicy
+        DefaultRetryPolicy {
+            rate_limit_handling: RateLimitHandling::FailFast,
+        }
+    }
+
+    /// Sets how requests rejected by per-partition rate limiting are handled.
+    ///
+    /// # Example
+    /// ```
+    /// # use std::time::Duration;
+    /// # use scylla::policies::retry::{DefaultRetryPolicy, RateLimitHandling};
+    /// let policy = DefaultRetryPolicy::new().with_rate_limit_handling(RateLimitHandling::Backoff {
+    ///     max_retries: 5,
+    ///     base_delay: Duration::from_millis(10),
+    ///     max_delay: Duration::from_millis(500),
+    /// });
+    /// ```
+    pub fn with_rate_limit_handling(self, rate_limit_handling: RateLimitHandling) -> Self {
+        Self {
+            rate_limit_handling,
+        }
     }
 }
 
@@ -24,7 +77,10 @@ impl Default for DefaultRetryPolicy {
 
 impl RetryPolicy for DefaultRetryPolicy {
     fn new_session(&self) -> Box<dyn RetrySession> {
-        Box::new(DefaultRetrySession::new())
+        Box::new(DefaultRetrySession {
+            rate_limit_handling: self.rate_limit_handling,
+            ..DefaultRetrySession::new()
+        })
     }
 }
 
@@ -33,6 +89,8 @@ pub struct DefaultRetrySession {
     was_unavailable_retry: bool,
     was_read_timeout_retry: bool,
     was_write_timeout_retry: bool,
+    rate_limit_handling: RateLimitHandling,
+    rate_limit_retries: u32,
 }
 
 impl DefaultRetrySession {
@@ -43,7 +101,40 @@ impl DefaultRetrySession {
             was_unavailable_retry: false,
             was_read_timeout_retry: false,
             was_write_timeout_retry: false,
+            rate_limit_handling: RateLimitHandling::FailFast,
+            rate_limit_retries: 0,
+        }
+    }
+
+    fn decide_on_rate_limit(
+        &mut self,
+        request_info: &RequestInfo,
+        op_type: &OperationType,
+        rejected_by_coordinator: bool,
+    ) -> RetryDecision {
+        let RateLimitHandling::Backoff {
+            max_retries,
+            base_delay,
+            max_delay,
+        } = self.rate_limit_handling
+        else {
+            return RetryDecision::DontRetry;
+        };
+        let safe_to_retry = request_info.is_idempotent
+            || rejected_by_coordinator
+            || matches!(op_type, OperationType::Read);
+        if !safe_to_retry || self.rate_limit_retries >= max_retries {
+            return RetryDecision::DontRetry;
         }
+
+        let delay = jittered_backoff(base_delay, max_delay, self.rate_limit_retries);
+        self.rate_limit_retries += 1;
+        debug!(
+            target: log_targets::RETRY,
+            "Request was rate limited, retrying #{} after {:?}.",
+            self.rate_limit_retries, delay
+        );
+        RetryDecision::RetrySameTargetAfter(None, delay)
     }
 }
 
@@ -137,6 +228,13 @@ impl RetrySession for DefaultRetrySession {
                     }
                     // The node is still bootstrapping it can't execute the request, we should try another one
                     DbError::IsBootstrapping => RetryDecision::RetryNextTarget(None),
+                    // Per-partition rate limit was exceeded - fail fast or back off, as configured
+                    DbError::RateLimitReached {
+                        op_type,
+                        rejected_by_coordinator,
+                    } => {
+                        self.decide_on_rate_limit(&request_info, op_type, *rejected_by_coordinator)
+                    }
                     // In all other cases propagate the error to the user
                     DbError::SyntaxError
                     | DbError::Invalid
@@ -149,7 +247,6 @@ impl RetrySession for DefaultRetrySession {
                     | DbError::WriteFailure { .. }
                     | DbError::Unprepared { .. }
                     | DbError::ProtocolError
-                    | DbError::RateLimitReached { .. }
                     | DbError::Other(_)
                     | _ => RetryDecision::DontRetry,
                 }
@@ -170,15 +267,20 @@ impl RetrySession for DefaultRetrySession {
     }
 
     fn reset(&mut self) {
-        *self = DefaultRetrySession::new();
+        *self = DefaultRetrySession {
+            rate_limit_handling: self.rate_limit_handling,
+            ..DefaultRetrySession::new()
+        };
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{DefaultRetryPolicy, RequestInfo, RetryDecision, RetryPolicy};
+    use std::time::Duration;
+
+    use super::{DefaultRetryPolicy, RateLimitHandling, RequestInfo, RetryDecision, RetryPolicy};
     use crate::errors::{BrokenConnectionErrorKind, RequestAttemptError};
-    use crate::errors::{DbError, WriteType};
+    use crate::errors::{DbError, OperationType, WriteType};
     use crate::statement::Consistency;
     use crate::test_utils::setup_tracing;
     use bytes::Bytes;
@@ -498,4 +600,68 @@ mod tests {
             RetryDecision::DontRetry
         );
     }
+
+    #[test]
+    fn default_rate_limit_handling() {
+        setup_tracing();
+        let rate_limited = |op_type, rejected_by_coordinator| {
+            RequestAttemptError::DbError(
+                DbError::RateLimitReached {
+                    op_type,
+                    rejected_by_coordinator,
+                },
+                String::new(),
+            )
+        };
+        let read = rate_limited(OperationType::Read, false);
+        let write = rate_limited(OperationType::Write, false);
+        let write_rejected_by_coordinator = rate_limited(OperationType::Write, true);
+
+        // Fails fast by default.
+        let mut policy = DefaultRetryPolicy::new().new_session();
+        assert_eq!(
+            policy.decide_should_retry(make_request_info(&read, true)),
+            RetryDecision::DontRetry
+        );
+
+        let max_delay = Duration::from_millis(30);
+        let policy =
+            DefaultRetryPolicy::new().with_rate_limit_handling(RateLimitHandling::Backoff {
+                max_retries: 2,
+                base_delay: Duration::from_millis(10),
+                max_delay,
+            });
+
+        for (error, is_idempotent) in [
+            (&read, false),
+            (&write_rejected_by_coordinator, false),
+            (&write, true),
+        ] {
+            let mut session = policy.new_session();
+            for _ in 0..2 {
+                match session.decide_should_retry(make_request_info(error, is_idempotent)) {
+                    RetryDecision::RetrySameTargetAfter(None, delay) => assert!(delay <= max_delay),
+                    decision => panic!("Unexpected decision: {decision:?}"),
+                }
+            }
+            assert_eq!(
+                session.decide_should_retry(make_request_info(error, is_idempotent)),
+                RetryDecision::DontRetry
+            );
+
+            // The limit of retries applies to a single request.
+            session.reset();
+            assert!(matches!(
+                session.decide_should_retry(make_request_info(error, is_idempotent)),
+                RetryDecision::RetrySameTargetAfter(..)
+            ));
+        }
+
+        // A non-idempotent write might have been applied by some replicas.
+        let mut session = policy.new_session();
+        assert_eq!(
+            session.decide_should_retry(make_request_info(&write, false)),
+            RetryDecision::DontRetry
+        );
+    }
 }
@@ -1,5 +1,9 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::observability::log_targets;
 use scylla_cql::Consistency;
-use tracing::debug;
+use tracing::{debug, info};
 
 use super::{RequestInfo, RetryDecision, RetryPolicy, RetrySession};
 use crate::errors::{DbError, RequestAttemptError, WriteType};
@@ -9,13 +13,48 @@ use crate::errors::{DbError, RequestAttemptError, WriteType};
 /// when it believes that the initial CL is reachable.
 /// Behaviour based on [DataStax Java Driver]\
 ///(<https://docs.datastax.com/en/drivers/java/3.11/com/datastax/driver/core/policies/DowngradingConsistencyRetryPolicy.html>)
+///
+/// A minimum consistency level can be declared with
+/// [with_minimum_consistency](DowngradingConsistencyRetryPolicy::with_minimum_consistency),
+/// in which case the policy never downgrades below it.
+/// Every downgrade is logged and counted, see [downgrades](DowngradingConsistencyRetryPolicy::downgrades).
 #[derive(Debug)]
-pub struct DowngradingConsistencyRetryPolicy;
+pub struct DowngradingConsistencyRetryPolicy {
+    minimum_consistency: Option<Consistency>,
+    downgrades: Arc<AtomicU64>,
+}
 
 impl DowngradingConsistencyRetryPolicy {
     /// Creates a new instance of [DowngradingConsistencyRetryPolicy].
     pub fn new() -> DowngradingConsistencyRetryPolicy {
-        DowngradingConsistencyRetryPolicy
+        DowngradingConsistencyRetryPolicy {
+            minimum_consistency: None,
+            downgrades: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Sets the lowest consistency level that requests may be downgraded to.
+    ///
+    /// Retries are only ever done at `One`, `Two` or `Three`, so a minimum
+    /// which depends on the replication factor (e.g. `Quorum`) disables downgrades altogether.
+    /// If a downgrade would violate the minimum, the error is returned to the user instead.
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::policies::retry::DowngradingConsistencyRetryPolicy;
+    /// # use scylla::statement::Consistency;
+    /// let policy = DowngradingConsistencyRetryPolicy::new()
+    ///     .with_minimum_consistency(Consistency::Two);
+    /// ```
+    pub fn with_minimum_consistency(mut self, minimum_consistency: Consistency) -> Self {
+        self.minimum_consistency = Some(minimum_consistency);
+        self
+    }
+
+    /// Returns the number of times the policy decided to retry a request
+    /// at a lower consistency level than the one requested.
+    pub fn downgrades(&self) -> u64 {
+        self.downgrades.load(Ordering::Relaxed)
     }
 }
 
@@ -27,20 +66,92 @@ impl Default for DowngradingConsistencyRetryPolicy {
 
 impl RetryPolicy for DowngradingConsistencyRetryPolicy {
     fn new_session(&self) -> Box<dyn RetrySession> {
-        Box::new(DowngradingConsistencyRetrySession::new())
+        Box::new(DowngradingConsistencyRetrySession {
+            was_retry: false,
+            minimum_consistency: self.minimum_consistency,
+            downgrades: Arc::clone(&self.downgrades),
+        })
     }
 }
 
 /// Implementation of [RetrySession] for [DowngradingConsistencyRetryPolicy].
 pub struct DowngradingConsistencyRetrySession {
     was_retry: bool,
+    minimum_consistency: Option<Consistency>,
+    downgrades: Arc<AtomicU64>,
 }
 
 impl DowngradingConsistencyRetrySession {
     /// Creates a new instance of [DowngradingConsistencyRetrySession].
     // TODO(2.0): unpub this.
     pub fn new() -> DowngradingConsistencyRetrySession {
-        DowngradingConsistencyRetrySession { was_retry: false }
+        DowngradingConsistencyRetrySession {
+            was_retry: false,
+            minimum_consistency: None,
+            downgrades: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Returns whether retrying at `cl` still satisfies the declared minimum.
+    fn satisfies_minimum(&self, cl: Consistency) -> bool {
+        // Number of replicas that are guaranteed to be contacted,
+        // or None if it depends on the replication factor.
+        fn guaranteed_replicas(cl: Consistency) -> Option<u8> {
+            match cl {
+                Consistency::Any => Some(0),
+                Consistency::One | Consistency::LocalOne => Some(1),
+                Consistency::Two => Some(2),
+                Consistency::Three => Some(3),
+                Consistency::Quorum
+                | Consistency::All
+                | Consistency::LocalQuorum
+                | Consistency::EachQuorum
+                | Consistency::Serial
+                | Consistency::LocalSerial => None,
+            }
+        }
+
+        match self.minimum_consistency {
+            None => true,
+            Some(minimum) if minimum == cl => true,
+            Some(minimum) => match (guaranteed_replicas(cl), guaranteed_replicas(minimum)) {
+                (Some(replicas), Some(minimum_replicas)) => replicas >= minimum_replicas,
+                _ => false,
+            },
+        }
+    }
+
+    fn max_likely_to_work_cl(&self, known_ok: i32, previous_cl: Consistency) -> RetryDecision {
+        let new_cl = if known_ok >= 3 {
+            Consistency::Three
+        } else if known_ok == 2 {
+            Consistency::Two
+        } else if known_ok == 1 || previous_cl == Consistency::EachQuorum {
+            // JAVA-1005: EACH_QUORUM does not report a global number of alive replicas
+            // so even if we get 0 alive replicas, there might be
+            // a node up in some other datacenter
+            Consistency::One
+        } else {
+            return RetryDecision::DontRetry;
+        };
+
+        if new_cl != previous_cl {
+            if !self.satisfies_minimum(new_cl) {
+                debug!(
+                    target: log_targets::RETRY,
+                    "Not lowering required consistency from {} to {}, as it would violate the minimum of {:?}.",
+                    previous_cl, new_cl, self.minimum_consistency
+                );
+                return RetryDecision::DontRetry;
+            }
+            info!(
+                target: log_targets::RETRY,
+                "Decided to lower required consistency from {} to {}.",
+                previous_cl, new_cl
+            );
+            self.downgrades.fetch_add(1, Ordering::Relaxed);
+        }
+        RetryDecision::RetrySameTarget(Some(new_cl))
     }
 }
 
@@ -67,28 +178,6 @@ impl RetrySession for DowngradingConsistencyRetrySession {
             cl => cl,
         };
 
-        fn max_likely_to_work_cl(known_ok: i32, previous_cl: Consistency) -> RetryDecision {
-            let decision = if known_ok >= 3 {
-                RetryDecision::RetrySameTarget(Some(Consistency::Three))
-            } else if known_ok == 2 {
-                RetryDecision::RetrySameTarget(Some(Consistency::Two))
-            } else if known_ok == 1 || previous_cl == Consistency::EachQuorum {
-                // JAVA-1005: EACH_QUORUM does not report a global number of alive replicas
-                // so even if we get 0 alive replicas, there might be
-                // a node up in some other datacenter
-                RetryDecision::RetrySameTarget(Some(Consistency::One))
-            } else {
-                RetryDecision::DontRetry
-            };
-            if let RetryDecision::RetrySameTarget(new_cl) = decision {
-                debug!(
-                    "Decided to lower required consistency from {} to {:?}.",
-                    previous_cl, new_cl
-                );
-            }
-            decision
-        }
-
         // Do not remove this lint!
         // It's there for a reason - we don't want new variants
         // automatically fall under `_` pattern when they are introduced.
@@ -123,7 +212,7 @@ impl RetrySession for DowngradingConsistencyRetrySession {
                     DbError::Unavailable { alive, .. } => {
                         if !self.was_retry {
                             self.was_retry = true;
-                            max_likely_to_work_cl(*alive, cl)
+                            self.max_likely_to_work_cl(*alive, cl)
                         } else {
                             RetryDecision::DontRetry
                         }
@@ -139,7 +228,7 @@ impl RetrySession for DowngradingConsistencyRetrySession {
                             RetryDecision::DontRetry
                         } else if received < required {
                             self.was_retry = true;
-                            max_likely_to_work_cl(*received, cl)
+                            self.max_likely_to_work_cl(*received, cl)
                         } else if !*data_present {
                             self.was_retry = true;
                             RetryDecision::RetrySameTarget(None)
@@ -165,7 +254,7 @@ impl RetrySession for DowngradingConsistencyRetrySession {
                                 WriteType::UnloggedBatch => {
                                     // Since only part of the batch could have been persisted,
                                     // retry with whatever consistency should allow to persist all
-                                    max_likely_to_work_cl(*received, cl)
+                                    self.max_likely_to_work_cl(*received, cl)
                                 }
                                 WriteType::BatchLog => RetryDecision::RetrySameTarget(None),
 
@@ -214,7 +303,7 @@ impl RetrySession for DowngradingConsistencyRetrySession {
     }
 
     fn reset(&mut self) {
-        *self = DowngradingConsistencyRetrySession::new();
+        self.was_retry = false;
     }
 }
 
@@ -735,4 +824,65 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn downgrading_consistency_respects_minimum_and_counts_downgrades() {
+        setup_tracing();
+        let unavailable = |alive| {
+            RequestAttemptError::DbError(
+                DbError::Unavailable {
+                    consistency: Consistency::Quorum,
+                    required: 3,
+                    alive,
+                },
+                String::new(),
+            )
+        };
+
+        let policy =
+            DowngradingConsistencyRetryPolicy::new().with_minimum_consistency(Consistency::Two);
+
+        let error = unavailable(2);
+        let mut session = policy.new_session();
+        assert_eq!(
+            session.decide_should_retry(make_request_info_with_cl(
+                &error,
+                true,
+                Consistency::Quorum
+            )),
+            RetryDecision::RetrySameTarget(Some(Consistency::Two))
+        );
+        assert_eq!(policy.downgrades(), 1);
+
+        // Downgrading to One would violate the minimum.
+        let error = unavailable(1);
+        let mut session = policy.new_session();
+        assert_eq!(
+            session.decide_should_retry(make_request_info_with_cl(
+                &error,
+                true,
+                Consistency::Quorum
+            )),
+            RetryDecision::DontRetry
+        );
+        assert_eq!(policy.downgrades(), 1);
+
+        // Retrying at the same consistency level is not a downgrade.
+        let error = unavailable(1);
+        let mut session = policy.new_session();
+        let _ =
+            session.decide_should_retry(make_request_info_with_cl(&error, true, Consistency::One));
+        assert_eq!(policy.downgrades(), 1);
+
+        // A minimum depending on the replication factor disables downgrades.
+        let policy = DowngradingConsistencyRetryPolicy::new()
+            .with_minimum_consistency(Consistency::LocalQuorum);
+        let error = unavailable(3);
+        let mut session = policy.new_session();
+        assert_eq!(
+            session.decide_should_retry(make_request_info_with_cl(&error, true, Consistency::All)),
+            RetryDecision::DontRetry
+        );
+        assert_eq!(policy.downgrades(), 0);
+    }
 }
@@ -0,0 +1,183 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use rand::Rng;
+
+use super::{DefaultRetryPolicy, RequestInfo, RetryDecision, RetryPolicy, RetrySession};
+
+/// Retry policy which delays retries, backing off exponentially with jitter.
+///
+/// Whether and where to retry is decided by the wrapped policy
+/// ([DefaultRetryPolicy] by default). Each retry it decides on is delayed by a random
+/// duration between zero and `base_delay * 2^n` (capped at `max_delay`), where `n`
+/// is the number of retries of the request so far. This way, overloaded nodes are not
+/// hammered with retries, and retries of many requests failing at once are spread out in time.
+///
+/// The delay counts towards the request timeout.
+///
+/// # Example
+/// ```
+/// # use std::sync::Arc;
+/// # use std::time::Duration;
+/// # use scylla::policies::retry::{DowngradingConsistencyRetryPolicy, ExponentialBackoffRetryPolicy};
+/// let policy = ExponentialBackoffRetryPolicy::new()
+///     .with_backoff_limits(Duration::from_millis(20), Duration::from_secs(1))
+///     .with_inner_policy(Arc::new(DowngradingConsistencyRetryPolicy::new()));
+/// ```
+#[derive(Debug)]
+pub struct ExponentialBackoffRetryPolicy {
+    inner: Arc<dyn RetryPolicy>,
+    base_delay: Duration,
+    max_delay: Duration,
+}
+
+impl ExponentialBackoffRetryPolicy {
+    /// Creates a new instance of [ExponentialBackoffRetryPolicy], wrapping [DefaultRetryPolicy],
+    /// with base delay of 50ms and maximum delay of 5s.
+    pub fn new() -> ExponentialBackoffRetryPolicy {
+        ExponentialBackoffRetryPolicy {
+            inner: Arc::new(DefaultRetryPolicy::new()),
+            base_delay: Duration::from_millis(50),
+            max_delay: Duration::from_secs(5),
+        }
+    }
+
+    /// Configures the base and maximum delay.
+    ///
+    /// The upper bound of the delay starts from `base` and doubles with each retry, up to `max`.
+    pub fn with_backoff_limits(self, base: Duration, max: Duration) -> Self {
+        assert!(
+            base <= max,
+            "base_delay ({:?}) must be less than or equal to max_delay ({:?})",
+            base,
+            max
+        );
+        Self {
+            base_delay: base,
+            max_delay: max,
+            ..self
+        }
+    }
+
+    /// Sets the policy which decides whether and where to retry.
+    pub fn with_inner_policy(self, inner: Arc<dyn RetryPolicy>) -> Self {
+        Self { inner, ..self }
+    }
+}
+
+impl Default for ExponentialBackoffRetryPolicy {
+    fn default() -> ExponentialBackoffRetryPolicy {
+        ExponentialBackoffRetryPolicy::new()
+    }
+}
+
+impl RetryPolicy for ExponentialBackoffRetryPolicy {
+    fn new_session(&self) -> Box<dyn RetrySession> {
+        Box::new(ExponentialBackoffRetrySession {
+            inner: self.inner.new_session(),
+            base_delay: self.base_delay,
+            max_delay: self.max_delay,
+            retries: 0,
+        })
+    }
+}
+
+/// Implementation of [RetrySession] for [ExponentialBackoffRetryPolicy].
+pub struct ExponentialBackoffRetrySession {
+    inner: Box<dyn RetrySession>,
+    base_delay: Duration,
+    max_delay: Duration,
+    retries: u32,
+}
+
+impl ExponentialBackoffRetrySession {
+    fn next_delay(&mut self) -> Duration {
+        let delay = jittered_backoff(self.base_delay, self.max_delay, self.retries);
+        self.retries = self.retries.saturating_add(1);
+        delay
+    }
+}
+
+/// Returns a random delay between zero and `base * 2^retries`, capped at `max`.
+pub(super) fn jittered_backoff(base: Duration, max: Duration, retries: u32) -> Duration {
+    let upper_bound = base
+        .saturating_mul(1u32.checked_shl(retries).unwrap_or(u32::MAX))
+        .min(max);
+    upper_bound.mul_f64(rand::rng().random_range(0.0..=1.0))
+}
+
+impl RetrySession for ExponentialBackoffRetrySession {
+    fn decide_should_retry(&mut self, request_info: RequestInfo) -> RetryDecision {
+        match self.inner.decide_should_retry(request_info) {
+            RetryDecision::RetrySameTarget(cl) => {
+                RetryDecision::RetrySameTargetAfter(cl, self.next_delay())
+            }
+            RetryDecision::RetryNextTarget(cl) => {
+                RetryDecision::RetryNextTargetAfter(cl, self.next_delay())
+            }
+            decision => decision,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+        self.retries = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use scylla_cql::Consistency;
+    use scylla_cql::frame::response::error::DbError;
+
+    use super::ExponentialBackoffRetryPolicy;
+    use crate::errors::RequestAttemptError;
+    use crate::policies::retry::{
+        FallthroughRetryPolicy, RequestInfo, RetryDecision, RetryPolicy, SchemaChangeRetryPolicy,
+    };
+    use crate::test_utils::setup_tracing;
+
+    #[test]
+    fn retries_are_delayed_exponentially_up_to_max() {
+        setup_tracing();
+        let error = RequestAttemptError::DbError(
+            DbError::ServerError,
+            "Failed to apply group 0 change due to concurrent modification".to_owned(),
+        );
+        let request_info = || RequestInfo {
+            error: &error,
+            is_idempotent: true,
+            consistency: Consistency::One,
+        };
+        let base = Duration::from_millis(10);
+        let max = Duration::from_millis(50);
+        let policy = ExponentialBackoffRetryPolicy::new()
+            .with_backoff_limits(base, max)
+            .with_inner_policy(Arc::new(SchemaChangeRetryPolicy::with_max_retries(6)));
+
+        let mut session = policy.new_session();
+        for retry in 0..6 {
+            let RetryDecision::RetrySameTargetAfter(None, delay) =
+                session.decide_should_retry(request_info())
+            else {
+                panic!("expected a delayed retry");
+            };
+            assert!(delay <= (base * 2u32.pow(retry)).min(max));
+        }
+        assert_eq!(
+            session.decide_should_retry(request_info()),
+            RetryDecision::DontRetry
+        );
+
+        // Decisions not to retry are passed through.
+        let policy = ExponentialBackoffRetryPolicy::new()
+            .with_inner_policy(Arc::new(FallthroughRetryPolicy::new()));
+        assert_eq!(
+            policy.new_session().decide_should_retry(request_info()),
+            RetryDecision::DontRetry
+        );
+    }
+}
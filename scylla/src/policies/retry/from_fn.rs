@@ -0,0 +1,125 @@
+use std::sync::Arc;
+
+use super::{RequestInfo, RetryDecision, RetryPolicy, RetrySession};
+
+type DecideFn = dyn Fn(&RequestInfo, usize) -> RetryDecision + Send + Sync;
+
+/// Retry policy which decides about retries with a closure.
+///
+/// The closure is called with information about the failed request and the number
+/// of retries of the request made so far. It makes it possible to express simple,
+/// application-specific rules without implementing [RetryPolicy] and [RetrySession].
+///
+/// # Example
+/// ```
+/// # use scylla::errors::{DbError, RequestAttemptError};
+/// # use scylla::policies::retry::{FnRetryPolicy, RetryDecision};
+/// // Retries idempotent requests on overloaded nodes at most twice, on other nodes.
+/// let policy = FnRetryPolicy::new(|request_info, retries| match request_info.error {
+///     RequestAttemptError::DbError(DbError::Overloaded, _)
+///         if request_info.is_idempotent && retries < 2 =>
+///     {
+///         RetryDecision::RetryNextTarget(None)
+///     }
+///     _ => RetryDecision::DontRetry,
+/// });
+/// ```
+#[derive(Clone)]
+pub struct FnRetryPolicy {
+    decide: Arc<DecideFn>,
+}
+
+impl FnRetryPolicy {
+    /// Creates a new instance of [FnRetryPolicy], which decides about retries with `decide`.
+    pub fn new(
+        decide: impl Fn(&RequestInfo, usize) -> RetryDecision + Send + Sync + 'static,
+    ) -> FnRetryPolicy {
+        FnRetryPolicy {
+            decide: Arc::new(decide),
+        }
+    }
+}
+
+impl std::fmt::Debug for FnRetryPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FnRetryPolicy").finish_non_exhaustive()
+    }
+}
+
+impl RetryPolicy for FnRetryPolicy {
+    fn new_session(&self) -> Box<dyn RetrySession> {
+        Box::new(FnRetrySession {
+            decide: Arc::clone(&self.decide),
+            retries: 0,
+        })
+    }
+}
+
+/// Implementation of [RetrySession] for [FnRetryPolicy].
+pub struct FnRetrySession {
+    decide: Arc<DecideFn>,
+    retries: usize,
+}
+
+impl RetrySession for FnRetrySession {
+    fn decide_should_retry(&mut self, request_info: RequestInfo) -> RetryDecision {
+        let decision = (self.decide)(&request_info, self.retries);
+        match decision {
+            RetryDecision::RetrySameTarget(_)
+            | RetryDecision::RetryNextTarget(_)
+            | RetryDecision::RetrySameTargetAfter(..)
+            | RetryDecision::RetryNextTargetAfter(..) => self.retries += 1,
+            RetryDecision::DontRetry | RetryDecision::IgnoreWriteError => {}
+        }
+        decision
+    }
+
+    fn reset(&mut self) {
+        self.retries = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use scylla_cql::Consistency;
+
+    use super::FnRetryPolicy;
+    use crate::errors::{DbError, RequestAttemptError};
+    use crate::policies::retry::{RequestInfo, RetryDecision, RetryPolicy};
+    use crate::test_utils::setup_tracing;
+
+    #[test]
+    fn closure_gets_number_of_retries() {
+        setup_tracing();
+        let policy = FnRetryPolicy::new(|request_info, retries| match request_info.error {
+            RequestAttemptError::DbError(DbError::Overloaded, _) if retries < 2 => {
+                RetryDecision::RetryNextTarget(Some(Consistency::One))
+            }
+            _ => RetryDecision::DontRetry,
+        });
+        let overloaded = RequestAttemptError::DbError(DbError::Overloaded, String::new());
+        let request_info = || RequestInfo {
+            error: &overloaded,
+            is_idempotent: true,
+            consistency: Consistency::Quorum,
+        };
+
+        let mut session = policy.new_session();
+        for _ in 0..2 {
+            assert_eq!(
+                session.decide_should_retry(request_info()),
+                RetryDecision::RetryNextTarget(Some(Consistency::One))
+            );
+        }
+        assert_eq!(
+            session.decide_should_retry(request_info()),
+            RetryDecision::DontRetry
+        );
+
+        session.reset();
+        assert_eq!(
+            session.decide_should_retry(request_info()),
+            RetryDecision::RetryNextTarget(Some(Consistency::One))
+        );
+    }
+}
@@ -2,12 +2,18 @@
 
 mod default;
 mod downgrading_consistency;
+mod exponential_backoff;
 mod fallthrough;
+mod from_fn;
 mod retry_policy;
+mod schema_change;
 
-pub use default::{DefaultRetryPolicy, DefaultRetrySession};
+pub use default::{DefaultRetryPolicy, DefaultRetrySession, RateLimitHandling};
 pub use downgrading_consistency::{
     DowngradingConsistencyRetryPolicy, DowngradingConsistencyRetrySession,
 };
+pub use exponential_backoff::{ExponentialBackoffRetryPolicy, ExponentialBackoffRetrySession};
 pub use fallthrough::{FallthroughRetryPolicy, FallthroughRetrySession};
-pub use retry_policy::{RequestInfo, RetryDecision, RetryPolicy, RetrySession};
+pub use from_fn::{FnRetryPolicy, FnRetrySession};
+pub use retry_policy::{RequestInfo, RetryDecision, RetryDecisionKind, RetryPolicy, RetrySession};
+pub use schema_change::{SchemaChangeRetryPolicy, SchemaChangeRetrySession};
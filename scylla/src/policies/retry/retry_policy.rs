@@ -2,6 +2,8 @@
 //! To decide when to retry a request the `Session` can use any object which implements
 //! the `RetryPolicy` trait
 
+use std::time::Duration;
+
 use crate::errors::RequestAttemptError;
 use crate::frame::types::Consistency;
 
@@ -27,12 +29,60 @@ pub enum RetryDecision {
     RetrySameTarget(Option<Consistency>), // None means that the same consistency should be used as before
     /// Request will be sent to the next target generated by load balancing policy.
     RetryNextTarget(Option<Consistency>), // ditto
+    /// Like [RetrySameTarget](RetryDecision::RetrySameTarget), but the request is sent
+    /// after the given delay.
+    RetrySameTargetAfter(Option<Consistency>, Duration),
+    /// Like [RetryNextTarget](RetryDecision::RetryNextTarget), but the request is sent
+    /// after the given delay.
+    RetryNextTargetAfter(Option<Consistency>, Duration),
     /// Fails the whole request.
     DontRetry,
     /// Will cause the driver to return an empty successful response.
     IgnoreWriteError,
 }
 
+impl RetryDecision {
+    /// Classifies the decision, given the consistency level of the failed attempt.
+    pub fn kind(&self, consistency: Consistency) -> RetryDecisionKind {
+        let is_downgrade =
+            |new_cl: &Option<Consistency>| new_cl.is_some_and(|cl| cl != consistency);
+        match self {
+            RetryDecision::RetrySameTarget(cl) | RetryDecision::RetrySameTargetAfter(cl, _) => {
+                if is_downgrade(cl) {
+                    RetryDecisionKind::Downgrade
+                } else {
+                    RetryDecisionKind::RetrySameTarget
+                }
+            }
+            RetryDecision::RetryNextTarget(cl) | RetryDecision::RetryNextTargetAfter(cl, _) => {
+                if is_downgrade(cl) {
+                    RetryDecisionKind::Downgrade
+                } else {
+                    RetryDecisionKind::RetryNextTarget
+                }
+            }
+            RetryDecision::DontRetry => RetryDecisionKind::DontRetry,
+            RetryDecision::IgnoreWriteError => RetryDecisionKind::IgnoreWriteError,
+        }
+    }
+}
+
+/// Kind of a [RetryDecision], used to label collected metrics.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum RetryDecisionKind {
+    /// Retry on the same target, with the same consistency.
+    RetrySameTarget,
+    /// Retry on the next target, with the same consistency.
+    RetryNextTarget,
+    /// Retry (on any target) with a changed consistency.
+    Downgrade,
+    /// No retry - the error is returned.
+    DontRetry,
+    /// No retry - the error is ignored.
+    IgnoreWriteError,
+}
+
 /// Specifies a policy used to decide when to retry a request
 pub trait RetryPolicy: std::fmt::Debug + Send + Sync {
     /// Called for each new request, starts a session of deciding about retries
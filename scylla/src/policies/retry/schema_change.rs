@@ -0,0 +1,129 @@
+use crate::observability::log_targets;
+use scylla_cql::frame::response::error::DbError;
+use tracing::warn;
+
+use crate::errors::RequestAttemptError;
+
+use super::{RequestInfo, RetryDecision, RetryPolicy, RetrySession};
+
+/// Message of the error returned by ScyllaDB when a schema change conflicts
+/// with another one applied concurrently through group 0 (Raft).
+const GROUP0_CONCURRENT_MODIFICATION: &str =
+    "Failed to apply group 0 change due to concurrent modification";
+
+/// Retry policy for schema-altering statements.
+///
+/// ScyllaDB applies schema changes through group 0 (Raft), and rejects a schema change
+/// that conflicts with another one applied concurrently. Such a change was not applied,
+/// so it is safe to retry it. This policy retries it on the same node, up to
+/// a configured number of times, and never retries other errors.
+///
+/// This policy is used by [Session::execute_schema_change](crate::client::session::Session::execute_schema_change).
+#[derive(Debug)]
+pub struct SchemaChangeRetryPolicy {
+    max_retries: usize,
+}
+
+impl SchemaChangeRetryPolicy {
+    /// Creates a new instance of [SchemaChangeRetryPolicy], retrying at most 10 times.
+    pub fn new() -> SchemaChangeRetryPolicy {
+        SchemaChangeRetryPolicy { max_retries: 10 }
+    }
+
+    /// Creates a new instance of [SchemaChangeRetryPolicy], retrying at most `max_retries` times.
+    pub fn with_max_retries(max_retries: usize) -> SchemaChangeRetryPolicy {
+        SchemaChangeRetryPolicy { max_retries }
+    }
+}
+
+impl Default for SchemaChangeRetryPolicy {
+    fn default() -> SchemaChangeRetryPolicy {
+        SchemaChangeRetryPolicy::new()
+    }
+}
+
+impl RetryPolicy for SchemaChangeRetryPolicy {
+    fn new_session(&self) -> Box<dyn RetrySession> {
+        Box::new(SchemaChangeRetrySession {
+            max_retries: self.max_retries,
+            retries: 0,
+        })
+    }
+}
+
+/// Implementation of [RetrySession] for [SchemaChangeRetryPolicy].
+pub struct SchemaChangeRetrySession {
+    max_retries: usize,
+    retries: usize,
+}
+
+impl RetrySession for SchemaChangeRetrySession {
+    fn decide_should_retry(&mut self, request_info: RequestInfo) -> RetryDecision {
+        match request_info.error {
+            RequestAttemptError::DbError(DbError::ServerError, message)
+                if message == GROUP0_CONCURRENT_MODIFICATION && self.retries < self.max_retries =>
+            {
+                self.retries += 1;
+                warn!(
+                    target: log_targets::RETRY,
+                    "Schema change conflicted with a concurrent one. Performing retry #{}.",
+                    self.retries
+                );
+                RetryDecision::RetrySameTarget(None)
+            }
+            _ => RetryDecision::DontRetry,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.retries = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use scylla_cql::frame::response::error::DbError;
+
+    use super::{GROUP0_CONCURRENT_MODIFICATION, SchemaChangeRetryPolicy};
+    use crate::errors::RequestAttemptError;
+    use crate::policies::retry::{RequestInfo, RetryDecision, RetryPolicy};
+    use crate::statement::Consistency;
+    use crate::test_utils::setup_tracing;
+
+    fn decide(error: &RequestAttemptError, session: &mut dyn super::RetrySession) -> RetryDecision {
+        session.decide_should_retry(RequestInfo {
+            error,
+            is_idempotent: false,
+            consistency: Consistency::Quorum,
+        })
+    }
+
+    #[test]
+    fn schema_change_retries_only_concurrent_modification() {
+        setup_tracing();
+        let conflict = RequestAttemptError::DbError(
+            DbError::ServerError,
+            GROUP0_CONCURRENT_MODIFICATION.to_owned(),
+        );
+        let other = RequestAttemptError::DbError(DbError::ServerError, "other".to_owned());
+
+        let mut session = SchemaChangeRetryPolicy::with_max_retries(2).new_session();
+        assert_eq!(decide(&other, session.as_mut()), RetryDecision::DontRetry);
+        for _ in 0..2 {
+            assert_eq!(
+                decide(&conflict, session.as_mut()),
+                RetryDecision::RetrySameTarget(None)
+            );
+        }
+        assert_eq!(
+            decide(&conflict, session.as_mut()),
+            RetryDecision::DontRetry
+        );
+
+        session.reset();
+        assert_eq!(
+            decide(&conflict, session.as_mut()),
+            RetryDecision::RetrySameTarget(None)
+        );
+    }
+}
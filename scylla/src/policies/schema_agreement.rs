@@ -0,0 +1,335 @@
+//! Schema agreement policy.
+//!
+//! After a schema change, the driver waits until the nodes agree on the schema
+//! version, i.e. until the change has propagated through the cluster.
+//! A [SchemaAgreementPolicy] configures how this waiting is done: how often the
+//! nodes are polled, how long to wait, what fraction of nodes has to agree,
+//! which nodes are not taken into account, and who is notified about the progress.
+//!
+//! Nodes which the driver is not connected to (e.g. because they are down)
+//! are never taken into account.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use uuid::Uuid;
+
+/// Progress of awaiting schema agreement, reported to a [SchemaAgreementListener].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct SchemaAgreementProgress {
+    pub(crate) elapsed: Duration,
+    pub(crate) schema_versions: Vec<(Uuid, Uuid)>,
+    pub(crate) prevailing_version: Option<Uuid>,
+    pub(crate) disagreeing_nodes: Vec<Uuid>,
+}
+
+impl SchemaAgreementProgress {
+    /// Time elapsed since the driver started awaiting schema agreement.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Schema versions reported by the nodes taken into account,
+    /// as `(host_id, schema_version)` pairs.
+    pub fn schema_versions(&self) -> &[(Uuid, Uuid)] {
+        &self.schema_versions
+    }
+
+    /// The schema version held by the most nodes, if any node reported its version.
+    pub fn prevailing_version(&self) -> Option<Uuid> {
+        self.prevailing_version
+    }
+
+    /// Host ids of nodes whose schema version differs from the prevailing one.
+    pub fn disagreeing_nodes(&self) -> &[Uuid] {
+        &self.disagreeing_nodes
+    }
+}
+
+/// Allows observing the progress of awaiting schema agreement.
+///
+/// The listener is called from the task awaiting schema agreement, so implementations
+/// should return quickly.
+pub trait SchemaAgreementListener: Send + Sync {
+    /// Called after each check that did not reach agreement.
+    fn on_disagreement(&self, progress: &SchemaAgreementProgress);
+}
+
+/// Configures awaiting schema agreement.
+///
+/// Construct it with [SchemaAgreementPolicy::builder], and pass it either to
+/// [SessionBuilder::schema_agreement_policy](crate::client::session_builder::SessionBuilder::schema_agreement_policy)
+/// or to [Session::await_schema_agreement_with_policy](crate::client::session::Session::await_schema_agreement_with_policy).
+#[derive(Clone)]
+pub struct SchemaAgreementPolicy {
+    pub(crate) interval: Duration,
+    pub(crate) timeout: Duration,
+    pub(crate) required_fraction: f64,
+    pub(crate) ignored_nodes: HashSet<Uuid>,
+    pub(crate) listener: Option<Arc<dyn SchemaAgreementListener>>,
+}
+
+impl std::fmt::Debug for SchemaAgreementPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SchemaAgreementPolicy")
+            .field("interval", &self.interval)
+            .field("timeout", &self.timeout)
+            .field("required_fraction", &self.required_fraction)
+            .field("ignored_nodes", &self.ignored_nodes)
+            .field("listener", &self.listener.as_ref().map(|_| "..."))
+            .finish()
+    }
+}
+
+impl Default for SchemaAgreementPolicy {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+/// Outcome of a single schema agreement check.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum SchemaAgreementCheck {
+    Agreed(Uuid),
+    Disagreed {
+        prevailing_version: Option<Uuid>,
+        disagreeing_nodes: Vec<Uuid>,
+    },
+}
+
+impl SchemaAgreementPolicy {
+    /// Creates a builder of a schema agreement policy.
+    pub fn builder() -> SchemaAgreementPolicyBuilder {
+        SchemaAgreementPolicyBuilder::new()
+    }
+
+    /// How often the nodes are asked for their schema versions.
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// How long to wait for agreement.
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Fraction of the nodes taken into account which must agree on the schema version.
+    pub fn required_fraction(&self) -> f64 {
+        self.required_fraction
+    }
+
+    /// Host ids of nodes which are not taken into account.
+    pub fn ignored_nodes(&self) -> &HashSet<Uuid> {
+        &self.ignored_nodes
+    }
+
+    /// Decides whether the nodes agree, given `(host_id, schema_version)` pairs reported
+    /// by the nodes. If `required_node` is not ignored, it has to hold the agreed version.
+    pub(crate) fn check(
+        &self,
+        schema_versions: &[(Uuid, Uuid)],
+        required_node: Option<Uuid>,
+    ) -> SchemaAgreementCheck {
+        let considered = || {
+            schema_versions
+                .iter()
+                .filter(|(host_id, _)| !self.ignored_nodes.contains(host_id))
+        };
+
+        // Versions in the order of first appearance, so that ties are broken deterministically.
+        let mut version_counts: Vec<(Uuid, usize)> = Vec::new();
+        for (_, version) in considered() {
+            match version_counts.iter_mut().find(|(v, _)| v == version) {
+                Some((_, count)) => *count += 1,
+                None => version_counts.push((*version, 1)),
+            }
+        }
+        let Some(&(prevailing_version, prevailing_count)) =
+            version_counts.iter().rev().max_by_key(|(_, count)| *count)
+        else {
+            return SchemaAgreementCheck::Disagreed {
+                prevailing_version: None,
+                disagreeing_nodes: Vec::new(),
+            };
+        };
+
+        let considered_count = considered().count();
+        let required_count = ((self.required_fraction * considered_count as f64).ceil() as usize)
+            .clamp(1, considered_count);
+        let required_node_agrees = required_node.is_none_or(|required_node| {
+            considered()
+                .filter(|(host_id, _)| *host_id == required_node)
+                .all(|(_, version)| *version == prevailing_version)
+        });
+
+        if prevailing_count >= required_count && required_node_agrees {
+            SchemaAgreementCheck::Agreed(prevailing_version)
+        } else {
+            SchemaAgreementCheck::Disagreed {
+                prevailing_version: Some(prevailing_version),
+                disagreeing_nodes: considered()
+                    .filter(|(_, version)| *version != prevailing_version)
+                    .map(|(host_id, _)| *host_id)
+                    .collect(),
+            }
+        }
+    }
+}
+
+/// Builder of [SchemaAgreementPolicy].
+///
+/// # Example
+/// ```
+/// # use scylla::policies::schema_agreement::SchemaAgreementPolicy;
+/// use std::time::Duration;
+///
+/// let policy = SchemaAgreementPolicy::builder()
+///     .interval(Duration::from_secs(1))
+///     .timeout(Duration::from_secs(300))
+///     .required_fraction(0.9)
+///     .build();
+/// ```
+#[derive(Clone, Debug)]
+pub struct SchemaAgreementPolicyBuilder {
+    policy: SchemaAgreementPolicy,
+}
+
+impl SchemaAgreementPolicyBuilder {
+    /// Creates a builder with the default configuration: nodes are polled every 200ms
+    /// for up to 60s, and all of them have to agree.
+    pub fn new() -> Self {
+        Self {
+            policy: SchemaAgreementPolicy {
+                interval: Duration::from_millis(200),
+                timeout: Duration::from_secs(60),
+                required_fraction: 1.0,
+                ignored_nodes: HashSet::new(),
+                listener: None,
+            },
+        }
+    }
+
+    /// Sets how often the nodes are asked for their schema versions.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.policy.interval = interval;
+        self
+    }
+
+    /// Sets how long to wait for agreement.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.policy.timeout = timeout;
+        self
+    }
+
+    /// Sets the fraction of nodes taken into account which must agree on the schema version.
+    /// At least one node must agree, even if the fraction is 0.
+    ///
+    /// Requiring less than all nodes to agree is useful in large clusters, where some nodes
+    /// may lag behind for a long time, e.g. during a rolling upgrade.
+    ///
+    /// # Panics
+    /// Panics if `fraction` is not in the `[0.0, 1.0]` range.
+    pub fn required_fraction(mut self, fraction: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&fraction),
+            "Required fraction of agreeing nodes must be in the [0.0, 1.0] range, got {fraction}"
+        );
+        self.policy.required_fraction = fraction;
+        self
+    }
+
+    /// Sets host ids of nodes which are not taken into account, e.g. nodes being upgraded.
+    pub fn ignored_nodes(mut self, ignored_nodes: impl IntoIterator<Item = Uuid>) -> Self {
+        self.policy.ignored_nodes = ignored_nodes.into_iter().collect();
+        self
+    }
+
+    /// Sets the listener notified about each check that did not reach agreement.
+    pub fn listener(mut self, listener: Arc<dyn SchemaAgreementListener>) -> Self {
+        self.policy.listener = Some(listener);
+        self
+    }
+
+    /// Builds the policy.
+    pub fn build(self) -> SchemaAgreementPolicy {
+        self.policy
+    }
+}
+
+impl Default for SchemaAgreementPolicyBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::{SchemaAgreementCheck, SchemaAgreementPolicy};
+
+    fn id(n: u128) -> Uuid {
+        Uuid::from_u128(n)
+    }
+
+    #[test]
+    fn schema_agreement_check() {
+        let (v1, v2) = (id(100), id(200));
+        let versions = [(id(1), v1), (id(2), v1), (id(3), v1), (id(4), v2)];
+
+        let all = SchemaAgreementPolicy::default();
+        assert_eq!(all.check(&[], None), {
+            SchemaAgreementCheck::Disagreed {
+                prevailing_version: None,
+                disagreeing_nodes: vec![],
+            }
+        });
+        assert_eq!(
+            all.check(&versions[..3], None),
+            SchemaAgreementCheck::Agreed(v1)
+        );
+        assert_eq!(
+            all.check(&versions, None),
+            SchemaAgreementCheck::Disagreed {
+                prevailing_version: Some(v1),
+                disagreeing_nodes: vec![id(4)],
+            }
+        );
+
+        let most = SchemaAgreementPolicy::builder()
+            .required_fraction(0.75)
+            .build();
+        assert_eq!(
+            most.check(&versions, None),
+            SchemaAgreementCheck::Agreed(v1)
+        );
+        // The required node has to hold the agreed version.
+        assert_eq!(
+            most.check(&versions, Some(id(4))),
+            SchemaAgreementCheck::Disagreed {
+                prevailing_version: Some(v1),
+                disagreeing_nodes: vec![id(4)],
+            }
+        );
+
+        let ignoring = SchemaAgreementPolicy::builder()
+            .ignored_nodes([id(4)])
+            .build();
+        assert_eq!(
+            ignoring.check(&versions, None),
+            SchemaAgreementCheck::Agreed(v1)
+        );
+        assert_eq!(
+            ignoring.check(&versions, Some(id(4))),
+            SchemaAgreementCheck::Agreed(v1)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn invalid_required_fraction() {
+        SchemaAgreementPolicy::builder().required_fraction(1.5);
+    }
+}
@@ -3,19 +3,23 @@
 //! This can help reduce latency for requests that may be slow due to network issues
 //! or node load.
 
+use crate::observability::log_targets;
 use futures::{
     future::FutureExt,
     stream::{FuturesUnordered, StreamExt},
 };
-#[cfg(feature = "metrics")]
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 use std::{future::Future, time::Duration};
-use tracing::{Instrument, trace_span};
+use tokio::time::Instant;
+use tracing::{Instrument, debug, trace_span};
 
+use crate::cluster::{ClusterState, Node};
 use crate::errors::{RequestAttemptError, RequestError};
 #[cfg(feature = "metrics")]
 use crate::observability::metrics::Metrics;
 use crate::response::Coordinator;
+use crate::utils::runtime;
 
 /// [`Context`] is passed as an argument to [`SpeculativeExecutionPolicy`] methods.
 #[non_exhaustive]
@@ -24,6 +28,13 @@ pub struct Context {
     /// Metrics instance that can be used as a context for deciding on speculative
     /// execution.
     pub metrics: Arc<Metrics>,
+
+    /// Current state of the cluster, as seen by the driver, that can be used
+    /// as a context for deciding on speculative execution.
+    pub cluster_state: Arc<ClusterState>,
+
+    /// The node to which the request is sent first, if known.
+    pub first_target: Option<Arc<Node>>,
 }
 
 /// The policy that decides if the driver will send speculative queries to the
@@ -64,6 +75,59 @@ pub struct PercentileSpeculativeExecutionPolicy {
     pub percentile: f64,
 }
 
+/// A policy that triggers speculative executions when the request takes longer
+/// than a given percentile of latencies of the node it was sent to.
+///
+/// Unlike [PercentileSpeculativeExecutionPolicy], which uses latencies of all nodes,
+/// this policy does not speculate too eagerly on nodes which are slower than others
+/// (e.g. located in a remote datacenter), and speculates early on the fast ones.
+/// Until enough latencies of a node are collected, latencies of all nodes are used.
+///
+/// # Example
+/// ```
+/// # fn example() {
+/// use scylla::policies::speculative_execution::PerNodePercentileSpeculativeExecutionPolicy;
+///
+/// let policy = PerNodePercentileSpeculativeExecutionPolicy::new(2, 99.0).with_min_samples(1000);
+/// # }
+/// ```
+#[cfg(feature = "metrics")]
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct PerNodePercentileSpeculativeExecutionPolicy {
+    /// The maximum number of speculative executions that will be triggered
+    /// for a given request (does not include the initial request)
+    pub max_retry_count: usize,
+
+    /// The percentile that a request's latency must fall into to be considered
+    /// slow (ex: 99.0)
+    pub percentile: f64,
+
+    /// The number of latencies of a node which must be collected before they are used
+    /// instead of latencies of all nodes.
+    pub min_samples: u64,
+}
+
+#[cfg(feature = "metrics")]
+impl PerNodePercentileSpeculativeExecutionPolicy {
+    /// Creates a policy with the given maximum number of speculative executions
+    /// and percentile. Latencies of a node are used once 100 of them are collected.
+    pub fn new(max_retry_count: usize, percentile: f64) -> Self {
+        Self {
+            max_retry_count,
+            percentile,
+            min_samples: 100,
+        }
+    }
+
+    /// Sets the number of latencies of a node which must be collected
+    /// before they are used instead of latencies of all nodes.
+    pub fn with_min_samples(mut self, min_samples: u64) -> Self {
+        self.min_samples = min_samples;
+        self
+    }
+}
+
 impl SpeculativeExecutionPolicy for SimpleSpeculativeExecutionPolicy {
     fn max_retry_count(&self, _: &Context) -> usize {
         self.max_retry_count
@@ -81,18 +145,396 @@ impl SpeculativeExecutionPolicy for PercentileSpeculativeExecutionPolicy {
     }
 
     fn retry_interval(&self, context: &Context) -> Duration {
-        let interval = context.metrics.get_latency_percentile_ms(self.percentile);
-        let ms = match interval {
-            Ok(d) => d,
-            Err(e) => {
-                tracing::warn!(
-                    "Failed to get latency percentile ({}), defaulting to 100 ms",
-                    e
-                );
-                100
+        global_percentile_interval(&context.metrics, self.percentile)
+    }
+}
+
+#[cfg(feature = "metrics")]
+impl SpeculativeExecutionPolicy for PerNodePercentileSpeculativeExecutionPolicy {
+    fn max_retry_count(&self, _: &Context) -> usize {
+        self.max_retry_count
+    }
+
+    fn retry_interval(&self, context: &Context) -> Duration {
+        let node_interval = context
+            .first_target
+            .as_ref()
+            .filter(|node| context.metrics.get_node_latency_count(node.host_id) >= self.min_samples)
+            .and_then(|node| {
+                context
+                    .metrics
+                    .get_node_latency_percentile_ms(node.host_id, self.percentile)
+                    .ok()
+            });
+        match node_interval {
+            Some(ms) => Duration::from_millis(ms),
+            None => global_percentile_interval(&context.metrics, self.percentile),
+        }
+    }
+}
+
+#[cfg(feature = "metrics")]
+fn global_percentile_interval(metrics: &Metrics, percentile: f64) -> Duration {
+    let interval = metrics.get_latency_percentile_ms(percentile);
+    let ms = match interval {
+        Ok(d) => d,
+        Err(e) => {
+            tracing::warn!(
+                target: log_targets::RETRY,
+                "Failed to get latency percentile ({}), defaulting to 100 ms",
+                e
+            );
+            100
+        }
+    };
+    Duration::from_millis(ms)
+}
+
+/// An event signalled when [HealthAwareSpeculativeExecutionPolicy] disables
+/// or re-enables speculative execution.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct HedgingBudgetEvent {
+    /// Whether speculative execution is enabled after the change.
+    pub speculative_execution_enabled: bool,
+
+    /// Number of enabled nodes with no working connections at the time of the change.
+    pub down_nodes: usize,
+
+    /// Error rate observed in the last complete window at the time of the change,
+    /// if it is tracked and enough requests were sent in that window.
+    pub error_rate: Option<f64>,
+}
+
+/// Allows listening to [HealthAwareSpeculativeExecutionPolicy] disabling
+/// and re-enabling speculative execution.
+pub trait HedgingBudgetListener: Send + Sync + std::fmt::Debug {
+    /// Invoked each time speculative execution gets disabled or re-enabled.
+    ///
+    /// This is called synchronously on the request execution path,
+    /// so the implementation should be cheap and must not block.
+    /// Events are delivered one at a time, in the order of the changes.
+    fn on_state_change(&self, event: &HedgingBudgetEvent);
+}
+
+/// A [`SpeculativeExecutionPolicy`] that wraps another policy and disables speculative
+/// execution altogether when the cluster looks unhealthy.
+///
+/// During partial outages, speculative executions only amplify the load put on the
+/// remaining nodes. This policy therefore stops scheduling speculative executions when:
+/// - the number of nodes that are down (enabled nodes with no working connections)
+///   exceeds `max_down_nodes`,
+/// - (with crate feature `"metrics"`) the ratio of failed requests to all requests,
+///   sampled over `error_rate_window`, exceeds `max_error_rate`.
+///
+/// The health of the cluster is evaluated at most once per `evaluation_interval`,
+/// by one of the requests executed after it elapses. Other requests use the verdict
+/// of the last evaluation, so the policy adds no contention to the request path.
+///
+/// Speculative execution is re-enabled as soon as none of the thresholds is exceeded.
+/// Both transitions are logged and reported to the [HedgingBudgetListener], if one is set.
+/// By default no thresholds are set, so the policy behaves exactly like the wrapped one.
+///
+/// # Example
+/// ```
+/// # fn example() {
+/// use std::sync::Arc;
+/// use std::time::Duration;
+/// use scylla::policies::speculative_execution::{
+///     HealthAwareSpeculativeExecutionPolicy, SimpleSpeculativeExecutionPolicy,
+/// };
+///
+/// let inner = Arc::new(SimpleSpeculativeExecutionPolicy {
+///     max_retry_count: 2,
+///     retry_interval: Duration::from_millis(50),
+/// });
+/// let policy = HealthAwareSpeculativeExecutionPolicy::builder(inner)
+///     .max_down_nodes(1)
+///     .build();
+/// # }
+/// ```
+pub struct HealthAwareSpeculativeExecutionPolicy {
+    inner: Arc<dyn SpeculativeExecutionPolicy>,
+    max_down_nodes: Option<usize>,
+    #[cfg(feature = "metrics")]
+    max_error_rate: Option<f64>,
+    #[cfg(feature = "metrics")]
+    error_rate_window: Duration,
+    #[cfg(feature = "metrics")]
+    error_rate_min_requests: u64,
+    evaluation_interval: Duration,
+    listener: Option<Arc<dyn HedgingBudgetListener>>,
+    /// Verdict of the last evaluation, read by requests without locking.
+    enabled: AtomicBool,
+    /// The moment the policy was built, relative to which `next_evaluation_nanos` is kept.
+    created_at: Instant,
+    /// Nanoseconds since `created_at` after which the health is to be evaluated again.
+    next_evaluation_nanos: AtomicU64,
+    /// Held during an evaluation, including the notification of the listener,
+    /// so that the events are delivered in the order of the changes.
+    state: Mutex<HedgingBudgetState>,
+}
+
+#[derive(Debug, Default)]
+struct HedgingBudgetState {
+    #[cfg(feature = "metrics")]
+    error_rate: ErrorRateSampler,
+}
+
+/// Computes the error rate over consecutive windows, based on the cumulative
+/// counters kept in [Metrics].
+#[cfg(feature = "metrics")]
+#[derive(Debug, Default)]
+struct ErrorRateSampler {
+    /// Start of the current window, with the counters as of that moment.
+    window_start: Option<(Instant, u64, u64)>,
+    /// Error rate in the last complete window.
+    last_error_rate: Option<f64>,
+}
+
+#[cfg(feature = "metrics")]
+impl ErrorRateSampler {
+    fn sample(&mut self, metrics: &Metrics, window: Duration, min_requests: u64) -> Option<f64> {
+        let now = Instant::now();
+        let errors = metrics.get_errors_num() + metrics.get_errors_iter_num();
+        let requests = metrics.get_queries_num() + metrics.get_queries_iter_num();
+
+        match self.window_start {
+            Some((start, start_errors, start_requests)) if now - start >= window => {
+                let window_requests = requests.saturating_sub(start_requests);
+                let window_errors = errors.saturating_sub(start_errors);
+                self.last_error_rate = (window_requests > 0 && window_requests >= min_requests)
+                    .then(|| window_errors as f64 / window_requests as f64);
+                self.window_start = Some((now, errors, requests));
             }
+            Some(_) => (),
+            None => self.window_start = Some((now, errors, requests)),
+        }
+
+        self.last_error_rate
+    }
+}
+
+impl std::fmt::Debug for HealthAwareSpeculativeExecutionPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut f = f.debug_struct("HealthAwareSpeculativeExecutionPolicy");
+        f.field("inner", &self.inner)
+            .field("max_down_nodes", &self.max_down_nodes);
+        #[cfg(feature = "metrics")]
+        f.field("max_error_rate", &self.max_error_rate)
+            .field("error_rate_window", &self.error_rate_window)
+            .field("error_rate_min_requests", &self.error_rate_min_requests);
+        f.field("evaluation_interval", &self.evaluation_interval)
+            .field("listener", &self.listener)
+            .finish()
+    }
+}
+
+impl HealthAwareSpeculativeExecutionPolicy {
+    /// Creates a builder of a policy wrapping the given speculative execution policy.
+    pub fn builder(
+        inner: Arc<dyn SpeculativeExecutionPolicy>,
+    ) -> HealthAwareSpeculativeExecutionPolicyBuilder {
+        HealthAwareSpeculativeExecutionPolicyBuilder::new(inner)
+    }
+
+    /// Returns `false` if speculative execution is currently disabled due to
+    /// the cluster being unhealthy.
+    ///
+    /// The state is reevaluated when a request with speculative execution is executed,
+    /// at most once per evaluation interval.
+    pub fn is_speculative_execution_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Returns whether speculative execution is allowed, reevaluating the cluster health
+    /// if the evaluation interval has elapsed.
+    fn evaluate(&self, context: &Context) -> bool {
+        let since_creation = Instant::now()
+            .saturating_duration_since(self.created_at)
+            .as_nanos() as u64;
+        if since_creation < self.next_evaluation_nanos.load(Ordering::Relaxed) {
+            return self.is_speculative_execution_enabled();
+        }
+        // If another request is evaluating the health right now, its verdict is as good.
+        // Without metrics, the guard only serializes the evaluations.
+        #[cfg_attr(not(feature = "metrics"), allow(unused_mut, unused_variables))]
+        let Ok(mut state) = self.state.try_lock() else {
+            return self.is_speculative_execution_enabled();
         };
-        Duration::from_millis(ms)
+        self.next_evaluation_nanos.store(
+            since_creation.saturating_add(self.evaluation_interval.as_nanos() as u64),
+            Ordering::Relaxed,
+        );
+
+        let down_nodes = context
+            .cluster_state
+            .get_nodes_info()
+            .iter()
+            .filter(|node| node.is_enabled() && !node.is_connected() && !node.is_idle())
+            .count();
+
+        #[cfg(feature = "metrics")]
+        let error_rate = match self.max_error_rate {
+            Some(_) => state.error_rate.sample(
+                &context.metrics,
+                self.error_rate_window,
+                self.error_rate_min_requests,
+            ),
+            None => None,
+        };
+        #[cfg(not(feature = "metrics"))]
+        let error_rate: Option<f64> = None;
+
+        let too_many_down_nodes = self.max_down_nodes.is_some_and(|max| down_nodes > max);
+        #[cfg(feature = "metrics")]
+        let too_high_error_rate = self
+            .max_error_rate
+            .zip(error_rate)
+            .is_some_and(|(max, rate)| rate > max);
+        #[cfg(not(feature = "metrics"))]
+        let too_high_error_rate = false;
+
+        let tripped = too_many_down_nodes || too_high_error_rate;
+        if tripped == self.is_speculative_execution_enabled() {
+            self.enabled.store(!tripped, Ordering::Relaxed);
+
+            if tripped {
+                debug!(
+                    target: log_targets::RETRY,
+                    "Disabling speculative execution: {} node(s) down, error rate: {:?}",
+                    down_nodes, error_rate
+                );
+            } else {
+                debug!(
+                    target: log_targets::RETRY,
+                    "Re-enabling speculative execution: {} node(s) down, error rate: {:?}",
+                    down_nodes, error_rate
+                );
+            }
+
+            // The evaluation lock is still held, so the listener sees the events in order.
+            if let Some(listener) = &self.listener {
+                listener.on_state_change(&HedgingBudgetEvent {
+                    speculative_execution_enabled: !tripped,
+                    down_nodes,
+                    error_rate,
+                });
+            }
+        }
+
+        !tripped
+    }
+}
+
+impl SpeculativeExecutionPolicy for HealthAwareSpeculativeExecutionPolicy {
+    fn max_retry_count(&self, context: &Context) -> usize {
+        if self.evaluate(context) {
+            self.inner.max_retry_count(context)
+        } else {
+            0
+        }
+    }
+
+    fn retry_interval(&self, context: &Context) -> Duration {
+        self.inner.retry_interval(context)
+    }
+}
+
+/// Builder of [HealthAwareSpeculativeExecutionPolicy].
+pub struct HealthAwareSpeculativeExecutionPolicyBuilder {
+    inner: Arc<dyn SpeculativeExecutionPolicy>,
+    max_down_nodes: Option<usize>,
+    #[cfg(feature = "metrics")]
+    max_error_rate: Option<f64>,
+    #[cfg(feature = "metrics")]
+    error_rate_window: Duration,
+    #[cfg(feature = "metrics")]
+    error_rate_min_requests: u64,
+    evaluation_interval: Duration,
+    listener: Option<Arc<dyn HedgingBudgetListener>>,
+}
+
+impl HealthAwareSpeculativeExecutionPolicyBuilder {
+    /// Creates a builder of a policy wrapping the given speculative execution policy,
+    /// with no thresholds set.
+    pub fn new(inner: Arc<dyn SpeculativeExecutionPolicy>) -> Self {
+        Self {
+            inner,
+            max_down_nodes: None,
+            #[cfg(feature = "metrics")]
+            max_error_rate: None,
+            #[cfg(feature = "metrics")]
+            error_rate_window: Duration::from_secs(10),
+            #[cfg(feature = "metrics")]
+            error_rate_min_requests: 20,
+            evaluation_interval: Duration::from_secs(1),
+            listener: None,
+        }
+    }
+
+    /// Sets the maximum number of nodes that can be down while speculative execution
+    /// stays enabled. A node is considered down if it is enabled, but the driver
+    /// has no working connections to it.
+    pub fn max_down_nodes(mut self, max_down_nodes: usize) -> Self {
+        self.max_down_nodes = Some(max_down_nodes);
+        self
+    }
+
+    /// Sets the maximum ratio of failed requests to all requests (in range `[0.0, 1.0]`)
+    /// at which speculative execution stays enabled.
+    #[cfg(feature = "metrics")]
+    pub fn max_error_rate(mut self, max_error_rate: f64) -> Self {
+        self.max_error_rate = Some(max_error_rate);
+        self
+    }
+
+    /// Sets the length of the window over which the error rate is computed.
+    /// Defaults to 10 seconds.
+    #[cfg(feature = "metrics")]
+    pub fn error_rate_window(mut self, error_rate_window: Duration) -> Self {
+        self.error_rate_window = error_rate_window;
+        self
+    }
+
+    /// Sets the minimum number of requests that must be sent during a window
+    /// for its error rate to be taken into account. Defaults to 20.
+    #[cfg(feature = "metrics")]
+    pub fn error_rate_min_requests(mut self, error_rate_min_requests: u64) -> Self {
+        self.error_rate_min_requests = error_rate_min_requests;
+        self
+    }
+
+    /// Sets how often the health of the cluster is evaluated. Defaults to 1 second.
+    pub fn evaluation_interval(mut self, evaluation_interval: Duration) -> Self {
+        self.evaluation_interval = evaluation_interval;
+        self
+    }
+
+    /// Sets a listener notified when speculative execution gets disabled or re-enabled.
+    pub fn listener(mut self, listener: Arc<dyn HedgingBudgetListener>) -> Self {
+        self.listener = Some(listener);
+        self
+    }
+
+    /// Builds the policy.
+    pub fn build(self) -> Arc<HealthAwareSpeculativeExecutionPolicy> {
+        Arc::new(HealthAwareSpeculativeExecutionPolicy {
+            inner: self.inner,
+            max_down_nodes: self.max_down_nodes,
+            #[cfg(feature = "metrics")]
+            max_error_rate: self.max_error_rate,
+            #[cfg(feature = "metrics")]
+            error_rate_window: self.error_rate_window,
+            #[cfg(feature = "metrics")]
+            error_rate_min_requests: self.error_rate_min_requests,
+            evaluation_interval: self.evaluation_interval,
+            listener: self.listener,
+            enabled: AtomicBool::new(true),
+            created_at: Instant::now(),
+            next_evaluation_nanos: AtomicU64::new(0),
+            state: Mutex::new(HedgingBudgetState::default()),
+        })
     }
 }
 
@@ -168,7 +610,7 @@ where
             .instrument(trace_span!("Speculative execution: original query")),
     );
 
-    let sleep = tokio::time::sleep(retry_interval).fuse();
+    let sleep = runtime::sleep(retry_interval).fuse();
     tokio::pin!(sleep);
 
     let mut last_error = None;
@@ -180,7 +622,7 @@ where
                     retries_remaining -= 1;
 
                     // reset the timeout
-                    sleep.set(tokio::time::sleep(retry_interval).fuse());
+                    sleep.set(runtime::sleep(retry_interval).fuse());
                 }
             }
             res = async_tasks.select_next_some() => {
@@ -214,24 +656,202 @@ mod tests {
     // Starting paused is done with `#[tokio::test(flavor = "current_thread", start_paused = true)]`.
     // Pausing can only be done with current_thread executor.
 
-    #[cfg(feature = "metrics")]
-    use std::sync::Arc;
-    use std::sync::LazyLock;
+    use std::sync::{Arc, LazyLock, Mutex};
     use std::time::Duration;
 
     use assert_matches::assert_matches;
 
+    use crate::cluster::ClusterState;
     use crate::errors::{RequestAttemptError, RequestError};
     #[cfg(feature = "metrics")]
     use crate::observability::metrics::Metrics;
-    use crate::policies::speculative_execution::{Context, SimpleSpeculativeExecutionPolicy};
+    use crate::policies::speculative_execution::{
+        Context, HealthAwareSpeculativeExecutionPolicy, HedgingBudgetEvent, HedgingBudgetListener,
+        SimpleSpeculativeExecutionPolicy, SpeculativeExecutionPolicy,
+    };
     use crate::response::Coordinator;
+    use crate::routing::locator::test::{create_locator, mock_metadata_for_token_aware_tests};
+    use crate::test_utils::MockClusterStateBuilder;
+
+    fn empty_cluster_state() -> Arc<ClusterState> {
+        Arc::new(ClusterState {
+            known_peers: Default::default(),
+            all_nodes: Default::default(),
+            keyspaces: Default::default(),
+            locator: create_locator(&mock_metadata_for_token_aware_tests()),
+            ring_version: 0,
+            tablets_version: 0,
+            internal_requests_disabled_nodes: Default::default(),
+            custom_partitioners: Default::default(),
+        })
+    }
 
     static EMPTY_CONTEXT: LazyLock<Context> = LazyLock::new(|| Context {
         #[cfg(feature = "metrics")]
         metrics: Arc::new(Metrics::new()),
+        cluster_state: empty_cluster_state(),
+        first_target: None,
     });
 
+    const INNER_POLICY: SimpleSpeculativeExecutionPolicy = SimpleSpeculativeExecutionPolicy {
+        max_retry_count: 3,
+        retry_interval: Duration::from_millis(100),
+    };
+
+    #[derive(Debug, Default)]
+    struct RecordingListener {
+        events: Mutex<Vec<HedgingBudgetEvent>>,
+    }
+
+    impl HedgingBudgetListener for RecordingListener {
+        fn on_state_change(&self, event: &HedgingBudgetEvent) {
+            self.events.lock().unwrap().push(event.clone());
+        }
+    }
+
+    impl RecordingListener {
+        fn take_states(&self) -> Vec<(bool, usize)> {
+            self.events
+                .lock()
+                .unwrap()
+                .drain(..)
+                .map(|event| (event.speculative_execution_enabled, event.down_nodes))
+                .collect()
+        }
+    }
+
+    #[tokio::test(flavor = "current_thread", start_paused = true)]
+    async fn test_health_aware_policy_disables_speculation_when_nodes_are_down() {
+        // Nodes are enabled, but never connect, so all 7 of them are down.
+        let cluster_state = MockClusterStateBuilder::new(mock_metadata_for_token_aware_tests())
+            .build()
+            .await;
+        let context = Context {
+            #[cfg(feature = "metrics")]
+            metrics: Arc::new(Metrics::new()),
+            cluster_state: Arc::new(cluster_state),
+            first_target: None,
+        };
+
+        let listener = Arc::new(RecordingListener::default());
+        let policy = HealthAwareSpeculativeExecutionPolicy::builder(Arc::new(INNER_POLICY))
+            .max_down_nodes(2)
+            .listener(Arc::clone(&listener) as _)
+            .build();
+
+        assert_eq!(policy.max_retry_count(&context), 0);
+        assert!(!policy.is_speculative_execution_enabled());
+        assert_eq!(policy.retry_interval(&context), INNER_POLICY.retry_interval);
+        assert_eq!(listener.take_states(), [(false, 7)]);
+
+        // The state does not change, so no new event is emitted.
+        assert_eq!(policy.max_retry_count(&context), 0);
+        assert_eq!(listener.take_states(), []);
+
+        // Bring all nodes but two up.
+        for node in context.cluster_state.get_nodes_info().iter().skip(2) {
+            node.use_enabled_as_connected();
+        }
+        // The verdict is not reevaluated until the evaluation interval elapses.
+        assert_eq!(policy.max_retry_count(&context), 0);
+        assert_eq!(listener.take_states(), []);
+        tokio::time::advance(Duration::from_secs(1)).await;
+        assert_eq!(
+            policy.max_retry_count(&context),
+            INNER_POLICY.max_retry_count
+        );
+        assert!(policy.is_speculative_execution_enabled());
+        assert_eq!(listener.take_states(), [(true, 2)]);
+    }
+
+    #[cfg(feature = "metrics")]
+    #[tokio::test(flavor = "current_thread", start_paused = true)]
+    async fn test_health_aware_policy_disables_speculation_on_high_error_rate() {
+        let window = Duration::from_secs(10);
+        let policy = HealthAwareSpeculativeExecutionPolicy::builder(Arc::new(INNER_POLICY))
+            .max_error_rate(0.5)
+            .error_rate_window(window)
+            .error_rate_min_requests(10)
+            .build();
+        let context = Context {
+            metrics: Arc::new(Metrics::new()),
+            cluster_state: empty_cluster_state(),
+            first_target: None,
+        };
+        let send_requests = |total: usize, failed: usize| {
+            for i in 0..total {
+                context.metrics.inc_total_nonpaged_queries();
+                if i < failed {
+                    context.metrics.inc_failed_nonpaged_queries();
+                }
+            }
+        };
+
+        // The first call starts the first window.
+        assert_eq!(
+            policy.max_retry_count(&context),
+            INNER_POLICY.max_retry_count
+        );
+
+        // Too few requests in the window to take the error rate into account.
+        send_requests(5, 5);
+        tokio::time::advance(window).await;
+        assert_eq!(
+            policy.max_retry_count(&context),
+            INNER_POLICY.max_retry_count
+        );
+
+        // Error rate exceeds the threshold, but the window is not complete yet.
+        send_requests(20, 15);
+        assert_eq!(
+            policy.max_retry_count(&context),
+            INNER_POLICY.max_retry_count
+        );
+        tokio::time::advance(window).await;
+        assert_eq!(policy.max_retry_count(&context), 0);
+
+        // The error rate stays as computed in the last complete window.
+        send_requests(20, 0);
+        assert_eq!(policy.max_retry_count(&context), 0);
+        tokio::time::advance(window).await;
+        assert_eq!(
+            policy.max_retry_count(&context),
+            INNER_POLICY.max_retry_count
+        );
+    }
+
+    #[cfg(feature = "metrics")]
+    #[tokio::test]
+    async fn test_per_node_percentile_policy_uses_latencies_of_first_target() {
+        use crate::cluster::Node;
+        use crate::policies::speculative_execution::PerNodePercentileSpeculativeExecutionPolicy;
+
+        let metrics = Arc::new(Metrics::new());
+        let node = Arc::new(Node::new_for_test(None, None, None, None));
+        let mut context = Context {
+            metrics: Arc::clone(&metrics),
+            cluster_state: empty_cluster_state(),
+            first_target: Some(Arc::clone(&node)),
+        };
+        let policy = PerNodePercentileSpeculativeExecutionPolicy::new(1, 50.0).with_min_samples(10);
+
+        for _ in 0..100 {
+            metrics.log_query_latency(20).unwrap();
+        }
+        for _ in 0..9 {
+            metrics.log_node_latency(node.host_id, 300).unwrap();
+        }
+        // Too few latencies of the node are collected, so the global ones are used.
+        assert_eq!(policy.retry_interval(&context), Duration::from_millis(20));
+
+        metrics.log_node_latency(node.host_id, 300).unwrap();
+        let interval = policy.retry_interval(&context);
+        assert!(interval.abs_diff(Duration::from_millis(300)) <= Duration::from_millis(3));
+
+        context.first_target = None;
+        assert_eq!(policy.retry_interval(&context), Duration::from_millis(20));
+    }
+
     static IGNORABLE_ERROR: Option<Result<((), Coordinator), RequestError>> = Some(Err(
         RequestError::LastAttemptError(RequestAttemptError::UnableToAllocStreamId),
     ));
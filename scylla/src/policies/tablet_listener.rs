@@ -0,0 +1,56 @@
+//! Tablet listeners can subscribe to updates of the tablet maps known to the driver.
+//!
+//! ScyllaDB tables using tablets are split into tablets, which are moved between nodes
+//! (migrated) as the cluster balances its load. The driver learns about new tablet
+//! placements from responses of the cluster, and drops tablets that became stale
+//! during topology refreshes. A [TabletListener] is notified about each such update,
+//! which allows e.g. external schedulers to react to tablet migrations.
+
+use std::sync::Arc;
+
+use scylla_cql::frame::response::result::TableSpec;
+
+use crate::cluster::ClusterState;
+
+/// Update of tablet maps, signalled to a [TabletListener].
+#[non_exhaustive]
+pub struct TabletsUpdateEvent {
+    pub(crate) cluster_state: Arc<ClusterState>,
+    pub(crate) changed_tables: Vec<TableSpec<'static>>,
+}
+
+// Manual implementation, because ClusterState doesn't implement Debug.
+impl std::fmt::Debug for TabletsUpdateEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TabletsUpdateEvent")
+            .field("tablets_version", &self.cluster_state.tablets_version())
+            .field("changed_tables", &self.changed_tables)
+            .finish()
+    }
+}
+
+impl TabletsUpdateEvent {
+    /// Cluster state which contains the updated tablet maps.
+    ///
+    /// See [ClusterState::table_tablets] and [ClusterState::tablets_version].
+    pub fn cluster_state(&self) -> &Arc<ClusterState> {
+        &self.cluster_state
+    }
+
+    /// Tables whose tablet maps changed. Tables whose tablets were all dropped
+    /// (e.g. because the table was dropped) are included, too.
+    pub fn changed_tables(&self) -> &[TableSpec<'static>] {
+        &self.changed_tables
+    }
+}
+
+/// Allows listening to updates of tablet maps.
+///
+/// The listener is called from the driver's background task which maintains
+/// the cluster metadata, after the updated tablet maps become visible through
+/// [Session::get_cluster_state](crate::client::session::Session::get_cluster_state).
+/// Implementations should return quickly, e.g. by forwarding the event to a channel.
+pub trait TabletListener: Send + Sync {
+    /// Called when tablet maps of some tables changed.
+    fn on_tablets_updated(&self, event: &TabletsUpdateEvent);
+}
@@ -7,6 +7,7 @@ use std::{
     time::{SystemTime, UNIX_EPOCH},
 };
 
+use crate::observability::log_targets;
 use std::sync::Mutex;
 use std::sync::atomic::Ordering;
 use tokio::time::{Duration, Instant};
@@ -115,10 +116,11 @@ impl MonotonicTimestampGenerator {
                         *last_warn = now;
                         drop(last_warn);
                         warn!(
+                            target: log_targets::REQUEST,
                             "Clock skew detected. The current time ({}) was {} \
-                    microseconds behind the last generated timestamp ({}). \
-                    The next generated timestamp will be artificially incremented \
-                    to guarantee monotonicity.",
+                        microseconds behind the last generated timestamp ({}). \
+                        The next generated timestamp will be artificially incremented \
+                        to guarantee monotonicity.",
                             u_cur,
                             last - u_cur,
                             last
@@ -128,7 +130,10 @@ impl MonotonicTimestampGenerator {
             }
         } else {
             // We have generated a timestamp before UNIX epoch, we will warn the user and increment the last timestamp
-            warn!("Clock skew detected. The current time was behind UNIX epoch.");
+            warn!(
+                target: log_targets::REQUEST,
+                "Clock skew detected. The current time was behind UNIX epoch.",
+            );
         }
 
         last + 1
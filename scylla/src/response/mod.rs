@@ -8,10 +8,14 @@
 //!   request that contains some rows, which can be deserialized by the user.
 
 mod coordinator;
+#[cfg(feature = "paging-state-codec")]
+mod paging_state;
 pub mod query_result;
 mod request_response;
 
 pub use coordinator::Coordinator;
+#[cfg(feature = "paging-state-codec")]
+pub use paging_state::{PagingStateCodec, PagingStateKey};
 pub(crate) use request_response::{
     NonErrorAuthResponse, NonErrorQueryResponse, NonErrorStartupResponse, QueryResponse,
 };
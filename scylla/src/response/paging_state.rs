@@ -0,0 +1,312 @@
+//! Exporting and importing [PagingState] across trust boundaries.
+//!
+//! A raw paging state is an opaque blob produced by the server. Applications
+//! often hand it out to their clients (e.g. as an HTTP cursor) and accept it back
+//! later. Forwarding such an externally supplied blob to the server as-is is risky:
+//! it may belong to a different statement, or it may have been tampered with.
+//!
+//! [PagingStateCodec] wraps the raw state into an envelope which is bound to
+//! the statement that produced it and, if a [PagingStateKey] is configured,
+//! authenticated with HMAC-SHA256. Importing the envelope validates it and
+//! returns a [PagingStateImportError] instead of handing garbage to the server.
+
+use std::sync::Arc;
+
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
+
+use crate::errors::PagingStateImportError;
+
+use super::PagingState;
+
+type HmacSha256 = Hmac<Sha256>;
+
+const ENVELOPE_VERSION: u8 = 1;
+
+const FLAG_SIGNED: u8 = 0x01;
+const FLAG_START: u8 = 0x02;
+const KNOWN_FLAGS: u8 = FLAG_SIGNED | FLAG_START;
+
+const STATEMENT_DIGEST_LEN: usize = 8;
+const TAG_LEN: usize = 32;
+
+/// Secret key used to sign exported paging states.
+///
+/// The key is never printed by its `Debug` implementation,
+/// and its memory is wiped once the last clone of the key is dropped.
+#[derive(Clone)]
+pub struct PagingStateKey(Arc<Zeroizing<Vec<u8>>>);
+
+impl PagingStateKey {
+    /// Creates a key from raw secret bytes.
+    ///
+    /// Any length is accepted, but keys shorter than 32 bytes
+    /// weaken the HMAC-SHA256 signature.
+    pub fn new(secret: impl Into<Vec<u8>>) -> Self {
+        Self(Arc::new(Zeroizing::new(secret.into())))
+    }
+
+    fn mac(&self) -> HmacSha256 {
+        // HMAC accepts keys of any length, so this never fails.
+        HmacSha256::new_from_slice(&self.0).expect("HMAC can take a key of any size")
+    }
+}
+
+impl std::fmt::Debug for PagingStateKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("PagingStateKey")
+            .field(&"<redacted>")
+            .finish()
+    }
+}
+
+/// Converts [PagingState] to and from a validated, optionally signed, byte envelope.
+///
+/// The envelope contains a fingerprint of the statement the paging state belongs to,
+/// so a state exported for one statement is rejected when imported for another one.
+/// If the codec holds a [PagingStateKey], exported envelopes are signed and only
+/// signed envelopes with a valid signature are accepted on import.
+///
+/// The codec configured on a session is available through
+/// [Session::paging_state_codec](crate::client::session::Session::paging_state_codec).
+#[derive(Clone, Debug, Default)]
+pub struct PagingStateCodec {
+    key: Option<PagingStateKey>,
+}
+
+impl PagingStateCodec {
+    /// Creates a new codec. If `key` is `None`, envelopes are not signed.
+    pub fn new(key: Option<PagingStateKey>) -> Self {
+        Self { key }
+    }
+
+    /// Returns true if this codec signs exported paging states.
+    pub fn is_signing(&self) -> bool {
+        self.key.is_some()
+    }
+
+    /// Wraps `paging_state` into an envelope bound to the given statement.
+    ///
+    /// `statement` should be the CQL text of the statement that is paged, e.g.
+    /// [Statement::contents](crate::statement::unprepared::Statement::contents) or
+    /// [PreparedStatement::get_statement](crate::statement::prepared::PreparedStatement::get_statement).
+    pub fn export(&self, statement: &str, paging_state: &PagingState) -> Vec<u8> {
+        let raw = paging_state.as_bytes_slice().map(|raw| &raw[..]);
+        let raw_len = raw.map_or(0, <[u8]>::len);
+
+        let mut flags = 0;
+        if self.key.is_some() {
+            flags |= FLAG_SIGNED;
+        }
+        if raw.is_none() {
+            flags |= FLAG_START;
+        }
+
+        let mut buf = Vec::with_capacity(2 + STATEMENT_DIGEST_LEN + 4 + raw_len + TAG_LEN);
+        buf.push(ENVELOPE_VERSION);
+        buf.push(flags);
+        buf.extend_from_slice(&statement_digest(statement));
+        // Length fits in u32, because server-side paging states are bounded by the frame size.
+        buf.extend_from_slice(&(raw_len as u32).to_be_bytes());
+        buf.extend_from_slice(raw.unwrap_or_default());
+
+        if let Some(key) = &self.key {
+            let mut mac = key.mac();
+            mac.update(&buf);
+            buf.extend_from_slice(&mac.finalize().into_bytes());
+        }
+
+        buf
+    }
+
+    /// Validates an envelope previously created by [Self::export]
+    /// and recovers the paging state from it.
+    ///
+    /// Fails if the envelope is malformed, was exported for a different statement,
+    /// or its signature does not match the key held by this codec.
+    pub fn import(
+        &self,
+        statement: &str,
+        envelope: &[u8],
+    ) -> Result<PagingState, PagingStateImportError> {
+        let [version, flags, rest @ ..] = envelope else {
+            return Err(PagingStateImportError::Malformed);
+        };
+        if *version != ENVELOPE_VERSION {
+            return Err(PagingStateImportError::UnsupportedVersion(*version));
+        }
+        if flags & !KNOWN_FLAGS != 0 {
+            return Err(PagingStateImportError::Malformed);
+        }
+
+        let signed = flags & FLAG_SIGNED != 0;
+        let body = match (signed, &self.key) {
+            (true, Some(key)) => {
+                let body_len = envelope
+                    .len()
+                    .checked_sub(TAG_LEN)
+                    .ok_or(PagingStateImportError::Malformed)?;
+                let (body, tag) = envelope.split_at(body_len);
+                let mut mac = key.mac();
+                mac.update(body);
+                mac.verify_slice(tag)
+                    .map_err(|_| PagingStateImportError::InvalidSignature)?;
+                &body[2..]
+            }
+            (true, None) => return Err(PagingStateImportError::MissingKey),
+            (false, Some(_)) => return Err(PagingStateImportError::MissingSignature),
+            (false, None) => rest,
+        };
+
+        let (digest, body) = body
+            .split_first_chunk::<STATEMENT_DIGEST_LEN>()
+            .ok_or(PagingStateImportError::Malformed)?;
+        if *digest != statement_digest(statement) {
+            return Err(PagingStateImportError::StatementMismatch);
+        }
+
+        let (len, raw) = body
+            .split_first_chunk::<4>()
+            .ok_or(PagingStateImportError::Malformed)?;
+        if u32::from_be_bytes(*len) as usize != raw.len() {
+            return Err(PagingStateImportError::Malformed);
+        }
+
+        match (flags & FLAG_START != 0, raw.is_empty()) {
+            (true, true) => Ok(PagingState::start()),
+            (false, false) => Ok(PagingState::new_from_raw_bytes(raw)),
+            // A start state carries no bytes, and the server never returns an empty one.
+            _ => Err(PagingStateImportError::Malformed),
+        }
+    }
+}
+
+fn statement_digest(statement: &str) -> [u8; STATEMENT_DIGEST_LEN] {
+    let hash = Sha256::digest(statement.as_bytes());
+    let mut digest = [0; STATEMENT_DIGEST_LEN];
+    digest.copy_from_slice(&hash[..STATEMENT_DIGEST_LEN]);
+    digest
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::{PagingStateCodec, PagingStateKey};
+    use crate::errors::PagingStateImportError;
+    use crate::response::PagingState;
+
+    const STATEMENT: &str = "SELECT a, b FROM ks.t WHERE a = ?";
+    const OTHER_STATEMENT: &str = "SELECT a, b FROM ks.other WHERE a = ?";
+
+    fn raw_state() -> PagingState {
+        PagingState::new_from_raw_bytes(&[0x00, 0x04, 0xde, 0xad, 0xbe, 0xef][..])
+    }
+
+    fn key(secret: &[u8]) -> Option<PagingStateKey> {
+        Some(PagingStateKey::new(secret))
+    }
+
+    #[test]
+    fn paging_state_roundtrip() {
+        for codec in [
+            PagingStateCodec::new(None),
+            PagingStateCodec::new(key(b"secret")),
+        ] {
+            for state in [PagingState::start(), raw_state()] {
+                let envelope = codec.export(STATEMENT, &state);
+                let imported = codec.import(STATEMENT, &envelope).unwrap();
+                assert_eq!(imported.as_bytes_slice(), state.as_bytes_slice());
+            }
+        }
+    }
+
+    #[test]
+    fn paging_state_rejects_other_statement() {
+        for codec in [
+            PagingStateCodec::new(None),
+            PagingStateCodec::new(key(b"secret")),
+        ] {
+            let envelope = codec.export(STATEMENT, &raw_state());
+            assert_matches!(
+                codec.import(OTHER_STATEMENT, &envelope),
+                Err(PagingStateImportError::StatementMismatch)
+            );
+        }
+    }
+
+    #[test]
+    fn paging_state_detects_tampering() {
+        let codec = PagingStateCodec::new(key(b"secret"));
+        let envelope = codec.export(STATEMENT, &raw_state());
+
+        for i in 2..envelope.len() {
+            let mut tampered = envelope.clone();
+            tampered[i] ^= 0x01;
+            assert_matches!(
+                codec.import(STATEMENT, &tampered),
+                Err(PagingStateImportError::InvalidSignature)
+            );
+        }
+
+        let other_key = PagingStateCodec::new(key(b"other secret"));
+        assert_matches!(
+            other_key.import(STATEMENT, &envelope),
+            Err(PagingStateImportError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn paging_state_signature_presence_must_match() {
+        let signing = PagingStateCodec::new(key(b"secret"));
+        let plain = PagingStateCodec::new(None);
+
+        assert_matches!(
+            signing.import(STATEMENT, &plain.export(STATEMENT, &raw_state())),
+            Err(PagingStateImportError::MissingSignature)
+        );
+        assert_matches!(
+            plain.import(STATEMENT, &signing.export(STATEMENT, &raw_state())),
+            Err(PagingStateImportError::MissingKey)
+        );
+    }
+
+    #[test]
+    fn paging_state_rejects_malformed_envelopes() {
+        let codec = PagingStateCodec::new(None);
+        let envelope = codec.export(STATEMENT, &raw_state());
+
+        assert_matches!(
+            codec.import(STATEMENT, &[]),
+            Err(PagingStateImportError::Malformed)
+        );
+        assert_matches!(
+            codec.import(STATEMENT, &[42, 0]),
+            Err(PagingStateImportError::UnsupportedVersion(42))
+        );
+        assert_matches!(
+            codec.import(STATEMENT, &[1, 0x80]),
+            Err(PagingStateImportError::Malformed)
+        );
+        // Truncated raw state.
+        assert_matches!(
+            codec.import(STATEMENT, &envelope[..envelope.len() - 1]),
+            Err(PagingStateImportError::Malformed)
+        );
+        // Trailing garbage.
+        let mut extended = envelope.clone();
+        extended.push(0);
+        assert_matches!(
+            codec.import(STATEMENT, &extended),
+            Err(PagingStateImportError::Malformed)
+        );
+        // Start flag set, but raw bytes present.
+        let mut start_with_bytes = envelope;
+        start_with_bytes[1] |= super::FLAG_START;
+        assert_matches!(
+            codec.import(STATEMENT, &start_with_bytes),
+            Err(PagingStateImportError::Malformed)
+        );
+    }
+}
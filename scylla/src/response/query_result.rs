@@ -307,6 +307,9 @@ impl QueryRowsResult {
 
     /// Returns an iterator over the received rows.
     ///
+    /// The rows may borrow from the result, so columns can be deserialized
+    /// to types such as `&str` or `&[u8]` without allocating.
+    ///
     /// Returns an error if the rows in the response are of incorrect type.
     #[inline]
     pub fn rows<'frame, R: DeserializeRow<'frame, 'frame>>(
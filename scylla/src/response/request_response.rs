@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 
+use crate::observability::log_targets;
 use bytes::Bytes;
 use scylla_cql::frame::request::query::PagingStateResponse;
 use scylla_cql::frame::response::{
@@ -112,6 +113,7 @@ impl NonErrorQueryResponse {
 
         if !paging_state.finished() {
             error!(
+                target: log_targets::REQUEST,
                 "Internal driver API misuse or a server bug: nonfinished paging state\
                 would be discarded by `NonErrorQueryResponse::into_query_result`"
             );
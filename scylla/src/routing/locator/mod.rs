@@ -19,12 +19,14 @@ mod token_ring;
 
 use rand::{Rng, seq::IteratorRandom};
 use scylla_cql::frame::response::result::TableSpec;
+pub use tablets::{TableTablets, Tablet};
 pub use token_ring::TokenRing;
 
 use self::tablets::TabletsInfo;
 
 use crate::cluster::metadata::Strategy;
 use crate::cluster::{Node, NodeRef};
+use crate::observability::log_targets;
 use crate::routing::{Shard, Token};
 use itertools::Itertools;
 use precomputed_replicas::PrecomputedReplicas;
@@ -45,14 +47,25 @@ use tracing::debug;
 /// - tablets, which is a newer mechanism used by ScyllaDB.
 #[derive(Debug, Clone)]
 pub struct ReplicaLocator {
+    /// Token ring based replica data. It is immutable once computed, so it is shared
+    /// (instead of copied) by locators which differ only in tablets.
+    token_ring: Arc<TokenRingReplicas>,
+
+    pub(crate) tablets: TabletsInfo,
+}
+
+/// The token ring together with replica sets precomputed for it.
+#[derive(Debug)]
+struct TokenRingReplicas {
     /// The data based on which `ReplicaLocator` computes replica sets.
     replication_data: ReplicationInfo,
 
     precomputed_replicas: PrecomputedReplicas,
 
-    datacenters: Vec<String>,
+    /// Strategies for which replica sets were precomputed, without duplicates.
+    precomputed_strategies: Vec<Strategy>,
 
-    pub(crate) tablets: TabletsInfo,
+    datacenters: Vec<String>,
 }
 
 impl ReplicaLocator {
@@ -65,8 +78,14 @@ impl ReplicaLocator {
         tablets: TabletsInfo,
     ) -> Self {
         let replication_data = ReplicationInfo::new(ring_iter);
+        let mut precomputed_strategies: Vec<Strategy> = Vec::new();
+        for strategy in precompute_replica_sets_for {
+            if !precomputed_strategies.contains(strategy) {
+                precomputed_strategies.push(strategy.clone());
+            }
+        }
         let precomputed_replicas =
-            PrecomputedReplicas::compute(&replication_data, precompute_replica_sets_for);
+            PrecomputedReplicas::compute(&replication_data, precomputed_strategies.iter());
 
         let datacenters = replication_data
             .get_global_ring()
@@ -77,9 +96,64 @@ impl ReplicaLocator {
             .collect();
 
         Self {
-            replication_data,
-            precomputed_replicas,
-            datacenters,
+            token_ring: Arc::new(TokenRingReplicas {
+                replication_data,
+                precomputed_replicas,
+                precomputed_strategies,
+                datacenters,
+            }),
+            tablets,
+        }
+    }
+
+    /// Checks whether the token ring data of this locator can be reused for
+    /// the given ring and strategies, i.e. whether building a new locator for them
+    /// would yield the same token ring data.
+    ///
+    /// Nodes are compared by identity, because a recreated `Node` must not be
+    /// referenced by the new locator.
+    pub(crate) fn token_ring_matches<'a>(
+        &self,
+        ring: &[(Token, Arc<Node>)],
+        mut strategies: impl Iterator<Item = &'a Strategy>,
+    ) -> bool {
+        let current_ring = self.ring();
+        if current_ring.len() != ring.len() {
+            return false;
+        }
+        let mut ring: Vec<&(Token, Arc<Node>)> = ring.iter().collect();
+        ring.sort_by_key(|(token, _)| *token);
+        let same_ring =
+            current_ring
+                .iter()
+                .zip(ring)
+                .all(|((token_a, node_a), (token_b, node_b))| {
+                    token_a == token_b && Arc::ptr_eq(node_a, node_b)
+                });
+        if !same_ring {
+            return false;
+        }
+
+        let precomputed = &self.token_ring.precomputed_strategies;
+        let mut seen = vec![false; precomputed.len()];
+        let all_precomputed =
+            strategies.all(
+                |strategy| match precomputed.iter().position(|s| s == strategy) {
+                    Some(idx) => {
+                        seen[idx] = true;
+                        true
+                    }
+                    None => false,
+                },
+            );
+        all_precomputed && seen.into_iter().all(|seen| seen)
+    }
+
+    /// Creates a locator sharing the token ring data with this one, but using
+    /// the given tablets. This is cheap, as no replica sets are computed.
+    pub(crate) fn with_tablets(&self, tablets: TabletsInfo) -> Self {
+        Self {
+            token_ring: Arc::clone(&self.token_ring),
             tablets,
         }
     }
@@ -157,7 +231,11 @@ impl ReplicaLocator {
                                 token,
                             };
                         } else {
-                            debug!("Datacenter ({}) does not exist!", dc);
+                            debug!(
+                                target: log_targets::TOPOLOGY,
+                                "Datacenter ({}) does not exist!",
+                                dc,
+                            );
                             return ReplicaSet {
                                 inner: ReplicaSetInner::Plain(EMPTY_REPLICAS),
                                 token,
@@ -176,6 +254,7 @@ impl ReplicaLocator {
                 }
                 Strategy::Other { name, .. } => {
                     debug!(
+                        target: log_targets::TOPOLOGY,
                         "Unknown strategy ({}), falling back to SimpleStrategy with replication_factor = 1",
                         name
                     )
@@ -197,17 +276,19 @@ impl ReplicaLocator {
 
     /// Gives access to the token ring, based on which all token ranges/replica sets are computed.
     pub fn ring(&self) -> &TokenRing<Arc<Node>> {
-        self.replication_data.get_global_ring()
+        self.token_ring.replication_data.get_global_ring()
     }
 
     /// Gives a list of all nodes in the token ring.
     pub fn unique_nodes_in_global_ring(&self) -> &[Arc<Node>] {
-        self.replication_data.unique_nodes_in_global_ring()
+        self.token_ring
+            .replication_data
+            .unique_nodes_in_global_ring()
     }
 
     /// Gives a list of all known datacenters.
     pub fn datacenter_names(&self) -> &[String] {
-        self.datacenters.as_slice()
+        self.token_ring.datacenters.as_slice()
     }
 
     /// Gives a list of all nodes in a specified datacenter ring (which is created by filtering the
@@ -216,7 +297,8 @@ impl ReplicaLocator {
         &'a self,
         datacenter_name: &str,
     ) -> Option<&'a [Arc<Node>]> {
-        self.replication_data
+        self.token_ring
+            .replication_data
             .unique_nodes_in_datacenter_ring(datacenter_name)
     }
 
@@ -230,13 +312,15 @@ impl ReplicaLocator {
         }
 
         if let Some(precomputed_replicas) = self
+            .token_ring
             .precomputed_replicas
             .get_precomputed_simple_strategy_replicas(token, replication_factor)
         {
             precomputed_replicas.into()
         } else {
             ReplicasArray::from_iter(
-                self.replication_data
+                self.token_ring
+                    .replication_data
                     .simple_strategy_replicas(token, replication_factor),
             )
         }
@@ -253,6 +337,7 @@ impl ReplicaLocator {
         }
 
         if let Some(precomputed_replicas) = self
+            .token_ring
             .precomputed_replicas
             .get_precomputed_network_strategy_replicas(
                 token,
@@ -262,7 +347,7 @@ impl ReplicaLocator {
         {
             ReplicasArray::from(precomputed_replicas)
         } else {
-            ReplicasArray::from_iter(self.replication_data.nts_replicas_in_datacenter(
+            ReplicasArray::from_iter(self.token_ring.replication_data.nts_replicas_in_datacenter(
                 token,
                 datacenter,
                 datacenter_replication_factor,
@@ -404,7 +489,7 @@ impl<'a> ReplicaSet<'a> {
                     token,
                 } => {
                     let mut nodes_to_skip = index;
-                    for datacenter in locator.datacenters.iter() {
+                    for datacenter in locator.token_ring.datacenters.iter() {
                         let requested_repfactor =
                             *datacenter_repfactors.get(datacenter).unwrap_or(&0);
                         let unique_nodes_in_dc_count = locator
@@ -461,7 +546,7 @@ impl<'a> IntoIterator for ReplicaSet<'a> {
                 locator,
                 token,
             } => {
-                if let Some(datacenter) = locator.datacenters.first() {
+                if let Some(datacenter) = locator.token_ring.datacenters.first() {
                     let repfactor = *datacenter_repfactors.get(datacenter.as_str()).unwrap_or(&0);
                     ReplicaSetIteratorInner::ChainedNTS {
                         replicas: locator
@@ -572,11 +657,11 @@ impl<'a> Iterator for ReplicaSetIterator<'a> {
                 if let Some(replica) = replicas.get(*replicas_idx) {
                     *replicas_idx += 1;
                     Some(with_computed_shard(replica, self.token))
-                } else if *datacenter_idx + 1 < locator.datacenters.len() {
+                } else if *datacenter_idx + 1 < locator.token_ring.datacenters.len() {
                     *datacenter_idx += 1;
                     *replicas_idx = 0;
 
-                    let datacenter = &locator.datacenters[*datacenter_idx];
+                    let datacenter = &locator.token_ring.datacenters[*datacenter_idx];
                     let repfactor = *datacenter_repfactors.get(datacenter).unwrap_or(&0);
                     *replicas =
                         locator.get_network_strategy_replicas(*token, datacenter, repfactor);
@@ -721,7 +806,11 @@ impl<'a> Iterator for ReplicasOrderedNTSIterator<'a> {
                 token,
             } => {
                 // We're going to find the primary replica for the given token.
-                let nodes_on_ring = locator.replication_data.get_global_ring().ring_range(token);
+                let nodes_on_ring = locator
+                    .token_ring
+                    .replication_data
+                    .get_global_ring()
+                    .ring_range(token);
                 for node in nodes_on_ring {
                     // If this node's DC has some replicas in this NTS...
                     if let Some(dc) = &node.datacenter {
@@ -761,7 +850,11 @@ impl<'a> Iterator for ReplicasOrderedNTSIterator<'a> {
                 all_replicas.remove(picked);
 
                 let mut replicas_ordered = vec![];
-                let nodes_on_ring = locator.replication_data.get_global_ring().ring_range(token);
+                let nodes_on_ring = locator
+                    .token_ring
+                    .replication_data
+                    .get_global_ring()
+                    .ring_range(token);
                 for node in nodes_on_ring {
                     if all_replicas.is_empty() {
                         // All replicas were put in order.
@@ -851,6 +944,7 @@ impl<'a> IntoIterator for ReplicasOrdered<'a> {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
     use crate::{routing::Token, routing::locator::test::*, test_utils::setup_tracing};
 
     #[tokio::test]
@@ -956,4 +1050,38 @@ mod tests {
             vec![A],
         );
     }
+
+    #[tokio::test]
+    async fn test_token_ring_reuse() {
+        setup_tracing();
+        let metadata = mock_metadata_for_token_aware_tests();
+        let locator = create_locator(&metadata);
+        let strategies = || {
+            metadata
+                .keyspaces
+                .values()
+                .map(|ks| &ks.as_ref().unwrap().strategy)
+        };
+
+        // The order of the ring does not matter.
+        let mut ring: Vec<_> = locator.ring().iter().cloned().collect();
+        ring.reverse();
+        assert!(locator.token_ring_matches(&ring, strategies()));
+
+        let reused = locator.with_tablets(TabletsInfo::new());
+        assert!(Arc::ptr_eq(&locator.token_ring, &reused.token_ring));
+
+        // A new strategy needs new replica sets to be precomputed.
+        let new_strategy = Strategy::SimpleStrategy {
+            replication_factor: 7,
+        };
+        assert!(!locator.token_ring_matches(&ring, strategies().chain([&new_strategy])));
+
+        // A strategy that is no longer used does not need to stay precomputed.
+        assert!(!locator.token_ring_matches(&ring, strategies().skip(1)));
+
+        // Recreated nodes must not be referenced by the new locator.
+        let recreated_ring: Vec<_> = create_ring(&metadata).collect();
+        assert!(!locator.token_ring_matches(&recreated_ring, strategies()));
+    }
 }
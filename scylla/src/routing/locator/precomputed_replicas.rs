@@ -238,6 +238,8 @@ mod tests {
                 tables: HashMap::new(),
                 views: HashMap::new(),
                 user_defined_types: HashMap::new(),
+                functions: HashMap::new(),
+                aggregates: HashMap::new(),
             }),
         )]
         .iter()
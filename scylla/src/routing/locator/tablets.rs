@@ -1,3 +1,4 @@
+use crate::observability::log_targets;
 use bytes::Bytes;
 use scylla_cql::deserialize::value::{DeserializeValue, ListlikeIterator};
 use scylla_cql::deserialize::{DeserializationError, FrameSlice, TypeCheckError};
@@ -6,8 +7,8 @@ use thiserror::Error;
 use tracing::warn;
 use uuid::Uuid;
 
-use crate::cluster::Node;
-use crate::routing::{Shard, Token};
+use crate::cluster::{Node, NodeRef};
+use crate::routing::{Shard, Token, TokenRange};
 use crate::utils::safe_format::IteratorSafeFormatExt;
 
 use std::collections::{HashMap, HashSet};
@@ -211,9 +212,12 @@ impl PartialEq for TabletReplicas {
     }
 }
 
+/// A tablet - a contiguous range of tokens of a table, replicated on a set of shards.
+///
+/// Tablets are learned by the driver lazily, from the responses of the cluster.
 #[derive(Clone, Debug)]
 #[cfg_attr(test, derive(PartialEq, Eq))]
-pub(crate) struct Tablet {
+pub struct Tablet {
     /// First token belonging to the tablet, inclusive
     first_token: Token,
     /// Last token belonging to the tablet, inclusive
@@ -260,6 +264,45 @@ impl Tablet {
         (self.first_token, self.last_token)
     }
 
+    /// First token belonging to the tablet, inclusive.
+    pub fn first_token(&self) -> Token {
+        self.first_token
+    }
+
+    /// Last token belonging to the tablet, inclusive.
+    pub fn last_token(&self) -> Token {
+        self.last_token
+    }
+
+    /// Token range of the tablet. Unlike the tablet's first token,
+    /// the start of the range is exclusive.
+    pub fn token_range(&self) -> TokenRange {
+        // A token is never i64::MIN, so this doesn't overflow.
+        let start = match self.first_token.value() - 1 {
+            i64::MIN => Token::INVALID,
+            value => Token::new(value),
+        };
+        TokenRange::new(start, self.last_token)
+    }
+
+    /// Replicas of the tablet, together with the shards that own the tablet.
+    ///
+    /// Replicas that are not (yet) known to the driver are omitted.
+    pub fn replicas(&self) -> impl Iterator<Item = (NodeRef<'_>, Shard)> {
+        self.replicas.all.iter().map(|(node, shard)| (node, *shard))
+    }
+
+    /// Returns true if both tablets span the same tokens and have the same replicas.
+    fn same_as(&self, other: &Tablet) -> bool {
+        self.range() == other.range()
+            && self.replicas.all.len() == other.replicas.all.len()
+            && self.replicas.all.iter().zip(other.replicas.all.iter()).all(
+                |((node, shard), (other_node, other_shard))| {
+                    node.host_id == other_node.host_id && shard == other_shard
+                },
+            )
+    }
+
     // Returns `Ok(())` if after the operation Tablet replicas are fully resolved.
     // Return `Err(replicas)` if some replicas failed to resolve. `replicas` is a
     // list of Uuids that failed to resolve.
@@ -326,9 +369,12 @@ impl Tablet {
 /// 2. Add a new tablet.
 ///
 /// Adding new Tablet will first remove all tablets that overlap with the new tablet.
+///
+/// Only tablets that the driver has learned about are present, so they
+/// may not cover the whole token ring.
 #[derive(Clone, Debug)]
 #[cfg_attr(test, derive(PartialEq, Eq))]
-pub(crate) struct TableTablets {
+pub struct TableTablets {
     table_spec: TableSpec<'static>,
     tablet_list: Vec<Tablet>,
     /// In order to make typical tablet maintance faster
@@ -348,7 +394,28 @@ impl TableTablets {
         }
     }
 
-    fn tablet_for_token(&self, token: Token) -> Option<&Tablet> {
+    /// The table the tablets belong to.
+    pub fn table_spec(&self) -> &TableSpec<'static> {
+        &self.table_spec
+    }
+
+    /// Number of known tablets of the table.
+    pub fn len(&self) -> usize {
+        self.tablet_list.len()
+    }
+
+    /// Returns true if no tablets of the table are known.
+    pub fn is_empty(&self) -> bool {
+        self.tablet_list.is_empty()
+    }
+
+    /// Iterates over known tablets of the table, ordered by token.
+    pub fn iter(&self) -> impl Iterator<Item = &Tablet> {
+        self.tablet_list.iter()
+    }
+
+    /// Returns the known tablet that owns the given token.
+    pub fn tablet_for_token(&self, token: Token) -> Option<&Tablet> {
         let idx = self
             .tablet_list
             .partition_point(|tablet| tablet.last_token < token);
@@ -361,6 +428,16 @@ impl TableTablets {
             .map(|tablet| tablet.replicas.all.as_ref())
     }
 
+    /// Returns true if both tablet maps consist of the same tablets.
+    fn same_as(&self, other: &TableTablets) -> bool {
+        self.tablet_list.len() == other.tablet_list.len()
+            && self
+                .tablet_list
+                .iter()
+                .zip(other.tablet_list.iter())
+                .all(|(tablet, other_tablet)| tablet.same_as(other_tablet))
+    }
+
     pub(crate) fn dc_replicas_for_token(
         &self,
         token: Token,
@@ -415,7 +492,7 @@ impl TableTablets {
             self.tablet_list.retain_mut(|tablet| {
                 let r = tablet.re_resolve_replicas(|id: Uuid| all_current_nodes.get(&id).cloned());
                 if let Err(failed) = &r {
-                    warn!("Nodes ({}) listed as replicas for a tablet {{ks: {}, table: {}, range: [{}. {}]}} are not present in ClusterState.known_peers, \
+                    warn!(target: log_targets::METADATA, "Nodes ({}) listed as replicas for a tablet {{ks: {}, table: {}, range: [{}. {}]}} are not present in ClusterState.known_peers, \
                            despite topology refresh. Removing problematic tablet.",
                            failed.iter().safe_format(", "), self.table_spec.ks_name(), self.table_spec.table_name(), tablet.first_token.value(), tablet.last_token.value());
                 }
@@ -501,6 +578,30 @@ impl TabletsInfo {
         self.tablets.get(&query_key)
     }
 
+    /// Iterates over tablet maps of all tables with known tablets.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &TableTablets> {
+        self.tablets.values()
+    }
+
+    /// Returns the tables whose tablet maps differ between `self` and `other`.
+    pub(crate) fn changed_tables(&self, other: &TabletsInfo) -> Vec<TableSpec<'static>> {
+        let changed = self.tablets.iter().filter(|(table_spec, table_tablets)| {
+            other
+                .tablets_for_table(table_spec)
+                .is_none_or(|other_tablets| !table_tablets.same_as(other_tablets))
+        });
+        let removed = other
+            .tablets
+            .keys()
+            .filter(|table_spec| self.tablets_for_table(table_spec).is_none());
+
+        changed
+            .map(|(table_spec, _)| table_spec)
+            .chain(removed)
+            .cloned()
+            .collect()
+    }
+
     pub(crate) fn add_tablet(&mut self, table_spec: TableSpec<'static>, tablet: Tablet) {
         if tablet.failed.is_some() {
             self.has_unknown_replicas = true;
@@ -509,6 +610,7 @@ impl TabletsInfo {
             .entry(table_spec)
             .or_insert_with_key(|k| {
                 tracing::debug!(
+                    target: log_targets::METADATA,
                     "Found new tablets table: {}.{}",
                     k.ks_name(),
                     k.table_name()
@@ -611,11 +713,11 @@ mod tests {
     use uuid::Uuid;
 
     use crate::cluster::Node;
-    use crate::routing::Token;
     use crate::routing::locator::tablets::{
         CUSTOM_PAYLOAD_TABLETS_V1_KEY, RAW_TABLETS_CQL_TYPE, RawTablet, RawTabletReplicas,
         TabletParsingError,
     };
+    use crate::routing::{Token, TokenRange};
     use crate::test_utils::setup_tracing;
     use crate::value::CqlValue;
 
@@ -1410,4 +1512,88 @@ mod tests {
 
         assert_eq!(pre, expected_after);
     }
+
+    #[test]
+    fn tablet_token_range() {
+        let tablet = |first: i64, last: i64| Tablet {
+            first_token: Token::new(first),
+            last_token: Token::new(last),
+            replicas: Default::default(),
+            failed: None,
+        };
+
+        assert_eq!(
+            tablet(-200, 1000).token_range(),
+            TokenRange::new(Token::new(-201), Token::new(1000))
+        );
+        // The first tablet of the ring starts right after i64::MIN.
+        let first = tablet(i64::MIN + 1, 1000);
+        assert_eq!(
+            first.token_range(),
+            TokenRange::new(Token::INVALID, Token::new(1000))
+        );
+        assert!(!first.token_range().is_wrapping());
+    }
+
+    #[test]
+    fn changed_tables_test() {
+        const TABLE_1: TableSpec<'static> = TableSpec::borrowed("ks", "table_1");
+        const TABLE_2: TableSpec<'static> = TableSpec::borrowed("ks", "table_2");
+
+        let node1 = Arc::new(Node::new_for_test(
+            Some(Uuid::from_u128(1)),
+            None,
+            Some(DC1.to_owned()),
+            None,
+        ));
+        let node2 = Arc::new(Node::new_for_test(
+            Some(Uuid::from_u128(2)),
+            None,
+            Some(DC1.to_owned()),
+            None,
+        ));
+        let tablet = |first: i64, last: i64, replica: &Arc<Node>| Tablet {
+            first_token: Token::new(first),
+            last_token: Token::new(last),
+            replicas: TabletReplicas {
+                all: vec![(Arc::clone(replica), 0)],
+                per_dc: HashMap::new(),
+            },
+            failed: None,
+        };
+
+        let mut pre = TabletsInfo::new();
+        pre.add_tablet(TABLE_1, tablet(-100, 0, &node1));
+        pre.add_tablet(TABLE_2, tablet(-100, 0, &node1));
+
+        // An identical copy has no changes.
+        assert!(pre.changed_tables(&pre.clone()).is_empty());
+
+        // Tablet migrated to another node.
+        let mut migrated = pre.clone();
+        migrated.add_tablet(TABLE_1, tablet(-100, 0, &node2));
+        assert_eq!(migrated.changed_tables(&pre), [TABLE_1]);
+
+        // Tablet split.
+        let mut split = pre.clone();
+        split.add_tablet(TABLE_2, tablet(-100, -50, &node1));
+        split.add_tablet(TABLE_2, tablet(-49, 0, &node1));
+        assert_eq!(split.changed_tables(&pre), [TABLE_2]);
+
+        // Tablets of a dropped table were removed.
+        let mut dropped = pre.clone();
+        dropped.perform_maintenance(
+            &|spec| *spec != TABLE_1,
+            &HashSet::new(),
+            &HashMap::new(),
+            &HashMap::new(),
+        );
+        assert_eq!(dropped.changed_tables(&pre), [TABLE_1]);
+
+        let tables = dropped
+            .iter()
+            .map(|tablets| tablets.table_spec().clone())
+            .collect::<Vec<_>>();
+        assert_eq!(tables, [TABLE_2]);
+    }
 }
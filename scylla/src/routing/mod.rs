@@ -55,3 +55,114 @@ impl Token {
         self.value
     }
 }
+
+/// A range of tokens on the token ring, from `start` (exclusive) to `end` (inclusive).
+///
+/// A range whose `start` is not lower than its `end` wraps around the ring,
+/// i.e. it spans from `start` to the end of the ring, and from the beginning
+/// of the ring to `end`. In particular, a range with `start == end` covers the whole ring.
+///
+/// In CQL, tokens of a non-wrapping range can be selected with
+/// `token(pk) > start AND token(pk) <= end`.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct TokenRange {
+    start: Token,
+    end: Token,
+}
+
+impl TokenRange {
+    /// Creates a new token range spanning from `start` (exclusive) to `end` (inclusive).
+    #[inline]
+    pub fn new(start: Token, end: Token) -> Self {
+        Self { start, end }
+    }
+
+    /// The exclusive start of the range.
+    #[inline]
+    pub fn start(&self) -> Token {
+        self.start
+    }
+
+    /// The inclusive end of the range.
+    #[inline]
+    pub fn end(&self) -> Token {
+        self.end
+    }
+
+    /// Returns `true` if the range wraps around the end of the ring.
+    #[inline]
+    pub fn is_wrapping(&self) -> bool {
+        self.start >= self.end
+    }
+
+    /// Returns `true` if the given token belongs to the range.
+    pub fn contains(&self, token: Token) -> bool {
+        if self.is_wrapping() {
+            token > self.start || token <= self.end
+        } else {
+            token > self.start && token <= self.end
+        }
+    }
+
+    /// Splits the range into non-wrapping ranges.
+    ///
+    /// A non-wrapping range is returned as is. A wrapping range is split into
+    /// a range ending at the end of the ring (`i64::MAX`) and a range starting
+    /// at the beginning of the ring (`i64::MIN`, exclusive). Empty ranges are omitted.
+    pub fn split_wrapping(self) -> Vec<TokenRange> {
+        if !self.is_wrapping() {
+            return vec![self];
+        }
+
+        let ring_end = Token::new(i64::MAX);
+        let mut ranges = Vec::with_capacity(2);
+        if self.start < ring_end {
+            ranges.push(TokenRange::new(self.start, ring_end));
+        }
+        ranges.push(TokenRange::new(Token::INVALID, self.end));
+        ranges
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Token, TokenRange};
+
+    #[test]
+    fn test_token_range() {
+        let t = Token::new;
+
+        let range = TokenRange::new(t(-10), t(20));
+        assert!(!range.is_wrapping());
+        assert!(!range.contains(t(-10)));
+        assert!(range.contains(t(0)));
+        assert!(range.contains(t(20)));
+        assert!(!range.contains(t(21)));
+        assert_eq!(range.split_wrapping(), [range]);
+
+        let wrapping = TokenRange::new(t(20), t(-10));
+        assert!(wrapping.is_wrapping());
+        assert!(wrapping.contains(t(i64::MAX)));
+        assert!(wrapping.contains(t(-10)));
+        assert!(!wrapping.contains(t(0)));
+        assert!(!wrapping.contains(t(20)));
+        assert_eq!(
+            wrapping.split_wrapping(),
+            [
+                TokenRange::new(t(20), t(i64::MAX)),
+                TokenRange::new(Token::INVALID, t(-10))
+            ]
+        );
+
+        // A range with equal bounds covers the whole ring.
+        let full = TokenRange::new(t(5), t(5));
+        assert!(full.contains(t(5)) && full.contains(t(6)) && full.contains(t(4)));
+
+        // Empty parts are omitted.
+        let ending_at_ring_end = TokenRange::new(t(i64::MAX), t(0));
+        assert_eq!(
+            ending_at_ring_end.split_wrapping(),
+            [TokenRange::new(Token::INVALID, t(0))]
+        );
+    }
+}
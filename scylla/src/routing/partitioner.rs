@@ -1,17 +1,23 @@
 //! Partitioners are algorithms that can compute token for a given partition key,
 //! ultimately allowing optimised routing of requests (such that a request is routed
 //! to replicas, which are nodes and shards that really own the data the request concerns).
-//! Currently, two partitioners are supported:
+//! Currently, two partitioners are supported natively:
 //! - Murmur3Partitioner
 //!     - the default partitioner,
 //!     - modified for compatibility with Cassandra's buggy implementation.
 //! - CDCPartitioner
 //!     - the partitioner employed when using CDC (_Change Data Capture_).
+//!
+//! Other partitioners can be implemented by users and registered with
+//! [SessionBuilder::custom_partitioner](crate::client::session_builder::SessionBuilder::custom_partitioner).
 
 use bytes::Buf;
 use scylla_cql::frame::types::RawValue;
 use scylla_cql::serialize::row::SerializedValues;
+use std::collections::HashMap;
+use std::fmt::Debug;
 use std::num::Wrapping;
+use std::sync::Arc;
 
 use crate::routing::Token;
 use crate::statement::prepared::TokenCalculationError;
@@ -31,11 +37,19 @@ pub enum PartitionerName {
     // TODO(2.0): Rename variant to Cdc.
     #[expect(clippy::upper_case_acronyms)]
     CDC,
+
+    /// A partitioner registered in [CustomPartitioners].
+    Custom(CustomPartitioner),
 }
 
 impl PartitionerName {
-    pub(crate) fn from_str(name: &str) -> Option<Self> {
-        if name.ends_with("Murmur3Partitioner") {
+    /// Resolves a partitioner class name, as found in schema metadata.
+    ///
+    /// Partitioners registered in `custom` take precedence over the native ones.
+    pub(crate) fn from_str(name: &str, custom: &CustomPartitioners) -> Option<Self> {
+        if let Some(partitioner) = custom.get(name) {
+            Some(PartitionerName::Custom(partitioner))
+        } else if name.ends_with("Murmur3Partitioner") {
             Some(PartitionerName::Murmur3)
         } else if name.ends_with("CDCPartitioner") {
             Some(PartitionerName::CDC)
@@ -45,7 +59,6 @@ impl PartitionerName {
     }
 }
 
-impl sealed::Sealed for PartitionerName {}
 impl Partitioner for PartitionerName {
     type Hasher = PartitionerHasherAny;
 
@@ -55,6 +68,9 @@ impl Partitioner for PartitionerName {
                 PartitionerHasherAny::Murmur3(Murmur3Partitioner.build_hasher())
             }
             PartitionerName::CDC => PartitionerHasherAny::CDC(CDCPartitioner.build_hasher()),
+            PartitionerName::Custom(custom) => {
+                PartitionerHasherAny::Custom(custom.partitioner.build_boxed_hasher())
+            }
         }
     }
 }
@@ -73,14 +89,17 @@ pub enum PartitionerHasherAny {
     // TODO(2.0): Rename CDCPartitionerHasher to CdcPartitionerHasher
     #[expect(clippy::upper_case_acronyms)]
     CDC(CDCPartitionerHasher),
+
+    /// Hasher of a [CustomPartitioner].
+    Custom(Box<dyn PartitionerHasher + Send>),
 }
 
-impl sealed::Sealed for PartitionerHasherAny {}
 impl PartitionerHasher for PartitionerHasherAny {
     fn write(&mut self, pk_part: &[u8]) {
         match self {
             PartitionerHasherAny::Murmur3(h) => h.write(pk_part),
             PartitionerHasherAny::CDC(h) => h.write(pk_part),
+            PartitionerHasherAny::Custom(h) => h.write(pk_part),
         }
     }
 
@@ -88,23 +107,21 @@ impl PartitionerHasher for PartitionerHasherAny {
         match self {
             PartitionerHasherAny::Murmur3(h) => h.finish(),
             PartitionerHasherAny::CDC(h) => h.finish(),
+            PartitionerHasherAny::Custom(h) => h.finish(),
         }
     }
 }
 
-mod sealed {
-    // This is a sealed trait - its whole purpose is to be unnameable.
-    // This means we need to disable the check.
-    #[expect(unnameable_types)]
-    pub trait Sealed {}
-}
-
 /// A trait for creating instances of `PartitionHasher`, which ultimately compute the token.
 ///
 /// The Partitioners' design is based on std::hash design: `Partitioner`
 /// corresponds to `HasherBuilder`, and `PartitionerHasher` to `Hasher`.
 /// See their documentation for more details.
-pub trait Partitioner: sealed::Sealed {
+///
+/// Besides the natively supported partitioners, users can implement this trait
+/// and register the implementation in [CustomPartitioners], so that token computation
+/// and token-aware routing honor it for tables employing that partitioner.
+pub trait Partitioner {
     /// Stateful hasher that can compute a token based on partition key values.
     type Hasher: PartitionerHasher;
 
@@ -127,7 +144,7 @@ pub trait Partitioner: sealed::Sealed {
 /// Instances of this trait are created by a `Partitioner` and are stateful.
 /// At any point, one can call `finish()` and a `Token` will be computed
 /// based on values that has been fed so far.
-pub trait PartitionerHasher: sealed::Sealed {
+pub trait PartitionerHasher {
     /// Accepts a serialized partition key value and feeds it to the hasher.
     fn write(&mut self, pk_part: &[u8]);
 
@@ -135,10 +152,121 @@ pub trait PartitionerHasher: sealed::Sealed {
     fn finish(&self) -> Token;
 }
 
+/// Object-safe counterpart of [Partitioner], allowing to store partitioners
+/// of different types together.
+trait DynPartitioner: Send + Sync {
+    fn build_boxed_hasher(&self) -> Box<dyn PartitionerHasher + Send>;
+}
+
+impl<P> DynPartitioner for P
+where
+    P: Partitioner + Send + Sync,
+    P::Hasher: Send + 'static,
+{
+    fn build_boxed_hasher(&self) -> Box<dyn PartitionerHasher + Send> {
+        Box::new(self.build_hasher())
+    }
+}
+
+/// A user-provided partitioner, along with the class name it was registered for.
+///
+/// Two custom partitioners are considered equal if they were registered
+/// for the same class name.
+#[derive(Clone)]
+pub struct CustomPartitioner {
+    class_name: Arc<str>,
+    partitioner: Arc<dyn DynPartitioner>,
+}
+
+impl CustomPartitioner {
+    /// The partitioner class name this partitioner was registered for.
+    pub fn class_name(&self) -> &str {
+        &self.class_name
+    }
+}
+
+impl Debug for CustomPartitioner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CustomPartitioner")
+            .field("class_name", &self.class_name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for CustomPartitioner {
+    fn eq(&self, other: &Self) -> bool {
+        self.class_name == other.class_name
+    }
+}
+
+impl Eq for CustomPartitioner {}
+
+/// User-provided [Partitioner] implementations, keyed by partitioner class names.
+///
+/// A partitioner registered for a class name is used for tables whose partitioner
+/// (as found in schema metadata) is either exactly that name, or a fully qualified
+/// name ending with `.` followed by that name. For example, a partitioner registered
+/// for `"RandomPartitioner"` is used for tables employing
+/// `org.apache.cassandra.dht.RandomPartitioner`.
+///
+/// Registered partitioners take precedence over the natively supported ones.
+#[derive(Clone, Default)]
+pub struct CustomPartitioners {
+    partitioners: Arc<HashMap<Arc<str>, CustomPartitioner>>,
+}
+
+impl CustomPartitioners {
+    /// Creates an empty set of custom partitioners.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `partitioner` for tables employing the partitioner `class_name`.
+    /// Replaces the partitioner previously registered for that name, if any.
+    pub fn register<P>(&mut self, class_name: impl Into<Arc<str>>, partitioner: P)
+    where
+        P: Partitioner + Send + Sync + 'static,
+        P::Hasher: Send + 'static,
+    {
+        let class_name = class_name.into();
+        Arc::make_mut(&mut self.partitioners).insert(
+            class_name.clone(),
+            CustomPartitioner {
+                class_name,
+                partitioner: Arc::new(partitioner),
+            },
+        );
+    }
+
+    /// Returns `true` if no partitioners are registered.
+    pub fn is_empty(&self) -> bool {
+        self.partitioners.is_empty()
+    }
+
+    /// Looks up a partitioner for the given (possibly fully qualified) class name.
+    fn get(&self, name: &str) -> Option<CustomPartitioner> {
+        if self.partitioners.is_empty() {
+            return None;
+        }
+        self.partitioners
+            .get(name)
+            .or_else(|| {
+                let (_, simple_name) = name.rsplit_once('.')?;
+                self.partitioners.get(simple_name)
+            })
+            .cloned()
+    }
+}
+
+impl Debug for CustomPartitioners {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.partitioners.keys()).finish()
+    }
+}
+
 /// Default partitioner used by ScyllaDB and Cassandra.
 pub struct Murmur3Partitioner;
 
-impl sealed::Sealed for Murmur3Partitioner {}
 impl Partitioner for Murmur3Partitioner {
     type Hasher = Murmur3PartitionerHasher;
 
@@ -209,8 +337,6 @@ impl Murmur3PartitionerHasher {
     }
 }
 
-impl sealed::Sealed for Murmur3PartitionerHasher {}
-
 // The implemented Murmur3 algorithm is roughly as follows:
 // 1. while there are at least 16 bytes given:
 //      consume 16 bytes by parsing them into i64s, then
@@ -329,7 +455,6 @@ pub struct CDCPartitionerHasher {
     state: CDCPartitionerHasherState,
 }
 
-impl sealed::Sealed for CDCPartitioner {}
 impl Partitioner for CDCPartitioner {
     type Hasher = CDCPartitionerHasher;
 
@@ -347,7 +472,6 @@ impl CDCPartitionerHasher {
     const BUF_CAPACITY: usize = 8;
 }
 
-impl sealed::Sealed for CDCPartitionerHasher {}
 impl PartitionerHasher for CDCPartitionerHasher {
     fn write(&mut self, pk_part: &[u8]) {
         match &mut self.state {
@@ -429,7 +553,11 @@ mod tests {
 
     use crate::test_utils::setup_tracing;
 
-    use super::{CDCPartitioner, Murmur3Partitioner, Partitioner, PartitionerHasher};
+    use super::{
+        CDCPartitioner, CustomPartitioners, Murmur3Partitioner, Partitioner, PartitionerHasher,
+        PartitionerName,
+    };
+    use crate::routing::Token;
 
     fn assert_correct_murmur3_hash(pk: &'static str, expected_hash: i64) {
         let hash = Murmur3Partitioner.hash_one(pk.as_bytes()).value();
@@ -525,4 +653,52 @@ mod tests {
             assert_correct_cdc_hash(s.0, s.1);
         }
     }
+
+    struct ConstPartitioner(i64);
+    struct ConstPartitionerHasher(i64);
+
+    impl Partitioner for ConstPartitioner {
+        type Hasher = ConstPartitionerHasher;
+
+        fn build_hasher(&self) -> Self::Hasher {
+            ConstPartitionerHasher(self.0)
+        }
+    }
+
+    impl PartitionerHasher for ConstPartitionerHasher {
+        fn write(&mut self, _pk_part: &[u8]) {}
+
+        fn finish(&self) -> Token {
+            Token::new(self.0)
+        }
+    }
+
+    #[test]
+    fn test_custom_partitioners() {
+        setup_tracing();
+        let mut custom = CustomPartitioners::new();
+        custom.register("RandomPartitioner", ConstPartitioner(1));
+        custom.register("com.scylladb.dht.CDCPartitioner", ConstPartitioner(2));
+
+        let token_of = |name: &str| {
+            PartitionerName::from_str(name, &custom)
+                .unwrap()
+                .hash_one(b"key")
+                .value()
+        };
+
+        // Both simple and fully qualified names match.
+        assert_eq!(token_of("RandomPartitioner"), 1);
+        assert_eq!(token_of("org.apache.cassandra.dht.RandomPartitioner"), 1);
+        // Registered partitioners take precedence over native ones.
+        assert_eq!(token_of("com.scylladb.dht.CDCPartitioner"), 2);
+        assert_eq!(
+            PartitionerName::from_str("org.apache.cassandra.dht.Murmur3Partitioner", &custom),
+            Some(PartitionerName::Murmur3)
+        );
+        assert_eq!(
+            PartitionerName::from_str("org.apache.cassandra.dht.ByteOrderedPartitioner", &custom),
+            None
+        );
+    }
 }
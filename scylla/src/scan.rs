@@ -0,0 +1,742 @@
+//! Parallel full table scans.
+//!
+//! A full table scan reads every row of a table, e.g. to export data or to run analytics.
+//! [TableScan] splits the token ring into ranges - following the tablets of the table
+//! or, for tables using vnodes, the token ring of the cluster - and queries the ranges
+//! in parallel, each one with its own paging. Rows from all ranges are merged into a single
+//! stream of typed rows, [TableScanStream].
+//!
+//! The progress of a scan can be saved at any point as a [ScanCheckpoint],
+//! and the scan can be resumed from it later, e.g. after a failure.
+//!
+//! # Example
+//! ```rust
+//! # use scylla::client::session::Session;
+//! # use std::error::Error;
+//! # async fn check_only_compiles(session: &Session) -> Result<(), Box<dyn Error>> {
+//! use futures::TryStreamExt;
+//! use scylla::scan::TableScan;
+//!
+//! let mut rows_stream = TableScan::new("ks", "t")
+//!     .columns(["a", "b"])
+//!     .rows_stream::<(i32, String)>(session)
+//!     .await?;
+//!
+//! while let Some((a, b)) = rows_stream.try_next().await? {
+//!     println!("a, b: {}, {}", a, b);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::{BTreeMap, VecDeque};
+use std::num::NonZeroUsize;
+use std::ops::ControlFlow;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, ready};
+
+use futures::future::BoxFuture;
+use futures::stream::FuturesUnordered;
+use futures::{FutureExt as _, Stream, StreamExt as _};
+use itertools::Itertools as _;
+use scylla_cql::deserialize::{DeserializationError, TypeCheckError};
+use scylla_cql::frame::frame_errors::ResultMetadataAndRowsCountParseError;
+use scylla_cql::frame::response::result::TableSpec;
+use thiserror::Error;
+
+use crate::client::session::Session;
+use crate::cluster::ClusterState;
+use crate::deserialize::DeserializeOwnedRow;
+use crate::deserialize::row::DeserializeRow;
+use crate::errors::{ExecutionError, PrepareError};
+use crate::response::PagingState;
+use crate::response::query_result::{IntoRowsResultError, RowsError};
+use crate::routing::{Token, TokenRange};
+use crate::statement::Consistency;
+use crate::statement::prepared::PreparedStatement;
+
+const DEFAULT_CONCURRENCY: NonZeroUsize = NonZeroUsize::new(16).unwrap();
+
+/// Configures a parallel full table scan.
+///
+/// The scan selects rows of a table range by range, using statements of the form
+/// `SELECT ... FROM ks.t WHERE token(pk) > ? AND token(pk) <= ?`. At most
+/// [`concurrency`](TableScan::concurrency) ranges are scanned at the same time.
+///
+/// Rows of a single range are returned in token order, but rows of different ranges
+/// are interleaved in the order their pages arrive.
+#[derive(Clone, Debug)]
+pub struct TableScan {
+    keyspace: String,
+    table: String,
+    columns: Vec<String>,
+    concurrency: NonZeroUsize,
+    page_size: Option<i32>,
+    consistency: Option<Consistency>,
+    checkpoint: Option<ScanCheckpoint>,
+}
+
+impl TableScan {
+    /// Creates a scan of all columns of the given table.
+    ///
+    /// Keyspace and table names are case-sensitive, as in cluster metadata.
+    pub fn new(keyspace: impl Into<String>, table: impl Into<String>) -> Self {
+        Self {
+            keyspace: keyspace.into(),
+            table: table.into(),
+            columns: Vec::new(),
+            concurrency: DEFAULT_CONCURRENCY,
+            page_size: None,
+            consistency: None,
+            checkpoint: None,
+        }
+    }
+
+    /// Selects only the given columns, in the given order.
+    ///
+    /// Column names are case-sensitive. By default, all columns are selected.
+    pub fn columns(mut self, columns: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.columns = columns.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets the maximal number of token ranges scanned at the same time.
+    ///
+    /// Each range being scanned holds at most two pages in memory: the one being
+    /// returned from the stream and the one being fetched. The default is 16.
+    pub fn concurrency(mut self, concurrency: NonZeroUsize) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+
+    /// Sets the page size of the range queries.
+    ///
+    /// Panics if given number is nonpositive.
+    pub fn page_size(mut self, page_size: i32) -> Self {
+        assert!(page_size > 0, "TableScan::page_size: nonpositive page size");
+        self.page_size = Some(page_size);
+        self
+    }
+
+    /// Sets the consistency of the range queries.
+    ///
+    /// By default, the consistency of the session's default execution profile is used.
+    pub fn consistency(mut self, consistency: Consistency) -> Self {
+        self.consistency = Some(consistency);
+        self
+    }
+
+    /// Resumes a scan from a checkpoint taken with [TableScanStream::checkpoint].
+    ///
+    /// Only the ranges that were not fully scanned are scanned again,
+    /// and rows that were already returned are skipped.
+    /// The checkpoint must come from a scan of the same table with the same columns.
+    pub fn resume_from(mut self, checkpoint: ScanCheckpoint) -> Self {
+        self.checkpoint = Some(checkpoint);
+        self
+    }
+
+    /// Prepares the range query and starts the scan.
+    ///
+    /// Fails if the table is not present in cluster metadata, the statement
+    /// can't be prepared, or the selected columns can't be deserialized to `RowT`.
+    pub async fn rows_stream<'a, RowT>(
+        self,
+        session: &'a Session,
+    ) -> Result<TableScanStream<'a, RowT>, TableScanError>
+    where
+        RowT: for<'frame, 'metadata> DeserializeRow<'frame, 'metadata> + Send + 'a,
+    {
+        let cluster_state = session.get_cluster_state();
+        let table = cluster_state
+            .get_keyspace(&self.keyspace)
+            .and_then(|keyspace| keyspace.tables.get(&self.table))
+            .ok_or_else(|| TableScanError::UnknownTable {
+                keyspace: self.keyspace.clone(),
+                table: self.table.clone(),
+            })?;
+
+        let query = scan_query(
+            &self.keyspace,
+            &self.table,
+            &table.partition_key,
+            &self.columns,
+        );
+        let mut statement = session.prepare(query).await?;
+        statement.set_is_idempotent(true);
+        if let Some(page_size) = self.page_size {
+            statement.set_page_size(page_size);
+        }
+        if let Some(consistency) = self.consistency {
+            statement.set_consistency(consistency);
+        }
+        RowT::type_check(
+            statement
+                .get_current_result_set_col_specs()
+                .get()
+                .as_slice(),
+        )?;
+
+        let ranges = match self.checkpoint {
+            Some(checkpoint) => checkpoint.ranges,
+            None => split_ring(&cluster_state, &self.keyspace, &self.table)
+                .into_iter()
+                .map(|range| RangeCheckpoint::new(range, PagingState::start(), 0))
+                .collect(),
+        };
+
+        Ok(TableScanStream {
+            session,
+            statement: Arc::new(statement),
+            progress: ScanProgress::new(ranges, self.concurrency),
+            in_flight: FuturesUnordered::new(),
+            terminated: false,
+        })
+    }
+}
+
+/// Progress of a scan of a single token range.
+///
+/// The scan of the range is resumed by fetching rows starting at the paging state,
+/// and skipping the given number of rows which were already returned.
+#[derive(Clone, Debug)]
+pub struct RangeCheckpoint {
+    range: TokenRange,
+    paging_state: PagingState,
+    rows_to_skip: usize,
+}
+
+impl RangeCheckpoint {
+    /// Creates a checkpoint of a token range from its parts.
+    pub fn new(range: TokenRange, paging_state: PagingState, rows_to_skip: usize) -> Self {
+        Self {
+            range,
+            paging_state,
+            rows_to_skip,
+        }
+    }
+
+    /// The scanned token range. It never wraps around the ring.
+    pub fn range(&self) -> TokenRange {
+        self.range
+    }
+
+    /// Paging state at which fetching of the range is resumed.
+    pub fn paging_state(&self) -> &PagingState {
+        &self.paging_state
+    }
+
+    /// Number of rows, starting at the paging state, that were already returned.
+    pub fn rows_to_skip(&self) -> usize {
+        self.rows_to_skip
+    }
+}
+
+/// Progress of a full table scan - the token ranges that are not fully scanned yet.
+///
+/// The checkpoint can be persisted (using the accessors of [RangeCheckpoint]) and
+/// the scan can be resumed from it with [TableScan::resume_from].
+#[derive(Clone, Debug)]
+pub struct ScanCheckpoint {
+    ranges: Vec<RangeCheckpoint>,
+}
+
+impl ScanCheckpoint {
+    /// Creates a checkpoint from progress of its token ranges.
+    pub fn new(ranges: impl IntoIterator<Item = RangeCheckpoint>) -> Self {
+        Self {
+            ranges: ranges.into_iter().collect(),
+        }
+    }
+
+    /// Token ranges that are not fully scanned yet.
+    pub fn ranges(&self) -> &[RangeCheckpoint] {
+        &self.ranges
+    }
+
+    /// Returns true if the whole table was scanned.
+    pub fn is_finished(&self) -> bool {
+        self.ranges.is_empty()
+    }
+}
+
+/// An error returned by [TableScan].
+#[derive(Error, Debug, Clone)]
+#[non_exhaustive]
+pub enum TableScanError {
+    /// The table is not present in cluster metadata.
+    #[error("Can't find metadata for the scanned table ({keyspace}.{table})")]
+    UnknownTable {
+        /// Keyspace of the scanned table.
+        keyspace: String,
+        /// Name of the scanned table.
+        table: String,
+    },
+
+    /// Failed to prepare the range query.
+    #[error("Failed to prepare the range query: {0}")]
+    PrepareError(#[from] PrepareError),
+
+    /// Failed to fetch a page of a token range.
+    #[error("Failed to fetch a page of a token range: {0}")]
+    ExecutionError(#[from] ExecutionError),
+
+    /// The range query returned a result that is not of Rows kind.
+    #[error("The range query returned a result that is not of Rows kind")]
+    ResultNotRows,
+
+    /// Failed to deserialize result metadata associated with a page.
+    #[error("Failed to deserialize result metadata associated with a page: {0}")]
+    ResultMetadataParseError(#[from] ResultMetadataAndRowsCountParseError),
+
+    /// Failed to type check the selected columns or a received page.
+    #[error("Failed to type check the selected columns: {0}")]
+    TypeCheckError(#[from] TypeCheckError),
+
+    /// An error occurred during row deserialization.
+    #[error("Row deserialization error: {0}")]
+    RowDeserializationError(#[from] DeserializationError),
+}
+
+/// Stream of rows of a table scan, returned by [TableScan::rows_stream].
+///
+/// The stream ends after the first error. Its [checkpoint](TableScanStream::checkpoint)
+/// still describes the rows that were returned, so the scan can be resumed from it.
+pub struct TableScanStream<'a, RowT> {
+    session: &'a Session,
+    statement: Arc<PreparedStatement>,
+    progress: ScanProgress<RowT>,
+    in_flight: FuturesUnordered<PageFetch<'a, RowT>>,
+    terminated: bool,
+}
+
+// Manual implementation not to depend on RowT implementing Debug.
+impl<RowT> std::fmt::Debug for TableScanStream<'_, RowT> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TableScanStream")
+            .field("statement", &self.statement.get_statement())
+            .field("checkpoint", &self.progress.checkpoint())
+            .field("in_flight", &self.in_flight.len())
+            .field("terminated", &self.terminated)
+            .finish()
+    }
+}
+
+impl<RowT> Unpin for TableScanStream<'_, RowT> {}
+
+impl<'a, RowT> TableScanStream<'a, RowT>
+where
+    RowT: for<'frame, 'metadata> DeserializeRow<'frame, 'metadata> + Send + 'a,
+{
+    fn fetch(&mut self, request: PageRequest) {
+        let session = self.session;
+        let statement = Arc::clone(&self.statement);
+        self.in_flight.push(
+            async move {
+                let page = fetch_page(session, &statement, request.range, request.paging_state);
+                (request.range_id, page.await)
+            }
+            .boxed(),
+        );
+    }
+}
+
+impl<RowT> TableScanStream<'_, RowT> {
+    /// Returns the progress of the scan, consistent with the rows returned so far.
+    pub fn checkpoint(&self) -> ScanCheckpoint {
+        self.progress.checkpoint()
+    }
+}
+
+impl<'a, RowT> Stream for TableScanStream<'a, RowT>
+where
+    RowT: for<'frame, 'metadata> DeserializeRow<'frame, 'metadata> + Send + 'a,
+{
+    type Item = Result<RowT, TableScanError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        if this.terminated {
+            return Poll::Ready(None);
+        }
+
+        loop {
+            if let Some(row) = this.progress.next_row() {
+                return Poll::Ready(Some(Ok(row)));
+            }
+
+            for request in this.progress.start_ranges() {
+                this.fetch(request);
+            }
+
+            // Every range that is being scanned and has no buffered rows
+            // has a page fetch in flight, so no fetches mean the scan is done.
+            match ready!(this.in_flight.poll_next_unpin(cx)) {
+                Some((range_id, Ok(page))) => {
+                    if let Some(request) = this.progress.accept_page(range_id, page) {
+                        this.fetch(request);
+                    }
+                }
+                Some((_, Err(err))) => {
+                    this.terminated = true;
+                    this.in_flight.clear();
+                    return Poll::Ready(Some(Err(err)));
+                }
+                None => {
+                    this.terminated = true;
+                    return Poll::Ready(None);
+                }
+            }
+        }
+    }
+}
+
+/// Page fetch in flight, resolving to the id of the fetched range and the page.
+type PageFetch<'a, RowT> = BoxFuture<'a, (u64, Result<FetchedPage<RowT>, TableScanError>)>;
+
+struct FetchedPage<RowT> {
+    rows: Vec<RowT>,
+    next_paging_state: Option<PagingState>,
+}
+
+#[derive(Debug)]
+struct PageRequest {
+    range_id: u64,
+    range: TokenRange,
+    paging_state: PagingState,
+}
+
+async fn fetch_page<RowT: DeserializeOwnedRow>(
+    session: &Session,
+    statement: &PreparedStatement,
+    range: TokenRange,
+    paging_state: PagingState,
+) -> Result<FetchedPage<RowT>, TableScanError> {
+    let bounds = (range.start().value(), range.end().value());
+    let (result, paging_state_response) = session
+        .execute_single_page(statement, bounds, paging_state)
+        .await?;
+
+    let rows_result = result.into_rows_result().map_err(|err| match err {
+        IntoRowsResultError::ResultNotRows(_) => TableScanError::ResultNotRows,
+        IntoRowsResultError::ResultMetadataLazyDeserializationError(err) => err.into(),
+    })?;
+    let rows = rows_result
+        .rows::<RowT>()
+        .map_err(|RowsError::TypeCheckFailed(err)| err)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let next_paging_state = match paging_state_response.into_paging_control_flow() {
+        ControlFlow::Break(()) => None,
+        ControlFlow::Continue(paging_state) => Some(paging_state),
+    };
+
+    Ok(FetchedPage {
+        rows,
+        next_paging_state,
+    })
+}
+
+/// Page whose rows are being returned from the stream.
+struct CurrentPage<RowT> {
+    range_id: u64,
+    rows: std::vec::IntoIter<RowT>,
+    next_paging_state: Option<PagingState>,
+    /// Rows to skip at the beginning of the next page,
+    /// if this page was shorter than the number of rows to skip.
+    rows_to_skip_after: usize,
+}
+
+/// Bookkeeping of a scan, independent of fetching the pages.
+///
+/// Positions of the ranges being scanned always reflect the rows returned
+/// from [ScanProgress::next_row], so that a checkpoint can be taken at any moment.
+struct ScanProgress<RowT> {
+    concurrency: usize,
+    pending: VecDeque<RangeCheckpoint>,
+    active: BTreeMap<u64, RangeCheckpoint>,
+    next_range_id: u64,
+    current: Option<CurrentPage<RowT>>,
+}
+
+impl<RowT> ScanProgress<RowT> {
+    fn new(ranges: Vec<RangeCheckpoint>, concurrency: NonZeroUsize) -> Self {
+        Self {
+            concurrency: concurrency.get(),
+            pending: ranges.into(),
+            active: BTreeMap::new(),
+            next_range_id: 0,
+            current: None,
+        }
+    }
+
+    /// Starts scanning pending ranges, up to the concurrency limit.
+    /// Returns the requests for their first pages.
+    fn start_ranges(&mut self) -> Vec<PageRequest> {
+        let mut requests = Vec::new();
+        while self.active.len() < self.concurrency {
+            let Some(range) = self.pending.pop_front() else {
+                break;
+            };
+            let range_id = self.next_range_id;
+            self.next_range_id += 1;
+            requests.push(PageRequest {
+                range_id,
+                range: range.range,
+                paging_state: range.paging_state.clone(),
+            });
+            self.active.insert(range_id, range);
+        }
+        requests
+    }
+
+    /// Makes the fetched page the current one.
+    /// Returns the request for the next page of the same range, if there is one.
+    fn accept_page(&mut self, range_id: u64, page: FetchedPage<RowT>) -> Option<PageRequest> {
+        debug_assert!(self.current.is_none());
+        let position = self
+            .active
+            .get(&range_id)
+            .expect("fetched a page of a range that is not being scanned");
+
+        let rows_to_skip = position.rows_to_skip;
+        let mut rows = page.rows.into_iter();
+        let skipped = rows.by_ref().take(rows_to_skip).count();
+
+        let request = page
+            .next_paging_state
+            .as_ref()
+            .map(|paging_state| PageRequest {
+                range_id,
+                range: position.range,
+                paging_state: paging_state.clone(),
+            });
+
+        self.current = Some(CurrentPage {
+            range_id,
+            rows,
+            next_paging_state: page.next_paging_state,
+            rows_to_skip_after: rows_to_skip - skipped,
+        });
+        request
+    }
+
+    /// Returns the next row of the current page, updating the position of its range.
+    fn next_row(&mut self) -> Option<RowT> {
+        let current = self.current.as_mut()?;
+        let row = current.rows.next();
+        if row.is_some() {
+            if let Some(position) = self.active.get_mut(&current.range_id) {
+                position.rows_to_skip += 1;
+            }
+        }
+
+        if current.rows.len() == 0 {
+            let finished = self.current.take().unwrap();
+            match finished.next_paging_state {
+                Some(paging_state) => {
+                    if let Some(position) = self.active.get_mut(&finished.range_id) {
+                        position.paging_state = paging_state;
+                        position.rows_to_skip = finished.rows_to_skip_after;
+                    }
+                }
+                None => {
+                    self.active.remove(&finished.range_id);
+                }
+            }
+        }
+
+        row
+    }
+
+    fn checkpoint(&self) -> ScanCheckpoint {
+        ScanCheckpoint::new(self.active.values().chain(self.pending.iter()).cloned())
+    }
+}
+
+/// Splits the whole ring into non-wrapping token ranges.
+///
+/// If the driver knows tablets of the table, the ranges follow tablet boundaries.
+/// Otherwise, they follow the token ring of the cluster.
+fn split_ring(cluster_state: &ClusterState, keyspace: &str, table: &str) -> Vec<TokenRange> {
+    let locator = cluster_state.replica_locator();
+    let table_spec = TableSpec::borrowed(keyspace, table);
+
+    match locator.tablets.tablets_for_table(&table_spec) {
+        Some(tablets) => ranges_from_split_points(tablets.iter().flat_map(|tablet| {
+            let range = tablet.token_range();
+            [range.start(), range.end()]
+        })),
+        None => ranges_from_split_points(locator.ring().iter().map(|(token, _node)| *token)),
+    }
+}
+
+/// Returns non-wrapping ranges covering the whole ring, ending at consecutive split points.
+fn ranges_from_split_points(split_points: impl Iterator<Item = Token>) -> Vec<TokenRange> {
+    let mut points = split_points
+        .chain([Token::INVALID, Token::new(i64::MAX)])
+        .collect::<Vec<_>>();
+    points.sort_unstable();
+    points.dedup();
+
+    points
+        .into_iter()
+        .tuple_windows()
+        .map(|(start, end)| TokenRange::new(start, end))
+        .collect()
+}
+
+fn scan_query(keyspace: &str, table: &str, partition_key: &[String], columns: &[String]) -> String {
+    let selector = if columns.is_empty() {
+        "*".to_owned()
+    } else {
+        columns.iter().map(|c| quote_identifier(c)).join(", ")
+    };
+    let pk = partition_key.iter().map(|c| quote_identifier(c)).join(", ");
+
+    format!(
+        "SELECT {} FROM {}.{} WHERE token({}) > ? AND token({}) <= ?",
+        selector,
+        quote_identifier(keyspace),
+        quote_identifier(table),
+        pk,
+        pk,
+    )
+}
+
+fn quote_identifier(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use super::{FetchedPage, RangeCheckpoint, ScanProgress, ranges_from_split_points, scan_query};
+    use crate::response::PagingState;
+    use crate::routing::{Token, TokenRange};
+
+    #[test]
+    fn test_ranges_from_split_points() {
+        let t = Token::new;
+
+        assert_eq!(
+            ranges_from_split_points(std::iter::empty()),
+            [TokenRange::new(Token::INVALID, t(i64::MAX))]
+        );
+        assert_eq!(
+            ranges_from_split_points([t(100), t(-50), t(100), t(i64::MAX)].into_iter()),
+            [
+                TokenRange::new(Token::INVALID, t(-50)),
+                TokenRange::new(t(-50), t(100)),
+                TokenRange::new(t(100), t(i64::MAX)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scan_query() {
+        assert_eq!(
+            scan_query("ks", "t", &["a".into(), "B".into()], &[]),
+            r#"SELECT * FROM "ks"."t" WHERE token("a", "B") > ? AND token("a", "B") <= ?"#
+        );
+        assert_eq!(
+            scan_query("ks", "t", &["a".into()], &["a".into(), r#"we"ird"#.into()]),
+            r#"SELECT "a", "we""ird" FROM "ks"."t" WHERE token("a") > ? AND token("a") <= ?"#
+        );
+    }
+
+    fn page(rows: &[i32], next: Option<&[u8]>) -> FetchedPage<i32> {
+        FetchedPage {
+            rows: rows.to_vec(),
+            next_paging_state: next.map(PagingState::new_from_raw_bytes),
+        }
+    }
+
+    fn range(end: i64) -> RangeCheckpoint {
+        RangeCheckpoint::new(
+            TokenRange::new(Token::new(end - 10), Token::new(end)),
+            PagingState::start(),
+            0,
+        )
+    }
+
+    fn positions(progress: &ScanProgress<i32>) -> Vec<(i64, Option<Vec<u8>>, usize)> {
+        progress
+            .checkpoint()
+            .ranges()
+            .iter()
+            .map(|r| {
+                (
+                    r.range().end().value(),
+                    r.paging_state().as_bytes_slice().map(|s| s.to_vec()),
+                    r.rows_to_skip(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_scan_progress() {
+        let mut progress = ScanProgress::new(
+            vec![range(10), range(20), range(30)],
+            NonZeroUsize::new(2).unwrap(),
+        );
+
+        let requests = progress.start_ranges();
+        assert_eq!(requests.len(), 2);
+        assert!(progress.start_ranges().is_empty());
+
+        // Second range responds first, with a page that has a continuation.
+        let next = progress
+            .accept_page(requests[1].range_id, page(&[1, 2], Some(b"p1")))
+            .unwrap();
+        assert_eq!(next.range_id, requests[1].range_id);
+        assert_eq!(progress.next_row(), Some(1));
+        assert_eq!(
+            positions(&progress),
+            [(10, None, 0), (20, None, 1), (30, None, 0)]
+        );
+        assert_eq!(progress.next_row(), Some(2));
+        assert_eq!(
+            positions(&progress),
+            [(10, None, 0), (20, Some(b"p1".to_vec()), 0), (30, None, 0)]
+        );
+        assert_eq!(progress.next_row(), None);
+
+        // First range is done after a single page, so the third range can start.
+        assert!(
+            progress
+                .accept_page(requests[0].range_id, page(&[3], None))
+                .is_none()
+        );
+        assert_eq!(progress.next_row(), Some(3));
+        assert_eq!(
+            positions(&progress),
+            [(20, Some(b"p1".to_vec()), 0), (30, None, 0)]
+        );
+        let requests = progress.start_ranges();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].range.end(), Token::new(30));
+    }
+
+    #[test]
+    fn test_scan_progress_resumes_mid_page() {
+        let mut resumed = range(10);
+        resumed.rows_to_skip = 3;
+        let mut progress = ScanProgress::new(vec![resumed], NonZeroUsize::new(1).unwrap());
+        let requests = progress.start_ranges();
+
+        // A page shorter than the number of rows to skip is skipped entirely.
+        progress.accept_page(requests[0].range_id, page(&[1, 2], Some(b"p1")));
+        assert_eq!(progress.next_row(), None);
+        assert_eq!(positions(&progress), [(10, Some(b"p1".to_vec()), 1)]);
+
+        progress.accept_page(requests[0].range_id, page(&[3, 4, 5], None));
+        assert_eq!(progress.next_row(), Some(4));
+        assert_eq!(positions(&progress), [(10, Some(b"p1".to_vec()), 2)]);
+        assert_eq!(progress.next_row(), Some(5));
+        assert!(progress.checkpoint().is_finished());
+    }
+}
@@ -12,8 +12,8 @@ use crate::policies::retry::RetryPolicy;
 use crate::statement::prepared::PreparedStatement;
 use crate::statement::unprepared::Statement;
 
-use super::StatementConfig;
 use super::{Consistency, SerialConsistency};
+use super::{StatementConfig, StatementTags};
 pub use crate::frame::request::batch::BatchType;
 
 /// CQL batch statement.
@@ -212,6 +212,27 @@ impl Batch {
         self.config.history_listener.take()
     }
 
+    /// Sets the tag `key` to `value`, returning the previous value, if any.
+    /// See [StatementTags] for how tags are used.
+    pub fn set_tag(&mut self, key: impl Into<String>, value: impl Into<String>) -> Option<String> {
+        self.config.tags.insert(key, value)
+    }
+
+    /// Removes the tag `key` set by [`Batch::set_tag`], returning its value, if it was set.
+    pub fn remove_tag(&mut self, key: &str) -> Option<String> {
+        self.config.tags.remove(key)
+    }
+
+    /// Replaces all tags of the statement.
+    pub fn set_tags(&mut self, tags: StatementTags) {
+        self.config.tags = tags;
+    }
+
+    /// Returns the tags of the statement.
+    pub fn get_tags(&self) -> &StatementTags {
+        &self.config.tags
+    }
+
     /// Associates the batch with execution profile referred by the provided handle.
     /// Handle may be later remapped to another profile, and batch will reflect those changes.
     pub fn set_execution_profile_handle(&mut self, profile_handle: Option<ExecutionProfileHandle>) {
@@ -5,6 +5,8 @@
 //! - PreparedStatement,
 //! - Batch.
 
+use std::collections::BTreeMap;
+use std::fmt::Display;
 use std::{sync::Arc, time::Duration};
 
 use thiserror::Error;
@@ -35,8 +37,13 @@ pub(crate) struct StatementConfig {
     pub(crate) tracing: bool,
     pub(crate) timestamp: Option<i64>,
     pub(crate) request_timeout: Option<Duration>,
+    /// Set by callers which await schema agreement on their own
+    /// (e.g. [Session::execute_schema_change](crate::client::session::Session::execute_schema_change)),
+    /// so that it is not awaited automatically after a schema change.
+    pub(crate) skip_auto_schema_agreement: bool,
 
     pub(crate) history_listener: Option<Arc<dyn HistoryListener>>,
+    pub(crate) tags: StatementTags,
 
     pub(crate) execution_profile_handle: Option<ExecutionProfileHandle>,
     pub(crate) load_balancing_policy: Option<Arc<dyn LoadBalancingPolicy>>,
@@ -51,6 +58,92 @@ impl StatementConfig {
     }
 }
 
+/// Key/value tags attached to a statement, used to correlate its executions
+/// across observability subsystems.
+///
+/// Tags are reported to the [HistoryListener] through
+/// [HistoryListener::log_request_tags], and are recorded in the `tags` field
+/// of the request's tracing span, so that they are attached to every event logged
+/// during the request, including errors.
+///
+/// Tags are meant to be small: they are cloned cheaply, but formatted
+/// for each request which is traced.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StatementTags(Arc<BTreeMap<String, String>>);
+
+impl StatementTags {
+    /// Creates an empty set of tags.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the tag `key` to `value`, returning the previous value, if any.
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<String>) -> Option<String> {
+        Arc::make_mut(&mut self.0).insert(key.into(), value.into())
+    }
+
+    /// Removes the tag `key`, returning its value, if it was set.
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        if !self.0.contains_key(key) {
+            return None;
+        }
+        Arc::make_mut(&mut self.0).remove(key)
+    }
+
+    /// Returns the value of the tag `key`, if it is set.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).map(String::as_str)
+    }
+
+    /// Iterates over the tags, sorted by keys.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+
+    /// Returns the number of tags.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if there are no tags.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for StatementTags {
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        Self(Arc::new(
+            iter.into_iter()
+                .map(|(key, value)| (key.into(), value.into()))
+                .collect(),
+        ))
+    }
+}
+
+/// Serializes the tags as a map from keys to values.
+#[cfg(feature = "serde")]
+impl serde::Serialize for StatementTags {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.as_ref().serialize(serializer)
+    }
+}
+
+/// Formats the tags as comma-separated `key=value` pairs.
+impl Display for StatementTags {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, (key, value)) in self.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{key}={value}")?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, Copy, Error)]
 #[error("Invalid page size provided: {0}; valid values are [1, i32::MAX]")]
 /// Invalid page size was provided.
@@ -1,12 +1,14 @@
 //! Defines the [`PreparedStatement`] type, which represents a statement
 //! that has been prepared in advance on the server.
 
+use crate::observability::log_targets;
 use arc_swap::{ArcSwap, Guard};
 use bytes::{Bytes, BytesMut};
+use itertools::Itertools;
 use scylla_cql::frame::response::result::{
     ColumnSpec, PartitionKeyIndex, ResultMetadata, TableSpec,
 };
-use scylla_cql::frame::types::RawValue;
+use scylla_cql::frame::types::{self, RawValue};
 use scylla_cql::serialize::SerializationError;
 use scylla_cql::serialize::row::{RowSerializationContext, SerializeRow, SerializedValues};
 use smallvec::{SmallVec, smallvec};
@@ -14,10 +16,12 @@ use std::convert::TryInto;
 use std::sync::Arc;
 use std::time::Duration;
 use thiserror::Error;
+use tracing::trace;
 use uuid::Uuid;
 
-use super::{PageSize, StatementConfig};
+use super::{PageSize, StatementConfig, StatementTags};
 use crate::client::execution_profile::ExecutionProfileHandle;
+use crate::cluster::ClusterState;
 use crate::errors::{BadQuery, ExecutionError};
 use crate::frame::response::result::{self, PreparedMetadata};
 use crate::frame::types::{Consistency, SerialConsistency};
@@ -218,6 +222,10 @@ struct PreparedStatementSharedData {
     current_result_metadata: ArcSwap<ResultMetadata<'static>>,
     statement: String,
     is_confirmed_lwt: bool,
+    /// Whether the statement may restrict its partition key with `IN`,
+    /// which makes it a candidate for IN-list routing. Computed once, so that
+    /// statements without `IN` do not pay for the analysis on each execution.
+    may_have_partition_key_in_list: bool,
 }
 
 impl Clone for PreparedStatement {
@@ -255,9 +263,11 @@ impl PreparedStatement {
         page_size: PageSize,
         config: StatementConfig,
     ) -> Self {
+        let may_have_partition_key_in_list = may_have_partition_key_in_list(&metadata);
         Self {
             shared: Arc::new(PreparedStatementSharedData {
                 id,
+                may_have_partition_key_in_list,
                 metadata,
                 initial_result_metadata: Arc::clone(&result_metadata),
                 current_result_metadata: ArcSwap::from(result_metadata),
@@ -395,6 +405,124 @@ impl PreparedStatement {
             .map(|opt| opt.map(|(_pk, token)| token))
     }
 
+    /// Analyses whether this statement, which restricts the partition key with `IN`
+    /// (e.g. `SELECT * FROM t WHERE pk IN ?` or `... WHERE pk IN (?, ?, ?)`),
+    /// addresses only partitions owned by the same replica set when executed with
+    /// the given values.
+    ///
+    /// If so, the driver routes such statement in a token-aware manner to that
+    /// replica set, even though the statement itself is not token-aware
+    /// (see [PreparedStatement::is_token_aware]). This method allows to check
+    /// in advance how a statement is going to be routed.
+    pub fn analyze_in_list_routing(
+        &self,
+        values: &impl SerializeRow,
+        cluster_state: &ClusterState,
+    ) -> Result<InListRoutingAnalysis, SerializationError> {
+        let serialized = self.serialize_values(values)?;
+        Ok(self.analyze_in_list_routing_untyped(&serialized, cluster_state))
+    }
+
+    pub(crate) fn analyze_in_list_routing_untyped(
+        &self,
+        values: &SerializedValues,
+        cluster_state: &ClusterState,
+    ) -> InListRoutingAnalysis {
+        if !self.shared.may_have_partition_key_in_list {
+            return InListRoutingAnalysis::NotApplicable;
+        }
+        let Some(table_spec) = self.get_table_spec() else {
+            return InListRoutingAnalysis::NotApplicable;
+        };
+        let Some(table) = cluster_state
+            .get_keyspace(table_spec.ks_name())
+            .and_then(|keyspace| keyspace.tables.get(table_spec.table_name()))
+        else {
+            return InListRoutingAnalysis::NotApplicable;
+        };
+
+        let Some(candidates) = in_list_partition_key_candidates(
+            &table.partition_key,
+            &self.get_prepared_metadata().col_specs,
+            values,
+        ) else {
+            return InListRoutingAnalysis::NotApplicable;
+        };
+
+        let partitions = candidates
+            .iter()
+            .try_fold(1_usize, |acc, column_values| {
+                acc.checked_mul(column_values.len())
+            })
+            .filter(|partitions| *partitions <= MAX_IN_LIST_PARTITIONS);
+        let Some(partitions) = partitions else {
+            return InListRoutingAnalysis::NotApplicable;
+        };
+
+        let replica_set_of = |token: Token| {
+            let mut host_ids: SmallVec<[Uuid; 8]> = cluster_state
+                .get_token_endpoints_iter(table_spec, token)
+                .map(|(node, _shard)| node.host_id)
+                .collect();
+            host_ids.sort_unstable();
+            host_ids
+        };
+
+        let mut first: Option<(Token, SmallVec<[Uuid; 8]>)> = None;
+        for partition in candidates.iter().multi_cartesian_product() {
+            let token = match calculate_token_for_encoded_values(
+                partition.iter().copied().copied(),
+                &self.partitioner_name,
+            ) {
+                Ok(token) => token,
+                Err(_) => return InListRoutingAnalysis::NotApplicable,
+            };
+            match &first {
+                None => first = Some((token, replica_set_of(token))),
+                Some((_, first_replicas)) => {
+                    if *first_replicas != replica_set_of(token) {
+                        return InListRoutingAnalysis::MultipleReplicaSets { partitions };
+                    }
+                }
+            }
+        }
+
+        match first {
+            Some((token, _)) => InListRoutingAnalysis::SingleReplicaSet { token, partitions },
+            None => InListRoutingAnalysis::NotApplicable,
+        }
+    }
+
+    /// Returns the token to route the statement with if it is not token-aware,
+    /// but restricts the partition key with `IN` to partitions owned by a single
+    /// replica set. See [PreparedStatement::analyze_in_list_routing].
+    pub(crate) fn in_list_routing_token(
+        &self,
+        values: &SerializedValues,
+        cluster_state: &ClusterState,
+    ) -> Option<Token> {
+        match self.analyze_in_list_routing_untyped(values, cluster_state) {
+            InListRoutingAnalysis::NotApplicable => None,
+            InListRoutingAnalysis::SingleReplicaSet { token, partitions } => {
+                trace!(
+                    target: log_targets::REQUEST,
+                    partitions,
+                    token = token.value(),
+                    "IN list addresses partitions owned by a single replica set, routing token-aware"
+                );
+                Some(token)
+            }
+            InListRoutingAnalysis::MultipleReplicaSets { partitions } => {
+                trace!(
+                    target: log_targets::REQUEST,
+                    partitions,
+                    "IN list addresses partitions owned by multiple replica sets, routing token-unaware"
+                );
+                None
+            }
+        }
+    }
+
     /// Return keyspace name and table name this statement is operating on.
     pub fn get_table_spec(&self) -> Option<&TableSpec<'_>> {
         self.get_prepared_metadata()
@@ -637,6 +765,27 @@ impl PreparedStatement {
         self.config.history_listener.take()
     }
 
+    /// Sets the tag `key` to `value`, returning the previous value, if any.
+    /// See [StatementTags] for how tags are used.
+    pub fn set_tag(&mut self, key: impl Into<String>, value: impl Into<String>) -> Option<String> {
+        self.config.tags.insert(key, value)
+    }
+
+    /// Removes the tag `key` set by [`PreparedStatement::set_tag`], returning its value, if it was set.
+    pub fn remove_tag(&mut self, key: &str) -> Option<String> {
+        self.config.tags.remove(key)
+    }
+
+    /// Replaces all tags of the statement.
+    pub fn set_tags(&mut self, tags: StatementTags) {
+        self.config.tags = tags;
+    }
+
+    /// Returns the tags of the statement.
+    pub fn get_tags(&self) -> &StatementTags {
+        &self.config.tags
+    }
+
     /// Associates the query with execution profile referred by the provided handle.
     /// Handle may be later remapped to another profile, and query will reflect those changes.
     pub fn set_execution_profile_handle(&mut self, profile_handle: Option<ExecutionProfileHandle>) {
@@ -656,6 +805,32 @@ impl PreparedStatement {
         SerializedValues::from_serializable(&ctx, values)
     }
 
+    /// Serializes `values` bound to this statement into `serialized`, replacing
+    /// its previous contents.
+    ///
+    /// The buffer of `serialized` is reused, so binding values in a loop this way
+    /// does not allocate a fresh buffer per request. The result can be executed with
+    /// [Session::execute_unpaged_serialized](crate::client::session::Session::execute_unpaged_serialized).
+    ///
+    /// If serialization fails, `serialized` is left empty.
+    pub fn serialize_values_into(
+        &self,
+        values: &impl SerializeRow,
+        serialized: &mut SerializedValues,
+    ) -> Result<(), SerializationError> {
+        let ctx = RowSerializationContext::from_prepared(self.get_prepared_metadata());
+        serialized.reset_from_serializable(&ctx, values)
+    }
+
+    /// Checks that values serialized beforehand fit the bind markers of this statement.
+    pub(crate) fn type_check_serialized_values(
+        &self,
+        serialized: &SerializedValues,
+    ) -> Result<(), SerializationError> {
+        let ctx = RowSerializationContext::from_prepared(self.get_prepared_metadata());
+        serialized.type_check(&ctx)
+    }
+
     pub(crate) fn make_unconfigured_handle(&self) -> UnconfiguredPreparedStatement {
         UnconfiguredPreparedStatement {
             shared: Arc::clone(&self.shared),
@@ -692,6 +867,105 @@ impl UnconfiguredPreparedStatement {
     }
 }
 
+/// The maximum number of partitions addressed by a statement that
+/// [PreparedStatement::analyze_in_list_routing] analyses.
+const MAX_IN_LIST_PARTITIONS: usize = 256;
+
+/// A result of analysing whether a statement that restricts the partition key with `IN`
+/// can be routed in a token-aware manner.
+///
+/// See [PreparedStatement::analyze_in_list_routing].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum InListRoutingAnalysis {
+    /// The analysis is not applicable. This is the case when the statement is token-aware
+    /// on its own, when it does not restrict all partition key columns with `=` or `IN`,
+    /// when any of the partition key values is null or unset, when the table is not known
+    /// in the cluster metadata, or when the statement addresses too many partitions.
+    NotApplicable,
+
+    /// All partitions addressed by the statement are owned by the same replica set,
+    /// so the statement is routed in a token-aware manner using `token`.
+    SingleReplicaSet {
+        /// Token of one of the addressed partitions, used for routing.
+        token: Token,
+        /// Number of partitions addressed by the statement.
+        partitions: usize,
+    },
+
+    /// Partitions addressed by the statement are owned by different replica sets,
+    /// so the statement is not routed in a token-aware manner.
+    MultipleReplicaSets {
+        /// Number of partitions addressed by the statement.
+        partitions: usize,
+    },
+}
+
+/// Tells, based on the bind markers alone, whether a statement may restrict
+/// its partition key with `IN`. This holds if the statement is not token-aware and
+/// either has a marker named `in(column)` (`column IN ?`), or has multiple markers
+/// of the same column (`column IN (?, ?)`).
+fn may_have_partition_key_in_list(metadata: &PreparedMetadata) -> bool {
+    if !metadata.pk_indexes.is_empty() {
+        return false;
+    }
+
+    let names = || metadata.col_specs.iter().map(|spec| spec.name());
+    names().any(|name| name.starts_with("in(") && name.ends_with(')'))
+        || names()
+            .enumerate()
+            .any(|(idx, name)| names().skip(idx + 1).any(|other| other == name))
+}
+
+/// For each partition key column (in the partition key order), collects the values
+/// that the column is restricted to by bound values.
+///
+/// A column is recognised as restricted by either a marker having the column's name
+/// (`pk = ?` or `pk IN (?, ?)`), or a marker named `in(pk)` (`pk IN ?`), which holds
+/// a serialized list of values.
+///
+/// Returns None if any of the columns is not restricted or any of the values
+/// is null, unset or malformed.
+fn in_list_partition_key_candidates<'v>(
+    partition_key: &[String],
+    col_specs: &[ColumnSpec<'_>],
+    values: &'v SerializedValues,
+) -> Option<Vec<Vec<&'v [u8]>>> {
+    let mut candidates: Vec<Vec<&'v [u8]>> = vec![Vec::new(); partition_key.len()];
+
+    for (spec, value) in col_specs.iter().zip(values.iter()) {
+        let name = spec.name();
+        let in_marker_column = name
+            .strip_prefix("in(")
+            .and_then(|rest| rest.strip_suffix(')'));
+
+        if let Some(idx) = partition_key.iter().position(|pk| pk == name) {
+            let RawValue::Value(value) = value else {
+                return None;
+            };
+            candidates[idx].push(value);
+        } else if let Some(idx) =
+            in_marker_column.and_then(|column| partition_key.iter().position(|pk| pk == column))
+        {
+            let RawValue::Value(mut list) = value else {
+                return None;
+            };
+            let count = types::read_int_length(&mut list).ok()?;
+            for _ in 0..count {
+                match types::read_value(&mut list).ok()? {
+                    RawValue::Value(element) => candidates[idx].push(element),
+                    RawValue::Null | RawValue::Unset => return None,
+                }
+            }
+        }
+    }
+
+    candidates
+        .iter()
+        .all(|column_values| !column_values.is_empty())
+        .then_some(candidates)
+}
+
 /// Error when extracting partition key from bound values.
 #[derive(Clone, Debug, Error, PartialEq, Eq, PartialOrd, Ord)]
 #[non_exhaustive]
@@ -799,51 +1073,70 @@ impl<'ps> PartitionKey<'ps> {
         &self,
         writer: &mut impl FnMut(&[u8]),
     ) -> Result<(), TokenCalculationError> {
-        let mut pk_val_iter = self.iter().map(|(val, _spec)| val);
-        if let Some(first_value) = pk_val_iter.next() {
-            if let Some(second_value) = pk_val_iter.next() {
-                // Composite partition key case
-                for value in std::iter::once(first_value)
-                    .chain(std::iter::once(second_value))
-                    .chain(pk_val_iter)
-                {
-                    let v_len_u16: u16 = value
-                        .len()
-                        .try_into()
-                        .map_err(|_| TokenCalculationError::ValueTooLong(value.len()))?;
-                    writer(&v_len_u16.to_be_bytes());
-                    writer(value);
-                    writer(&[0u8]);
-                }
-            } else {
-                // Single-value partition key case
-                writer(first_value);
-            }
-        }
-        Ok(())
+        write_encoded_partition_key(self.iter().map(|(val, _spec)| val), writer)
     }
 
     pub(crate) fn calculate_token(
         &self,
         partitioner_name: &PartitionerName,
     ) -> Result<Token, TokenCalculationError> {
-        let mut partitioner_hasher = partitioner_name.build_hasher();
-        let mut writer = |chunk: &[u8]| partitioner_hasher.write(chunk);
-
-        self.write_encoded_partition_key(&mut writer)?;
+        calculate_token_for_encoded_values(self.iter().map(|(val, _spec)| val), partitioner_name)
+    }
+}
 
-        Ok(partitioner_hasher.finish())
+/// Encodes the partition key consisting of given values of its columns,
+/// passing the encoded chunks to `writer`.
+fn write_encoded_partition_key<'a>(
+    mut pk_val_iter: impl Iterator<Item = &'a [u8]>,
+    writer: &mut impl FnMut(&[u8]),
+) -> Result<(), TokenCalculationError> {
+    if let Some(first_value) = pk_val_iter.next() {
+        if let Some(second_value) = pk_val_iter.next() {
+            // Composite partition key case
+            for value in std::iter::once(first_value)
+                .chain(std::iter::once(second_value))
+                .chain(pk_val_iter)
+            {
+                let v_len_u16: u16 = value
+                    .len()
+                    .try_into()
+                    .map_err(|_| TokenCalculationError::ValueTooLong(value.len()))?;
+                writer(&v_len_u16.to_be_bytes());
+                writer(value);
+                writer(&[0u8]);
+            }
+        } else {
+            // Single-value partition key case
+            writer(first_value);
+        }
     }
+    Ok(())
+}
+
+/// Calculates the token of the partition key consisting of given values of its columns.
+fn calculate_token_for_encoded_values<'a>(
+    pk_val_iter: impl Iterator<Item = &'a [u8]>,
+    partitioner_name: &PartitionerName,
+) -> Result<Token, TokenCalculationError> {
+    let mut partitioner_hasher = partitioner_name.build_hasher();
+    let mut writer = |chunk: &[u8]| partitioner_hasher.write(chunk);
+
+    write_encoded_partition_key(pk_val_iter, &mut writer)?;
+
+    Ok(partitioner_hasher.finish())
 }
 
 #[cfg(test)]
 mod tests {
     use scylla_cql::frame::response::result::{
-        ColumnSpec, ColumnType, NativeType, PartitionKeyIndex, PreparedMetadata, TableSpec,
+        CollectionType, ColumnSpec, ColumnType, NativeType, PartitionKeyIndex, PreparedMetadata,
+        TableSpec,
     };
     use scylla_cql::serialize::row::SerializedValues;
 
-    use crate::statement::prepared::PartitionKey;
+    use crate::statement::prepared::{
+        PartitionKey, in_list_partition_key_candidates, may_have_partition_key_in_list,
+    };
     use crate::test_utils::setup_tracing;
 
     fn make_meta(
@@ -915,6 +1208,95 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_in_list_partition_key_candidates() {
+        setup_tracing();
+        let table_spec = TableSpec::owned("ks".to_owned(), "t".to_owned());
+        let int = ColumnType::Native(NativeType::Int);
+        let int_list = ColumnType::Collection {
+            frozen: false,
+            typ: CollectionType::List(Box::new(int.clone())),
+        };
+        let spec = |name: &str, typ: &ColumnType<'static>| {
+            ColumnSpec::owned(name.to_owned(), typ.clone(), table_spec.clone())
+        };
+        let partition_key = ["a".to_owned(), "b".to_owned()];
+
+        // WHERE a IN ? AND b IN (?, ?) AND c = ?
+        let col_specs = [
+            spec("in(a)", &int_list),
+            spec("b", &int),
+            spec("b", &int),
+            spec("c", &int),
+        ];
+        let mut values = SerializedValues::new();
+        values.add_value(&vec![1i32, 2, 3], &int_list).unwrap();
+        values.add_value(&4i32, &int).unwrap();
+        values.add_value(&5i32, &int).unwrap();
+        values.add_value(&6i32, &int).unwrap();
+
+        let candidates =
+            in_list_partition_key_candidates(&partition_key, &col_specs, &values).unwrap();
+        assert_eq!(
+            candidates,
+            vec![
+                vec![
+                    1i32.to_be_bytes().as_slice(),
+                    2i32.to_be_bytes().as_slice(),
+                    3i32.to_be_bytes().as_slice(),
+                ],
+                vec![4i32.to_be_bytes().as_slice(), 5i32.to_be_bytes().as_slice()],
+            ]
+        );
+
+        // WHERE a IN ? AND c = ? - partition key column `b` is not restricted.
+        let col_specs = [spec("in(a)", &int_list), spec("c", &int)];
+        let mut values = SerializedValues::new();
+        values.add_value(&vec![1i32, 2, 3], &int_list).unwrap();
+        values.add_value(&6i32, &int).unwrap();
+        assert!(in_list_partition_key_candidates(&partition_key, &col_specs, &values).is_none());
+
+        // WHERE a = ? AND b = ? - a null value.
+        let col_specs = [spec("a", &int), spec("b", &int)];
+        let mut values = SerializedValues::new();
+        values.add_value(&1i32, &int).unwrap();
+        values.add_value(&None::<i32>, &int).unwrap();
+        assert!(in_list_partition_key_candidates(&partition_key, &col_specs, &values).is_none());
+    }
+
+    #[test]
+    fn test_may_have_partition_key_in_list() {
+        setup_tracing();
+        let table_spec = TableSpec::owned("ks".to_owned(), "t".to_owned());
+        let int = ColumnType::Native(NativeType::Int);
+        let meta = |names: &[&str]| {
+            let col_specs: Vec<_> = names
+                .iter()
+                .map(|name| ColumnSpec::owned(name.to_string(), int.clone(), table_spec.clone()))
+                .collect();
+            PreparedMetadata {
+                flags: 0,
+                col_count: col_specs.len(),
+                col_specs,
+                pk_indexes: Vec::new(),
+            }
+        };
+
+        // WHERE a IN ?
+        assert!(may_have_partition_key_in_list(&meta(&["in(a)", "c"])));
+        // WHERE a IN (?, ?)
+        assert!(may_have_partition_key_in_list(&meta(&["a", "a", "c"])));
+        // WHERE a = ? AND c = ?
+        assert!(!may_have_partition_key_in_list(&meta(&["a", "c"])));
+        // Token-aware statements are routed by their partition key anyway.
+        let mut token_aware = meta(&["in(a)"]);
+        token_aware.pk_indexes = vec![PartitionKeyIndex {
+            index: 0,
+            sequence: 0,
+        }];
+        assert!(!may_have_partition_key_in_list(&token_aware));
+    }
+
     #[test]
     fn test_column_specs_guard_debug() {
         use crate::statement::prepared::PreparedStatement;
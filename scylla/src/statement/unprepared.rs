@@ -1,7 +1,8 @@
 //! Defines the [`Statement`] type, which represents an unprepared CQL statement.
 
-use super::{PageSize, StatementConfig};
+use super::{PageSize, StatementConfig, StatementTags};
 use crate::client::execution_profile::ExecutionProfileHandle;
+use crate::errors::ServerTimeoutError;
 use crate::frame::types::{Consistency, SerialConsistency};
 use crate::observability::history::HistoryListener;
 use crate::policies::load_balancing::LoadBalancingPolicy;
@@ -19,14 +20,20 @@ pub struct Statement {
     /// The CQL statement text.
     pub contents: String,
     page_size: PageSize,
+    server_timeout: Option<Duration>,
 }
 
+/// How much longer than the server-side timeout the driver waits for a response,
+/// so that the error reported by the server on timeout reaches the driver.
+const SERVER_TIMEOUT_CLIENT_MARGIN: Duration = Duration::from_millis(500);
+
 impl Statement {
     /// Creates a new [`Statement`] from a CQL statement string.
     pub fn new(query_text: impl Into<String>) -> Self {
         Self {
             contents: query_text.into(),
             page_size: PageSize::default(),
+            server_timeout: None,
             config: Default::default(),
         }
     }
@@ -147,6 +154,42 @@ impl Statement {
         self.config.request_timeout
     }
 
+    /// Sets the server-side timeout for this statement, using ScyllaDB's `USING TIMEOUT` clause.
+    ///
+    /// The clause is added to the [contents](Self::contents) of the statement, which must be
+    /// a `SELECT`, `INSERT`, `UPDATE` or `DELETE` statement without a `USING TIMEOUT` clause.
+    /// Other `USING` options of the statement, e.g. `TTL`, are preserved. The CQL protocol has
+    /// no option for per-request timeouts, so the clause is used regardless of the protocol version.
+    /// The clause is supported by ScyllaDB only.
+    ///
+    /// The [client-side timeout](Self::set_request_timeout) is set slightly above the server-side
+    /// one, so that the driver receives the timeout error reported by the server instead of
+    /// giving up on its own. Call [Statement::set_request_timeout] afterwards to override it.
+    ///
+    /// The timeout is truncated to whole milliseconds and must be at least one millisecond long.
+    ///
+    /// ```rust
+    /// # use scylla::statement::unprepared::Statement;
+    /// # use std::time::Duration;
+    /// let mut statement = Statement::new("UPDATE ks.t USING TTL 60 SET v = ? WHERE pk = ?");
+    /// statement.set_server_timeout(Duration::from_secs(2)).unwrap();
+    /// assert_eq!(
+    ///     statement.contents,
+    ///     "UPDATE ks.t USING TTL 60 AND TIMEOUT 2000ms SET v = ? WHERE pk = ?"
+    /// );
+    /// ```
+    pub fn set_server_timeout(&mut self, timeout: Duration) -> Result<(), ServerTimeoutError> {
+        self.contents = with_server_timeout(&self.contents, timeout)?;
+        self.server_timeout = Some(timeout);
+        self.config.request_timeout = Some(timeout + SERVER_TIMEOUT_CLIENT_MARGIN);
+        Ok(())
+    }
+
+    /// Gets the server-side timeout set with [Statement::set_server_timeout].
+    pub fn get_server_timeout(&self) -> Option<Duration> {
+        self.server_timeout
+    }
+
     /// Set the retry policy for this statement, overriding the one from execution profile if not None.
     #[inline]
     pub fn set_retry_policy(&mut self, retry_policy: Option<Arc<dyn RetryPolicy>>) {
@@ -192,6 +235,27 @@ impl Statement {
         self.config.history_listener.take()
     }
 
+    /// Sets the tag `key` to `value`, returning the previous value, if any.
+    /// See [StatementTags] for how tags are used.
+    pub fn set_tag(&mut self, key: impl Into<String>, value: impl Into<String>) -> Option<String> {
+        self.config.tags.insert(key, value)
+    }
+
+    /// Removes the tag `key` set by [`Statement::set_tag`], returning its value, if it was set.
+    pub fn remove_tag(&mut self, key: &str) -> Option<String> {
+        self.config.tags.remove(key)
+    }
+
+    /// Replaces all tags of the statement.
+    pub fn set_tags(&mut self, tags: StatementTags) {
+        self.config.tags = tags;
+    }
+
+    /// Returns the tags of the statement.
+    pub fn get_tags(&self) -> &StatementTags {
+        &self.config.tags
+    }
+
     /// Associates the query with execution profile referred by the provided handle.
     /// Handle may be later remapped to another profile, and query will reflect those changes.
     pub fn set_execution_profile_handle(&mut self, profile_handle: Option<ExecutionProfileHandle>) {
@@ -204,6 +268,100 @@ impl Statement {
     }
 }
 
+/// A word of a CQL statement, outside of string literals and quoted identifiers.
+struct Word<'a> {
+    text: &'a str,
+    start: usize,
+}
+
+impl Word<'_> {
+    fn is(&self, keyword: &str) -> bool {
+        self.text.eq_ignore_ascii_case(keyword)
+    }
+}
+
+/// Splits a CQL statement into words, skipping string literals, quoted identifiers and comments.
+fn words(contents: &str) -> Vec<Word<'_>> {
+    let bytes = contents.as_bytes();
+    let mut words = Vec::new();
+    let mut i = 0;
+    let skip_until = |from: usize, end: &str| {
+        contents[from..]
+            .find(end)
+            .map_or(contents.len(), |pos| from + pos + end.len())
+    };
+    while i < bytes.len() {
+        match bytes[i] {
+            // Doubled quotes inside a literal (escapes) split it in two, which is harmless.
+            quote @ (b'\'' | b'"') => {
+                i = skip_until(i + 1, if quote == b'"' { "\"" } else { "'" });
+            }
+            b'$' if bytes.get(i + 1) == Some(&b'$') => i = skip_until(i + 2, "$$"),
+            b'-' if bytes.get(i + 1) == Some(&b'-') => i = skip_until(i + 2, "\n"),
+            b'/' if bytes.get(i + 1) == Some(&b'/') => i = skip_until(i + 2, "\n"),
+            b'/' if bytes.get(i + 1) == Some(&b'*') => i = skip_until(i + 2, "*/"),
+            byte if byte.is_ascii_alphanumeric() || byte == b'_' => {
+                let start = i;
+                while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                    i += 1;
+                }
+                words.push(Word {
+                    text: &contents[start..i],
+                    start,
+                });
+            }
+            _ => i += 1,
+        }
+    }
+    words
+}
+
+/// Adds a `USING TIMEOUT` clause to a CQL statement.
+fn with_server_timeout(contents: &str, timeout: Duration) -> Result<String, ServerTimeoutError> {
+    let millis = timeout.as_millis();
+    if millis == 0 {
+        return Err(ServerTimeoutError::TimeoutTooShort(timeout));
+    }
+
+    let contents = contents.trim_end().trim_end_matches(';').trim_end();
+    let words = words(contents);
+    if words
+        .windows(2)
+        .any(|pair| (pair[0].is("USING") || pair[0].is("AND")) && pair[1].is("TIMEOUT"))
+    {
+        return Err(ServerTimeoutError::TimeoutAlreadySet);
+    }
+
+    // The position to insert the clause at, and whether the statement already has a USING clause.
+    let (position, has_using) = match words.first() {
+        Some(word) if word.is("SELECT") => (contents.len(), false),
+        Some(word) if word.is("INSERT") => {
+            (contents.len(), words.iter().any(|word| word.is("USING")))
+        }
+        // UPDATE <table> [USING ...] SET ..., DELETE [...] FROM <table> [USING ...] WHERE ...
+        Some(word) if word.is("UPDATE") || word.is("DELETE") => {
+            let keyword = if word.is("UPDATE") { "SET" } else { "WHERE" };
+            let Some(idx) = words.iter().skip(2).position(|word| word.is(keyword)) else {
+                return Err(ServerTimeoutError::UnsupportedStatement);
+            };
+            let idx = idx + 2;
+            (
+                words[idx].start,
+                words[..idx].iter().any(|word| word.is("USING")),
+            )
+        }
+        _ => return Err(ServerTimeoutError::UnsupportedStatement),
+    };
+
+    let clause = if has_using { "AND" } else { "USING" };
+    let (before, after) = contents.split_at(position);
+    Ok(if after.is_empty() {
+        format!("{before} {clause} TIMEOUT {millis}ms")
+    } else {
+        format!("{before}{clause} TIMEOUT {millis}ms {after}")
+    })
+}
+
 impl From<String> for Statement {
     fn from(s: String) -> Statement {
         Statement::new(s)
@@ -215,3 +373,79 @@ impl<'a> From<&'a str> for Statement {
         Statement::new(s.to_owned())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use assert_matches::assert_matches;
+
+    use super::{Statement, with_server_timeout};
+    use crate::errors::ServerTimeoutError;
+
+    #[test]
+    fn server_timeout_clause() {
+        let timeout = Duration::from_millis(1500);
+        let cases = [
+            (
+                "SELECT * FROM ks.t WHERE pk = ? LIMIT 10;",
+                "SELECT * FROM ks.t WHERE pk = ? LIMIT 10 USING TIMEOUT 1500ms",
+            ),
+            (
+                "INSERT INTO ks.t (pk, v) VALUES (?, 'using') IF NOT EXISTS",
+                "INSERT INTO ks.t (pk, v) VALUES (?, 'using') IF NOT EXISTS USING TIMEOUT 1500ms",
+            ),
+            (
+                "insert into ks.t (pk, v) values (?, ?) using ttl 10",
+                "insert into ks.t (pk, v) values (?, ?) using ttl 10 AND TIMEOUT 1500ms",
+            ),
+            (
+                "UPDATE ks.\"set\" SET v = ? WHERE pk = ?",
+                "UPDATE ks.\"set\" USING TIMEOUT 1500ms SET v = ? WHERE pk = ?",
+            ),
+            (
+                "DELETE m['where'] FROM ks.t USING TIMESTAMP 5 WHERE pk = ?",
+                "DELETE m['where'] FROM ks.t USING TIMESTAMP 5 AND TIMEOUT 1500ms WHERE pk = ?",
+            ),
+        ];
+        for (contents, expected) in cases {
+            assert_eq!(with_server_timeout(contents, timeout).unwrap(), expected);
+        }
+
+        assert_matches!(
+            with_server_timeout("SELECT * FROM t USING TIMEOUT 1s", timeout),
+            Err(ServerTimeoutError::TimeoutAlreadySet)
+        );
+        assert_matches!(
+            with_server_timeout("BEGIN BATCH APPLY BATCH", timeout),
+            Err(ServerTimeoutError::UnsupportedStatement)
+        );
+        assert_matches!(
+            with_server_timeout("CREATE TABLE t (pk int PRIMARY KEY)", timeout),
+            Err(ServerTimeoutError::UnsupportedStatement)
+        );
+        assert_matches!(
+            with_server_timeout("SELECT * FROM t", Duration::from_micros(10)),
+            Err(ServerTimeoutError::TimeoutTooShort(_))
+        );
+    }
+
+    #[test]
+    fn server_timeout_sets_client_timeout() {
+        let mut statement = Statement::new("SELECT * FROM t");
+        statement
+            .set_server_timeout(Duration::from_secs(2))
+            .unwrap();
+        assert_eq!(statement.get_server_timeout(), Some(Duration::from_secs(2)));
+        assert!(statement.get_request_timeout().unwrap() > Duration::from_secs(2));
+
+        // A failed attempt leaves the statement untouched.
+        assert!(
+            statement
+                .set_server_timeout(Duration::from_secs(1))
+                .is_err()
+        );
+        assert_eq!(statement.contents, "SELECT * FROM t USING TIMEOUT 2000ms");
+        assert_eq!(statement.get_server_timeout(), Some(Duration::from_secs(2)));
+    }
+}
@@ -0,0 +1,330 @@
+//! Typed access to ScyllaDB virtual tables.
+//!
+//! Virtual tables in the `system` keyspace expose the runtime state of a node:
+//! the clients connected to it (`system.clients`), its runtime statistics
+//! (`system.runtime_info`) and its live configuration (`system.config`).
+//! [SystemViews], obtained from [Session::system], queries them and deserializes
+//! the rows into typed structs, so that health tooling does not need to write
+//! raw queries.
+//!
+//! Virtual tables are node-local: each node reports only its own state.
+//! By default, the request is routed by the load balancing policy of the session,
+//! so use [SystemViews::on_node] to choose the node that should be asked.
+//!
+//! Values of `system.runtime_info` and `system.config` are text. [parse_size]
+//! and [parse_duration] convert the common textual representations of sizes
+//! and durations.
+//!
+//! # Example
+//! ```rust
+//! # use scylla::client::session::Session;
+//! # use std::error::Error;
+//! # async fn check_only_compiles(session: &Session) -> Result<(), Box<dyn Error>> {
+//! use scylla::policies::load_balancing::NodeIdentifier;
+//!
+//! for node in session.get_cluster_state().get_nodes_info() {
+//!     let clients = session
+//!         .system()
+//!         .on_node(NodeIdentifier::Node(node.clone()))
+//!         .clients()
+//!         .await?;
+//!     println!("{}: {} clients", node.address, clients.len());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::net::IpAddr;
+use std::time::Duration;
+
+use futures::TryStreamExt as _;
+use scylla_cql::deserialize::TypeCheckError;
+use thiserror::Error;
+
+use crate::DeserializeRow;
+use crate::client::session::Session;
+use crate::deserialize::DeserializeOwnedRow;
+use crate::errors::{NextRowError, PagerExecutionError};
+use crate::policies::load_balancing::{NodeIdentifier, SingleTargetLoadBalancingPolicy};
+use crate::statement::unprepared::Statement;
+
+/// Queries virtual tables of the `system` keyspace.
+///
+/// Obtained from [Session::system].
+#[derive(Debug, Clone)]
+pub struct SystemViews<'a> {
+    session: &'a Session,
+    target: Option<NodeIdentifier>,
+}
+
+impl<'a> SystemViews<'a> {
+    pub(crate) fn new(session: &'a Session) -> Self {
+        Self {
+            session,
+            target: None,
+        }
+    }
+
+    /// Sends the queries to the given node, instead of the one chosen
+    /// by the load balancing policy.
+    pub fn on_node(mut self, node: NodeIdentifier) -> Self {
+        self.target = Some(node);
+        self
+    }
+
+    /// Fetches the clients connected to the node, from `system.clients`.
+    pub async fn clients(&self) -> Result<Vec<ClientInfo>, SystemViewsError> {
+        self.fetch(
+            "SELECT address, port, client_type, connection_stage, driver_name, \
+            driver_version, hostname, protocol_version, shard_id, ssl_enabled, username \
+            FROM system.clients",
+        )
+        .await
+    }
+
+    /// Fetches the runtime statistics of the node, from `system.runtime_info`.
+    pub async fn runtime_info(&self) -> Result<Vec<RuntimeInfoEntry>, SystemViewsError> {
+        self.fetch("SELECT \"group\", item, value FROM system.runtime_info")
+            .await
+    }
+
+    /// Fetches the live configuration of the node, from `system.config`.
+    pub async fn config(&self) -> Result<Vec<ConfigEntry>, SystemViewsError> {
+        self.fetch("SELECT name, source, type, value FROM system.config")
+            .await
+    }
+
+    async fn fetch<RowT: DeserializeOwnedRow>(
+        &self,
+        query: &str,
+    ) -> Result<Vec<RowT>, SystemViewsError> {
+        let mut statement = Statement::new(query);
+        if let Some(target) = &self.target {
+            statement.set_load_balancing_policy(Some(SingleTargetLoadBalancingPolicy::new(
+                target.clone(),
+                None,
+            )));
+        }
+        let rows = self
+            .session
+            .query_iter(statement, ())
+            .await?
+            .rows_stream::<RowT>()?
+            .try_collect()
+            .await?;
+        Ok(rows)
+    }
+}
+
+/// A client connection, as reported by `system.clients`.
+#[derive(Debug, DeserializeRow, Clone, PartialEq, Eq)]
+#[scylla(crate = "crate")]
+#[non_exhaustive]
+pub struct ClientInfo {
+    /// Address of the client.
+    pub address: IpAddr,
+    /// Port of the client.
+    pub port: i32,
+    /// Protocol used by the client, e.g. `cql`.
+    pub client_type: Option<String>,
+    /// Stage of the connection, e.g. `READY` or `AUTHENTICATING`.
+    pub connection_stage: Option<String>,
+    /// Name of the driver, as sent by the client in STARTUP.
+    pub driver_name: Option<String>,
+    /// Version of the driver, as sent by the client in STARTUP.
+    pub driver_version: Option<String>,
+    /// Hostname of the client.
+    pub hostname: Option<String>,
+    /// Version of the CQL protocol used by the connection.
+    pub protocol_version: Option<i32>,
+    /// Shard serving the connection.
+    pub shard_id: Option<i32>,
+    /// Whether the connection uses TLS.
+    pub ssl_enabled: Option<bool>,
+    /// User the connection is authenticated as.
+    pub username: Option<String>,
+}
+
+/// A single runtime statistic, as reported by `system.runtime_info`.
+#[derive(Debug, DeserializeRow, Clone, PartialEq, Eq)]
+#[scylla(crate = "crate")]
+#[non_exhaustive]
+pub struct RuntimeInfoEntry {
+    /// Group of the statistic, e.g. `memory` or `gossip`.
+    pub group: String,
+    /// Name of the statistic within its group.
+    pub item: String,
+    /// Value of the statistic, as text.
+    pub value: Option<String>,
+}
+
+impl RuntimeInfoEntry {
+    /// Interprets the value as a size in bytes. See [parse_size].
+    pub fn as_size(&self) -> Option<u64> {
+        self.value.as_deref().and_then(parse_size)
+    }
+
+    /// Interprets the value as a duration. See [parse_duration].
+    pub fn as_duration(&self) -> Option<Duration> {
+        self.value.as_deref().and_then(parse_duration)
+    }
+}
+
+/// A configuration option, as reported by `system.config`.
+#[derive(Debug, DeserializeRow, Clone, PartialEq, Eq)]
+#[scylla(crate = "crate")]
+#[non_exhaustive]
+pub struct ConfigEntry {
+    /// Name of the option.
+    pub name: String,
+    /// Where the value comes from, e.g. `default` or `config`.
+    pub source: Option<String>,
+    /// Type of the option, e.g. `string` or `uint32_t`.
+    #[scylla(rename = "type")]
+    pub option_type: Option<String>,
+    /// Value of the option, as text.
+    pub value: Option<String>,
+}
+
+impl ConfigEntry {
+    /// Interprets the value as a size in bytes. See [parse_size].
+    pub fn as_size(&self) -> Option<u64> {
+        self.value.as_deref().and_then(parse_size)
+    }
+
+    /// Interprets the value as a duration. See [parse_duration].
+    pub fn as_duration(&self) -> Option<Duration> {
+        self.value.as_deref().and_then(parse_duration)
+    }
+}
+
+/// An error returned by [SystemViews].
+#[derive(Error, Debug, Clone)]
+#[non_exhaustive]
+pub enum SystemViewsError {
+    /// Failed to execute the query.
+    #[error("Failed to query a system view: {0}")]
+    ExecutionFailed(#[from] PagerExecutionError),
+
+    /// The system view has columns of unexpected types.
+    #[error("System view has invalid column type: {0}")]
+    InvalidColumnType(#[from] TypeCheckError),
+
+    /// Failed to fetch or deserialize a row.
+    #[error("Failed to fetch a row of a system view: {0}")]
+    RowFetchFailed(#[from] NextRowError),
+}
+
+/// Parses a size in bytes, e.g. `1024`, `512KB` or `1.5 GiB`.
+///
+/// A number without a unit is a number of bytes. Units are case-insensitive,
+/// and `K`, `KB` and `KiB` (and likewise for `M`, `G` and `T`) all denote powers of 1024.
+/// Returns `None` if the text is not a valid size.
+pub fn parse_size(text: &str) -> Option<u64> {
+    let text = text.trim();
+    let split = text
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let exponent = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 0,
+        "k" | "kb" | "kib" => 1,
+        "m" | "mb" | "mib" => 2,
+        "g" | "gb" | "gib" => 3,
+        "t" | "tb" | "tib" => 4,
+        _ => return None,
+    };
+    let multiplier = 1024_u64.pow(exponent);
+    if let Ok(whole) = number.parse::<u64>() {
+        return whole.checked_mul(multiplier);
+    }
+    let fractional = number.parse::<f64>().ok()?;
+    let bytes = fractional * multiplier as f64;
+    (bytes.is_finite() && bytes < u64::MAX as f64).then_some(bytes as u64)
+}
+
+/// Parses a duration made of one or more `<number><unit>` parts, e.g. `250ms`,
+/// `1h 30m` or `2d3h`.
+///
+/// Supported units are `d`, `h`, `m` (or `min`), `s`, `ms`, `us` (or `µs`) and `ns`.
+/// Numbers may be fractional. Returns `None` if the text is not a valid duration,
+/// including when a number has no unit.
+pub fn parse_duration(text: &str) -> Option<Duration> {
+    let mut rest = text.trim();
+    if rest.is_empty() {
+        return None;
+    }
+    let mut total = Duration::ZERO;
+    while !rest.is_empty() {
+        let number_end = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let (number, after_number) = rest.split_at(number_end);
+        let unit_end = after_number
+            .find(|c: char| c.is_ascii_digit() || c.is_whitespace())
+            .unwrap_or(after_number.len());
+        let (unit, after_unit) = after_number.split_at(unit_end);
+        let unit_nanos: u64 = match unit {
+            "d" => 86_400_000_000_000,
+            "h" => 3_600_000_000_000,
+            "m" | "min" => 60_000_000_000,
+            "s" => 1_000_000_000,
+            "ms" => 1_000_000,
+            "us" | "µs" => 1_000,
+            "ns" => 1,
+            _ => return None,
+        };
+        let part = match number.parse::<u64>() {
+            Ok(whole) => Duration::from_nanos(whole.checked_mul(unit_nanos)?),
+            Err(_) => {
+                let fractional = number.parse::<f64>().ok()?;
+                Duration::try_from_secs_f64(fractional * unit_nanos as f64 / 1e9).ok()?
+            }
+        };
+        total = total.checked_add(part)?;
+        rest = after_unit.trim_start();
+    }
+    Some(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{parse_duration, parse_size};
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("0"), Some(0));
+        assert_eq!(parse_size("1024"), Some(1024));
+        assert_eq!(parse_size("12B"), Some(12));
+        assert_eq!(parse_size("512KB"), Some(512 * 1024));
+        assert_eq!(parse_size("2 MiB"), Some(2 * 1024 * 1024));
+        assert_eq!(parse_size("1.5G"), Some(3 * 512 * 1024 * 1024));
+        assert_eq!(parse_size(" 1tb "), Some(1024_u64.pow(4)));
+
+        assert_eq!(parse_size(""), None);
+        assert_eq!(parse_size("KB"), None);
+        assert_eq!(parse_size("12 parsecs"), None);
+        assert_eq!(parse_size("-1"), None);
+        assert_eq!(parse_size("99999999999999999999T"), None);
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("250ms"), Some(Duration::from_millis(250)));
+        assert_eq!(parse_duration("1h 30m"), Some(Duration::from_secs(5400)));
+        assert_eq!(
+            parse_duration("2d3h"),
+            Some(Duration::from_secs(2 * 86400 + 3 * 3600))
+        );
+        assert_eq!(parse_duration("1.5s"), Some(Duration::from_millis(1500)));
+        assert_eq!(parse_duration("10us"), Some(Duration::from_micros(10)));
+        assert_eq!(parse_duration("7ns"), Some(Duration::from_nanos(7)));
+
+        assert_eq!(parse_duration(""), None);
+        assert_eq!(parse_duration("10"), None);
+        assert_eq!(parse_duration("10 parsecs"), None);
+        assert_eq!(parse_duration("s"), None);
+    }
+}
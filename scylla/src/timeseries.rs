@@ -0,0 +1,421 @@
+//! Time-bucketed partitions.
+//!
+//! Time-series tables usually bound the size of their partitions by making a time bucket
+//! (e.g. the day of the measurement) a component of the partition key:
+//! ```text
+//! CREATE TABLE ks.measurements (sensor int, day timestamp, ts timestamp, value double,
+//!     PRIMARY KEY ((sensor, day), ts))
+//! ```
+//! [TimeBucketing] computes the bucket of a timestamp, which is bound along with
+//! the rest of the partition key, so writes are routed just like any other prepared statement.
+//! [BucketedRead] reads a time range spanning multiple buckets, querying the buckets
+//! concurrently and merging their rows into a single stream, [BucketedRowsStream].
+//!
+//! # Example
+//! ```rust
+//! # use scylla::client::session::Session;
+//! # use std::error::Error;
+//! # async fn check_only_compiles(session: &Session) -> Result<(), Box<dyn Error>> {
+//! use futures::TryStreamExt;
+//! use scylla::timeseries::{BucketedRead, TimeBucketing};
+//! use scylla::value::CqlTimestamp;
+//!
+//! let bucketing = TimeBucketing::daily();
+//!
+//! let insert = session
+//!     .prepare("INSERT INTO ks.measurements (sensor, day, ts, value) VALUES (?, ?, ?, ?)")
+//!     .await?;
+//! let ts = CqlTimestamp(1_700_000_000_000);
+//! let day = bucketing.bucket_of(ts).start();
+//! session.execute_unpaged(&insert, (1, day, ts, 21.5)).await?;
+//!
+//! let select = session
+//!     .prepare("SELECT ts, value FROM ks.measurements WHERE sensor = ? AND day = ?")
+//!     .await?;
+//! let (from, to) = (CqlTimestamp(1_699_000_000_000), CqlTimestamp(1_700_000_000_000));
+//! let mut rows_stream = BucketedRead::new(select, bucketing, from, to)
+//!     .rows_stream::<(CqlTimestamp, f64), _>(session, |bucket| (1, bucket.start()))?;
+//!
+//! while let Some((ts, value)) = rows_stream.try_next().await? {
+//!     println!("ts, value: {}, {}", ts.0, value);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::num::NonZeroUsize;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures::stream::BoxStream;
+use futures::{Stream, StreamExt as _, TryStreamExt as _, future, stream};
+use scylla_cql::deserialize::TypeCheckError;
+use scylla_cql::serialize::row::SerializeRow;
+use scylla_cql::value::{CqlDate, CqlTimestamp};
+use thiserror::Error;
+
+use crate::client::pager::NextRowError;
+use crate::client::session::Session;
+use crate::deserialize::row::DeserializeRow;
+use crate::errors::PagerExecutionError;
+use crate::statement::prepared::PreparedStatement;
+
+const DEFAULT_CONCURRENCY: NonZeroUsize = NonZeroUsize::new(4).unwrap();
+
+const MILLIS_PER_DAY: i64 = 24 * 60 * 60 * 1000;
+
+/// A scheme dividing time into consecutive buckets of equal width.
+///
+/// Buckets are aligned to the Unix epoch: the bucket with index `i` spans
+/// timestamps from `i * width` (inclusive) to `(i + 1) * width` (exclusive).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimeBucketing {
+    width_ms: i64,
+}
+
+impl TimeBucketing {
+    /// Creates a bucketing scheme with buckets of the given width.
+    ///
+    /// The width is truncated to whole milliseconds.
+    /// Panics if it is shorter than a millisecond or longer than `i64::MAX` milliseconds.
+    pub fn new(width: Duration) -> Self {
+        let width_ms = i64::try_from(width.as_millis())
+            .ok()
+            .filter(|width_ms| *width_ms > 0)
+            .expect("TimeBucketing::new: bucket width out of range");
+        Self { width_ms }
+    }
+
+    /// Creates a bucketing scheme with hour-sized buckets.
+    pub fn hourly() -> Self {
+        Self::new(Duration::from_secs(60 * 60))
+    }
+
+    /// Creates a bucketing scheme with day-sized buckets (UTC days).
+    pub fn daily() -> Self {
+        Self::new(Duration::from_secs(24 * 60 * 60))
+    }
+
+    /// Width of a single bucket.
+    pub fn width(&self) -> Duration {
+        Duration::from_millis(self.width_ms as u64)
+    }
+
+    /// Returns the bucket the timestamp belongs to.
+    pub fn bucket_of(&self, timestamp: CqlTimestamp) -> TimeBucket {
+        TimeBucket {
+            index: timestamp.0.div_euclid(self.width_ms),
+            width_ms: self.width_ms,
+        }
+    }
+
+    /// Returns the buckets covering timestamps from `from` to `to` (both inclusive),
+    /// in chronological order.
+    ///
+    /// Returns no buckets if `from` is later than `to`.
+    pub fn buckets(
+        &self,
+        from: CqlTimestamp,
+        to: CqlTimestamp,
+    ) -> impl DoubleEndedIterator<Item = TimeBucket> + Send + use<> {
+        let width_ms = self.width_ms;
+        let (first, last) = if from.0 <= to.0 {
+            (self.bucket_of(from).index, self.bucket_of(to).index)
+        } else {
+            (1, 0)
+        };
+        (first..=last).map(move |index| TimeBucket { index, width_ms })
+    }
+}
+
+/// A single bucket of a [TimeBucketing] scheme.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TimeBucket {
+    index: i64,
+    width_ms: i64,
+}
+
+impl TimeBucket {
+    /// Number of buckets between the Unix epoch and this bucket,
+    /// negative for buckets before the epoch.
+    pub fn index(&self) -> i64 {
+        self.index
+    }
+
+    /// The earliest timestamp belonging to the bucket.
+    ///
+    /// Saturates at the earliest representable timestamp, as the first bucket
+    /// before the epoch may start before it.
+    pub fn start(&self) -> CqlTimestamp {
+        CqlTimestamp(self.index.saturating_mul(self.width_ms))
+    }
+
+    /// The earliest timestamp after the bucket, i.e. the start of the next bucket.
+    ///
+    /// Returns `None` for the last bucket, which includes the latest representable
+    /// timestamp, so no timestamp is after it.
+    pub fn end(&self) -> Option<CqlTimestamp> {
+        self.index
+            .checked_add(1)
+            .and_then(|next| next.checked_mul(self.width_ms))
+            .map(CqlTimestamp)
+    }
+
+    /// Checks whether the timestamp belongs to the bucket.
+    pub fn contains(&self, timestamp: CqlTimestamp) -> bool {
+        timestamp.0 >= self.start().0 && self.end().is_none_or(|end| timestamp.0 < end.0)
+    }
+
+    /// The day containing the start of the bucket, for buckets stored in `date` columns.
+    ///
+    /// Returns `None` if the day can't be represented as a CQL `date`.
+    pub fn start_date(&self) -> Option<CqlDate> {
+        let days = self.start().0.div_euclid(MILLIS_PER_DAY);
+        u32::try_from(days + (1 << 31)).ok().map(CqlDate)
+    }
+}
+
+/// Configures a read of a time range spanning multiple buckets.
+///
+/// The statement selects rows of a single bucket. It is executed once per bucket
+/// of the time range, with values computed from the bucket, and at most
+/// [`concurrency`](BucketedRead::concurrency) buckets are queried at the same time.
+///
+/// Rows are returned bucket by bucket, in chronological order of the buckets
+/// (or reverse chronological with [`descending`](BucketedRead::descending)).
+/// Within a bucket, rows come in the order returned by the database.
+#[derive(Clone, Debug)]
+pub struct BucketedRead {
+    statement: PreparedStatement,
+    bucketing: TimeBucketing,
+    from: CqlTimestamp,
+    to: CqlTimestamp,
+    concurrency: NonZeroUsize,
+    descending: bool,
+}
+
+impl BucketedRead {
+    /// Creates a read of the buckets covering timestamps from `from` to `to` (both inclusive).
+    ///
+    /// Only the buckets are derived from the time range; to skip rows outside of it
+    /// in the first and the last bucket, restrict the statement's clustering key
+    /// and bind the bounds along with the bucket.
+    pub fn new(
+        statement: PreparedStatement,
+        bucketing: TimeBucketing,
+        from: CqlTimestamp,
+        to: CqlTimestamp,
+    ) -> Self {
+        Self {
+            statement,
+            bucketing,
+            from,
+            to,
+            concurrency: DEFAULT_CONCURRENCY,
+            descending: false,
+        }
+    }
+
+    /// Sets the maximal number of buckets queried at the same time.
+    ///
+    /// Buckets are queried ahead of the one whose rows are being returned.
+    /// The default is 4.
+    pub fn concurrency(mut self, concurrency: NonZeroUsize) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+
+    /// Returns the buckets from the latest to the earliest one.
+    ///
+    /// Combined with a descending clustering order, rows are returned newest first.
+    pub fn descending(mut self) -> Self {
+        self.descending = true;
+        self
+    }
+
+    /// Starts the read, executing the statement with values returned by `values`
+    /// for each bucket.
+    ///
+    /// Fails if the selected columns can't be deserialized to `RowT`.
+    pub fn rows_stream<'a, RowT, V>(
+        self,
+        session: &'a Session,
+        values: impl Fn(TimeBucket) -> V + Send + 'a,
+    ) -> Result<BucketedRowsStream<'a, RowT>, BucketedReadError>
+    where
+        RowT: for<'frame, 'metadata> DeserializeRow<'frame, 'metadata> + Send + 'a,
+        V: SerializeRow + Send + 'a,
+    {
+        RowT::type_check(
+            self.statement
+                .get_current_result_set_col_specs()
+                .get()
+                .as_slice(),
+        )?;
+
+        let buckets = self.bucketing.buckets(self.from, self.to);
+        let buckets: Box<dyn Iterator<Item = TimeBucket> + Send> = if self.descending {
+            Box::new(buckets.rev())
+        } else {
+            Box::new(buckets)
+        };
+
+        let statement = self.statement;
+        let inner = stream::iter(buckets)
+            .map(move |bucket| {
+                let statement = statement.clone();
+                let values = values(bucket);
+                async move {
+                    let pager = session.execute_iter(statement, values).await?;
+                    let rows = pager.rows_stream::<RowT>()?;
+                    Ok::<_, BucketedReadError>(rows.map_err(BucketedReadError::from))
+                }
+            })
+            .buffered(self.concurrency.get())
+            .try_flatten()
+            // End the stream after the first error.
+            .scan(false, |failed, row| {
+                if *failed {
+                    return future::ready(None);
+                }
+                *failed = row.is_err();
+                future::ready(Some(row))
+            });
+
+        Ok(BucketedRowsStream {
+            inner: inner.boxed(),
+        })
+    }
+}
+
+/// An error returned by [BucketedRead].
+#[derive(Error, Debug, Clone)]
+#[non_exhaustive]
+// Check triggers because all variants end with "Error".
+#[expect(clippy::enum_variant_names)]
+pub enum BucketedReadError {
+    /// Failed to start querying a bucket.
+    #[error("Failed to query a bucket: {0}")]
+    PagerExecutionError(#[from] PagerExecutionError),
+
+    /// Failed to type check the selected columns.
+    #[error("Failed to type check the selected columns: {0}")]
+    TypeCheckError(#[from] TypeCheckError),
+
+    /// Failed to fetch or deserialize a row of a bucket.
+    #[error("Failed to fetch a row of a bucket: {0}")]
+    NextRowError(#[from] NextRowError),
+}
+
+/// Stream of rows of a [BucketedRead], returned by [BucketedRead::rows_stream].
+///
+/// The stream ends after the first error.
+pub struct BucketedRowsStream<'a, RowT> {
+    inner: BoxStream<'a, Result<RowT, BucketedReadError>>,
+}
+
+impl<RowT> Stream for BucketedRowsStream<'_, RowT> {
+    type Item = Result<RowT, BucketedReadError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+impl<RowT> std::fmt::Debug for BucketedRowsStream<'_, RowT> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BucketedRowsStream").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use scylla_cql::value::{CqlDate, CqlTimestamp};
+
+    use super::TimeBucketing;
+
+    #[test]
+    fn test_bucket_of() {
+        let bucketing = TimeBucketing::new(Duration::from_millis(10));
+
+        let bucket = bucketing.bucket_of(CqlTimestamp(25));
+        assert_eq!(bucket.index(), 2);
+        assert_eq!(bucket.start(), CqlTimestamp(20));
+        assert_eq!(bucket.end(), Some(CqlTimestamp(30)));
+        assert!(bucket.contains(CqlTimestamp(20)));
+        assert!(bucket.contains(CqlTimestamp(29)));
+        assert!(!bucket.contains(CqlTimestamp(30)));
+        assert!(!bucket.contains(CqlTimestamp(19)));
+        assert_eq!(bucketing.bucket_of(CqlTimestamp(20)), bucket);
+        assert_ne!(bucketing.bucket_of(CqlTimestamp(30)), bucket);
+
+        // Timestamps before the epoch are rounded down as well.
+        let bucket = bucketing.bucket_of(CqlTimestamp(-1));
+        assert_eq!(bucket.index(), -1);
+        assert_eq!(bucket.start(), CqlTimestamp(-10));
+    }
+
+    #[test]
+    fn test_bucket_bounds_saturate() {
+        let bucketing = TimeBucketing::new(Duration::from_millis(10));
+
+        // The bucket of the earliest timestamp starts before it.
+        let bucket = bucketing.bucket_of(CqlTimestamp(i64::MIN + 1));
+        assert_eq!(bucket.start(), CqlTimestamp(i64::MIN));
+        assert_eq!(bucket.end(), Some(CqlTimestamp(i64::MIN + 8)));
+        assert_eq!(bucket.start_date(), None);
+        assert!(bucket.contains(CqlTimestamp(i64::MIN)));
+
+        // The bucket of the latest timestamp has no end.
+        let bucket = bucketing.bucket_of(CqlTimestamp(i64::MAX));
+        assert_eq!(bucket.start(), CqlTimestamp(i64::MAX - 7));
+        assert_eq!(bucket.end(), None);
+        assert!(bucket.contains(CqlTimestamp(i64::MAX)));
+        assert!(!bucket.contains(CqlTimestamp(i64::MAX - 8)));
+    }
+
+    #[test]
+    fn test_buckets() {
+        let bucketing = TimeBucketing::new(Duration::from_millis(10));
+        let starts = |from, to| {
+            bucketing
+                .buckets(CqlTimestamp(from), CqlTimestamp(to))
+                .map(|bucket| bucket.start().0)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(starts(5, 5), [0]);
+        assert_eq!(starts(-5, 20), [-10, 0, 10, 20]);
+        assert_eq!(starts(20, 5), Vec::<i64>::new());
+        assert_eq!(
+            bucketing
+                .buckets(CqlTimestamp(0), CqlTimestamp(29))
+                .rev()
+                .map(|bucket| bucket.index())
+                .collect::<Vec<_>>(),
+            [2, 1, 0]
+        );
+    }
+
+    #[test]
+    fn test_start_date() {
+        let daily = TimeBucketing::daily();
+        assert_eq!(
+            daily.bucket_of(CqlTimestamp(0)).start_date(),
+            Some(CqlDate(1 << 31))
+        );
+        assert_eq!(
+            daily.bucket_of(CqlTimestamp(-1)).start_date(),
+            Some(CqlDate((1 << 31) - 1))
+        );
+        assert_eq!(
+            TimeBucketing::hourly()
+                .bucket_of(CqlTimestamp(36 * 60 * 60 * 1000))
+                .start_date(),
+            Some(CqlDate((1 << 31) + 1))
+        );
+    }
+}
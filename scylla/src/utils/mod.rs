@@ -2,3 +2,5 @@
 pub(crate) mod test_utils;
 
 pub(crate) mod safe_format;
+
+pub(crate) mod runtime;
@@ -0,0 +1,656 @@
+//! Thin layer over the async runtime which drives the driver's tasks,
+//! timers and sockets.
+//!
+//! By default, the driver runs on tokio. With the `smol-2` feature enabled,
+//! the driver can also be used outside of a tokio runtime: then tasks are spawned
+//! on smol's global executor, timers and sockets are driven by async-io, and
+//! blocking work is offloaded to the `blocking` thread pool. This allows using
+//! the driver in applications which run on smol, async-std or any other executor.
+//!
+//! The backend is chosen on each call: tokio is used whenever the caller runs
+//! within a tokio runtime, so enabling the feature changes nothing for tokio
+//! applications (and tokio's paused clock keeps driving timers in tests).
+//! Tasks spawned by the driver run on the same backend as their parent,
+//! so a session created within a tokio runtime consistently uses tokio.
+//!
+//! Only the parts of tokio which actually depend on a tokio runtime are abstracted
+//! here. Synchronization primitives (`tokio::sync`), I/O traits and `tokio::select!`
+//! work on any executor, so the rest of the driver keeps using them directly.
+
+use std::fmt;
+
+#[cfg(not(feature = "smol-2"))]
+pub(crate) use tokio_backend::*;
+
+#[cfg(feature = "smol-2")]
+pub(crate) use dispatch::*;
+
+/// Error returned by [timeout] and [timeout_at] when the deadline passes
+/// before the future completes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Elapsed;
+
+impl fmt::Display for Elapsed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("deadline has elapsed")
+    }
+}
+
+impl std::error::Error for Elapsed {}
+
+mod tokio_backend {
+    use std::future::Future;
+    use std::io;
+    use std::net::SocketAddr;
+    use std::time::Duration;
+
+    use futures::FutureExt;
+    use socket2::SockRef;
+    use tokio::time::Instant;
+
+    use super::Elapsed;
+
+    pub(crate) type TcpStream = tokio::net::TcpStream;
+
+    /// Spawns a task which runs in the background until completion.
+    pub(crate) fn spawn<F>(future: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        tokio::task::spawn(future);
+    }
+
+    /// Spawns a task whose result can be awaited with the returned handle.
+    /// Dropping the handle detaches the task.
+    pub(crate) fn spawn_with_handle<F>(future: F) -> JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        JoinHandle(tokio::task::spawn(future))
+    }
+
+    pub(crate) struct JoinHandle<T>(tokio::task::JoinHandle<T>);
+
+    impl<T> JoinHandle<T> {
+        /// Waits for the task to finish. If the task panicked, the panic is propagated.
+        /// Returns `None` if the task was cancelled, which happens when the runtime
+        /// is being shut down.
+        pub(crate) async fn join(self) -> Option<T> {
+            match self.0.await {
+                Ok(output) => Some(output),
+                Err(join_error) => match join_error.try_into_panic() {
+                    Ok(panic_payload) => std::panic::resume_unwind(panic_payload),
+                    Err(_) => None,
+                },
+            }
+        }
+    }
+
+    /// Runs a blocking, CPU-heavy computation on a thread dedicated to such work.
+    pub(crate) async fn spawn_blocking<F, R>(f: F) -> R
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        match tokio::task::spawn_blocking(f).await {
+            Ok(result) => result,
+            Err(join_error) => std::panic::resume_unwind(join_error.into_panic()),
+        }
+    }
+
+    // The functions below return plain futures instead of being `async`,
+    // so that they do not add another level of nesting to the (already deep)
+    // futures of the driver.
+
+    pub(crate) fn yield_now() -> impl Future<Output = ()> {
+        tokio::task::yield_now()
+    }
+
+    pub(crate) fn sleep(duration: Duration) -> impl Future<Output = ()> {
+        tokio::time::sleep(duration)
+    }
+
+    pub(crate) fn sleep_until(deadline: Instant) -> impl Future<Output = ()> {
+        tokio::time::sleep_until(deadline)
+    }
+
+    pub(crate) fn timeout<F: Future>(
+        duration: Duration,
+        future: F,
+    ) -> impl Future<Output = Result<F::Output, Elapsed>> {
+        tokio::time::timeout(duration, future).map(|result| result.map_err(|_| Elapsed))
+    }
+
+    pub(crate) fn timeout_at<F: Future>(
+        deadline: Instant,
+        future: F,
+    ) -> impl Future<Output = Result<F::Output, Elapsed>> {
+        tokio::time::timeout_at(deadline, future).map(|result| result.map_err(|_| Elapsed))
+    }
+
+    /// Ticks periodically. The first tick completes immediately.
+    /// If a tick is missed, the following ones are delayed, so that they are
+    /// still `period` apart, instead of bursting to catch up.
+    pub(crate) struct Interval(tokio::time::Interval);
+
+    pub(crate) fn interval(period: Duration) -> Interval {
+        let mut interval = tokio::time::interval(period);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        Interval(interval)
+    }
+
+    impl Interval {
+        /// Waits for the next tick. Cancel safe.
+        pub(crate) async fn tick(&mut self) {
+            self.0.tick().await;
+        }
+    }
+
+    /// Opens a TCP connection from `bind_address` to `connect_address`.
+    /// `configure` is called on the socket before it is bound.
+    pub(crate) async fn connect_tcp(
+        connect_address: SocketAddr,
+        bind_address: SocketAddr,
+        configure: impl FnOnce(&SockRef<'_>) -> io::Result<()>,
+    ) -> io::Result<TcpStream> {
+        let socket = match connect_address {
+            SocketAddr::V4(_) => tokio::net::TcpSocket::new_v4()?,
+            SocketAddr::V6(_) => tokio::net::TcpSocket::new_v6()?,
+        };
+        configure(&SockRef::from(&socket))?;
+        socket.bind(bind_address)?;
+        socket.connect(connect_address).await
+    }
+
+    /// Gives access to options of the socket underlying the stream.
+    pub(crate) fn sock_ref(stream: &TcpStream) -> SockRef<'_> {
+        SockRef::from(stream)
+    }
+
+    /// Resolves `host`, which is either "hostname:port", or a hostname
+    /// together with a port.
+    pub(crate) async fn lookup_host(
+        host: impl tokio::net::ToSocketAddrs,
+    ) -> io::Result<Vec<SocketAddr>> {
+        Ok(tokio::net::lookup_host(host).await?.collect())
+    }
+}
+
+#[cfg(feature = "smol-2")]
+mod smol_backend {
+    use std::future::Future;
+    use std::io;
+    use std::net::SocketAddr;
+    use std::time::Duration;
+
+    use futures::FutureExt;
+    use smol::{Async, Timer};
+    use socket2::{Domain, Protocol, SockRef, Socket, Type};
+    use tokio::time::Instant;
+    use tokio_util::compat::{Compat, FuturesAsyncReadCompatExt};
+
+    use super::Elapsed;
+
+    /// smol's sockets implement I/O traits of the `futures` crate,
+    /// so they are adapted to the tokio ones used by the rest of the driver.
+    pub(crate) type TcpStream = Compat<Async<std::net::TcpStream>>;
+
+    /// Spawns a task which runs in the background until completion.
+    pub(crate) fn spawn<F>(future: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        smol::spawn(future).detach();
+    }
+
+    /// Spawns a task whose result can be awaited with the returned handle.
+    /// Dropping the handle detaches the task.
+    pub(crate) fn spawn_with_handle<F>(future: F) -> JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        JoinHandle(Some(smol::spawn(future)))
+    }
+
+    // smol's tasks are cancelled when dropped, so the task is kept
+    // in an option, and detached in `drop` unless it was joined.
+    pub(crate) struct JoinHandle<T>(Option<smol::Task<T>>);
+
+    impl<T> JoinHandle<T> {
+        /// Waits for the task to finish. If the task panicked, the panic is propagated.
+        /// Returns `None` if the task was cancelled, which happens when the executor
+        /// is being shut down.
+        pub(crate) async fn join(mut self) -> Option<T> {
+            let task = self.0.take().expect("task is only taken when joined");
+            task.fallible().await
+        }
+    }
+
+    impl<T> Drop for JoinHandle<T> {
+        fn drop(&mut self) {
+            if let Some(task) = self.0.take() {
+                task.detach();
+            }
+        }
+    }
+
+    /// Runs a blocking, CPU-heavy computation on a thread dedicated to such work.
+    pub(crate) async fn spawn_blocking<F, R>(f: F) -> R
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        smol::unblock(f).await
+    }
+
+    // The functions below return plain futures instead of being `async`,
+    // so that they do not add another level of nesting to the (already deep)
+    // futures of the driver.
+
+    pub(crate) fn yield_now() -> impl Future<Output = ()> {
+        smol::future::yield_now()
+    }
+
+    pub(crate) fn sleep(duration: Duration) -> impl Future<Output = ()> {
+        Timer::after(duration).map(drop)
+    }
+
+    pub(crate) fn sleep_until(deadline: Instant) -> impl Future<Output = ()> {
+        Timer::at(deadline.into_std()).map(drop)
+    }
+
+    pub(crate) fn timeout<F: Future>(
+        duration: Duration,
+        future: F,
+    ) -> impl Future<Output = Result<F::Output, Elapsed>> {
+        race_with_timer(future, Timer::after(duration))
+    }
+
+    pub(crate) fn timeout_at<F: Future>(
+        deadline: Instant,
+        future: F,
+    ) -> impl Future<Output = Result<F::Output, Elapsed>> {
+        race_with_timer(future, Timer::at(deadline.into_std()))
+    }
+
+    // The future is polled first, so that a future which is ready
+    // is not reported as timed out, the same as in tokio.
+    fn race_with_timer<F: Future>(
+        future: F,
+        timer: Timer,
+    ) -> impl Future<Output = Result<F::Output, Elapsed>> {
+        smol::future::or(future.map(Ok), timer.map(|_| Err(Elapsed)))
+    }
+
+    /// Ticks periodically. The first tick completes immediately.
+    /// If a tick is missed, the following ones are delayed, so that they are
+    /// still `period` apart, instead of bursting to catch up.
+    pub(crate) struct Interval {
+        period: Duration,
+        next_tick: std::time::Instant,
+    }
+
+    pub(crate) fn interval(period: Duration) -> Interval {
+        Interval {
+            period,
+            next_tick: std::time::Instant::now(),
+        }
+    }
+
+    impl Interval {
+        /// Waits for the next tick. Cancel safe.
+        pub(crate) async fn tick(&mut self) {
+            // The state is only updated after the timer fires,
+            // so dropping this future does not lose the tick.
+            let now = Timer::at(self.next_tick).await;
+            self.next_tick = now + self.period;
+        }
+    }
+
+    /// Opens a TCP connection from `bind_address` to `connect_address`.
+    /// `configure` is called on the socket before it is bound.
+    pub(crate) async fn connect_tcp(
+        connect_address: SocketAddr,
+        bind_address: SocketAddr,
+        configure: impl FnOnce(&SockRef<'_>) -> io::Result<()>,
+    ) -> io::Result<TcpStream> {
+        let socket = Socket::new(
+            Domain::for_address(connect_address),
+            Type::STREAM,
+            Some(Protocol::TCP),
+        )?;
+        configure(&SockRef::from(&socket))?;
+        socket.bind(&bind_address.into())?;
+
+        socket.set_nonblocking(true)?;
+        match socket.connect(&connect_address.into()) {
+            Ok(()) => {}
+            #[cfg(unix)]
+            Err(err) if err.raw_os_error() == Some(libc::EINPROGRESS) => {}
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => {}
+            Err(err) => return Err(err),
+        }
+
+        // The socket becomes writable once the connection is established or fails.
+        let stream = Async::new_nonblocking(std::net::TcpStream::from(socket))?;
+        stream.writable().await?;
+        match stream.get_ref().take_error()? {
+            None => Ok(stream.compat()),
+            Some(err) => Err(err),
+        }
+    }
+
+    /// Gives access to options of the socket underlying the stream.
+    pub(crate) fn sock_ref(stream: &TcpStream) -> SockRef<'_> {
+        SockRef::from(stream.get_ref().get_ref())
+    }
+
+    /// Resolves `host`, which is either "hostname:port", or a hostname
+    /// together with a port.
+    pub(crate) async fn lookup_host(
+        host: impl smol::net::AsyncToSocketAddrs,
+    ) -> io::Result<Vec<SocketAddr>> {
+        smol::net::resolve(host).await
+    }
+}
+
+#[cfg(feature = "smol-2")]
+mod dispatch {
+    use std::future::Future;
+    use std::io;
+    use std::net::SocketAddr;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+    use std::time::Duration;
+
+    use futures::future::Either;
+    use socket2::SockRef;
+    use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+    use tokio::time::Instant;
+
+    use super::{Elapsed, smol_backend, tokio_backend};
+
+    fn within_tokio_runtime() -> bool {
+        tokio::runtime::Handle::try_current().is_ok()
+    }
+
+    /// A socket of either backend, depending on the one which opened it.
+    #[derive(Debug)]
+    pub(crate) enum TcpStream {
+        Tokio(tokio_backend::TcpStream),
+        Smol(smol_backend::TcpStream),
+    }
+
+    impl AsyncRead for TcpStream {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<io::Result<()>> {
+            match self.get_mut() {
+                TcpStream::Tokio(stream) => Pin::new(stream).poll_read(cx, buf),
+                TcpStream::Smol(stream) => Pin::new(stream).poll_read(cx, buf),
+            }
+        }
+    }
+
+    impl AsyncWrite for TcpStream {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            match self.get_mut() {
+                TcpStream::Tokio(stream) => Pin::new(stream).poll_write(cx, buf),
+                TcpStream::Smol(stream) => Pin::new(stream).poll_write(cx, buf),
+            }
+        }
+
+        fn poll_write_vectored(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            bufs: &[io::IoSlice<'_>],
+        ) -> Poll<io::Result<usize>> {
+            match self.get_mut() {
+                TcpStream::Tokio(stream) => Pin::new(stream).poll_write_vectored(cx, bufs),
+                TcpStream::Smol(stream) => Pin::new(stream).poll_write_vectored(cx, bufs),
+            }
+        }
+
+        fn is_write_vectored(&self) -> bool {
+            match self {
+                TcpStream::Tokio(stream) => stream.is_write_vectored(),
+                TcpStream::Smol(stream) => stream.is_write_vectored(),
+            }
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            match self.get_mut() {
+                TcpStream::Tokio(stream) => Pin::new(stream).poll_flush(cx),
+                TcpStream::Smol(stream) => Pin::new(stream).poll_flush(cx),
+            }
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            match self.get_mut() {
+                TcpStream::Tokio(stream) => Pin::new(stream).poll_shutdown(cx),
+                TcpStream::Smol(stream) => Pin::new(stream).poll_shutdown(cx),
+            }
+        }
+    }
+
+    /// Spawns a task which runs in the background until completion.
+    pub(crate) fn spawn<F>(future: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        if within_tokio_runtime() {
+            tokio_backend::spawn(future)
+        } else {
+            smol_backend::spawn(future)
+        }
+    }
+
+    /// Spawns a task whose result can be awaited with the returned handle.
+    /// Dropping the handle detaches the task.
+    pub(crate) fn spawn_with_handle<F>(future: F) -> JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        if within_tokio_runtime() {
+            JoinHandle::Tokio(tokio_backend::spawn_with_handle(future))
+        } else {
+            JoinHandle::Smol(smol_backend::spawn_with_handle(future))
+        }
+    }
+
+    pub(crate) enum JoinHandle<T> {
+        Tokio(tokio_backend::JoinHandle<T>),
+        Smol(smol_backend::JoinHandle<T>),
+    }
+
+    impl<T> JoinHandle<T> {
+        /// Waits for the task to finish. If the task panicked, the panic is propagated.
+        /// Returns `None` if the task was cancelled, which happens when the runtime
+        /// is being shut down.
+        pub(crate) async fn join(self) -> Option<T> {
+            match self {
+                JoinHandle::Tokio(handle) => handle.join().await,
+                JoinHandle::Smol(handle) => handle.join().await,
+            }
+        }
+    }
+
+    /// Runs a blocking, CPU-heavy computation on a thread dedicated to such work.
+    pub(crate) async fn spawn_blocking<F, R>(f: F) -> R
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        if within_tokio_runtime() {
+            tokio_backend::spawn_blocking(f).await
+        } else {
+            smol_backend::spawn_blocking(f).await
+        }
+    }
+
+    pub(crate) fn yield_now() -> impl Future<Output = ()> {
+        if within_tokio_runtime() {
+            Either::Left(tokio_backend::yield_now())
+        } else {
+            Either::Right(smol_backend::yield_now())
+        }
+    }
+
+    pub(crate) fn sleep(duration: Duration) -> impl Future<Output = ()> {
+        if within_tokio_runtime() {
+            Either::Left(tokio_backend::sleep(duration))
+        } else {
+            Either::Right(smol_backend::sleep(duration))
+        }
+    }
+
+    pub(crate) fn sleep_until(deadline: Instant) -> impl Future<Output = ()> {
+        if within_tokio_runtime() {
+            Either::Left(tokio_backend::sleep_until(deadline))
+        } else {
+            Either::Right(smol_backend::sleep_until(deadline))
+        }
+    }
+
+    pub(crate) fn timeout<F: Future>(
+        duration: Duration,
+        future: F,
+    ) -> impl Future<Output = Result<F::Output, Elapsed>> {
+        if within_tokio_runtime() {
+            Either::Left(tokio_backend::timeout(duration, future))
+        } else {
+            Either::Right(smol_backend::timeout(duration, future))
+        }
+    }
+
+    pub(crate) fn timeout_at<F: Future>(
+        deadline: Instant,
+        future: F,
+    ) -> impl Future<Output = Result<F::Output, Elapsed>> {
+        if within_tokio_runtime() {
+            Either::Left(tokio_backend::timeout_at(deadline, future))
+        } else {
+            Either::Right(smol_backend::timeout_at(deadline, future))
+        }
+    }
+
+    /// Ticks periodically. The first tick completes immediately.
+    /// If a tick is missed, the following ones are delayed, so that they are
+    /// still `period` apart, instead of bursting to catch up.
+    pub(crate) enum Interval {
+        Tokio(tokio_backend::Interval),
+        Smol(smol_backend::Interval),
+    }
+
+    pub(crate) fn interval(period: Duration) -> Interval {
+        if within_tokio_runtime() {
+            Interval::Tokio(tokio_backend::interval(period))
+        } else {
+            Interval::Smol(smol_backend::interval(period))
+        }
+    }
+
+    impl Interval {
+        /// Waits for the next tick. Cancel safe.
+        pub(crate) async fn tick(&mut self) {
+            match self {
+                Interval::Tokio(interval) => interval.tick().await,
+                Interval::Smol(interval) => interval.tick().await,
+            }
+        }
+    }
+
+    /// Opens a TCP connection from `bind_address` to `connect_address`.
+    /// `configure` is called on the socket before it is bound.
+    pub(crate) async fn connect_tcp(
+        connect_address: SocketAddr,
+        bind_address: SocketAddr,
+        configure: impl FnOnce(&SockRef<'_>) -> io::Result<()>,
+    ) -> io::Result<TcpStream> {
+        if within_tokio_runtime() {
+            tokio_backend::connect_tcp(connect_address, bind_address, configure)
+                .await
+                .map(TcpStream::Tokio)
+        } else {
+            smol_backend::connect_tcp(connect_address, bind_address, configure)
+                .await
+                .map(TcpStream::Smol)
+        }
+    }
+
+    /// Gives access to options of the socket underlying the stream.
+    pub(crate) fn sock_ref(stream: &TcpStream) -> SockRef<'_> {
+        match stream {
+            TcpStream::Tokio(stream) => tokio_backend::sock_ref(stream),
+            TcpStream::Smol(stream) => smol_backend::sock_ref(stream),
+        }
+    }
+
+    /// Resolves `host`, which is either "hostname:port", or a hostname
+    /// together with a port.
+    pub(crate) async fn lookup_host<H>(host: H) -> io::Result<Vec<SocketAddr>>
+    where
+        H: tokio::net::ToSocketAddrs + smol::net::AsyncToSocketAddrs,
+    {
+        if within_tokio_runtime() {
+            tokio_backend::lookup_host(host).await
+        } else {
+            smol_backend::lookup_host(host).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{Elapsed, connect_tcp, interval, sock_ref, timeout};
+    use crate::test_utils::setup_tracing;
+
+    async fn exercise_backend() {
+        let never = std::future::pending::<()>();
+        assert_eq!(
+            timeout(Duration::from_millis(10), never).await,
+            Err(Elapsed)
+        );
+        assert_eq!(timeout(Duration::from_secs(10), async { 42 }).await, Ok(42));
+
+        let start = std::time::Instant::now();
+        let mut interval = interval(Duration::from_millis(20));
+        interval.tick().await;
+        interval.tick().await;
+        assert!(start.elapsed() >= Duration::from_millis(20));
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let stream = connect_tcp(address, "127.0.0.1:0".parse().unwrap(), |sf| {
+            sf.set_nodelay(true)
+        })
+        .await
+        .unwrap();
+        assert!(sock_ref(&stream).nodelay().unwrap());
+        listener.accept().unwrap();
+    }
+
+    #[tokio::test]
+    async fn tokio_backend() {
+        setup_tracing();
+        exercise_backend().await;
+    }
+
+    #[cfg(feature = "smol-2")]
+    #[test]
+    fn smol_backend_runs_without_tokio_runtime() {
+        setup_tracing();
+        smol::block_on(exercise_backend());
+    }
+}
@@ -1,5 +1,3 @@
-use scylla_cql::frame::response::error::DbError;
-use tracing::{error, warn};
 use tracing_subscriber::Layer;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
@@ -9,12 +7,16 @@ use crate::client::session::Session;
 use crate::client::session_builder::{GenericSessionBuilder, SessionBuilderKind};
 use crate::cluster::ClusterState;
 use crate::cluster::NodeRef;
-use crate::errors::{ExecutionError, RequestAttemptError};
+use crate::cluster::metadata::{Metadata, Peer};
+use crate::errors::ExecutionError;
 use crate::network::Connection;
+use crate::policies::host_filter::HostFilter;
 use crate::policies::load_balancing::{FallbackPlan, LoadBalancingPolicy, RoutingInfo};
-use crate::policies::retry::{RequestInfo, RetryDecision, RetryPolicy, RetrySession};
-use crate::routing::Shard;
+use crate::policies::retry::SchemaChangeRetryPolicy;
+use crate::routing::locator::test::{id_to_invalid_addr, mock_metadata_for_token_aware_tests};
+use crate::routing::{Shard, Token};
 use crate::statement::unprepared::Statement;
+use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use std::{num::NonZeroU32, time::Duration};
@@ -22,6 +24,7 @@ use std::{
     sync::atomic::{AtomicUsize, Ordering},
     time::{SystemTime, UNIX_EPOCH},
 };
+use uuid::Uuid;
 
 static UNIQUE_COUNTER: AtomicUsize = AtomicUsize::new(0);
 
@@ -156,59 +159,13 @@ impl LoadBalancingPolicy for SchemaQueriesLBP {
     }
 }
 
-#[derive(Debug, Default)]
-struct SchemaQueriesRetrySession {
-    count: usize,
-}
-
-impl RetrySession for SchemaQueriesRetrySession {
-    fn decide_should_retry(&mut self, request_info: RequestInfo) -> RetryDecision {
-        match request_info.error {
-            RequestAttemptError::DbError(DbError::ServerError, s)
-                if s == "Failed to apply group 0 change due to concurrent modification" =>
-            {
-                self.count += 1;
-                // Give up if there are many failures.
-                // In this case we really should do something about it in the
-                // core, because it is absurd for DDL queries to fail this often.
-                if self.count >= 10 {
-                    error!(
-                        "Received TENTH(!) group 0 concurrent modification error during DDL. Please fix ScyllaDB Core."
-                    );
-                    RetryDecision::DontRetry
-                } else {
-                    warn!(
-                        "Received group 0 concurrent modification error during DDL. Performing retry #{}.",
-                        self.count
-                    );
-                    RetryDecision::RetrySameTarget(None)
-                }
-            }
-            _ => RetryDecision::DontRetry,
-        }
-    }
-
-    fn reset(&mut self) {
-        *self = Default::default()
-    }
-}
-
-#[derive(Debug)]
-struct SchemaQueriesRetryPolicy;
-
-impl RetryPolicy for SchemaQueriesRetryPolicy {
-    fn new_session(&self) -> Box<dyn RetrySession> {
-        Box::new(SchemaQueriesRetrySession::default())
-    }
-}
-
 fn apply_ddl_lbp(query: &mut Statement) {
     let policy = query
         .get_execution_profile_handle()
         .map(|profile| profile.pointee_to_builder())
         .unwrap_or_default()
         .load_balancing_policy(Arc::new(SchemaQueriesLBP))
-        .retry_policy(Arc::new(SchemaQueriesRetryPolicy))
+        .retry_policy(Arc::new(SchemaChangeRetryPolicy::new()))
         .build();
     query.set_execution_profile_handle(Some(policy.into_handle()));
 }
@@ -226,7 +183,7 @@ impl PerformDDL for Session {
     async fn ddl(&self, query: impl Into<Statement> + Send) -> Result<(), ExecutionError> {
         let mut query = query.into();
         apply_ddl_lbp(&mut query);
-        self.query_unpaged(query, &[]).await.map(|_| ())
+        self.execute_schema_change(query).await.map(|_| ())
     }
 }
 
@@ -250,3 +207,100 @@ impl PerformDDL for Connection {
             .map_err(ExecutionError::LastAttemptError)
     }
 }
+
+/// Builds a [ClusterState] out of the given metadata. Unlike the `mock_cluster_state_*`
+/// functions, it does not mark the nodes as connected.
+pub(crate) struct MockClusterStateBuilder<'a> {
+    metadata: Metadata,
+    internal_requests_host_filter: Option<&'a dyn HostFilter>,
+    previous: Option<&'a ClusterState>,
+}
+
+impl<'a> MockClusterStateBuilder<'a> {
+    pub(crate) fn new(metadata: Metadata) -> Self {
+        Self {
+            metadata,
+            internal_requests_host_filter: None,
+            previous: None,
+        }
+    }
+
+    pub(crate) fn internal_requests_host_filter(mut self, host_filter: &'a dyn HostFilter) -> Self {
+        self.internal_requests_host_filter = Some(host_filter);
+        self
+    }
+
+    /// Builds the state as an update of `previous`, reusing its nodes,
+    /// replica locator and ring version, like a metadata refresh does.
+    pub(crate) fn previous(mut self, previous: &'a ClusterState) -> Self {
+        self.previous = Some(previous);
+        self
+    }
+
+    pub(crate) async fn build(self) -> ClusterState {
+        let (connectivity_events_sender, _) = tokio::sync::mpsc::unbounded_channel();
+        let mut state = ClusterState::new(
+            self.metadata,
+            &Default::default(),
+            self.previous
+                .map_or(&HashMap::new(), |previous| &previous.known_peers),
+            &mut |_, _| (),
+            &None,
+            None,
+            self.internal_requests_host_filter,
+            &connectivity_events_sender,
+            &Default::default(),
+            self.previous.map(|previous| &previous.locator),
+            &HashMap::new(),
+            #[cfg(feature = "metrics")]
+            &Default::default(),
+        )
+        .await;
+        if let Some(previous) = self.previous {
+            state.inherit_ring_version(previous);
+        }
+        state
+    }
+}
+
+// based on locator mock cluster
+pub(crate) async fn mock_cluster_state_for_token_aware_tests() -> ClusterState {
+    let state = MockClusterStateBuilder::new(mock_metadata_for_token_aware_tests())
+        .build()
+        .await;
+
+    for node in state.get_nodes_info() {
+        node.use_enabled_as_connected();
+    }
+
+    state
+}
+
+// creates ClusterState with info about 5 nodes living in 2 different datacenters
+// ring field is minimal, not intended to influence the tests
+pub(crate) async fn mock_cluster_state_for_token_unaware_tests() -> ClusterState {
+    let peers = [("eu", 1), ("eu", 2), ("eu", 3), ("us", 4), ("us", 5)]
+        .iter()
+        .map(|(dc, id)| Peer {
+            datacenter: Some(dc.to_string()),
+            rack: None,
+            address: id_to_invalid_addr(*id),
+            tokens: vec![Token::new(*id as i64 * 100)],
+            host_id: Uuid::new_v4(),
+            release_version: None,
+        })
+        .collect::<Vec<_>>();
+
+    let info = Metadata {
+        peers,
+        keyspaces: HashMap::new(),
+    };
+
+    let state = MockClusterStateBuilder::new(info).build().await;
+
+    for node in state.get_nodes_info() {
+        node.use_enabled_as_connected();
+    }
+
+    state
+}
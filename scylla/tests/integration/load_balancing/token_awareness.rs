@@ -1,3 +1,6 @@
+use assert_matches::assert_matches;
+use scylla::statement::prepared::InListRoutingAnalysis;
+
 use crate::utils::{
     PerformDDL as _, create_new_session_builder, scylla_supports_tablets, setup_tracing,
     unique_keyspace_name,
@@ -65,3 +68,72 @@ async fn test_token_awareness() {
 
     session.ddl(format!("DROP KEYSPACE {ks}")).await.unwrap();
 }
+
+#[tokio::test]
+async fn test_token_awareness_for_in_list() {
+    setup_tracing();
+    let session = create_new_session_builder().build().await.unwrap();
+    let ks = unique_keyspace_name();
+
+    let mut create_ks = format!(
+        "CREATE KEYSPACE IF NOT EXISTS {ks} WITH REPLICATION = {{'class' : 'NetworkTopologyStrategy', 'replication_factor' : 1}}"
+    );
+    if scylla_supports_tablets(&session).await {
+        create_ks += " AND TABLETS = {'enabled': false}"
+    }
+
+    session.ddl(create_ks).await.unwrap();
+    session
+        .ddl(format!(
+            "CREATE TABLE IF NOT EXISTS {ks}.t (a int primary key)"
+        ))
+        .await
+        .unwrap();
+
+    let mut prepared_statement = session
+        .prepare(format!("SELECT a FROM {ks}.t WHERE a IN ?"))
+        .await
+        .unwrap();
+    prepared_statement.set_tracing(true);
+    assert!(!prepared_statement.is_token_aware());
+
+    let cluster_state = session.get_cluster_state();
+
+    // A single partition is always owned by a single replica set.
+    for key in 0..10i32 {
+        let values = (vec![key],);
+        let analysis = prepared_statement
+            .analyze_in_list_routing(&values, &cluster_state)
+            .unwrap();
+        assert_matches!(
+            analysis,
+            InListRoutingAnalysis::SingleReplicaSet { partitions: 1, .. }
+        );
+
+        let res = session
+            .execute_unpaged(&prepared_statement, values)
+            .await
+            .unwrap();
+        let tracing_info = session
+            .get_tracing_info(res.tracing_id().as_ref().unwrap())
+            .await
+            .unwrap();
+
+        // Verify that only one node was involved
+        assert_eq!(tracing_info.nodes().len(), 1);
+    }
+
+    // Many partitions are spread over all nodes.
+    if cluster_state.get_nodes_info().len() > 1 {
+        let values = ((0..100i32).collect::<Vec<_>>(),);
+        let analysis = prepared_statement
+            .analyze_in_list_routing(&values, &cluster_state)
+            .unwrap();
+        assert_matches!(
+            analysis,
+            InListRoutingAnalysis::MultipleReplicaSets { partitions: 100 }
+        );
+    }
+
+    session.ddl(format!("DROP KEYSPACE {ks}")).await.unwrap();
+}
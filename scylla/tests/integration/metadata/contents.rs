@@ -2,7 +2,10 @@ use std::sync::Arc;
 
 use itertools::Itertools as _;
 use scylla::{
-    cluster::metadata::{CollectionType, ColumnKind, ColumnType, NativeType, UserDefinedType},
+    cluster::metadata::{
+        CollectionType, ColumnKind, ColumnType, CompactionStrategy, IndexKind, NativeType,
+        UserDefinedType,
+    },
     value::Row,
 };
 
@@ -443,6 +446,10 @@ async fn test_table_partitioner_in_metadata() {
         "com.scylladb.dht.CDCPartitioner"
     );
 
+    let cdc_options = table.extensions.cdc.as_ref().unwrap();
+    assert!(cdc_options.enabled);
+    assert_eq!(cdc_table.extensions.cdc, None);
+
     session.ddl(format!("DROP KEYSPACE {ks}")).await.unwrap();
 }
 
@@ -505,6 +512,92 @@ async fn test_views_in_schema_info() {
     session.ddl(format!("DROP KEYSPACE {ks}")).await.unwrap();
 }
 
+#[tokio::test]
+#[cfg_attr(cassandra_tests, ignore)]
+async fn test_indexes_in_schema_info() {
+    setup_tracing();
+
+    let session = create_new_session_builder().build().await.unwrap();
+    let ks = unique_keyspace_name();
+
+    let mut create_ks = format!(
+        "CREATE KEYSPACE IF NOT EXISTS {ks} WITH REPLICATION = {{'class' : 'NetworkTopologyStrategy', 'replication_factor' : 1}}"
+    );
+    // Secondary indexes are backed by materialized views, which are not supported with tablets in Scylla 2025.1.
+    if scylla_supports_tablets(&session).await {
+        create_ks += " and TABLETS = { 'enabled': false}";
+    }
+    session.ddl(create_ks).await.unwrap();
+    session.use_keyspace(ks.clone(), false).await.unwrap();
+
+    session
+        .ddl("CREATE TABLE t(id int PRIMARY KEY, v int, w int)")
+        .await
+        .unwrap();
+    session.ddl("CREATE INDEX t_v_idx ON t(v)").await.unwrap();
+    session.ddl("CREATE MATERIALIZED VIEW mv AS SELECT * FROM t WHERE w IS NOT NULL PRIMARY KEY (w, id)").await.unwrap();
+
+    session.await_schema_agreement().await.unwrap();
+    session.refresh_metadata().await.unwrap();
+
+    let cluster_state = session.get_cluster_state();
+    let keyspace_meta = cluster_state.get_keyspace(&ks).unwrap();
+
+    let indexes = &keyspace_meta.tables["t"].indexes;
+    assert_eq!(indexes.keys().collect::<Vec<_>>(), vec!["t_v_idx"]);
+    let index = &indexes["t_v_idx"];
+    assert_eq!(index.kind, IndexKind::Composites);
+    assert_eq!(index.target(), Some("v"));
+
+    // The view backing the index is reported along with the explicitly created one.
+    let views_of_t = keyspace_meta
+        .views_of("t")
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>();
+    assert!(views_of_t.contains(&"mv"));
+
+    session.ddl(format!("DROP KEYSPACE {ks}")).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_table_options_in_schema_info() {
+    setup_tracing();
+
+    let session = create_new_session_builder().build().await.unwrap();
+    let ks = unique_keyspace_name();
+
+    session
+        .ddl(format!("CREATE KEYSPACE {ks} WITH REPLICATION = {{'class' : 'NetworkTopologyStrategy', 'replication_factor' : 1}}"))
+        .await
+        .unwrap();
+    session
+        .ddl(format!(
+            "CREATE TABLE {ks}.t(id int PRIMARY KEY, v int) WITH \
+            compaction = {{'class': 'LeveledCompactionStrategy'}} AND \
+            default_time_to_live = 3600 AND gc_grace_seconds = 7200 AND \
+            comment = 'options test'"
+        ))
+        .await
+        .unwrap();
+
+    session.await_schema_agreement().await.unwrap();
+    session.refresh_metadata().await.unwrap();
+
+    let cluster_state = session.get_cluster_state();
+    let options = &cluster_state.get_keyspace(&ks).unwrap().tables["t"].options;
+
+    assert_eq!(
+        options.compaction.strategy,
+        Some(CompactionStrategy::Leveled)
+    );
+    assert_eq!(options.default_time_to_live, Some(3600));
+    assert_eq!(options.gc_grace_seconds, Some(7200));
+    assert_eq!(options.comment.as_deref(), Some("options test"));
+    assert!(options.caching.keys.is_some());
+
+    session.ddl(format!("DROP KEYSPACE {ks}")).await.unwrap();
+}
+
 /// This test case indicates that we support enough CQL types to parse schema keyspace information.
 #[tokio::test]
 async fn test_fetch_system_keyspace() {
@@ -527,3 +620,30 @@ async fn test_fetch_system_keyspace() {
         .unwrap()
         .for_each(|_| ());
 }
+
+#[tokio::test]
+async fn test_refresh_metadata_keyspace() {
+    setup_tracing();
+    let session = create_new_session_builder().build().await.unwrap();
+    let ks = unique_keyspace_name();
+
+    session
+        .ddl(format!("CREATE KEYSPACE {ks} WITH REPLICATION = {{'class' : 'NetworkTopologyStrategy', 'replication_factor' : 1}}"))
+        .await
+        .unwrap();
+    session.refresh_metadata_keyspace(&ks).await.unwrap();
+    assert!(session.get_cluster_state().get_keyspace(&ks).is_some());
+
+    session
+        .ddl(format!("CREATE TABLE {ks}.t (a int PRIMARY KEY, b text)"))
+        .await
+        .unwrap();
+    session.refresh_metadata_keyspace(&ks).await.unwrap();
+    let cluster_state = session.get_cluster_state();
+    let columns = &cluster_state.get_keyspace(&ks).unwrap().tables["t"].columns;
+    assert_eq!(columns.keys().sorted().collect::<Vec<_>>(), vec!["a", "b"]);
+
+    session.ddl(format!("DROP KEYSPACE {ks}")).await.unwrap();
+    session.refresh_metadata_keyspace(&ks).await.unwrap();
+    assert!(session.get_cluster_state().get_keyspace(&ks).is_none());
+}
@@ -5,7 +5,8 @@ use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
 use futures::StreamExt;
 use scylla::errors::{RequestAttemptError, RequestError};
 use scylla::observability::history::{
-    AttemptResult, HistoryCollector, RequestHistoryResult, StructuredHistory, TimePoint,
+    AttemptResult, DetailedHistory, HistoryCollector, RequestHistoryResult, StructuredHistory,
+    TimePoint,
 };
 use scylla::statement::unprepared::Statement;
 use scylla::value::Row;
@@ -210,6 +211,35 @@ async fn failed_query_history() {
     );
 }
 
+#[tokio::test]
+async fn tagged_query_history() {
+    setup_tracing();
+    let session = create_new_session_builder().build().await.unwrap();
+
+    let mut query = Statement::new("SELECT * FROM system.local WHERE key='local'");
+    query.set_tag("service", "billing");
+    let history_collector = Arc::new(HistoryCollector::new());
+    query.set_history_listener(history_collector.clone());
+
+    // Tags survive preparation, so both requests below are tagged.
+    let prepared = session.prepare(query.clone()).await.unwrap();
+    session.query_unpaged(query, ()).await.unwrap();
+    let mut rows = session
+        .execute_iter(prepared, ())
+        .await
+        .unwrap()
+        .rows_stream::<Row>()
+        .unwrap();
+    while rows.next().await.is_some() {}
+
+    let history: DetailedHistory = history_collector.clone_detailed_history();
+    assert_eq!(history.requests.len(), 2);
+    for request in &history.requests {
+        assert_eq!(request.tags.get("service"), Some("billing"));
+    }
+    assert!(format!("{history}").contains("| tags: service=billing\n"));
+}
+
 #[tokio::test]
 async fn iterator_query_history() {
     setup_tracing();
@@ -8,5 +8,8 @@ mod pager;
 mod retries;
 mod schema_agreement;
 mod self_identity;
+mod system_views;
+#[cfg(feature = "tower-service-03")]
+mod tower;
 mod tracing;
 mod use_keyspace;
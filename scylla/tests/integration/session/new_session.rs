@@ -1,10 +1,13 @@
 use std::net::Ipv4Addr;
+use std::num::NonZeroUsize;
+use std::time::Duration;
 
 use crate::utils::{create_new_session_builder, find_local_ip_for_destination, setup_tracing};
 
 use assert_matches::assert_matches;
 use futures::FutureExt as _;
 use scylla::client::session_builder::SessionBuilder;
+use scylla::client::{PoolSize, PoolWarmup};
 use scylla::errors::{ConnectionError, ConnectionPoolError, MetadataError, NewSessionError};
 use tokio::net::TcpListener;
 
@@ -120,3 +123,156 @@ async fn test_connection_failure() {
         Err(err) => println!("Connection error (it was expected): {err:?}"),
     }
 }
+
+/// Make sure that Session::connect succeeds in degraded mode when the control connection
+/// fails to connect and degraded startup is allowed.
+#[tokio::test]
+async fn test_degraded_startup() {
+    setup_tracing();
+
+    // Create a dummy server which immediately closes the connection.
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let (fut, _handle) = async move {
+        loop {
+            let _ = listener.accept().await;
+        }
+    }
+    .remote_handle();
+    tokio::spawn(fut);
+
+    let session = SessionBuilder::new()
+        .known_node_addr(addr)
+        .allow_degraded_startup(true)
+        .build()
+        .await
+        .unwrap();
+    assert!(session.is_metadata_degraded());
+    assert!(session.diagnostics_report().metadata_degraded);
+
+    let cluster_state = session.get_cluster_state();
+    let nodes = cluster_state.get_nodes_info();
+    assert_eq!(nodes.len(), 1);
+    assert_eq!(
+        (nodes[0].address.ip(), nodes[0].address.port()),
+        (addr.ip(), addr.port())
+    );
+    assert!(cluster_state.get_keyspace("system_schema").is_none());
+}
+
+#[tokio::test]
+async fn diagnostics_report_of_new_session() {
+    setup_tracing();
+    let session = create_new_session_builder().build().await.unwrap();
+
+    let report = session.diagnostics_report();
+    assert!(report.control_connection.working);
+    assert!(!report.metadata_degraded);
+    assert!(report.control_connection.last_error.is_none());
+    assert_eq!(
+        report.nodes.len(),
+        session.get_cluster_state().get_nodes_info().len()
+    );
+    for node in &report.nodes {
+        assert!(node.connected);
+        assert!(node.connections_per_shard.iter().sum::<usize>() > 0);
+    }
+}
+
+#[tokio::test]
+async fn control_connection_standbys_of_new_session() {
+    setup_tracing();
+    let session = create_new_session_builder()
+        .control_connection_standbys(1)
+        .build()
+        .await
+        .unwrap();
+
+    let status = session.control_connection_status();
+    assert!(status.working);
+    let nodes_count = session.get_cluster_state().get_nodes_info().len();
+    assert_eq!(
+        status.standby_addresses.len(),
+        usize::min(1, nodes_count - 1)
+    );
+    assert!(!status.standby_addresses.contains(&status.address));
+}
+
+#[tokio::test]
+async fn node_stats_of_new_session() {
+    setup_tracing();
+    let session = create_new_session_builder().build().await.unwrap();
+    session
+        .query_unpaged("SELECT host_id FROM system.local WHERE key='local'", &[])
+        .await
+        .unwrap();
+
+    for node in session.get_cluster_state().get_nodes_info() {
+        let stats = node.stats();
+        assert_eq!(stats.in_flight_requests, 0);
+        assert_eq!(stats.errors, 0);
+        assert!(stats.connections_per_shard.iter().sum::<usize>() > 0);
+        assert!(stats.last_connection_attempt.is_some());
+        assert!(stats.release_version.is_some());
+        assert_eq!(
+            stats.shard_count,
+            node.sharder().map(|sharder| sharder.nr_shards)
+        );
+    }
+}
+
+#[tokio::test]
+async fn cluster_state_dump_of_new_session() {
+    setup_tracing();
+    let session = create_new_session_builder().build().await.unwrap();
+
+    let cluster_state = session.get_cluster_state();
+    let dump = cluster_state.dump();
+    assert_eq!(dump.nodes.len(), cluster_state.get_nodes_info().len());
+    assert_eq!(
+        dump.datacenters.iter().map(|dc| dc.nodes).sum::<usize>(),
+        dump.nodes.len()
+    );
+    let ownership = dump
+        .token_ownership
+        .iter()
+        .map(|node| node.ownership)
+        .sum::<f64>();
+    assert!((ownership - 1.0).abs() < 1e-6);
+    assert!(dump.keyspaces.iter().any(|ks| ks.name == "system_schema"));
+}
+
+#[tokio::test]
+async fn stable_topology_of_new_session() {
+    setup_tracing();
+    let session = create_new_session_builder().build().await.unwrap();
+
+    session
+        .await_stable_topology(
+            std::time::Duration::from_secs(1),
+            std::time::Duration::from_secs(30),
+        )
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn pool_warmup_fills_pools_before_build_returns() {
+    setup_tracing();
+
+    let session = create_new_session_builder()
+        .pool_size(PoolSize::PerShard(NonZeroUsize::new(2).unwrap()))
+        .pool_warmup(Some(
+            PoolWarmup::new(Duration::from_secs(30)).with_required_fraction(1.0),
+        ))
+        .build()
+        .await
+        .unwrap();
+
+    for node in session.get_cluster_state().get_nodes_info() {
+        let connections_per_shard = node.stats().connections_per_shard;
+        assert!(!connections_per_shard.is_empty());
+        assert!(connections_per_shard.iter().all(|&count| count >= 2));
+    }
+}
@@ -245,6 +245,138 @@ async fn test_iter_methods_when_altering_table() {
     session.ddl(format!("DROP KEYSPACE {ks}")).await.unwrap();
 }
 
+#[tokio::test]
+async fn test_pager_progress() {
+    setup_tracing();
+    let session = create_new_session_builder().build().await.unwrap();
+    let ks = unique_keyspace_name();
+
+    session
+        .ddl(format!(
+            "CREATE KEYSPACE IF NOT EXISTS {ks} WITH
+        REPLICATION = {{'class' : 'NetworkTopologyStrategy', 'replication_factor' : 1}}"
+        ))
+        .await
+        .unwrap();
+    session
+        .ddl(format!(
+            "CREATE TABLE IF NOT EXISTS {ks}.t (a int, b int, primary key (a, b))"
+        ))
+        .await
+        .unwrap();
+
+    let insert_stmt = session
+        .prepare(format!("INSERT INTO {ks}.t (a, b) VALUES (?, ?)"))
+        .await
+        .unwrap();
+    for b in 0..25 {
+        session.execute_unpaged(&insert_stmt, (0, b)).await.unwrap();
+    }
+
+    let mut select_stmt = Statement::new(format!("SELECT a, b FROM {ks}.t WHERE a = 0"));
+    select_stmt.set_page_size(10);
+    let pager = session.query_iter(select_stmt, &[]).await.unwrap();
+    let mut progress_watch = pager.progress_watch();
+    let mut stream = pager.rows_stream::<(i32, i32)>().unwrap();
+
+    // The first page is fetched before the pager is returned.
+    let progress = stream.progress();
+    assert_eq!(progress.rows_yielded, 0);
+    assert_eq!(progress.pages_fetched, 1);
+    assert!(progress.bytes_received > 0);
+
+    for _ in 0..15 {
+        stream.next().await.unwrap().unwrap();
+    }
+    let progress = stream.progress();
+    assert_eq!(progress.rows_yielded, 15);
+    assert_eq!(progress.pages_fetched, 2);
+    // The watched value was published when the second page was received.
+    let watched = *progress_watch.borrow_and_update();
+    assert_eq!(watched.rows_yielded, 10);
+    assert_eq!(watched.pages_fetched, 2);
+
+    while let Some(row) = stream.next().await {
+        row.unwrap();
+    }
+    let progress = stream.progress();
+    assert_eq!(progress.rows_yielded, 25);
+    assert!(progress.pages_fetched >= 3);
+    assert!(progress_watch.has_changed().unwrap());
+    assert_eq!(*progress_watch.borrow_and_update(), progress);
+
+    session.ddl(format!("DROP KEYSPACE {ks}")).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_pager_next_row_borrowed() {
+    setup_tracing();
+    let session = create_new_session_builder().build().await.unwrap();
+    let ks = unique_keyspace_name();
+
+    session
+        .ddl(format!(
+            "CREATE KEYSPACE IF NOT EXISTS {ks} WITH
+        REPLICATION = {{'class' : 'NetworkTopologyStrategy', 'replication_factor' : 1}}"
+        ))
+        .await
+        .unwrap();
+    session
+        .ddl(format!(
+            "CREATE TABLE IF NOT EXISTS {ks}.t (a int, b text, c blob, primary key (a))"
+        ))
+        .await
+        .unwrap();
+
+    let insert_stmt = session
+        .prepare(format!("INSERT INTO {ks}.t (a, b, c) VALUES (?, ?, ?)"))
+        .await
+        .unwrap();
+    for a in 0..25 {
+        session
+            .execute_unpaged(&insert_stmt, (a, a.to_string(), vec![a as u8]))
+            .await
+            .unwrap();
+    }
+
+    let mut select_stmt = Statement::new(format!("SELECT a, b, c FROM {ks}.t"));
+    select_stmt.set_page_size(10);
+    let mut pager = session.query_iter(select_stmt, &[]).await.unwrap();
+
+    let mut rows_num = 0;
+    while let Some(row) = pager.next_row::<(i32, &str, &[u8])>().await {
+        let (a, b, c) = row.unwrap();
+        assert_eq!(b, a.to_string());
+        assert_eq!(c, [a as u8]);
+        rows_num += 1;
+    }
+    assert_eq!(rows_num, 25);
+    assert!(pager.progress().pages_fetched >= 3);
+
+    // Rows are type checked.
+    let mut pager = session
+        .query_iter(format!("SELECT a, b, c FROM {ks}.t"), &[])
+        .await
+        .unwrap();
+    assert_matches!(
+        pager.next_row::<(i32, i32, &[u8])>().await,
+        Some(Err(NextRowError::NextPageError(
+            NextPageError::TypeCheckError(_)
+        )))
+    );
+
+    // The check is repeated when another row type is requested for the same page.
+    assert_matches!(pager.next_row::<(i32, &str, &[u8])>().await, Some(Ok(_)));
+    assert_matches!(
+        pager.next_row::<(i32, i32, &[u8])>().await,
+        Some(Err(NextRowError::NextPageError(
+            NextPageError::TypeCheckError(_)
+        )))
+    );
+
+    session.ddl(format!("DROP KEYSPACE {ks}")).await.unwrap();
+}
+
 #[tokio::test]
 async fn test_pager_timeouts() {
     setup_tracing();
@@ -1,4 +1,6 @@
 use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
 
 use assert_matches::assert_matches;
 use scylla::client::PoolSize;
@@ -6,6 +8,9 @@ use scylla::client::session::Session;
 use scylla::client::session_builder::SessionBuilder;
 use scylla::errors::{DbError, ExecutionError, RequestAttemptError, SchemaAgreementError};
 use scylla::policies::load_balancing::{NodeIdentifier, SingleTargetLoadBalancingPolicy};
+use scylla::policies::schema_agreement::{
+    SchemaAgreementListener, SchemaAgreementPolicy, SchemaAgreementProgress,
+};
 use scylla::response::query_result::QueryResult;
 use scylla::statement::Statement;
 use scylla_proxy::{
@@ -15,7 +20,8 @@ use scylla_proxy::{
 use tracing::info;
 
 use crate::utils::{
-    calculate_proxy_host_ids, setup_tracing, test_with_3_node_cluster, unique_keyspace_name,
+    calculate_proxy_host_ids, create_new_session_builder, setup_tracing, test_with_3_node_cluster,
+    unique_keyspace_name,
 };
 
 async fn run_some_ddl_with_unreachable_node(
@@ -230,3 +236,89 @@ async fn test_schema_await_with_transient_failure() {
         Err(err) => panic!("{}", err),
     }
 }
+
+struct CountingListener(AtomicUsize);
+
+impl SchemaAgreementListener for CountingListener {
+    fn on_disagreement(&self, progress: &SchemaAgreementProgress) {
+        assert!(progress.disagreeing_nodes().is_empty());
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[tokio::test]
+async fn test_schema_agreement_policy() {
+    setup_tracing();
+    let session = create_new_session_builder().build().await.unwrap();
+    let agreed_version = session.await_schema_agreement().await.unwrap();
+    let host_ids: Vec<_> = session
+        .get_cluster_state()
+        .get_nodes_info()
+        .iter()
+        .map(|node| node.host_id)
+        .collect();
+
+    // Ignoring some nodes does not prevent agreement among the others.
+    let policy = SchemaAgreementPolicy::builder()
+        .required_fraction(0.5)
+        .ignored_nodes(host_ids.iter().copied().take(1))
+        .build();
+    let version = session
+        .await_schema_agreement_with_policy(&policy)
+        .await
+        .unwrap();
+    assert_eq!(version, agreed_version);
+
+    // If all nodes are ignored, there is no agreement, and the listener is notified.
+    let listener = Arc::new(CountingListener(AtomicUsize::new(0)));
+    let policy = SchemaAgreementPolicy::builder()
+        .interval(Duration::from_millis(50))
+        .timeout(Duration::from_millis(500))
+        .ignored_nodes(host_ids)
+        .listener(listener.clone())
+        .build();
+    let err = session
+        .await_schema_agreement_with_policy(&policy)
+        .await
+        .unwrap_err();
+    assert_matches!(err, SchemaAgreementError::Timeout(_));
+    assert!(listener.0.load(Ordering::Relaxed) > 0);
+}
+
+#[tokio::test]
+async fn test_execute_schema_change() {
+    setup_tracing();
+    // `execute_schema_change` awaits agreement and refreshes metadata even without automatic waiting.
+    let session = create_new_session_builder()
+        .auto_await_schema_agreement(false)
+        .build()
+        .await
+        .unwrap();
+    let ks = unique_keyspace_name();
+
+    let version = session
+        .execute_schema_change(format!(
+            "CREATE KEYSPACE {ks} WITH REPLICATION = {{'class' : 'NetworkTopologyStrategy', 'replication_factor' : 1}}"
+        ))
+        .await
+        .unwrap();
+    assert_eq!(
+        session.check_schema_agreement().await.unwrap(),
+        Some(version)
+    );
+    assert!(session.get_cluster_state().get_keyspace(&ks).is_some());
+
+    session
+        .execute_schema_change(format!("CREATE TABLE {ks}.t (a int PRIMARY KEY, b text)"))
+        .await
+        .unwrap();
+    let cluster_state = session.get_cluster_state();
+    let keyspace = cluster_state.get_keyspace(&ks).unwrap();
+    assert!(keyspace.tables.contains_key("t"));
+
+    session
+        .execute_schema_change(format!("DROP KEYSPACE {ks}"))
+        .await
+        .unwrap();
+    assert!(session.get_cluster_state().get_keyspace(&ks).is_none());
+}
@@ -0,0 +1,31 @@
+use scylla::policies::load_balancing::NodeIdentifier;
+
+use crate::utils::{create_new_session_builder, setup_tracing};
+
+#[tokio::test]
+#[cfg_attr(cassandra_tests, ignore)]
+async fn test_system_views() {
+    setup_tracing();
+    let session = create_new_session_builder().build().await.unwrap();
+
+    for node in session.get_cluster_state().get_nodes_info() {
+        let system = session.system().on_node(NodeIdentifier::Node(node.clone()));
+
+        let clients = system.clients().await.unwrap();
+        assert!(
+            clients
+                .iter()
+                .any(|client| client.driver_name.as_deref() == Some("ScyllaDB Rust Driver"))
+        );
+
+        let runtime_info = system.runtime_info().await.unwrap();
+        assert!(!runtime_info.is_empty());
+
+        let config = system.config().await.unwrap();
+        assert!(
+            config
+                .iter()
+                .any(|entry| entry.name == "native_transport_port")
+        );
+    }
+}
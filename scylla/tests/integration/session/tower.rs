@@ -0,0 +1,56 @@
+use std::sync::Arc;
+
+use futures::future::poll_fn;
+use scylla::client::tower::{SessionRequest, SessionService};
+use tower_service_03::Service;
+
+use crate::utils::{
+    PerformDDL as _, create_new_session_builder, setup_tracing, unique_keyspace_name,
+};
+
+#[tokio::test]
+async fn test_session_service() {
+    setup_tracing();
+    let session = Arc::new(create_new_session_builder().build().await.unwrap());
+    let ks = unique_keyspace_name();
+    session
+        .ddl(format!(
+            "CREATE KEYSPACE {ks} WITH REPLICATION = {{'class' : 'NetworkTopologyStrategy', 'replication_factor' : 1}}"
+        ))
+        .await
+        .unwrap();
+    session
+        .ddl(format!("CREATE TABLE {ks}.t (a int primary key, b text)"))
+        .await
+        .unwrap();
+
+    let mut service = SessionService::new(Arc::clone(&session));
+    let prepared = session
+        .prepare(format!("INSERT INTO {ks}.t (a, b) VALUES (?, ?)"))
+        .await
+        .unwrap();
+
+    poll_fn(|cx| Service::<SessionRequest<()>>::poll_ready(&mut service, cx))
+        .await
+        .unwrap();
+    service
+        .call(SessionRequest::prepared(prepared, (1_i32, "one")))
+        .await
+        .unwrap();
+
+    let rows = service
+        .call(SessionRequest::unprepared(
+            format!("SELECT a, b FROM {ks}.t WHERE a = ?"),
+            (1_i32,),
+        ))
+        .await
+        .unwrap()
+        .into_rows_result()
+        .unwrap();
+    assert_eq!(
+        rows.single_row::<(i32, String)>().unwrap(),
+        (1, "one".to_owned())
+    );
+
+    session.ddl(format!("DROP KEYSPACE {ks}")).await.unwrap();
+}
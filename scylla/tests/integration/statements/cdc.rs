@@ -0,0 +1,100 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use futures::TryStreamExt as _;
+use scylla::cdc::{CdcGeneration, CdcLogReader, CdcOperation};
+use scylla::value::CqlTimestamp;
+
+use crate::utils::{
+    PerformDDL as _, create_new_session_builder, scylla_supports_tablets, setup_tracing,
+    unique_keyspace_name,
+};
+
+#[tokio::test]
+#[cfg_attr(cassandra_tests, ignore)]
+async fn test_cdc_log_reader() {
+    setup_tracing();
+
+    let session = create_new_session_builder().build().await.unwrap();
+    let ks = unique_keyspace_name();
+
+    // CDC is not yet compatible with Scylla's tablets.
+    let mut create_ks = format!(
+        "CREATE KEYSPACE {ks} WITH REPLICATION = {{'class' : 'NetworkTopologyStrategy', 'replication_factor' : 1}}"
+    );
+    if scylla_supports_tablets(&session).await {
+        create_ks += " AND TABLETS = {'enabled': false}";
+    }
+    session.ddl(create_ks).await.unwrap();
+    session
+        .ddl(format!(
+            "CREATE TABLE {ks}.t (pk int PRIMARY KEY, v int) WITH cdc = {{'enabled': true}}"
+        ))
+        .await
+        .unwrap();
+    session.refresh_metadata().await.unwrap();
+
+    let insert = session
+        .prepare(format!("INSERT INTO {ks}.t (pk, v) VALUES (?, ?)"))
+        .await
+        .unwrap();
+    for pk in 0..10 {
+        session
+            .execute_unpaged(&insert, (pk, pk * 10))
+            .await
+            .unwrap();
+    }
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as i64;
+    let generations = CdcGeneration::fetch_all(&session).await.unwrap();
+    let generation = generations
+        .iter()
+        .rev()
+        .find(|generation| generation.timestamp().0 <= now)
+        .unwrap();
+
+    let cluster_state = session.get_cluster_state();
+    let reader =
+        CdcLogReader::prepare_with_selector(&session, &ks, "t", "\"cdc$operation\", pk, v")
+            .await
+            .unwrap();
+    let (from, to) = (generation.timestamp(), CqlTimestamp(now + 60 * 60 * 1000));
+    for pk in 0..10 {
+        let stream = generation
+            .stream_for_partition_key(&cluster_state, &ks, "t", &(pk,))
+            .unwrap()
+            .unwrap();
+        let rows: Vec<_> = reader
+            .read(&session, &stream, from, to)
+            .await
+            .unwrap()
+            .rows_stream::<(i8, i32, Option<i32>)>()
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        let changes: Vec<_> = rows
+            .into_iter()
+            .filter(|(_, row_pk, _)| *row_pk == pk)
+            .map(|(operation, _, v)| (CdcOperation::try_from(operation).unwrap(), v))
+            .collect();
+        assert_eq!(changes, [(CdcOperation::RowInsert, Some(pk * 10))]);
+    }
+
+    // No changes before the window.
+    let stream = generation.streams().next().unwrap();
+    let rows: Vec<_> = reader
+        .read(&session, stream, CqlTimestamp(0), from)
+        .await
+        .unwrap()
+        .rows_stream::<(i8, i32, Option<i32>)>()
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+    assert!(rows.is_empty());
+
+    session.ddl(format!("DROP KEYSPACE {ks}")).await.unwrap();
+}
@@ -0,0 +1,85 @@
+use std::collections::BTreeSet;
+use std::num::NonZeroUsize;
+
+use futures::{StreamExt as _, TryStreamExt as _};
+use scylla::client::session::ResultOrdering;
+
+use crate::utils::{
+    PerformDDL as _, create_new_session_builder, setup_tracing, unique_keyspace_name,
+};
+
+const ROWS: i32 = 200;
+
+#[tokio::test]
+async fn test_execute_stream() {
+    setup_tracing();
+
+    let session = create_new_session_builder().build().await.unwrap();
+    let ks = unique_keyspace_name();
+
+    session
+        .ddl(format!("CREATE KEYSPACE {ks} WITH REPLICATION = {{'class' : 'NetworkTopologyStrategy', 'replication_factor' : 1}}"))
+        .await
+        .unwrap();
+    session
+        .ddl(format!("CREATE TABLE {ks}.t (a int PRIMARY KEY, b text)"))
+        .await
+        .unwrap();
+
+    let insert = session
+        .prepare(format!("INSERT INTO {ks}.t (a, b) VALUES (?, ?)"))
+        .await
+        .unwrap();
+
+    // Ordered results come back in the order of the value stream.
+    let values = futures::stream::iter((0..ROWS / 2).map(|i| (i, i.to_string())));
+    let indices: Vec<usize> = session
+        .execute_stream(
+            &insert,
+            values,
+            NonZeroUsize::new(8).unwrap(),
+            ResultOrdering::Ordered,
+        )
+        .map(|(idx, result)| {
+            result.unwrap();
+            idx
+        })
+        .collect()
+        .await;
+    assert_eq!(indices, (0..(ROWS / 2) as usize).collect::<Vec<_>>());
+
+    // Unordered results cover every value set exactly once.
+    let values = futures::stream::iter((ROWS / 2..ROWS).map(|i| (i, i.to_string())));
+    let indices: BTreeSet<usize> = session
+        .execute_stream(
+            &insert,
+            values,
+            NonZeroUsize::new(8).unwrap(),
+            ResultOrdering::Unordered,
+        )
+        .map(|(idx, result)| {
+            result.unwrap();
+            idx
+        })
+        .collect()
+        .await;
+    assert_eq!(indices, (0..(ROWS / 2) as usize).collect::<BTreeSet<_>>());
+
+    let rows: BTreeSet<(i32, String)> = session
+        .query_iter(format!("SELECT a, b FROM {ks}.t"), &[])
+        .await
+        .unwrap()
+        .rows_stream::<(i32, String)>()
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(
+        rows,
+        (0..ROWS)
+            .map(|i| (i, i.to_string()))
+            .collect::<BTreeSet<_>>()
+    );
+
+    session.ddl(format!("DROP KEYSPACE {ks}")).await.unwrap();
+}
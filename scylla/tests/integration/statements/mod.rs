@@ -1,11 +1,15 @@
 mod batch;
+mod cdc;
 mod consistency;
 mod coordinator;
+mod execute_stream;
 mod execution_profiles;
 mod named_bind_markers;
 mod prepared;
 mod prepared_metadata_extension;
 mod request_timeout;
+mod scan;
+mod timeseries;
 mod timestamps;
 mod transparent_reprepare;
 mod unprepared;
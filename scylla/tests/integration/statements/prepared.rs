@@ -2,13 +2,13 @@ use assert_matches::assert_matches;
 use itertools::Itertools;
 use scylla::client::session::Session;
 use scylla::cluster::metadata::{ColumnType, NativeType};
-use scylla::errors::{DbError, PrepareError, RequestAttemptError};
+use scylla::errors::{BadQuery, DbError, ExecutionError, PrepareError, RequestAttemptError};
 use scylla::frame::response::result::{ColumnSpec, TableSpec};
 use scylla::policies::load_balancing::{NodeIdentifier, SingleTargetLoadBalancingPolicy};
 use scylla::response::{PagingState, PagingStateResponse};
 use scylla::routing::Token;
 use scylla::routing::partitioner::PartitionerName;
-use scylla::serialize::row::SerializeRow;
+use scylla::serialize::row::{SerializeRow, SerializedValues};
 use scylla::statement::Statement;
 use scylla::statement::prepared::PreparedStatement;
 use scylla_cql::frame::types;
@@ -216,6 +216,99 @@ async fn test_prepared_config() {
     assert_eq!(prepared_statement.get_page_size(), 42);
 }
 
+#[tokio::test]
+async fn test_execute_preserialized_values() {
+    setup_tracing();
+    let session = create_new_session_builder().build().await.unwrap();
+    let ks = unique_keyspace_name();
+
+    session.ddl(format!("CREATE KEYSPACE IF NOT EXISTS {ks} WITH REPLICATION = {{'class' : 'NetworkTopologyStrategy', 'replication_factor' : 1}}")).await.unwrap();
+    session
+        .ddl(format!(
+            "CREATE TABLE IF NOT EXISTS {ks}.t (a int, b text, primary key (a))"
+        ))
+        .await
+        .unwrap();
+
+    let insert = session
+        .prepare(format!("INSERT INTO {ks}.t (a, b) VALUES (?, ?)"))
+        .await
+        .unwrap();
+    let mut values = SerializedValues::new();
+    for a in 0..5_i32 {
+        insert
+            .serialize_values_into(&(a, a.to_string()), &mut values)
+            .unwrap();
+        session
+            .execute_unpaged_serialized(&insert, &values)
+            .await
+            .unwrap();
+    }
+
+    let mut rows: Vec<(i32, String)> = session
+        .query_unpaged(format!("SELECT a, b FROM {ks}.t"), &[])
+        .await
+        .unwrap()
+        .into_rows_result()
+        .unwrap()
+        .rows::<(i32, String)>()
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    rows.sort_unstable();
+    assert_eq!(rows, (0..5).map(|a| (a, a.to_string())).collect::<Vec<_>>());
+
+    // Values which don't match the bind markers are rejected before being sent.
+    let select = session
+        .prepare(format!("SELECT a, b FROM {ks}.t WHERE a = ?"))
+        .await
+        .unwrap();
+    select.serialize_values_into(&(1,), &mut values).unwrap();
+    assert_matches!(
+        session.execute_unpaged_serialized(&insert, &values).await,
+        Err(ExecutionError::BadQuery(BadQuery::SerializationError(_)))
+    );
+    let mut values = SerializedValues::new();
+    values
+        .add_value(&1_i64, &ColumnType::Native(NativeType::BigInt))
+        .unwrap();
+    values
+        .add_value(&"abc", &ColumnType::Native(NativeType::Text))
+        .unwrap();
+    assert_matches!(
+        session.execute_unpaged_serialized(&insert, &values).await,
+        Err(ExecutionError::BadQuery(BadQuery::SerializationError(_)))
+    );
+}
+
+#[tokio::test]
+async fn test_prepare_all() {
+    setup_tracing();
+    let session = create_new_session_builder().build().await.unwrap();
+
+    let results = session
+        .prepare_all(
+            [
+                "SELECT keyspace_name FROM system_schema.tables",
+                "SELECT * FROM system_schema.nonexistent_table",
+                "SELECT table_name FROM system_schema.tables WHERE keyspace_name = ?",
+            ],
+            std::num::NonZeroUsize::new(2).unwrap(),
+        )
+        .await;
+
+    assert_eq!(results.len(), 3);
+    assert_eq!(
+        results[0].as_ref().unwrap().get_statement(),
+        "SELECT keyspace_name FROM system_schema.tables"
+    );
+    assert!(results[1].is_err());
+    assert_eq!(
+        results[2].as_ref().unwrap().get_variable_col_specs().len(),
+        1
+    );
+}
+
 #[tokio::test]
 #[cfg_attr(cassandra_tests, ignore)]
 async fn test_prepared_partitioner() {
@@ -13,7 +13,10 @@ use scylla_proxy::{
     Condition, ProxyError, Reaction as _, RequestOpcode, RequestReaction, RequestRule, WorkerError,
 };
 
-use crate::utils::{setup_tracing, test_with_3_node_cluster};
+use crate::utils::{
+    PerformDDL as _, create_new_session_builder, setup_tracing, test_with_3_node_cluster,
+    unique_keyspace_name,
+};
 
 #[tokio::test]
 async fn test_request_timeout() {
@@ -141,3 +144,43 @@ async fn test_request_timeout() {
         Err(err) => panic!("{}", err),
     }
 }
+
+#[tokio::test]
+#[cfg_attr(cassandra_tests, ignore)]
+async fn test_server_timeout() {
+    setup_tracing();
+
+    let session = create_new_session_builder().build().await.unwrap();
+    let ks = unique_keyspace_name();
+    session
+        .ddl(format!("CREATE KEYSPACE {ks} WITH REPLICATION = {{'class' : 'NetworkTopologyStrategy', 'replication_factor' : 1}}"))
+        .await
+        .unwrap();
+    session
+        .ddl(format!("CREATE TABLE {ks}.t (pk int PRIMARY KEY, v int)"))
+        .await
+        .unwrap();
+
+    let timeout = Duration::from_secs(5);
+    let mut insert = Statement::new(format!(
+        "INSERT INTO {ks}.t (pk, v) VALUES (?, ?) USING TTL 3600"
+    ));
+    insert.set_server_timeout(timeout).unwrap();
+    session.query_unpaged(insert, (1, 2)).await.unwrap();
+
+    let mut select = Statement::new(format!("SELECT v FROM {ks}.t WHERE pk = ?"));
+    select.set_server_timeout(timeout).unwrap();
+    let prepared = session.prepare(select).await.unwrap();
+    assert!(prepared.get_request_timeout().unwrap() > timeout);
+    let (v,) = session
+        .execute_unpaged(&prepared, (1,))
+        .await
+        .unwrap()
+        .into_rows_result()
+        .unwrap()
+        .single_row::<(i32,)>()
+        .unwrap();
+    assert_eq!(v, 2);
+
+    session.ddl(format!("DROP KEYSPACE {ks}")).await.unwrap();
+}
@@ -0,0 +1,97 @@
+use std::collections::BTreeSet;
+use std::num::NonZeroUsize;
+
+use futures::{StreamExt as _, TryStreamExt as _};
+use scylla::scan::TableScan;
+
+use crate::utils::{
+    PerformDDL as _, create_new_session_builder, setup_tracing, unique_keyspace_name,
+};
+
+const ROWS: i32 = 1000;
+
+#[tokio::test]
+async fn test_table_scan() {
+    setup_tracing();
+
+    let session = create_new_session_builder().build().await.unwrap();
+    let ks = unique_keyspace_name();
+
+    session
+        .ddl(format!("CREATE KEYSPACE {ks} WITH REPLICATION = {{'class' : 'NetworkTopologyStrategy', 'replication_factor' : 1}}"))
+        .await
+        .unwrap();
+    session
+        .ddl(format!(
+            "CREATE TABLE {ks}.t (pk int, ck int, v text, PRIMARY KEY (pk, ck))"
+        ))
+        .await
+        .unwrap();
+
+    let insert = session
+        .prepare(format!("INSERT INTO {ks}.t (pk, ck, v) VALUES (?, ?, ?)"))
+        .await
+        .unwrap();
+    for i in 0..ROWS {
+        session
+            .execute_unpaged(&insert, (i % 100, i, i.to_string()))
+            .await
+            .unwrap();
+    }
+    session.refresh_metadata().await.unwrap();
+
+    let expected = (0..ROWS).map(|i| (i % 100, i)).collect::<BTreeSet<_>>();
+
+    // Full scan returns every row exactly once.
+    let rows: Vec<(i32, i32)> = TableScan::new(&ks, "t")
+        .columns(["pk", "ck"])
+        .concurrency(NonZeroUsize::new(4).unwrap())
+        .page_size(7)
+        .rows_stream::<(i32, i32)>(&session)
+        .await
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), ROWS as usize);
+    assert_eq!(rows.into_iter().collect::<BTreeSet<_>>(), expected);
+
+    // Interrupted scan resumed from a checkpoint returns the remaining rows.
+    let mut stream = TableScan::new(&ks, "t")
+        .columns(["pk", "ck"])
+        .page_size(7)
+        .rows_stream::<(i32, i32)>(&session)
+        .await
+        .unwrap();
+    let mut rows = Vec::new();
+    for _ in 0..(ROWS / 3) {
+        rows.push(stream.next().await.unwrap().unwrap());
+    }
+    let checkpoint = stream.checkpoint();
+    drop(stream);
+    assert!(!checkpoint.is_finished());
+
+    let resumed: Vec<(i32, i32)> = TableScan::new(&ks, "t")
+        .columns(["pk", "ck"])
+        .page_size(5)
+        .resume_from(checkpoint)
+        .rows_stream::<(i32, i32)>(&session)
+        .await
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+    rows.extend(resumed);
+    assert_eq!(rows.len(), ROWS as usize);
+    assert_eq!(rows.into_iter().collect::<BTreeSet<_>>(), expected);
+
+    // Unknown tables are reported before scanning.
+    assert!(
+        TableScan::new(&ks, "missing")
+            .rows_stream::<(i32, i32)>(&session)
+            .await
+            .is_err()
+    );
+
+    session.ddl(format!("DROP KEYSPACE {ks}")).await.unwrap();
+}
@@ -0,0 +1,81 @@
+use std::num::NonZeroUsize;
+
+use futures::TryStreamExt as _;
+use scylla::timeseries::{BucketedRead, TimeBucketing};
+use scylla::value::CqlTimestamp;
+
+use crate::utils::{
+    PerformDDL as _, create_new_session_builder, setup_tracing, unique_keyspace_name,
+};
+
+const HOUR_MS: i64 = 60 * 60 * 1000;
+
+#[tokio::test]
+async fn test_bucketed_read() {
+    setup_tracing();
+
+    let session = create_new_session_builder().build().await.unwrap();
+    let ks = unique_keyspace_name();
+
+    session
+        .ddl(format!("CREATE KEYSPACE {ks} WITH REPLICATION = {{'class' : 'NetworkTopologyStrategy', 'replication_factor' : 1}}"))
+        .await
+        .unwrap();
+    session
+        .ddl(format!(
+            "CREATE TABLE {ks}.t (sensor int, day timestamp, ts timestamp, v int, PRIMARY KEY ((sensor, day), ts))"
+        ))
+        .await
+        .unwrap();
+
+    let bucketing = TimeBucketing::daily();
+    let insert = session
+        .prepare(format!(
+            "INSERT INTO {ks}.t (sensor, day, ts, v) VALUES (?, ?, ?, ?)"
+        ))
+        .await
+        .unwrap();
+    // Every 6 hours over 4 days, for two sensors.
+    for i in 0..16 {
+        let ts = CqlTimestamp(i as i64 * 6 * HOUR_MS);
+        for sensor in [1, 2] {
+            session
+                .execute_unpaged(&insert, (sensor, bucketing.bucket_of(ts).start(), ts, i))
+                .await
+                .unwrap();
+        }
+    }
+
+    let select = session
+        .prepare(format!(
+            "SELECT v FROM {ks}.t WHERE sensor = ? AND day = ? AND ts >= ? AND ts <= ?"
+        ))
+        .await
+        .unwrap();
+    // From the middle of the first day to the middle of the third one.
+    let (from, to) = (CqlTimestamp(12 * HOUR_MS), CqlTimestamp(60 * HOUR_MS));
+
+    let rows: Vec<_> = BucketedRead::new(select.clone(), bucketing, from, to)
+        .concurrency(NonZeroUsize::new(2).unwrap())
+        .rows_stream::<(i32,), _>(&session, |bucket| (1, bucket.start(), from, to))
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(rows, (2..=10).map(|i| (i,)).collect::<Vec<_>>());
+
+    // Buckets are read from the latest one, rows within a bucket in clustering order.
+    let rows: Vec<_> = BucketedRead::new(select, bucketing, from, to)
+        .descending()
+        .rows_stream::<(i32,), _>(&session, |bucket| (1, bucket.start(), from, to))
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(
+        rows,
+        [(8,), (9,), (10,), (4,), (5,), (6,), (7,), (2,), (3,)]
+    );
+
+    session.ddl(format!("DROP KEYSPACE {ks}")).await.unwrap();
+}
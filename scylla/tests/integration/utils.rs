@@ -9,12 +9,12 @@ use scylla::cluster::ClusterState;
 use scylla::cluster::NodeRef;
 use scylla::cluster::metadata::ColumnType;
 use scylla::deserialize::value::DeserializeValue;
-use scylla::errors::{DbError, ExecutionError, RequestAttemptError};
+use scylla::errors::ExecutionError;
 use scylla::policies::host_filter::AllowListHostFilter;
 use scylla::policies::load_balancing::{
     FallbackPlan, LoadBalancingPolicy, NodeIdentifier, RoutingInfo, SingleTargetLoadBalancingPolicy,
 };
-use scylla::policies::retry::{RequestInfo, RetryDecision, RetryPolicy, RetrySession};
+use scylla::policies::retry::SchemaChangeRetryPolicy;
 use scylla::response::query_result::QueryResult;
 use scylla::routing::Shard;
 use scylla::serialize::row::SerializeRow;
@@ -35,7 +35,6 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use std::{env, iter};
 use tokio::sync::mpsc;
-use tracing::{error, warn};
 use tracing_subscriber::Layer;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
@@ -250,59 +249,13 @@ impl LoadBalancingPolicy for SchemaQueriesLBP {
     }
 }
 
-#[derive(Debug, Default)]
-struct SchemaQueriesRetrySession {
-    count: usize,
-}
-
-impl RetrySession for SchemaQueriesRetrySession {
-    fn decide_should_retry(&mut self, request_info: RequestInfo) -> RetryDecision {
-        match request_info.error {
-            RequestAttemptError::DbError(DbError::ServerError, s)
-                if s == "Failed to apply group 0 change due to concurrent modification" =>
-            {
-                self.count += 1;
-                // Give up if there are many failures.
-                // In this case we really should do something about it in the
-                // core, because it is absurd for DDL queries to fail this often.
-                if self.count >= 10 {
-                    error!(
-                        "Received TENTH(!) group 0 concurrent modification error during DDL. Please fix Scylla Core."
-                    );
-                    RetryDecision::DontRetry
-                } else {
-                    warn!(
-                        "Received group 0 concurrent modification error during DDL. Performing retry #{}.",
-                        self.count
-                    );
-                    RetryDecision::RetrySameTarget(None)
-                }
-            }
-            _ => RetryDecision::DontRetry,
-        }
-    }
-
-    fn reset(&mut self) {
-        *self = Default::default()
-    }
-}
-
-#[derive(Debug)]
-struct SchemaQueriesRetryPolicy;
-
-impl RetryPolicy for SchemaQueriesRetryPolicy {
-    fn new_session(&self) -> Box<dyn RetrySession> {
-        Box::new(SchemaQueriesRetrySession::default())
-    }
-}
-
 fn apply_ddl_lbp(query: &mut Statement) {
     let policy = query
         .get_execution_profile_handle()
         .map(|profile| profile.pointee_to_builder())
         .unwrap_or(ExecutionProfile::builder())
         .load_balancing_policy(Arc::new(SchemaQueriesLBP))
-        .retry_policy(Arc::new(SchemaQueriesRetryPolicy))
+        .retry_policy(Arc::new(SchemaChangeRetryPolicy::new()))
         .build();
     query.set_execution_profile_handle(Some(policy.into_handle()));
 }
@@ -320,7 +273,7 @@ impl PerformDDL for Session {
     async fn ddl(&self, query: impl Into<Statement> + Send) -> Result<(), ExecutionError> {
         let mut query = query.into();
         apply_ddl_lbp(&mut query);
-        self.query_unpaged(query, &[]).await.map(|_| ())
+        self.execute_schema_change(query).await.map(|_| ())
     }
 }
 